@@ -120,6 +120,8 @@ pub struct Function {
     pub ret_ty: Option<Node<Type>>,
     pub body: Node<Block>,
     pub public: bool,
+    pub generics: Vec<String>,              // Generic type parameters
+    pub bounds: Vec<(String, Vec<String>)>, // Vec<(generic, required traits)>
 }
 
 impl Function {
@@ -135,6 +137,8 @@ impl Function {
             ret_ty,
             body,
             public: false,
+            generics: Vec::new(),
+            bounds: Vec::new(),
         }
     }
 
@@ -150,6 +154,50 @@ impl Function {
             ret_ty,
             body,
             public: true,
+            generics: Vec::new(),
+            bounds: Vec::new(),
+        }
+    }
+
+    /// Declare the generic type parameters of the function and their trait bounds
+    pub fn with_generics(
+        mut self,
+        generics: Vec<String>,
+        bounds: Vec<(String, Vec<String>)>,
+    ) -> Self {
+        self.generics = generics;
+        self.bounds = bounds;
+        self
+    }
+
+    pub fn is_generic(&self) -> bool {
+        !self.generics.is_empty()
+    }
+
+    /// Whether the function takes a `self` receiver. Methods declared `static fn` do not;
+    /// they are associated functions called on the type, like `Point.origin()`.
+    pub fn takes_self(&self) -> bool {
+        self.params
+            .first()
+            .is_some_and(|param| param.as_ref().name.as_ref() == "self")
+    }
+
+    /// Replace `Self` in the signature with the implementing type, typing an
+    /// unannotated `self` receiver along the way
+    pub fn resolve_self_type(&mut self, type_name: &str) {
+        for param in &mut self.params {
+            let param = param.as_mut();
+            match &mut param.ty {
+                Some(ty) => ty.as_mut().replace_self(type_name),
+                None if param.name.as_ref() == "self" => {
+                    let span = *param.name.span();
+                    param.ty = Some(Node::new(Type::Simple(type_name.to_string()), span));
+                }
+                None => {}
+            }
+        }
+        if let Some(ret_ty) = &mut self.ret_ty {
+            ret_ty.as_mut().replace_self(type_name);
         }
     }
 }
@@ -157,7 +205,65 @@ impl Function {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Type {
     Simple(String),
-    Generic { base: String, args: Vec<Node<Type>> },
+    Generic {
+        base: String,
+        args: Vec<Node<Type>>,
+    },
+    Function {
+        params: Vec<Node<Type>>,
+        ret: Option<Box<Node<Type>>>,
+    },
+    Dyn(String),            // Trait object: `dyn Trait`
+    Tuple(Vec<Node<Type>>), // `(int, string)`
+}
+
+impl Type {
+    /// Recursively replace `Self` with a concrete type name
+    pub fn replace_self(&mut self, type_name: &str) {
+        match self {
+            Type::Simple(name) if name == "Self" => *name = type_name.to_string(),
+            Type::Simple(_) | Type::Dyn(_) => {}
+            Type::Generic { args, .. } | Type::Tuple(args) => {
+                for arg in args {
+                    arg.as_mut().replace_self(type_name);
+                }
+            }
+            Type::Function { params, ret } => {
+                for param in params {
+                    param.as_mut().replace_self(type_name);
+                }
+                if let Some(ret) = ret {
+                    ret.as_mut().as_mut().replace_self(type_name);
+                }
+            }
+        }
+    }
+}
+
+/// Writes the type as it appears in source, such as `list<int>` or `fn(int) -> str`
+impl Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let join = |types: &[Node<Type>]| {
+            types
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        match self {
+            Type::Simple(name) => write!(f, "{}", name),
+            Type::Generic { base, args } => write!(f, "{}<{}>", base, join(args)),
+            Type::Function { params, ret } => {
+                write!(f, "fn({})", join(params))?;
+                match ret {
+                    Some(ret) => write!(f, " -> {}", ret),
+                    None => Ok(()),
+                }
+            }
+            Type::Dyn(name) => write!(f, "dyn {}", name),
+            Type::Tuple(elements) => write!(f, "({})", join(elements)),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -165,11 +271,67 @@ pub struct Param {
     pub name: Node<String>,
     pub ty: Option<Node<Type>>,
     pub default: Option<Node<Expr>>,
+    /// `*args: T` collects the remaining positional arguments; `ty` is then `list<T>`, or
+    /// `list` when the element type is left out
+    pub variadic: bool,
+    /// `(a, b): T` destructures the argument with an irrefutable pattern; `name` is then
+    /// a hidden name the argument is passed under
+    pub pattern: Option<Node<Pattern>>,
 }
 
 impl Param {
     pub fn new(name: Node<String>, ty: Option<Node<Type>>, default: Option<Node<Expr>>) -> Self {
-        Self { name, ty, default }
+        Self {
+            name,
+            ty,
+            default,
+            variadic: false,
+            pattern: None,
+        }
+    }
+
+    /// A parameter that binds the names in `pattern` instead of a single name
+    pub fn destructured(
+        pattern: Node<Pattern>,
+        ty: Option<Node<Type>>,
+        default: Option<Node<Expr>>,
+    ) -> Self {
+        let span = *pattern.span();
+        let name = Node::new(format!("__param{}", span.start()), span);
+        Self {
+            pattern: Some(pattern),
+            ..Self::new(name, ty, default)
+        }
+    }
+
+    /// The variadic parameter `*name: element`, whose value is a `list<element>`
+    pub fn variadic(name: Node<String>, element: Option<Node<Type>>) -> Self {
+        let ty = match element {
+            Some(element) => {
+                let span = *element.span();
+                let list = Type::Generic {
+                    base: "list".to_string(),
+                    args: vec![element],
+                };
+                Node::new(list, span)
+            }
+            None => Node::new(Type::Simple("list".to_string()), *name.span()),
+        };
+        Self {
+            name,
+            ty: Some(ty),
+            default: None,
+            variadic: true,
+            pattern: None,
+        }
+    }
+
+    /// Element type of a variadic parameter as written, `None` when it is untyped
+    pub fn variadic_element(&self) -> Option<&Node<Type>> {
+        match self.ty.as_ref().map(Node::as_ref) {
+            Some(Type::Generic { args, .. }) if self.variadic => args.first(),
+            _ => None,
+        }
     }
 }
 
@@ -214,19 +376,54 @@ impl EnumVariant {
     }
 }
 
+/// Signature of the method that `@derive(name)` generates on a struct or enum, with `Self`
+/// for the type and an empty body, or `None` for a name that cannot be derived. The type
+/// checker and code generator supply the behavior; every node takes the span of the derive
+/// name.
+pub fn derived_signature(derive: &Node<String>) -> Option<Function> {
+    let (name, takes_other, ret_ty) = match derive.as_ref().as_str() {
+        "Eq" => ("eq", true, "bool"),
+        "Ord" => ("lt", true, "bool"),
+        "Hash" => ("hash", false, "int"),
+        "Debug" => ("str", false, "str"),
+        "Clone" => ("clone", false, "Self"),
+        _ => return None,
+    };
+    let span = *derive.span();
+    let param = |name: &str, ty: Option<&str>| {
+        let name = Node::new(name.to_string(), span);
+        let ty = ty.map(|ty| Node::new(Type::Simple(ty.to_string()), span));
+        Node::new(Param::new(name, ty, None), span)
+    };
+    let mut params = vec![param("self", None)];
+    if takes_other {
+        params.push(param("other", Some("Self")));
+    }
+    let ret_ty = Node::new(Type::Simple(ret_ty.to_string()), span);
+    let body = Node::new(Block::new(Vec::new()), span);
+    Some(Function::new(name, params, Some(ret_ty), body))
+}
+
 #[derive(Debug, Clone)]
 pub enum Statement {
     // Variable declarations and assignments
     Let {
-        name: Node<String>,
+        pattern: Node<Pattern>, // a name, `_`, or a tuple destructuring
         expr: Node<Expr>,
         ty: Option<Node<Type>>,
         public: bool,
     },
     Assignment {
-        name: Node<String>,
+        target: Node<Expr>,   // a place expression, see `Expr::is_place`
+        op: Option<BinaryOp>, // `Some` for compound assignments such as `+=`
         expr: Node<Expr>,
     },
+    Const {
+        name: String,
+        ty: Node<Type>,
+        expr: Node<Expr>, // evaluated at compile time
+        public: bool,
+    },
 
     // Control flow
     If {
@@ -235,20 +432,45 @@ pub enum Statement {
         elif_blocks: Vec<(Node<Expr>, Node<Block>)>, // Vec<(condition, block)>
         else_block: Option<Node<Block>>,
     },
+    // Loops may carry a label (`outer: for ...`) that `break` and `continue` can name
     For {
-        var: Node<String>,
+        label: Option<String>,
+        pattern: Node<Pattern>,
         iterable: Node<Expr>,
         body: Node<Block>,
     },
     While {
+        label: Option<String>,
         cond: Node<Expr>,
         body: Node<Block>,
     },
-    Break,
-    Continue,
+    // `break [label] [value]`; a value is only allowed when leaving a `loop:` expression
+    Break {
+        label: Option<String>,
+        value: Option<Node<Expr>>,
+    },
+    Continue {
+        label: Option<String>,
+    },
     Pass,
     Return(Option<Node<Expr>>),
 
+    // Exceptions
+    Raise(Option<Node<Expr>>), // a bare `raise` re-raises the exception being handled
+    Try {
+        body: Node<Block>,
+        handlers: Vec<Node<ExceptHandler>>,
+        finally_block: Option<Node<Block>>,
+    },
+
+    // Resource cleanup
+    With {
+        expr: Node<Expr>,
+        binding: Option<String>, // `with expr as name:`
+        body: Node<Block>,
+    },
+    Defer(Node<Expr>), // evaluated when the enclosing block is left
+
     // Function definitions
     Function(Node<Function>),
 
@@ -258,13 +480,27 @@ pub enum Statement {
         fields: Vec<(String, Node<Type>)>,
         methods: Vec<Node<Function>>, // Methods (functions with self parameter)
         public: bool,
-        generics: Vec<String>, // Generic type parameters
+        generics: Vec<String>,              // Generic type parameters
+        bounds: Vec<(String, Vec<String>)>, // Vec<(generic, required traits)>
+        derives: Vec<Node<String>>,         // `@derive(Eq, Hash)`, see `derived_signature`
     },
     Enum {
         name: String,
         variants: Vec<Node<EnumVariant>>,
         public: bool,
         generics: Vec<String>,
+        bounds: Vec<(String, Vec<String>)>,
+        derives: Vec<Node<String>>,
+    },
+    Trait {
+        name: String,
+        methods: Vec<Node<Function>>, // Methods with an empty body are required
+        public: bool,
+    },
+    Impl {
+        trait_name: Option<String>, // None for `impl Type:`, which adds methods to a struct or enum
+        type_name: String,
+        methods: Vec<Node<Function>>,
     },
     TypeAlias {
         name: String,
@@ -298,15 +534,20 @@ impl Statement {
         match self {
             Statement::Let { .. }
             | Statement::Assignment { .. }
-            | Statement::Break
-            | Statement::Continue
+            | Statement::Const { .. }
+            | Statement::Break { .. }
+            | Statement::Continue { .. }
             | Statement::Pass
             | Statement::Return(_)
+            | Statement::Raise(_)
+            | Statement::Defer(_)
             | Statement::Expr(_)
             | Statement::Use { .. }
             | Statement::PubUse { .. }
             | Statement::Struct { .. }
             | Statement::Enum { .. }
+            | Statement::Trait { .. }
+            | Statement::Impl { .. }
             | Statement::TypeAlias { .. } => 1,
 
             Statement::If {
@@ -325,8 +566,22 @@ impl Statement {
                 }
                 count
             }
-            Statement::For { body, .. } | Statement::While { body, .. } => {
-                1 + body.as_ref().recursive_count()
+            Statement::For { body, .. }
+            | Statement::While { body, .. }
+            | Statement::With { body, .. } => 1 + body.as_ref().recursive_count(),
+            Statement::Try {
+                body,
+                handlers,
+                finally_block,
+            } => {
+                let mut count = 1 + body.as_ref().recursive_count();
+                for handler in handlers {
+                    count += handler.as_ref().body.as_ref().recursive_count();
+                }
+                if let Some(block) = finally_block {
+                    count += block.as_ref().recursive_count();
+                }
+                count
             }
             Statement::Function(func) => 1 + func.as_ref().body.as_ref().recursive_count(),
             Statement::Block(block) => block.as_ref().recursive_count(),
@@ -337,7 +592,10 @@ impl Statement {
     pub fn is_pure(&self) -> bool {
         matches!(
             self,
-            Statement::Let { .. } | Statement::Break | Statement::Continue | Statement::Pass
+            Statement::Let { .. }
+                | Statement::Break { value: None, .. }
+                | Statement::Continue { .. }
+                | Statement::Pass
         )
     }
 }
@@ -368,11 +626,30 @@ pub enum Expr {
         object: Box<Node<Expr>>,
         field: String,
     },
+    Index {
+        object: Box<Node<Expr>>,
+        index: Box<Node<Expr>>,
+    },
+    Slice {
+        object: Box<Node<Expr>>,
+        start: Option<Box<Node<Expr>>>,
+        end: Option<Box<Node<Expr>>>,
+    },
+
+    // Anonymous functions: `fn(x) => expr` or `fn(x):` followed by an indented block
+    Lambda {
+        params: Vec<Node<Param>>,
+        ret_ty: Option<Node<Type>>,
+        body: Node<Block>,
+    },
 
-    // Function calls
+    // Function calls; `type_args` holds explicit instantiations such as `max<int>(a, b)` and
+    // `kwargs` the keyword arguments that follow the positional ones, as in `f(x, verbose=true)`
     Call {
         func: Box<Node<Expr>>,
         args: Vec<Node<Expr>>,
+        kwargs: Vec<(String, Node<Expr>)>,
+        type_args: Vec<Node<Type>>,
     },
 
     // Binary operations
@@ -388,6 +665,12 @@ pub enum Expr {
         expr: Box<Node<Expr>>,
     },
 
+    // Numeric conversion: `x as u8`
+    Cast {
+        expr: Box<Node<Expr>>,
+        ty: Node<Type>,
+    },
+
     // Control flow expressions
     If {
         cond: Box<Node<Expr>>,
@@ -395,6 +678,12 @@ pub enum Expr {
         else_branch: Option<Box<Node<Expr>>>,
     },
 
+    // `loop:` with an indented body, left only through `break`; `break value` gives its result
+    Loop {
+        label: Option<String>,
+        body: Node<Block>,
+    },
+
     // Match expressions (pattern matching)
     Match {
         value: Box<Node<Expr>>,
@@ -408,6 +697,7 @@ pub enum Expr {
     },
 
     // Collection literals
+    Tuple(Vec<Node<Expr>>), // `(a, b)`; a one-element tuple is written `(a,)`
     Array(Vec<Node<Expr>>),
     Dict(Vec<(Node<Expr>, Node<Expr>)>), // Key-value pairs
     ListComprehension {
@@ -427,12 +717,20 @@ pub enum Expr {
     // String interpolation
     FString {
         parts: Vec<Node<FStringPart>>,
+        /// Written as `f"""..."""`
+        multiline: bool,
     },
 
     // Async operations
     Await(Box<Node<Expr>>),
     Spawn(Box<Node<Expr>>),
 
+    // Error propagation: `expr?` unwraps `Ok`/`Some` or returns `Err`/`None` early
+    Try(Box<Node<Expr>>),
+
+    // Spread argument: `f(*xs)` passes the elements of `xs` to the variadic parameter of `f`
+    Spread(Box<Node<Expr>>),
+
     // Struct instantiation
     Struct {
         name: String,
@@ -440,6 +738,18 @@ pub enum Expr {
     },
 }
 
+impl Expr {
+    /// Whether this expression names a storage location that can be assigned to:
+    /// a variable, a field of a place, or an index into any expression.
+    pub fn is_place(&self) -> bool {
+        match self {
+            Expr::Identifier(_) | Expr::Index { .. } => true,
+            Expr::Member { object, .. } => object.as_ref().as_ref().is_place(),
+            _ => false,
+        }
+    }
+}
+
 /// Match arm for pattern matching
 #[derive(Debug, Clone)]
 pub struct MatchArm {
@@ -448,6 +758,14 @@ pub struct MatchArm {
     pub body: Node<Block>,
 }
 
+/// `except` clause of a `try` statement. Without an exception type it catches everything.
+#[derive(Debug, Clone)]
+pub struct ExceptHandler {
+    pub exception_type: Option<String>,
+    pub binding: Option<String>,
+    pub body: Node<Block>,
+}
+
 /// Pattern for match expressions
 #[derive(Debug, Clone)]
 pub enum Pattern {
@@ -473,6 +791,91 @@ pub enum Pattern {
         patterns: Vec<Node<Pattern>>,
         rest: Option<String>, // Variable name for rest pattern
     },
+    /// Tuple pattern ((a, b))
+    Tuple(Vec<Node<Pattern>>),
+    /// Or pattern (1 | 2 | 3); every alternative binds the same names
+    Or(Vec<Node<Pattern>>),
+    /// Range pattern over numbers or single-character strings (0..10, "a"..="z")
+    Range {
+        start: Node<Literal>,
+        end: Node<Literal>,
+        inclusive: bool,
+    },
+    /// Binding pattern (p @ Point(x=0)); names the value matched by the inner pattern
+    Binding {
+        name: String,
+        pattern: Box<Node<Pattern>>,
+    },
+    /// String prefix pattern ("GET " + path); `rest` names the text after the prefix
+    StringPrefix {
+        prefix: Node<Literal>,
+        rest: Option<String>,
+    },
+}
+
+impl Pattern {
+    /// The bound name if this pattern is a plain identifier
+    pub fn as_identifier(&self) -> Option<&str> {
+        match self {
+            Pattern::Identifier(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// Names bound by this pattern, in source order
+    pub fn bindings(&self) -> Vec<&str> {
+        let mut names = Vec::new();
+        self.collect_bindings(&mut names);
+        names
+    }
+
+    fn collect_bindings<'a>(&'a self, names: &mut Vec<&'a str>) {
+        match self {
+            Pattern::Wildcard | Pattern::Literal(_) | Pattern::Range { .. } => {}
+            Pattern::Identifier(name) => names.push(name),
+            Pattern::Binding { name, pattern } => {
+                names.push(name);
+                pattern.as_ref().as_ref().collect_bindings(names);
+            }
+            // Alternatives bind the same names, so the first one stands for all of them
+            Pattern::Or(alternatives) => {
+                if let Some(first) = alternatives.first() {
+                    first.as_ref().collect_bindings(names);
+                }
+            }
+            Pattern::EnumVariant { fields, .. } => {
+                for field in fields {
+                    field.as_ref().collect_bindings(names);
+                }
+            }
+            Pattern::Struct { fields, .. } => {
+                for (field, pattern) in fields {
+                    match pattern {
+                        Some(pattern) => pattern.as_ref().collect_bindings(names),
+                        None => names.push(field),
+                    }
+                }
+            }
+            Pattern::Array { patterns, rest } => {
+                for pattern in patterns {
+                    pattern.as_ref().collect_bindings(names);
+                }
+                if let Some(rest) = rest {
+                    names.push(rest);
+                }
+            }
+            Pattern::Tuple(patterns) => {
+                for pattern in patterns {
+                    pattern.as_ref().collect_bindings(names);
+                }
+            }
+            Pattern::StringPrefix { rest, .. } => {
+                if let Some(rest) = rest {
+                    names.push(rest);
+                }
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -489,6 +892,15 @@ pub enum BinaryOp {
     Mul,
     Div,
     Mod,
+    FloorDiv,
+    Pow,
+
+    // Bitwise
+    BitAnd,
+    BitOr,
+    BitXor,
+    Shl,
+    Shr,
 
     // Comparison
     Eq,
@@ -505,16 +917,94 @@ pub enum BinaryOp {
     Or,
 }
 
+impl BinaryOp {
+    /// Method a struct defines to support the operator, so `a + b` calls `a.add(b)`.
+    /// `!=`, `>`, `<=` and `>=` are derived from `eq` and `lt`.
+    pub fn method_name(self) -> Option<&'static str> {
+        Some(match self {
+            BinaryOp::Add => "add",
+            BinaryOp::Sub => "sub",
+            BinaryOp::Mul => "mul",
+            BinaryOp::Div => "div",
+            BinaryOp::Mod => "mod",
+            BinaryOp::FloorDiv => "floordiv",
+            BinaryOp::Pow => "pow",
+            BinaryOp::BitAnd => "bitand",
+            BinaryOp::BitOr => "bitor",
+            BinaryOp::BitXor => "bitxor",
+            BinaryOp::Shl => "shl",
+            BinaryOp::Shr => "shr",
+            BinaryOp::Eq | BinaryOp::Ne => "eq",
+            BinaryOp::Lt | BinaryOp::Gt | BinaryOp::LtEq | BinaryOp::GtEq => "lt",
+            BinaryOp::Is | BinaryOp::IsNot | BinaryOp::And | BinaryOp::Or => return None,
+        })
+    }
+
+    /// Whether the operator method is called on the right operand: `a > b` is `b.lt(a)`
+    pub fn swaps_operands(self) -> bool {
+        matches!(self, BinaryOp::Gt | BinaryOp::LtEq)
+    }
+
+    /// Whether the result of the operator method is negated: `a >= b` is `not a.lt(b)`
+    pub fn negates_result(self) -> bool {
+        matches!(self, BinaryOp::Ne | BinaryOp::LtEq | BinaryOp::GtEq)
+    }
+
+    /// Operator as written in source
+    pub fn symbol(self) -> &'static str {
+        match self {
+            BinaryOp::Add => "+",
+            BinaryOp::Sub => "-",
+            BinaryOp::Mul => "*",
+            BinaryOp::Div => "/",
+            BinaryOp::Mod => "%",
+            BinaryOp::FloorDiv => "//",
+            BinaryOp::Pow => "**",
+            BinaryOp::BitAnd => "&",
+            BinaryOp::BitOr => "|",
+            BinaryOp::BitXor => "^",
+            BinaryOp::Shl => "<<",
+            BinaryOp::Shr => ">>",
+            BinaryOp::Eq => "==",
+            BinaryOp::Ne => "!=",
+            BinaryOp::Lt => "<",
+            BinaryOp::Gt => ">",
+            BinaryOp::LtEq => "<=",
+            BinaryOp::GtEq => ">=",
+            BinaryOp::Is => "is",
+            BinaryOp::IsNot => "is not",
+            BinaryOp::And => "and",
+            BinaryOp::Or => "or",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum UnaryOp {
     Neg,
     Not,
+    BitNot,
+}
+
+impl UnaryOp {
+    /// Method a struct defines to support the operator, so `-a` calls `a.neg()`
+    pub fn method_name(self) -> Option<&'static str> {
+        match self {
+            UnaryOp::Neg => Some("neg"),
+            UnaryOp::BitNot => Some("bitnot"),
+            UnaryOp::Not => None,
+        }
+    }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct NumberLiteral {
     pub value: f64,
     pub is_float_literal: bool,
+    /// Exact value of an integer literal, which `value` only approximates beyond 2^53
+    pub integer: Option<i128>,
+    /// The literal as written (`0xFF`, `1_000`), kept so the formatter can reproduce it
+    pub source: Option<String>,
 }
 
 impl NumberLiteral {
@@ -522,13 +1012,77 @@ impl NumberLiteral {
         Self {
             value,
             is_float_literal,
+            integer: (!is_float_literal).then_some(value as i128),
+            source: None,
+        }
+    }
+
+    pub fn from_int(value: i128) -> Self {
+        Self {
+            value: value as f64,
+            is_float_literal: false,
+            integer: Some(value),
+            source: None,
         }
     }
+
+    /// Parse the text of a number token: decimal, `0x`/`0o`/`0b` integers, and floats with an
+    /// optional exponent, any of them with `_` separators. Integers too large for `i128`
+    /// saturate; the type checker reports them as out of range.
+    pub fn parse(text: &str) -> Option<Self> {
+        let digits = text.replace('_', "");
+        let radix = match digits.get(..2) {
+            Some("0x" | "0X") => 16,
+            Some("0o" | "0O") => 8,
+            Some("0b" | "0B") => 2,
+            _ => 10,
+        };
+        let mut literal = if radix != 10 {
+            Self::from_int(parse_integer(&digits[2..], radix)?)
+        } else if digits.contains(['.', 'e', 'E']) {
+            Self::new(digits.parse().ok()?, true)
+        } else {
+            Self::from_int(parse_integer(&digits, 10)?)
+        };
+        literal.source = Some(text.to_string());
+        Some(literal)
+    }
+
+    /// The value as an integer, exact for integer literals
+    pub fn int_value(&self) -> i128 {
+        self.integer.unwrap_or(self.value as i128)
+    }
+
+    /// The same literal with a leading `-`, as written in a negative pattern such as `-1..0`
+    pub fn negated(self) -> Self {
+        Self {
+            value: -self.value,
+            is_float_literal: self.is_float_literal,
+            integer: self.integer.map(|value| -value),
+            source: self.source.map(|source| format!("-{}", source)),
+        }
+    }
+}
+
+/// Parse unsigned digits in `radix`, saturating at `i128::MAX`
+fn parse_integer(digits: &str, radix: u32) -> Option<i128> {
+    if digits.is_empty() {
+        return None;
+    }
+    digits.chars().try_fold(0i128, |value, ch| {
+        let digit = ch.to_digit(radix)?;
+        Some(
+            value
+                .saturating_mul(i128::from(radix))
+                .saturating_add(i128::from(digit)),
+        )
+    })
 }
 
 impl PartialEq for NumberLiteral {
     fn eq(&self, other: &Self) -> bool {
         self.is_float_literal == other.is_float_literal
+            && self.integer == other.integer
             && self.value.to_bits() == other.value.to_bits()
     }
 }
@@ -539,12 +1093,63 @@ impl Hash for NumberLiteral {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.value.to_bits().hash(state);
         self.is_float_literal.hash(state);
+        self.integer.hash(state);
+    }
+}
+
+/// How a string literal is written in the source, kept so the formatter can reproduce it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum StringStyle {
+    /// `"..."`
+    #[default]
+    Quoted,
+    /// `"""..."""`, which may span several lines
+    Multiline,
+    /// `r"..."`, where backslashes are not escapes
+    Raw,
+    /// `r"""..."""`
+    RawMultiline,
+}
+
+#[derive(Debug, Clone)]
+pub struct StringLiteral {
+    pub value: String,
+    pub style: StringStyle,
+}
+
+impl StringLiteral {
+    pub fn new(value: impl Into<String>) -> Self {
+        Self::with_style(value, StringStyle::Quoted)
+    }
+
+    pub fn with_style(value: impl Into<String>, style: StringStyle) -> Self {
+        Self {
+            value: value.into(),
+            style,
+        }
+    }
+}
+
+// Strings written in different styles are the same value
+impl PartialEq for StringLiteral {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl Eq for StringLiteral {}
+
+impl Hash for StringLiteral {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.hash(state);
     }
 }
 
 #[derive(Debug, Clone)]
 pub enum Literal {
-    String(String),
+    String(StringLiteral),
+    /// `b"..."`, the bytes of the text as a `list<int>`
+    Bytes(Vec<u8>),
     Number(NumberLiteral),
     Bool(bool),
     None,
@@ -555,6 +1160,7 @@ impl PartialEq for Literal {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (Literal::String(a), Literal::String(b)) => a == b,
+            (Literal::Bytes(a), Literal::Bytes(b)) => a == b,
             (Literal::Bool(a), Literal::Bool(b)) => a == b,
             (Literal::Number(a), Literal::Number(b)) => a == b,
             (Literal::None, Literal::None) | (Literal::Unit, Literal::Unit) => true,
@@ -586,6 +1192,10 @@ impl Hash for Literal {
             Literal::Unit => {
                 4u8.hash(state);
             }
+            Literal::Bytes(bytes) => {
+                5u8.hash(state);
+                bytes.hash(state);
+            }
         }
     }
 }
@@ -146,8 +146,15 @@ fn type_spec_to_ffi(spec: &TypeSpec, position: &str, function_name: &str) -> Res
             }
         }
         TypeSpec::Bool => Ok(FfiType::Bool),
+        TypeSpec::I8 => Ok(FfiType::I8),
+        TypeSpec::I16 => Ok(FfiType::I16),
         TypeSpec::I32 => Ok(FfiType::I32),
         TypeSpec::I64 => Ok(FfiType::I64),
+        TypeSpec::U8 => Ok(FfiType::U8),
+        TypeSpec::U16 => Ok(FfiType::U16),
+        TypeSpec::U32 => Ok(FfiType::U32),
+        TypeSpec::U64 => Ok(FfiType::U64),
+        TypeSpec::F32 => Ok(FfiType::F32),
         TypeSpec::F64 => Ok(FfiType::F64),
         TypeSpec::Str => Ok(FfiType::Str),
         TypeSpec::Opaque | TypeSpec::Option(_) => Ok(FfiType::Opaque),
@@ -0,0 +1,548 @@
+//! Methods generated by `@derive(...)` on structs and enums. The type checker validates the
+//! attributes and records the signatures; the bodies are emitted here field by field with the
+//! lowering that `==`, `<`, `hash(x)` and `str(x)` use, so nested structs and enums are handled
+//! by their own methods, derived or written by hand.
+
+use anyhow::{Result, anyhow, bail};
+use inkwell::IntPredicate;
+use inkwell::basic_block::BasicBlock;
+use inkwell::values::{BasicValueEnum, FunctionValue, IntValue};
+
+use crate::llvm::compiler::Compiler;
+use crate::llvm::compiler::types::{EvaluatedValue, FunctionContext, OtterType};
+use otterc_ast::nodes::{BinaryOp, Function, Node, Statement, Type, derived_signature};
+use otterc_typecheck::{EnumLayout, TypeInfo};
+
+/// A field of a struct or a payload of an enum variant. Enum values are opaque handles, so
+/// the enum a value belongs to is kept to find its methods.
+#[derive(Clone)]
+struct Field<'ctx> {
+    value: EvaluatedValue<'ctx>,
+    enum_name: Option<String>,
+}
+
+/// The method `derive` generates on `type_name`, named and typed like a struct method
+fn derived_method(type_name: &str, derive: &Node<String>) -> Option<Function> {
+    let mut method = derived_signature(derive)?;
+    method.name = format!("{}_{}", type_name, method.name);
+    method.resolve_self_type(type_name);
+    Some(method)
+}
+
+impl<'ctx> Compiler<'ctx> {
+    /// Declares the methods named by the `@derive` attributes of the struct or enum
+    /// `type_name`
+    pub(crate) fn declare_derived_methods(
+        &mut self,
+        type_name: &str,
+        derives: &[Node<String>],
+    ) -> Result<()> {
+        for method in derives
+            .iter()
+            .filter_map(|derive| derived_method(type_name, derive))
+        {
+            self.register_function_prototype(&method)?;
+        }
+        Ok(())
+    }
+
+    /// Emits the bodies of the methods that `statement`, a struct or enum, derives
+    pub(crate) fn compile_derived_methods(&mut self, statement: &Statement) -> Result<()> {
+        let (Statement::Struct { name, derives, .. } | Statement::Enum { name, derives, .. }) =
+            statement
+        else {
+            return Ok(());
+        };
+        for derive in derives {
+            let Some(method) = derived_method(name, derive) else {
+                continue;
+            };
+            let function = *self
+                .declared_functions
+                .get(&method.name)
+                .ok_or_else(|| anyhow!("Function {} not found", method.name))?;
+            let entry = self.context.append_basic_block(function, "entry");
+            self.builder.position_at_end(entry);
+            let mut ctx = FunctionContext::new();
+            let params: Vec<BasicValueEnum> = function.get_param_iter().collect();
+            match statement {
+                Statement::Struct { fields, .. } => self.derive_struct_method(
+                    derive.as_ref(),
+                    name,
+                    fields,
+                    function,
+                    &params,
+                    &mut ctx,
+                ),
+                _ => self.derive_enum_method(derive.as_ref(), name, function, &params, &mut ctx),
+            }?;
+        }
+        Ok(())
+    }
+
+    fn derive_struct_method(
+        &mut self,
+        derive: &str,
+        name: &str,
+        fields: &[(String, Node<Type>)],
+        function: FunctionValue<'ctx>,
+        params: &[BasicValueEnum<'ctx>],
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<()> {
+        let struct_id = self
+            .struct_id(name)
+            .ok_or_else(|| anyhow!("Missing struct layout for {}", name))?;
+        let receiver = self.struct_fields(struct_id, params[0], fields)?;
+        match derive {
+            "Eq" => {
+                let other = self.struct_fields(struct_id, params[1], fields)?;
+                self.return_all_equal(function, receiver.into_iter().zip(other).collect(), ctx)
+            }
+            "Ord" => {
+                let other = self.struct_fields(struct_id, params[1], fields)?;
+                self.return_lexicographic_lt(
+                    function,
+                    receiver.into_iter().zip(other).collect(),
+                    ctx,
+                )
+            }
+            "Hash" => {
+                let seed = self.context.i64_type().const_zero();
+                let hash = self.combined_hash(seed, receiver, ctx)?;
+                self.builder.build_return(Some(&hash))?;
+                Ok(())
+            }
+            "Debug" => {
+                let labelled = fields
+                    .iter()
+                    .map(|(field_name, _)| Some(field_name.as_str()))
+                    .zip(receiver)
+                    .collect();
+                let text = self.describe_fields(&format!("{}(", name), labelled, ")", ctx)?;
+                self.return_value(text)
+            }
+            "Clone" => {
+                let (struct_type, field_types) = {
+                    let info = self.struct_info(struct_id);
+                    (info.ty, info.field_types.clone())
+                };
+                let mut aggregate = struct_type.get_undef();
+                for (index, (field, ty)) in receiver.into_iter().zip(field_types).enumerate() {
+                    let copy = self.cloned_field(field, ctx)?;
+                    let raw = copy
+                        .value
+                        .ok_or_else(|| anyhow!("field {} of {} has no value", index, name))?;
+                    let coerced = self.coerce_value(raw, copy.ty, ty)?;
+                    aggregate = self
+                        .builder
+                        .build_insert_value(aggregate, coerced, index as u32, "clone_field")?
+                        .into_struct_value();
+                }
+                self.builder.build_return(Some(&aggregate))?;
+                Ok(())
+            }
+            other => bail!("cannot derive `{}`", other),
+        }
+    }
+
+    fn derive_enum_method(
+        &mut self,
+        derive: &str,
+        name: &str,
+        function: FunctionValue<'ctx>,
+        params: &[BasicValueEnum<'ctx>],
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<()> {
+        let layout = self
+            .enum_layout(name)
+            .cloned()
+            .ok_or_else(|| anyhow!("Missing enum layout for {}", name))?;
+        let tag = self
+            .call_ffi_returning_value("runtime.enum.get_tag", vec![params[0]], "tag")?
+            .into_int_value();
+
+        // Values of different variants are unequal, and the earlier variant orders first
+        if matches!(derive, "Eq" | "Ord") {
+            let other_tag = self
+                .call_ffi_returning_value("runtime.enum.get_tag", vec![params[1]], "other_tag")?
+                .into_int_value();
+            let same =
+                self.builder
+                    .build_int_compare(IntPredicate::EQ, tag, other_tag, "same_variant")?;
+            let same_block = self.context.append_basic_block(function, "same_variant");
+            let other_block = self.context.append_basic_block(function, "other_variant");
+            self.builder
+                .build_conditional_branch(same, same_block, other_block)?;
+            self.builder.position_at_end(other_block);
+            let result = if derive == "Eq" {
+                self.context.bool_type().const_zero()
+            } else {
+                self.builder
+                    .build_int_compare(IntPredicate::ULT, tag, other_tag, "earlier")?
+            };
+            self.builder.build_return(Some(&result))?;
+            self.builder.position_at_end(same_block);
+        }
+
+        for (variant, variant_tag, block) in self.switch_on_variant(function, tag, &layout)? {
+            self.builder.position_at_end(block);
+            let field_types = layout
+                .variant_fields
+                .get(&variant)
+                .cloned()
+                .unwrap_or_default();
+            let receiver = self.enum_fields(params[0], &field_types)?;
+            match derive {
+                "Eq" => {
+                    let other = self.enum_fields(params[1], &field_types)?;
+                    let pairs = receiver.into_iter().zip(other).collect();
+                    self.return_all_equal(function, pairs, ctx)?;
+                }
+                "Ord" => {
+                    let other = self.enum_fields(params[1], &field_types)?;
+                    let pairs = receiver.into_iter().zip(other).collect();
+                    self.return_lexicographic_lt(function, pairs, ctx)?;
+                }
+                "Hash" => {
+                    let seed = self
+                        .context
+                        .i64_type()
+                        .const_int(u64::from(variant_tag), false);
+                    let hash = self.combined_hash(seed, receiver, ctx)?;
+                    self.builder.build_return(Some(&hash))?;
+                }
+                "Debug" => {
+                    let prefix = format!("{}.{}", name, variant);
+                    let text = if receiver.is_empty() {
+                        self.text_literal(&prefix)?
+                    } else {
+                        let unlabelled = receiver.into_iter().map(|field| (None, field)).collect();
+                        self.describe_fields(&format!("{}(", prefix), unlabelled, ")", ctx)?
+                    };
+                    self.return_value(text)?;
+                }
+                "Clone" => {
+                    let mut values = Vec::with_capacity(receiver.len());
+                    for field in receiver {
+                        values.push(self.cloned_field(field, ctx)?);
+                    }
+                    let copy = self.create_enum_instance(
+                        name,
+                        &variant,
+                        variant_tag,
+                        &field_types,
+                        values,
+                    )?;
+                    self.return_value(copy)?;
+                }
+                other => bail!("cannot derive `{}`", other),
+            }
+        }
+        Ok(())
+    }
+
+    /// Branches on the variant of an enum value, returning each variant with its tag and
+    /// the block it continues in
+    fn switch_on_variant(
+        &mut self,
+        function: FunctionValue<'ctx>,
+        tag: IntValue<'ctx>,
+        layout: &EnumLayout,
+    ) -> Result<Vec<(String, u32, BasicBlock<'ctx>)>> {
+        let unknown = self.context.append_basic_block(function, "unknown_variant");
+        let variants: Vec<_> = layout
+            .variants
+            .iter()
+            .enumerate()
+            .map(|(index, variant)| {
+                let block = self.context.append_basic_block(function, variant);
+                (variant.clone(), index as u32, block)
+            })
+            .collect();
+        let cases: Vec<_> = variants
+            .iter()
+            .map(|(_, index, block)| (tag.get_type().const_int(u64::from(*index), false), *block))
+            .collect();
+        self.builder.build_switch(tag, unknown, &cases)?;
+        self.builder.position_at_end(unknown);
+        self.builder.build_unreachable()?;
+        Ok(variants)
+    }
+
+    /// The fields of the struct value `value`, in declaration order
+    fn struct_fields(
+        &mut self,
+        struct_id: u32,
+        value: BasicValueEnum<'ctx>,
+        fields: &[(String, Node<Type>)],
+    ) -> Result<Vec<Field<'ctx>>> {
+        let value = value.into_struct_value();
+        let mut result = Vec::with_capacity(fields.len());
+        for (index, (field_name, ty)) in fields.iter().enumerate() {
+            let raw = self
+                .builder
+                .build_extract_value(value, index as u32, field_name)?;
+            let field_ty = self.struct_info(struct_id).field_types[index].clone();
+            let enum_name = match ty.as_ref() {
+                Type::Simple(name) | Type::Generic { base: name, .. }
+                    if self.enum_layout(name).is_some() =>
+                {
+                    Some(name.clone())
+                }
+                _ => None,
+            };
+            result.push(Field {
+                value: EvaluatedValue::with_value(raw, field_ty),
+                enum_name,
+            });
+        }
+        Ok(result)
+    }
+
+    /// The payload of the enum value `handle`, whose variant has fields of `field_types`
+    fn enum_fields(
+        &mut self,
+        handle: BasicValueEnum<'ctx>,
+        field_types: &[TypeInfo],
+    ) -> Result<Vec<Field<'ctx>>> {
+        let mut result = Vec::with_capacity(field_types.len());
+        for (index, ty) in field_types.iter().enumerate() {
+            let payload = self.load_enum_field(handle, index as u32, ty)?;
+            let value = self.retype_enum_payload(payload, ty)?;
+            let enum_name = match ty {
+                TypeInfo::Enum { name, .. } => Some(name.clone()),
+                _ => None,
+            };
+            result.push(Field { value, enum_name });
+        }
+        Ok(result)
+    }
+
+    /// Lowered name of the method `method` of the struct or enum held in `field`
+    fn field_method(&self, field: &Field<'ctx>, method: &str) -> Option<String> {
+        match (&field.value.ty, &field.enum_name) {
+            (OtterType::Struct(struct_id), _) => {
+                self.resolve_struct_method_name(*struct_id, method)
+            }
+            (_, Some(enum_name)) => self.enum_method_name(enum_name, method),
+            _ => None,
+        }
+    }
+
+    /// `left op right` for a comparison operator, as a bool
+    fn compare_fields(
+        &mut self,
+        op: BinaryOp,
+        left: Field<'ctx>,
+        right: Field<'ctx>,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<IntValue<'ctx>> {
+        let method = op
+            .method_name()
+            .and_then(|method| self.field_method(&left, method));
+        let result = self.build_binary_values(&op, left.value, right.value, method, ctx)?;
+        Ok(result
+            .value
+            .ok_or_else(|| anyhow!("`{}` produced no value", op.symbol()))?
+            .into_int_value())
+    }
+
+    /// Returns `true` when every pair of fields is equal, and `false` from the first pair
+    /// that is not
+    fn return_all_equal(
+        &mut self,
+        function: FunctionValue<'ctx>,
+        pairs: Vec<(Field<'ctx>, Field<'ctx>)>,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<()> {
+        let bool_type = self.context.bool_type();
+        let not_equal = self.context.append_basic_block(function, "not_equal");
+        for (left, right) in pairs {
+            let equal = self.compare_fields(BinaryOp::Eq, left, right, ctx)?;
+            let next = self.context.append_basic_block(function, "next_field");
+            self.builder
+                .build_conditional_branch(equal, next, not_equal)?;
+            self.builder.position_at_end(next);
+        }
+        self.builder
+            .build_return(Some(&bool_type.const_all_ones()))?;
+        self.builder.position_at_end(not_equal);
+        self.builder.build_return(Some(&bool_type.const_zero()))?;
+        Ok(())
+    }
+
+    /// Returns whether the left fields order before the right ones: the first pair where one
+    /// field is less than the other decides, and all fields equal is `false`. Only `<` is
+    /// used, so fields need nothing but an `lt` method.
+    fn return_lexicographic_lt(
+        &mut self,
+        function: FunctionValue<'ctx>,
+        pairs: Vec<(Field<'ctx>, Field<'ctx>)>,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<()> {
+        let bool_type = self.context.bool_type();
+        let less = self.context.append_basic_block(function, "less");
+        let not_less = self.context.append_basic_block(function, "not_less");
+        for (left, right) in pairs {
+            let lt = self.compare_fields(BinaryOp::Lt, left.clone(), right.clone(), ctx)?;
+            let check_greater = self.context.append_basic_block(function, "check_greater");
+            self.builder
+                .build_conditional_branch(lt, less, check_greater)?;
+            self.builder.position_at_end(check_greater);
+            let gt = self.compare_fields(BinaryOp::Lt, right, left, ctx)?;
+            let next = self.context.append_basic_block(function, "next_field");
+            self.builder.build_conditional_branch(gt, not_less, next)?;
+            self.builder.position_at_end(next);
+        }
+        self.builder.build_unconditional_branch(not_less)?;
+        self.builder.position_at_end(less);
+        self.builder
+            .build_return(Some(&bool_type.const_all_ones()))?;
+        self.builder.position_at_end(not_less);
+        self.builder.build_return(Some(&bool_type.const_zero()))?;
+        Ok(())
+    }
+
+    /// `(seed * 31 + hash(f0)) * 31 + hash(f1) ...`, wrapping on overflow
+    fn combined_hash(
+        &mut self,
+        seed: IntValue<'ctx>,
+        fields: Vec<Field<'ctx>>,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<IntValue<'ctx>> {
+        let factor = self.context.i64_type().const_int(31, false);
+        let mut hash = seed;
+        for field in fields {
+            let method = self.field_method(&field, "hash");
+            let field_hash = self.hash_value(field.value, method, ctx)?;
+            let raw = field_hash
+                .value
+                .ok_or_else(|| anyhow!("`hash` returned no value"))?;
+            let field_hash = self
+                .convert_number(raw, &field_hash.ty, &OtterType::I64)?
+                .into_int_value();
+            let scaled = self.builder.build_int_mul(hash, factor, "hash_scaled")?;
+            hash = self.builder.build_int_add(scaled, field_hash, "hash")?;
+        }
+        Ok(hash)
+    }
+
+    /// `(a, b)` for the tuple `value`, its elements shown as `str` shows them. `elements` are
+    /// the checker's types of the elements when known; they name the enums among them.
+    pub(crate) fn display_tuple(
+        &mut self,
+        value: EvaluatedValue<'ctx>,
+        elements: &[TypeInfo],
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<EvaluatedValue<'ctx>> {
+        let OtterType::Tuple(element_types) = value.ty else {
+            bail!("cannot display {:?} as a tuple", value.ty);
+        };
+        let tuple = value
+            .value
+            .ok_or_else(|| anyhow!("tuple has no value"))?
+            .into_struct_value();
+        let mut fields = Vec::with_capacity(element_types.len());
+        for (index, ty) in element_types.into_iter().enumerate() {
+            let raw = self
+                .builder
+                .build_extract_value(tuple, index as u32, "tuple_elem")?;
+            let enum_name = match elements.get(index) {
+                Some(TypeInfo::Enum { name, .. }) => Some(name.clone()),
+                _ => None,
+            };
+            let field = Field {
+                value: EvaluatedValue::with_value(raw, ty),
+                enum_name,
+            };
+            fields.push((None, field));
+        }
+        let suffix = if fields.len() == 1 { ",)" } else { ")" };
+        self.describe_fields("(", fields, suffix, ctx)
+    }
+
+    /// `prefix`, the fields separated by commas with `label=` before the labelled ones, then
+    /// `suffix`. Strings are quoted so `Name(x="a, b")` reads unambiguously.
+    fn describe_fields(
+        &mut self,
+        prefix: &str,
+        fields: Vec<(Option<&str>, Field<'ctx>)>,
+        suffix: &str,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<EvaluatedValue<'ctx>> {
+        let mut text = self.text_literal(prefix)?;
+        let mut pending = String::new();
+        for (index, (label, field)) in fields.into_iter().enumerate() {
+            if index > 0 {
+                pending.push_str(", ");
+            }
+            if let Some(label) = label {
+                pending.push_str(label);
+                pending.push('=');
+            }
+            let quoted = field.value.ty == OtterType::Str;
+            if quoted {
+                pending.push('"');
+            }
+            if !pending.is_empty() {
+                let literal = self.text_literal(&pending)?;
+                text = self.build_string_concat(text, literal)?;
+                pending.clear();
+            }
+            let method = self.field_method(&field, "str");
+            let shown = self.display_value(field.value, method, ctx)?;
+            text = self.build_string_concat(text, shown)?;
+            if quoted {
+                pending.push('"');
+            }
+        }
+        pending.push_str(suffix);
+        let literal = self.text_literal(&pending)?;
+        self.build_string_concat(text, literal)
+    }
+
+    pub(crate) fn text_literal(&mut self, text: &str) -> Result<EvaluatedValue<'ctx>> {
+        let value = self.builder.build_global_string_ptr(text, "derive_text")?;
+        Ok(EvaluatedValue::with_value(
+            value.as_pointer_value().into(),
+            OtterType::Str,
+        ))
+    }
+
+    /// A copy of `field`: lists are sliced and values with a `clone` method cloned, other
+    /// values are copied or shared
+    fn cloned_field(
+        &mut self,
+        field: Field<'ctx>,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<EvaluatedValue<'ctx>> {
+        if let OtterType::List(_) = field.value.ty {
+            let handle = field
+                .value
+                .value
+                .ok_or_else(|| anyhow!("list field has no value"))?;
+            let i64_type = self.context.i64_type();
+            let args = vec![
+                handle,
+                i64_type.const_zero().into(),
+                i64_type.const_int(i64::MAX as u64, false).into(),
+            ];
+            let copy = self.call_ffi_returning_value("list.slice", args, "list_copy")?;
+            return Ok(EvaluatedValue::with_value(copy, field.value.ty));
+        }
+        let Some(method) = self.field_method(&field, "clone") else {
+            return Ok(field.value);
+        };
+        let copy = self.call_method(&method, field.value, None)?;
+        self.check_pending_exception(ctx)?;
+        Ok(copy)
+    }
+
+    fn return_value(&mut self, value: EvaluatedValue<'ctx>) -> Result<()> {
+        let value = value
+            .value
+            .ok_or_else(|| anyhow!("derived method produced no value"))?;
+        self.builder.build_return(Some(&value))?;
+        Ok(())
+    }
+}
@@ -1,14 +1,24 @@
 use anyhow::{Result, anyhow, bail};
 use inkwell::AddressSpace;
 use inkwell::IntPredicate;
-use inkwell::types::{BasicTypeEnum, PointerType, StructType};
-use inkwell::values::{BasicMetadataValueEnum, BasicValueEnum, FunctionValue, IntValue};
+use inkwell::intrinsics::Intrinsic;
+use inkwell::module::Linkage;
+use inkwell::types::{
+    BasicMetadataTypeEnum, BasicType, BasicTypeEnum, FunctionType, PointerType, StructType,
+};
+use inkwell::values::{
+    BasicMetadataValueEnum, BasicValueEnum, FloatValue, FunctionValue, IntValue, PointerValue,
+};
 use std::collections::BTreeSet;
+use std::sync::atomic::Ordering;
 
 use crate::llvm::compiler::Compiler;
 use crate::llvm::compiler::types::{EvaluatedValue, FunctionContext, OtterType, Variable};
-use otterc_ast::nodes::{BinaryOp, Block, Expr, FStringPart, Literal, Node, Statement, UnaryOp};
-use otterc_typecheck::TypeInfo;
+use otterc_ast::nodes::{
+    BinaryOp, Block, Expr, FStringPart, Function, Literal, MatchArm, Node, Param, Pattern,
+    Statement, StringLiteral, Type, UnaryOp,
+};
+use otterc_typecheck::{ConstValue, EnumLayout, ITERATOR_ADAPTERS, TypeInfo, arrange_arguments};
 
 struct CapturedVariable<'ctx> {
     name: String,
@@ -16,6 +26,13 @@ struct CapturedVariable<'ctx> {
     llvm_ty: BasicTypeEnum<'ctx>,
 }
 
+/// Integer a `match` switches on to pick its next arm: the scrutinee's enum tag or its value
+struct MatchSwitch<'ctx> {
+    key: IntValue<'ctx>,
+    /// Keys each arm can match, or `None` when the arm has to be tried whatever the key is
+    arm_keys: Vec<Option<Vec<u64>>>,
+}
+
 impl<'ctx> Compiler<'ctx> {
     fn eval_await_expr(
         &mut self,
@@ -205,6 +222,678 @@ impl<'ctx> Compiler<'ctx> {
         Ok(function)
     }
 
+    fn eval_lambda_expr(
+        &mut self,
+        expr: &Expr,
+        params: &[Node<Param>],
+        body: &Node<Block>,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<EvaluatedValue<'ctx>> {
+        let closure_ty = self
+            .expr_type(expr)
+            .and_then(|ty| self.typeinfo_to_otter_type(ty))
+            .ok_or_else(|| anyhow!("missing type information for closure"))?;
+        let OtterType::Function {
+            params: param_types,
+            ret,
+        } = closure_ty.clone()
+        else {
+            bail!("closure has non-function type {:?}", closure_ty);
+        };
+
+        // Captured locals are copied into the environment when the closure is created
+        let mut captured = BTreeSet::new();
+        self.collect_captured_names_in_block(body.as_ref(), ctx, &mut captured);
+        let mut captures = Vec::new();
+        for name in captured {
+            if params
+                .iter()
+                .any(|param| *param.as_ref().name.as_ref() == name)
+            {
+                continue;
+            }
+            if let Some(var) = ctx.get(&name)
+                && let Some(llvm_ty) = self.basic_type(var.ty.clone())?
+            {
+                captures.push(CapturedVariable {
+                    name,
+                    ty: var.ty.clone(),
+                    llvm_ty,
+                });
+            }
+        }
+
+        let mut env_fields: Vec<BasicTypeEnum> = vec![self.raw_ptr_type().into()];
+        env_fields.extend(captures.iter().map(|capture| capture.llvm_ty));
+        let env_type = self.context.struct_type(&env_fields, false);
+
+        let return_type = match self.expr_type(expr) {
+            Some(TypeInfo::Function { return_type, .. }) => Some(return_type.as_ref().clone()),
+            _ => None,
+        };
+        let function = self.build_lambda_function(
+            params,
+            &param_types,
+            &ret,
+            return_type,
+            body,
+            env_type,
+            &captures,
+        )?;
+
+        let env_ptr = self.build_heap_alloc(Self::conservative_size_of(env_type.into()))?;
+        let fn_slot = self
+            .builder
+            .build_struct_gep(env_type, env_ptr, 0, "closure_fn_slot")?;
+        self.builder
+            .build_store(fn_slot, function.as_global_value().as_pointer_value())?;
+        for (index, capture) in captures.iter().enumerate() {
+            let var = ctx.get(&capture.name).ok_or_else(|| {
+                anyhow!("captured variable '{}' missing from scope", capture.name)
+            })?;
+            let loaded = self
+                .builder
+                .build_load(capture.llvm_ty, var.ptr, &capture.name)?;
+            let slot = self.builder.build_struct_gep(
+                env_type,
+                env_ptr,
+                index as u32 + 1,
+                &format!("closure_capture_{}", capture.name),
+            )?;
+            self.builder.build_store(slot, loaded)?;
+        }
+
+        let handle = self
+            .builder
+            .build_ptr_to_int(env_ptr, self.context.i64_type(), "closure")?;
+        Ok(EvaluatedValue::with_value(handle.into(), closure_ty))
+    }
+
+    /// Emits the function behind a closure. It takes the environment pointer followed by the
+    /// declared parameters and reloads the captured variables into its own stack slots.
+    #[expect(
+        clippy::too_many_arguments,
+        reason = "TODO: Create a struct to hold these args"
+    )]
+    fn build_lambda_function(
+        &mut self,
+        params: &[Node<Param>],
+        param_types: &[OtterType],
+        ret: &OtterType,
+        return_type: Option<TypeInfo>,
+        body: &Node<Block>,
+        env_type: StructType<'ctx>,
+        captures: &[CapturedVariable<'ctx>],
+    ) -> Result<FunctionValue<'ctx>> {
+        let lambda_id = self.lambda_counter.fetch_add(1, Ordering::Relaxed);
+        let fn_type = self.closure_fn_type(param_types, ret)?;
+        let function =
+            self.module
+                .add_function(&format!("__otter_lambda_{}", lambda_id), fn_type, None);
+        let entry = self.context.append_basic_block(function, "entry");
+        let prev_block = self.builder.get_insert_block();
+        self.builder.position_at_end(entry);
+
+        let mut lambda_ctx = FunctionContext::new();
+        lambda_ctx.return_type = return_type;
+        let env_ptr = function
+            .get_nth_param(0)
+            .ok_or_else(|| anyhow!("closure missing environment parameter"))?
+            .into_pointer_value();
+        for (index, capture) in captures.iter().enumerate() {
+            let slot = self.builder.build_struct_gep(
+                env_type,
+                env_ptr,
+                index as u32 + 1,
+                &format!("closure_capture_gep_{}", capture.name),
+            )?;
+            let loaded = self
+                .builder
+                .build_load(capture.llvm_ty, slot, &capture.name)?;
+            let alloca =
+                self.create_entry_block_alloca(function, &capture.name, capture.ty.clone())?;
+            self.builder.build_store(alloca, loaded)?;
+            lambda_ctx.insert(
+                capture.name.clone(),
+                Variable {
+                    ptr: alloca,
+                    ty: capture.ty.clone(),
+                },
+            );
+        }
+
+        for (index, (param, ty)) in params.iter().zip(param_types).enumerate() {
+            let name = param.as_ref().name.as_ref();
+            let value = function
+                .get_nth_param(index as u32 + 1)
+                .ok_or_else(|| anyhow!("closure missing parameter '{}'", name))?;
+            let alloca = self.create_entry_block_alloca(function, name, ty.clone())?;
+            self.builder.build_store(alloca, value)?;
+            lambda_ctx.insert(
+                name.clone(),
+                Variable {
+                    ptr: alloca,
+                    ty: ty.clone(),
+                },
+            );
+        }
+
+        self.lower_block(body.as_ref(), function, &mut lambda_ctx)?;
+
+        if self
+            .builder
+            .get_insert_block()
+            .and_then(|block| block.get_terminator())
+            .is_none()
+        {
+            match self.basic_type(ret.clone())? {
+                Some(llvm_ty) => self.builder.build_return(Some(&llvm_ty.const_zero()))?,
+                None => self.builder.build_return(None)?,
+            };
+        }
+
+        if let Some(block) = prev_block {
+            self.builder.position_at_end(block);
+        }
+
+        Ok(function)
+    }
+
+    /// Wraps a named function in a closure with an empty environment so it can be passed
+    /// around as a value.
+    fn build_function_reference(&mut self, name: &str) -> Result<EvaluatedValue<'ctx>> {
+        let function = *self
+            .declared_functions
+            .get(name)
+            .ok_or_else(|| anyhow!("Function {} not found", name))?;
+        let params: Vec<OtterType> = function
+            .get_param_iter()
+            .map(|param| self.otter_type_from_basic_type(param.get_type()))
+            .collect();
+        let ret = self
+            .function_return_types
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| {
+                function
+                    .get_type()
+                    .get_return_type()
+                    .map(|ty| self.otter_type_from_basic_type(ty))
+                    .unwrap_or(OtterType::Unit)
+            });
+
+        let trampoline_key = format!("__fnref_{}", name);
+        let trampoline = if let Some(existing) = self.declared_functions.get(&trampoline_key) {
+            *existing
+        } else {
+            let fn_type = self.closure_fn_type(&params, &ret)?;
+            let trampoline =
+                self.module
+                    .add_function(&format!("__otter_fnref_{}", name), fn_type, None);
+            let entry = self.context.append_basic_block(trampoline, "entry");
+            let prev_block = self.builder.get_insert_block();
+            self.builder.position_at_end(entry);
+
+            let forwarded: Vec<BasicMetadataValueEnum> = trampoline
+                .get_param_iter()
+                .skip(1)
+                .map(Into::into)
+                .collect();
+            let call = self.builder.build_call(function, &forwarded, "forward")?;
+            match call.try_as_basic_value().left() {
+                Some(value) => self.builder.build_return(Some(&value))?,
+                None => self.builder.build_return(None)?,
+            };
+
+            if let Some(block) = prev_block {
+                self.builder.position_at_end(block);
+            }
+            self.declared_functions.insert(trampoline_key, trampoline);
+            trampoline
+        };
+
+        let env_type = self
+            .context
+            .struct_type(&[self.raw_ptr_type().into()], false);
+        let env_ptr = self.build_heap_alloc(Self::conservative_size_of(env_type.into()))?;
+        self.builder
+            .build_store(env_ptr, trampoline.as_global_value().as_pointer_value())?;
+        let handle = self
+            .builder
+            .build_ptr_to_int(env_ptr, self.context.i64_type(), "fn_ref")?;
+        Ok(EvaluatedValue::with_value(
+            handle.into(),
+            OtterType::Function {
+                params,
+                ret: Box::new(ret),
+            },
+        ))
+    }
+
+    fn build_closure_call(
+        &mut self,
+        closure: EvaluatedValue<'ctx>,
+        args: &[Node<Expr>],
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<EvaluatedValue<'ctx>> {
+        let mut arg_values = Vec::with_capacity(args.len());
+        for arg in args {
+            arg_values.push(self.eval_expr(arg.as_ref(), ctx)?);
+        }
+        self.call_closure_with_values(closure, arg_values, ctx)
+    }
+
+    /// Calls a closure with arguments that are already lowered.
+    pub(crate) fn call_closure_with_values(
+        &mut self,
+        closure: EvaluatedValue<'ctx>,
+        args: Vec<EvaluatedValue<'ctx>>,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<EvaluatedValue<'ctx>> {
+        let OtterType::Function { params, ret } = closure.ty.clone() else {
+            bail!("cannot call a value of type {:?}", closure.ty);
+        };
+        if args.len() != params.len() {
+            bail!(
+                "closure expects {} argument(s), got {}",
+                params.len(),
+                args.len()
+            );
+        }
+
+        let handle = closure
+            .value
+            .ok_or_else(|| anyhow!("missing closure value"))?
+            .into_int_value();
+        let env_ptr = self
+            .builder
+            .build_int_to_ptr(handle, self.raw_ptr_type(), "closure_env")?;
+        // The function pointer is the first field of every environment
+        let fn_ptr = self
+            .builder
+            .build_load(self.raw_ptr_type(), env_ptr, "closure_fn")?
+            .into_pointer_value();
+
+        let mut arg_values: Vec<BasicMetadataValueEnum> = vec![env_ptr.into()];
+        for (arg_val, param_ty) in args.into_iter().zip(&params) {
+            let value = arg_val
+                .value
+                .ok_or_else(|| anyhow!("Cannot pass unit value as argument"))?;
+            arg_values.push(
+                self.coerce_type(value, arg_val.ty, param_ty.clone())?
+                    .into(),
+            );
+        }
+
+        let fn_type = self.closure_fn_type(&params, &ret)?;
+        let call =
+            self.builder
+                .build_indirect_call(fn_type, fn_ptr, &arg_values, "closure_call")?;
+        self.check_pending_exception(ctx)?;
+        match call.try_as_basic_value().left() {
+            Some(value) => Ok(EvaluatedValue::with_value(value, *ret)),
+            None => Ok(EvaluatedValue {
+                ty: OtterType::Unit,
+                value: None,
+            }),
+        }
+    }
+
+    fn closure_fn_type(&self, params: &[OtterType], ret: &OtterType) -> Result<FunctionType<'ctx>> {
+        let mut llvm_params: Vec<BasicMetadataTypeEnum> = vec![self.raw_ptr_type().into()];
+        for ty in params {
+            let llvm_ty = self
+                .basic_type(ty.clone())?
+                .unwrap_or_else(|| self.context.i8_type().into());
+            llvm_params.push(llvm_ty.into());
+        }
+        Ok(match self.basic_type(ret.clone())? {
+            Some(ret_ty) => ret_ty.fn_type(&llvm_params, false),
+            None => self.context.void_type().fn_type(&llvm_params, false),
+        })
+    }
+
+    /// Upper bound on the allocation size of `ty`, giving every scalar its own 8-byte slot.
+    /// The target data layout is only attached to the module after lowering.
+    pub(crate) fn conservative_size_of(ty: BasicTypeEnum<'ctx>) -> u64 {
+        match ty {
+            BasicTypeEnum::StructType(struct_ty) => struct_ty
+                .get_field_types()
+                .into_iter()
+                .map(Self::conservative_size_of)
+                .sum(),
+            _ => 8,
+        }
+    }
+
+    /// Like `coerce_type`, but also boxes struct values into trait objects, which needs
+    /// a heap allocation.
+    pub(crate) fn coerce_value(
+        &mut self,
+        value: BasicValueEnum<'ctx>,
+        from_ty: OtterType,
+        to_ty: OtterType,
+    ) -> Result<BasicValueEnum<'ctx>> {
+        if let (OtterType::Struct(struct_id), OtterType::Dyn(trait_name)) = (&from_ty, &to_ty) {
+            return self.build_dyn_object(value, *struct_id, trait_name);
+        }
+        if let (OtterType::Tuple(from_elems), OtterType::Tuple(to_elems)) = (&from_ty, &to_ty)
+            && from_elems.len() == to_elems.len()
+            && from_ty != to_ty
+        {
+            let tuple_value = value.into_struct_value();
+            let mut elements = Vec::with_capacity(to_elems.len());
+            for (index, (from_elem, to_elem)) in from_elems.iter().zip(to_elems).enumerate() {
+                let element = self.builder.build_extract_value(
+                    tuple_value,
+                    index as u32,
+                    &format!("tuple_{}", index),
+                )?;
+                elements.push(self.coerce_value(element, from_elem.clone(), to_elem.clone())?);
+            }
+            return self.build_tuple_value(&elements, to_ty);
+        }
+        self.coerce_type(value, from_ty, to_ty)
+    }
+
+    /// Assembles a tuple, an anonymous LLVM struct, from already lowered elements.
+    pub(crate) fn build_tuple_value(
+        &mut self,
+        elements: &[BasicValueEnum<'ctx>],
+        ty: OtterType,
+    ) -> Result<BasicValueEnum<'ctx>> {
+        let tuple_type = self
+            .basic_type(ty)?
+            .ok_or_else(|| anyhow!("tuple type has no LLVM representation"))?
+            .into_struct_type();
+        let mut tuple = tuple_type.get_undef();
+        for (index, element) in elements.iter().enumerate() {
+            tuple = self
+                .builder
+                .build_insert_value(tuple, *element, index as u32, "tuple_insert")?
+                .into_struct_value();
+        }
+        Ok(tuple.into())
+    }
+
+    /// Copies a tuple onto the GC heap so it fits in a runtime collection slot, which
+    /// only holds 64-bit handles.
+    pub(crate) fn box_tuple(&mut self, value: BasicValueEnum<'ctx>) -> Result<IntValue<'ctx>> {
+        let tuple_type = value.get_type();
+        let ptr = self.build_heap_alloc(Self::conservative_size_of(tuple_type))?;
+        self.builder.build_store(ptr, value)?;
+        Ok(self
+            .builder
+            .build_ptr_to_int(ptr, self.context.i64_type(), "tuple_handle")?)
+    }
+
+    /// Loads a tuple back out of a handle made by `box_tuple`.
+    pub(crate) fn unbox_tuple(
+        &mut self,
+        handle: IntValue<'ctx>,
+        ty: OtterType,
+    ) -> Result<BasicValueEnum<'ctx>> {
+        let tuple_type = self
+            .basic_type(ty)?
+            .ok_or_else(|| anyhow!("tuple type has no LLVM representation"))?;
+        let ptr = self
+            .builder
+            .build_int_to_ptr(handle, self.raw_ptr_type(), "tuple_ptr")?;
+        Ok(self.builder.build_load(tuple_type, ptr, "tuple_value")?)
+    }
+
+    /// Boxes a struct into a `dyn Trait` handle: a GC-allocated `{ vtable, struct }` pair.
+    fn build_dyn_object(
+        &mut self,
+        value: BasicValueEnum<'ctx>,
+        struct_id: u32,
+        trait_name: &str,
+    ) -> Result<BasicValueEnum<'ctx>> {
+        let vtable = self.trait_vtable(trait_name, struct_id)?;
+        let object_type = self.dyn_object_type(struct_id);
+        let object_ptr = self.build_heap_alloc(Self::conservative_size_of(object_type.into()))?;
+        let vtable_slot =
+            self.builder
+                .build_struct_gep(object_type, object_ptr, 0, "dyn_vtable_gep")?;
+        self.builder.build_store(vtable_slot, vtable)?;
+        let data_slot =
+            self.builder
+                .build_struct_gep(object_type, object_ptr, 1, "dyn_data_gep")?;
+        self.builder.build_store(data_slot, value)?;
+        Ok(self
+            .builder
+            .build_ptr_to_int(object_ptr, self.context.i64_type(), "dyn_handle")?
+            .into())
+    }
+
+    fn dyn_object_type(&self, struct_id: u32) -> StructType<'ctx> {
+        self.context.struct_type(
+            &[
+                self.raw_ptr_type().into(),
+                self.struct_info(struct_id).ty.into(),
+            ],
+            false,
+        )
+    }
+
+    /// Returns the vtable of `trait_name` for a struct, emitting it on first use. Slots hold
+    /// one thunk per trait method, in declaration order.
+    fn trait_vtable(&mut self, trait_name: &str, struct_id: u32) -> Result<PointerValue<'ctx>> {
+        let struct_name = self.struct_info(struct_id).name.clone();
+        let global_name = format!("__otter_vtable_{}_{}", struct_name, trait_name);
+        if let Some(existing) = self.module.get_global(&global_name) {
+            return Ok(existing.as_pointer_value());
+        }
+
+        let methods = self
+            .traits
+            .get(trait_name)
+            .cloned()
+            .ok_or_else(|| anyhow!("unknown trait '{}'", trait_name))?;
+        let mut slots: Vec<BasicValueEnum> = Vec::with_capacity(methods.len());
+        for method in &methods {
+            let thunk = self.build_dyn_thunk(trait_name, struct_id, method)?;
+            slots.push(thunk.as_global_value().as_pointer_value().into());
+        }
+
+        let table = self.context.const_struct(&slots, false);
+        let global = self.module.add_global(table.get_type(), None, &global_name);
+        global.set_initializer(&table);
+        global.set_constant(true);
+        global.set_linkage(Linkage::Private);
+        Ok(global.as_pointer_value())
+    }
+
+    /// LLVM signature of a trait method called through a vtable: the trait object's data
+    /// pointer followed by the declared parameters.
+    fn dyn_method_fn_type(
+        &self,
+        trait_name: &str,
+        method: &Function,
+    ) -> Result<FunctionType<'ctx>> {
+        let mut params = Vec::with_capacity(method.params.len().saturating_sub(1));
+        for param in method.params.iter().skip(1) {
+            let ty = param.as_ref().ty.as_ref().map_or(OtterType::I64, |ty| {
+                self.otter_type_from_annotation(ty.as_ref())
+            });
+            params.push(ty);
+        }
+        let ret = method.ret_ty.as_ref().map_or(OtterType::Unit, |ty| {
+            self.otter_type_from_annotation(ty.as_ref())
+        });
+        let mentions_self = method
+            .params
+            .iter()
+            .skip(1)
+            .filter_map(|param| param.as_ref().ty.as_ref())
+            .chain(method.ret_ty.as_ref())
+            .any(|ty| matches!(ty.as_ref(), Type::Simple(name) if name == "Self"));
+        if mentions_self {
+            bail!(
+                "method '{}' of trait '{}' uses `Self` and cannot be called on a trait object",
+                method.name,
+                trait_name
+            );
+        }
+        self.closure_fn_type(&params, &ret)
+    }
+
+    /// Emits the vtable entry for one trait method: it unboxes the struct and forwards to
+    /// the statically compiled `{Struct}_{method}`.
+    fn build_dyn_thunk(
+        &mut self,
+        trait_name: &str,
+        struct_id: u32,
+        method: &Function,
+    ) -> Result<FunctionValue<'ctx>> {
+        let struct_name = self.struct_info(struct_id).name.clone();
+        let target_name = format!("{}_{}", struct_name, method.name);
+        let target = *self.declared_functions.get(&target_name).ok_or_else(|| {
+            anyhow!(
+                "'{}' does not implement '{}.{}'",
+                struct_name,
+                trait_name,
+                method.name
+            )
+        })?;
+
+        let fn_type = self.dyn_method_fn_type(trait_name, method)?;
+        let thunk = self.module.add_function(
+            &format!("__otter_dyn_{}_{}_{}", struct_name, trait_name, method.name),
+            fn_type,
+            None,
+        );
+        let entry = self.context.append_basic_block(thunk, "entry");
+        let prev_block = self.builder.get_insert_block();
+        self.builder.position_at_end(entry);
+
+        let object_type = self.dyn_object_type(struct_id);
+        let object_ptr = thunk
+            .get_nth_param(0)
+            .ok_or_else(|| anyhow!("trait object thunk missing receiver"))?
+            .into_pointer_value();
+        let data_slot =
+            self.builder
+                .build_struct_gep(object_type, object_ptr, 1, "dyn_data_gep")?;
+        // Methods update the boxed struct in place
+        let receiver: BasicMetadataValueEnum = if self.receiver_methods.contains(&target_name) {
+            data_slot.into()
+        } else {
+            self.builder
+                .build_load(self.struct_info(struct_id).ty, data_slot, "dyn_receiver")?
+                .into()
+        };
+
+        let mut forwarded: Vec<BasicMetadataValueEnum> = vec![receiver];
+        forwarded.extend(
+            thunk
+                .get_param_iter()
+                .skip(1)
+                .map(BasicMetadataValueEnum::from),
+        );
+        let call = self.builder.build_call(target, &forwarded, "dyn_forward")?;
+        match call.try_as_basic_value().left() {
+            Some(value) => self.builder.build_return(Some(&value))?,
+            None => self.builder.build_return(None)?,
+        };
+
+        if let Some(block) = prev_block {
+            self.builder.position_at_end(block);
+        }
+        Ok(thunk)
+    }
+
+    /// Calls a trait method on a `dyn Trait` value by loading its slot from the vtable.
+    fn build_dyn_method_call(
+        &mut self,
+        trait_name: &str,
+        object: EvaluatedValue<'ctx>,
+        method_name: &str,
+        args: &[Node<Expr>],
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<EvaluatedValue<'ctx>> {
+        let methods = self
+            .traits
+            .get(trait_name)
+            .ok_or_else(|| anyhow!("unknown trait '{}'", trait_name))?;
+        let (slot, method) = methods
+            .iter()
+            .enumerate()
+            .find(|(_, method)| method.name == method_name)
+            .map(|(slot, method)| (slot, method.clone()))
+            .ok_or_else(|| anyhow!("trait '{}' has no method '{}'", trait_name, method_name))?;
+        let slot_count = methods.len();
+
+        let param_types: Vec<OtterType> = method
+            .params
+            .iter()
+            .skip(1)
+            .map(|param| {
+                param.as_ref().ty.as_ref().map_or(OtterType::I64, |ty| {
+                    self.otter_type_from_annotation(ty.as_ref())
+                })
+            })
+            .collect();
+        if args.len() != param_types.len() {
+            bail!(
+                "'{}.{}' expects {} argument(s), got {}",
+                trait_name,
+                method_name,
+                param_types.len(),
+                args.len()
+            );
+        }
+        let ret = method.ret_ty.as_ref().map_or(OtterType::Unit, |ty| {
+            self.otter_type_from_annotation(ty.as_ref())
+        });
+
+        let handle = object
+            .value
+            .ok_or_else(|| anyhow!("missing trait object value"))?
+            .into_int_value();
+        let object_ptr =
+            self.builder
+                .build_int_to_ptr(handle, self.raw_ptr_type(), "dyn_object")?;
+        let vtable = self
+            .builder
+            .build_load(self.raw_ptr_type(), object_ptr, "dyn_vtable")?
+            .into_pointer_value();
+        let slot_types: Vec<BasicTypeEnum> = vec![self.raw_ptr_type().into(); slot_count];
+        let vtable_type = self.context.struct_type(&slot_types, false);
+        let slot_ptr =
+            self.builder
+                .build_struct_gep(vtable_type, vtable, slot as u32, "dyn_slot_gep")?;
+        let fn_ptr = self
+            .builder
+            .build_load(self.raw_ptr_type(), slot_ptr, method_name)?
+            .into_pointer_value();
+
+        let mut arg_values: Vec<BasicMetadataValueEnum> = vec![object_ptr.into()];
+        for (arg, param_ty) in args.iter().zip(&param_types) {
+            let arg_val = self.eval_expr(arg.as_ref(), ctx)?;
+            let value = arg_val
+                .value
+                .ok_or_else(|| anyhow!("Cannot pass unit value as argument"))?;
+            arg_values.push(
+                self.coerce_value(value, arg_val.ty, param_ty.clone())?
+                    .into(),
+            );
+        }
+
+        let fn_type = self.dyn_method_fn_type(trait_name, &method)?;
+        let call = self
+            .builder
+            .build_indirect_call(fn_type, fn_ptr, &arg_values, "dyn_call")?;
+        self.check_pending_exception(ctx)?;
+        match call.try_as_basic_value().left() {
+            Some(value) => Ok(EvaluatedValue::with_value(value, ret)),
+            None => Ok(EvaluatedValue {
+                ty: OtterType::Unit,
+                value: None,
+            }),
+        }
+    }
+
     fn collect_captured_names(
         &self,
         expr: &Expr,
@@ -221,9 +910,21 @@ impl<'ctx> Compiler<'ctx> {
             Expr::Member { object, .. } => {
                 self.collect_captured_names(object.as_ref().as_ref(), ctx, captures);
             }
-            Expr::Call { func, args } => {
+            Expr::Index { object, index } => {
+                self.collect_captured_names(object.as_ref().as_ref(), ctx, captures);
+                self.collect_captured_names(index.as_ref().as_ref(), ctx, captures);
+            }
+            Expr::Slice { object, start, end } => {
+                self.collect_captured_names(object.as_ref().as_ref(), ctx, captures);
+                for bound in [start, end].into_iter().flatten() {
+                    self.collect_captured_names(bound.as_ref().as_ref(), ctx, captures);
+                }
+            }
+            Expr::Call {
+                func, args, kwargs, ..
+            } => {
                 self.collect_captured_names(func.as_ref().as_ref(), ctx, captures);
-                for arg in args {
+                for arg in args.iter().chain(kwargs.iter().map(|(_, value)| value)) {
                     self.collect_captured_names(arg.as_ref(), ctx, captures);
                 }
             }
@@ -231,7 +932,7 @@ impl<'ctx> Compiler<'ctx> {
                 self.collect_captured_names(left.as_ref().as_ref(), ctx, captures);
                 self.collect_captured_names(right.as_ref().as_ref(), ctx, captures);
             }
-            Expr::Unary { expr, .. } => {
+            Expr::Unary { expr, .. } | Expr::Cast { expr, .. } => {
                 self.collect_captured_names(expr.as_ref().as_ref(), ctx, captures);
             }
             Expr::If {
@@ -258,7 +959,7 @@ impl<'ctx> Compiler<'ctx> {
                 self.collect_captured_names(start.as_ref().as_ref(), ctx, captures);
                 self.collect_captured_names(end.as_ref().as_ref(), ctx, captures);
             }
-            Expr::Array(elements) => {
+            Expr::Tuple(elements) | Expr::Array(elements) => {
                 for element in elements {
                     self.collect_captured_names(element.as_ref(), ctx, captures);
                 }
@@ -295,7 +996,7 @@ impl<'ctx> Compiler<'ctx> {
                     self.collect_captured_names(cond.as_ref().as_ref(), ctx, captures);
                 }
             }
-            Expr::FString { parts } => {
+            Expr::FString { parts, .. } => {
                 for part in parts {
                     if let FStringPart::Expr(expr) = part.as_ref() {
                         self.collect_captured_names(expr.as_ref(), ctx, captures);
@@ -307,9 +1008,12 @@ impl<'ctx> Compiler<'ctx> {
                     self.collect_captured_names(value.as_ref(), ctx, captures);
                 }
             }
-            Expr::Await(inner) | Expr::Spawn(inner) => {
+            Expr::Await(inner) | Expr::Spawn(inner) | Expr::Try(inner) | Expr::Spread(inner) => {
                 self.collect_captured_names(inner.as_ref().as_ref(), ctx, captures);
             }
+            Expr::Lambda { body, .. } | Expr::Loop { body, .. } => {
+                self.collect_captured_names_in_block(body.as_ref(), ctx, captures);
+            }
         }
     }
 
@@ -333,8 +1037,16 @@ impl<'ctx> Compiler<'ctx> {
         match stmt {
             Statement::Expr(expr)
             | Statement::Let { expr, .. }
-            | Statement::Assignment { expr, .. }
-            | Statement::Return(Some(expr)) => {
+            | Statement::Return(Some(expr))
+            | Statement::Raise(Some(expr))
+            | Statement::Defer(expr)
+            | Statement::Break {
+                value: Some(expr), ..
+            } => {
+                self.collect_captured_names(expr.as_ref(), ctx, captures);
+            }
+            Statement::Assignment { target, expr, .. } => {
+                self.collect_captured_names(target.as_ref(), ctx, captures);
                 self.collect_captured_names(expr.as_ref(), ctx, captures);
             }
             Statement::If {
@@ -357,22 +1069,47 @@ impl<'ctx> Compiler<'ctx> {
                 self.collect_captured_names(iterable.as_ref(), ctx, captures);
                 self.collect_captured_names_in_block(body.as_ref(), ctx, captures);
             }
-            Statement::While { cond, body } => {
+            Statement::While { cond, body, .. } => {
                 self.collect_captured_names(cond.as_ref(), ctx, captures);
                 self.collect_captured_names_in_block(body.as_ref(), ctx, captures);
             }
+            Statement::With { expr, body, .. } => {
+                self.collect_captured_names(expr.as_ref(), ctx, captures);
+                self.collect_captured_names_in_block(body.as_ref(), ctx, captures);
+            }
             Statement::Block(block) => {
                 self.collect_captured_names_in_block(block.as_ref(), ctx, captures);
             }
-            Statement::Return(None)
-            | Statement::Break
-            | Statement::Continue
-            | Statement::Pass
-            | Statement::Use { .. }
-            | Statement::PubUse { .. }
-            | Statement::Struct { .. }
-            | Statement::Enum { .. }
-            | Statement::TypeAlias { .. }
+            Statement::Try {
+                body,
+                handlers,
+                finally_block,
+            } => {
+                self.collect_captured_names_in_block(body.as_ref(), ctx, captures);
+                for handler in handlers {
+                    self.collect_captured_names_in_block(
+                        handler.as_ref().body.as_ref(),
+                        ctx,
+                        captures,
+                    );
+                }
+                if let Some(block) = finally_block {
+                    self.collect_captured_names_in_block(block.as_ref(), ctx, captures);
+                }
+            }
+            Statement::Return(None)
+            | Statement::Raise(None)
+            | Statement::Break { value: None, .. }
+            | Statement::Continue { .. }
+            | Statement::Pass
+            | Statement::Use { .. }
+            | Statement::PubUse { .. }
+            | Statement::Struct { .. }
+            | Statement::Enum { .. }
+            | Statement::Trait { .. }
+            | Statement::Impl { .. }
+            | Statement::TypeAlias { .. }
+            | Statement::Const { .. }
             | Statement::Function(_) => {}
         }
     }
@@ -399,7 +1136,7 @@ impl<'ctx> Compiler<'ctx> {
         function
     }
 
-    fn raw_ptr_type(&self) -> PointerType<'ctx> {
+    pub(crate) fn raw_ptr_type(&self) -> PointerType<'ctx> {
         #[expect(deprecated, reason = "TODO: Use Context::ptr_type instead")]
         {
             self.context.i8_type().ptr_type(AddressSpace::default())
@@ -438,6 +1175,12 @@ impl<'ctx> Compiler<'ctx> {
                             value: None,
                         })
                     }
+                } else if self.constants.contains_key(name) {
+                    let expr_id = expr as *const Expr as usize;
+                    let type_info = self.expr_types.get(&expr_id).cloned();
+                    self.eval_constant(name, type_info.as_ref())
+                } else if self.declared_functions.contains_key(name) {
+                    self.build_function_reference(name)
                 } else {
                     bail!("Variable {} not found", name);
                 }
@@ -446,7 +1189,19 @@ impl<'ctx> Compiler<'ctx> {
                 self.eval_binary_expr(left.as_ref().as_ref(), op, right.as_ref().as_ref(), ctx)
             }
             Expr::Unary { op, expr } => self.eval_unary_expr(op, expr.as_ref().as_ref(), ctx),
-            Expr::Call { func: _, args: _ } => self.eval_call_expr(expr, ctx),
+            Expr::Cast { expr, ty } => {
+                let value = self.eval_expr(expr.as_ref().as_ref(), ctx)?;
+                let target = self.otter_type_from_annotation(ty.as_ref());
+                let converted = self.coerce_type(
+                    value
+                        .value
+                        .ok_or_else(|| anyhow!("cannot convert a unit value"))?,
+                    value.ty,
+                    target.clone(),
+                )?;
+                Ok(EvaluatedValue::with_value(converted, target))
+            }
+            Expr::Call { .. } => self.eval_call_expr(expr, ctx),
             Expr::Member { object, field } => {
                 if let Some(value) =
                     self.try_build_enum_member(expr, object.as_ref().as_ref(), field, ctx)?
@@ -487,6 +1242,14 @@ impl<'ctx> Compiler<'ctx> {
                     }
                 }
             }
+            Expr::Struct { name, fields }
+                if self.struct_info_by_name(name).is_none()
+                    && ctx.get(name).is_none()
+                    && (self.function_param_names.contains_key(name)
+                        || self.generic_functions.contains_key(name)) =>
+            {
+                self.eval_keyword_call(name, fields, ctx)
+            }
             Expr::Struct { name, fields } => {
                 let (struct_id, _) = self
                     .struct_info_by_name(name)
@@ -512,7 +1275,7 @@ impl<'ctx> Compiler<'ctx> {
                         info.field_types[idx].clone()
                     };
                     let coerced =
-                        self.coerce_type(raw_value, field_value.ty.clone(), expected_ty)?;
+                        self.coerce_value(raw_value, field_value.ty.clone(), expected_ty)?;
                     aggregate = self
                         .builder
                         .build_insert_value(aggregate, coerced, idx as u32, field_name)
@@ -531,7 +1294,11 @@ impl<'ctx> Compiler<'ctx> {
                 else_branch: _,
             } => self.eval_if_expr(expr, ctx),
             Expr::Match { value: _, arms: _ } => self.eval_match_expr(expr, ctx),
-            Expr::FString { parts: _ } => self.eval_fstring_expr(expr, ctx),
+            Expr::Loop { label, body } => {
+                self.eval_loop_expr(expr, label.as_deref(), body.as_ref(), ctx)
+            }
+            Expr::FString { .. } => self.eval_fstring_expr(expr, ctx),
+            Expr::Tuple(elements) => self.eval_tuple_expr(elements, ctx),
             Expr::Array(elements) => {
                 let expr_id = expr as *const Expr as usize;
                 let expr_type = self.expr_types.get(&expr_id).cloned();
@@ -570,8 +1337,20 @@ impl<'ctx> Compiler<'ctx> {
                 condition.as_ref().map(|c| c.as_ref().as_ref()),
                 ctx,
             ),
+            Expr::Index { object, index } => {
+                self.eval_index_expr(expr, object.as_ref().as_ref(), index.as_ref().as_ref(), ctx)
+            }
+            Expr::Slice { object, start, end } => self.eval_slice_expr(
+                object.as_ref().as_ref(),
+                start.as_ref().map(|s| s.as_ref().as_ref()),
+                end.as_ref().map(|e| e.as_ref().as_ref()),
+                ctx,
+            ),
             Expr::Await(inner) => self.eval_await_expr(expr, inner.as_ref().as_ref(), ctx),
             Expr::Spawn(expr) => self.eval_spawn_expr(expr.as_ref().as_ref(), ctx),
+            Expr::Try(inner) => self.eval_try_expr(expr, inner.as_ref().as_ref(), ctx),
+            Expr::Lambda { params, body, .. } => self.eval_lambda_expr(expr, params, body, ctx),
+            Expr::Spread(_) => bail!("`*` spreading is only valid as a call argument"),
             _ => bail!("Expression type not implemented: {:?}", expr),
         }
     }
@@ -597,22 +1376,31 @@ impl<'ctx> Compiler<'ctx> {
         let mut incoming_values = Vec::new();
         let mut incoming_blocks = Vec::new();
 
-        let mut next_check_bb = self
-            .context
-            .append_basic_block(function, "match_arm_check_0");
-        self.builder.build_unconditional_branch(next_check_bb)?;
-
         let matched_type_info = self.expr_type(value.as_ref().as_ref()).cloned();
 
-        for (i, arm) in arms.iter().enumerate() {
-            self.builder.position_at_end(next_check_bb);
-
-            next_check_bb = if i < arms.len() - 1 {
+        // Arms are entered through dispatch blocks that switch on the scrutinee's tag or
+        // value, so an arm whose pattern cannot match is skipped without being tested
+        let check_bbs: Vec<_> = (0..arms.len())
+            .map(|i| {
                 self.context
-                    .append_basic_block(function, &format!("match_arm_check_{}", i + 1))
-            } else {
-                self.context.append_basic_block(function, "match_no_match")
-            };
+                    .append_basic_block(function, &format!("match_arm_check_{}", i))
+            })
+            .collect();
+        let no_match_bb = self.context.append_basic_block(function, "match_no_match");
+        let switch = self.build_match_switch(arms, &matched_val)?;
+        let first_bb =
+            self.match_dispatch_block(switch.as_ref(), 0, &check_bbs, no_match_bb, function)?;
+        self.builder.build_unconditional_branch(first_bb)?;
+
+        for (i, arm) in arms.iter().enumerate() {
+            let next_check_bb = self.match_dispatch_block(
+                switch.as_ref(),
+                i + 1,
+                &check_bbs,
+                no_match_bb,
+                function,
+            )?;
+            self.builder.position_at_end(check_bbs[i]);
 
             let body_bb = self
                 .context
@@ -665,7 +1453,7 @@ impl<'ctx> Compiler<'ctx> {
             }
         }
 
-        self.builder.position_at_end(next_check_bb);
+        self.builder.position_at_end(no_match_bb);
         self.builder.build_unreachable()?;
 
         self.builder.position_at_end(merge_bb);
@@ -729,17 +1517,273 @@ impl<'ctx> Compiler<'ctx> {
         }
     }
 
-    fn compile_pattern_match(
+    /// Set up a switch over the arms of a `match` that test enum variants or integer
+    /// literals. Without one, the arms are tried one after another.
+    fn build_match_switch(
+        &mut self,
+        arms: &[Node<MatchArm>],
+        matched_val: &EvaluatedValue<'ctx>,
+    ) -> Result<Option<MatchSwitch<'ctx>>> {
+        let enum_name = arms
+            .iter()
+            .find_map(|arm| Self::switched_enum(arm.as_ref().pattern.as_ref()));
+        let key = match enum_name {
+            Some(_) => {
+                let handle = matched_val
+                    .value
+                    .ok_or_else(|| anyhow!("Enum value is void"))?;
+                self.call_ffi_returning_value("runtime.enum.get_tag", vec![handle], "tag")?
+                    .into_int_value()
+            }
+            None if matched_val.ty.is_integer() => matched_val
+                .value
+                .ok_or_else(|| anyhow!("Integer value is void"))?
+                .into_int_value(),
+            None => return Ok(None),
+        };
+
+        let layout = enum_name.and_then(|name| self.enum_layout(name));
+        let width = key.get_type().get_bit_width();
+        let arm_keys: Vec<_> = arms
+            .iter()
+            .map(|arm| Self::switch_keys(arm.as_ref().pattern.as_ref(), layout, width))
+            .collect();
+        if arm_keys.iter().all(Option::is_none) {
+            return Ok(None);
+        }
+        Ok(Some(MatchSwitch { key, arm_keys }))
+    }
+
+    /// The enum whose variants a pattern tests at the top level
+    fn switched_enum(pattern: &Pattern) -> Option<&str> {
+        match pattern {
+            Pattern::EnumVariant { enum_name, .. } => Some(enum_name),
+            Pattern::Binding { pattern, .. } => Self::switched_enum(pattern.as_ref().as_ref()),
+            Pattern::Or(alternatives) => alternatives
+                .iter()
+                .find_map(|alternative| Self::switched_enum(alternative.as_ref())),
+            _ => None,
+        }
+    }
+
+    /// Switch keys a pattern can match: enum tags when `layout` is given, otherwise integer
+    /// values truncated to `width` bits. `None` when the pattern may match any key.
+    fn switch_keys(pattern: &Pattern, layout: Option<&EnumLayout>, width: u32) -> Option<Vec<u64>> {
+        match pattern {
+            Pattern::EnumVariant { variant, .. } => {
+                layout?.tag_of(variant).map(|tag| vec![u64::from(tag)])
+            }
+            Pattern::Literal(literal) if layout.is_none() => match literal.as_ref() {
+                Literal::Number(number) if !number.is_float_literal => {
+                    let mask = u64::MAX >> (64 - width);
+                    Some(vec![number.int_value() as u64 & mask])
+                }
+                _ => None,
+            },
+            Pattern::Binding { pattern, .. } => {
+                Self::switch_keys(pattern.as_ref().as_ref(), layout, width)
+            }
+            Pattern::Or(alternatives) => {
+                let mut keys = Vec::new();
+                for alternative in alternatives {
+                    keys.extend(Self::switch_keys(alternative.as_ref(), layout, width)?);
+                }
+                Some(keys)
+            }
+            _ => None,
+        }
+    }
+
+    /// Block that continues a `match` at the first arm from `start` on that can match the
+    /// switch key, or at `no_match` when none can
+    fn match_dispatch_block(
+        &mut self,
+        switch: Option<&MatchSwitch<'ctx>>,
+        start: usize,
+        check_bbs: &[inkwell::basic_block::BasicBlock<'ctx>],
+        no_match: inkwell::basic_block::BasicBlock<'ctx>,
+        function: FunctionValue<'ctx>,
+    ) -> Result<inkwell::basic_block::BasicBlock<'ctx>> {
+        let Some(switch) = switch else {
+            return Ok(check_bbs.get(start).copied().unwrap_or(no_match));
+        };
+        let target = |key: Option<u64>| {
+            (start..check_bbs.len())
+                .find(|&i| match (&switch.arm_keys[i], key) {
+                    (None, _) => true,
+                    (Some(keys), Some(key)) => keys.contains(&key),
+                    (Some(_), None) => false,
+                })
+                .map_or(no_match, |i| check_bbs[i])
+        };
+
+        let default = target(None);
+        let mut cases: Vec<(u64, inkwell::basic_block::BasicBlock<'ctx>)> = Vec::new();
+        for key in switch.arm_keys[start..].iter().flatten().flatten() {
+            if !cases.iter().any(|(seen, _)| seen == key) {
+                cases.push((*key, target(Some(*key))));
+            }
+        }
+        cases.retain(|(_, block)| *block != default);
+        if cases.is_empty() {
+            return Ok(default);
+        }
+
+        let current = self.builder.get_insert_block();
+        let block = self
+            .context
+            .append_basic_block(function, &format!("match_dispatch_{}", start));
+        self.builder.position_at_end(block);
+        let key_type = switch.key.get_type();
+        let cases: Vec<_> = cases
+            .into_iter()
+            .map(|(key, block)| (key_type.const_int(key, false), block))
+            .collect();
+        self.builder.build_switch(switch.key, default, &cases)?;
+        if let Some(current) = current {
+            self.builder.position_at_end(current);
+        }
+        Ok(block)
+    }
+
+    /// Bind `name` to the value a pattern is matched against
+    fn bind_matched_value(
+        &mut self,
+        name: &str,
+        matched_val: &EvaluatedValue<'ctx>,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<()> {
+        let function = self
+            .builder
+            .get_insert_block()
+            .unwrap()
+            .get_parent()
+            .unwrap();
+        let matched_ty = matched_val.ty.clone();
+        let alloca = self.create_entry_block_alloca(function, name, matched_ty.clone())?;
+
+        if let Some(v) = matched_val.value {
+            self.builder.build_store(alloca, v)?;
+        }
+
+        ctx.insert(
+            name.to_string(),
+            Variable {
+                ptr: alloca,
+                ty: matched_ty,
+            },
+        );
+        Ok(())
+    }
+
+    /// `value` lies between `low` and `high`, compared at the type of `value`. Strings must
+    /// also be a single character.
+    fn build_range_check(
+        &mut self,
+        value: &EvaluatedValue<'ctx>,
+        low: &EvaluatedValue<'ctx>,
+        high: &EvaluatedValue<'ctx>,
+        inclusive: bool,
+    ) -> Result<IntValue<'ctx>> {
+        let ty = value.ty.clone();
+        let raw = |evaluated: &EvaluatedValue<'ctx>| {
+            evaluated
+                .value
+                .ok_or_else(|| anyhow!("range pattern operand is void"))
+        };
+
+        let (above_low, below_high) = if let Some((_, signed)) = ty.integer_layout() {
+            let (ge, le, lt) = if signed {
+                (IntPredicate::SGE, IntPredicate::SLE, IntPredicate::SLT)
+            } else {
+                (IntPredicate::UGE, IntPredicate::ULE, IntPredicate::ULT)
+            };
+            let v = raw(value)?.into_int_value();
+            let l = self
+                .coerce_type(raw(low)?, low.ty.clone(), ty.clone())?
+                .into_int_value();
+            let h = self
+                .coerce_type(raw(high)?, high.ty.clone(), ty.clone())?
+                .into_int_value();
+            (
+                self.builder.build_int_compare(ge, v, l, "range_low")?,
+                self.builder.build_int_compare(
+                    if inclusive { le } else { lt },
+                    v,
+                    h,
+                    "range_high",
+                )?,
+            )
+        } else if ty.is_float() {
+            use inkwell::FloatPredicate;
+            let v = raw(value)?.into_float_value();
+            let l = self
+                .coerce_type(raw(low)?, low.ty.clone(), ty.clone())?
+                .into_float_value();
+            let h = self
+                .coerce_type(raw(high)?, high.ty.clone(), ty.clone())?
+                .into_float_value();
+            let upper = if inclusive {
+                FloatPredicate::OLE
+            } else {
+                FloatPredicate::OLT
+            };
+            (
+                self.builder
+                    .build_float_compare(FloatPredicate::OGE, v, l, "range_low")?,
+                self.builder
+                    .build_float_compare(upper, v, h, "range_high")?,
+            )
+        } else if ty == OtterType::Str {
+            let v = self.ensure_string_value(value.clone())?;
+            let l = self.ensure_string_value(low.clone())?;
+            let h = self.ensure_string_value(high.clone())?;
+            let len = self
+                .call_ffi_returning_value("str.char_len", vec![v], "str_len")?
+                .into_int_value();
+            let single = self.builder.build_int_compare(
+                IntPredicate::EQ,
+                len,
+                len.get_type().const_int(1, false),
+                "single_char",
+            )?;
+            let from_low = self
+                .call_ffi_returning_value("std.strings.compare", vec![v, l], "strcmp")?
+                .into_int_value();
+            let from_high = self
+                .call_ffi_returning_value("std.strings.compare", vec![v, h], "strcmp")?
+                .into_int_value();
+            let zero = from_low.get_type().const_zero();
+            let upper = if inclusive {
+                IntPredicate::SLE
+            } else {
+                IntPredicate::SLT
+            };
+            let above_low =
+                self.builder
+                    .build_int_compare(IntPredicate::SGE, from_low, zero, "range_low")?;
+            (
+                self.builder
+                    .build_and(single, above_low, "range_low_char")?,
+                self.builder
+                    .build_int_compare(upper, from_high, zero, "range_high")?,
+            )
+        } else {
+            bail!("Range patterns are not supported for type {:?}", ty);
+        };
+
+        Ok(self.builder.build_and(above_low, below_high, "in_range")?)
+    }
+
+    pub(crate) fn compile_pattern_match(
         &mut self,
-        pattern: &Node<otterc_ast::nodes::Pattern>,
+        pattern: &Node<Pattern>,
         matched_val: &EvaluatedValue<'ctx>,
         matched_type: Option<TypeInfo>,
         success_bb: inkwell::basic_block::BasicBlock<'ctx>,
         fail_bb: inkwell::basic_block::BasicBlock<'ctx>,
         ctx: &mut FunctionContext<'ctx>,
     ) -> Result<()> {
-        use otterc_ast::nodes::Pattern;
-
         match pattern.as_ref() {
             Pattern::Wildcard => {
                 self.builder.build_unconditional_branch(success_bb)?;
@@ -754,29 +1798,158 @@ impl<'ctx> Compiler<'ctx> {
                     .build_conditional_branch(is_equal, success_bb, fail_bb)?;
                 Ok(())
             }
+            Pattern::Identifier(name) if self.constants.contains_key(name) => {
+                let const_val = self.eval_constant(name, matched_type.as_ref())?;
+                let is_equal = self.build_equality_check(matched_val, &const_val)?;
+                self.builder
+                    .build_conditional_branch(is_equal, success_bb, fail_bb)?;
+                Ok(())
+            }
             Pattern::Identifier(name) => {
+                self.bind_matched_value(name, matched_val, ctx)?;
+                self.builder.build_unconditional_branch(success_bb)?;
+                Ok(())
+            }
+            Pattern::Binding {
+                name,
+                pattern: inner,
+            } => {
+                self.bind_matched_value(name, matched_val, ctx)?;
+                self.compile_pattern_match(
+                    inner,
+                    matched_val,
+                    matched_type,
+                    success_bb,
+                    fail_bb,
+                    ctx,
+                )
+            }
+            Pattern::Range {
+                start,
+                end,
+                inclusive,
+            } => {
+                let low = self.eval_literal(start.as_ref(), None)?;
+                let high = self.eval_literal(end.as_ref(), None)?;
+                let in_range = self.build_range_check(matched_val, &low, &high, *inclusive)?;
+                self.builder
+                    .build_conditional_branch(in_range, success_bb, fail_bb)?;
+                Ok(())
+            }
+            Pattern::StringPrefix { prefix, rest } => {
+                let Literal::String(text) = prefix.as_ref() else {
+                    bail!("prefix patterns start with a string literal");
+                };
+                let value = self.ensure_string_value(matched_val.clone())?;
+                let prefix_val = self.eval_literal(prefix.as_ref(), None)?;
+                let prefix_ptr = self.ensure_string_value(prefix_val)?;
+                let has_prefix = self
+                    .call_ffi_returning_value(
+                        "string.starts_with",
+                        vec![value, prefix_ptr],
+                        "has_prefix",
+                    )?
+                    .into_int_value();
+                let Some(rest_name) = rest else {
+                    self.builder
+                        .build_conditional_branch(has_prefix, success_bb, fail_bb)?;
+                    return Ok(());
+                };
+
+                // The rest starts after the prefix's characters, as `string.slice` counts them
+                let function = self
+                    .builder
+                    .get_insert_block()
+                    .unwrap()
+                    .get_parent()
+                    .unwrap();
+                let rest_bb = self.context.append_basic_block(function, "prefix_rest");
+                self.builder
+                    .build_conditional_branch(has_prefix, rest_bb, fail_bb)?;
+                self.builder.position_at_end(rest_bb);
+                let i64_type = self.context.i64_type();
+                let start = i64_type.const_int(text.value.chars().count() as u64, false);
+                let end = i64_type.const_int(i64::MAX as u64, false);
+                let rest_val = self.call_ffi_returning_value(
+                    "string.slice",
+                    vec![value, start.into(), end.into()],
+                    "rest",
+                )?;
+                self.bind_matched_value(
+                    rest_name,
+                    &EvaluatedValue::with_value(rest_val, OtterType::Str),
+                    ctx,
+                )?;
+                self.builder.build_unconditional_branch(success_bb)?;
+                Ok(())
+            }
+            Pattern::Or(alternatives) => {
                 let function = self
                     .builder
                     .get_insert_block()
                     .unwrap()
                     .get_parent()
                     .unwrap();
-                let matched_ty = matched_val.ty.clone();
-                let alloca = self.create_entry_block_alloca(function, name, matched_ty.clone())?;
+                let names: Vec<String> = pattern
+                    .as_ref()
+                    .bindings()
+                    .into_iter()
+                    .filter(|name| !self.constants.contains_key(*name))
+                    .map(str::to_string)
+                    .collect();
+
+                // The arm body sees the variables bound by the first alternative; the others
+                // copy their values into them before joining it
+                let mut shared: Vec<(String, Variable<'ctx>)> = Vec::new();
+                for (index, alternative) in alternatives.iter().enumerate() {
+                    let is_last = index + 1 == alternatives.len();
+                    let next_bb = if is_last {
+                        fail_bb
+                    } else {
+                        self.context
+                            .append_basic_block(function, &format!("or_alternative_{}", index + 1))
+                    };
+                    let matched_bb = self
+                        .context
+                        .append_basic_block(function, &format!("or_matched_{}", index));
+                    self.compile_pattern_match(
+                        alternative,
+                        matched_val,
+                        matched_type.clone(),
+                        matched_bb,
+                        next_bb,
+                        ctx,
+                    )?;
 
-                if let Some(v) = matched_val.value {
-                    self.builder.build_store(alloca, v)?;
-                }
+                    self.builder.position_at_end(matched_bb);
+                    if index == 0 {
+                        shared = names
+                            .iter()
+                            .filter_map(|name| ctx.get(name).map(|var| (name.clone(), var.clone())))
+                            .collect();
+                    } else {
+                        for (name, target) in &shared {
+                            let Some(source) = ctx.get(name).cloned() else {
+                                continue;
+                            };
+                            let Some(basic_ty) = self.basic_type(source.ty.clone())? else {
+                                continue;
+                            };
+                            let value = self.builder.build_load(basic_ty, source.ptr, name)?;
+                            let value = self.coerce_type(value, source.ty, target.ty.clone())?;
+                            self.builder.build_store(target.ptr, value)?;
+                        }
+                    }
+                    self.builder.build_unconditional_branch(success_bb)?;
 
-                ctx.insert(
-                    name.clone(),
-                    crate::llvm::compiler::types::Variable {
-                        ptr: alloca,
-                        ty: matched_ty,
-                    },
-                );
+                    if !is_last {
+                        self.builder.position_at_end(next_bb);
+                    }
+                }
 
-                self.builder.build_unconditional_branch(success_bb)?;
+                for (name, variable) in shared {
+                    ctx.insert(name, variable);
+                }
                 Ok(())
             }
             Pattern::EnumVariant {
@@ -850,35 +2023,9 @@ impl<'ctx> Compiler<'ctx> {
                             })?
                             .clone();
 
-                        // Use type-specific getter based on field type
-                        let get_field_fn_name = match enum_field_kind(&field_type) {
-                            EnumFieldKind::Int => "runtime.enum.get_i64",
-                            EnumFieldKind::Float => "runtime.enum.get_f64",
-                            EnumFieldKind::Bool => "runtime.enum.get_bool",
-                            EnumFieldKind::Ptr => "runtime.enum.get_ptr",
-                        };
-                        let get_field_fn = self.get_or_declare_ffi_function(get_field_fn_name)?;
-                        let field_idx_val =
-                            self.context.i64_type().const_int(field_idx as u64, false);
-                        let field_val = self
-                            .builder
-                            .build_call(
-                                get_field_fn,
-                                &[handle.into(), field_idx_val.into()],
-                                "field",
-                            )?
-                            .try_as_basic_value()
-                            .left()
-                            .unwrap();
-
-                        // Convert to appropriate OtterType
-                        let field_otter_type = match enum_field_kind(&field_type) {
-                            EnumFieldKind::Int => OtterType::I64,
-                            EnumFieldKind::Float => OtterType::F64,
-                            EnumFieldKind::Bool => OtterType::Bool,
-                            EnumFieldKind::Ptr => OtterType::Opaque,
-                        };
-                        let field_eval = EvaluatedValue::with_value(field_val, field_otter_type);
+                        let field_eval =
+                            self.load_enum_field(handle, field_idx as u32, &field_type)?;
+                        let field_eval = self.retype_enum_payload(field_eval, &field_type)?;
 
                         let next_field_bb = if field_idx < fields.len() - 1 {
                             self.context.append_basic_block(
@@ -910,14 +2057,77 @@ impl<'ctx> Compiler<'ctx> {
 
                 Ok(())
             }
-            Pattern::Struct {
-                name: _struct_name,
-                fields,
-            } => {
+            Pattern::Struct { name, fields } => {
                 if fields.is_empty() {
                     self.builder.build_unconditional_branch(success_bb)?;
-                } else {
-                    bail!("Struct pattern matching with fields not yet fully supported");
+                    return Ok(());
+                }
+                let OtterType::Struct(struct_id) = matched_val.ty.clone() else {
+                    bail!(
+                        "Cannot match struct pattern '{}' against {:?}",
+                        name,
+                        matched_val.ty
+                    );
+                };
+                let struct_value = matched_val
+                    .value
+                    .ok_or_else(|| anyhow!("Struct value is void"))?
+                    .into_struct_value();
+                let field_infos = match &matched_type {
+                    Some(TypeInfo::Struct { fields, .. }) => Some(fields.clone()),
+                    _ => None,
+                };
+
+                // Fields are tested in the order the pattern lists them
+                for (idx, (field, field_pattern)) in fields.iter().enumerate() {
+                    let (field_index, field_ty) = {
+                        let info = self.struct_info(struct_id);
+                        let field_index =
+                            info.field_indices.get(field).copied().ok_or_else(|| {
+                                anyhow!("struct '{}' has no field '{}'", info.name, field)
+                            })?;
+                        (field_index, info.field_types[field_index].clone())
+                    };
+                    let field_val = self.builder.build_extract_value(
+                        struct_value,
+                        field_index as u32,
+                        field,
+                    )?;
+                    let field_eval = EvaluatedValue::with_value(field_val, field_ty);
+
+                    let next_bb = if idx < fields.len() - 1 {
+                        self.context.append_basic_block(
+                            self.builder
+                                .get_insert_block()
+                                .unwrap()
+                                .get_parent()
+                                .unwrap(),
+                            &format!("struct_field_check_{}", idx + 1),
+                        )
+                    } else {
+                        success_bb
+                    };
+
+                    match field_pattern {
+                        Some(field_pattern) => self.compile_pattern_match(
+                            field_pattern,
+                            &field_eval,
+                            field_infos
+                                .as_ref()
+                                .and_then(|fields| fields.get(field).cloned()),
+                            next_bb,
+                            fail_bb,
+                            ctx,
+                        )?,
+                        None => {
+                            self.bind_matched_value(field, &field_eval, ctx)?;
+                            self.builder.build_unconditional_branch(next_bb)?;
+                        }
+                    }
+
+                    if idx < fields.len() - 1 {
+                        self.builder.position_at_end(next_bb);
+                    }
                 }
                 Ok(())
             }
@@ -1037,6 +2247,58 @@ impl<'ctx> Compiler<'ctx> {
                     self.builder.build_unconditional_branch(success_bb)?;
                 }
 
+                Ok(())
+            }
+            Pattern::Tuple(patterns) => {
+                let OtterType::Tuple(element_types) = matched_val.ty.clone() else {
+                    bail!("Cannot match a tuple pattern against {:?}", matched_val.ty);
+                };
+                let tuple_value = matched_val
+                    .value
+                    .ok_or_else(|| anyhow!("Tuple value is void"))?
+                    .into_struct_value();
+                let element_infos = match matched_type {
+                    Some(TypeInfo::Tuple(elements)) => elements,
+                    _ => Vec::new(),
+                };
+
+                // Elements are tested left to right; the first mismatch jumps to fail_bb
+                for (idx, (elem_pattern, elem_ty)) in patterns.iter().zip(element_types).enumerate()
+                {
+                    let elem_val = self.builder.build_extract_value(
+                        tuple_value,
+                        idx as u32,
+                        &format!("tuple_elem_{}", idx),
+                    )?;
+                    let elem_eval = EvaluatedValue::with_value(elem_val, elem_ty);
+
+                    let next_bb = if idx < patterns.len() - 1 {
+                        self.context.append_basic_block(
+                            self.builder
+                                .get_insert_block()
+                                .unwrap()
+                                .get_parent()
+                                .unwrap(),
+                            &format!("tuple_elem_check_{}", idx + 1),
+                        )
+                    } else {
+                        success_bb
+                    };
+
+                    self.compile_pattern_match(
+                        elem_pattern,
+                        &elem_eval,
+                        element_infos.get(idx).cloned(),
+                        next_bb,
+                        fail_bb,
+                        ctx,
+                    )?;
+
+                    if idx < patterns.len() - 1 {
+                        self.builder.position_at_end(next_bb);
+                    }
+                }
+
                 Ok(())
             }
         }
@@ -1066,16 +2328,28 @@ impl<'ctx> Compiler<'ctx> {
     ) -> Result<IntValue<'ctx>> {
         let lhs_ty = lhs.ty.clone();
         match lhs_ty {
-            OtterType::I64 | OtterType::Bool => {
+            // A literal pattern is compared at the type of the value it matches
+            ref ty if ty.is_integer() => {
+                let l = lhs.value.unwrap().into_int_value();
+                let r = self
+                    .coerce_type(rhs.value.unwrap(), rhs.ty.clone(), lhs_ty.clone())?
+                    .into_int_value();
+                Ok(self
+                    .builder
+                    .build_int_compare(IntPredicate::EQ, l, r, "eq")?)
+            }
+            OtterType::Bool => {
                 let l = lhs.value.unwrap().into_int_value();
                 let r = rhs.value.unwrap().into_int_value();
                 Ok(self
                     .builder
                     .build_int_compare(IntPredicate::EQ, l, r, "eq")?)
             }
-            OtterType::F64 => {
+            OtterType::F32 | OtterType::F64 => {
                 let l = lhs.value.unwrap().into_float_value();
-                let r = rhs.value.unwrap().into_float_value();
+                let r = self
+                    .coerce_type(rhs.value.unwrap(), rhs.ty.clone(), lhs_ty.clone())?
+                    .into_float_value();
                 Ok(self
                     .builder
                     .build_float_compare(inkwell::FloatPredicate::OEQ, l, r, "eq")?)
@@ -1139,18 +2413,20 @@ impl<'ctx> Compiler<'ctx> {
         expr: &Expr,
         ctx: &mut FunctionContext<'ctx>,
     ) -> Result<EvaluatedValue<'ctx>> {
-        let Expr::FString { parts } = expr else {
+        let Expr::FString { parts, .. } = expr else {
             bail!("Expected FString expression")
         };
 
-        let mut result = self.eval_literal(&Literal::String(String::new()), None)?;
+        let mut result = self.eval_literal(&Literal::String(StringLiteral::new("")), None)?;
 
         for part in parts {
             let part_val = match part.as_ref() {
                 otterc_ast::nodes::FStringPart::Text(s) => {
-                    self.eval_literal(&Literal::String(s.clone()), None)?
+                    self.eval_literal(&Literal::String(StringLiteral::new(s.clone())), None)?
+                }
+                otterc_ast::nodes::FStringPart::Expr(e) => {
+                    self.build_display_value(e.as_ref(), ctx)?
                 }
-                otterc_ast::nodes::FStringPart::Expr(e) => self.eval_expr(e.as_ref(), ctx)?,
             };
 
             result = self.build_string_concat(result, part_val)?;
@@ -1159,71 +2435,95 @@ impl<'ctx> Compiler<'ctx> {
         Ok(result)
     }
 
-    fn eval_literal(
+    /// Emit the value of a module constant, widened to the type the checker expects
+    fn eval_constant(
         &mut self,
-        lit: &Literal,
+        name: &str,
         type_info: Option<&TypeInfo>,
     ) -> Result<EvaluatedValue<'ctx>> {
-        match lit {
+        let value = self
+            .constants
+            .get(name)
+            .cloned()
+            .ok_or_else(|| anyhow!("Constant {} not found", name))?;
+        match (value, type_info) {
+            (value @ (ConstValue::Int(_) | ConstValue::Float(_)), Some(ty)) if ty.is_numeric() => {
+                self.eval_literal(&value.to_literal(), Some(ty))
+            }
+            (value, _) => self.eval_literal(&value.to_literal(), None),
+        }
+    }
+
+    fn eval_literal(
+        &mut self,
+        lit: &Literal,
+        type_info: Option<&TypeInfo>,
+    ) -> Result<EvaluatedValue<'ctx>> {
+        match lit {
             Literal::Number(n) => {
                 // Use type checker's type information if available
-                let inferred_type = if let Some(type_info) = type_info {
-                    match type_info {
-                        TypeInfo::I64 => OtterType::I64,
-                        TypeInfo::I32 => OtterType::I32,
-                        TypeInfo::F64 => OtterType::F64,
-                        _ => {
-                            // Fallback: use the literal's is_float_literal flag or check value
-                            let is_float = n.is_float_literal || n.value.fract() != 0.0;
-                            if is_float {
-                                OtterType::F64
-                            } else {
-                                OtterType::I64
-                            }
+                let inferred_type = match type_info.and_then(|ty| self.typeinfo_to_otter_type(ty)) {
+                    Some(ty) if ty.is_integer() || ty.is_float() => ty,
+                    _ => {
+                        // No numeric type from the checker, use the literal's is_float_literal
+                        // flag or check value
+                        let is_float = n.is_float_literal || n.value.fract() != 0.0;
+                        if is_float {
+                            OtterType::F64
+                        } else {
+                            OtterType::I64
                         }
                     }
-                } else {
-                    // No type info from checker, use the literal's is_float_literal flag or check value
-                    let is_float = n.is_float_literal || n.value.fract() != 0.0;
-                    if is_float {
-                        OtterType::F64
-                    } else {
-                        OtterType::I64
-                    }
                 };
 
                 match inferred_type {
-                    OtterType::I64 => {
-                        // Convert f64 to i64, then to u64 for const_int
-                        let i64_val = n.value as i64;
-                        let val = self.context.i64_type().const_int(i64_val as u64, false);
-                        Ok(EvaluatedValue::with_value(val.into(), OtterType::I64))
-                    }
-                    OtterType::I32 => {
-                        let val = self
-                            .context
-                            .i32_type()
-                            .const_int((n.value as i32) as u64, false);
-                        Ok(EvaluatedValue::with_value(val.into(), OtterType::I32))
+                    ty if ty.is_integer() => {
+                        // Reinterpret the exact value as bits for const_int; the checker has
+                        // made sure it fits the type
+                        let int_type = self.basic_type(ty.clone())?.unwrap().into_int_type();
+                        let val = int_type.const_int(n.int_value() as u64, false);
+                        Ok(EvaluatedValue::with_value(val.into(), ty))
                     }
-                    OtterType::F64 => {
-                        let val = self.context.f64_type().const_float(n.value);
-                        Ok(EvaluatedValue::with_value(val.into(), OtterType::F64))
+                    OtterType::F32 => {
+                        let val = self.context.f32_type().const_float(n.value);
+                        Ok(EvaluatedValue::with_value(val.into(), OtterType::F32))
                     }
                     _ => {
-                        // Fallback to F64 for safety
                         let val = self.context.f64_type().const_float(n.value);
                         Ok(EvaluatedValue::with_value(val.into(), OtterType::F64))
                     }
                 }
             }
             Literal::String(s) => {
-                let val = self.builder.build_global_string_ptr(s, "str_lit")?;
+                let val = self.builder.build_global_string_ptr(&s.value, "str_lit")?;
                 Ok(EvaluatedValue::with_value(
                     val.as_pointer_value().into(),
                     OtterType::Str,
                 ))
             }
+            Literal::Bytes(bytes) => {
+                let create_fn = self.get_or_declare_ffi_function("list.new")?;
+                let handle = self
+                    .builder
+                    .build_call(create_fn, &[], "bytes_handle")?
+                    .try_as_basic_value()
+                    .left()
+                    .ok_or_else(|| anyhow!("list creation returned void"))?
+                    .into_int_value();
+                for (idx, byte) in bytes.iter().enumerate() {
+                    let value = self.context.i8_type().const_int(u64::from(*byte), false);
+                    self.append_value_to_list(
+                        handle,
+                        value.into(),
+                        OtterType::U8,
+                        &format!("byte_{}", idx),
+                    )?;
+                }
+                Ok(EvaluatedValue::with_value(
+                    handle.into(),
+                    OtterType::list_of(OtterType::U8),
+                ))
+            }
             Literal::Bool(b) => {
                 let val = self.context.bool_type().const_int(*b as u64, false);
                 Ok(EvaluatedValue::with_value(val.into(), OtterType::Bool))
@@ -1243,11 +2543,38 @@ impl<'ctx> Compiler<'ctx> {
         right: &Expr,
         ctx: &mut FunctionContext<'ctx>,
     ) -> Result<EvaluatedValue<'ctx>> {
+        // Enum values are opaque handles, so their operator methods are found by the
+        // checker's type of the receiver
+        let enum_method = op.method_name().and_then(|method| {
+            let receiver = if op.swaps_operands() { right } else { left };
+            self.resolve_enum_method_name(receiver, method)
+        });
         let lhs = self.eval_expr(left, ctx)?;
         let rhs = self.eval_expr(right, ctx)?;
+        self.build_binary_values(op, lhs, rhs, enum_method, ctx)
+    }
+
+    /// Applies `op` to operands that are already evaluated, so a compound assignment can
+    /// reuse the value it read from its place. `enum_method` is the operator method of an
+    /// enum receiver, see `eval_binary_expr`.
+    pub(crate) fn build_binary_values(
+        &mut self,
+        op: &BinaryOp,
+        lhs: EvaluatedValue<'ctx>,
+        rhs: EvaluatedValue<'ctx>,
+        enum_method: Option<String>,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<EvaluatedValue<'ctx>> {
         let lhs_ty = lhs.ty.clone();
         let rhs_ty = rhs.ty.clone();
 
+        if let Some(method) = enum_method {
+            return self.build_enum_binary(*op, &method, lhs, rhs, ctx);
+        }
+        if matches!(lhs_ty, OtterType::Struct(_)) || matches!(rhs_ty, OtterType::Struct(_)) {
+            return self.build_struct_binary(*op, lhs, rhs, ctx);
+        }
+
         if matches!(op, BinaryOp::Add) && (lhs_ty == OtterType::Str || rhs_ty == OtterType::Str) {
             return self.build_string_concat(lhs, rhs);
         }
@@ -1320,39 +2647,22 @@ impl<'ctx> Compiler<'ctx> {
             };
         }
 
-        // Coerce types if needed - promote to F64 if either operand is F64
-        let (lhs_val, rhs_val, result_ty) = if lhs_ty == OtterType::F64 || rhs_ty == OtterType::F64
-        {
-            // Promote both to F64
-            let l_f64 = if lhs_ty == OtterType::F64 {
-                lhs.value.unwrap().into_float_value()
-            } else if lhs_ty == OtterType::I64 {
-                let int_val = lhs.value.unwrap().into_int_value();
-                self.builder
-                    .build_signed_int_to_float(int_val, self.context.f64_type(), "itof")?
-            } else if lhs_ty == OtterType::Opaque {
-                let coerced = self.coerce_type(lhs.value.unwrap(), lhs_ty, OtterType::F64)?;
-                coerced.into_float_value()
-            } else {
-                bail!("Cannot coerce {:?} to F64", lhs_ty);
-            };
-
-            let r_f64 = if rhs_ty == OtterType::F64 {
-                rhs.value.unwrap().into_float_value()
-            } else if rhs_ty == OtterType::I64 {
-                let int_val = rhs.value.unwrap().into_int_value();
-                self.builder
-                    .build_signed_int_to_float(int_val, self.context.f64_type(), "itof")?
-            } else if rhs_ty == OtterType::Opaque {
-                let coerced = self.coerce_type(rhs.value.unwrap(), rhs_ty, OtterType::F64)?;
-                coerced.into_float_value()
-            } else {
-                bail!("Cannot coerce {:?} to F64", rhs_ty);
-            };
-
-            (l_f64.into(), r_f64.into(), OtterType::F64)
-        } else if lhs_ty == OtterType::I64 && rhs_ty == OtterType::I64 {
-            (lhs.value.unwrap(), rhs.value.unwrap(), OtterType::I64)
+        // Numbers meet at their common type the way the type checker widens them, except
+        // that a shift keeps the type of the value being shifted
+        let is_shift = matches!(op, BinaryOp::Shl | BinaryOp::Shr);
+        let common = match (&lhs_ty, &rhs_ty) {
+            (left, right) if is_shift && left.is_integer() && right.is_integer() => {
+                Some(left.clone())
+            }
+            (OtterType::F64, OtterType::Opaque) | (OtterType::Opaque, OtterType::F64) => {
+                Some(OtterType::F64)
+            }
+            (left, right) => left.common_numeric_type(right),
+        };
+        let (lhs_val, rhs_val, result_ty) = if let Some(common) = common {
+            let l = self.coerce_type(lhs.value.unwrap(), lhs_ty, common.clone())?;
+            let r = self.coerce_type(rhs.value.unwrap(), rhs_ty, common.clone())?;
+            (l, r, common)
         } else if lhs_ty == OtterType::Bool && rhs_ty == OtterType::Bool {
             (lhs.value.unwrap(), rhs.value.unwrap(), OtterType::Bool)
         } else {
@@ -1365,26 +2675,87 @@ impl<'ctx> Compiler<'ctx> {
 
         // Perform the operation based on the result type
         match result_ty {
-            OtterType::I64 => {
+            ref ty if let Some((bits, signed)) = ty.integer_layout() => {
                 let l = lhs_val.into_int_value();
                 let r = rhs_val.into_int_value();
+                let (lt, gt, le, ge) = if signed {
+                    (
+                        IntPredicate::SLT,
+                        IntPredicate::SGT,
+                        IntPredicate::SLE,
+                        IntPredicate::SGE,
+                    )
+                } else {
+                    (
+                        IntPredicate::ULT,
+                        IntPredicate::UGT,
+                        IntPredicate::ULE,
+                        IntPredicate::UGE,
+                    )
+                };
                 match op {
                     BinaryOp::Add => Ok(EvaluatedValue::with_value(
                         self.builder.build_int_add(l, r, "add")?.into(),
-                        OtterType::I64,
+                        result_ty,
                     )),
                     BinaryOp::Sub => Ok(EvaluatedValue::with_value(
                         self.builder.build_int_sub(l, r, "sub")?.into(),
-                        OtterType::I64,
+                        result_ty,
                     )),
                     BinaryOp::Mul => Ok(EvaluatedValue::with_value(
                         self.builder.build_int_mul(l, r, "mul")?.into(),
-                        OtterType::I64,
+                        result_ty,
                     )),
-                    BinaryOp::Div => Ok(EvaluatedValue::with_value(
+                    BinaryOp::Div if signed => Ok(EvaluatedValue::with_value(
                         self.builder.build_int_signed_div(l, r, "div")?.into(),
-                        OtterType::I64,
+                        result_ty,
+                    )),
+                    // Unsigned division already rounds toward negative infinity
+                    BinaryOp::Div | BinaryOp::FloorDiv if !signed => {
+                        Ok(EvaluatedValue::with_value(
+                            self.builder.build_int_unsigned_div(l, r, "div")?.into(),
+                            result_ty,
+                        ))
+                    }
+                    BinaryOp::Mod if signed => Ok(EvaluatedValue::with_value(
+                        self.builder.build_int_signed_rem(l, r, "rem")?.into(),
+                        result_ty,
+                    )),
+                    BinaryOp::Mod => Ok(EvaluatedValue::with_value(
+                        self.builder.build_int_unsigned_rem(l, r, "rem")?.into(),
+                        result_ty,
+                    )),
+                    BinaryOp::FloorDiv => Ok(EvaluatedValue::with_value(
+                        self.build_int_floor_div(l, r)?.into(),
+                        result_ty,
+                    )),
+                    BinaryOp::Pow => Ok(EvaluatedValue::with_value(
+                        self.build_int_pow(l, r, signed)?.into(),
+                        result_ty,
+                    )),
+                    BinaryOp::BitAnd => Ok(EvaluatedValue::with_value(
+                        self.builder.build_and(l, r, "bitand")?.into(),
+                        result_ty,
+                    )),
+                    BinaryOp::BitOr => Ok(EvaluatedValue::with_value(
+                        self.builder.build_or(l, r, "bitor")?.into(),
+                        result_ty,
                     )),
+                    BinaryOp::BitXor => Ok(EvaluatedValue::with_value(
+                        self.builder.build_xor(l, r, "bitxor")?.into(),
+                        result_ty,
+                    )),
+                    BinaryOp::Shl | BinaryOp::Shr => {
+                        // Shift amounts wrap at the bit width instead of producing poison
+                        let width_mask = l.get_type().const_int(u64::from(bits) - 1, false);
+                        let amount = self.builder.build_and(r, width_mask, "shift_amount")?;
+                        let shifted = if matches!(op, BinaryOp::Shl) {
+                            self.builder.build_left_shift(l, amount, "shl")?
+                        } else {
+                            self.builder.build_right_shift(l, amount, signed, "shr")?
+                        };
+                        Ok(EvaluatedValue::with_value(shifted.into(), result_ty))
+                    }
                     BinaryOp::Eq => Ok(EvaluatedValue::with_value(
                         self.builder
                             .build_int_compare(IntPredicate::EQ, l, r, "eq")?
@@ -1398,51 +2769,55 @@ impl<'ctx> Compiler<'ctx> {
                         OtterType::Bool,
                     )),
                     BinaryOp::Lt => Ok(EvaluatedValue::with_value(
-                        self.builder
-                            .build_int_compare(IntPredicate::SLT, l, r, "lt")?
-                            .into(),
+                        self.builder.build_int_compare(lt, l, r, "lt")?.into(),
                         OtterType::Bool,
                     )),
                     BinaryOp::Gt => Ok(EvaluatedValue::with_value(
-                        self.builder
-                            .build_int_compare(IntPredicate::SGT, l, r, "gt")?
-                            .into(),
+                        self.builder.build_int_compare(gt, l, r, "gt")?.into(),
                         OtterType::Bool,
                     )),
                     BinaryOp::LtEq => Ok(EvaluatedValue::with_value(
-                        self.builder
-                            .build_int_compare(IntPredicate::SLE, l, r, "le")?
-                            .into(),
+                        self.builder.build_int_compare(le, l, r, "le")?.into(),
                         OtterType::Bool,
                     )),
                     BinaryOp::GtEq => Ok(EvaluatedValue::with_value(
-                        self.builder
-                            .build_int_compare(IntPredicate::SGE, l, r, "ge")?
-                            .into(),
+                        self.builder.build_int_compare(ge, l, r, "ge")?.into(),
                         OtterType::Bool,
                     )),
-                    _ => bail!("Unsupported binary op for I64"),
+                    _ => bail!("Unsupported binary op for {:?}", result_ty),
                 }
             }
-            OtterType::F64 => {
+            OtterType::F32 | OtterType::F64 => {
                 let l = lhs_val.into_float_value();
                 let r = rhs_val.into_float_value();
                 match op {
                     BinaryOp::Add => Ok(EvaluatedValue::with_value(
                         self.builder.build_float_add(l, r, "add")?.into(),
-                        OtterType::F64,
+                        result_ty,
                     )),
                     BinaryOp::Sub => Ok(EvaluatedValue::with_value(
                         self.builder.build_float_sub(l, r, "sub")?.into(),
-                        OtterType::F64,
+                        result_ty,
                     )),
                     BinaryOp::Mul => Ok(EvaluatedValue::with_value(
                         self.builder.build_float_mul(l, r, "mul")?.into(),
-                        OtterType::F64,
+                        result_ty,
                     )),
                     BinaryOp::Div => Ok(EvaluatedValue::with_value(
                         self.builder.build_float_div(l, r, "div")?.into(),
-                        OtterType::F64,
+                        result_ty,
+                    )),
+                    BinaryOp::FloorDiv => {
+                        let quotient = self.builder.build_float_div(l, r, "div")?;
+                        Ok(EvaluatedValue::with_value(
+                            self.build_float_intrinsic("llvm.floor", &[quotient])?
+                                .into(),
+                            result_ty,
+                        ))
+                    }
+                    BinaryOp::Pow => Ok(EvaluatedValue::with_value(
+                        self.build_float_intrinsic("llvm.pow", &[l, r])?.into(),
+                        result_ty,
                     )),
                     BinaryOp::Eq => Ok(EvaluatedValue::with_value(
                         self.builder
@@ -1480,7 +2855,7 @@ impl<'ctx> Compiler<'ctx> {
                             .into(),
                         OtterType::Bool,
                     )),
-                    _ => bail!("Unsupported binary op for F64"),
+                    _ => bail!("Unsupported binary op for {:?}", result_ty),
                 }
             }
             OtterType::Bool => {
@@ -1521,20 +2896,23 @@ impl<'ctx> Compiler<'ctx> {
         ctx: &mut FunctionContext<'ctx>,
     ) -> Result<EvaluatedValue<'ctx>> {
         let val = self.eval_expr(expr, ctx)?;
+        if matches!(val.ty, OtterType::Struct(_)) && op.method_name().is_some() {
+            return self.build_struct_unary(*op, val, ctx);
+        }
         match op {
             UnaryOp::Neg => {
                 let val_ty = val.ty.clone();
-                if val_ty == OtterType::I64 {
+                if val_ty.is_integer() {
                     let v = val.value.unwrap().into_int_value();
                     Ok(EvaluatedValue::with_value(
                         self.builder.build_int_neg(v, "neg")?.into(),
-                        OtterType::I64,
+                        val_ty,
                     ))
-                } else if val_ty == OtterType::F64 {
+                } else if val_ty.is_float() {
                     let v = val.value.unwrap().into_float_value();
                     Ok(EvaluatedValue::with_value(
                         self.builder.build_float_neg(v, "neg")?.into(),
-                        OtterType::F64,
+                        val_ty,
                     ))
                 } else {
                     bail!("Unsupported type for negation");
@@ -1552,17 +2930,190 @@ impl<'ctx> Compiler<'ctx> {
                     bail!("Unsupported type for not");
                 }
             }
+            UnaryOp::BitNot => {
+                if val.ty.is_integer() {
+                    let v = val.value.unwrap().into_int_value();
+                    Ok(EvaluatedValue::with_value(
+                        self.builder.build_not(v, "bitnot")?.into(),
+                        val.ty,
+                    ))
+                } else {
+                    bail!("Unsupported type for bitwise not: {:?}", val.ty);
+                }
+            }
         }
     }
 
+    /// Integer division rounding toward negative infinity, so `-7 // 2` is `-4`.
+    fn build_int_floor_div(
+        &mut self,
+        lhs: IntValue<'ctx>,
+        rhs: IntValue<'ctx>,
+    ) -> Result<IntValue<'ctx>> {
+        let int_type = lhs.get_type();
+        let zero = int_type.const_zero();
+        let quotient = self.builder.build_int_signed_div(lhs, rhs, "div")?;
+        let remainder = self.builder.build_int_signed_rem(lhs, rhs, "rem")?;
+
+        // Truncating division rounds up whenever the remainder is non-zero and its sign
+        // differs from the divisor's
+        let inexact =
+            self.builder
+                .build_int_compare(IntPredicate::NE, remainder, zero, "inexact")?;
+        let signs = self.builder.build_xor(remainder, rhs, "signs")?;
+        let signs_differ =
+            self.builder
+                .build_int_compare(IntPredicate::SLT, signs, zero, "signs_differ")?;
+        let adjust = self.builder.build_and(inexact, signs_differ, "adjust")?;
+        let adjustment = self
+            .builder
+            .build_int_z_extend(adjust, int_type, "adjustment")?;
+        Ok(self
+            .builder
+            .build_int_sub(quotient, adjustment, "floor_div")?)
+    }
+
+    /// Integer exponentiation by squaring. A negative exponent truncates toward zero the
+    /// way integer division does, so only bases of `1` and `-1` produce a non-zero result.
+    fn build_int_pow(
+        &mut self,
+        base: IntValue<'ctx>,
+        exponent: IntValue<'ctx>,
+        signed: bool,
+    ) -> Result<IntValue<'ctx>> {
+        let int_type = base.get_type();
+        let zero = int_type.const_zero();
+        let one = int_type.const_int(1, false);
+        let entry_bb = self.builder.get_insert_block().unwrap();
+        let function = entry_bb.get_parent().unwrap();
+
+        let negative = if signed {
+            self.builder.build_int_compare(
+                IntPredicate::SLT,
+                exponent,
+                zero,
+                "negative_exponent",
+            )?
+        } else {
+            self.context.bool_type().const_zero()
+        };
+        let negated = self.builder.build_int_neg(exponent, "negated_exponent")?;
+        let magnitude = self
+            .builder
+            .build_select(negative, negated, exponent, "exponent_magnitude")?
+            .into_int_value();
+
+        let loop_bb = self.context.append_basic_block(function, "pow_loop");
+        let body_bb = self.context.append_basic_block(function, "pow_body");
+        let done_bb = self.context.append_basic_block(function, "pow_done");
+        self.builder.build_unconditional_branch(loop_bb)?;
+
+        self.builder.position_at_end(loop_bb);
+        let result = self.builder.build_phi(int_type, "pow_result")?;
+        let factor = self.builder.build_phi(int_type, "pow_factor")?;
+        let remaining = self.builder.build_phi(int_type, "pow_remaining")?;
+        let remaining_value = remaining.as_basic_value().into_int_value();
+        let more =
+            self.builder
+                .build_int_compare(IntPredicate::NE, remaining_value, zero, "pow_more")?;
+        self.builder
+            .build_conditional_branch(more, body_bb, done_bb)?;
+
+        self.builder.position_at_end(body_bb);
+        let result_value = result.as_basic_value().into_int_value();
+        let factor_value = factor.as_basic_value().into_int_value();
+        let low_bit = self
+            .builder
+            .build_and(remaining_value, one, "pow_low_bit")?;
+        let odd = self
+            .builder
+            .build_int_compare(IntPredicate::NE, low_bit, zero, "pow_odd")?;
+        let multiplied = self
+            .builder
+            .build_int_mul(result_value, factor_value, "pow_mul")?;
+        let next_result = self
+            .builder
+            .build_select(odd, multiplied, result_value, "pow_next_result")?
+            .into_int_value();
+        let next_factor = self
+            .builder
+            .build_int_mul(factor_value, factor_value, "pow_square")?;
+        let next_remaining =
+            self.builder
+                .build_right_shift(remaining_value, one, false, "pow_halve")?;
+        self.builder.build_unconditional_branch(loop_bb)?;
+
+        result.add_incoming(&[(&one, entry_bb), (&next_result, body_bb)]);
+        factor.add_incoming(&[(&base, entry_bb), (&next_factor, body_bb)]);
+        remaining.add_incoming(&[(&magnitude, entry_bb), (&next_remaining, body_bb)]);
+
+        self.builder.position_at_end(done_bb);
+        let minus_one = int_type.const_all_ones();
+        let base_is_one =
+            self.builder
+                .build_int_compare(IntPredicate::EQ, base, one, "base_is_one")?;
+        let base_is_minus_one = self.builder.build_int_compare(
+            IntPredicate::EQ,
+            base,
+            minus_one,
+            "base_is_minus_one",
+        )?;
+        let unit_base = self
+            .builder
+            .build_or(base_is_one, base_is_minus_one, "unit_base")?;
+        let not_unit_base = self.builder.build_not(unit_base, "not_unit_base")?;
+        let truncates = self
+            .builder
+            .build_and(negative, not_unit_base, "pow_truncates")?;
+        Ok(self
+            .builder
+            .build_select(
+                truncates,
+                zero,
+                result.as_basic_value().into_int_value(),
+                "pow",
+            )?
+            .into_int_value())
+    }
+
+    /// Calls an overloaded LLVM float intrinsic such as `llvm.pow` at the type of its first
+    /// argument.
+    fn build_float_intrinsic(
+        &mut self,
+        name: &str,
+        args: &[FloatValue<'ctx>],
+    ) -> Result<FloatValue<'ctx>> {
+        let float_type = args
+            .first()
+            .map_or_else(|| self.context.f64_type(), |arg| arg.get_type());
+        let function = Intrinsic::find(name)
+            .and_then(|intrinsic| intrinsic.get_declaration(&self.module, &[float_type.into()]))
+            .ok_or_else(|| anyhow!("missing LLVM intrinsic {}", name))?;
+        let args: Vec<BasicMetadataValueEnum<'ctx>> =
+            args.iter().map(|arg| (*arg).into()).collect();
+        self.builder
+            .build_call(function, &args, name.trim_start_matches("llvm."))?
+            .try_as_basic_value()
+            .left()
+            .map(|value| value.into_float_value())
+            .ok_or_else(|| anyhow!("{} did not return a value", name))
+    }
+
     pub(crate) fn basic_type(&self, ty: OtterType) -> Result<Option<BasicTypeEnum<'ctx>>> {
         match ty {
             OtterType::Unit => Ok(None),
             OtterType::Bool => Ok(Some(self.context.bool_type().into())),
-            OtterType::I32 => Ok(Some(self.context.i32_type().into())),
-            OtterType::I64 | OtterType::Opaque | OtterType::List(_) | OtterType::Map => {
-                Ok(Some(self.context.i64_type().into()))
-            }
+            OtterType::I8 | OtterType::U8 => Ok(Some(self.context.i8_type().into())),
+            OtterType::I16 | OtterType::U16 => Ok(Some(self.context.i16_type().into())),
+            OtterType::I32 | OtterType::U32 => Ok(Some(self.context.i32_type().into())),
+            OtterType::F32 => Ok(Some(self.context.f32_type().into())),
+            OtterType::I64
+            | OtterType::U64
+            | OtterType::Opaque
+            | OtterType::List(_)
+            | OtterType::Map
+            | OtterType::Function { .. }
+            | OtterType::Dyn(_) => Ok(Some(self.context.i64_type().into())),
             OtterType::F64 => Ok(Some(self.context.f64_type().into())),
             OtterType::Str => Ok(Some(self.string_ptr_type.into())),
             OtterType::Struct(id) => Ok(Some(self.struct_info(id).ty.into())),
@@ -1600,65 +3151,31 @@ impl<'ctx> Compiler<'ctx> {
             return Ok(value);
         }
 
-        if matches!((&from_ty, &to_ty), (OtterType::List(_), OtterType::List(_))) {
+        if matches!(
+            (&from_ty, &to_ty),
+            (OtterType::List(_), OtterType::List(_))
+                | (OtterType::Function { .. }, OtterType::Function { .. })
+        ) {
             return Ok(value);
         }
 
         // Perform type coercion based on source and target types
         match (from_ty.clone(), to_ty.clone()) {
             // Numeric conversions
-            (OtterType::I32, OtterType::I64) => {
-                let int_val = value.into_int_value();
-                Ok(self
-                    .builder
-                    .build_int_s_extend(int_val, self.context.i64_type(), "i32_to_i64")?
-                    .into())
-            }
-            (OtterType::I64, OtterType::I32) => {
-                let int_val = value.into_int_value();
-                Ok(self
-                    .builder
-                    .build_int_truncate(int_val, self.context.i32_type(), "i64_to_i32")?
-                    .into())
-            }
-            (OtterType::I32, OtterType::F64) | (OtterType::I64, OtterType::F64) => {
-                let int_val = value.into_int_value();
-                Ok(self
-                    .builder
-                    .build_signed_int_to_float(int_val, self.context.f64_type(), "int_to_f64")?
-                    .into())
-            }
-            (OtterType::F64, OtterType::I32) => {
-                let float_val = value.into_float_value();
-                Ok(self
-                    .builder
-                    .build_float_to_signed_int(float_val, self.context.i32_type(), "f64_to_i32")?
-                    .into())
-            }
-            (OtterType::F64, OtterType::I64) => {
-                let float_val = value.into_float_value();
-                Ok(self
-                    .builder
-                    .build_float_to_signed_int(float_val, self.context.i64_type(), "f64_to_i64")?
-                    .into())
+            (from, to) if (from.is_integer() || from.is_float()) && (to.is_integer() || to.is_float()) => {
+                self.convert_number(value, &from, &to)
             }
 
             // Bool conversions
-            (OtterType::Bool, OtterType::I32) => {
-                let bool_val = value.into_int_value();
-                Ok(self
-                    .builder
-                    .build_int_z_extend(bool_val, self.context.i32_type(), "bool_to_i32")?
-                    .into())
-            }
-            (OtterType::Bool, OtterType::I64) => {
+            (OtterType::Bool, to) if to.is_integer() => {
                 let bool_val = value.into_int_value();
+                let int_type = self.basic_type(to)?.unwrap().into_int_type();
                 Ok(self
                     .builder
-                    .build_int_z_extend(bool_val, self.context.i64_type(), "bool_to_i64")?
+                    .build_int_z_extend(bool_val, int_type, "bool_to_int")?
                     .into())
             }
-            (OtterType::I32 | OtterType::I64, OtterType::Bool) => {
+            (from, OtterType::Bool) if from.is_integer() => {
                 let int_val = value.into_int_value();
                 let zero = int_val.get_type().const_zero();
                 Ok(self
@@ -1669,9 +3186,9 @@ impl<'ctx> Compiler<'ctx> {
 
             // Opaque type conversions (treat as i64)
             (OtterType::Opaque, OtterType::I64) | (OtterType::I64, OtterType::Opaque)
-            // List/Map conversions (treat as opaque pointers)
-            | (OtterType::List(_) | OtterType::Map, OtterType::Opaque)
-            | (OtterType::Opaque, OtterType::List(_) | OtterType::Map) => {
+            // List/Map/closure/trait object conversions (treat as opaque pointers)
+            | (OtterType::List(_) | OtterType::Map | OtterType::Function { .. } | OtterType::Dyn(_), OtterType::Opaque)
+            | (OtterType::Opaque, OtterType::List(_) | OtterType::Map | OtterType::Function { .. } | OtterType::Dyn(_)) => {
                 Ok(value) // Already same representation
             }
             (OtterType::F64, OtterType::Opaque) => {
@@ -1740,7 +3257,93 @@ impl<'ctx> Compiler<'ctx> {
         }
     }
 
-    fn cast_argument_for_call(
+    /// Convert between numeric types the way `as` does. Integers are sign- or zero-extended
+    /// by the signedness of the source, or truncated; floats convert to integers toward zero,
+    /// saturating at the bounds of the target.
+    pub(crate) fn convert_number(
+        &self,
+        value: BasicValueEnum<'ctx>,
+        from_ty: &OtterType,
+        to_ty: &OtterType,
+    ) -> Result<BasicValueEnum<'ctx>> {
+        let target = self
+            .basic_type(to_ty.clone())?
+            .ok_or_else(|| anyhow!("{:?} is not a numeric type", to_ty))?;
+        match (from_ty.integer_layout(), to_ty.integer_layout()) {
+            (Some((from_bits, signed)), Some((to_bits, _))) => {
+                let int_val = value.into_int_value();
+                let int_type = target.into_int_type();
+                Ok(if from_bits < to_bits && signed {
+                    self.builder
+                        .build_int_s_extend(int_val, int_type, "sext")?
+                        .into()
+                } else if from_bits < to_bits {
+                    self.builder
+                        .build_int_z_extend(int_val, int_type, "zext")?
+                        .into()
+                } else if from_bits > to_bits {
+                    self.builder
+                        .build_int_truncate(int_val, int_type, "trunc")?
+                        .into()
+                } else {
+                    // Same width: only the interpretation of the bits changes
+                    value
+                })
+            }
+            (Some((_, signed)), None) => {
+                let int_val = value.into_int_value();
+                let float_type = target.into_float_type();
+                Ok(if signed {
+                    self.builder
+                        .build_signed_int_to_float(int_val, float_type, "int_to_float")?
+                        .into()
+                } else {
+                    self.builder
+                        .build_unsigned_int_to_float(int_val, float_type, "uint_to_float")?
+                        .into()
+                })
+            }
+            (None, Some((_, signed))) => {
+                let float_val = value.into_float_value();
+                let int_type = target.into_int_type();
+                let name = if signed {
+                    "llvm.fptosi.sat"
+                } else {
+                    "llvm.fptoui.sat"
+                };
+                let function = Intrinsic::find(name)
+                    .and_then(|intrinsic| {
+                        intrinsic.get_declaration(
+                            &self.module,
+                            &[int_type.into(), float_val.get_type().into()],
+                        )
+                    })
+                    .ok_or_else(|| anyhow!("missing LLVM intrinsic {}", name))?;
+                self.builder
+                    .build_call(function, &[float_val.into()], "float_to_int")?
+                    .try_as_basic_value()
+                    .left()
+                    .ok_or_else(|| anyhow!("{} did not return a value", name))
+            }
+            (None, None) => {
+                let float_val = value.into_float_value();
+                let float_type = target.into_float_type();
+                Ok(match (from_ty, to_ty) {
+                    (OtterType::F32, OtterType::F64) => self
+                        .builder
+                        .build_float_ext(float_val, float_type, "fpext")?
+                        .into(),
+                    (OtterType::F64, OtterType::F32) => self
+                        .builder
+                        .build_float_trunc(float_val, float_type, "fptrunc")?
+                        .into(),
+                    _ => value,
+                })
+            }
+        }
+    }
+
+    pub(crate) fn cast_argument_for_call(
         &self,
         value: BasicValueEnum<'ctx>,
         from_ty: OtterType,
@@ -1752,6 +3355,38 @@ impl<'ctx> Compiler<'ctx> {
                 .builder
                 .build_float_to_signed_int(float_val, param_type.into_int_type(), "ftoi")?
                 .into())
+        } else if let (BasicValueEnum::IntValue(int_val), BasicTypeEnum::FloatType(float_type)) =
+            (value, param_type)
+        {
+            // Integers passed where a float is expected, such as `max<float>(0.5, 2)`
+            let unsigned = from_ty.integer_layout().is_some_and(|(_, signed)| !signed);
+            let converted = if unsigned {
+                self.builder
+                    .build_unsigned_int_to_float(int_val, *float_type, "itof")?
+            } else {
+                self.builder
+                    .build_signed_int_to_float(int_val, *float_type, "itof")?
+            };
+            Ok(converted.into())
+        } else if let (BasicValueEnum::IntValue(int_val), BasicTypeEnum::IntType(int_type)) =
+            (value, param_type)
+            && int_val.get_type() != *int_type
+        {
+            // Sized integers meet parameters of a different width, such as a `u8` passed on
+            // as an `i64`
+            let signed = from_ty.integer_layout().is_some_and(|(_, signed)| signed);
+            Ok(self
+                .builder
+                .build_int_cast_sign_flag(int_val, *int_type, signed, "int_arg")?
+                .into())
+        } else if let (BasicValueEnum::FloatValue(float_val), BasicTypeEnum::FloatType(float_type)) =
+            (value, param_type)
+            && float_val.get_type() != *float_type
+        {
+            Ok(self
+                .builder
+                .build_float_cast(float_val, *float_type, "float_arg")?
+                .into())
         } else if value.get_type() != *param_type
             && value.get_type().is_struct_type()
             && param_type.is_struct_type()
@@ -1784,7 +3419,13 @@ impl<'ctx> Compiler<'ctx> {
         expr: &Expr,
         ctx: &mut FunctionContext<'ctx>,
     ) -> Result<EvaluatedValue<'ctx>> {
-        if let Expr::Call { func, args } = expr {
+        if let Expr::Call {
+            func,
+            args,
+            kwargs,
+            type_args,
+        } = expr
+        {
             let mut implicit_self: Option<EvaluatedValue<'ctx>> = None;
             if let Some(enum_value) =
                 self.try_build_enum_constructor(expr, func.as_ref().as_ref(), args, ctx)?
@@ -1792,9 +3433,45 @@ impl<'ctx> Compiler<'ctx> {
                 return Ok(enum_value);
             }
 
+            // Closure values are called through their environment
+            let callee_is_closure = match func.as_ref().as_ref() {
+                Expr::Identifier(name) => ctx
+                    .get(name)
+                    .is_some_and(|var| matches!(var.ty, OtterType::Function { .. })),
+                Expr::Member { object, field } => {
+                    self.is_function_field(object.as_ref().as_ref(), field)
+                }
+                _ => true,
+            };
+            if callee_is_closure {
+                let closure = self.eval_expr(func.as_ref().as_ref(), ctx)?;
+                return self.build_closure_call(closure, args, ctx);
+            }
+
             // Evaluate function expression
             let func_name = match func.as_ref().as_ref() {
+                Expr::Identifier(name)
+                    if ctx.get(name).is_none() && self.generic_functions.contains_key(name) =>
+                {
+                    self.instantiate_generic(name, func.as_ref().as_ref(), type_args)?
+                }
                 Expr::Identifier(name) => name.clone(),
+                Expr::Member { object, field }
+                    if let Some(func_name) = self.resolve_associated_function_name(
+                        object.as_ref().as_ref(),
+                        field,
+                        ctx,
+                    ) =>
+                {
+                    func_name
+                }
+                Expr::Member { object, field }
+                    if let Some(method_name) =
+                        self.resolve_enum_method_name(object.as_ref().as_ref(), field) =>
+                {
+                    implicit_self = Some(self.eval_expr(object.as_ref().as_ref(), ctx)?);
+                    method_name
+                }
                 Expr::Member { object, field } => {
                     // First, try to evaluate the object to check its runtime type
                     // This handles cases like list.append() where the object is a variable
@@ -1803,31 +3480,25 @@ impl<'ctx> Compiler<'ctx> {
                             // Check if it's a list type and handle list methods
                             if matches!(evaluated.ty, OtterType::List(_)) {
                                 if field == "append" && !args.is_empty() {
-                                    // Determine the append function based on argument type
-                                    let arg_val = self.eval_expr(args[0].as_ref(), ctx)?;
-                                    let method_name: String = match arg_val.ty {
-                                        OtterType::Str => "append<list,string>".to_string(),
-                                        OtterType::I64 | OtterType::I32 => {
-                                            "append<list,int>".to_string()
-                                        }
-                                        OtterType::F64 => "append<list,float>".to_string(),
-                                        OtterType::Bool => "append<list,bool>".to_string(),
-                                        OtterType::List(_) | OtterType::Opaque => {
-                                            "append<list,list>".to_string()
-                                        }
-                                        _ => bail!(
-                                            "unsupported append argument type: {:?}",
-                                            arg_val.ty
-                                        ),
-                                    };
-                                    implicit_self = Some(evaluated);
-                                    method_name
+                                    return self.build_list_append(
+                                        evaluated,
+                                        args[0].as_ref(),
+                                        ctx,
+                                    );
                                 } else {
                                     bail!(
                                         "list method '{}' not supported or missing arguments",
                                         field
                                     );
                                 }
+                            } else if let OtterType::Dyn(trait_name) = evaluated.ty.clone() {
+                                return self.build_dyn_method_call(
+                                    &trait_name,
+                                    evaluated,
+                                    field,
+                                    args,
+                                    ctx,
+                                );
                             } else if let OtterType::Struct(struct_id) = evaluated.ty.clone() {
                                 if let Some(method_name) =
                                     self.resolve_struct_method_name(struct_id, field)
@@ -1841,6 +3512,11 @@ impl<'ctx> Compiler<'ctx> {
                                         field
                                     );
                                 }
+                            } else if matches!(evaluated.ty, OtterType::Str)
+                                && let Some(method_name) = self.string_method_name(field)
+                            {
+                                implicit_self = Some(evaluated);
+                                method_name
                             } else {
                                 // Not a list or struct, continue with other checks
                                 if let Expr::Identifier(enum_name) = object.as_ref().as_ref() {
@@ -1904,27 +3580,18 @@ impl<'ctx> Compiler<'ctx> {
                         if matches!(evaluated.ty, OtterType::List(_)) {
                             // Handle list method calls like list.append()
                             if field == "append" && !args.is_empty() {
-                                // Determine the append function based on argument type
-                                let arg_val = self.eval_expr(args[0].as_ref(), ctx)?;
-                                let method_name: String = match arg_val.ty {
-                                    OtterType::Str => "append<list,string>".to_string(),
-                                    OtterType::I64 | OtterType::I32 => {
-                                        "append<list,int>".to_string()
-                                    }
-                                    OtterType::F64 => "append<list,float>".to_string(),
-                                    OtterType::Bool => "append<list,bool>".to_string(),
-                                    OtterType::List(_) | OtterType::Opaque => {
-                                        "append<list,list>".to_string()
-                                    }
-                                    _ => {
-                                        bail!("unsupported append argument type: {:?}", arg_val.ty)
-                                    }
-                                };
-                                implicit_self = Some(evaluated);
-                                method_name
+                                return self.build_list_append(evaluated, args[0].as_ref(), ctx);
                             } else {
                                 bail!("list method '{}' not supported or missing arguments", field);
                             }
+                        } else if let OtterType::Dyn(trait_name) = evaluated.ty.clone() {
+                            return self.build_dyn_method_call(
+                                &trait_name,
+                                evaluated,
+                                field,
+                                args,
+                                ctx,
+                            );
                         } else if let OtterType::Struct(struct_id) = evaluated.ty.clone() {
                             if let Some(method_name) =
                                 self.resolve_struct_method_name(struct_id, field)
@@ -1938,6 +3605,11 @@ impl<'ctx> Compiler<'ctx> {
                                     field
                                 );
                             }
+                        } else if matches!(evaluated.ty, OtterType::Str)
+                            && let Some(method_name) = self.string_method_name(field)
+                        {
+                            implicit_self = Some(evaluated);
+                            method_name
                         } else if let Some(func_name) =
                             self.resolve_member_function_name(object.as_ref().as_ref(), field)
                         {
@@ -1949,6 +3621,40 @@ impl<'ctx> Compiler<'ctx> {
                 }
                 _ => bail!("Complex function expressions not yet supported"),
             };
+            // Runtime functions overloaded by type are registered as `name<types>`
+            let func_name =
+                if type_args.is_empty() || self.declared_functions.contains_key(&func_name) {
+                    func_name
+                } else {
+                    let types = type_args
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(",");
+                    format!("{}<{}>", func_name, types)
+                };
+
+            if func_name == "sort"
+                && args.len() == 1
+                && !self.declared_functions.contains_key("sort")
+            {
+                return self.build_sort_call(args[0].as_ref(), ctx);
+            }
+            if func_name == "hash"
+                && args.len() == 1
+                && !self.declared_functions.contains_key("hash")
+            {
+                return self.build_hash_call(args[0].as_ref(), ctx);
+            }
+            if ITERATOR_ADAPTERS.contains(&func_name.as_str())
+                && !self.declared_functions.contains_key(&func_name)
+            {
+                return self.build_iterator_adapter(expr, &func_name, args, ctx);
+            }
+            if func_name == "str" && args.len() == 1 && !self.declared_functions.contains_key("str")
+            {
+                return self.build_display_value(args[0].as_ref(), ctx);
+            }
 
             // Handle overloaded builtins like len() - evaluate first arg to determine type
             let (function, resolved_func_name, first_arg_evaluated) =
@@ -1990,99 +3696,327 @@ impl<'ctx> Compiler<'ctx> {
 
             // Evaluate arguments and convert types as needed
             let mut arg_values: Vec<BasicMetadataValueEnum> = Vec::new();
-            let mut param_offset = 0;
-
             if let Some(self_arg) = implicit_self {
-                let v = self_arg
-                    .value
-                    .ok_or_else(|| anyhow!("Cannot pass unit value as self"))?;
-                let param_type = param_types.first().ok_or_else(|| {
-                    anyhow!("Method '{}' missing self parameter", resolved_func_name)
-                })?;
-                let converted = self.cast_argument_for_call(v, self_arg.ty.clone(), param_type)?;
-                arg_values.push(converted.into());
-                param_offset = 1;
-            }
-
-            for (i, arg) in args.iter().enumerate() {
-                // Reuse first arg if it was already evaluated for len() dispatch
-                let arg_val = if i == 0 {
-                    if let Some(val) = first_arg_evaluated.as_ref() {
-                        val.clone()
-                    } else {
-                        self.eval_expr(arg.as_ref(), ctx)?
-                    }
+                if self.receiver_methods.contains(&resolved_func_name) {
+                    let receiver = match func.as_ref().as_ref() {
+                        Expr::Member { object, .. } => Some(object.as_ref().as_ref()),
+                        _ => None,
+                    };
+                    let receiver_ptr = self.receiver_pointer(receiver, self_arg, ctx)?;
+                    arg_values.push(receiver_ptr.into());
                 } else {
-                    self.eval_expr(arg.as_ref(), ctx)?
-                };
-                if let Some(v) = arg_val.value {
-                    let param_type = param_types.get(i + param_offset).ok_or_else(|| {
-                        anyhow!("Too many arguments for function {}", resolved_func_name)
+                    let v = self_arg
+                        .value
+                        .ok_or_else(|| anyhow!("Cannot pass unit value as self"))?;
+                    let param_type = param_types.first().ok_or_else(|| {
+                        anyhow!("Method '{}' missing self parameter", resolved_func_name)
                     })?;
                     let converted =
-                        self.cast_argument_for_call(v, arg_val.ty.clone(), param_type)?;
+                        self.cast_argument_for_call(v, self_arg.ty.clone(), param_type)?;
                     arg_values.push(converted.into());
-                } else {
-                    bail!("Cannot pass unit value as argument");
                 }
             }
 
-            // Fill in default values for missing arguments
-            if arg_values.len() < param_types.len() {
-                let defaults_to_eval =
-                    if let Some(defaults) = self.function_defaults.get(&resolved_func_name) {
-                        let mut to_eval = Vec::new();
-                        for i in arg_values.len()..param_types.len() {
-                            if let Some(default_expr) = defaults.get(i).and_then(|d| d.as_ref()) {
-                                to_eval.push((i, default_expr.clone()));
-                            } else {
-                                bail!("Missing argument {} for function {}", i, resolved_func_name);
-                            }
-                        }
-                        to_eval
-                    } else {
-                        bail!("Missing arguments for function {}", resolved_func_name);
-                    };
+            self.push_call_arguments(
+                &resolved_func_name,
+                &param_types,
+                args,
+                kwargs,
+                first_arg_evaluated,
+                &mut arg_values,
+                ctx,
+            )?;
+            self.emit_function_call(function, &resolved_func_name, &func_name, &arg_values, ctx)
+        } else {
+            bail!("Expected Call expression");
+        }
+    }
 
-                for (i, default_expr) in defaults_to_eval {
-                    let val = self.eval_expr(&default_expr, ctx)?;
-                    if let Some(v) = val.value {
-                        let param_type = param_types[i];
-                        let converted = self.cast_argument_for_call(v, val.ty, &param_type)?;
-                        arg_values.push(converted.into());
-                    } else {
-                        bail!("Default value for argument {} evaluated to void", i);
-                    }
-                }
+    /// Evaluate the arguments of a call onto `arg_values`, which already holds the receiver
+    /// of a method call. Keyword arguments go to the parameter they name, the arguments past
+    /// the fixed parameters of a variadic function are collected into a list, and parameters
+    /// that are not given take their default value.
+    #[expect(
+        clippy::too_many_arguments,
+        reason = "Arguments mirror the parts of a call"
+    )]
+    fn push_call_arguments(
+        &mut self,
+        func_name: &str,
+        param_types: &[BasicTypeEnum<'ctx>],
+        args: &[Node<Expr>],
+        kwargs: &[(String, Node<Expr>)],
+        mut first_arg: Option<EvaluatedValue<'ctx>>,
+        arg_values: &mut Vec<BasicMetadataValueEnum<'ctx>>,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<()> {
+        let param_offset = arg_values.len();
+        let rest_index = self
+            .is_variadic_function(func_name)
+            .then(|| param_types.len().saturating_sub(1));
+        let mut rest = Vec::new();
+        for (i, arg) in args.iter().enumerate() {
+            if rest_index.is_some_and(|index| i + param_offset >= index) {
+                rest.push(arg);
+                continue;
+            }
+            // Reuse first arg if it was already evaluated for len() dispatch
+            let arg_val = match first_arg.take() {
+                Some(val) => val,
+                None => self.eval_expr(arg.as_ref(), ctx)?,
+            };
+            let converted =
+                self.convert_call_argument(func_name, i + param_offset, arg_val, param_types)?;
+            arg_values.push(converted);
+        }
+
+        // Keyword arguments are evaluated in the order they are written, after the variadic
+        // arguments that precede them
+        let mut keyword_values = vec![None; param_types.len()];
+        if let Some(index) = rest_index {
+            keyword_values[index] = Some(self.build_rest_list(func_name, &rest, ctx)?);
+        }
+        for (name, value) in kwargs {
+            let index = self
+                .function_param_names
+                .get(func_name)
+                .and_then(|names| names.iter().position(|param| param == name))
+                .ok_or_else(|| anyhow!("function {} has no parameter named {}", func_name, name))?;
+            let arg_val = self.eval_expr(value.as_ref(), ctx)?;
+            keyword_values[index] =
+                Some(self.convert_call_argument(func_name, index, arg_val, param_types)?);
+        }
+
+        // Fill in default values for missing arguments
+        for (i, param_type) in param_types.iter().enumerate().skip(arg_values.len()) {
+            if let Some(value) = keyword_values[i].take() {
+                arg_values.push(value);
+                continue;
             }
+            let default_expr = self
+                .function_defaults
+                .get(func_name)
+                .and_then(|defaults| defaults.get(i).cloned().flatten())
+                .ok_or_else(|| anyhow!("Missing argument {} for function {}", i, func_name))?;
+            let val = self.eval_expr(&default_expr, ctx)?;
+            let v = val
+                .value
+                .ok_or_else(|| anyhow!("Default value for argument {} evaluated to void", i))?;
+            let converted = self.cast_argument_for_call(v, val.ty, param_type)?;
+            arg_values.push(converted.into());
+        }
+        Ok(())
+    }
 
-            // Call the function
-            let call_site = self.builder.build_call(function, &arg_values, &func_name)?;
-
-            // Get return value
-            if let Some(ret_val) = call_site.try_as_basic_value().left() {
-                // Use declared return type if available, otherwise infer from LLVM type
-                let return_ty = self
-                    .function_return_types
-                    .get(&resolved_func_name)
-                    .cloned()
-                    .unwrap_or_else(|| {
-                        function
-                            .get_type()
-                            .get_return_type()
-                            .map(|ty| self.otter_type_from_basic_type(ty))
-                            .unwrap_or(OtterType::Opaque)
-                    });
-                Ok(EvaluatedValue::with_value(ret_val, return_ty))
-            } else {
-                // Function returns void
-                Ok(EvaluatedValue {
-                    ty: OtterType::Unit,
-                    value: None,
-                })
+    /// Runtime function of the `string` module that `s.method(...)` calls
+    fn string_method_name(&self, method: &str) -> Option<String> {
+        let name = format!("string.{}", method);
+        self.symbol_registry.contains(&name).then_some(name)
+    }
+
+    /// The lists returned by the `string` module hold strings
+    fn string_module_return_type(&self, func_name: &str) -> Option<OtterType> {
+        use otterc_symbol::registry::FfiType;
+
+        if !is_string_module_function(func_name) {
+            return None;
+        }
+        let function = self.symbol_registry.resolve(func_name)?;
+        matches!(function.signature.result, FfiType::List)
+            .then(|| OtterType::list_of(OtterType::Str))
+    }
+
+    /// Whether the last parameter of `func_name` collects the remaining arguments, either as
+    /// declared in Otter or as registered by the runtime
+    fn is_variadic_function(&self, func_name: &str) -> bool {
+        if self.function_param_names.contains_key(func_name) {
+            return self.variadic_functions.contains(func_name);
+        }
+        self.symbol_registry
+            .resolve(func_name)
+            .is_some_and(|function| function.signature.variadic.is_some())
+    }
+
+    /// Element type of the variadic parameter of `func_name`, when it is a primitive that
+    /// the arguments are converted to
+    fn variadic_element_type(&self, func_name: &str) -> Option<OtterType> {
+        use otterc_symbol::registry::FfiType;
+
+        if let Some(types) = self.function_param_types.get(func_name) {
+            return types.last().and_then(OtterType::list_element).cloned();
+        }
+        let element = self
+            .symbol_registry
+            .resolve(func_name)?
+            .signature
+            .variadic?;
+        match element {
+            FfiType::I32 | FfiType::I64 => Some(OtterType::I64),
+            FfiType::F64 => Some(OtterType::F64),
+            FfiType::Bool => Some(OtterType::Bool),
+            FfiType::Str => Some(OtterType::Str),
+            _ => None,
+        }
+    }
+
+    /// Collect the variadic arguments of a call to `func_name` into a new list; `*xs` adds
+    /// the elements of `xs`
+    fn build_rest_list(
+        &mut self,
+        func_name: &str,
+        rest: &[&Node<Expr>],
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<BasicMetadataValueEnum<'ctx>> {
+        let element_ty = self.variadic_element_type(func_name);
+        let list_fn = self.get_or_declare_ffi_function("list.new")?;
+        let handle = self
+            .builder
+            .build_call(list_fn, &[], "rest")?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("failed to create the variadic arguments of {}", func_name))?
+            .into_int_value();
+
+        for &arg in rest {
+            if let Expr::Spread(list) = arg.as_ref() {
+                let spread = self.eval_expr(list.as_ref().as_ref(), ctx)?;
+                let spread = spread
+                    .value
+                    .ok_or_else(|| anyhow!("cannot spread a unit value"))?;
+                let extend_fn = self.get_or_declare_ffi_function("extend<list>")?;
+                self.builder
+                    .build_call(extend_fn, &[handle.into(), spread.into()], "spread")?;
+                continue;
             }
+            let evaluated = self.eval_expr(arg.as_ref(), ctx)?;
+            let value = evaluated
+                .value
+                .ok_or_else(|| anyhow!("Cannot pass unit value as argument"))?;
+            // Numbers are stored as the declared element type, so `*xs: float` holds floats
+            let (value, value_ty) = match &element_ty {
+                Some(element @ (OtterType::I64 | OtterType::F64))
+                    if evaluated.ty != *element
+                        && (evaluated.ty.is_integer() || evaluated.ty.is_float()) =>
+                {
+                    let value = self.coerce_type(value, evaluated.ty, element.clone())?;
+                    (value, element.clone())
+                }
+                _ => (value, evaluated.ty),
+            };
+            self.append_value_to_list(handle, value, value_ty, "rest_arg")?;
+        }
+        Ok(handle.into())
+    }
+
+    /// Convert an evaluated argument to the type of parameter `index` of `func_name`
+    fn convert_call_argument(
+        &mut self,
+        func_name: &str,
+        index: usize,
+        arg: EvaluatedValue<'ctx>,
+        param_types: &[BasicTypeEnum<'ctx>],
+    ) -> Result<BasicMetadataValueEnum<'ctx>> {
+        let mut value = arg
+            .value
+            .ok_or_else(|| anyhow!("Cannot pass unit value as argument"))?;
+        let param_type = param_types
+            .get(index)
+            .ok_or_else(|| anyhow!("Too many arguments for function {}", func_name))?;
+        let mut arg_ty = arg.ty;
+        // Structs passed where a trait object is expected are boxed first
+        if let Some(dyn_ty @ OtterType::Dyn(_)) = self
+            .function_param_types
+            .get(func_name)
+            .and_then(|types| types.get(index))
+            .cloned()
+        {
+            value = self.coerce_value(value, arg_ty, dyn_ty.clone())?;
+            arg_ty = dyn_ty;
+        }
+        Ok(self
+            .cast_argument_for_call(value, arg_ty, param_type)?
+            .into())
+    }
+
+    /// `f(x=1)` parses like a struct literal; this lowers it as a call of the function `name`
+    fn eval_keyword_call(
+        &mut self,
+        name: &str,
+        kwargs: &[(String, Node<Expr>)],
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<EvaluatedValue<'ctx>> {
+        let func_name = if let Some(generic) = self.generic_functions.get(name) {
+            let names: Vec<String> = generic
+                .params
+                .iter()
+                .map(|param| param.as_ref().name.as_ref().clone())
+                .collect();
+            let arranged = arrange_arguments(&names, &[], kwargs);
+            self.instantiate_generic_from_args(name, &arranged)?
         } else {
-            bail!("Expected Call expression");
+            name.to_string()
+        };
+        let function = *self
+            .declared_functions
+            .get(&func_name)
+            .ok_or_else(|| anyhow!("Function {} not found", func_name))?;
+        let param_types: Vec<BasicTypeEnum> = function
+            .get_param_iter()
+            .map(|arg| arg.get_type())
+            .collect();
+        let mut arg_values = Vec::with_capacity(param_types.len());
+        self.push_call_arguments(
+            &func_name,
+            &param_types,
+            &[],
+            kwargs,
+            None,
+            &mut arg_values,
+            ctx,
+        )?;
+        self.emit_function_call(function, &func_name, &func_name, &arg_values, ctx)
+    }
+
+    fn emit_function_call(
+        &mut self,
+        function: FunctionValue<'ctx>,
+        resolved_func_name: &str,
+        func_name: &str,
+        arg_values: &[BasicMetadataValueEnum<'ctx>],
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<EvaluatedValue<'ctx>> {
+        let call_site = self.builder.build_call(function, arg_values, func_name)?;
+        // Otter functions (unlike most runtime FFI calls) can leave an exception pending. Of
+        // the runtime, the `string` module raises when parsing or indexing fails, and
+        // `math.min`/`math.max` when a collected argument is not a number.
+        if self.function_return_types.contains_key(resolved_func_name)
+            || is_raising_runtime_function(resolved_func_name)
+        {
+            self.check_pending_exception(ctx)?;
+        }
+
+        // Get return value
+        if let Some(ret_val) = call_site.try_as_basic_value().left() {
+            // Use declared return type if available, otherwise infer from LLVM type
+            let return_ty = self
+                .function_return_types
+                .get(resolved_func_name)
+                .cloned()
+                .or_else(|| self.string_module_return_type(resolved_func_name))
+                .unwrap_or_else(|| {
+                    function
+                        .get_type()
+                        .get_return_type()
+                        .map(|ty| self.otter_type_from_basic_type(ty))
+                        .unwrap_or(OtterType::Opaque)
+                });
+            Ok(EvaluatedValue::with_value(ret_val, return_ty))
+        } else {
+            // Function returns void
+            Ok(EvaluatedValue {
+                ty: OtterType::Unit,
+                value: None,
+            })
         }
     }
 
@@ -2152,46 +4086,224 @@ impl<'ctx> Compiler<'ctx> {
             let then_ty = then_val.ty.clone();
             let else_ty = else_val.ty.clone();
 
-            if then_ty == else_ty
-                && let Some(then_val) = then_val.value
-                && else_val.value.is_some()
-            {
-                if let Some(basic_ty) = self.basic_type(then_ty.clone())? {
-                    let phi = self.builder.build_phi(basic_ty, "if_result")?;
-                    phi.add_incoming(&[(&then_val, then_bb_end), (&then_val, else_bb_end)]);
-                    Ok(EvaluatedValue::with_value(phi.as_basic_value(), then_ty))
-                } else {
-                    // Unit type
-                    Ok(EvaluatedValue {
-                        ty: OtterType::Unit,
-                        value: None,
-                    })
-                }
-            } else {
-                // Different types or unit - return unit
-                Ok(EvaluatedValue {
-                    ty: OtterType::Unit,
-                    value: None,
-                })
+            if then_ty == else_ty
+                && let Some(then_val) = then_val.value
+                && else_val.value.is_some()
+            {
+                if let Some(basic_ty) = self.basic_type(then_ty.clone())? {
+                    let phi = self.builder.build_phi(basic_ty, "if_result")?;
+                    phi.add_incoming(&[(&then_val, then_bb_end), (&then_val, else_bb_end)]);
+                    Ok(EvaluatedValue::with_value(phi.as_basic_value(), then_ty))
+                } else {
+                    // Unit type
+                    Ok(EvaluatedValue {
+                        ty: OtterType::Unit,
+                        value: None,
+                    })
+                }
+            } else {
+                // Different types or unit - return unit
+                Ok(EvaluatedValue {
+                    ty: OtterType::Unit,
+                    value: None,
+                })
+            }
+        } else {
+            bail!("Expected If expression");
+        }
+    }
+
+    pub(crate) fn build_string_concat(
+        &mut self,
+        lhs: EvaluatedValue<'ctx>,
+        rhs: EvaluatedValue<'ctx>,
+    ) -> Result<EvaluatedValue<'ctx>> {
+        let left_ptr = self.ensure_string_value(lhs)?;
+        let right_ptr = self.ensure_string_value(rhs)?;
+        let result = self.call_ffi_returning_value(
+            "std.strings.concat",
+            vec![left_ptr, right_ptr],
+            "str_concat",
+        )?;
+        Ok(EvaluatedValue::with_value(result, OtterType::Str))
+    }
+
+    fn eval_index_expr(
+        &mut self,
+        expr: &Expr,
+        object: &Expr,
+        index: &Expr,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<EvaluatedValue<'ctx>> {
+        let object_value = self.eval_expr(object, ctx)?;
+        let object_ty = object_value.ty.clone();
+        let handle = object_value
+            .value
+            .ok_or_else(|| anyhow!("cannot index into an expression without a value"))?;
+        let result_ty = self
+            .expr_type(expr)
+            .and_then(|ty| self.typeinfo_to_otter_type(ty));
+
+        match object_ty {
+            OtterType::List(_) | OtterType::Opaque | OtterType::Map => {
+                let place = self.element_place(object_value, index, result_ty, ctx)?;
+                self.load_place(&place)
+            }
+            OtterType::Str => {
+                let index_value = self.eval_index_value(index, ctx)?;
+                let len = self
+                    .call_ffi_returning_value("str.char_len", vec![handle], "str_len")?
+                    .into_int_value();
+                let index_value =
+                    self.build_checked_index(index_value, len, "string index out of range")?;
+                let value = self.call_ffi_returning_value(
+                    "string.char_at",
+                    vec![handle, index_value.into()],
+                    "str_char",
+                )?;
+                Ok(EvaluatedValue::with_value(value, OtterType::Str))
+            }
+            other => bail!("cannot index into value of type {:?}", other),
+        }
+    }
+
+    fn eval_slice_expr(
+        &mut self,
+        object: &Expr,
+        start: Option<&Expr>,
+        end: Option<&Expr>,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<EvaluatedValue<'ctx>> {
+        let object_value = self.eval_expr(object, ctx)?;
+        let object_ty = object_value.ty.clone();
+        let handle = object_value
+            .value
+            .ok_or_else(|| anyhow!("cannot slice an expression without a value"))?;
+
+        // Missing bounds default to the whole range; the runtime clamps them.
+        let i64_type = self.context.i64_type();
+        let start_value = match start {
+            Some(start) => self.eval_index_value(start, ctx)?,
+            None => i64_type.const_zero(),
+        };
+        let end_value = match end {
+            Some(end) => self.eval_index_value(end, ctx)?,
+            None => i64_type.const_int(i64::MAX as u64, false),
+        };
+        let args = vec![handle, start_value.into(), end_value.into()];
+
+        match object_ty {
+            OtterType::List(_) | OtterType::Opaque => {
+                let value = self.call_ffi_returning_value("list.slice", args, "list_slice")?;
+                let list_ty = match object_ty {
+                    OtterType::Opaque => OtterType::opaque_list(),
+                    other => other,
+                };
+                Ok(EvaluatedValue::with_value(value, list_ty))
+            }
+            OtterType::Str => {
+                let value = self.call_ffi_returning_value("string.slice", args, "str_slice")?;
+                Ok(EvaluatedValue::with_value(value, OtterType::Str))
+            }
+            other => bail!("cannot slice value of type {:?}", other),
+        }
+    }
+
+    pub(crate) fn eval_index_value(
+        &mut self,
+        index: &Expr,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<IntValue<'ctx>> {
+        let index_eval = self.eval_expr(index, ctx)?;
+        let raw = index_eval
+            .value
+            .ok_or_else(|| anyhow!("index expression produced no value"))?;
+        Ok(self
+            .coerce_type(raw, index_eval.ty, OtterType::I64)?
+            .into_int_value())
+    }
+
+    /// Normalize a possibly negative index against `len` and panic when it is out of bounds.
+    pub(crate) fn build_checked_index(
+        &mut self,
+        index: IntValue<'ctx>,
+        len: IntValue<'ctx>,
+        message: &str,
+    ) -> Result<IntValue<'ctx>> {
+        let zero = self.context.i64_type().const_zero();
+        let is_negative =
+            self.builder
+                .build_int_compare(IntPredicate::SLT, index, zero, "idx_negative")?;
+        let wrapped = self.builder.build_int_add(index, len, "idx_wrapped")?;
+        let normalized = self
+            .builder
+            .build_select(is_negative, wrapped, index, "idx")?
+            .into_int_value();
+        // An unsigned comparison also rejects indices that are still negative.
+        let in_bounds =
+            self.builder
+                .build_int_compare(IntPredicate::ULT, normalized, len, "idx_in_bounds")?;
+        self.build_runtime_check(in_bounds, message)?;
+        Ok(normalized)
+    }
+
+    /// Branch to a runtime panic with `message` unless `condition` holds.
+    pub(crate) fn build_runtime_check(
+        &mut self,
+        condition: IntValue<'ctx>,
+        message: &str,
+    ) -> Result<()> {
+        let function = self
+            .builder
+            .get_insert_block()
+            .and_then(|block| block.get_parent())
+            .ok_or_else(|| anyhow!("runtime check emitted outside of a function"))?;
+        let ok_bb = self.context.append_basic_block(function, "check_ok");
+        let fail_bb = self.context.append_basic_block(function, "check_failed");
+        self.builder
+            .build_conditional_branch(condition, ok_bb, fail_bb)?;
+
+        self.builder.position_at_end(fail_bb);
+        let panic_fn = self.get_or_declare_ffi_function("panic")?;
+        let message = self.builder.build_global_string_ptr(message, "panic_msg")?;
+        self.builder
+            .build_call(panic_fn, &[message.as_pointer_value().into()], "")?;
+        self.builder.build_unreachable()?;
+
+        self.builder.position_at_end(ok_bb);
+        Ok(())
+    }
+
+    pub(crate) fn list_get_target(&self, ty: &OtterType) -> Result<(&'static str, OtterType)> {
+        match ty {
+            OtterType::Str => Ok(("list.get", OtterType::Str)),
+            int if int.is_integer() => Ok(("list.get_int", OtterType::I64)),
+            OtterType::F32 | OtterType::F64 => Ok(("list.get_float", OtterType::F64)),
+            OtterType::Bool => Ok(("list.get_bool", OtterType::Bool)),
+            OtterType::List(_) => Ok(("list.get_list", ty.clone())),
+            OtterType::Map => Ok(("list.get_map", OtterType::Map)),
+            OtterType::Opaque => Ok(("list.get_list", OtterType::opaque_list())),
+            OtterType::Function { .. } | OtterType::Dyn(_) => {
+                Ok(("list.get_list", OtterType::Opaque))
             }
-        } else {
-            bail!("Expected If expression");
+            _ => bail!("unsupported list element type: {:?}", ty),
         }
     }
 
-    fn build_string_concat(
-        &mut self,
-        lhs: EvaluatedValue<'ctx>,
-        rhs: EvaluatedValue<'ctx>,
-    ) -> Result<EvaluatedValue<'ctx>> {
-        let left_ptr = self.ensure_string_value(lhs)?;
-        let right_ptr = self.ensure_string_value(rhs)?;
-        let result = self.call_ffi_returning_value(
-            "std.strings.concat",
-            vec![left_ptr, right_ptr],
-            "str_concat",
-        )?;
-        Ok(EvaluatedValue::with_value(result, OtterType::Str))
+    pub(crate) fn map_get_target(&self, ty: &OtterType) -> Result<(&'static str, OtterType)> {
+        match ty {
+            OtterType::Str => Ok(("map.get", OtterType::Str)),
+            int if int.is_integer() => Ok(("map.get_int", OtterType::I64)),
+            OtterType::F32 | OtterType::F64 => Ok(("map.get_float", OtterType::F64)),
+            OtterType::Bool => Ok(("map.get_bool", OtterType::Bool)),
+            OtterType::List(_) => Ok(("map.get_list", ty.clone())),
+            OtterType::Map => Ok(("map.get_map", OtterType::Map)),
+            OtterType::Opaque => Ok(("map.get_list", OtterType::opaque_list())),
+            OtterType::Function { .. } | OtterType::Dyn(_) => {
+                Ok(("map.get_list", OtterType::Opaque))
+            }
+            _ => bail!("unsupported map value type: {:?}", ty),
+        }
     }
 
     fn eval_array_expr(
@@ -2210,28 +4322,70 @@ impl<'ctx> Compiler<'ctx> {
             .ok_or_else(|| anyhow!("list creation returned void"))?
             .into_int_value();
 
+        let list_ty = expr_type
+            .and_then(|ty| self.typeinfo_to_otter_type(ty))
+            .unwrap_or_else(OtterType::opaque_list);
+        // Structs in a list of trait objects are boxed one by one
+        let dyn_element = list_ty
+            .list_element()
+            .filter(|ty| matches!(ty, OtterType::Dyn(_)))
+            .cloned();
+
         // Append each element to the list
         for (idx, elem) in elements.iter().enumerate() {
             let elem_val = self.eval_expr(elem.as_ref(), ctx)?;
-            let elem_value = elem_val
+            let mut elem_value = elem_val
                 .value
                 .ok_or_else(|| anyhow!("array element {} produced no value", idx))?;
-            self.append_value_to_list(handle, elem_value, elem_val.ty, &format!("append_{}", idx))?;
+            let mut elem_ty = elem_val.ty;
+            if let Some(dyn_ty) = &dyn_element {
+                elem_value = self.coerce_value(elem_value, elem_ty, dyn_ty.clone())?;
+                elem_ty = dyn_ty.clone();
+            }
+            self.append_value_to_list(handle, elem_value, elem_ty, &format!("append_{}", idx))?;
         }
 
-        let list_ty = expr_type
-            .and_then(|ty| self.typeinfo_to_otter_type(ty))
-            .unwrap_or_else(OtterType::opaque_list);
         Ok(EvaluatedValue::with_value(handle.into(), list_ty))
     }
 
-    fn append_value_to_list(
+    fn eval_tuple_expr(
+        &mut self,
+        elements: &[Node<Expr>],
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<EvaluatedValue<'ctx>> {
+        let mut values = Vec::with_capacity(elements.len());
+        let mut types = Vec::with_capacity(elements.len());
+        for (idx, elem) in elements.iter().enumerate() {
+            let elem_val = self.eval_expr(elem.as_ref(), ctx)?;
+            let value = match elem_val.value {
+                Some(value) => value,
+                // Unit elements still occupy an i8 slot so that indices line up with the type
+                None if elem_val.ty == OtterType::Unit => {
+                    self.context.i8_type().const_zero().into()
+                }
+                None => bail!("tuple element {} produced no value", idx),
+            };
+            values.push(value);
+            types.push(elem_val.ty);
+        }
+        let tuple_ty = OtterType::Tuple(types);
+        let tuple = self.build_tuple_value(&values, tuple_ty.clone())?;
+        Ok(EvaluatedValue::with_value(tuple, tuple_ty))
+    }
+
+    pub(crate) fn append_value_to_list(
         &mut self,
         list_handle: IntValue<'ctx>,
         value: BasicValueEnum<'ctx>,
         value_ty: OtterType,
         label: &str,
     ) -> Result<()> {
+        // Tuples and structs are aggregates; the list keeps a handle to a heap copy instead
+        let (value, value_ty) = if matches!(value_ty, OtterType::Tuple(_) | OtterType::Struct(_)) {
+            (self.box_tuple(value)?.into(), OtterType::Opaque)
+        } else {
+            (value, value_ty)
+        };
         let (append_fn_name, expected_ty) = self.list_append_target(&value_ty)?;
         let append_fn = self.get_or_declare_ffi_function(append_fn_name)?;
         let coerced_value = self.coerce_type(value, value_ty, expected_ty)?;
@@ -2242,15 +4396,46 @@ impl<'ctx> Compiler<'ctx> {
         Ok(())
     }
 
+    /// `list.append(value)`: the value is evaluated once and stored like an element of a list
+    /// literal, boxed into a trait object when the list holds `dyn` values
+    fn build_list_append(
+        &mut self,
+        list: EvaluatedValue<'ctx>,
+        arg: &Expr,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<EvaluatedValue<'ctx>> {
+        let handle = list
+            .value
+            .ok_or_else(|| anyhow!("cannot append to a list without a value"))?
+            .into_int_value();
+        let arg_val = self.eval_expr(arg, ctx)?;
+        let mut value = arg_val
+            .value
+            .ok_or_else(|| anyhow!("cannot append a unit value"))?;
+        let mut value_ty = arg_val.ty;
+        if let Some(dyn_ty @ OtterType::Dyn(_)) = list.ty.list_element().cloned() {
+            value = self.coerce_value(value, value_ty, dyn_ty.clone())?;
+            value_ty = dyn_ty;
+        }
+        self.append_value_to_list(handle, value, value_ty, "list_append")?;
+        Ok(EvaluatedValue {
+            ty: OtterType::Unit,
+            value: None,
+        })
+    }
+
     fn list_append_target(&self, ty: &OtterType) -> Result<(&'static str, OtterType)> {
         match ty {
             OtterType::Str => Ok(("append<list,string>", OtterType::Str)),
-            OtterType::I32 | OtterType::I64 => Ok(("append<list,int>", OtterType::I64)),
-            OtterType::F64 => Ok(("append<list,float>", OtterType::F64)),
+            int if int.is_integer() => Ok(("append<list,int>", OtterType::I64)),
+            OtterType::F32 | OtterType::F64 => Ok(("append<list,float>", OtterType::F64)),
             OtterType::Bool => Ok(("append<list,bool>", OtterType::Bool)),
             OtterType::List(_) => Ok(("append<list,list>", ty.clone())),
             OtterType::Map => Ok(("append<list,map>", OtterType::Map)),
             OtterType::Opaque => Ok(("append<list,list>", OtterType::opaque_list())),
+            OtterType::Function { .. } | OtterType::Dyn(_) => {
+                Ok(("append<list,list>", OtterType::Opaque))
+            }
             _ => bail!("unsupported array element type: {:?}", ty),
         }
     }
@@ -2279,9 +4464,9 @@ impl<'ctx> Compiler<'ctx> {
         let element_ty = self
             .comprehension_element_type(full_expr)
             .or_else(|| self.list_element_type(iterable))
-            .or_else(|| self.infer_comprehension_var_type(var, element, condition))
-            .unwrap_or(OtterType::Opaque);
-        let list_ty_from_element = OtterType::list_of(element_ty.clone());
+            .or_else(|| self.infer_comprehension_var_type(var, element, condition));
+        let list_ty_from_element =
+            OtterType::list_of(element_ty.clone().unwrap_or(OtterType::Opaque));
 
         let result_list_ty = self
             .expr_type(full_expr)
@@ -2289,48 +4474,15 @@ impl<'ctx> Compiler<'ctx> {
             .unwrap_or_else(|| list_ty_from_element.clone());
 
         let result = (|| -> Result<EvaluatedValue<'ctx>> {
-            let iterable_val = self.eval_expr(iterable, ctx)?;
-            if !matches!(iterable_val.ty, OtterType::List(_)) {
-                bail!(
-                    "list comprehension expects list iterable, got {:?}",
-                    iterable_val.ty
-                );
-            }
-            let iterable_handle = iterable_val
-                .value
-                .ok_or_else(|| anyhow!("list comprehension iterable produced no value"))?;
-
-            let iter_create_fn = self.get_or_declare_ffi_function("__otter_iter_array")?;
-            let iter_has_next_fn =
-                self.get_or_declare_ffi_function("__otter_iter_has_next_array")?;
-            let iter_next_fn = self.get_or_declare_ffi_function("__otter_iter_next_array")?;
-            let iter_free_fn = self.get_or_declare_ffi_function("__otter_iter_free_array")?;
-
-            let iter_handle = self
-                .builder
-                .build_call(iter_create_fn, &[iterable_handle.into()], "list_comp_iter")?
-                .try_as_basic_value()
-                .left()
-                .ok_or_else(|| anyhow!("iterator creation failed"))?;
-
             let function = self
                 .builder
                 .get_insert_block()
                 .and_then(|b| b.get_parent())
                 .ok_or_else(|| anyhow!("no active function for list comprehension"))?;
 
-            let var_alloca = self.create_entry_block_alloca(function, var, element_ty.clone())?;
-            ctx.insert(
-                var.to_string(),
-                Variable {
-                    ptr: var_alloca,
-                    ty: element_ty.clone(),
-                },
-            );
-            inserted_new_binding = true;
+            let cursor = self.open_iter_cursor(iterable, element_ty, function, ctx)?;
 
             let loop_cond_bb = self.context.append_basic_block(function, "listcomp_cond");
-            let loop_body_bb = self.context.append_basic_block(function, "listcomp_body");
             let loop_cleanup_bb = self
                 .context
                 .append_basic_block(function, "listcomp_cleanup");
@@ -2339,31 +4491,9 @@ impl<'ctx> Compiler<'ctx> {
             self.builder.build_unconditional_branch(loop_cond_bb)?;
 
             self.builder.position_at_end(loop_cond_bb);
-            let has_next_call = self.builder.build_call(
-                iter_has_next_fn,
-                &[iter_handle.into()],
-                "listcomp_has_next",
-            )?;
-            let has_next = has_next_call
-                .try_as_basic_value()
-                .left()
-                .ok_or_else(|| anyhow!("iterator has_next returned void"))?
-                .into_int_value();
-            self.builder
-                .build_conditional_branch(has_next, loop_body_bb, loop_cleanup_bb)?;
-
-            self.builder.position_at_end(loop_body_bb);
-            let next_call =
-                self.builder
-                    .build_call(iter_next_fn, &[iter_handle.into()], "listcomp_next")?;
-            let element_val = next_call
-                .try_as_basic_value()
-                .left()
-                .ok_or_else(|| anyhow!("iterator next returned void"))?;
-            let decoded = self.decode_and_convert_tagged_value(element_val, &element_ty)?;
-            if let Some(value) = decoded {
-                self.builder.build_store(var_alloca, value)?;
-            }
+            let element_val = self.advance_iter_cursor(&cursor, loop_cleanup_bb, function, ctx)?;
+            self.bind_comprehension_var(var, element_val, function, ctx)?;
+            inserted_new_binding = true;
 
             let loop_continue_bb = self
                 .context
@@ -2392,8 +4522,7 @@ impl<'ctx> Compiler<'ctx> {
             self.builder.build_unconditional_branch(loop_cond_bb)?;
 
             self.builder.position_at_end(loop_cleanup_bb);
-            self.builder
-                .build_call(iter_free_fn, &[iter_handle.into()], "listcomp_free")?;
+            self.close_iter_cursor(&cursor)?;
             self.builder.build_unconditional_branch(loop_exit_bb)?;
 
             self.builder.position_at_end(loop_exit_bb);
@@ -2414,6 +4543,28 @@ impl<'ctx> Compiler<'ctx> {
         result
     }
 
+    /// Binds the comprehension variable to the current element in a slot of its own
+    fn bind_comprehension_var(
+        &mut self,
+        var: &str,
+        element: EvaluatedValue<'ctx>,
+        function: FunctionValue<'ctx>,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<()> {
+        let var_alloca = self.create_entry_block_alloca(function, var, element.ty.clone())?;
+        if let Some(value) = element.value {
+            self.builder.build_store(var_alloca, value)?;
+        }
+        ctx.insert(
+            var.to_string(),
+            Variable {
+                ptr: var_alloca,
+                ty: element.ty,
+            },
+        );
+        Ok(())
+    }
+
     fn emit_list_comprehension_append(
         &mut self,
         result_handle: IntValue<'ctx>,
@@ -2481,36 +4632,10 @@ impl<'ctx> Compiler<'ctx> {
         let mut inserted_new_binding = false;
 
         let result = (|| -> Result<EvaluatedValue<'ctx>> {
-            let iterable_val = self.eval_expr(iterable, ctx)?;
-            if !matches!(iterable_val.ty, OtterType::List(_)) {
-                bail!(
-                    "dict comprehension expects list iterable, got {:?}",
-                    iterable_val.ty
-                );
-            }
-
-            let iterable_handle = iterable_val
-                .value
-                .ok_or_else(|| anyhow!("dict comprehension iterable produced no value"))?;
-
-            let iter_create_fn = self.get_or_declare_ffi_function("__otter_iter_array")?;
-            let iter_has_next_fn =
-                self.get_or_declare_ffi_function("__otter_iter_has_next_array")?;
-            let iter_next_fn = self.get_or_declare_ffi_function("__otter_iter_next_array")?;
-            let iter_free_fn = self.get_or_declare_ffi_function("__otter_iter_free_array")?;
-
-            let iter_handle = self
-                .builder
-                .build_call(iter_create_fn, &[iterable_handle.into()], "dict_comp_iter")?
-                .try_as_basic_value()
-                .left()
-                .ok_or_else(|| anyhow!("iterator creation failed"))?;
-
             let element_ty = self
                 .comprehension_element_type(full_expr)
                 .or_else(|| self.list_element_type(iterable))
-                .or_else(|| self.infer_comprehension_var_type(var, value, condition))
-                .unwrap_or(OtterType::Opaque);
+                .or_else(|| self.infer_comprehension_var_type(var, value, condition));
 
             let function = self
                 .builder
@@ -2518,18 +4643,9 @@ impl<'ctx> Compiler<'ctx> {
                 .and_then(|b| b.get_parent())
                 .ok_or_else(|| anyhow!("no active function for dict comprehension"))?;
 
-            let var_alloca = self.create_entry_block_alloca(function, var, element_ty.clone())?;
-            ctx.insert(
-                var.to_string(),
-                Variable {
-                    ptr: var_alloca,
-                    ty: element_ty.clone(),
-                },
-            );
-            inserted_new_binding = true;
+            let cursor = self.open_iter_cursor(iterable, element_ty, function, ctx)?;
 
             let loop_cond_bb = self.context.append_basic_block(function, "dictcomp_cond");
-            let loop_body_bb = self.context.append_basic_block(function, "dictcomp_body");
             let loop_cleanup_bb = self
                 .context
                 .append_basic_block(function, "dictcomp_cleanup");
@@ -2538,31 +4654,9 @@ impl<'ctx> Compiler<'ctx> {
             self.builder.build_unconditional_branch(loop_cond_bb)?;
 
             self.builder.position_at_end(loop_cond_bb);
-            let has_next_call = self.builder.build_call(
-                iter_has_next_fn,
-                &[iter_handle.into()],
-                "dictcomp_has_next",
-            )?;
-            let has_next = has_next_call
-                .try_as_basic_value()
-                .left()
-                .ok_or_else(|| anyhow!("iterator has_next returned void"))?
-                .into_int_value();
-            self.builder
-                .build_conditional_branch(has_next, loop_body_bb, loop_cleanup_bb)?;
-
-            self.builder.position_at_end(loop_body_bb);
-            let next_call =
-                self.builder
-                    .build_call(iter_next_fn, &[iter_handle.into()], "dictcomp_next")?;
-            let element_val = next_call
-                .try_as_basic_value()
-                .left()
-                .ok_or_else(|| anyhow!("iterator next returned void"))?;
-            let decoded = self.decode_and_convert_tagged_value(element_val, &element_ty)?;
-            if let Some(value) = decoded {
-                self.builder.build_store(var_alloca, value)?;
-            }
+            let element_val = self.advance_iter_cursor(&cursor, loop_cleanup_bb, function, ctx)?;
+            self.bind_comprehension_var(var, element_val, function, ctx)?;
+            inserted_new_binding = true;
 
             let loop_continue_bb = self
                 .context
@@ -2591,8 +4685,7 @@ impl<'ctx> Compiler<'ctx> {
             self.builder.build_unconditional_branch(loop_cond_bb)?;
 
             self.builder.position_at_end(loop_cleanup_bb);
-            self.builder
-                .build_call(iter_free_fn, &[iter_handle.into()], "dictcomp_free")?;
+            self.close_iter_cursor(&cursor)?;
             self.builder.build_unconditional_branch(loop_exit_bb)?;
 
             self.builder.position_at_end(loop_exit_bb);
@@ -2620,8 +4713,7 @@ impl<'ctx> Compiler<'ctx> {
         value_expr: &Expr,
         ctx: &mut FunctionContext<'ctx>,
     ) -> Result<()> {
-        let key_eval = self.eval_expr(key_expr, ctx)?;
-        let key_value = self.ensure_string_value(key_eval)?;
+        let key_value = self.map_key_value(map_handle.into(), key_expr, ctx)?;
 
         let value_eval = self.eval_expr(value_expr, ctx)?;
         let raw_value = value_eval
@@ -2641,15 +4733,18 @@ impl<'ctx> Compiler<'ctx> {
         Ok(())
     }
 
-    fn map_set_target(&self, ty: &OtterType) -> Result<(&'static str, OtterType)> {
+    pub(crate) fn map_set_target(&self, ty: &OtterType) -> Result<(&'static str, OtterType)> {
         match ty {
             OtterType::Str => Ok(("map.set", OtterType::Str)),
-            OtterType::I32 | OtterType::I64 => Ok(("set<map,int>", OtterType::I64)),
-            OtterType::F64 => Ok(("set<map,float>", OtterType::F64)),
+            int if int.is_integer() => Ok(("set<map,int>", OtterType::I64)),
+            OtterType::F32 | OtterType::F64 => Ok(("set<map,float>", OtterType::F64)),
             OtterType::Bool => Ok(("set<map,bool>", OtterType::Bool)),
             OtterType::List(_) => Ok(("set<map,list>", ty.clone())),
             OtterType::Map => Ok(("set<map,map>", OtterType::Map)),
             OtterType::Opaque => Ok(("set<map,list>", OtterType::opaque_list())),
+            OtterType::Function { .. } | OtterType::Dyn(_) => {
+                Ok(("set<map,list>", OtterType::Opaque))
+            }
             _ => bail!("unsupported dict comprehension value type: {:?}", ty),
         }
     }
@@ -2683,18 +4778,34 @@ impl<'ctx> Compiler<'ctx> {
             Expr::Binary { left, right, .. } => self
                 .find_identifier_type_in_expr(left.as_ref().as_ref(), var)
                 .or_else(|| self.find_identifier_type_in_expr(right.as_ref().as_ref(), var)),
-            Expr::Unary { expr, .. } | Expr::Await(expr) | Expr::Spawn(expr) => {
-                self.find_identifier_type_in_expr(expr.as_ref().as_ref(), var)
-            }
-            Expr::Call { func, args } => self
+            Expr::Unary { expr, .. }
+            | Expr::Cast { expr, .. }
+            | Expr::Await(expr)
+            | Expr::Spawn(expr)
+            | Expr::Try(expr)
+            | Expr::Spread(expr) => self.find_identifier_type_in_expr(expr.as_ref().as_ref(), var),
+            Expr::Call {
+                func, args, kwargs, ..
+            } => self
                 .find_identifier_type_in_expr(func.as_ref().as_ref(), var)
                 .or_else(|| {
                     args.iter()
+                        .chain(kwargs.iter().map(|(_, value)| value))
                         .find_map(|arg| self.find_identifier_type_in_expr(arg.as_ref(), var))
                 }),
             Expr::Member { object, .. } => {
                 self.find_identifier_type_in_expr(object.as_ref().as_ref(), var)
             }
+            Expr::Index { object, index } => self
+                .find_identifier_type_in_expr(object.as_ref().as_ref(), var)
+                .or_else(|| self.find_identifier_type_in_expr(index.as_ref().as_ref(), var)),
+            Expr::Slice { object, start, end } => self
+                .find_identifier_type_in_expr(object.as_ref().as_ref(), var)
+                .or_else(|| {
+                    [start, end].into_iter().flatten().find_map(|bound| {
+                        self.find_identifier_type_in_expr(bound.as_ref().as_ref(), var)
+                    })
+                }),
             Expr::If {
                 cond,
                 then_branch,
@@ -2724,7 +4835,7 @@ impl<'ctx> Compiler<'ctx> {
             Expr::Range { start, end } => self
                 .find_identifier_type_in_expr(start.as_ref().as_ref(), var)
                 .or_else(|| self.find_identifier_type_in_expr(end.as_ref().as_ref(), var)),
-            Expr::Array(elements) => elements
+            Expr::Tuple(elements) | Expr::Array(elements) => elements
                 .iter()
                 .find_map(|elem| self.find_identifier_type_in_expr(elem.as_ref(), var)),
             Expr::Dict(pairs) => pairs.iter().find_map(|(key, value)| {
@@ -2771,13 +4882,24 @@ impl<'ctx> Compiler<'ctx> {
                         })
                 }
             }
-            Expr::FString { parts } => parts.iter().find_map(|part| match part.as_ref() {
+            Expr::FString { parts, .. } => parts.iter().find_map(|part| match part.as_ref() {
                 FStringPart::Expr(expr) => self.find_identifier_type_in_expr(expr.as_ref(), var),
                 _ => None,
             }),
             Expr::Struct { fields, .. } => fields
                 .iter()
                 .find_map(|(_, expr)| self.find_identifier_type_in_expr(expr.as_ref(), var)),
+            Expr::Lambda { params, body, .. } => {
+                if params
+                    .iter()
+                    .any(|param| param.as_ref().name.as_ref() == var)
+                {
+                    None
+                } else {
+                    self.find_identifier_type_in_block(body.as_ref(), var)
+                }
+            }
+            Expr::Loop { body, .. } => self.find_identifier_type_in_block(body.as_ref(), var),
         }
     }
 
@@ -2792,17 +4914,26 @@ impl<'ctx> Compiler<'ctx> {
         match stmt {
             Statement::Expr(expr)
             | Statement::Return(Some(expr))
-            | Statement::Let { expr, .. }
-            | Statement::Assignment { expr, .. } => {
-                self.find_identifier_type_in_expr(expr.as_ref(), var)
+            | Statement::Raise(Some(expr))
+            | Statement::Defer(expr)
+            | Statement::Break {
+                value: Some(expr), ..
             }
+            | Statement::Let { expr, .. } => self.find_identifier_type_in_expr(expr.as_ref(), var),
+            Statement::Assignment { target, expr, .. } => self
+                .find_identifier_type_in_expr(target.as_ref(), var)
+                .or_else(|| self.find_identifier_type_in_expr(expr.as_ref(), var)),
             Statement::Return(None)
-            | Statement::Break
-            | Statement::Continue
+            | Statement::Raise(None)
+            | Statement::Break { value: None, .. }
+            | Statement::Continue { .. }
             | Statement::Pass
             | Statement::Struct { .. }
             | Statement::Enum { .. }
+            | Statement::Trait { .. }
+            | Statement::Impl { .. }
             | Statement::TypeAlias { .. }
+            | Statement::Const { .. }
             | Statement::Use { .. }
             | Statement::PubUse { .. }
             | Statement::Function(_) => None,
@@ -2825,17 +4956,39 @@ impl<'ctx> Compiler<'ctx> {
                         .as_ref()
                         .and_then(|block| self.find_identifier_type_in_block(block.as_ref(), var))
                 }),
-            Statement::While { cond, body } => self
+            Statement::While { cond, body, .. } => self
                 .find_identifier_type_in_expr(cond.as_ref(), var)
                 .or_else(|| self.find_identifier_type_in_block(body.as_ref(), var)),
+            Statement::With { expr, body, .. } => self
+                .find_identifier_type_in_expr(expr.as_ref(), var)
+                .or_else(|| self.find_identifier_type_in_block(body.as_ref(), var)),
             Statement::For { iterable, body, .. } => self
                 .find_identifier_type_in_expr(iterable.as_ref(), var)
                 .or_else(|| self.find_identifier_type_in_block(body.as_ref(), var)),
+            Statement::Try {
+                body,
+                handlers,
+                finally_block,
+            } => self
+                .find_identifier_type_in_block(body.as_ref(), var)
+                .or_else(|| {
+                    handlers.iter().find_map(|handler| {
+                        self.find_identifier_type_in_block(handler.as_ref().body.as_ref(), var)
+                    })
+                })
+                .or_else(|| {
+                    finally_block
+                        .as_ref()
+                        .and_then(|block| self.find_identifier_type_in_block(block.as_ref(), var))
+                }),
             Statement::Block(block) => self.find_identifier_type_in_block(block.as_ref(), var),
         }
     }
 
-    fn ensure_string_value(&mut self, value: EvaluatedValue<'ctx>) -> Result<BasicValueEnum<'ctx>> {
+    pub(crate) fn ensure_string_value(
+        &mut self,
+        value: EvaluatedValue<'ctx>,
+    ) -> Result<BasicValueEnum<'ctx>> {
         let EvaluatedValue { ty, value } = value;
         let base_value = value.ok_or_else(|| anyhow!("expected value for string operation"))?;
 
@@ -2844,17 +4997,21 @@ impl<'ctx> Compiler<'ctx> {
             OtterType::I64 => {
                 self.call_ffi_returning_value("std.strings.format_int", vec![base_value], "fmt_int")
             }
-            OtterType::I32 => {
-                let int_val = base_value.into_int_value();
-                let widened = self.builder.build_int_s_extend(
-                    int_val,
-                    self.context.i64_type(),
-                    "i32_to_i64",
-                )?;
+            OtterType::U64 => self.call_ffi_returning_value(
+                "std.strings.format_uint",
+                vec![base_value],
+                "fmt_uint",
+            ),
+            ref int if int.is_integer() => {
+                let widened = self.convert_number(base_value, int, &OtterType::I64)?;
+                self.call_ffi_returning_value("std.strings.format_int", vec![widened], "fmt_int")
+            }
+            OtterType::F32 => {
+                let widened = self.convert_number(base_value, &ty, &OtterType::F64)?;
                 self.call_ffi_returning_value(
-                    "std.strings.format_int",
-                    vec![widened.into()],
-                    "fmt_int",
+                    "std.strings.format_float",
+                    vec![widened],
+                    "fmt_float",
                 )
             }
             OtterType::F64 => self.call_ffi_returning_value(
@@ -2882,7 +5039,7 @@ impl<'ctx> Compiler<'ctx> {
         }
     }
 
-    fn call_ffi_returning_value(
+    pub(crate) fn call_ffi_returning_value(
         &mut self,
         name: &str,
         args: Vec<BasicValueEnum<'ctx>>,
@@ -2920,6 +5077,13 @@ impl<'ctx> Compiler<'ctx> {
             } else {
                 return Ok(None);
             }
+            // An associated function like `Shape.unit()` also returns the enum
+            if self
+                .declared_functions
+                .contains_key(&format!("{}_{}", enum_name, field))
+            {
+                return Ok(None);
+            }
             let mut evaluated_args = Vec::with_capacity(args.len());
             for arg in args {
                 evaluated_args.push(self.eval_expr(arg.as_ref(), ctx)?);
@@ -2960,8 +5124,8 @@ impl<'ctx> Compiler<'ctx> {
             let field_types: Vec<TypeInfo> = evaluated_args
                 .iter()
                 .map(|val| match val.ty.clone() {
-                    OtterType::I64 => TypeInfo::I64,
-                    OtterType::F64 => TypeInfo::F64,
+                    ty if ty.is_integer() => TypeInfo::I64,
+                    OtterType::F32 | OtterType::F64 => TypeInfo::F64,
                     OtterType::Bool => TypeInfo::Bool,
                     OtterType::Str => TypeInfo::Str,
                     _ => TypeInfo::Unknown,
@@ -3009,7 +5173,11 @@ impl<'ctx> Compiler<'ctx> {
         })
     }
 
-    fn resolve_member_function_name(&self, object: &Expr, field: &str) -> Option<String> {
+    pub(crate) fn resolve_member_function_name(
+        &self,
+        object: &Expr,
+        field: &str,
+    ) -> Option<String> {
         if let Some(module) = self.module_path_from_expr(object) {
             let candidate = format!("{}.{}", module, field);
             if self.symbol_registry.contains(&candidate)
@@ -3039,6 +5207,234 @@ impl<'ctx> Compiler<'ctx> {
         }
     }
 
+    /// Lowers `expr?`: a `Result`/`Option` operand that holds `Err`/`None` is returned from
+    /// the enclosing function, otherwise evaluation continues with the `Ok`/`Some` payload.
+    fn eval_try_expr(
+        &mut self,
+        full_expr: &Expr,
+        inner: &Expr,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<EvaluatedValue<'ctx>> {
+        let operand_type = self
+            .expr_type(inner)
+            .cloned()
+            .ok_or_else(|| anyhow!("missing type information for `?` operand"))?;
+        let TypeInfo::Enum { name, args, .. } = &operand_type else {
+            bail!(
+                "`?` expects a Result or Option, got {}",
+                operand_type.display_name()
+            );
+        };
+        let (success, failure) = match name.as_str() {
+            "Result" => ("Ok", "Err"),
+            "Option" => ("Some", "None"),
+            _ => bail!("`?` expects a Result or Option, got {}", name),
+        };
+        let layout = self
+            .enum_layout(name)
+            .ok_or_else(|| anyhow!("Missing enum layout for {name}"))?;
+        let success_tag = layout
+            .tag_of(success)
+            .ok_or_else(|| anyhow!("Unknown variant {name}.{success}"))?;
+        let payload_type = layout
+            .fields_of(success, args)
+            .and_then(|fields| fields.into_iter().next());
+        let error_type = layout
+            .fields_of(failure, args)
+            .and_then(|fields| fields.into_iter().next());
+
+        let handle = self
+            .eval_expr(inner, ctx)?
+            .value
+            .ok_or_else(|| anyhow!("`?` operand has no value"))?;
+        let get_tag_fn = self.get_or_declare_ffi_function("runtime.enum.get_tag")?;
+        let tag = self
+            .builder
+            .build_call(get_tag_fn, &[handle.into()], "tag")?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("runtime.enum.get_tag returned void"))?
+            .into_int_value();
+        let success_tag = self.context.i64_type().const_int(success_tag as u64, false);
+        let is_success =
+            self.builder
+                .build_int_compare(IntPredicate::EQ, tag, success_tag, "try_is_success")?;
+
+        let function = self
+            .builder
+            .get_insert_block()
+            .and_then(|block| block.get_parent())
+            .ok_or_else(|| anyhow!("`?` used outside of a function"))?;
+        let propagate_bb = self.context.append_basic_block(function, "try_propagate");
+        let continue_bb = self.context.append_basic_block(function, "try_continue");
+        self.builder
+            .build_conditional_branch(is_success, continue_bb, propagate_bb)?;
+
+        self.builder.position_at_end(propagate_bb);
+        let propagated = match &error_type {
+            Some(error_type) => self.adapt_propagated_error(handle, error_type, ctx)?,
+            None => handle,
+        };
+        self.run_finally_blocks(0, function, ctx)?;
+        if self
+            .builder
+            .get_insert_block()
+            .and_then(|block| block.get_terminator())
+            .is_none()
+        {
+            self.builder.build_return(Some(&propagated))?;
+        }
+
+        self.builder.position_at_end(continue_bb);
+        let Some(payload_type) = payload_type else {
+            return Ok(EvaluatedValue {
+                ty: OtterType::Unit,
+                value: None,
+            });
+        };
+        let payload = self.load_enum_field(handle, 0, &payload_type)?;
+        let payload_type = self.expr_type(full_expr).cloned().unwrap_or(payload_type);
+        self.retype_enum_payload(payload, &payload_type)
+    }
+
+    /// Converts the error inside a propagated `Err` handle to the enclosing function's error
+    /// type by calling the `into_error` method of the source error type. Handles whose error
+    /// type already matches are returned unchanged.
+    fn adapt_propagated_error(
+        &mut self,
+        handle: BasicValueEnum<'ctx>,
+        source_error: &TypeInfo,
+        ctx: &FunctionContext<'ctx>,
+    ) -> Result<BasicValueEnum<'ctx>> {
+        let target_error = match &ctx.return_type {
+            Some(
+                TypeInfo::Generic { base, args }
+                | TypeInfo::Enum {
+                    name: base, args, ..
+                },
+            ) if base == "Result" => self
+                .enum_layout(base)
+                .and_then(|layout| layout.fields_of("Err", args))
+                .and_then(|fields| fields.into_iter().next()),
+            _ => None,
+        };
+        let Some(target_error) = target_error else {
+            return Ok(handle);
+        };
+        let source_name = nominal_type_name(source_error);
+        if source_name == nominal_type_name(&target_error) {
+            return Ok(handle);
+        }
+        let hook_name = format!("{}_into_error", source_name);
+        let Some(hook) = self.declared_functions.get(&hook_name).copied() else {
+            return Ok(handle);
+        };
+
+        let error = self.load_enum_field(handle, 0, source_error)?;
+        let error = self.retype_enum_payload(error, source_error)?;
+        let error_arg: BasicMetadataValueEnum = if self.receiver_methods.contains(&hook_name) {
+            self.spill_receiver(error)?.into()
+        } else {
+            let error_value = error
+                .value
+                .ok_or_else(|| anyhow!("propagated error has no value"))?;
+            match self
+                .function_param_types
+                .get(&hook_name)
+                .and_then(|params| params.first())
+                .cloned()
+            {
+                Some(param_ty) => self.coerce_value(error_value, error.ty, param_ty)?,
+                None => error_value,
+            }
+            .into()
+        };
+        let converted = self
+            .builder
+            .build_call(hook, &[error_arg], "into_error")?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("{} returned void", hook_name))?;
+        let converted_ty = self
+            .function_return_types
+            .get(&hook_name)
+            .cloned()
+            .unwrap_or(OtterType::Opaque);
+
+        let err_tag = self
+            .enum_layout("Result")
+            .and_then(|layout| layout.tag_of("Err"))
+            .ok_or_else(|| anyhow!("Unknown variant Result.Err"))?;
+        let rebuilt = self.create_enum_instance(
+            "Result",
+            "Err",
+            err_tag,
+            &[target_error],
+            vec![EvaluatedValue::with_value(converted, converted_ty)],
+        )?;
+        rebuilt
+            .value
+            .ok_or_else(|| anyhow!("failed to rebuild propagated error"))
+    }
+
+    /// Reads field `index` of an enum handle with the getter that matches its declared type.
+    /// Pointer-sized fields come back as opaque handles.
+    pub(crate) fn load_enum_field(
+        &mut self,
+        handle: BasicValueEnum<'ctx>,
+        index: u32,
+        field_type: &TypeInfo,
+    ) -> Result<EvaluatedValue<'ctx>> {
+        let (getter, ty) = match enum_field_kind(field_type) {
+            EnumFieldKind::Int => ("runtime.enum.get_i64", OtterType::I64),
+            EnumFieldKind::Float => ("runtime.enum.get_f64", OtterType::F64),
+            EnumFieldKind::Bool => ("runtime.enum.get_bool", OtterType::Bool),
+            EnumFieldKind::Ptr => ("runtime.enum.get_ptr", OtterType::Opaque),
+        };
+        let get_field_fn = self.get_or_declare_ffi_function(getter)?;
+        let index_val = self.context.i64_type().const_int(index as u64, false);
+        let value = self
+            .builder
+            .build_call(get_field_fn, &[handle.into(), index_val.into()], "field")?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("{} returned void", getter))?;
+        Ok(EvaluatedValue::with_value(value, ty))
+    }
+
+    /// Gives an opaque enum payload the representation of its declared type: handles are
+    /// reinterpreted, and boxed tuples and structs are loaded back.
+    pub(crate) fn retype_enum_payload(
+        &mut self,
+        payload: EvaluatedValue<'ctx>,
+        field_type: &TypeInfo,
+    ) -> Result<EvaluatedValue<'ctx>> {
+        let (OtterType::Opaque, Some(value)) = (&payload.ty, payload.value) else {
+            return Ok(payload);
+        };
+        match self.typeinfo_to_otter_type(field_type) {
+            Some(OtterType::Str) => {
+                let ptr = self.builder.build_int_to_ptr(
+                    value.into_int_value(),
+                    self.string_ptr_type,
+                    "enum_str",
+                )?;
+                Ok(EvaluatedValue::with_value(ptr.into(), OtterType::Str))
+            }
+            Some(
+                ty @ (OtterType::List(_)
+                | OtterType::Map
+                | OtterType::Function { .. }
+                | OtterType::Dyn(_)),
+            ) => Ok(EvaluatedValue::with_value(value, ty)),
+            Some(ty @ (OtterType::Tuple(_) | OtterType::Struct(_))) => {
+                let value = self.unbox_tuple(value.into_int_value(), ty.clone())?;
+                Ok(EvaluatedValue::with_value(value, ty))
+            }
+            _ => Ok(payload),
+        }
+    }
+
     fn build_enum_value_from_type(
         &mut self,
         enum_type: &TypeInfo,
@@ -3071,7 +5467,7 @@ impl<'ctx> Compiler<'ctx> {
         }
     }
 
-    fn create_enum_instance(
+    pub(crate) fn create_enum_instance(
         &mut self,
         _enum_name: &str,
         _variant_name: &str,
@@ -3155,16 +5551,14 @@ impl<'ctx> Compiler<'ctx> {
         Ok(())
     }
 
-    fn value_as_i64(&mut self, value: EvaluatedValue<'ctx>) -> Result<IntValue<'ctx>> {
+    pub(crate) fn value_as_i64(&mut self, value: EvaluatedValue<'ctx>) -> Result<IntValue<'ctx>> {
         let EvaluatedValue { ty, value } = value;
         let raw = value.ok_or_else(|| anyhow!("missing value for enum field"))?;
         let int_value = match ty {
             OtterType::I64 | OtterType::Opaque => raw.into_int_value(),
-            OtterType::I32 => self.builder.build_int_s_extend(
-                raw.into_int_value(),
-                self.context.i64_type(),
-                "i32_to_i64",
-            )?,
+            ref int if int.is_integer() => self
+                .convert_number(raw, int, &OtterType::I64)?
+                .into_int_value(),
             OtterType::Bool => self.builder.build_int_z_extend(
                 raw.into_int_value(),
                 self.context.i64_type(),
@@ -3175,6 +5569,8 @@ impl<'ctx> Compiler<'ctx> {
                 self.context.i64_type(),
                 "str_ptr_to_int",
             )?,
+            // Tuples and structs are kept as handles to a heap copy, see `box_tuple`
+            OtterType::Tuple(_) | OtterType::Struct(_) => self.box_tuple(raw)?,
             _ => {
                 bail!("cannot convert {:?} to i64 for enum field", ty);
             }
@@ -3192,11 +5588,9 @@ impl<'ctx> Compiler<'ctx> {
                 self.context.f64_type(),
                 "i64_to_f64",
             )?,
-            OtterType::I32 => self.builder.build_signed_int_to_float(
-                raw.into_int_value(),
-                self.context.f64_type(),
-                "i32_to_f64",
-            )?,
+            ref number if number.is_integer() || number.is_float() => self
+                .convert_number(raw, number, &OtterType::F64)?
+                .into_float_value(),
             _ => {
                 bail!("cannot convert {:?} to f64 for enum field", ty);
             }
@@ -3223,11 +5617,32 @@ enum EnumFieldKind {
     Ptr,
 }
 
+/// Whether `func_name` is a function of the `string` runtime module
+fn is_string_module_function(func_name: &str) -> bool {
+    func_name.starts_with("string.")
+}
+
+/// Runtime functions that can leave an exception pending
+fn is_raising_runtime_function(func_name: &str) -> bool {
+    is_string_module_function(func_name) || matches!(func_name, "math.min" | "math.max")
+}
+
+/// The name `?` uses to tell error types apart and to find their `into_error` method.
+fn nominal_type_name(ty: &TypeInfo) -> String {
+    match ty {
+        TypeInfo::Struct { name, .. }
+        | TypeInfo::Enum { name, .. }
+        | TypeInfo::Alias { name, .. }
+        | TypeInfo::Generic { base: name, .. } => name.clone(),
+        other => other.display_name(),
+    }
+}
+
 fn enum_field_kind(field_type: &TypeInfo) -> EnumFieldKind {
     match field_type {
         TypeInfo::Bool => EnumFieldKind::Bool,
-        TypeInfo::I32 | TypeInfo::I64 => EnumFieldKind::Int,
-        TypeInfo::F64 => EnumFieldKind::Float,
+        TypeInfo::F32 | TypeInfo::F64 => EnumFieldKind::Float,
+        ty if ty.is_integer() => EnumFieldKind::Int,
         TypeInfo::Alias { underlying, .. } => enum_field_kind(underlying),
         _ => EnumFieldKind::Ptr,
     }
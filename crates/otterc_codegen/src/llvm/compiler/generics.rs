@@ -0,0 +1,253 @@
+//! Monomorphization of generic functions. Every distinct list of type arguments a generic
+//! function is called with is lowered to a function of its own, named the way the type
+//! checker names the instantiation (`max<i64>`).
+
+use std::collections::HashMap;
+
+use anyhow::{Result, anyhow};
+use otterc_ast::nodes::{Expr, Function, Node, Program, Statement, Type};
+use otterc_span::Span;
+use otterc_typecheck::{TypeInfo, instantiation_name};
+
+use super::Compiler;
+
+/// An instantiation that has been declared but whose body is not lowered yet
+pub(crate) struct PendingInstantiation {
+    generic: String,
+    name: String,
+    args: HashMap<String, TypeInfo>,
+}
+
+/// Types recorded by the type checker that were replaced while an instantiation is lowered
+#[derive(Default)]
+struct RecordedTypes {
+    by_id: Vec<(usize, TypeInfo)>,
+    by_span: Vec<(Span, TypeInfo)>,
+    comprehension_vars: Vec<(Span, TypeInfo)>,
+}
+
+impl<'ctx> Compiler<'ctx> {
+    /// Type of an annotation with the type parameters of the current instantiation replaced
+    pub(crate) fn annotation_type_info(&self, ty: &Type) -> TypeInfo {
+        TypeInfo::from(ty).substitute(&self.generic_args)
+    }
+
+    /// Name of the instantiation that a call to the generic function `name` resolves to. The
+    /// instantiation is declared on first use; its body is lowered by `lower_instantiations`.
+    pub(crate) fn instantiate_generic(
+        &mut self,
+        name: &str,
+        callee: &Expr,
+        type_args: &[Node<Type>],
+    ) -> Result<String> {
+        let function = self
+            .generic_functions
+            .get(name)
+            .cloned()
+            .ok_or_else(|| anyhow!("generic function {} not found", name))?;
+
+        // The type checker records the callee with its instantiated signature
+        let mut args = HashMap::new();
+        if let Some(TypeInfo::Function {
+            params,
+            return_type,
+            ..
+        }) = self.expr_type(callee)
+        {
+            for (param, actual) in function.params.iter().zip(params) {
+                if let Some(ty) = &param.as_ref().ty {
+                    bind_type_params(ty.as_ref(), actual, &function.generics, &mut args);
+                }
+            }
+            if let Some(ret_ty) = &function.ret_ty {
+                bind_type_params(ret_ty.as_ref(), return_type, &function.generics, &mut args);
+            }
+        }
+        for (generic, ty) in function.generics.iter().zip(type_args) {
+            if !args.contains_key(generic) {
+                let ty = self.annotation_type_info(ty.as_ref());
+                args.insert(generic.clone(), ty);
+            }
+        }
+        self.declare_instantiation(name, function, args)
+    }
+
+    /// Name of the instantiation for a call that only has the arguments to go by, such as
+    /// `pair(a=1, b=2)`, whose callee is not an expression of its own
+    pub(crate) fn instantiate_generic_from_args(
+        &mut self,
+        name: &str,
+        arguments: &[Option<&Node<Expr>>],
+    ) -> Result<String> {
+        let function = self
+            .generic_functions
+            .get(name)
+            .cloned()
+            .ok_or_else(|| anyhow!("generic function {} not found", name))?;
+
+        let mut args = HashMap::new();
+        for (param, argument) in function.params.iter().zip(arguments) {
+            if let (Some(ty), Some(argument)) = (&param.as_ref().ty, argument)
+                && let Some(actual) = self.expr_type(argument.as_ref()).cloned()
+            {
+                bind_type_params(ty.as_ref(), &actual, &function.generics, &mut args);
+            }
+        }
+        self.declare_instantiation(name, function, args)
+    }
+
+    fn declare_instantiation(
+        &mut self,
+        name: &str,
+        function: Function,
+        args: HashMap<String, TypeInfo>,
+    ) -> Result<String> {
+        let concrete_args = function
+            .generics
+            .iter()
+            .map(|generic| {
+                args.get(generic).cloned().ok_or_else(|| {
+                    anyhow!("cannot infer type parameter `{}` of `{}`", generic, name)
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let instance = instantiation_name(name, &concrete_args);
+
+        if !self.declared_functions.contains_key(&instance) {
+            let mut prototype = function;
+            prototype.name = instance.clone();
+            let outer = std::mem::replace(&mut self.generic_args, args.clone());
+            let declared = self.register_function_prototype(&prototype);
+            self.generic_args = outer;
+            declared?;
+            self.pending_instantiations.push(PendingInstantiation {
+                generic: name.to_string(),
+                name: instance.clone(),
+                args,
+            });
+        }
+        Ok(instance)
+    }
+
+    /// Lower the bodies of the declared instantiations. A body can declare further
+    /// instantiations, which are lowered in turn.
+    pub(crate) fn lower_instantiations(&mut self, program: &Program) -> Result<()> {
+        while let Some(pending) = self.pending_instantiations.pop() {
+            let function = program
+                .statements
+                .iter()
+                .find_map(|statement| match statement.as_ref() {
+                    Statement::Function(function) if function.as_ref().name == pending.generic => {
+                        Some(function)
+                    }
+                    _ => None,
+                })
+                .ok_or_else(|| anyhow!("generic function {} not found", pending.generic))?;
+
+            self.record_function_spans(function.as_ref());
+            let recorded = self.substitute_recorded_types(*function.span(), &pending.args);
+            let outer = std::mem::replace(&mut self.generic_args, pending.args);
+            let lowered = self.compile_function_as(function.as_ref(), &pending.name);
+            self.generic_args = outer;
+            self.restore_recorded_types(recorded);
+            lowered?;
+        }
+        Ok(())
+    }
+
+    /// Replace the type parameters in the types recorded for the expressions of one generic
+    /// function, returning the types as they were
+    fn substitute_recorded_types(
+        &mut self,
+        scope: Span,
+        args: &HashMap<String, TypeInfo>,
+    ) -> RecordedTypes {
+        let within = |span: &Span| scope.start() <= span.start() && span.end() <= scope.end();
+        let mut recorded = RecordedTypes::default();
+        for (id, ty) in self.expr_types.iter_mut() {
+            if self.expr_spans.get(id).is_some_and(within) {
+                recorded.by_id.push((*id, ty.clone()));
+                *ty = ty.substitute(args);
+            }
+        }
+        for (span, ty) in self.expr_types_by_span.iter_mut() {
+            if within(span) {
+                recorded.by_span.push((*span, ty.clone()));
+                *ty = ty.substitute(args);
+            }
+        }
+        for (span, ty) in self.comprehension_var_types.iter_mut() {
+            if within(span) {
+                recorded.comprehension_vars.push((*span, ty.clone()));
+                *ty = ty.substitute(args);
+            }
+        }
+        recorded
+    }
+
+    fn restore_recorded_types(&mut self, recorded: RecordedTypes) {
+        self.expr_types.extend(recorded.by_id);
+        self.expr_types_by_span.extend(recorded.by_span);
+        self.comprehension_var_types
+            .extend(recorded.comprehension_vars);
+    }
+}
+
+/// Match a declared type against the type the checker inferred for it, binding the type
+/// parameters it mentions
+fn bind_type_params(
+    declared: &Type,
+    actual: &TypeInfo,
+    generics: &[String],
+    bound: &mut HashMap<String, TypeInfo>,
+) {
+    match (declared, actual) {
+        (Type::Simple(name), _) if generics.contains(name) => {
+            bound.entry(name.clone()).or_insert_with(|| actual.clone());
+        }
+        (Type::Generic { args, .. }, TypeInfo::List(element)) => {
+            if let Some(arg) = args.first() {
+                bind_type_params(arg.as_ref(), element, generics, bound);
+            }
+        }
+        (Type::Generic { args, .. }, TypeInfo::Dict { key, value }) => {
+            for (arg, actual) in args.iter().zip([key, value]) {
+                bind_type_params(arg.as_ref(), actual, generics, bound);
+            }
+        }
+        (
+            Type::Generic { args, .. },
+            TypeInfo::Enum {
+                args: actual_args, ..
+            }
+            | TypeInfo::Generic {
+                args: actual_args, ..
+            },
+        ) => {
+            for (arg, actual) in args.iter().zip(actual_args) {
+                bind_type_params(arg.as_ref(), actual, generics, bound);
+            }
+        }
+        (Type::Tuple(elements), TypeInfo::Tuple(actual_elements)) => {
+            for (element, actual) in elements.iter().zip(actual_elements) {
+                bind_type_params(element.as_ref(), actual, generics, bound);
+            }
+        }
+        (
+            Type::Function { params, ret },
+            TypeInfo::Function {
+                params: actual_params,
+                return_type,
+                ..
+            },
+        ) => {
+            for (param, actual) in params.iter().zip(actual_params) {
+                bind_type_params(param.as_ref(), actual, generics, bound);
+            }
+            if let Some(ret) = ret {
+                bind_type_params(ret.as_ref().as_ref(), return_type, generics, bound);
+            }
+        }
+        _ => {}
+    }
+}
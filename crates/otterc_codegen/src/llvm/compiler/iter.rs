@@ -0,0 +1,696 @@
+//! Lowering of the values a `for` loop or comprehension walks over. Every iterable is
+//! opened as a cursor before the loop starts; the loop header then asks the cursor for
+//! its next element and leaves through the exit block once the cursor is exhausted.
+//! The lazy `map`/`filter`/`zip`/`take` adapters are built here too: they wrap their
+//! source in a runtime iterator that calls back into generated thunks as it is pulled.
+
+use anyhow::{Result, anyhow, bail};
+use inkwell::IntPredicate;
+use inkwell::basic_block::BasicBlock;
+use inkwell::types::BasicTypeEnum;
+use inkwell::values::{BasicMetadataValueEnum, BasicValueEnum, FunctionValue, PointerValue};
+
+use crate::llvm::compiler::Compiler;
+use crate::llvm::compiler::types::{EvaluatedValue, FunctionContext, OtterType};
+use otterc_ast::nodes::{Expr, Node};
+use otterc_typecheck::TypeInfo;
+
+/// Iteration state opened before a loop
+#[derive(Debug, Clone)]
+pub(crate) enum IterCursor<'ctx> {
+    /// Runtime iterator over a list, string or map
+    Runtime {
+        handle: BasicValueEnum<'ctx>,
+        has_next_fn: FunctionValue<'ctx>,
+        next_fn: FunctionValue<'ctx>,
+        free_fn: FunctionValue<'ctx>,
+        element_type: OtterType,
+        /// Lazy iterators run user callbacks while advancing, which may raise
+        may_raise: bool,
+    },
+    /// Struct with a `next(self) -> Option<T>` method, advanced in place
+    Struct {
+        slot: PointerValue<'ctx>,
+        struct_type: BasicTypeEnum<'ctx>,
+        next_fn: FunctionValue<'ctx>,
+        by_pointer: bool,
+        payload_type: Option<TypeInfo>,
+        some_tag: u32,
+    },
+}
+
+impl<'ctx> Compiler<'ctx> {
+    /// Evaluates `iterable` and opens a cursor over it. `element_hint` overrides the
+    /// element type of a list whose handle does not record it.
+    pub(crate) fn open_iter_cursor(
+        &mut self,
+        iterable: &Expr,
+        element_hint: Option<OtterType>,
+        function: FunctionValue<'ctx>,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<IterCursor<'ctx>> {
+        if let Expr::Range { start, end } = iterable {
+            let (list, start_ty) = self.range_list(start, end, ctx)?;
+            // Elements of a range have the type of its start
+            return self.runtime_cursor("array", list, start_ty);
+        }
+        if let Some(element_type) = self
+            .expr_type(iterable)
+            .and_then(|ty| self.iterator_element(ty))
+        {
+            let iterator = self.eval_expr(iterable, ctx)?;
+            let handle = iterator
+                .value
+                .ok_or_else(|| anyhow!("iterator has no value"))?;
+            return self.runtime_cursor("lazy", handle, element_type);
+        }
+
+        let value = self.eval_expr(iterable, ctx)?;
+        let list_element = element_hint
+            .or_else(|| self.list_element_type(iterable))
+            .or_else(|| self.dict_key_type(iterable));
+        self.cursor_over_value(value, list_element, function, ctx)
+    }
+
+    /// The list of numbers in `start..end`, and the type of its elements
+    fn range_list(
+        &mut self,
+        start: &Node<Expr>,
+        end: &Node<Expr>,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<(BasicValueEnum<'ctx>, OtterType)> {
+        let start_val = self.eval_expr(start.as_ref(), ctx)?;
+        let end_val = self.eval_expr(end.as_ref(), ctx)?;
+        let start_ty = start_val.ty.clone();
+        let range_fn_name = if start_ty == OtterType::F64 {
+            "range<float>"
+        } else {
+            "range<int>"
+        };
+        let range_fn = self.get_or_declare_ffi_function(range_fn_name)?;
+        let list = self
+            .builder
+            .build_call(
+                range_fn,
+                &[
+                    start_val
+                        .value
+                        .ok_or_else(|| anyhow!("range start has no value"))?
+                        .into(),
+                    end_val
+                        .value
+                        .ok_or_else(|| anyhow!("range end has no value"))?
+                        .into(),
+                ],
+                "range_list",
+            )?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("{} returned void", range_fn_name))?;
+        Ok((list, start_ty))
+    }
+
+    /// Element type of a lazy `Iterator<T>`
+    fn iterator_element(&self, ty: &TypeInfo) -> Option<OtterType> {
+        match ty.resolve_alias() {
+            TypeInfo::Generic { base, args } if base == "Iterator" => Some(
+                args.first()
+                    .and_then(|element| self.typeinfo_to_otter_type(element))
+                    .unwrap_or(OtterType::Opaque),
+            ),
+            _ => None,
+        }
+    }
+
+    /// Type of the keys of `iterable` when it is a dict. Enum keys are yielded as their
+    /// handles and struct keys boxed, like list elements.
+    fn dict_key_type(&self, iterable: &Expr) -> Option<OtterType> {
+        let TypeInfo::Dict { key, .. } = self.expr_type(iterable)?.resolve_alias() else {
+            return None;
+        };
+        match key.resolve_alias() {
+            TypeInfo::Enum { .. } => Some(OtterType::Opaque),
+            key => self.typeinfo_to_otter_type(key),
+        }
+    }
+
+    fn cursor_over_value(
+        &mut self,
+        value: EvaluatedValue<'ctx>,
+        list_element: Option<OtterType>,
+        function: FunctionValue<'ctx>,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<IterCursor<'ctx>> {
+        let handle = |value: &EvaluatedValue<'ctx>| {
+            value.value.ok_or_else(|| anyhow!("iterable has no value"))
+        };
+        match value.ty.clone() {
+            // Strings yield one-character strings and maps yield their keys
+            OtterType::Str => self.runtime_cursor("string", handle(&value)?, OtterType::Str),
+            OtterType::Map => {
+                let key_type = list_element.unwrap_or(OtterType::Str);
+                self.runtime_cursor("map", handle(&value)?, key_type)
+            }
+            OtterType::List(element) => {
+                let element_type = list_element.unwrap_or(*element);
+                self.runtime_cursor("array", handle(&value)?, element_type)
+            }
+            OtterType::Struct(struct_id) => {
+                let struct_name = self.struct_info(struct_id).name.clone();
+                let next_name = format!("{}_next", struct_name);
+                if let Some(next_fn) = self.declared_functions.get(&next_name).copied() {
+                    return self.struct_cursor(value, &next_name, next_fn, function);
+                }
+
+                let iterator = self.call_iter_method(value, struct_id, ctx)?;
+                if let Some(element_type) = self.iter_method_element(struct_id) {
+                    let handle = handle(&iterator)?;
+                    return self.runtime_cursor("lazy", handle, element_type);
+                }
+                self.cursor_over_value(iterator, None, function, ctx)
+            }
+            other => bail!("For loops over type {:?} are not supported yet", other),
+        }
+    }
+
+    /// Calls the `iter` method of the struct `value` for what to iterate over instead
+    fn call_iter_method(
+        &mut self,
+        value: EvaluatedValue<'ctx>,
+        struct_id: u32,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<EvaluatedValue<'ctx>> {
+        let struct_name = self.struct_info(struct_id).name.clone();
+        let iter_name = format!("{}_iter", struct_name);
+        let Some(iter_fn) = self.declared_functions.get(&iter_name).copied() else {
+            bail!(
+                "`{}` has no `next` or `iter` method to iterate with",
+                struct_name
+            );
+        };
+        let receiver: BasicMetadataValueEnum = if self.receiver_methods.contains(&iter_name) {
+            self.spill_receiver(value)?.into()
+        } else {
+            value
+                .value
+                .ok_or_else(|| anyhow!("iterable has no value"))?
+                .into()
+        };
+        let iterator = self
+            .builder
+            .build_call(iter_fn, &[receiver], "iter")?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("{} returned void", iter_name))?;
+        self.check_pending_exception(ctx)?;
+        let iterator_ty = self
+            .function_return_types
+            .get(&iter_name)
+            .cloned()
+            .unwrap_or(OtterType::Opaque);
+        if iterator_ty == OtterType::Struct(struct_id) {
+            bail!(
+                "`{}.iter` returns `{}` itself, which has no `next` method",
+                struct_name,
+                struct_name
+            );
+        }
+        Ok(EvaluatedValue::with_value(iterator, iterator_ty))
+    }
+
+    /// Element type of the lazy iterator a struct's `iter` method returns, if it returns one
+    fn iter_method_element(&self, struct_id: u32) -> Option<OtterType> {
+        let iter_name = format!("{}_iter", self.struct_info(struct_id).name);
+        self.declared_return_types
+            .get(&iter_name)
+            .and_then(|ty| self.iterator_element(ty))
+    }
+
+    fn runtime_cursor(
+        &mut self,
+        kind: &str,
+        collection: BasicValueEnum<'ctx>,
+        element_type: OtterType,
+    ) -> Result<IterCursor<'ctx>> {
+        let create_fn = self.get_or_declare_ffi_function(&format!("__otter_iter_{}", kind))?;
+        let has_next_fn =
+            self.get_or_declare_ffi_function(&format!("__otter_iter_has_next_{}", kind))?;
+        let next_fn = self.get_or_declare_ffi_function(&format!("__otter_iter_next_{}", kind))?;
+        let free_fn = self.get_or_declare_ffi_function(&format!("__otter_iter_free_{}", kind))?;
+        let handle = self
+            .builder
+            .build_call(create_fn, &[collection.into()], "iter_handle")?
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("Iterator creation failed"))?;
+        Ok(IterCursor::Runtime {
+            handle,
+            has_next_fn,
+            next_fn,
+            free_fn,
+            element_type,
+            may_raise: kind == "lazy",
+        })
+    }
+
+    /// Copies the struct into a slot of its own, so the loop advances its own iterator and
+    /// leaves the iterated variable untouched.
+    fn struct_cursor(
+        &mut self,
+        value: EvaluatedValue<'ctx>,
+        next_name: &str,
+        next_fn: FunctionValue<'ctx>,
+        function: FunctionValue<'ctx>,
+    ) -> Result<IterCursor<'ctx>> {
+        let struct_type = self
+            .basic_type(value.ty.clone())?
+            .ok_or_else(|| anyhow!("struct has no LLVM representation"))?;
+        let slot = self.create_entry_block_alloca(function, "iter_state", value.ty.clone())?;
+        self.builder.build_store(
+            slot,
+            value
+                .value
+                .ok_or_else(|| anyhow!("iterable has no value"))?,
+        )?;
+        self.struct_cursor_at(slot, struct_type, next_name, next_fn)
+    }
+
+    /// Cursor advancing the struct iterator stored at `slot` with its `next` method
+    fn struct_cursor_at(
+        &mut self,
+        slot: PointerValue<'ctx>,
+        struct_type: BasicTypeEnum<'ctx>,
+        next_name: &str,
+        next_fn: FunctionValue<'ctx>,
+    ) -> Result<IterCursor<'ctx>> {
+        let layout = self
+            .enum_layout("Option")
+            .ok_or_else(|| anyhow!("Missing enum layout for Option"))?;
+        let some_tag = layout
+            .tag_of("Some")
+            .ok_or_else(|| anyhow!("Unknown variant Option.Some"))?;
+        let payload_type = match self.declared_return_types.get(next_name) {
+            Some(
+                TypeInfo::Generic { base, args }
+                | TypeInfo::Enum {
+                    name: base, args, ..
+                },
+            ) if base == "Option" => layout
+                .fields_of("Some", args)
+                .and_then(|fields| fields.into_iter().next()),
+            _ => bail!("`{}` must return an Option to be iterated", next_name),
+        };
+        Ok(IterCursor::Struct {
+            slot,
+            struct_type,
+            next_fn,
+            by_pointer: self.receiver_methods.contains(next_name),
+            payload_type,
+            some_tag,
+        })
+    }
+
+    /// Produces the next element of `cursor`. Control moves to `done_bb` once the cursor is
+    /// exhausted; otherwise the builder is left in a block where the element is available.
+    pub(crate) fn advance_iter_cursor(
+        &mut self,
+        cursor: &IterCursor<'ctx>,
+        done_bb: BasicBlock<'ctx>,
+        function: FunctionValue<'ctx>,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<EvaluatedValue<'ctx>> {
+        match cursor {
+            IterCursor::Runtime {
+                handle,
+                has_next_fn,
+                next_fn,
+                element_type,
+                may_raise,
+                ..
+            } => {
+                let has_next = self
+                    .builder
+                    .build_call(*has_next_fn, &[(*handle).into()], "has_next")?
+                    .try_as_basic_value()
+                    .left()
+                    .ok_or_else(|| anyhow!("has_next check failed"))?
+                    .into_int_value();
+                if *may_raise {
+                    self.check_pending_exception(ctx)?;
+                }
+                let next_bb = self.context.append_basic_block(function, "iter_next");
+                self.builder
+                    .build_conditional_branch(has_next, next_bb, done_bb)?;
+
+                self.builder.position_at_end(next_bb);
+                let element = self
+                    .builder
+                    .build_call(*next_fn, &[(*handle).into()], "next_element")?
+                    .try_as_basic_value()
+                    .left()
+                    .ok_or_else(|| anyhow!("next element failed"))?;
+                // Elements come back tagged: upper 8 bits = type tag, lower 56 bits = data
+                let value = self.decode_and_convert_tagged_value(element, element_type)?;
+                Ok(EvaluatedValue {
+                    ty: element_type.clone(),
+                    value,
+                })
+            }
+            IterCursor::Struct {
+                slot,
+                struct_type,
+                next_fn,
+                by_pointer,
+                payload_type,
+                some_tag,
+            } => {
+                let receiver: BasicMetadataValueEnum = if *by_pointer {
+                    (*slot).into()
+                } else {
+                    self.builder
+                        .build_load(*struct_type, *slot, "iter_state")?
+                        .into()
+                };
+                let option = self
+                    .builder
+                    .build_call(*next_fn, &[receiver], "next_option")?
+                    .try_as_basic_value()
+                    .left()
+                    .ok_or_else(|| anyhow!("iterator `next` returned void"))?;
+                self.check_pending_exception(ctx)?;
+                let get_tag_fn = self.get_or_declare_ffi_function("runtime.enum.get_tag")?;
+                let tag = self
+                    .builder
+                    .build_call(get_tag_fn, &[option.into()], "tag")?
+                    .try_as_basic_value()
+                    .left()
+                    .ok_or_else(|| anyhow!("runtime.enum.get_tag returned void"))?
+                    .into_int_value();
+                let some_tag = self.context.i64_type().const_int(*some_tag as u64, false);
+                let is_some = self.builder.build_int_compare(
+                    IntPredicate::EQ,
+                    tag,
+                    some_tag,
+                    "iter_is_some",
+                )?;
+                let some_bb = self.context.append_basic_block(function, "iter_some");
+                self.builder
+                    .build_conditional_branch(is_some, some_bb, done_bb)?;
+
+                self.builder.position_at_end(some_bb);
+                let Some(payload_type) = payload_type else {
+                    return Ok(EvaluatedValue {
+                        ty: OtterType::Unit,
+                        value: None,
+                    });
+                };
+                let payload = self.load_enum_field(option, 0, payload_type)?;
+                self.retype_enum_payload(payload, payload_type)
+            }
+        }
+    }
+
+    /// Releases the runtime iterators held by `cursor`
+    pub(crate) fn close_iter_cursor(&mut self, cursor: &IterCursor<'ctx>) -> Result<()> {
+        match cursor {
+            IterCursor::Runtime {
+                handle, free_fn, ..
+            } => {
+                self.builder.build_call(*free_fn, &[(*handle).into()], "")?;
+                Ok(())
+            }
+            IterCursor::Struct { .. } => Ok(()),
+        }
+    }
+
+    /// `map(f, iterable)`, `filter(predicate, iterable)`, `zip(left, right)` and
+    /// `take(iterable, count)`: a runtime iterator that produces the adapted elements of its
+    /// source only as a loop asks for them. `call` is the adapter call itself.
+    pub(crate) fn build_iterator_adapter(
+        &mut self,
+        call: &Expr,
+        name: &str,
+        args: &[Node<Expr>],
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<EvaluatedValue<'ctx>> {
+        let [first, second] = args else {
+            bail!("`{}` expects 2 arguments, got {}", name, args.len());
+        };
+        let iterator = match name {
+            "map" | "filter" => {
+                let func = self.eval_expr(first.as_ref(), ctx)?;
+                if !matches!(func.ty, OtterType::Function { .. }) {
+                    bail!("`{}` expects a function, got {:?}", name, func.ty);
+                }
+                let source = self.lazy_iterator(second.as_ref(), ctx)?;
+                let apply = self.lazy_apply_thunk(func.ty.clone())?;
+                let apply = self.function_address(apply)?;
+                let func = func
+                    .value
+                    .ok_or_else(|| anyhow!("`{}` function has no value", name))?;
+                self.call_ffi_returning_value(
+                    &format!("__otter_lazy_{}", name),
+                    vec![source, apply, func],
+                    name,
+                )?
+            }
+            "zip" => {
+                let left = self.lazy_iterator(first.as_ref(), ctx)?;
+                let right = self.lazy_iterator(second.as_ref(), ctx)?;
+                let pair_type = self
+                    .expr_type(call)
+                    .and_then(|ty| self.iterator_element(ty))
+                    .ok_or_else(|| anyhow!("`zip` has no element type"))?;
+                let pair = self.lazy_pair_thunk(pair_type)?;
+                let pair = self.function_address(pair)?;
+                self.call_ffi_returning_value("__otter_lazy_zip", vec![left, right, pair], "zip")?
+            }
+            "take" => {
+                let source = self.lazy_iterator(first.as_ref(), ctx)?;
+                let count = self.eval_expr(second.as_ref(), ctx)?;
+                let count_value = count
+                    .value
+                    .ok_or_else(|| anyhow!("`take` count has no value"))?;
+                let count_value = self.coerce_type(count_value, count.ty, OtterType::I64)?;
+                self.call_ffi_returning_value(
+                    "__otter_lazy_take",
+                    vec![source, count_value],
+                    "take",
+                )?
+            }
+            other => bail!("unknown iterator adapter `{}`", other),
+        };
+        Ok(EvaluatedValue::with_value(iterator, OtterType::Opaque))
+    }
+
+    /// Handle of a lazy iterator over `iterable`, which can be anything a loop walks over
+    fn lazy_iterator(
+        &mut self,
+        iterable: &Expr,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<BasicValueEnum<'ctx>> {
+        if let Expr::Range { start, end } = iterable {
+            let (list, _) = self.range_list(start, end, ctx)?;
+            return self.call_ffi_returning_value("__otter_lazy_from_list", vec![list], "lazy");
+        }
+        let is_iterator = self
+            .expr_type(iterable)
+            .is_some_and(|ty| self.iterator_element(ty).is_some());
+        let value = self.eval_expr(iterable, ctx)?;
+        if is_iterator {
+            return value.value.ok_or_else(|| anyhow!("iterator has no value"));
+        }
+        self.lazy_iterator_over_value(value, ctx)
+    }
+
+    fn lazy_iterator_over_value(
+        &mut self,
+        value: EvaluatedValue<'ctx>,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<BasicValueEnum<'ctx>> {
+        let handle = value
+            .value
+            .ok_or_else(|| anyhow!("iterable has no value"))?;
+        let source_fn = match value.ty {
+            OtterType::Str => "__otter_lazy_from_string",
+            OtterType::Map => "__otter_lazy_from_map",
+            OtterType::List(_) => "__otter_lazy_from_list",
+            OtterType::Struct(struct_id) => {
+                let next_name = format!("{}_next", self.struct_info(struct_id).name);
+                if let Some(next_fn) = self.declared_functions.get(&next_name).copied() {
+                    // The runtime advances its own copy of the iterator
+                    let state = self.box_tuple(handle)?;
+                    let pull = self.lazy_pull_thunk(struct_id, &next_name, next_fn)?;
+                    let pull = self.function_address(pull)?;
+                    return self.call_ffi_returning_value(
+                        "__otter_lazy_from_next",
+                        vec![pull, state.into()],
+                        "lazy",
+                    );
+                }
+                let iterator = self.call_iter_method(value, struct_id, ctx)?;
+                if self.iter_method_element(struct_id).is_some() {
+                    return iterator
+                        .value
+                        .ok_or_else(|| anyhow!("iterator has no value"));
+                }
+                return self.lazy_iterator_over_value(iterator, ctx);
+            }
+            other => bail!("cannot make an iterator over {:?}", other),
+        };
+        self.call_ffi_returning_value(source_fn, vec![handle], "lazy")
+    }
+
+    /// Emits `fn(closure, element, out)` for the runtime: decodes `element` as the closure's
+    /// parameter, calls the closure and appends the result to the list `out`
+    fn lazy_apply_thunk(&mut self, closure_type: OtterType) -> Result<FunctionValue<'ctx>> {
+        let OtterType::Function { params, .. } = &closure_type else {
+            bail!("cannot apply a value of type {:?}", closure_type);
+        };
+        let param_type = params
+            .first()
+            .cloned()
+            .ok_or_else(|| anyhow!("iterator callbacks take one argument"))?;
+
+        let i64_type = self.context.i64_type();
+        let fn_type = self
+            .context
+            .void_type()
+            .fn_type(&[i64_type.into(), i64_type.into(), i64_type.into()], false);
+        let thunk = self
+            .module
+            .add_function("__otter_lazy_apply", fn_type, None);
+        let entry = self.context.append_basic_block(thunk, "entry");
+        let prev_block = self.builder.get_insert_block();
+        self.builder.position_at_end(entry);
+
+        let [closure, element, out] = [0, 1, 2].map(|index| thunk.get_nth_param(index));
+        let (Some(closure), Some(element), Some(out)) = (closure, element, out) else {
+            bail!("iterator callback thunk is missing parameters");
+        };
+        let value = self.decode_and_convert_tagged_value(element, &param_type)?;
+        let argument = EvaluatedValue {
+            ty: param_type,
+            value,
+        };
+        // An exception raised by the closure leaves the thunk without a result
+        let mut thunk_ctx = FunctionContext::new();
+        let result = self.call_closure_with_values(
+            EvaluatedValue::with_value(closure, closure_type),
+            vec![argument],
+            &mut thunk_ctx,
+        )?;
+        let result_value = result
+            .value
+            .ok_or_else(|| anyhow!("iterator callbacks must return a value"))?;
+        self.append_value_to_list(out.into_int_value(), result_value, result.ty, "lazy_result")?;
+        self.builder.build_return(None)?;
+
+        if let Some(block) = prev_block {
+            self.builder.position_at_end(block);
+        }
+        Ok(thunk)
+    }
+
+    /// Emits `fn(left, right, out)` for the runtime: decodes both elements and appends the
+    /// tuple of them to the list `out`
+    fn lazy_pair_thunk(&mut self, pair_type: OtterType) -> Result<FunctionValue<'ctx>> {
+        let OtterType::Tuple(element_types) = &pair_type else {
+            bail!("`zip` yields tuples, not {:?}", pair_type);
+        };
+        let element_types = element_types.clone();
+
+        let i64_type = self.context.i64_type();
+        let fn_type = self
+            .context
+            .void_type()
+            .fn_type(&[i64_type.into(), i64_type.into(), i64_type.into()], false);
+        let thunk = self.module.add_function("__otter_lazy_pair", fn_type, None);
+        let entry = self.context.append_basic_block(thunk, "entry");
+        let prev_block = self.builder.get_insert_block();
+        self.builder.position_at_end(entry);
+
+        let mut elements = Vec::with_capacity(element_types.len());
+        for (index, element_type) in element_types.iter().enumerate() {
+            let encoded = thunk
+                .get_nth_param(index as u32)
+                .ok_or_else(|| anyhow!("`zip` thunk is missing an element"))?;
+            let element = self
+                .decode_and_convert_tagged_value(encoded, element_type)?
+                .ok_or_else(|| anyhow!("`zip` elements must have a value"))?;
+            elements.push(element);
+        }
+        let out = thunk
+            .get_nth_param(2)
+            .ok_or_else(|| anyhow!("`zip` thunk is missing its output list"))?
+            .into_int_value();
+        let pair = self.build_tuple_value(&elements, pair_type.clone())?;
+        self.append_value_to_list(out, pair, pair_type, "lazy_pair")?;
+        self.builder.build_return(None)?;
+
+        if let Some(block) = prev_block {
+            self.builder.position_at_end(block);
+        }
+        Ok(thunk)
+    }
+
+    /// Emits `fn(state, out) -> bool` for the runtime: advances the struct iterator boxed at
+    /// `state` and appends its next element to the list `out`, or returns false once it is
+    /// exhausted
+    fn lazy_pull_thunk(
+        &mut self,
+        struct_id: u32,
+        next_name: &str,
+        next_fn: FunctionValue<'ctx>,
+    ) -> Result<FunctionValue<'ctx>> {
+        let thunk_name = format!("__otter_lazy_pull_{}", next_name);
+        if let Some(existing) = self.module.get_function(&thunk_name) {
+            return Ok(existing);
+        }
+
+        let i64_type = self.context.i64_type();
+        let fn_type = self
+            .context
+            .bool_type()
+            .fn_type(&[i64_type.into(), i64_type.into()], false);
+        let thunk = self.module.add_function(&thunk_name, fn_type, None);
+        let entry = self.context.append_basic_block(thunk, "entry");
+        let prev_block = self.builder.get_insert_block();
+        self.builder.position_at_end(entry);
+
+        let state = thunk
+            .get_nth_param(0)
+            .ok_or_else(|| anyhow!("`{}` thunk is missing its state", next_name))?
+            .into_int_value();
+        let out = thunk
+            .get_nth_param(1)
+            .ok_or_else(|| anyhow!("`{}` thunk is missing its output list", next_name))?
+            .into_int_value();
+        let slot = self
+            .builder
+            .build_int_to_ptr(state, self.raw_ptr_type(), "iter_state")?;
+        let struct_type = self
+            .basic_type(OtterType::Struct(struct_id))?
+            .ok_or_else(|| anyhow!("struct has no LLVM representation"))?;
+        let cursor = self.struct_cursor_at(slot, struct_type, next_name, next_fn)?;
+
+        let exhausted_bb = self.context.append_basic_block(thunk, "exhausted");
+        let mut thunk_ctx = FunctionContext::new();
+        let element = self.advance_iter_cursor(&cursor, exhausted_bb, thunk, &mut thunk_ctx)?;
+        let element_value = element
+            .value
+            .ok_or_else(|| anyhow!("`{}` must yield values to be adapted", next_name))?;
+        self.append_value_to_list(out, element_value, element.ty, "lazy_element")?;
+        self.builder
+            .build_return(Some(&self.context.bool_type().const_int(1, false)))?;
+
+        self.builder.position_at_end(exhausted_bb);
+        self.builder
+            .build_return(Some(&self.context.bool_type().const_zero()))?;
+
+        if let Some(block) = prev_block {
+            self.builder.position_at_end(block);
+        }
+        Ok(thunk)
+    }
+}
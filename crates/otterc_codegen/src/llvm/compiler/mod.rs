@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use std::sync::atomic::AtomicUsize;
 
@@ -9,7 +9,7 @@ use inkwell::module::Module;
 use inkwell::passes::{PassBuilderOptions, PassManager};
 use inkwell::targets::TargetMachine;
 use inkwell::types::{BasicType, BasicTypeEnum, PointerType, StructType};
-use inkwell::values::{FunctionValue, PointerValue};
+use inkwell::values::{BasicValueEnum, FunctionValue, PointerValue};
 
 use crate::llvm::bridges::prepare_rust_bridges;
 use otterc_ast::nodes::{Block, Expr, FStringPart, Function, Node, Program, Statement};
@@ -17,12 +17,18 @@ use otterc_config::CodegenOptLevel;
 use otterc_config::TargetTriple;
 use otterc_span::Span;
 use otterc_symbol::registry::SymbolRegistry;
-use otterc_typecheck::{EnumLayout, TypeInfo};
+use otterc_typecheck::{ConstValue, EnumLayout, TypeInfo, evaluate_constants};
 
+pub mod derive;
 pub mod expr;
+pub mod generics;
+pub mod iter;
+pub mod operators;
+pub mod place;
 pub mod stmt;
 pub mod types;
 
+use self::generics::PendingInstantiation;
 use self::types::{FunctionContext, OtterType};
 
 struct StructInfo<'ctx> {
@@ -30,6 +36,7 @@ struct StructInfo<'ctx> {
     ty: StructType<'ctx>,
     field_indices: HashMap<String, usize>,
     field_types: Vec<OtterType>,
+    bounds: Vec<(String, Vec<String>)>,
 }
 
 pub struct Compiler<'ctx> {
@@ -42,17 +49,39 @@ pub struct Compiler<'ctx> {
     pub(crate) string_ptr_type: PointerType<'ctx>,
     pub(crate) declared_functions: HashMap<String, FunctionValue<'ctx>>,
     pub(crate) function_return_types: HashMap<String, OtterType>,
+    /// Return types as written, which keep the payload types of `Option` and `Result`
+    pub(crate) declared_return_types: HashMap<String, TypeInfo>,
     pub(crate) expr_types: HashMap<usize, TypeInfo>,
     expr_types_by_span: HashMap<Span, TypeInfo>,
     pub(crate) comprehension_var_types: HashMap<Span, TypeInfo>,
     expr_spans: HashMap<usize, Span>,
     pub(crate) enum_layouts: HashMap<String, EnumLayout>,
+    /// Values of the module's `const` declarations, emitted inline at each use
+    pub(crate) constants: HashMap<String, ConstValue>,
     pub(crate) function_defaults: HashMap<String, Vec<Option<Expr>>>,
-    #[expect(dead_code, reason = "Work in progress")]
+    /// Parameter names, which keyword arguments are matched against
+    pub(crate) function_param_names: HashMap<String, Vec<String>>,
+    /// Functions whose last parameter collects the remaining positional arguments in a list
+    pub(crate) variadic_functions: HashSet<String>,
+    pub(crate) function_param_types: HashMap<String, Vec<OtterType>>,
     pub(crate) lambda_counter: AtomicUsize,
     next_spawn_id: u64,
     struct_ids: HashMap<String, u32>,
     struct_infos: Vec<StructInfo<'ctx>>,
+    /// Trait method declarations in vtable order
+    traits: HashMap<String, Vec<Function>>,
+    /// Struct methods taking `self`, by lowered name; they receive a pointer to the
+    /// caller's struct so assignments to its fields persist
+    receiver_methods: HashSet<String>,
+    /// Trait bounds of the generic struct currently being lowered; bounded parameters are
+    /// stored as trait objects
+    generic_bounds: Vec<(String, Vec<String>)>,
+    /// Generic functions of the module; only their instantiations are lowered
+    generic_functions: HashMap<String, Function>,
+    /// Concrete types of the type parameters of the instantiation being declared or lowered
+    generic_args: HashMap<String, TypeInfo>,
+    /// Instantiations that have been declared but whose bodies are not lowered yet
+    pending_instantiations: Vec<PendingInstantiation>,
     pub cached_ir: Option<String>,
     /// Target triple for platform-specific ABI handling
     target_triple: Option<TargetTriple>,
@@ -73,17 +102,28 @@ impl<'ctx> Compiler<'ctx> {
         match stmt {
             Statement::Expr(expr)
             | Statement::Let { expr, .. }
-            | Statement::Assignment { expr, .. }
-            | Statement::Return(Some(expr)) => self.record_expr_spans(expr),
+            | Statement::Return(Some(expr))
+            | Statement::Raise(Some(expr))
+            | Statement::Defer(expr)
+            | Statement::Break {
+                value: Some(expr), ..
+            } => self.record_expr_spans(expr),
+            Statement::Assignment { target, expr, .. } => {
+                self.record_expr_spans(target);
+                self.record_expr_spans(expr);
+            }
             Statement::Return(None)
-            | Statement::Break
-            | Statement::Continue
+            | Statement::Raise(None)
+            | Statement::Break { value: None, .. }
+            | Statement::Continue { .. }
             | Statement::Pass
             | Statement::Use { .. }
             | Statement::PubUse { .. }
             | Statement::Enum { .. }
-            | Statement::TypeAlias { .. } => {}
-            Statement::Struct { methods, .. } => {
+            | Statement::Trait { .. }
+            | Statement::TypeAlias { .. }
+            | Statement::Const { .. } => {}
+            Statement::Struct { methods, .. } | Statement::Impl { methods, .. } => {
                 for method in methods {
                     self.record_function_spans(method.as_ref());
                 }
@@ -109,10 +149,27 @@ impl<'ctx> Compiler<'ctx> {
                 self.record_expr_spans(iterable);
                 self.record_block_spans(body.as_ref());
             }
-            Statement::While { cond, body } => {
+            Statement::While { cond, body, .. } => {
                 self.record_expr_spans(cond);
                 self.record_block_spans(body.as_ref());
             }
+            Statement::With { expr, body, .. } => {
+                self.record_expr_spans(expr);
+                self.record_block_spans(body.as_ref());
+            }
+            Statement::Try {
+                body,
+                handlers,
+                finally_block,
+            } => {
+                self.record_block_spans(body.as_ref());
+                for handler in handlers {
+                    self.record_block_spans(handler.as_ref().body.as_ref());
+                }
+                if let Some(block) = finally_block {
+                    self.record_block_spans(block.as_ref());
+                }
+            }
             Statement::Block(block) => self.record_block_spans(block.as_ref()),
         }
     }
@@ -127,16 +184,33 @@ impl<'ctx> Compiler<'ctx> {
                 self.record_expr_spans(left);
                 self.record_expr_spans(right);
             }
-            Expr::Unary { expr, .. } | Expr::Await(expr) | Expr::Spawn(expr) => {
+            Expr::Unary { expr, .. }
+            | Expr::Cast { expr, .. }
+            | Expr::Await(expr)
+            | Expr::Spawn(expr)
+            | Expr::Try(expr)
+            | Expr::Spread(expr) => {
                 self.record_expr_spans(expr);
             }
-            Expr::Call { func, args } => {
+            Expr::Call {
+                func, args, kwargs, ..
+            } => {
                 self.record_expr_spans(func);
-                for arg in args {
+                for arg in args.iter().chain(kwargs.iter().map(|(_, value)| value)) {
                     self.record_expr_spans(arg);
                 }
             }
             Expr::Member { object, .. } => self.record_expr_spans(object),
+            Expr::Index { object, index } => {
+                self.record_expr_spans(object);
+                self.record_expr_spans(index);
+            }
+            Expr::Slice { object, start, end } => {
+                self.record_expr_spans(object);
+                for bound in [start, end].into_iter().flatten() {
+                    self.record_expr_spans(bound);
+                }
+            }
             Expr::If {
                 cond,
                 then_branch,
@@ -161,7 +235,7 @@ impl<'ctx> Compiler<'ctx> {
                 self.record_expr_spans(start);
                 self.record_expr_spans(end);
             }
-            Expr::Array(elements) => {
+            Expr::Tuple(elements) | Expr::Array(elements) => {
                 for elem in elements {
                     self.record_expr_spans(elem);
                 }
@@ -198,7 +272,7 @@ impl<'ctx> Compiler<'ctx> {
                     self.record_expr_spans(cond);
                 }
             }
-            Expr::FString { parts } => {
+            Expr::FString { parts, .. } => {
                 for part in parts {
                     if let FStringPart::Expr(expr) = part.as_ref() {
                         self.record_expr_spans(expr);
@@ -210,6 +284,9 @@ impl<'ctx> Compiler<'ctx> {
                     self.record_expr_spans(value);
                 }
             }
+            Expr::Lambda { body, .. } | Expr::Loop { body, .. } => {
+                self.record_block_spans(body.as_ref());
+            }
         }
     }
     #[expect(
@@ -248,16 +325,27 @@ impl<'ctx> Compiler<'ctx> {
             string_ptr_type,
             declared_functions: HashMap::new(),
             function_return_types: HashMap::new(),
+            declared_return_types: HashMap::new(),
             expr_types,
             expr_types_by_span,
             comprehension_var_types,
             expr_spans: HashMap::new(),
             enum_layouts,
+            constants: HashMap::new(),
             function_defaults: HashMap::new(),
+            function_param_names: HashMap::new(),
+            variadic_functions: HashSet::new(),
+            function_param_types: HashMap::new(),
             lambda_counter: AtomicUsize::new(0),
             next_spawn_id: 0,
             struct_ids: HashMap::new(),
             struct_infos: Vec::new(),
+            traits: HashMap::new(),
+            receiver_methods: HashSet::new(),
+            generic_bounds: Vec::new(),
+            generic_functions: HashMap::new(),
+            generic_args: HashMap::new(),
+            pending_instantiations: Vec::new(),
             cached_ir: None,
             target_triple,
         }
@@ -302,6 +390,7 @@ impl<'ctx> Compiler<'ctx> {
             ty: struct_type,
             field_indices: HashMap::new(),
             field_types: Vec::new(),
+            bounds: Vec::new(),
         });
         (id, struct_type)
     }
@@ -326,37 +415,78 @@ impl<'ctx> Compiler<'ctx> {
         None
     }
 
-    fn rewrite_method_self_param(
+    /// Methods an `impl` block adds to its type, named and typed like struct methods: the
+    /// block's own methods plus, for a trait impl, a copy of each trait default it does not
+    /// override.
+    fn impl_methods(
         &self,
-        method_func: &mut otterc_ast::nodes::Function,
-        struct_name: &str,
-    ) {
-        let Some(first_param) = method_func.params.first_mut() else {
-            return;
-        };
+        trait_name: Option<&str>,
+        type_name: &str,
+        methods: &[Node<Function>],
+    ) -> Vec<Function> {
+        let defaults = trait_name.and_then(|name| self.traits.get(name));
+        let defaults = defaults.into_iter().flatten().filter(|default| {
+            !default.body.as_ref().is_empty()
+                && !methods
+                    .iter()
+                    .any(|method| method.as_ref().name == default.name)
+        });
+        methods
+            .iter()
+            .map(|method| method.as_ref())
+            .chain(defaults)
+            .map(|method| {
+                let mut method_func = method.clone();
+                method_func.name = format!("{}_{}", type_name, method_func.name);
+                method_func.resolve_self_type(type_name);
+                method_func
+            })
+            .collect()
+    }
 
-        // If the first parameter explicitly references `Self`, rewrite it to the
-        // concrete struct name. This keeps the existing behavior but allows the
-        // later logic to assume `self` always has a known type.
-        if let Some(ty) = first_param.as_ref().ty.as_ref()
-            && matches!(ty.as_ref(), otterc_ast::nodes::Type::Simple(name) if name == "Self")
-        {
-            let span = *ty.span();
-            let replacement = otterc_ast::nodes::Type::Simple(struct_name.to_string());
-            first_param.as_mut().ty = Some(Node::new(replacement, span));
-            return;
+    /// Methods of structs that take `self`, by lowered name. Every such method receives a
+    /// pointer to its receiver, so assignments to the fields of `self` update the caller's struct.
+    fn collect_receiver_methods(&self, program: &Program) -> HashSet<String> {
+        let struct_names: HashSet<&str> = program
+            .statements
+            .iter()
+            .filter_map(|statement| match statement.as_ref() {
+                Statement::Struct { name, .. } => Some(name.as_str()),
+                _ => None,
+            })
+            .collect();
+        let mut receiver_methods = HashSet::new();
+        for statement in &program.statements {
+            let methods = match statement.as_ref() {
+                Statement::Struct { name, methods, .. } => self.impl_methods(None, name, methods),
+                Statement::Impl {
+                    trait_name,
+                    type_name,
+                    methods,
+                } if struct_names.contains(type_name.as_str()) => {
+                    self.impl_methods(trait_name.as_deref(), type_name, methods)
+                }
+                _ => continue,
+            };
+            receiver_methods.extend(
+                methods
+                    .into_iter()
+                    .filter(|method| {
+                        method
+                            .params
+                            .first()
+                            .is_some_and(|param| param.as_ref().name.as_ref() == "self")
+                    })
+                    .map(|method| method.name),
+            );
         }
+        receiver_methods
+    }
 
-        // Methods commonly omit an explicit type on `self`. Ensure it is treated as the
-        // enclosing struct so later codegen knows the correct OtterType instead of
-        // defaulting to i64.
-        let param_name_is_self = first_param.as_ref().name.as_ref() == "self";
-        let has_type = first_param.as_ref().ty.is_some();
-        if param_name_is_self && !has_type {
-            let span = *first_param.as_ref().name.span();
-            let replacement = otterc_ast::nodes::Type::Simple(struct_name.to_string());
-            first_param.as_mut().ty = Some(Node::new(replacement, span));
-        }
+    fn struct_bounds(&self, name: &str) -> Vec<(String, Vec<String>)> {
+        self.struct_info_by_name(name)
+            .map(|(_, info)| info.bounds.clone())
+            .unwrap_or_default()
     }
 
     fn resolve_struct_method_name(&self, struct_id: u32, method: &str) -> Option<String> {
@@ -369,17 +499,85 @@ impl<'ctx> Compiler<'ctx> {
         }
     }
 
+    /// Whether `object.field(...)` calls a closure stored in a struct field, which happens when
+    /// the struct has no method of that name
+    fn is_function_field(&self, object: &Expr, field: &str) -> bool {
+        let Some(TypeInfo::Struct { name, fields }) = self.expr_type(object) else {
+            return false;
+        };
+        matches!(fields.get(field), Some(TypeInfo::Function { .. }))
+            && self
+                .struct_id(name)
+                .is_none_or(|id| self.resolve_struct_method_name(id, field).is_none())
+    }
+
+    /// Lowered name of `Type.function()` when `object` names a struct or enum rather than
+    /// a value
+    fn resolve_associated_function_name(
+        &self,
+        object: &Expr,
+        function: &str,
+        ctx: &FunctionContext<'ctx>,
+    ) -> Option<String> {
+        let Expr::Identifier(type_name) = object else {
+            return None;
+        };
+        if ctx.get(type_name).is_some()
+            || (self.struct_id(type_name).is_none() && self.enum_layout(type_name).is_none())
+        {
+            return None;
+        }
+        let candidate = format!("{}_{}", type_name, function);
+        self.declared_functions
+            .contains_key(&candidate)
+            .then_some(candidate)
+    }
+
+    /// Lowered name of a method called on an enum value. Enum values are opaque at this
+    /// level, so the receiver's type comes from the type checker.
+    fn resolve_enum_method_name(&self, object: &Expr, method: &str) -> Option<String> {
+        let Some(TypeInfo::Enum { name, .. }) = self.expr_type(object) else {
+            return None;
+        };
+        self.enum_method_name(name, method)
+    }
+
+    /// Lowered name of the method `method` of the enum `enum_name`, if it has one
+    fn enum_method_name(&self, enum_name: &str, method: &str) -> Option<String> {
+        let candidate = format!("{}_{}", enum_name, method);
+        self.declared_functions
+            .contains_key(&candidate)
+            .then_some(candidate)
+    }
+
     pub fn compile_module(&mut self, program: &Program) -> Result<()> {
         for statement in &program.statements {
             self.record_statement_spans(statement.as_ref());
         }
 
+        // The type checker already reported constants that fail to evaluate
+        let (constants, _) = evaluate_constants(&program.statements, |_| TypeInfo::Unknown);
+        self.constants = constants;
+
         // Prepare Rust bridges
         let _libraries = prepare_rust_bridges(program, self.symbol_registry)?;
 
+        // Collect trait declarations so impl blocks can pick up default methods
+        for statement in &program.statements {
+            if let Statement::Trait { name, methods, .. } = statement.as_ref() {
+                let methods = methods.iter().map(|method| method.as_ref().clone());
+                self.traits.insert(name.clone(), methods.collect());
+            }
+        }
+        self.receiver_methods = self.collect_receiver_methods(program);
+
         // First pass: register all functions and types
         for statement in &program.statements {
             match statement.as_ref() {
+                Statement::Function(func) if func.as_ref().is_generic() => {
+                    self.generic_functions
+                        .insert(func.as_ref().name.clone(), func.as_ref().clone());
+                }
                 Statement::Function(func) => {
                     self.register_function_prototype(func.as_ref())?;
                 }
@@ -387,9 +585,11 @@ impl<'ctx> Compiler<'ctx> {
                     name,
                     fields,
                     methods,
+                    bounds,
                     ..
                 } => {
                     let (struct_id, struct_type) = self.ensure_struct_info(name);
+                    self.generic_bounds = bounds.clone();
 
                     let mut field_layout = Vec::new();
                     let mut field_indices = HashMap::new();
@@ -404,15 +604,41 @@ impl<'ctx> Compiler<'ctx> {
                     if let Some(info) = self.struct_infos.get_mut(struct_id as usize) {
                         info.field_indices = field_indices;
                         info.field_types = field_types;
+                        info.bounds = bounds.clone();
                     }
 
                     // Register methods
                     for method in methods {
                         let mut method_func = method.as_ref().clone();
                         method_func.name = format!("{}_{}", name, method_func.name);
-                        self.rewrite_method_self_param(&mut method_func, name);
+                        method_func.resolve_self_type(name);
                         self.register_function_prototype(&method_func)?;
                     }
+                    self.generic_bounds.clear();
+                }
+                _ => {}
+            }
+        }
+
+        // Impl blocks and derived methods go last so that every struct layout is known.
+        // Default methods are instantiated once per implementing type, so calls on concrete
+        // receivers are dispatched statically.
+        for statement in &program.statements {
+            match statement.as_ref() {
+                Statement::Impl {
+                    trait_name,
+                    type_name,
+                    methods,
+                } => {
+                    self.generic_bounds = self.struct_bounds(type_name);
+                    for method_func in self.impl_methods(trait_name.as_deref(), type_name, methods)
+                    {
+                        self.register_function_prototype(&method_func)?;
+                    }
+                    self.generic_bounds.clear();
+                }
+                Statement::Struct { name, derives, .. } | Statement::Enum { name, derives, .. } => {
+                    self.declare_derived_methods(name, derives)?;
                 }
                 _ => {}
             }
@@ -421,22 +647,41 @@ impl<'ctx> Compiler<'ctx> {
         // Second pass: compile function bodies
         for statement in &program.statements {
             match statement.as_ref() {
+                Statement::Function(func) if func.as_ref().is_generic() => {}
                 Statement::Function(func) => {
                     self.record_function_spans(func.as_ref());
                     self.compile_function(func.as_ref())?;
                 }
                 Statement::Struct { name, methods, .. } => {
+                    self.generic_bounds = self.struct_bounds(name);
                     for method in methods {
                         let mut method_func = method.as_ref().clone();
                         method_func.name = format!("{}_{}", name, method_func.name);
-                        self.rewrite_method_self_param(&mut method_func, name);
+                        method_func.resolve_self_type(name);
+                        self.record_function_spans(&method_func);
+                        self.compile_function(&method_func)?;
+                    }
+                    self.generic_bounds.clear();
+                    self.compile_derived_methods(statement.as_ref())?;
+                }
+                Statement::Impl {
+                    trait_name,
+                    type_name,
+                    methods,
+                } => {
+                    self.generic_bounds = self.struct_bounds(type_name);
+                    for method_func in self.impl_methods(trait_name.as_deref(), type_name, methods)
+                    {
                         self.record_function_spans(&method_func);
                         self.compile_function(&method_func)?;
                     }
+                    self.generic_bounds.clear();
                 }
+                Statement::Enum { .. } => self.compile_derived_methods(statement.as_ref())?,
                 _ => {}
             }
         }
+        self.lower_instantiations(program)?;
 
         // Verify module
         if let Err(e) = self.module.verify() {
@@ -458,9 +703,11 @@ impl<'ctx> Compiler<'ctx> {
         // Helper to calculate struct size in bytes
         fn ffi_type_size(ffi_ty: &FfiType) -> usize {
             match ffi_ty {
-                FfiType::Unit | FfiType::Bool => 1,
-                FfiType::I32 => 4,
+                FfiType::Unit | FfiType::Bool | FfiType::I8 | FfiType::U8 => 1,
+                FfiType::I16 | FfiType::U16 => 2,
+                FfiType::I32 | FfiType::U32 | FfiType::F32 => 4,
                 FfiType::I64
+                | FfiType::U64
                 | FfiType::F64
                 | FfiType::Str
                 | FfiType::Opaque
@@ -492,12 +739,14 @@ impl<'ctx> Compiler<'ctx> {
             ffi_ty: &FfiType,
         ) -> BasicTypeEnum<'ctx> {
             match ffi_ty {
-                FfiType::Unit => context.i8_type().into(),
+                FfiType::Unit | FfiType::I8 | FfiType::U8 => context.i8_type().into(),
                 FfiType::Bool => context.bool_type().into(),
-                FfiType::I32 => context.i32_type().into(),
-                FfiType::I64 | FfiType::Opaque | FfiType::List | FfiType::Map => {
+                FfiType::I16 | FfiType::U16 => context.i16_type().into(),
+                FfiType::I32 | FfiType::U32 => context.i32_type().into(),
+                FfiType::I64 | FfiType::U64 | FfiType::Opaque | FfiType::List | FfiType::Map => {
                     context.i64_type().into()
                 }
+                FfiType::F32 => context.f32_type().into(),
                 FfiType::F64 => context.f64_type().into(),
                 FfiType::Str => string_ptr_type.into(),
                 FfiType::Struct { fields } | FfiType::Tuple(fields) => {
@@ -593,13 +842,16 @@ impl<'ctx> Compiler<'ctx> {
         match ty {
             otterc_ast::nodes::Type::Simple(name) => match name.as_str() {
                 // Lists and Maps are opaque handles
-                "int" | "i64" | "list" | "List" | "map" | "Map" => {
-                    Ok(self.context.i64_type().into())
+                "list" | "List" | "map" | "Map" => Ok(self.context.i64_type().into()),
+                numeric if let Some(ty) = OtterType::numeric_from_name(numeric) => {
+                    Ok(self.basic_type(ty)?.unwrap())
                 }
-                "float" | "f64" => Ok(self.context.f64_type().into()),
                 "bool" => Ok(self.context.bool_type().into()),
                 "string" | "str" => Ok(self.string_ptr_type.into()),
                 "void" | "unit" => Ok(self.context.i8_type().into()), // Unit as i8 (or void for return)
+                other if self.generic_args.contains_key(other) => Ok(self
+                    .basic_type(self.otter_type_from_annotation(ty))?
+                    .unwrap_or_else(|| self.context.i8_type().into())),
                 other => {
                     if let Some(id) = self.struct_id(other) {
                         Ok(self.struct_info(id).ty.into())
@@ -608,24 +860,43 @@ impl<'ctx> Compiler<'ctx> {
                     }
                 }
             },
-            otterc_ast::nodes::Type::Generic { .. } => Ok(self.context.i64_type().into()), // Treat generics as opaque handles
+            // Generics are treated as opaque handles, closures as environment handles
+            otterc_ast::nodes::Type::Generic { .. } | otterc_ast::nodes::Type::Function { .. } => {
+                Ok(self.context.i64_type().into())
+            }
+            otterc_ast::nodes::Type::Dyn(_) => Ok(self.context.i64_type().into()), // Trait objects are handles
+            otterc_ast::nodes::Type::Tuple(_) => self
+                .basic_type(self.otter_type_from_annotation(ty))?
+                .ok_or_else(|| anyhow!("tuple type has no LLVM representation")),
         }
     }
 
     fn otter_type_from_annotation(&self, ty: &otterc_ast::nodes::Type) -> OtterType {
         match ty {
             otterc_ast::nodes::Type::Simple(name) => match name.as_str() {
-                "int" | "i64" => OtterType::I64,
-                "float" | "f64" => OtterType::F64,
+                numeric if let Some(ty) = OtterType::numeric_from_name(numeric) => ty,
                 "bool" => OtterType::Bool,
                 "string" | "str" => OtterType::Str,
                 "unit" | "void" => OtterType::Unit,
                 "list" | "List" => OtterType::opaque_list(),
-                "map" | "Map" => OtterType::Map,
-                other => self
-                    .struct_id(other)
-                    .map(OtterType::Struct)
+                "map" | "Map" | "dict" | "Dict" => OtterType::Map,
+                other if self.generic_args.contains_key(other) => self
+                    .typeinfo_to_otter_type(&self.generic_args[other])
                     .unwrap_or(OtterType::Opaque),
+                other => {
+                    let bound = self
+                        .generic_bounds
+                        .iter()
+                        .find(|(generic, _)| generic == other)
+                        .and_then(|(_, traits)| traits.first());
+                    if let Some(trait_name) = bound {
+                        OtterType::Dyn(trait_name.clone())
+                    } else {
+                        self.struct_id(other)
+                            .map(OtterType::Struct)
+                            .unwrap_or(OtterType::Opaque)
+                    }
+                }
             },
             otterc_ast::nodes::Type::Generic { base, args, .. } => {
                 // Handle generic types like list<str>, map<str, int>, etc.
@@ -637,10 +908,26 @@ impl<'ctx> Compiler<'ctx> {
                             .unwrap_or(OtterType::Opaque);
                         OtterType::list_of(element)
                     }
-                    "map" | "Map" => OtterType::Map,
+                    "map" | "Map" | "dict" | "Dict" => OtterType::Map,
                     _ => OtterType::Opaque,
                 }
             }
+            otterc_ast::nodes::Type::Function { params, ret } => OtterType::Function {
+                params: params
+                    .iter()
+                    .map(|param| self.otter_type_from_annotation(param.as_ref()))
+                    .collect(),
+                ret: Box::new(ret.as_deref().map_or(OtterType::Unit, |ret| {
+                    self.otter_type_from_annotation(ret.as_ref())
+                })),
+            },
+            otterc_ast::nodes::Type::Dyn(trait_name) => OtterType::Dyn(trait_name.clone()),
+            otterc_ast::nodes::Type::Tuple(elements) => OtterType::Tuple(
+                elements
+                    .iter()
+                    .map(|element| self.otter_type_from_annotation(element.as_ref()))
+                    .collect(),
+            ),
         }
     }
 
@@ -649,6 +936,9 @@ impl<'ctx> Compiler<'ctx> {
             BasicTypeEnum::IntType(int_type) if int_type == self.context.bool_type() => {
                 OtterType::Bool
             }
+            BasicTypeEnum::IntType(int_type) if int_type == self.context.i16_type() => {
+                OtterType::I16
+            }
             BasicTypeEnum::IntType(int_type) if int_type == self.context.i32_type() => {
                 OtterType::I32
             }
@@ -658,6 +948,9 @@ impl<'ctx> Compiler<'ctx> {
             BasicTypeEnum::IntType(int_type) if int_type == self.context.i8_type() => {
                 OtterType::Unit
             }
+            BasicTypeEnum::FloatType(float_type) if float_type == self.context.f32_type() => {
+                OtterType::F32
+            }
             BasicTypeEnum::FloatType(float_type) if float_type == self.context.f64_type() => {
                 OtterType::F64
             }
@@ -700,9 +993,12 @@ impl<'ctx> Compiler<'ctx> {
             None
         };
 
+        let receiver_by_pointer = self.receiver_methods.contains(&func.name);
         let mut param_types = Vec::new();
-        for param in &func.params {
-            if let Some(ty) = &param.as_ref().ty {
+        for (index, param) in func.params.iter().enumerate() {
+            if index == 0 && receiver_by_pointer {
+                param_types.push(self.raw_ptr_type().into());
+            } else if let Some(ty) = &param.as_ref().ty {
                 param_types.push(self.map_ast_type(ty.as_ref())?.into());
             } else {
                 // Default to i64 if no type specified
@@ -732,6 +1028,12 @@ impl<'ctx> Compiler<'ctx> {
         };
         self.function_return_types
             .insert(func.name.clone(), ret_otter_type);
+        if let Some(ret_ty) = &func.ret_ty {
+            self.declared_return_types.insert(
+                func.name.clone(),
+                self.annotation_type_info(ret_ty.as_ref()),
+            );
+        }
 
         // Store default values
         let defaults: Vec<Option<Expr>> = func
@@ -740,20 +1042,55 @@ impl<'ctx> Compiler<'ctx> {
             .map(|p| p.as_ref().default.as_ref().map(|e| e.as_ref().clone()))
             .collect();
         self.function_defaults.insert(func.name.clone(), defaults);
+        let names = func
+            .params
+            .iter()
+            .map(|p| p.as_ref().name.as_ref().clone())
+            .collect();
+        self.function_param_names.insert(func.name.clone(), names);
+        if func.params.last().is_some_and(|p| p.as_ref().variadic) {
+            self.variadic_functions.insert(func.name.clone());
+        }
+
+        // Store parameter types so arguments can be boxed into trait objects
+        let param_otter_types: Vec<OtterType> = func
+            .params
+            .iter()
+            .map(|p| {
+                p.as_ref().ty.as_ref().map_or(OtterType::I64, |ty| {
+                    self.otter_type_from_annotation(ty.as_ref())
+                })
+            })
+            .collect();
+        self.function_param_types
+            .insert(func.name.clone(), param_otter_types);
 
         Ok(())
     }
 
     fn compile_function(&mut self, func: &otterc_ast::nodes::Function) -> Result<()> {
-        let function = self
+        self.compile_function_as(func, &func.name)
+    }
+
+    /// Lower the body of `func` into the function declared as `name`
+    fn compile_function_as(
+        &mut self,
+        func: &otterc_ast::nodes::Function,
+        name: &str,
+    ) -> Result<()> {
+        let function = *self
             .declared_functions
-            .get(&func.name)
-            .ok_or_else(|| anyhow!("Function {} not found", func.name))?;
+            .get(name)
+            .ok_or_else(|| anyhow!("Function {} not found", name))?;
 
-        let entry = self.context.append_basic_block(*function, "entry");
+        let entry = self.context.append_basic_block(function, "entry");
         self.builder.position_at_end(entry);
 
         let mut ctx = FunctionContext::new();
+        ctx.return_type = func
+            .ret_ty
+            .as_ref()
+            .map(|ret_ty| self.annotation_type_info(ret_ty.as_ref()));
 
         // Bind arguments
         for (i, param) in func.params.iter().enumerate() {
@@ -769,9 +1106,21 @@ impl<'ctx> Compiler<'ctx> {
                 (self.context.i64_type().into(), OtterType::I64)
             };
 
+            // Methods work on the caller's struct directly
+            if i == 0 && self.receiver_methods.contains(name) {
+                ctx.insert(
+                    param_name.as_ref().to_string(),
+                    crate::llvm::compiler::types::Variable {
+                        ptr: arg_val.into_pointer_value(),
+                        ty: otter_type,
+                    },
+                );
+                continue;
+            }
+
             // Allocate stack space for parameter
             let alloca = self.create_entry_block_alloca(
-                *function,
+                function,
                 param_name.as_ref().as_str(),
                 otter_type.clone(),
             )?;
@@ -782,13 +1131,29 @@ impl<'ctx> Compiler<'ctx> {
                 param_name.as_ref().to_string(),
                 crate::llvm::compiler::types::Variable {
                     ptr: alloca,
-                    ty: otter_type,
+                    ty: otter_type.clone(),
                 },
             );
+
+            if let Some(pattern) = &param.as_ref().pattern {
+                let type_info = param
+                    .as_ref()
+                    .ty
+                    .as_ref()
+                    .map(|ty| self.annotation_type_info(ty.as_ref()));
+                self.bind_pattern_value(
+                    pattern,
+                    Some(arg_val),
+                    otter_type,
+                    type_info,
+                    function,
+                    &mut ctx,
+                )?;
+            }
         }
 
         // Compile body
-        self.lower_block(func.body.as_ref(), *function, &mut ctx)?;
+        self.lower_block(func.body.as_ref(), function, &mut ctx)?;
 
         // Add implicit return if needed
         if self
@@ -882,11 +1247,19 @@ impl<'ctx> Compiler<'ctx> {
             .builder
             .build_call(alloc_func, &[size_val.into()], "alloc")?;
 
-        Ok(call
+        // `otter_alloc` is registered as returning an opaque handle, which lowers to an i64
+        match call
             .try_as_basic_value()
             .left()
-            .unwrap()
-            .into_pointer_value())
+            .ok_or_else(|| anyhow!("gc.alloc returned void"))?
+        {
+            BasicValueEnum::PointerValue(ptr) => Ok(ptr),
+            handle => Ok(self.builder.build_int_to_ptr(
+                handle.into_int_value(),
+                self.raw_ptr_type(),
+                "alloc_ptr",
+            )?),
+        }
     }
 
     /// Register a root with the GC
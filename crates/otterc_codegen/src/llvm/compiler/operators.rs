@@ -0,0 +1,587 @@
+//! Operators on structs and enums. `a + b` calls the `add` method of `a`, `a > b` calls
+//! `b.lt(a)` and so on, see `BinaryOp::method_name`. The same `lt` method orders the
+//! elements of `sort`, the `hash` method keys a value in a dict and backs `hash(x)`, and the
+//! `str` method prints a value in `str(x)` and f-strings.
+
+use anyhow::{Result, anyhow, bail};
+use inkwell::IntPredicate;
+use inkwell::types::BasicTypeEnum;
+use inkwell::values::{BasicMetadataValueEnum, BasicValueEnum, FunctionValue};
+
+use crate::llvm::compiler::Compiler;
+use crate::llvm::compiler::place::Place;
+use crate::llvm::compiler::types::{EvaluatedValue, FunctionContext, OtterType};
+use otterc_ast::nodes::{BinaryOp, Expr, UnaryOp};
+use otterc_typecheck::TypeInfo;
+
+impl<'ctx> Compiler<'ctx> {
+    /// `lhs op rhs` where one of the operands is a struct
+    pub(crate) fn build_struct_binary(
+        &mut self,
+        op: BinaryOp,
+        lhs: EvaluatedValue<'ctx>,
+        rhs: EvaluatedValue<'ctx>,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<EvaluatedValue<'ctx>> {
+        let method = op
+            .method_name()
+            .ok_or_else(|| anyhow!("`{}` is not supported on structs", op.symbol()))?;
+        let (receiver, other) = if op.swaps_operands() {
+            (rhs, lhs)
+        } else {
+            (lhs, rhs)
+        };
+        let result = self.call_operator_method(receiver, method, Some(other))?;
+        self.finish_operator_call(op, result, ctx)
+    }
+
+    /// `lhs op rhs` where the receiver is an enum whose operator method is lowered as `method`
+    pub(crate) fn build_enum_binary(
+        &mut self,
+        op: BinaryOp,
+        method: &str,
+        lhs: EvaluatedValue<'ctx>,
+        rhs: EvaluatedValue<'ctx>,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<EvaluatedValue<'ctx>> {
+        let (receiver, other) = if op.swaps_operands() {
+            (rhs, lhs)
+        } else {
+            (lhs, rhs)
+        };
+        let result = self.call_method(method, receiver, Some(other))?;
+        self.finish_operator_call(op, result, ctx)
+    }
+
+    fn finish_operator_call(
+        &mut self,
+        op: BinaryOp,
+        result: EvaluatedValue<'ctx>,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<EvaluatedValue<'ctx>> {
+        self.check_pending_exception(ctx)?;
+        if !op.negates_result() {
+            return Ok(result);
+        }
+        let value = result
+            .value
+            .ok_or_else(|| anyhow!("operator method for `{}` returned no value", op.symbol()))?
+            .into_int_value();
+        let negated = self.builder.build_not(value, "not")?;
+        Ok(EvaluatedValue::with_value(negated.into(), OtterType::Bool))
+    }
+
+    /// `-operand` or `~operand` on a struct
+    pub(crate) fn build_struct_unary(
+        &mut self,
+        op: UnaryOp,
+        operand: EvaluatedValue<'ctx>,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<EvaluatedValue<'ctx>> {
+        let method = op
+            .method_name()
+            .ok_or_else(|| anyhow!("{:?} is not supported on structs", op))?;
+        let result = self.call_operator_method(operand, method, None)?;
+        self.check_pending_exception(ctx)?;
+        Ok(result)
+    }
+
+    /// Calls the operator method `method` of the struct `receiver`
+    fn call_operator_method(
+        &mut self,
+        receiver: EvaluatedValue<'ctx>,
+        method: &str,
+        other: Option<EvaluatedValue<'ctx>>,
+    ) -> Result<EvaluatedValue<'ctx>> {
+        let OtterType::Struct(struct_id) = receiver.ty else {
+            bail!(
+                "operator method `{}` called on non-struct {:?}",
+                method,
+                receiver.ty
+            );
+        };
+        let name = self
+            .resolve_struct_method_name(struct_id, method)
+            .ok_or_else(|| {
+                anyhow!(
+                    "struct method '{}.{}' not found",
+                    self.struct_info(struct_id).name,
+                    method
+                )
+            })?;
+        self.call_method(&name, receiver, other)
+    }
+
+    /// Calls the method lowered as `name` on `receiver`, passing `other` if given. Operands
+    /// are temporaries, so a method that takes `self` by reference is given a copy.
+    pub(crate) fn call_method(
+        &mut self,
+        name: &str,
+        receiver: EvaluatedValue<'ctx>,
+        other: Option<EvaluatedValue<'ctx>>,
+    ) -> Result<EvaluatedValue<'ctx>> {
+        let function = self.declared_functions[name];
+        let param_types: Vec<BasicTypeEnum> = function
+            .get_param_iter()
+            .map(|param| param.get_type())
+            .collect();
+
+        let mut args: Vec<BasicMetadataValueEnum> = Vec::with_capacity(2);
+        let param_type = param_types
+            .first()
+            .ok_or_else(|| anyhow!("Method '{}' missing self parameter", name))?;
+        if self.receiver_methods.contains(name) {
+            args.push(self.spill_receiver(receiver)?.into());
+        } else {
+            let receiver_value = receiver
+                .value
+                .ok_or_else(|| anyhow!("Cannot pass unit value as self"))?;
+            args.push(
+                self.cast_argument_for_call(receiver_value, receiver.ty, param_type)?
+                    .into(),
+            );
+        }
+        if let Some(other) = other {
+            let param_type = param_types
+                .get(1)
+                .ok_or_else(|| anyhow!("operator method '{}' takes no operand", name))?;
+            let mut value = other
+                .value
+                .ok_or_else(|| anyhow!("Cannot pass unit value as argument"))?;
+            let mut value_ty = other.ty;
+            // Structs passed where a trait object is expected are boxed first
+            if let Some(dyn_ty @ OtterType::Dyn(_)) = self
+                .function_param_types
+                .get(name)
+                .and_then(|types| types.get(1))
+                .cloned()
+            {
+                value = self.coerce_value(value, value_ty, dyn_ty.clone())?;
+                value_ty = dyn_ty;
+            }
+            args.push(
+                self.cast_argument_for_call(value, value_ty, param_type)?
+                    .into(),
+            );
+        }
+
+        let call = self.builder.build_call(function, &args, name)?;
+        let value = call
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| anyhow!("operator method '{}' returned no value", name))?;
+        let ty = self
+            .function_return_types
+            .get(name)
+            .cloned()
+            .unwrap_or(OtterType::Opaque);
+        Ok(EvaluatedValue::with_value(value, ty))
+    }
+
+    /// `sort(xs)`: lists of structs and enums are ordered by their `lt` method, other lists
+    /// by the runtime's natural order of numbers, bools and strings
+    pub(crate) fn build_sort_call(
+        &mut self,
+        list: &Expr,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<EvaluatedValue<'ctx>> {
+        let enum_lt = match self.expr_type(list) {
+            Some(TypeInfo::List(element)) => match element.as_ref() {
+                TypeInfo::Enum { name, .. } => Some(format!("{}_lt", name))
+                    .filter(|lt| self.declared_functions.contains_key(lt)),
+                _ => None,
+            },
+            _ => None,
+        };
+        let evaluated = self.eval_expr(list, ctx)?;
+        let handle = evaluated
+            .value
+            .ok_or_else(|| anyhow!("sort expects a list value"))?;
+        let element = evaluated
+            .ty
+            .list_element()
+            .filter(|ty| **ty != OtterType::Opaque)
+            .cloned()
+            .or_else(|| self.list_element_type(list));
+
+        let less = match (element, enum_lt) {
+            (Some(OtterType::Struct(struct_id)), _) => {
+                let lt = self
+                    .resolve_struct_method_name(struct_id, "lt")
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "struct method '{}.lt' not found",
+                            self.struct_info(struct_id).name
+                        )
+                    })?;
+                Some(self.boxed_method_thunk(OtterType::Struct(struct_id), &lt)?)
+            }
+            (_, Some(lt)) => Some(self.boxed_method_thunk(OtterType::Opaque, &lt)?),
+            _ => None,
+        };
+        if let Some(less) = less {
+            let address = self.function_address(less)?;
+            let sort_fn = self.get_or_declare_ffi_function("sort_by<list>")?;
+            self.builder
+                .build_call(sort_fn, &[handle.into(), address.into()], "sort")?;
+            // `lt` runs inside the sort, so an exception it raises surfaces afterwards
+            self.check_pending_exception(ctx)?;
+        } else {
+            let sort_fn = self.get_or_declare_ffi_function("sort<list>")?;
+            self.builder.build_call(sort_fn, &[handle.into()], "sort")?;
+        }
+        Ok(EvaluatedValue {
+            ty: OtterType::Unit,
+            value: None,
+        })
+    }
+
+    /// Emits `fn(u64, u64) -> bool` for the runtime to compare two boxed values of type
+    /// `element`, such as list elements or map keys: structs are unboxed, and both are
+    /// passed to the method lowered as `method`.
+    fn boxed_method_thunk(
+        &mut self,
+        element: OtterType,
+        method: &str,
+    ) -> Result<FunctionValue<'ctx>> {
+        let thunk_name = format!("__otter_boxed_{}", method);
+        if let Some(existing) = self.module.get_function(&thunk_name) {
+            return Ok(existing);
+        }
+
+        let i64_type = self.context.i64_type();
+        let fn_type = self
+            .context
+            .bool_type()
+            .fn_type(&[i64_type.into(), i64_type.into()], false);
+        let thunk = self.module.add_function(&thunk_name, fn_type, None);
+        let entry = self.context.append_basic_block(thunk, "entry");
+        let prev_block = self.builder.get_insert_block();
+        self.builder.position_at_end(entry);
+
+        let mut operands = Vec::with_capacity(2);
+        for index in 0..2 {
+            let handle = thunk
+                .get_nth_param(index)
+                .ok_or_else(|| anyhow!("`{}` thunk missing operand", method))?
+                .into_int_value();
+            // Enum values are boxed as their handles
+            let value: BasicValueEnum = match element {
+                OtterType::Struct(_) => self.unbox_tuple(handle, element.clone())?,
+                _ => handle.into(),
+            };
+            operands.push(EvaluatedValue::with_value(value, element.clone()));
+        }
+        let other = operands.pop();
+        let receiver = operands
+            .pop()
+            .ok_or_else(|| anyhow!("`{}` thunk missing receiver", method))?;
+        let result = self.call_method(method, receiver, other)?;
+        let result = result
+            .value
+            .ok_or_else(|| anyhow!("`{}` returned no value", method))?;
+        self.builder.build_return(Some(&result))?;
+
+        if let Some(block) = prev_block {
+            self.builder.position_at_end(block);
+        }
+        Ok(thunk)
+    }
+
+    /// Address of `function`, passed to the runtime as an opaque value
+    pub(crate) fn function_address(
+        &mut self,
+        function: FunctionValue<'ctx>,
+    ) -> Result<BasicValueEnum<'ctx>> {
+        let address = self.builder.build_ptr_to_int(
+            function.as_global_value().as_pointer_value(),
+            self.context.i64_type(),
+            "function_address",
+        )?;
+        Ok(address.into())
+    }
+
+    /// Lowered name of the method `method` of `value`, the result of `expr`, when it is a
+    /// struct or enum that defines one
+    fn value_method_name(
+        &self,
+        expr: &Expr,
+        value: &EvaluatedValue<'ctx>,
+        method: &str,
+    ) -> Option<String> {
+        match value.ty {
+            OtterType::Struct(struct_id) => self.resolve_struct_method_name(struct_id, method),
+            OtterType::Opaque => self.resolve_enum_method_name(expr, method),
+            _ => None,
+        }
+    }
+
+    /// Runtime key of a dict entry in the map `map`. Maps are keyed by strings, so the
+    /// runtime picks a slot for a struct or enum key from its `hash` method and tells keys
+    /// with the same hash apart with its `eq` method.
+    pub(crate) fn map_key_value(
+        &mut self,
+        map: BasicValueEnum<'ctx>,
+        key: &Expr,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<BasicValueEnum<'ctx>> {
+        let value = self.eval_expr(key, ctx)?;
+        let (Some(hash_method), Some(eq_method)) = (
+            self.value_method_name(key, &value, "hash"),
+            self.value_method_name(key, &value, "eq"),
+        ) else {
+            return self.ensure_string_value(value);
+        };
+        let hash = self.call_method(&hash_method, value.clone(), None)?;
+        self.check_pending_exception(ctx)?;
+        let hash_value = hash
+            .value
+            .ok_or_else(|| anyhow!("`{}` returned no value", hash_method))?;
+        let hash_value = self.convert_number(hash_value, &hash.ty, &OtterType::I64)?;
+
+        let eq = self.boxed_method_thunk(value.ty.clone(), &eq_method)?;
+        let eq_address = self.function_address(eq)?;
+        let boxed_key = self.value_as_i64(value)?;
+        let slot = self.call_ffi_returning_value(
+            "map.key_slot",
+            vec![map, hash_value, boxed_key.into(), eq_address],
+            "key_slot",
+        )?;
+        // `eq` runs inside the lookup, so an exception it raises surfaces afterwards
+        self.check_pending_exception(ctx)?;
+        Ok(slot)
+    }
+
+    /// `hash(x)`: numbers hash to their value or bits, strings through the runtime, and
+    /// structs and enums call their `hash` method
+    pub(crate) fn build_hash_call(
+        &mut self,
+        expr: &Expr,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<EvaluatedValue<'ctx>> {
+        let value = self.eval_expr(expr, ctx)?;
+        let method = self.value_method_name(expr, &value, "hash");
+        self.hash_value(value, method, ctx)
+    }
+
+    /// Hash of `value`, by the method lowered as `method` when it is a struct or enum
+    pub(crate) fn hash_value(
+        &mut self,
+        value: EvaluatedValue<'ctx>,
+        method: Option<String>,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<EvaluatedValue<'ctx>> {
+        if let Some(method) = method {
+            let hash = self.call_method(&method, value, None)?;
+            self.check_pending_exception(ctx)?;
+            return Ok(hash);
+        }
+        let raw = value
+            .value
+            .ok_or_else(|| anyhow!("cannot hash a unit value"))?;
+        let i64_type = self.context.i64_type();
+        let hash: BasicValueEnum = match &value.ty {
+            OtterType::Bool => self
+                .builder
+                .build_int_z_extend(raw.into_int_value(), i64_type, "hash_bool")?
+                .into(),
+            int if int.is_integer() => self.convert_number(raw, int, &OtterType::I64)?,
+            float @ (OtterType::F32 | OtterType::F64) => {
+                let widened = self
+                    .convert_number(raw, float, &OtterType::F64)?
+                    .into_float_value();
+                // Adding zero turns -0.0 into 0.0, which compares equal to it
+                let zero = self.context.f64_type().const_zero();
+                let normalized = self.builder.build_float_add(widened, zero, "hash_float")?;
+                self.builder
+                    .build_bit_cast(normalized, i64_type, "hash_bits")?
+            }
+            OtterType::Str => {
+                self.call_ffi_returning_value("std.strings.hash", vec![raw], "hash_str")?
+            }
+            other => bail!("cannot hash a value of type {:?}", other),
+        };
+        Ok(EvaluatedValue::with_value(hash, OtterType::I64))
+    }
+
+    /// `str(x)` and `{x}` in an f-string: structs and enums with a `str` method are printed by
+    /// it, lists and tuples element by element, other values as the runtime formats them
+    pub(crate) fn build_display_value(
+        &mut self,
+        expr: &Expr,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<EvaluatedValue<'ctx>> {
+        let value = self.eval_expr(expr, ctx)?;
+        let info = self.expr_type(expr).cloned();
+        self.display_typed(value, info.as_ref(), ctx)
+    }
+
+    /// Text of `value` whose type the checker inferred as `info`. Enums are opaque handles
+    /// here, so `info` is what finds the `str` method of an enum, also as a list or tuple
+    /// element.
+    fn display_typed(
+        &mut self,
+        value: EvaluatedValue<'ctx>,
+        info: Option<&TypeInfo>,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<EvaluatedValue<'ctx>> {
+        match (&value.ty, info) {
+            (OtterType::Tuple(_), Some(TypeInfo::Tuple(elements))) => {
+                self.display_tuple(value, elements, ctx)
+            }
+            (OtterType::List(element), Some(TypeInfo::List(element_info)))
+                if self.displays_elements_itself(element, Some(element_info)) =>
+            {
+                self.display_list(value, Some(element_info), ctx)
+            }
+            (OtterType::Struct(struct_id), _) => {
+                let method = self.resolve_struct_method_name(*struct_id, "str");
+                self.display_value(value, method, ctx)
+            }
+            (_, Some(TypeInfo::Enum { name, .. })) => {
+                let method = self.enum_method_name(name, "str");
+                self.display_value(value, method, ctx)
+            }
+            _ => self.display_value(value, None, ctx),
+        }
+    }
+
+    /// Text of `value`, printed by the method lowered as `method` when it is a struct or enum
+    pub(crate) fn display_value(
+        &mut self,
+        value: EvaluatedValue<'ctx>,
+        method: Option<String>,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<EvaluatedValue<'ctx>> {
+        let Some(method) = method else {
+            match &value.ty {
+                OtterType::Tuple(_) => return self.display_tuple(value, &[], ctx),
+                OtterType::List(element) if self.displays_elements_itself(element, None) => {
+                    return self.display_list(value, None, ctx);
+                }
+                _ => {}
+            }
+            let text = self.ensure_string_value(value)?;
+            return Ok(EvaluatedValue::with_value(text, OtterType::Str));
+        };
+        let text = self.call_method(&method, value, None)?;
+        self.check_pending_exception(ctx)?;
+        Ok(text)
+    }
+
+    /// Whether a list of `element` is shown element by element rather than by the runtime,
+    /// which formats numbers, strings and lists of them but knows no struct, tuple or enum
+    /// and stores a `u64` as a signed integer
+    fn displays_elements_itself(&self, element: &OtterType, info: Option<&TypeInfo>) -> bool {
+        match (element, info) {
+            (OtterType::Struct(_) | OtterType::Tuple(_) | OtterType::U64, _) => true,
+            (_, Some(TypeInfo::Enum { name, .. })) => self.enum_method_name(name, "str").is_some(),
+            (OtterType::List(inner), Some(TypeInfo::List(inner_info))) => {
+                self.displays_elements_itself(inner, Some(inner_info))
+            }
+            (OtterType::List(inner), _) => self.displays_elements_itself(inner, None),
+            _ => false,
+        }
+    }
+
+    /// `[a, b]` for the list `value`, each element shown as `str` shows it. `element_info` is
+    /// the checker's type of the elements when known.
+    fn display_list(
+        &mut self,
+        value: EvaluatedValue<'ctx>,
+        element_info: Option<&TypeInfo>,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<EvaluatedValue<'ctx>> {
+        let OtterType::List(element_ty) = value.ty else {
+            bail!("cannot display {:?} as a list", value.ty);
+        };
+        let handle = value.value.ok_or_else(|| anyhow!("list has no value"))?;
+        let function = self
+            .builder
+            .get_insert_block()
+            .and_then(|block| block.get_parent())
+            .ok_or_else(|| anyhow!("list displayed outside a function"))?;
+        let i64_type = self.context.i64_type();
+        let index_ptr =
+            self.create_entry_block_alloca(function, "display_index", OtterType::I64)?;
+        let text_ptr = self.create_entry_block_alloca(function, "display_text", OtterType::Str)?;
+        self.builder.build_store(index_ptr, i64_type.const_zero())?;
+        let open = self.builder.build_global_string_ptr("[", "display_open")?;
+        self.builder
+            .build_store(text_ptr, open.as_pointer_value())?;
+        let len = self
+            .call_ffi_returning_value("len<list>", vec![handle], "list_len")?
+            .into_int_value();
+
+        let cond_bb = self
+            .context
+            .append_basic_block(function, "list_display_cond");
+        let body_bb = self
+            .context
+            .append_basic_block(function, "list_display_body");
+        let done_bb = self
+            .context
+            .append_basic_block(function, "list_display_done");
+        self.builder.build_unconditional_branch(cond_bb)?;
+
+        self.builder.position_at_end(cond_bb);
+        let index = self
+            .builder
+            .build_load(i64_type, index_ptr, "display_index")?
+            .into_int_value();
+        let in_range =
+            self.builder
+                .build_int_compare(IntPredicate::SLT, index, len, "display_in_range")?;
+        self.builder
+            .build_conditional_branch(in_range, body_bb, done_bb)?;
+
+        self.builder.position_at_end(body_bb);
+        let text = self
+            .builder
+            .build_load(self.string_ptr_type, text_ptr, "display_text")?;
+        let is_first = self.builder.build_int_compare(
+            IntPredicate::EQ,
+            index,
+            i64_type.const_zero(),
+            "display_first",
+        )?;
+        let none = self.builder.build_global_string_ptr("", "display_none")?;
+        let comma = self
+            .builder
+            .build_global_string_ptr(", ", "display_comma")?;
+        let separator = self.builder.build_select(
+            is_first,
+            none.as_pointer_value(),
+            comma.as_pointer_value(),
+            "display_separator",
+        )?;
+        let text = self.build_string_concat(
+            EvaluatedValue::with_value(text, OtterType::Str),
+            EvaluatedValue::with_value(separator, OtterType::Str),
+        )?;
+        let place = Place::ListElement {
+            handle,
+            index,
+            ty: *element_ty,
+        };
+        let element = self.load_place(&place)?;
+        let shown = self.display_typed(element, element_info, ctx)?;
+        let text = self
+            .build_string_concat(text, shown)?
+            .value
+            .ok_or_else(|| anyhow!("concatenation produced no value"))?;
+        self.builder.build_store(text_ptr, text)?;
+        let next =
+            self.builder
+                .build_int_add(index, i64_type.const_int(1, false), "display_next")?;
+        self.builder.build_store(index_ptr, next)?;
+        self.builder.build_unconditional_branch(cond_bb)?;
+
+        self.builder.position_at_end(done_bb);
+        let text = self
+            .builder
+            .build_load(self.string_ptr_type, text_ptr, "display_text")?;
+        let close = self.text_literal("]")?;
+        self.build_string_concat(EvaluatedValue::with_value(text, OtterType::Str), close)
+    }
+}
@@ -0,0 +1,327 @@
+//! Lowering of assignable places: variables, struct fields, and the elements of lists and
+//! maps. A place is resolved once, evaluating its object and index, and can then be read
+//! and written, so `xs[f()] += 1` calls `f` a single time. Fields of a struct held in a
+//! collection are written back by updating the whole element.
+
+use anyhow::{Result, anyhow, bail};
+use inkwell::types::StructType;
+use inkwell::values::{BasicValueEnum, IntValue, PointerValue};
+
+use crate::llvm::compiler::Compiler;
+use crate::llvm::compiler::types::{EvaluatedValue, FunctionContext, OtterType};
+use otterc_ast::nodes::{BinaryOp, Expr};
+
+/// A resolved assignment target
+#[derive(Debug, Clone)]
+pub(crate) enum Place<'ctx> {
+    /// A variable, or a field reached from one through member accesses
+    Pointer(PointerValue<'ctx>, OtterType),
+    /// An element of a runtime list, with its index already bounds checked
+    ListElement {
+        handle: BasicValueEnum<'ctx>,
+        index: IntValue<'ctx>,
+        ty: OtterType,
+    },
+    /// An entry of a runtime map
+    MapEntry {
+        handle: BasicValueEnum<'ctx>,
+        key: BasicValueEnum<'ctx>,
+        ty: OtterType,
+    },
+    /// A field of a struct that lives in a list or map rather than in a variable
+    Field {
+        base: Box<Place<'ctx>>,
+        index: u32,
+        ty: OtterType,
+    },
+}
+
+impl Place<'_> {
+    pub(crate) fn ty(&self) -> &OtterType {
+        match self {
+            Place::Pointer(_, ty)
+            | Place::ListElement { ty, .. }
+            | Place::MapEntry { ty, .. }
+            | Place::Field { ty, .. } => ty,
+        }
+    }
+}
+
+impl<'ctx> Compiler<'ctx> {
+    /// `target = expr`, or `target op= expr` when `op` is given
+    pub(crate) fn lower_assignment(
+        &mut self,
+        target: &Expr,
+        op: Option<BinaryOp>,
+        expr: &Expr,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<()> {
+        let place = self.resolve_place(target, ctx)?;
+        let value = match op {
+            Some(op) => {
+                let enum_method = op
+                    .method_name()
+                    .and_then(|method| self.resolve_enum_method_name(target, method));
+                let current = self.load_place(&place)?;
+                let operand = self.eval_expr(expr, ctx)?;
+                self.build_binary_values(&op, current, operand, enum_method, ctx)?
+            }
+            None => self.eval_expr(expr, ctx)?,
+        };
+        self.store_place(&place, value)
+    }
+
+    /// Evaluates the objects and indexes of a place expression
+    pub(crate) fn resolve_place(
+        &mut self,
+        place: &Expr,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<Place<'ctx>> {
+        match place {
+            Expr::Identifier(name) => {
+                let var = ctx
+                    .get(name)
+                    .ok_or_else(|| anyhow!("Variable {} not declared", name))?;
+                Ok(Place::Pointer(var.ptr, var.ty.clone()))
+            }
+            Expr::Member { object, field } => {
+                let base = self.resolve_place(object.as_ref().as_ref(), ctx)?;
+                let (index, field_ty, struct_ty) = self.struct_field(base.ty(), field)?;
+                match base {
+                    Place::Pointer(ptr, _) => {
+                        let field_ptr = self
+                            .builder
+                            .build_struct_gep(struct_ty, ptr, index, field)?;
+                        Ok(Place::Pointer(field_ptr, field_ty))
+                    }
+                    base => Ok(Place::Field {
+                        base: Box::new(base),
+                        index,
+                        ty: field_ty,
+                    }),
+                }
+            }
+            Expr::Index { object, index } => {
+                let object_value = self.eval_expr(object.as_ref().as_ref(), ctx)?;
+                let element_ty = self
+                    .expr_type(place)
+                    .and_then(|ty| self.typeinfo_to_otter_type(ty));
+                self.element_place(object_value, index.as_ref().as_ref(), element_ty, ctx)
+            }
+            _ => bail!("expression is not an assignable place: {:?}", place),
+        }
+    }
+
+    /// The element of an evaluated list or map at `index`. `element_ty` is the checker's
+    /// type of the element, which a list's own element type takes precedence over.
+    pub(crate) fn element_place(
+        &mut self,
+        object: EvaluatedValue<'ctx>,
+        index: &Expr,
+        element_ty: Option<OtterType>,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<Place<'ctx>> {
+        let handle = object
+            .value
+            .ok_or_else(|| anyhow!("cannot index into an expression without a value"))?;
+        match object.ty {
+            OtterType::List(_) | OtterType::Opaque => {
+                let index_value = self.eval_index_value(index, ctx)?;
+                let len = self
+                    .call_ffi_returning_value("len<list>", vec![handle], "list_len")?
+                    .into_int_value();
+                let index =
+                    self.build_checked_index(index_value, len, "list index out of range")?;
+                let ty = object
+                    .ty
+                    .list_element()
+                    .filter(|ty| **ty != OtterType::Opaque)
+                    .cloned()
+                    .or(element_ty)
+                    .unwrap_or(OtterType::Opaque);
+                Ok(Place::ListElement { handle, index, ty })
+            }
+            OtterType::Map => {
+                let key = self.map_key_value(handle, index, ctx)?;
+                Ok(Place::MapEntry {
+                    handle,
+                    key,
+                    ty: element_ty.unwrap_or(OtterType::Opaque),
+                })
+            }
+            other => bail!("cannot index into value of type {:?}", other),
+        }
+    }
+
+    /// Reads the current value of a place
+    pub(crate) fn load_place(&mut self, place: &Place<'ctx>) -> Result<EvaluatedValue<'ctx>> {
+        match place {
+            Place::Pointer(ptr, ty) => match self.basic_type(ty.clone())? {
+                Some(basic_ty) => {
+                    let value = self.builder.build_load(basic_ty, *ptr, "place_value")?;
+                    Ok(EvaluatedValue::with_value(value, ty.clone()))
+                }
+                None => Ok(EvaluatedValue {
+                    ty: ty.clone(),
+                    value: None,
+                }),
+            },
+            Place::ListElement { handle, index, ty } => {
+                let args = vec![*handle, (*index).into()];
+                self.load_collection_slot(args, ty, false)
+            }
+            Place::MapEntry { handle, key, ty } => {
+                let has_key = self
+                    .call_ffi_returning_value("map.has", vec![*handle, *key], "map_has")?
+                    .into_int_value();
+                self.build_runtime_check(has_key, "key not found in map")?;
+                self.load_collection_slot(vec![*handle, *key], ty, true)
+            }
+            Place::Field { base, index, ty } => {
+                let base = self.load_place(base)?;
+                let value = base
+                    .value
+                    .ok_or_else(|| anyhow!("struct place produced no value"))?;
+                let field = self.builder.build_extract_value(
+                    value.into_struct_value(),
+                    *index,
+                    "field_value",
+                )?;
+                Ok(EvaluatedValue::with_value(field, ty.clone()))
+            }
+        }
+    }
+
+    /// Writes `value` into a place, converting it to the place's type
+    pub(crate) fn store_place(
+        &mut self,
+        place: &Place<'ctx>,
+        value: EvaluatedValue<'ctx>,
+    ) -> Result<()> {
+        let Some(raw) = value.value else {
+            if value.ty != OtterType::Unit {
+                bail!("Cannot assign non-unit expression with no value");
+            }
+            // Unit type assignments are no-ops
+            return Ok(());
+        };
+        let ty = match place.ty() {
+            OtterType::Opaque => value.ty.clone(),
+            ty => ty.clone(),
+        };
+        let raw = self.coerce_value(raw, value.ty, ty.clone())?;
+        match place {
+            Place::Pointer(ptr, _) => {
+                self.builder.build_store(*ptr, raw)?;
+            }
+            Place::ListElement { handle, index, .. } => {
+                // Tuples and structs are kept as handles to a heap copy, see `box_tuple`
+                let (raw, ty) = self.box_aggregate(raw, ty)?;
+                let (set_fn_name, expected_ty) = self.list_set_target(&ty)?;
+                let raw = self.coerce_type(raw, ty, expected_ty)?;
+                self.call_ffi_returning_value(
+                    set_fn_name,
+                    vec![*handle, (*index).into(), raw],
+                    "list_set",
+                )?;
+            }
+            Place::MapEntry { handle, key, .. } => {
+                let (raw, ty) = self.box_aggregate(raw, ty)?;
+                let (set_fn_name, expected_ty) = self.map_set_target(&ty)?;
+                let raw = self.coerce_type(raw, ty, expected_ty)?;
+                self.call_ffi_returning_value(set_fn_name, vec![*handle, *key, raw], "map_set")?;
+            }
+            Place::Field { base, index, .. } => {
+                let base_value = self.load_place(base)?;
+                let base_ty = base_value.ty.clone();
+                let struct_value = base_value
+                    .value
+                    .ok_or_else(|| anyhow!("struct place produced no value"))?
+                    .into_struct_value();
+                let updated = self
+                    .builder
+                    .build_insert_value(struct_value, raw, *index, "field_update")?
+                    .into_struct_value();
+                self.store_place(base, EvaluatedValue::with_value(updated.into(), base_ty))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Field index, field type and LLVM layout of `field` in a struct type
+    pub(crate) fn struct_field(
+        &self,
+        ty: &OtterType,
+        field: &str,
+    ) -> Result<(u32, OtterType, StructType<'ctx>)> {
+        let OtterType::Struct(struct_id) = ty else {
+            bail!("cannot assign field '{}' on value of type {:?}", field, ty);
+        };
+        let info = self.struct_info(*struct_id);
+        let index = info
+            .field_indices
+            .get(field)
+            .copied()
+            .ok_or_else(|| anyhow!("struct '{}' has no field '{}'", info.name, field))?;
+        Ok((index as u32, info.field_types[index].clone(), info.ty))
+    }
+
+    /// Reads a list element or map value with the typed getter for `ty`
+    fn load_collection_slot(
+        &mut self,
+        args: Vec<BasicValueEnum<'ctx>>,
+        ty: &OtterType,
+        is_map: bool,
+    ) -> Result<EvaluatedValue<'ctx>> {
+        if matches!(ty, OtterType::Tuple(_) | OtterType::Struct(_)) {
+            let get_fn_name = if is_map {
+                "map.get_list"
+            } else {
+                "list.get_list"
+            };
+            let handle = self.call_ffi_returning_value(get_fn_name, args, "boxed_elem")?;
+            let value = self.unbox_tuple(handle.into_int_value(), ty.clone())?;
+            return Ok(EvaluatedValue::with_value(value, ty.clone()));
+        }
+        let (get_fn_name, fetched_ty) = if is_map {
+            self.map_get_target(ty)?
+        } else {
+            self.list_get_target(ty)?
+        };
+        let value = self.call_ffi_returning_value(get_fn_name, args, "collection_elem")?;
+        if *ty == OtterType::Opaque {
+            return Ok(EvaluatedValue::with_value(value, fetched_ty));
+        }
+        let value = self.coerce_type(value, fetched_ty, ty.clone())?;
+        Ok(EvaluatedValue::with_value(value, ty.clone()))
+    }
+
+    /// Boxes tuples and structs so they fit in a collection slot
+    fn box_aggregate(
+        &mut self,
+        value: BasicValueEnum<'ctx>,
+        ty: OtterType,
+    ) -> Result<(BasicValueEnum<'ctx>, OtterType)> {
+        if matches!(ty, OtterType::Tuple(_) | OtterType::Struct(_)) {
+            Ok((self.box_tuple(value)?.into(), OtterType::Opaque))
+        } else {
+            Ok((value, ty))
+        }
+    }
+
+    fn list_set_target(&self, ty: &OtterType) -> Result<(&'static str, OtterType)> {
+        match ty {
+            OtterType::Str => Ok(("set<list,string>", OtterType::Str)),
+            int if int.is_integer() => Ok(("set<list,int>", OtterType::I64)),
+            OtterType::F32 | OtterType::F64 => Ok(("set<list,float>", OtterType::F64)),
+            OtterType::Bool => Ok(("set<list,bool>", OtterType::Bool)),
+            OtterType::List(_) => Ok(("set<list,list>", ty.clone())),
+            OtterType::Map => Ok(("set<list,map>", OtterType::Map)),
+            OtterType::Opaque => Ok(("set<list,list>", OtterType::opaque_list())),
+            OtterType::Function { .. } | OtterType::Dyn(_) => {
+                Ok(("set<list,list>", OtterType::Opaque))
+            }
+            _ => bail!("unsupported list element type: {:?}", ty),
+        }
+    }
+}
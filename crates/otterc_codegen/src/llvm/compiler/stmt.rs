@@ -1,18 +1,15 @@
-use anyhow::{Result, bail};
-use inkwell::values::{BasicValueEnum, FunctionValue};
+use anyhow::{Result, anyhow, bail};
+use inkwell::IntPredicate;
+use inkwell::basic_block::BasicBlock;
+use inkwell::values::{BasicValueEnum, FunctionValue, PointerValue};
 
 use crate::llvm::compiler::Compiler;
-use crate::llvm::compiler::types::{EvaluatedValue, FunctionContext, OtterType, Variable};
-use otterc_ast::nodes::{Block, Expr, Statement};
-use otterc_typecheck::TypeInfo;
-
-struct IteratorRuntime<'ctx> {
-    create_fn: FunctionValue<'ctx>,
-    has_next_fn: FunctionValue<'ctx>,
-    next_fn: FunctionValue<'ctx>,
-    free_fn: FunctionValue<'ctx>,
-    element_type: OtterType,
-}
+use crate::llvm::compiler::types::{
+    CaughtException, EvaluatedValue, FinallyScope, FunctionContext, LoopContext, OtterType,
+    Variable,
+};
+use otterc_ast::nodes::{Block, ExceptHandler, Expr, Node, Pattern, Statement};
+use otterc_typecheck::{RUNTIME_EXCEPTION_TYPES, TypeInfo};
 
 impl<'ctx> Compiler<'ctx> {
     pub(crate) fn lower_block(
@@ -21,7 +18,15 @@ impl<'ctx> Compiler<'ctx> {
         function: FunctionValue<'ctx>,
         ctx: &mut FunctionContext<'ctx>,
     ) -> Result<()> {
-        for stmt in &block.statements {
+        for (index, stmt) in block.statements.iter().enumerate() {
+            if let Statement::Defer(expr) = stmt.as_ref() {
+                // The rest of the block becomes the body of a `try` whose `finally` runs the
+                // deferred expression, so later `defer`s run first
+                let rest = Block::new(block.statements[index + 1..].to_vec());
+                let deferred =
+                    Block::new(vec![Node::new(Statement::Expr(expr.clone()), *stmt.span())]);
+                return self.lower_try_statement(&rest, &[], Some(&deferred), function, ctx);
+            }
             self.lower_statement(stmt.as_ref(), function, ctx)?;
         }
         Ok(())
@@ -39,20 +44,60 @@ impl<'ctx> Compiler<'ctx> {
                 Ok(())
             }
             Statement::Return(expr) => {
-                if let Some(expr) = expr {
+                let value = if let Some(expr) = expr {
                     let val = self.eval_expr(expr.as_ref(), ctx)?;
                     if let Some(v) = val.value {
-                        self.builder.build_return(Some(&v))?;
+                        // Box struct results of functions declared to return a trait object
+                        let v = match function
+                            .get_name()
+                            .to_str()
+                            .ok()
+                            .and_then(|name| self.function_return_types.get(name).cloned())
+                        {
+                            Some(ret_ty @ OtterType::Dyn(_)) => {
+                                self.coerce_value(v, val.ty, ret_ty)?
+                            }
+                            _ => v,
+                        };
+                        Some(v)
                     } else {
-                        self.builder.build_return(None)?;
+                        None
                     }
                 } else {
-                    self.builder.build_return(None)?;
+                    None
+                };
+                // `finally` blocks run after the returned value has been computed
+                self.run_finally_blocks(0, function, ctx)?;
+                if !self.current_block_terminated() {
+                    match value {
+                        Some(v) => self.builder.build_return(Some(&v))?,
+                        None => self.builder.build_return(None)?,
+                    };
                 }
                 Ok(())
             }
+            Statement::Raise(value) => {
+                self.lower_raise(value.as_ref().map(|value| value.as_ref()), function, ctx)
+            }
+            Statement::Try {
+                body,
+                handlers,
+                finally_block,
+            } => self.lower_try_statement(
+                body.as_ref(),
+                handlers,
+                finally_block.as_ref().map(|block| block.as_ref()),
+                function,
+                ctx,
+            ),
+            Statement::With {
+                expr,
+                binding,
+                body,
+            } => self.lower_with_statement(expr, binding.as_deref(), body.as_ref(), function, ctx),
+            Statement::Defer(_) => bail!("`defer` is only supported in statement blocks"),
             Statement::Let {
-                name,
+                pattern,
                 ty,
                 expr,
                 public: _,
@@ -70,58 +115,19 @@ impl<'ctx> Compiler<'ctx> {
                     val_ty.clone()
                 };
 
-                // Skip allocation for Unit types
-                if let Some(_basic_ty) = self.basic_type(var_ty.clone())? {
-                    // Use create_entry_block_alloca to ensure alloca is in the entry block
-                    // This prevents stack overflow in loops and ensures dominance
-                    let function = self
-                        .builder
-                        .get_insert_block()
-                        .unwrap()
-                        .get_parent()
-                        .unwrap();
-                    let alloca =
-                        self.create_entry_block_alloca(function, name.as_ref(), var_ty.clone())?;
-
-                    if let Some(v) = val_value {
-                        // Coerce value to variable type if needed
-                        let coerced_val = self.coerce_type(v, val_ty, var_ty.clone())?;
-                        self.builder.build_store(alloca, coerced_val)?;
-                    }
-
-                    ctx.insert(
-                        name.as_ref().to_string(),
-                        Variable {
-                            ptr: alloca,
-                            ty: var_ty,
-                        },
-                    );
-                }
-                // For Unit types, we don't create a variable
-                Ok(())
+                // Coerce value to variable type if needed
+                let value = match val_value {
+                    Some(v) => Some(self.coerce_value(v, val_ty, var_ty.clone())?),
+                    None => None,
+                };
+                let type_info = match ty {
+                    Some(annotation) => Some(self.annotation_type_info(annotation.as_ref())),
+                    None => self.expr_type(expr.as_ref()).cloned(),
+                };
+                self.bind_pattern_value(pattern, value, var_ty, type_info, function, ctx)
             }
-            Statement::Assignment { name, expr } => {
-                let val = self.eval_expr(expr.as_ref(), ctx)?;
-                let EvaluatedValue {
-                    ty: val_ty,
-                    value: val_value,
-                } = val;
-                if let Some(var) = ctx.get(name.as_ref()) {
-                    if let Some(v) = val_value {
-                        // Type checking and coercion
-                        let coerced_val = self.coerce_type(v, val_ty.clone(), var.ty.clone())?;
-                        self.builder.build_store(var.ptr, coerced_val)?;
-                    } else if val_ty != OtterType::Unit {
-                        bail!(
-                            "Cannot assign non-unit expression with no value to variable {}",
-                            name.as_ref()
-                        );
-                    }
-                    // Unit type assignments are no-ops
-                } else {
-                    bail!("Variable {} not declared", name.as_ref());
-                }
-                Ok(())
+            Statement::Assignment { target, op, expr } => {
+                self.lower_assignment(target.as_ref(), *op, expr.as_ref(), ctx)
             }
             Statement::If {
                 cond,
@@ -136,39 +142,42 @@ impl<'ctx> Compiler<'ctx> {
                 elif_blocks,
                 else_block.as_ref().map(|b| b.as_ref()),
             ),
-            Statement::While { cond, body } => {
-                self.lower_while_loop(function, ctx, cond.as_ref(), body.as_ref())
-            }
-            Statement::Break => {
-                if let Some(loop_ctx) = ctx.current_loop() {
-                    self.builder.build_unconditional_branch(loop_ctx.exit_bb)?;
-                } else {
-                    bail!("break statement outside of loop");
-                }
-                Ok(())
-            }
-            Statement::Continue => {
-                if let Some(loop_ctx) = ctx.current_loop() {
-                    self.builder.build_unconditional_branch(loop_ctx.cond_bb)?;
-                } else {
-                    bail!("continue statement outside of loop");
-                }
-                Ok(())
+            Statement::While { label, cond, body } => self.lower_while_loop(
+                function,
+                ctx,
+                label.as_deref(),
+                cond.as_ref(),
+                body.as_ref(),
+            ),
+            Statement::Break { label, value } => self.lower_loop_jump(
+                label.as_deref(),
+                value.as_ref().map(|value| value.as_ref()),
+                false,
+                function,
+                ctx,
+            ),
+            Statement::Continue { label } => {
+                self.lower_loop_jump(label.as_deref(), None, true, function, ctx)
             }
             Statement::Pass
             | Statement::Struct { .. }
             // Handled at module level
             | Statement::Enum { .. }
+            | Statement::Trait { .. }
+            | Statement::Impl { .. }
             | Statement::TypeAlias { .. }
+            | Statement::Const { .. }
             | Statement::Function(_)
             | Statement::Use { .. }
             | Statement::PubUse { .. } => Ok(()),
             Statement::For {
-                var,
+                label,
+                pattern,
                 iterable,
                 body,
             } => self.lower_for_loop(
-                var.as_ref(),
+                label.as_deref(),
+                pattern,
                 iterable.as_ref(),
                 body.as_ref(),
                 function,
@@ -178,6 +187,68 @@ impl<'ctx> Compiler<'ctx> {
         }
     }
 
+    /// Compute the address of a variable or of a (nested) struct field stored in one,
+    /// so `p.a.b = v` becomes a chain of struct GEPs followed by a single store.
+    fn place_pointer(
+        &mut self,
+        place: &Expr,
+        ctx: &FunctionContext<'ctx>,
+    ) -> Result<(PointerValue<'ctx>, OtterType)> {
+        match place {
+            Expr::Identifier(name) => {
+                let var = ctx
+                    .get(name)
+                    .ok_or_else(|| anyhow!("Variable {} not declared", name))?;
+                Ok((var.ptr, var.ty.clone()))
+            }
+            Expr::Member { object, field } => {
+                let (object_ptr, object_ty) = self.place_pointer(object.as_ref().as_ref(), ctx)?;
+                let (index, field_ty, struct_ty) = self.struct_field(&object_ty, field)?;
+                let field_ptr = self
+                    .builder
+                    .build_struct_gep(struct_ty, object_ptr, index, field)?;
+                Ok((field_ptr, field_ty))
+            }
+            _ => bail!("expression is not an assignable place: {:?}", place),
+        }
+    }
+
+    /// Address passed as `self` to a struct method: the storage of the receiver when it is a
+    /// variable or one of its fields, otherwise a temporary copy.
+    pub(crate) fn receiver_pointer(
+        &mut self,
+        receiver: Option<&Expr>,
+        value: EvaluatedValue<'ctx>,
+        ctx: &FunctionContext<'ctx>,
+    ) -> Result<PointerValue<'ctx>> {
+        if let Some(place @ (Expr::Identifier(_) | Expr::Member { .. })) = receiver
+            && place.is_place()
+            && let Ok((ptr, OtterType::Struct(_))) = self.place_pointer(place, ctx)
+        {
+            return Ok(ptr);
+        }
+        self.spill_receiver(value)
+    }
+
+    /// A temporary copy of `value` to pass as `self` when the receiver has no storage
+    pub(crate) fn spill_receiver(
+        &mut self,
+        value: EvaluatedValue<'ctx>,
+    ) -> Result<PointerValue<'ctx>> {
+        let value_ty = value.ty.clone();
+        let value = value
+            .value
+            .ok_or_else(|| anyhow!("Cannot pass unit value as self"))?;
+        let function = self
+            .builder
+            .get_insert_block()
+            .and_then(|block| block.get_parent())
+            .ok_or_else(|| anyhow!("method call outside of a function"))?;
+        let slot = self.create_entry_block_alloca(function, "receiver", value_ty)?;
+        self.builder.build_store(slot, value)?;
+        Ok(slot)
+    }
+
     fn lower_if_statement(
         &mut self,
         function: FunctionValue<'ctx>,
@@ -224,6 +295,15 @@ impl<'ctx> Compiler<'ctx> {
                 &elif_blocks[1..],
                 else_block,
             )?;
+            // The nested chain leaves the builder in its own merge block
+            if self
+                .builder
+                .get_insert_block()
+                .and_then(|b| b.get_terminator())
+                .is_none()
+            {
+                self.builder.build_unconditional_branch(merge_bb)?;
+            }
         } else if let Some(block) = else_block {
             self.lower_block(block, function, ctx)?;
             if self
@@ -246,6 +326,7 @@ impl<'ctx> Compiler<'ctx> {
         &mut self,
         function: FunctionValue<'ctx>,
         ctx: &mut FunctionContext<'ctx>,
+        label: Option<&str>,
         cond: &Expr,
         body: &Block,
     ) -> Result<()> {
@@ -264,7 +345,7 @@ impl<'ctx> Compiler<'ctx> {
 
         // Body
         self.builder.position_at_end(body_bb);
-        ctx.push_loop(cond_bb, exit_bb);
+        ctx.push_loop(LoopContext::new(label, cond_bb, exit_bb));
         self.lower_block(body, function, ctx)?;
         ctx.pop_loop();
 
@@ -281,282 +362,778 @@ impl<'ctx> Compiler<'ctx> {
         Ok(())
     }
 
-    fn lower_for_loop(
+    /// Lowers a `loop:` expression. The body repeats until a `break`, which stores its value
+    /// in a slot that is read back once the loop is left.
+    pub(crate) fn eval_loop_expr(
         &mut self,
-        var: &str,
-        iterable: &Expr,
+        expr: &Expr,
+        label: Option<&str>,
         body: &Block,
-        function: FunctionValue<'ctx>,
         ctx: &mut FunctionContext<'ctx>,
-    ) -> Result<()> {
-        use otterc_ast::nodes::Expr;
-
-        // Iterator protocol implementation for range-based for loops
-
-        // Check if iterable is a range expression
-        if let Expr::Range { start, end } = iterable {
-            // Evaluate start and end
-            let start_val = self.eval_expr(start.as_ref().as_ref(), ctx)?;
-            let end_val = self.eval_expr(end.as_ref().as_ref(), ctx)?;
-            let start_ty = start_val.ty.clone();
-
-            // Determine if we're using I64 or F64 range
-            let is_float = start_ty == OtterType::F64;
+    ) -> Result<EvaluatedValue<'ctx>> {
+        let function = self
+            .builder
+            .get_insert_block()
+            .and_then(|block| block.get_parent())
+            .ok_or_else(|| anyhow!("`loop` lowered outside of a function"))?;
+
+        let result_ty = self
+            .expr_type(expr)
+            .and_then(|ty| self.typeinfo_to_otter_type(ty))
+            .unwrap_or(OtterType::Unit);
+        let result = match self.basic_type(result_ty.clone())? {
+            Some(_) => Some(Variable {
+                ptr: self.create_entry_block_alloca(function, "loop_result", result_ty.clone())?,
+                ty: result_ty,
+            }),
+            None => None,
+        };
 
-            // Call the appropriate range function to get a list handle
-            let range_fn_name = if is_float {
-                "range<float>"
-            } else {
-                "range<int>"
-            };
+        let body_bb = self.context.append_basic_block(function, "loop_body");
+        let exit_bb = self.context.append_basic_block(function, "loop_exit");
 
-            let range_fn = self.get_or_declare_ffi_function(range_fn_name)?;
+        self.builder.build_unconditional_branch(body_bb)?;
+        self.builder.position_at_end(body_bb);
+        ctx.push_loop(LoopContext {
+            result: result.clone(),
+            ..LoopContext::new(label, body_bb, exit_bb)
+        });
+        self.lower_block(body, function, ctx)?;
+        ctx.pop_loop();
 
-            let list_handle = self.builder.build_call(
-                range_fn,
-                &[
-                    start_val.value.unwrap().into(),
-                    end_val.value.unwrap().into(),
-                ],
-                "range_list",
-            )?;
+        if !self.current_block_terminated() {
+            self.builder.build_unconditional_branch(body_bb)?;
+        }
 
-            let list_val = list_handle.try_as_basic_value().left().unwrap();
+        self.builder.position_at_end(exit_bb);
+        match result {
+            Some(slot) => {
+                let llvm_ty = self
+                    .basic_type(slot.ty.clone())?
+                    .ok_or_else(|| anyhow!("`loop` result has no LLVM representation"))?;
+                let value = self.builder.build_load(llvm_ty, slot.ptr, "loop_value")?;
+                Ok(EvaluatedValue::with_value(value, slot.ty))
+            }
+            None => Ok(EvaluatedValue {
+                ty: OtterType::Unit,
+                value: None,
+            }),
+        }
+    }
 
-            // Now treat it as a list iteration
-            let iter_create_fn = self.get_or_declare_ffi_function("__otter_iter_array")?;
-            let iter_has_next_fn =
-                self.get_or_declare_ffi_function("__otter_iter_has_next_array")?;
-            let iter_next_fn = self.get_or_declare_ffi_function("__otter_iter_next_array")?;
-            let iter_free_fn = self.get_or_declare_ffi_function("__otter_iter_free_array")?;
+    /// Lowers `break` and `continue`. The value of `break value` is stored first; then the
+    /// `finally` blocks and the `for` iterators of the loops being left are released, and
+    /// control moves to the exit or the next iteration of the target loop.
+    fn lower_loop_jump(
+        &mut self,
+        label: Option<&str>,
+        value: Option<&Expr>,
+        is_continue: bool,
+        function: FunctionValue<'ctx>,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<()> {
+        let keyword = if is_continue { "continue" } else { "break" };
+        let Some(index) = ctx.loop_index(label) else {
+            match label {
+                Some(label) => bail!("{} to unknown loop label `{}`", keyword, label),
+                None => bail!("{} statement outside of loop", keyword),
+            }
+        };
+        let target = ctx.loop_stack[index].clone();
 
-            self.lower_collection_for_loop(
-                var,
-                EvaluatedValue::with_value(list_val, OtterType::list_of(start_ty.clone())),
-                body,
-                function,
-                ctx,
-                IteratorRuntime {
-                    create_fn: iter_create_fn,
-                    has_next_fn: iter_has_next_fn,
-                    next_fn: iter_next_fn,
-                    free_fn: iter_free_fn,
-                    element_type: start_ty, // Elements of range have same type as start
-                },
-            )?;
+        if let Some(value) = value {
+            let evaluated = self.eval_expr(value, ctx)?;
+            if let (Some(slot), Some(v)) = (&target.result, evaluated.value) {
+                let v = self.coerce_value(v, evaluated.ty, slot.ty.clone())?;
+                self.builder.build_store(slot.ptr, v)?;
+            }
+        }
 
-            Ok(())
+        self.run_loop_finally_blocks(index + 1, function, ctx)?;
+        if self.current_block_terminated() {
+            return Ok(());
+        }
+        // The target's own iterator is released on its exit path
+        let inner_cursors: Vec<_> = ctx.loop_stack[index + 1..]
+            .iter()
+            .rev()
+            .filter_map(|loop_ctx| loop_ctx.cursor.clone())
+            .collect();
+        for cursor in &inner_cursors {
+            self.close_iter_cursor(cursor)?;
+        }
+        let destination = if is_continue {
+            target.cond_bb
         } else {
-            // Handle other iterable types (arrays, strings, etc.)
-            let iterable_val = self.eval_expr(iterable, ctx)?;
-            let iterable_ty = iterable_val.ty.clone();
-
-            match iterable_ty {
-                OtterType::Str => {
-                    // String iteration (character by character)
-                    let (iter_create_fn, iter_has_next_fn, iter_next_fn, iter_free_fn) = (
-                        self.get_or_declare_ffi_function("__otter_iter_string")?,
-                        self.get_or_declare_ffi_function("__otter_iter_has_next_string")?,
-                        self.get_or_declare_ffi_function("__otter_iter_next_string")?,
-                        self.get_or_declare_ffi_function("__otter_iter_free_string")?,
-                    );
-
-                    self.lower_collection_for_loop(
-                        var,
-                        iterable_val,
-                        body,
-                        function,
-                        ctx,
-                        IteratorRuntime {
-                            create_fn: iter_create_fn,
-                            has_next_fn: iter_has_next_fn,
-                            next_fn: iter_next_fn,
-                            free_fn: iter_free_fn,
-                            element_type: OtterType::Str, // Each character is a string
-                        },
-                    )
+            target.exit_bb
+        };
+        self.builder.build_unconditional_branch(destination)?;
+        Ok(())
+    }
+
+    /// Bind the names of a `let`, `for` or parameter pattern to `value`, taking tuples apart
+    /// field by field and lowering other patterns like a match arm. Each bound name gets its
+    /// own stack slot. `type_info` is the checked type of the value when it is known.
+    pub(crate) fn bind_pattern_value(
+        &mut self,
+        pattern: &Node<Pattern>,
+        value: Option<BasicValueEnum<'ctx>>,
+        ty: OtterType,
+        type_info: Option<TypeInfo>,
+        function: FunctionValue<'ctx>,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<()> {
+        match pattern.as_ref() {
+            Pattern::Wildcard => Ok(()),
+            Pattern::Identifier(name) => {
+                // Skip allocation for Unit types
+                if self.basic_type(ty.clone())?.is_none() {
+                    return Ok(());
                 }
-                OtterType::List(_) => {
-                    // Array/list iteration
-                    let iter_create_fn = self.get_or_declare_ffi_function("__otter_iter_array")?;
-                    let iter_has_next_fn =
-                        self.get_or_declare_ffi_function("__otter_iter_has_next_array")?;
-                    let iter_next_fn =
-                        self.get_or_declare_ffi_function("__otter_iter_next_array")?;
-                    let iter_free_fn =
-                        self.get_or_declare_ffi_function("__otter_iter_free_array")?;
-
-                    let element_type = self
-                        .list_element_type(iterable)
-                        .or_else(|| iterable_val.ty.list_element().cloned())
-                        .unwrap_or(OtterType::Opaque);
-
-                    self.lower_collection_for_loop(
-                        var,
-                        iterable_val,
-                        body,
-                        function,
-                        ctx,
-                        IteratorRuntime {
-                            create_fn: iter_create_fn,
-                            has_next_fn: iter_has_next_fn,
-                            next_fn: iter_next_fn,
-                            free_fn: iter_free_fn,
-                            element_type,
-                        },
-                    )
+                // Use create_entry_block_alloca to ensure alloca is in the entry block
+                // This prevents stack overflow in loops and ensures dominance
+                let alloca = self.create_entry_block_alloca(function, name, ty.clone())?;
+                if let Some(v) = value {
+                    self.builder.build_store(alloca, v)?;
                 }
-                OtterType::Map => {
-                    // Map iteration yields keys (strings)
-                    let iter_create_fn = self.get_or_declare_ffi_function("__otter_iter_map")?;
-                    let iter_has_next_fn =
-                        self.get_or_declare_ffi_function("__otter_iter_has_next_map")?;
-                    let iter_next_fn =
-                        self.get_or_declare_ffi_function("__otter_iter_next_map")?;
-                    let iter_free_fn =
-                        self.get_or_declare_ffi_function("__otter_iter_free_map")?;
-
-                    self.lower_collection_for_loop(
-                        var,
-                        iterable_val,
-                        body,
+                ctx.insert(name.clone(), Variable { ptr: alloca, ty });
+                Ok(())
+            }
+            Pattern::Tuple(patterns) => {
+                let OtterType::Tuple(element_types) = ty else {
+                    bail!("cannot destructure a non-tuple value with a tuple pattern");
+                };
+                let tuple_value = value
+                    .ok_or_else(|| anyhow!("tuple pattern bound to an expression without a value"))?
+                    .into_struct_value();
+                let element_infos = match type_info {
+                    Some(TypeInfo::Tuple(element_infos)) => element_infos,
+                    _ => Vec::new(),
+                };
+                for (index, (element_pattern, element_ty)) in
+                    patterns.iter().zip(element_types).enumerate()
+                {
+                    let element = self.builder.build_extract_value(
+                        tuple_value,
+                        index as u32,
+                        &format!("tuple_{}", index),
+                    )?;
+                    self.bind_pattern_value(
+                        element_pattern,
+                        Some(element),
+                        element_ty,
+                        element_infos.get(index).cloned(),
                         function,
                         ctx,
-                        IteratorRuntime {
-                            create_fn: iter_create_fn,
-                            has_next_fn: iter_has_next_fn,
-                            next_fn: iter_next_fn,
-                            free_fn: iter_free_fn,
-                            element_type: OtterType::Str,
-                        },
-                    )
+                    )?;
                 }
-                _ => bail!(
-                    "For loops over type {:?} are not supported yet",
-                    iterable_ty
-                ),
+                Ok(())
+            }
+            _ => {
+                // The type checker only accepts patterns that match every value, so the
+                // failure edge of the test is never taken
+                let bound_bb = self.context.append_basic_block(function, "pattern_bound");
+                let refuted_bb = self.context.append_basic_block(function, "pattern_refuted");
+                let matched = EvaluatedValue { ty, value };
+                self.compile_pattern_match(
+                    pattern, &matched, type_info, bound_bb, refuted_bb, ctx,
+                )?;
+                self.builder.position_at_end(refuted_bb);
+                self.builder.build_unreachable()?;
+                self.builder.position_at_end(bound_bb);
+                Ok(())
             }
         }
     }
 
-    fn lower_collection_for_loop(
+    fn lower_for_loop(
         &mut self,
-        var: &str,
-        iterable_val: crate::llvm::compiler::types::EvaluatedValue<'ctx>,
+        label: Option<&str>,
+        pattern: &Node<Pattern>,
+        iterable: &Expr,
         body: &Block,
         function: FunctionValue<'ctx>,
         ctx: &mut FunctionContext<'ctx>,
-        iter_runtime: IteratorRuntime<'ctx>,
     ) -> Result<()> {
-        let IteratorRuntime {
-            create_fn,
-            has_next_fn,
-            next_fn,
-            free_fn,
-            element_type,
-        } = iter_runtime;
-        // Create iterator from collection
-        let iter_handle = self.builder.build_call(
-            create_fn,
-            &[iterable_val
-                .value
-                .ok_or_else(|| anyhow::anyhow!("Iterable has no value"))?
-                .into()],
-            "iter_handle",
-        )?;
-
-        let iter_val = iter_handle
-            .try_as_basic_value()
-            .left()
-            .ok_or_else(|| anyhow::anyhow!("Iterator creation failed"))?;
+        let cursor = self.open_iter_cursor(iterable, None, function, ctx)?;
 
-        // Create loop variable allocation
-        let element_ty = element_type.clone();
-        let var_alloca = self.create_entry_block_alloca(function, var, element_ty.clone())?;
-
-        // Insert variable into context
-        ctx.insert(
-            var.to_string(),
-            Variable {
-                ptr: var_alloca,
-                ty: element_ty.clone(),
-            },
-        );
-
-        // Create basic blocks
         let loop_cond_bb = self.context.append_basic_block(function, "loop_cond");
-        let loop_body_bb = self.context.append_basic_block(function, "loop_body");
         let cleanup_bb = self.context.append_basic_block(function, "loop_cleanup");
         let exit_bb = self.context.append_basic_block(function, "loop_exit");
 
-        // Jump to condition check
         self.builder.build_unconditional_branch(loop_cond_bb)?;
-
-        // Condition check block
         self.builder.position_at_end(loop_cond_bb);
+        let element = self.advance_iter_cursor(&cursor, cleanup_bb, function, ctx)?;
+        // Bind the loop variable(s); their slots live in the entry block
+        self.bind_pattern_value(pattern, element.value, element.ty, None, function, ctx)?;
+
+        ctx.push_loop(LoopContext {
+            cursor: Some(cursor.clone()),
+            ..LoopContext::new(label, loop_cond_bb, cleanup_bb)
+        });
+        self.lower_block(body, function, ctx)?;
+        ctx.pop_loop();
 
-        // Check if iterator has next element
-        let has_next_call = self
-            .builder
-            .build_call(has_next_fn, &[iter_val.into()], "has_next")?;
+        if !self.current_block_terminated() {
+            self.builder.build_unconditional_branch(loop_cond_bb)?;
+        }
 
-        let has_next = has_next_call
-            .try_as_basic_value()
-            .left()
-            .ok_or_else(|| anyhow::anyhow!("has_next check failed"))?
-            .into_int_value();
+        // Cleanup block - free the runtime iterators
+        self.builder.position_at_end(cleanup_bb);
+        self.close_iter_cursor(&cursor)?;
+        self.builder.build_unconditional_branch(exit_bb)?;
+
+        self.builder.position_at_end(exit_bb);
 
+        Ok(())
+    }
+
+    fn current_block_terminated(&self) -> bool {
         self.builder
-            .build_conditional_branch(has_next, loop_body_bb, cleanup_bb)?;
+            .get_insert_block()
+            .and_then(|block| block.get_terminator())
+            .is_some()
+    }
 
-        // Loop body block
-        self.builder.position_at_end(loop_body_bb);
+    /// Lowers `raise`: the exception is stored in the runtime and control moves to the
+    /// innermost handler, or back to the caller when there is none.
+    fn lower_raise(
+        &mut self,
+        value: Option<&Expr>,
+        function: FunctionValue<'ctx>,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<()> {
+        let (message, exception_type, payload): (
+            BasicValueEnum<'ctx>,
+            BasicValueEnum<'ctx>,
+            BasicValueEnum<'ctx>,
+        ) = match value {
+            Some(expr) => {
+                let raised = self.eval_expr(expr, ctx)?;
+                let value = raised
+                    .value
+                    .ok_or_else(|| anyhow!("raised expression has no value"))?;
+                match raised.ty {
+                    OtterType::Str => {
+                        let label = self
+                            .builder
+                            .build_global_string_ptr("Exception", "exception_type")?;
+                        (
+                            value,
+                            label.as_pointer_value().into(),
+                            self.context.i64_type().const_zero().into(),
+                        )
+                    }
+                    OtterType::Struct(struct_id) => {
+                        // Structs are raised under their own name and carried as a
+                        // heap copy, so `except Name as e` can load them back
+                        let info = self.struct_info(struct_id);
+                        let struct_type = info.ty;
+                        let message_field = info
+                            .field_indices
+                            .get("message")
+                            .copied()
+                            .filter(|&idx| info.field_types[idx] == OtterType::Str);
+                        let label = self
+                            .builder
+                            .build_global_string_ptr(&info.name, "exception_type")?
+                            .as_pointer_value();
+                        let message = match message_field {
+                            Some(idx) => self.builder.build_extract_value(
+                                value.into_struct_value(),
+                                idx as u32,
+                                "exception_message",
+                            )?,
+                            None => label.into(),
+                        };
+                        let ptr =
+                            self.build_heap_alloc(Self::conservative_size_of(struct_type.into()))?;
+                        self.builder.build_store(ptr, value)?;
+                        let payload = self.builder.build_ptr_to_int(
+                            ptr,
+                            self.context.i64_type(),
+                            "exception_payload",
+                        )?;
+                        (message, label.into(), payload.into())
+                    }
+                    other => bail!("cannot raise a value of type {:?}", other),
+                }
+            }
+            None => {
+                let caught = ctx
+                    .caught_exception
+                    .ok_or_else(|| anyhow!("bare `raise` outside of an `except` clause"))?;
+                self.load_caught_exception(caught)?
+            }
+        };
 
-        // Get next element
-        let next_call = self
-            .builder
-            .build_call(next_fn, &[iter_val.into()], "next_element")?;
+        let throw_fn = self.get_or_declare_ffi_function("__otter_throw_value")?;
+        self.builder.build_call(
+            throw_fn,
+            &[message.into(), exception_type.into(), payload.into()],
+            "",
+        )?;
+        let target = self.exception_target(function, ctx)?;
+        self.builder.build_unconditional_branch(target)?;
 
-        let element_val = next_call
-            .try_as_basic_value()
-            .left()
-            .ok_or_else(|| anyhow::anyhow!("next element failed"))?;
+        // Statements after a `raise` are unreachable but still need a block to go in
+        let after_bb = self.context.append_basic_block(function, "after_raise");
+        self.builder.position_at_end(after_bb);
+        Ok(())
+    }
 
-        // Decode the runtime type tag and convert to the correct type
-        // The runtime now returns tagged values: upper 8 bits = type tag, lower 56 bits = data
-        let decoded_value = self.decode_and_convert_tagged_value(element_val, &element_ty)?;
-        if let Some(value) = decoded_value {
-            self.builder.build_store(var_alloca, value)?;
+    fn lower_try_statement(
+        &mut self,
+        body: &Block,
+        handlers: &[Node<ExceptHandler>],
+        finally_block: Option<&Block>,
+        function: FunctionValue<'ctx>,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<()> {
+        let outer_landingpad = ctx.exception_landingpad;
+        let dispatch_bb = self.context.append_basic_block(function, "try_dispatch");
+        let finally_bb =
+            finally_block.map(|_| self.context.append_basic_block(function, "try_finally"));
+        let end_bb = self.context.append_basic_block(function, "try_end");
+
+        let scope_index = ctx.finally_stack.len();
+        if let Some(block) = finally_block {
+            ctx.finally_stack.push(FinallyScope {
+                block: block.clone(),
+                loop_depth: ctx.loop_stack.len(),
+                landingpad: outer_landingpad,
+            });
         }
 
-        // Execute loop body
-        ctx.push_loop(loop_cond_bb, cleanup_bb);
+        ctx.exception_landingpad = Some(dispatch_bb);
         self.lower_block(body, function, ctx)?;
-        ctx.pop_loop();
+        // Exceptions raised by the handlers still run the `finally` block
+        ctx.exception_landingpad = finally_bb.or(outer_landingpad);
+        self.finish_try_clause(end_bb, scope_index, function, ctx)?;
+
+        // Handlers are tried in order by comparing the exception's type label
+        self.builder.position_at_end(dispatch_bb);
+        let mut caught_all = false;
+        if !handlers.is_empty() {
+            let raised_type =
+                self.call_ffi_returning_value("__otter_get_exception_type", vec![], "raised_type")?;
+            for handler in handlers {
+                let handler = handler.as_ref();
+                let handler_bb = self.context.append_basic_block(function, "except");
+                let next_bb = match handler.exception_type.as_deref() {
+                    None | Some("Exception") => {
+                        self.builder.build_unconditional_branch(handler_bb)?;
+                        None
+                    }
+                    Some(name) => {
+                        let label = self.builder.build_global_string_ptr(name, "except_type")?;
+                        let equal = self
+                            .call_ffi_returning_value(
+                                "std.strings.equal",
+                                vec![raised_type, label.as_pointer_value().into()],
+                                "except_type_eq",
+                            )?
+                            .into_int_value();
+                        let matches = self.builder.build_int_compare(
+                            IntPredicate::NE,
+                            equal,
+                            equal.get_type().const_zero(),
+                            "except_matches",
+                        )?;
+                        let next_bb = self.context.append_basic_block(function, "except_next");
+                        self.builder
+                            .build_conditional_branch(matches, handler_bb, next_bb)?;
+                        Some(next_bb)
+                    }
+                };
 
-        // Jump back to condition check
-        if self.builder.get_insert_block().is_some()
-            && self
-                .builder
-                .get_insert_block()
-                .unwrap()
-                .get_terminator()
-                .is_none()
-        {
-            self.builder.build_unconditional_branch(loop_cond_bb)?;
+                self.builder.position_at_end(handler_bb);
+                self.lower_except_handler(handler, function, ctx)?;
+                self.finish_try_clause(end_bb, scope_index, function, ctx)?;
+
+                match next_bb {
+                    Some(next_bb) => self.builder.position_at_end(next_bb),
+                    None => {
+                        // Later clauses can never match
+                        caught_all = true;
+                        break;
+                    }
+                }
+            }
+        }
+        if !caught_all {
+            let unhandled_bb = self.exception_target(function, ctx)?;
+            self.builder.build_unconditional_branch(unhandled_bb)?;
         }
 
-        // Cleanup block - free iterator
-        self.builder.position_at_end(cleanup_bb);
-        self.builder.build_call(free_fn, &[iter_val.into()], "")?;
-        self.builder.build_unconditional_branch(exit_bb)?;
+        // An exception escaping the body or a handler runs the `finally` block, then keeps
+        // propagating
+        ctx.finally_stack.truncate(scope_index);
+        ctx.exception_landingpad = outer_landingpad;
+        if let (Some(finally_bb), Some(block)) = (finally_bb, finally_block) {
+            self.builder.position_at_end(finally_bb);
+            let pending = self.stash_exception(function)?;
+            self.lower_block(block, function, ctx)?;
+            if !self.current_block_terminated() {
+                let (message, exception_type, payload) = self.load_caught_exception(pending)?;
+                let throw_fn = self.get_or_declare_ffi_function("__otter_throw_value")?;
+                self.builder.build_call(
+                    throw_fn,
+                    &[message.into(), exception_type.into(), payload.into()],
+                    "",
+                )?;
+                let target = self.exception_target(function, ctx)?;
+                self.builder.build_unconditional_branch(target)?;
+            }
+        }
 
-        self.builder.position_at_end(exit_bb);
+        self.builder.position_at_end(end_bb);
+        Ok(())
+    }
+
+    /// Lowers an `except` clause entered with the exception still pending in the runtime.
+    fn lower_except_handler(
+        &mut self,
+        handler: &ExceptHandler,
+        function: FunctionValue<'ctx>,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<()> {
+        let caught = self.stash_exception(function)?;
+        let previous_caught = ctx.caught_exception.replace(caught);
+
+        let mut shadowed = None;
+        if let Some(name) = &handler.binding {
+            // Exceptions raised by runtime functions carry only their message
+            let binds_message = handler.exception_type.as_deref().is_none_or(|ty| {
+                ty == "Exception"
+                    || (RUNTIME_EXCEPTION_TYPES.contains(&ty)
+                        && self.struct_info_by_name(ty).is_none())
+            });
+            let (value, ty) = match handler.exception_type.as_deref().filter(|_| !binds_message) {
+                None => {
+                    let message =
+                        self.builder
+                            .build_load(self.string_ptr_type, caught.message, name)?;
+                    (message, OtterType::Str)
+                }
+                Some(struct_name) => {
+                    let (struct_id, info) = self
+                        .struct_info_by_name(struct_name)
+                        .ok_or_else(|| anyhow!("unknown exception type '{}'", struct_name))?;
+                    let struct_type = info.ty;
+                    let payload = self
+                        .builder
+                        .build_load(self.context.i64_type(), caught.payload, "exception_payload")?
+                        .into_int_value();
+                    let ptr = self.builder.build_int_to_ptr(
+                        payload,
+                        self.string_ptr_type,
+                        "exception_ptr",
+                    )?;
+                    let value = self.builder.build_load(struct_type, ptr, name)?;
+                    (value, OtterType::Struct(struct_id))
+                }
+            };
+            let alloca = self.create_entry_block_alloca(function, name, ty.clone())?;
+            self.builder.build_store(alloca, value)?;
+            shadowed = Some((name, ctx.remove(name)));
+            ctx.insert(name.clone(), Variable { ptr: alloca, ty });
+        }
+
+        self.lower_block(handler.body.as_ref(), function, ctx)?;
 
+        if let Some((name, previous)) = shadowed {
+            ctx.remove(name);
+            if let Some(previous) = previous {
+                ctx.insert(name.clone(), previous);
+            }
+        }
+        ctx.caught_exception = previous_caught;
         Ok(())
     }
 
-    // Exception handling (try/except/finally/raise) removed - use Result<T, E> pattern matching instead
+    /// Lowers `with expr as name:` as a `try` whose `finally` block releases the value. The
+    /// value is also kept in a hidden slot, so the body can rebind `name` without changing
+    /// what gets released.
+    fn lower_with_statement(
+        &mut self,
+        header: &Node<Expr>,
+        binding: Option<&str>,
+        body: &Block,
+        function: FunctionValue<'ctx>,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<()> {
+        let resource = self.eval_expr(header.as_ref(), ctx)?;
+        let value = resource
+            .value
+            .ok_or_else(|| anyhow!("`with` expression produced no value"))?;
+        let ty = resource.ty;
+
+        let slot = format!("__with{}", ctx.finally_stack.len());
+        let release = self.with_release_call(header, &ty, &slot)?;
+        let slot_ptr = self.create_entry_block_alloca(function, &slot, ty.clone())?;
+        self.builder.build_store(slot_ptr, value)?;
+        ctx.insert(
+            slot.clone(),
+            Variable {
+                ptr: slot_ptr,
+                ty: ty.clone(),
+            },
+        );
+
+        let mut shadowed = None;
+        if let Some(name) = binding {
+            let alloca = self.create_entry_block_alloca(function, name, ty.clone())?;
+            self.builder.build_store(alloca, value)?;
+            shadowed = Some((name, ctx.remove(name)));
+            ctx.insert(name.to_string(), Variable { ptr: alloca, ty });
+        }
+
+        let span = *header.span();
+        let cleanup = Block::new(vec![Node::new(
+            Statement::Expr(Node::new(release, span)),
+            span,
+        )]);
+        self.lower_try_statement(body, &[], Some(&cleanup), function, ctx)?;
+
+        if let Some((name, previous)) = shadowed {
+            ctx.remove(name);
+            if let Some(previous) = previous {
+                ctx.insert(name.to_string(), previous);
+            }
+        }
+        ctx.remove(&slot);
+        Ok(())
+    }
+
+    /// The call that releases a `with` value held in `slot`: the `exit` or `close` method of a
+    /// struct, or the `<constructor>.exit` companion of the runtime function called in the
+    /// header. Handles from a variable or a helper function are rejected by the type checker.
+    fn with_release_call(&self, header: &Node<Expr>, ty: &OtterType, slot: &str) -> Result<Expr> {
+        let span = *header.span();
+        let slot_expr = || Box::new(Node::new(Expr::Identifier(slot.to_string()), span));
+        if let OtterType::Struct(struct_id) = ty {
+            let method = ["exit", "close"]
+                .into_iter()
+                .find(|method| {
+                    self.resolve_struct_method_name(*struct_id, method)
+                        .is_some()
+                })
+                .ok_or_else(|| {
+                    anyhow!(
+                        "struct '{}' has no `exit` or `close` method for `with`",
+                        self.struct_info(*struct_id).name
+                    )
+                })?;
+            return Ok(Expr::Call {
+                func: Box::new(Node::new(
+                    Expr::Member {
+                        object: slot_expr(),
+                        field: method.to_string(),
+                    },
+                    span,
+                )),
+                args: vec![],
+                kwargs: vec![],
+                type_args: vec![],
+            });
+        }
+
+        let constructor = match header.as_ref() {
+            Expr::Call { func, .. } => match func.as_ref().as_ref() {
+                Expr::Identifier(name) => Some(name.clone()),
+                Expr::Member { object, field } => {
+                    self.resolve_member_function_name(object.as_ref().as_ref(), field)
+                }
+                _ => None,
+            },
+            _ => None,
+        };
+        let exit = constructor
+            .map(|name| format!("{}.exit", name))
+            .filter(|name| self.symbol_registry.contains(name))
+            .ok_or_else(|| {
+                anyhow!(
+                    "`with` needs a value with an `exit` or `close` method, or a runtime handle created in its header"
+                )
+            })?;
+        Ok(Expr::Call {
+            func: Box::new(Node::new(Expr::Identifier(exit), span)),
+            args: vec![*slot_expr()],
+            kwargs: vec![],
+            type_args: vec![],
+        })
+    }
+
+    /// Ends the body or a handler of a `try`: runs its `finally` block and leaves the
+    /// statement, unless the clause already jumped away.
+    fn finish_try_clause(
+        &mut self,
+        end_bb: BasicBlock<'ctx>,
+        scope_index: usize,
+        function: FunctionValue<'ctx>,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<()> {
+        if self.current_block_terminated() {
+            return Ok(());
+        }
+        self.run_finally_blocks(scope_index, function, ctx)?;
+        if !self.current_block_terminated() {
+            self.builder.build_unconditional_branch(end_bb)?;
+        }
+        Ok(())
+    }
+
+    /// Inlines the pending `finally` blocks from the innermost one out to `from`, as a
+    /// `return`, `break` or `continue` leaves their `try` statements.
+    pub(crate) fn run_finally_blocks(
+        &mut self,
+        from: usize,
+        function: FunctionValue<'ctx>,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<()> {
+        let scopes = ctx.finally_stack.clone();
+        let landingpad = ctx.exception_landingpad;
+        for (index, scope) in scopes.iter().enumerate().skip(from).rev() {
+            if self.current_block_terminated() {
+                break;
+            }
+            // A `return` inside the block only runs the blocks around it
+            ctx.finally_stack.truncate(index);
+            ctx.exception_landingpad = scope.landingpad;
+            self.lower_block(&scope.block, function, ctx)?;
+        }
+        ctx.finally_stack = scopes;
+        ctx.exception_landingpad = landingpad;
+        Ok(())
+    }
+
+    /// Runs the `finally` blocks of the `try` statements inside the loop at nesting `depth`
+    /// (the outermost loop of the function is at depth 1), which a jump out of it leaves.
+    fn run_loop_finally_blocks(
+        &mut self,
+        depth: usize,
+        function: FunctionValue<'ctx>,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<()> {
+        let from = ctx
+            .finally_stack
+            .iter()
+            .position(|scope| scope.loop_depth >= depth)
+            .unwrap_or(ctx.finally_stack.len());
+        self.run_finally_blocks(from, function, ctx)
+    }
+
+    /// Branches to the current exception target when the call just emitted left an
+    /// exception pending.
+    pub(crate) fn check_pending_exception(
+        &mut self,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<()> {
+        let function = self
+            .builder
+            .get_insert_block()
+            .and_then(|block| block.get_parent())
+            .ok_or_else(|| anyhow!("exception check emitted outside of a function"))?;
+        let pending = self
+            .call_ffi_returning_value("__otter_has_exception", vec![], "has_exception")?
+            .into_int_value();
+        let target = self.exception_target(function, ctx)?;
+        let continue_bb = self.context.append_basic_block(function, "no_exception");
+        self.builder
+            .build_conditional_branch(pending, target, continue_bb)?;
+        self.builder.position_at_end(continue_bb);
+        Ok(())
+    }
+
+    /// Block a pending exception goes to: the innermost handler, or the function exit.
+    fn exception_target(
+        &mut self,
+        function: FunctionValue<'ctx>,
+        ctx: &mut FunctionContext<'ctx>,
+    ) -> Result<BasicBlock<'ctx>> {
+        if let Some(landingpad) = ctx.exception_landingpad {
+            return Ok(landingpad);
+        }
+        if let Some(exit) = ctx.exception_exit {
+            return Ok(exit);
+        }
+
+        // The exception stays pending for the caller, which checks it after the call.
+        // Leaving `main` reports it and ends the program.
+        let exit = self
+            .context
+            .append_basic_block(function, "propagate_exception");
+        let prev_block = self.builder.get_insert_block();
+        self.builder.position_at_end(exit);
+        if function.get_name().to_bytes() == b"otter_entry" {
+            let report_fn = self.get_or_declare_ffi_function("__otter_uncaught_exception")?;
+            self.builder.build_call(report_fn, &[], "")?;
+            self.builder.build_unreachable()?;
+        } else {
+            match function.get_type().get_return_type() {
+                Some(ty) => self.builder.build_return(Some(&ty.const_zero()))?,
+                None => self.builder.build_return(None)?,
+            };
+        }
+        if let Some(block) = prev_block {
+            self.builder.position_at_end(block);
+        }
+        ctx.exception_exit = Some(exit);
+        Ok(exit)
+    }
+
+    /// Copies the pending exception into stack slots and clears it, so code can run
+    /// without seeing it as a new exception.
+    fn stash_exception(&mut self, function: FunctionValue<'ctx>) -> Result<CaughtException<'ctx>> {
+        let caught = CaughtException {
+            message: self.create_entry_block_alloca(function, "caught_message", OtterType::Str)?,
+            exception_type: self.create_entry_block_alloca(
+                function,
+                "caught_type",
+                OtterType::Str,
+            )?,
+            payload: self.create_entry_block_alloca(
+                function,
+                "caught_payload",
+                OtterType::Opaque,
+            )?,
+        };
+        let message =
+            self.call_ffi_returning_value("__otter_copy_exception_message", vec![], "message")?;
+        self.builder.build_store(caught.message, message)?;
+        let exception_type =
+            self.call_ffi_returning_value("__otter_copy_exception_type", vec![], "type")?;
+        self.builder
+            .build_store(caught.exception_type, exception_type)?;
+        let payload =
+            self.call_ffi_returning_value("__otter_get_exception_payload", vec![], "payload")?;
+        self.builder.build_store(caught.payload, payload)?;
+        let clear_fn = self.get_or_declare_ffi_function("__otter_clear_exception")?;
+        self.builder.build_call(clear_fn, &[], "")?;
+        Ok(caught)
+    }
+
+    fn load_caught_exception(
+        &mut self,
+        caught: CaughtException<'ctx>,
+    ) -> Result<(
+        BasicValueEnum<'ctx>,
+        BasicValueEnum<'ctx>,
+        BasicValueEnum<'ctx>,
+    )> {
+        let message = self
+            .builder
+            .build_load(self.string_ptr_type, caught.message, "message")?;
+        let exception_type =
+            self.builder
+                .build_load(self.string_ptr_type, caught.exception_type, "type")?;
+        let payload =
+            self.builder
+                .build_load(self.context.i64_type(), caught.payload, "payload")?;
+        Ok((message, exception_type, payload))
+    }
+
     pub(crate) fn list_element_type(&self, iterable: &Expr) -> Option<OtterType> {
         if let Some(ty) = self.expr_type(iterable) {
             self.resolve_list_element_type_from_typeinfo(ty)
@@ -584,8 +1161,15 @@ impl<'ctx> Compiler<'ctx> {
         match ty {
             TypeInfo::Unit => Some(OtterType::Unit),
             TypeInfo::Bool => Some(OtterType::Bool),
+            TypeInfo::I8 => Some(OtterType::I8),
+            TypeInfo::I16 => Some(OtterType::I16),
             TypeInfo::I32 => Some(OtterType::I32),
             TypeInfo::I64 => Some(OtterType::I64),
+            TypeInfo::U8 => Some(OtterType::U8),
+            TypeInfo::U16 => Some(OtterType::U16),
+            TypeInfo::U32 => Some(OtterType::U32),
+            TypeInfo::U64 => Some(OtterType::U64),
+            TypeInfo::F32 => Some(OtterType::F32),
             TypeInfo::F64 => Some(OtterType::F64),
             TypeInfo::Str => Some(OtterType::Str),
             TypeInfo::List(inner) => {
@@ -595,7 +1179,32 @@ impl<'ctx> Compiler<'ctx> {
                 Some(OtterType::list_of(element))
             }
             TypeInfo::Dict { .. } => Some(OtterType::Map),
+            TypeInfo::Function {
+                params,
+                return_type,
+                ..
+            } => Some(OtterType::Function {
+                // Untyped parameters default to i64, as they do for named functions
+                params: params
+                    .iter()
+                    .map(|param| self.typeinfo_to_otter_type(param).unwrap_or(OtterType::I64))
+                    .collect(),
+                ret: Box::new(
+                    self.typeinfo_to_otter_type(return_type)
+                        .unwrap_or(OtterType::Opaque),
+                ),
+            }),
             TypeInfo::Struct { name, .. } => self.struct_id(name).map(OtterType::Struct),
+            TypeInfo::Dyn(trait_name) => Some(OtterType::Dyn(trait_name.clone())),
+            TypeInfo::Tuple(elements) => Some(OtterType::Tuple(
+                elements
+                    .iter()
+                    .map(|element| {
+                        self.typeinfo_to_otter_type(element)
+                            .unwrap_or(OtterType::Opaque)
+                    })
+                    .collect(),
+            )),
             TypeInfo::Alias { underlying, .. } => self.typeinfo_to_otter_type(underlying),
             TypeInfo::Generic { base, args } => {
                 // Handle generic types
@@ -622,6 +1231,8 @@ impl<'ctx> Compiler<'ctx> {
                             Some(OtterType::list_of(element))
                         }
                         "Dict" | "dict" => Some(OtterType::Map),
+                        // Lazy iterators are runtime handles
+                        "Iterator" => Some(OtterType::Opaque),
                         _ => {
                             #[expect(clippy::print_stderr, reason = "TODO: Use robust logging")]
                             {
@@ -669,6 +1280,26 @@ impl<'ctx> Compiler<'ctx> {
         let decoded_value = match expected_type {
             OtterType::Unit => return Ok(None),
 
+            narrow @ (OtterType::I8
+            | OtterType::I16
+            | OtterType::U8
+            | OtterType::U16
+            | OtterType::U32
+            | OtterType::U64
+            | OtterType::F32) => {
+                // Narrow numbers are stored as i64/f64 and converted once decoded
+                let wide = if narrow.is_float() {
+                    OtterType::F64
+                } else {
+                    OtterType::I64
+                };
+                let Some(value) = self.decode_and_convert_tagged_value(encoded_value, &wide)?
+                else {
+                    return Ok(None);
+                };
+                self.convert_number(value, &wide, narrow)?
+            }
+
             OtterType::Bool => {
                 let decode_fn = self.get_or_declare_ffi_function("__otter_decode_value_as_bool")?;
                 let result =
@@ -827,7 +1458,11 @@ impl<'ctx> Compiler<'ctx> {
                 result.try_as_basic_value().left().unwrap()
             }
 
-            OtterType::List(_) | OtterType::Map | OtterType::Opaque => {
+            OtterType::List(_)
+            | OtterType::Map
+            | OtterType::Opaque
+            | OtterType::Function { .. }
+            | OtterType::Dyn(_) => {
                 let decode_fn =
                     self.get_or_declare_ffi_function("__otter_decode_value_as_handle")?;
                 let result =
@@ -928,14 +1563,15 @@ impl<'ctx> Compiler<'ctx> {
                 phi.as_basic_value()
             }
 
-            OtterType::Struct(_) | OtterType::Tuple(_) => {
-                // Structs and tuples are handled as opaque handles
+            OtterType::Tuple(_) | OtterType::Struct(_) => {
+                // Collections hold tuples and structs boxed on the GC heap, see `box_tuple`
                 let decode_fn =
                     self.get_or_declare_ffi_function("__otter_decode_value_as_handle")?;
                 let result =
                     self.builder
                         .build_call(decode_fn, &[encoded_int.into()], "decoded_handle")?;
-                result.try_as_basic_value().left().unwrap()
+                let handle = result.try_as_basic_value().left().unwrap().into_int_value();
+                self.unbox_tuple(handle, expected_type.clone())?
             }
         };
 
@@ -955,12 +1591,25 @@ impl<'ctx> Compiler<'ctx> {
             | OtterType::List(_)
             | OtterType::Map
             | OtterType::Struct(_)
-            | OtterType::Tuple(_) => raw_value,
-            OtterType::I32 => {
+            | OtterType::Tuple(_)
+            | OtterType::Function { .. }
+            | OtterType::Dyn(_) => raw_value,
+            int @ (OtterType::I8
+            | OtterType::I16
+            | OtterType::I32
+            | OtterType::U8
+            | OtterType::U16
+            | OtterType::U32
+            | OtterType::U64) => {
                 let int_val = raw_value.into_int_value();
-                self.builder
-                    .build_int_truncate(int_val, self.context.i32_type(), "iter_i32")?
-                    .into()
+                self.convert_number(int_val.into(), &OtterType::I64, int)?
+            }
+            OtterType::F32 => {
+                let int_val = raw_value.into_int_value();
+                let wide =
+                    self.builder
+                        .build_bit_cast(int_val, self.context.f64_type(), "iter_f64")?;
+                self.convert_number(wide, &OtterType::F64, &OtterType::F32)?
             }
             OtterType::F64 => {
                 // raw_value is an i64 containing the bit pattern of an f64
@@ -1,13 +1,23 @@
+use crate::llvm::compiler::iter::IterCursor;
 use inkwell::basic_block::BasicBlock;
 use inkwell::values::{BasicValueEnum, PointerValue};
+use otterc_ast::nodes::Block;
+use otterc_typecheck::TypeInfo;
 use std::collections::HashMap;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum OtterType {
     Unit,
     Bool,
+    I8,
+    I16,
     I32,
     I64,
+    U8,
+    U16,
+    U32,
+    U64,
+    F32,
     F64,
     Str,
     Opaque,               // For handles, pointers, etc.
@@ -15,6 +25,12 @@ pub enum OtterType {
     Map,
     Struct(u32),
     Tuple(Vec<OtterType>),
+    Function {
+        // Closure handle: address of a GC-allocated `{ fn_ptr, captures... }` environment
+        params: Vec<OtterType>,
+        ret: Box<OtterType>,
+    },
+    Dyn(String), // Trait object handle: address of a GC-allocated `{ vtable, struct }` pair
 }
 
 impl OtterType {
@@ -35,6 +51,74 @@ impl OtterType {
             _ => None,
         }
     }
+
+    /// Numeric type named by a type annotation such as `u8` or `float`
+    pub fn numeric_from_name(name: &str) -> Option<Self> {
+        match name {
+            "i8" => Some(OtterType::I8),
+            "i16" => Some(OtterType::I16),
+            "i32" => Some(OtterType::I32),
+            "int" | "i64" => Some(OtterType::I64),
+            "u8" => Some(OtterType::U8),
+            "u16" => Some(OtterType::U16),
+            "u32" => Some(OtterType::U32),
+            "u64" => Some(OtterType::U64),
+            "f32" => Some(OtterType::F32),
+            "float" | "f64" => Some(OtterType::F64),
+            _ => None,
+        }
+    }
+
+    /// Bit width of an integer type and whether it is signed
+    pub fn integer_layout(&self) -> Option<(u32, bool)> {
+        match self {
+            OtterType::I8 => Some((8, true)),
+            OtterType::I16 => Some((16, true)),
+            OtterType::I32 => Some((32, true)),
+            OtterType::I64 => Some((64, true)),
+            OtterType::U8 => Some((8, false)),
+            OtterType::U16 => Some((16, false)),
+            OtterType::U32 => Some((32, false)),
+            OtterType::U64 => Some((64, false)),
+            _ => None,
+        }
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.integer_layout().is_some()
+    }
+
+    pub fn is_float(&self) -> bool {
+        matches!(self, OtterType::F32 | OtterType::F64)
+    }
+
+    /// Type both operands of an arithmetic operator are converted to, following the
+    /// checker's widening rules
+    pub fn common_numeric_type(&self, other: &OtterType) -> Option<OtterType> {
+        let widens =
+            |from: &OtterType, to: &OtterType| match (from.integer_layout(), to.integer_layout()) {
+                (Some((from_bits, from_signed)), Some((to_bits, to_signed))) => {
+                    from_bits < to_bits && (to_signed || !from_signed)
+                }
+                (Some((bits, _)), None) => {
+                    *to == OtterType::F64 || (*to == OtterType::F32 && bits <= 16)
+                }
+                (None, None) => *from == OtterType::F32 && *to == OtterType::F64,
+                (None, Some(_)) => false,
+            };
+        let numeric = |ty: &OtterType| ty.is_integer() || ty.is_float();
+        if !numeric(self) || !numeric(other) {
+            None
+        } else if self == other || widens(other, self) {
+            Some(self.clone())
+        } else if widens(self, other) {
+            Some(other.clone())
+        } else if self.is_float() || other.is_float() {
+            Some(OtterType::F64)
+        } else {
+            None
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -60,15 +144,58 @@ pub struct Variable<'ctx> {
 
 #[derive(Debug, Clone)]
 pub struct LoopContext<'ctx> {
+    pub label: Option<String>,
     pub cond_bb: BasicBlock<'ctx>,
     pub exit_bb: BasicBlock<'ctx>,
+    /// Slot that `break value` stores the result of a `loop:` expression in
+    pub result: Option<Variable<'ctx>>,
+    /// Iterator of a `for` loop, released when a labelled jump leaves the loop from inside
+    pub(crate) cursor: Option<IterCursor<'ctx>>,
+}
+
+impl<'ctx> LoopContext<'ctx> {
+    pub fn new(label: Option<&str>, cond_bb: BasicBlock<'ctx>, exit_bb: BasicBlock<'ctx>) -> Self {
+        Self {
+            label: label.map(str::to_string),
+            cond_bb,
+            exit_bb,
+            result: None,
+            cursor: None,
+        }
+    }
+}
+
+/// `finally` block of an enclosing `try`, run again on every path that leaves the statement
+#[derive(Debug, Clone)]
+pub struct FinallyScope<'ctx> {
+    pub block: Block,
+    /// Loop nesting depth at the `try`, so `break`/`continue` only run the blocks they leave
+    pub loop_depth: usize,
+    /// Landing pad outside the `try`, which receives exceptions raised by the block itself
+    pub landingpad: Option<BasicBlock<'ctx>>,
+}
+
+/// Stack slots holding an exception taken off the runtime while a handler or `finally` runs
+#[derive(Debug, Clone, Copy)]
+pub struct CaughtException<'ctx> {
+    pub message: PointerValue<'ctx>,
+    pub exception_type: PointerValue<'ctx>,
+    pub payload: PointerValue<'ctx>,
 }
 
 #[derive(Debug, Clone)]
 pub struct FunctionContext<'ctx> {
     pub variables: HashMap<String, Variable<'ctx>>,
     pub loop_stack: Vec<LoopContext<'ctx>>,
+    /// Where a pending exception goes; `None` leaves the function
     pub exception_landingpad: Option<BasicBlock<'ctx>>,
+    /// Shared block that passes a pending exception on to the caller
+    pub exception_exit: Option<BasicBlock<'ctx>>,
+    pub finally_stack: Vec<FinallyScope<'ctx>>,
+    /// Exception handled by the enclosing `except` clause, re-raised by a bare `raise`
+    pub caught_exception: Option<CaughtException<'ctx>>,
+    /// Declared return type of the function being lowered, used by `?` to adapt errors
+    pub return_type: Option<TypeInfo>,
 }
 
 impl<'ctx> FunctionContext<'ctx> {
@@ -77,6 +204,10 @@ impl<'ctx> FunctionContext<'ctx> {
             variables: HashMap::new(),
             loop_stack: Vec::new(),
             exception_landingpad: None,
+            exception_exit: None,
+            finally_stack: Vec::new(),
+            caught_exception: None,
+            return_type: None,
         }
     }
 
@@ -92,16 +223,23 @@ impl<'ctx> FunctionContext<'ctx> {
         self.variables.remove(name)
     }
 
-    pub fn push_loop(&mut self, cond_bb: BasicBlock<'ctx>, exit_bb: BasicBlock<'ctx>) {
-        self.loop_stack.push(LoopContext { cond_bb, exit_bb });
+    pub fn push_loop(&mut self, loop_ctx: LoopContext<'ctx>) {
+        self.loop_stack.push(loop_ctx);
     }
 
     pub fn pop_loop(&mut self) -> Option<LoopContext<'ctx>> {
         self.loop_stack.pop()
     }
 
-    pub fn current_loop(&self) -> Option<&LoopContext<'ctx>> {
-        self.loop_stack.last()
+    /// Position in `loop_stack` of the loop labelled `label`, or of the innermost loop
+    pub fn loop_index(&self, label: Option<&str>) -> Option<usize> {
+        match label {
+            Some(label) => self
+                .loop_stack
+                .iter()
+                .rposition(|loop_ctx| loop_ctx.label.as_deref() == Some(label)),
+            None => self.loop_stack.len().checked_sub(1),
+        }
     }
 }
 
@@ -195,13 +195,20 @@ fn parse_type(identifier: &str) -> Result<TypeSpec> {
     match identifier.to_ascii_lowercase().as_str() {
         "unit" | "void" => Ok(TypeSpec::Unit),
         "bool" => Ok(TypeSpec::Bool),
+        "i8" | "int8" => Ok(TypeSpec::I8),
+        "i16" | "int16" => Ok(TypeSpec::I16),
         "i32" | "int32" => Ok(TypeSpec::I32),
         "i64" | "int64" => Ok(TypeSpec::I64),
+        "u8" | "uint8" => Ok(TypeSpec::U8),
+        "u16" | "uint16" => Ok(TypeSpec::U16),
+        "u32" | "uint32" => Ok(TypeSpec::U32),
+        "u64" | "uint64" => Ok(TypeSpec::U64),
+        "f32" | "float32" | "float" => Ok(TypeSpec::F32),
         "f64" | "float64" | "double" => Ok(TypeSpec::F64),
         "str" | "string" => Ok(TypeSpec::Str),
         "opaque" | "handle" => Ok(TypeSpec::Opaque),
         other => Err(anyhow!(
-            "unsupported FFI type identifier `{}` (expected unit, bool, i32, i64, f64, str, or opaque)",
+            "unsupported FFI type identifier `{}` (expected unit, bool, an integer such as i32 or u8, f32, f64, str, or opaque)",
             other
         )),
     }
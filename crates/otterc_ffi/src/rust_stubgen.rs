@@ -688,25 +688,37 @@ impl RustStubGenerator {
                     call_args.push(format!("&{owned}"));
                 }
                 (
-                    TypeSpec::F64,
+                    TypeSpec::F32 | TypeSpec::F64,
                     ArgContext::Json {
                         indent,
                         array_name,
                         func_name,
                     },
                 ) => {
+                    let narrow = if matches!(param, TypeSpec::F32) {
+                        " as f32"
+                    } else {
+                        ""
+                    };
                     setup.push(format!(
-                        "{indent}let {arg_name}_value = {array}.get({idx}).ok_or_else(|| format!(\"missing argument {idx} for {func}\"))?;\n{indent}let {arg_name} = {arg_name}_value.as_f64().ok_or_else(|| format!(\"argument {idx} for {func} must be a number\"))?;\n",
+                        "{indent}let {arg_name}_value = {array}.get({idx}).ok_or_else(|| format!(\"missing argument {idx} for {func}\"))?;\n{indent}let {arg_name} = {arg_name}_value.as_f64().ok_or_else(|| format!(\"argument {idx} for {func} must be a number\"))?{narrow};\n",
                         indent = indent,
                         arg_name = arg_name,
                         array = array_name,
                         idx = idx,
-                        func = func_name
+                        func = func_name,
+                        narrow = narrow
                     ));
                     call_args.push(arg_name);
                 }
                 (
-                    TypeSpec::I32,
+                    TypeSpec::I8
+                    | TypeSpec::I16
+                    | TypeSpec::I32
+                    | TypeSpec::U8
+                    | TypeSpec::U16
+                    | TypeSpec::U32
+                    | TypeSpec::U64,
                     ArgContext::Json {
                         indent,
                         array_name,
@@ -714,12 +726,13 @@ impl RustStubGenerator {
                     },
                 ) => {
                     setup.push(format!(
-                        "{indent}let {arg_name}_value = {array}.get({idx}).ok_or_else(|| format!(\"missing argument {idx} for {func}\"))?;\n{indent}let {arg_name}_raw = {arg_name}_value.as_i64().ok_or_else(|| format!(\"argument {idx} for {func} must be an integer\"))?;\n{indent}let {arg_name} = {arg_name}_raw as i32;\n",
+                        "{indent}let {arg_name}_value = {array}.get({idx}).ok_or_else(|| format!(\"missing argument {idx} for {func}\"))?;\n{indent}let {arg_name}_raw = {arg_name}_value.as_i64().ok_or_else(|| format!(\"argument {idx} for {func} must be an integer\"))?;\n{indent}let {arg_name} = {arg_name}_raw as {rust_ty};\n",
                         indent = indent,
                         arg_name = arg_name,
                         array = array_name,
                         idx = idx,
-                        func = func_name
+                        func = func_name,
+                        rust_ty = param.to_rust()
                     ));
                     call_args.push(arg_name);
                 }
@@ -832,8 +845,15 @@ impl RustStubGenerator {
             TypeSpec::Unit => "json!(null)".to_string(),
             TypeSpec::Str
             | TypeSpec::Bool
+            | TypeSpec::I8
+            | TypeSpec::I16
             | TypeSpec::I32
             | TypeSpec::I64
+            | TypeSpec::U8
+            | TypeSpec::U16
+            | TypeSpec::U32
+            | TypeSpec::U64
+            | TypeSpec::F32
             | TypeSpec::F64
             | TypeSpec::Opaque
             | TypeSpec::List(_)
@@ -849,8 +869,15 @@ impl RustStubGenerator {
             TypeSpec::Unit => "json!(null)".to_string(),
             TypeSpec::Str
             | TypeSpec::Bool
+            | TypeSpec::I8
+            | TypeSpec::I16
             | TypeSpec::I32
             | TypeSpec::I64
+            | TypeSpec::U8
+            | TypeSpec::U16
+            | TypeSpec::U32
+            | TypeSpec::U64
+            | TypeSpec::F32
             | TypeSpec::F64
             | TypeSpec::Opaque
             | TypeSpec::List(_)
@@ -865,9 +892,17 @@ impl RustStubGenerator {
         match spec {
             TypeSpec::Unit => "()".to_string(),
             TypeSpec::Bool => "bool".to_string(),
-            TypeSpec::I32 => "i32".to_string(),
-            TypeSpec::I64 | TypeSpec::Opaque => "i64".to_string(),
-            TypeSpec::F64 => "f64".to_string(),
+            TypeSpec::I8
+            | TypeSpec::I16
+            | TypeSpec::I32
+            | TypeSpec::I64
+            | TypeSpec::U8
+            | TypeSpec::U16
+            | TypeSpec::U32
+            | TypeSpec::U64
+            | TypeSpec::F32
+            | TypeSpec::F64
+            | TypeSpec::Opaque => spec.to_rust().to_string(),
             TypeSpec::Str => "String".to_string(),
             TypeSpec::List(elem) => format!("Vec<{}>", self.type_spec_to_rust_type(elem)),
             TypeSpec::Map(key, value) => format!("std::collections::HashMap<{}, {}>", 
@@ -930,20 +965,20 @@ fn map_rust_type_to_spec(ty: &RustTypeRef) -> Option<TypeSpec> {
     match ty {
         RustTypeRef::Unit => Some(TypeSpec::Unit),
         RustTypeRef::Bool => Some(TypeSpec::Bool),
-        RustTypeRef::I8
-        | RustTypeRef::I16
-        | RustTypeRef::I32
-        | RustTypeRef::U8
-        | RustTypeRef::U16
-        | RustTypeRef::U32
-        | RustTypeRef::Char => Some(TypeSpec::I32),
+        RustTypeRef::I8 => Some(TypeSpec::I8),
+        RustTypeRef::I16 => Some(TypeSpec::I16),
+        RustTypeRef::I32 | RustTypeRef::Char => Some(TypeSpec::I32),
+        RustTypeRef::U8 => Some(TypeSpec::U8),
+        RustTypeRef::U16 => Some(TypeSpec::U16),
+        RustTypeRef::U32 => Some(TypeSpec::U32),
+        RustTypeRef::U64 => Some(TypeSpec::U64),
         RustTypeRef::I64
-        | RustTypeRef::U64
         | RustTypeRef::I128
         | RustTypeRef::U128
         | RustTypeRef::Usize
         | RustTypeRef::Isize => Some(TypeSpec::I64),
-        RustTypeRef::F32 | RustTypeRef::F64 => Some(TypeSpec::F64),
+        RustTypeRef::F32 => Some(TypeSpec::F32),
+        RustTypeRef::F64 => Some(TypeSpec::F64),
         RustTypeRef::Str | RustTypeRef::String => Some(TypeSpec::Str),
         RustTypeRef::Option { inner } => {
             // Try to convert inner type; if successful, wrap in Option, otherwise use Opaque
@@ -995,8 +1030,15 @@ fn rust_value_ty(spec: &TypeSpec) -> &'static str {
     match spec {
         TypeSpec::Unit => "()",
         TypeSpec::Bool => "bool",
+        TypeSpec::I8 => "i8",
+        TypeSpec::I16 => "i16",
         TypeSpec::I32 => "i32",
         TypeSpec::I64 | TypeSpec::Opaque => "i64",
+        TypeSpec::U8 => "u8",
+        TypeSpec::U16 => "u16",
+        TypeSpec::U32 => "u32",
+        TypeSpec::U64 => "u64",
+        TypeSpec::F32 => "f32",
         TypeSpec::F64 => "f64",
         TypeSpec::Str | TypeSpec::List(_) | TypeSpec::Map(_, _) | TypeSpec::Option(_) => "String",
     }
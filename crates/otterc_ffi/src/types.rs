@@ -40,8 +40,15 @@ impl FunctionSpec {
 pub enum TypeSpec {
     Unit,
     Bool,
+    I8,
+    I16,
     I32,
     I64,
+    U8,
+    U16,
+    U32,
+    U64,
+    F32,
     F64,
     Str,
     Opaque,
@@ -58,8 +65,15 @@ impl TypeSpec {
         match self {
             TypeSpec::Unit => "()",
             TypeSpec::Bool => "bool",
+            TypeSpec::I8 => "i8",
+            TypeSpec::I16 => "i16",
             TypeSpec::I32 => "i32",
             TypeSpec::I64 | TypeSpec::Opaque => "i64",
+            TypeSpec::U8 => "u8",
+            TypeSpec::U16 => "u16",
+            TypeSpec::U32 => "u32",
+            TypeSpec::U64 => "u64",
+            TypeSpec::F32 => "f32",
             TypeSpec::F64 => "f64",
             TypeSpec::Str | TypeSpec::List(_) | TypeSpec::Map(_, _) | TypeSpec::Option(_) => "*const ::std::os::raw::c_char",
         }
@@ -69,8 +83,16 @@ impl TypeSpec {
         match self {
             TypeSpec::Unit => "()",
             TypeSpec::Bool => "false",
-            TypeSpec::I32 | TypeSpec::I64 | TypeSpec::Opaque => "0",
-            TypeSpec::F64 => "0.0",
+            TypeSpec::I8
+            | TypeSpec::I16
+            | TypeSpec::I32
+            | TypeSpec::I64
+            | TypeSpec::U8
+            | TypeSpec::U16
+            | TypeSpec::U32
+            | TypeSpec::U64
+            | TypeSpec::Opaque => "0",
+            TypeSpec::F32 | TypeSpec::F64 => "0.0",
             TypeSpec::Str | TypeSpec::List(_) | TypeSpec::Map(_, _) | TypeSpec::Option(_) => "::std::ptr::null_mut()",
         }
     }
@@ -79,8 +101,15 @@ impl TypeSpec {
         match self {
             TypeSpec::Unit => "FfiType::Unit",
             TypeSpec::Bool => "FfiType::Bool",
+            TypeSpec::I8 => "FfiType::I8",
+            TypeSpec::I16 => "FfiType::I16",
             TypeSpec::I32 => "FfiType::I32",
             TypeSpec::I64 => "FfiType::I64",
+            TypeSpec::U8 => "FfiType::U8",
+            TypeSpec::U16 => "FfiType::U16",
+            TypeSpec::U32 => "FfiType::U32",
+            TypeSpec::U64 => "FfiType::U64",
+            TypeSpec::F32 => "FfiType::F32",
             TypeSpec::F64 => "FfiType::F64",
             TypeSpec::Str => "FfiType::Str",
             TypeSpec::Opaque | TypeSpec::Option(_) => "FfiType::Opaque",
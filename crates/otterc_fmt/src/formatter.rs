@@ -1,6 +1,6 @@
 use otterc_ast::nodes::{
-    BinaryOp, Block, Expr, FStringPart, Function, Literal, Node, Pattern, Program, Statement, Type,
-    UnaryOp,
+    BinaryOp, Block, Expr, FStringPart, Function, Literal, Node, Param, Pattern, Program,
+    Statement, StringStyle, Type, UnaryOp,
 };
 
 /// Formats OtterLang code
@@ -32,7 +32,7 @@ impl Formatter {
     fn format_statement(&self, stmt: &Node<Statement>, indent: usize) -> String {
         match stmt.as_ref() {
             Statement::Let {
-                name,
+                pattern,
                 ty,
                 expr,
                 public,
@@ -47,16 +47,17 @@ impl Formatter {
                     "{}{}let {}{} = {}\n",
                     self.indent(indent),
                     pub_str,
-                    name,
+                    self.format_pattern(pattern),
                     ty_str,
                     self.format_expr(expr, indent)
                 )
             }
-            Statement::Assignment { name, expr, .. } => {
+            Statement::Assignment { target, op, expr } => {
                 format!(
-                    "{}{} = {}\n",
+                    "{}{} {}= {}\n",
                     self.indent(indent),
-                    name,
+                    self.format_expr(target, indent),
+                    op.map(|op| op.symbol()).unwrap_or_default(),
                     self.format_expr(expr, indent)
                 )
             }
@@ -68,23 +69,25 @@ impl Formatter {
                 else_block,
             } => self.format_if(cond, then_block, elif_blocks, else_block, indent),
             Statement::For {
-                var,
+                label,
+                pattern,
                 iterable,
                 body,
-                ..
             } => {
                 format!(
-                    "{}for {} in {}:\n{}",
+                    "{}{}for {} in {}:\n{}",
                     self.indent(indent),
-                    var,
+                    format_label(label.as_deref()),
+                    self.format_pattern(pattern),
                     self.format_expr(iterable, indent),
                     self.format_block(body, indent + 1)
                 )
             }
-            Statement::While { cond, body } => {
+            Statement::While { label, cond, body } => {
                 format!(
-                    "{}while {}:\n{}",
+                    "{}{}while {}:\n{}",
                     self.indent(indent),
+                    format_label(label.as_deref()),
                     self.format_expr(cond, indent),
                     self.format_block(body, indent + 1)
                 )
@@ -100,8 +103,89 @@ impl Formatter {
                     format!("{}return\n", self.indent(indent))
                 }
             }
-            Statement::Break => format!("{}break\n", self.indent(indent)),
-            Statement::Continue => format!("{}continue\n", self.indent(indent)),
+            Statement::Raise(expr) => {
+                if let Some(expr) = expr {
+                    format!(
+                        "{}raise {}\n",
+                        self.indent(indent),
+                        self.format_expr(expr, indent)
+                    )
+                } else {
+                    format!("{}raise\n", self.indent(indent))
+                }
+            }
+            Statement::Try {
+                body,
+                handlers,
+                finally_block,
+            } => {
+                let mut result = format!(
+                    "{}try:\n{}",
+                    self.indent(indent),
+                    self.format_block(body, indent + 1)
+                );
+                for handler in handlers {
+                    let handler = handler.as_ref();
+                    let clause = match (&handler.exception_type, &handler.binding) {
+                        (Some(ty), Some(binding)) => format!(" {} as {}", ty, binding),
+                        (Some(ty), None) => format!(" {}", ty),
+                        _ => String::new(),
+                    };
+                    result.push_str(&format!(
+                        "{}except{}:\n{}",
+                        self.indent(indent),
+                        clause,
+                        self.format_block(&handler.body, indent + 1)
+                    ));
+                }
+                if let Some(block) = finally_block {
+                    result.push_str(&format!(
+                        "{}finally:\n{}",
+                        self.indent(indent),
+                        self.format_block(block, indent + 1)
+                    ));
+                }
+                result
+            }
+            Statement::With {
+                expr,
+                binding,
+                body,
+            } => {
+                let binding = binding
+                    .as_ref()
+                    .map(|name| format!(" as {}", name))
+                    .unwrap_or_default();
+                format!(
+                    "{}with {}{}:\n{}",
+                    self.indent(indent),
+                    self.format_expr(expr, indent),
+                    binding,
+                    self.format_block(body, indent + 1)
+                )
+            }
+            Statement::Defer(expr) => {
+                format!(
+                    "{}defer {}\n",
+                    self.indent(indent),
+                    self.format_expr(expr, indent)
+                )
+            }
+            Statement::Break { label, value } => {
+                let mut result = format!("{}break", self.indent(indent));
+                if let Some(label) = label {
+                    result.push_str(&format!(" {}", label));
+                }
+                if let Some(value) = value {
+                    result.push_str(&format!(" {}", self.format_expr(value, indent)));
+                }
+                result.push('\n');
+                result
+            }
+            Statement::Continue { label } => match label {
+                Some(label) => format!("{}continue {}\n", self.indent(indent), label),
+                None => format!("{}continue\n", self.indent(indent)),
+            },
             Statement::Pass => format!("{}pass\n", self.indent(indent)),
             Statement::Expr(expr) => {
                 format!(
@@ -116,20 +200,19 @@ impl Formatter {
                 methods,
                 public,
                 generics,
+                bounds,
+                derives,
             } => {
                 let pub_str = if *public { "pub " } else { "" };
-                let gen_str = if generics.is_empty() {
-                    String::new()
-                } else {
-                    format!("<{}>", generics.join(", "))
-                };
-                let mut result = format!(
+                let gen_str = self.format_generics(generics, bounds);
+                let mut result = self.format_derives(derives, indent);
+                result.push_str(&format!(
                     "{}{}struct {}{}:\n",
                     self.indent(indent),
                     pub_str,
                     name,
                     gen_str
-                );
+                ));
                 for (field_name, field_type) in fields {
                     result.push_str(&format!(
                         "{}    {}: {}\n",
@@ -139,7 +222,7 @@ impl Formatter {
                     ));
                 }
                 for method in methods {
-                    result.push_str(&self.format_function(method, indent + 1));
+                    result.push_str(&self.format_method(method, indent + 1));
                 }
                 result
             }
@@ -148,20 +231,19 @@ impl Formatter {
                 variants,
                 public,
                 generics,
+                bounds,
+                derives,
             } => {
                 let pub_str = if *public { "pub " } else { "" };
-                let gen_str = if generics.is_empty() {
-                    String::new()
-                } else {
-                    format!("<{}>", generics.join(", "))
-                };
-                let mut result = format!(
+                let gen_str = self.format_generics(generics, bounds);
+                let mut result = self.format_derives(derives, indent);
+                result.push_str(&format!(
                     "{}{}enum {}{}:\n",
                     self.indent(indent),
                     pub_str,
                     name,
                     gen_str
-                );
+                ));
                 for variant in variants {
                     if variant.as_ref().fields.is_empty() {
                         result.push_str(&format!(
@@ -187,6 +269,46 @@ impl Formatter {
                 }
                 result
             }
+            Statement::Trait {
+                name,
+                methods,
+                public,
+            } => {
+                let pub_str = if *public { "pub " } else { "" };
+                let mut result = format!("{}{}trait {}:\n", self.indent(indent), pub_str, name);
+                for method in methods {
+                    if method.as_ref().body.as_ref().is_empty() {
+                        result.push_str(&self.format_signature(method, indent + 1));
+                        result.push('\n');
+                    } else {
+                        result.push_str(&self.format_function(method, indent + 1));
+                    }
+                }
+                result
+            }
+            Statement::Impl {
+                trait_name,
+                type_name,
+                methods,
+            } => {
+                let header = match trait_name {
+                    Some(trait_name) => format!(
+                        "{}impl {} for {}",
+                        self.indent(indent),
+                        trait_name,
+                        type_name
+                    ),
+                    None => format!("{}impl {}", self.indent(indent), type_name),
+                };
+                if methods.is_empty() {
+                    return format!("{}\n", header);
+                }
+                let mut result = format!("{}:\n", header);
+                for method in methods {
+                    result.push_str(&self.format_method(method, indent + 1));
+                }
+                result
+            }
             Statement::TypeAlias {
                 name,
                 target,
@@ -208,6 +330,22 @@ impl Formatter {
                     self.format_type(target)
                 )
             }
+            Statement::Const {
+                name,
+                ty,
+                expr,
+                public,
+            } => {
+                let pub_str = if *public { "pub " } else { "" };
+                format!(
+                    "{}{}const {}: {} = {}\n",
+                    self.indent(indent),
+                    pub_str,
+                    name,
+                    self.format_type(ty),
+                    self.format_expr(expr, indent)
+                )
+            }
             Statement::Use { imports } => {
                 let modules: Vec<String> = imports
                     .iter()
@@ -240,16 +378,91 @@ impl Formatter {
     }
 
     fn format_function(&self, f: &Node<Function>, indent: usize) -> String {
+        format!(
+            "{}:\n{}",
+            self.format_signature(f, indent),
+            self.format_block(&f.as_ref().body, indent + 1)
+        )
+    }
+
+    /// A method of a struct or `impl` block, marking associated functions `static`
+    fn format_method(&self, f: &Node<Function>, indent: usize) -> String {
+        let method = self.format_function(f, indent);
+        if f.as_ref().takes_self() {
+            return method;
+        }
+        let indentation = self.indent(indent);
+        format!("{}static {}", indentation, &method[indentation.len()..])
+    }
+
+    fn format_signature(&self, f: &Node<Function>, indent: usize) -> String {
         let pub_str = if f.as_ref().public { "pub " } else { "" };
-        let params_str = f
-            .as_ref()
-            .params
+        let gen_str = self.format_generics(&f.as_ref().generics, &f.as_ref().bounds);
+        let params_str = self.format_params(&f.as_ref().params, indent);
+        let ret_str = if let Some(ref ret_ty) = f.as_ref().ret_ty {
+            format!(" -> {}", self.format_type(ret_ty))
+        } else {
+            String::new()
+        };
+        format!(
+            "{}{}fn {}{}({}){}",
+            self.indent(indent),
+            pub_str,
+            f.as_ref().name,
+            gen_str,
+            params_str,
+            ret_str
+        )
+    }
+
+    fn format_derives(&self, derives: &[Node<String>], indent: usize) -> String {
+        if derives.is_empty() {
+            return String::new();
+        }
+        let names: Vec<&str> = derives
+            .iter()
+            .map(|derive| derive.as_ref().as_str())
+            .collect();
+        format!("{}@derive({})\n", self.indent(indent), names.join(", "))
+    }
+
+    fn format_generics(&self, generics: &[String], bounds: &[(String, Vec<String>)]) -> String {
+        if generics.is_empty() {
+            return String::new();
+        }
+        let params = generics
+            .iter()
+            .map(
+                |generic| match bounds.iter().find(|(name, _)| name == generic) {
+                    Some((_, traits)) => format!("{}: {}", generic, traits.join(" + ")),
+                    None => generic.clone(),
+                },
+            )
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("<{}>", params)
+    }
+
+    fn format_params(&self, params: &[Node<Param>], indent: usize) -> String {
+        params
             .iter()
             .map(|p| {
+                if p.as_ref().variadic {
+                    return match p.as_ref().variadic_element() {
+                        Some(element) => {
+                            format!("*{}: {}", p.as_ref().name, self.format_type(element))
+                        }
+                        None => format!("*{}", p.as_ref().name),
+                    };
+                }
+                let name = match &p.as_ref().pattern {
+                    Some(pattern) => self.format_pattern(pattern),
+                    None => p.as_ref().name.as_ref().clone(),
+                };
                 let base = if let Some(ref ty) = p.as_ref().ty {
-                    format!("{}: {}", p.as_ref().name, self.format_type(ty))
+                    format!("{}: {}", name, self.format_type(ty))
                 } else {
-                    p.as_ref().name.as_ref().clone()
+                    name
                 };
                 if let Some(default) = &p.as_ref().default {
                     format!("{} = {}", base, self.format_expr(default, indent))
@@ -258,21 +471,7 @@ impl Formatter {
                 }
             })
             .collect::<Vec<_>>()
-            .join(", ");
-        let ret_str = if let Some(ref ret_ty) = f.as_ref().ret_ty {
-            format!(" -> {}", self.format_type(ret_ty))
-        } else {
-            String::new()
-        };
-        format!(
-            "{}{}fn {}({}){}:\n{}",
-            self.indent(indent),
-            pub_str,
-            f.as_ref().name,
-            params_str,
-            ret_str,
-            self.format_block(&f.as_ref().body, indent + 1)
-        )
+            .join(", ")
     }
 
     fn format_block(&self, block: &Node<Block>, indent: usize) -> String {
@@ -334,17 +533,71 @@ impl Formatter {
                     self.format_expr(expr, indent)
                 )
             }
-            Expr::Call { func, args } => {
+            Expr::Cast { expr, ty } => {
+                let operand = self.format_expr(expr, indent);
+                if matches!(
+                    expr.as_ref().as_ref(),
+                    Expr::Binary { .. } | Expr::Range { .. }
+                ) {
+                    format!("({}) as {}", operand, self.format_type(ty))
+                } else {
+                    format!("{} as {}", operand, self.format_type(ty))
+                }
+            }
+            Expr::Call {
+                func,
+                args,
+                kwargs,
+                type_args,
+            } => {
                 let args_str = args
                     .iter()
                     .map(|arg| self.format_expr(arg, indent))
+                    .chain(kwargs.iter().map(|(name, value)| {
+                        format!("{}={}", name, self.format_expr(value, indent))
+                    }))
                     .collect::<Vec<_>>()
                     .join(", ");
-                format!("{}({})", self.format_expr(func, indent), args_str)
+                let type_args_str = if type_args.is_empty() {
+                    String::new()
+                } else {
+                    let types = type_args
+                        .iter()
+                        .map(|ty| self.format_type(ty))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    format!("<{}>", types)
+                };
+                format!(
+                    "{}{}({})",
+                    self.format_expr(func, indent),
+                    type_args_str,
+                    args_str
+                )
             }
             Expr::Member { object, field } => {
                 format!("{}.{}", self.format_expr(object, indent), field)
             }
+            Expr::Index { object, index } => {
+                format!(
+                    "{}[{}]",
+                    self.format_expr(object, indent),
+                    self.format_expr(index, indent)
+                )
+            }
+            Expr::Slice { object, start, end } => {
+                let bound = |b: &Option<Box<Node<Expr>>>| {
+                    b.as_ref()
+                        .map(|b| self.format_expr(b, indent))
+                        .unwrap_or_default()
+                };
+                format!(
+                    "{}[{}:{}]",
+                    self.format_expr(object, indent),
+                    bound(start),
+                    bound(end)
+                )
+            }
             Expr::If {
                 cond,
                 then_branch,
@@ -369,6 +622,12 @@ impl Formatter {
                     self.format_expr(end, indent)
                 )
             }
+            Expr::Tuple(elements) => self.format_tuple(
+                elements
+                    .iter()
+                    .map(|e| self.format_expr(e, indent))
+                    .collect(),
+            ),
             Expr::Array(elements) => {
                 let elements_str = elements
                     .iter()
@@ -450,21 +709,59 @@ impl Formatter {
                     .join(", ");
                 format!("{}({})", name, fields_str)
             }
-            // Lambda expressions removed - use anonymous fn syntax instead
+            Expr::Lambda {
+                params,
+                ret_ty,
+                body,
+            } => {
+                let params_str = self.format_params(params, indent);
+                let ret_str = ret_ty
+                    .as_ref()
+                    .map(|ty| format!(" -> {}", self.format_type(ty)))
+                    .unwrap_or_default();
+                if let [stmt] = body.as_ref().statements.as_slice()
+                    && let Statement::Return(Some(value)) = stmt.as_ref()
+                {
+                    format!(
+                        "fn({}){} => {}",
+                        params_str,
+                        ret_str,
+                        self.format_expr(value, indent)
+                    )
+                } else {
+                    format!(
+                        "fn({}){}:\n{}",
+                        params_str,
+                        ret_str,
+                        self.format_block(body, indent + 1).trim_end_matches('\n')
+                    )
+                }
+            }
+            Expr::Loop { label, body } => format!(
+                "{}loop:\n{}",
+                format_label(label.as_deref()),
+                self.format_block(body, indent + 1).trim_end_matches('\n')
+            ),
             Expr::Await(expr) => format!("await {}", self.format_expr(expr, indent)),
             Expr::Spawn(expr) => format!("spawn {}", self.format_expr(expr, indent)),
-            Expr::FString { parts } => {
+            Expr::Try(expr) => format!("{}?", self.format_expr(expr, indent)),
+            Expr::Spread(expr) => format!("*{}", self.format_expr(expr, indent)),
+            Expr::FString { parts, multiline } => {
                 let parts_str = parts
                     .iter()
                     .map(|part| match part.as_ref() {
-                        FStringPart::Text(s) => s.clone(),
+                        FStringPart::Text(s) => escape_string(s, *multiline, true),
                         FStringPart::Expr(e) => {
                             format!("{{{}}}", self.format_expr(e, indent))
                         }
                     })
                     .collect::<Vec<_>>()
                     .join("");
-                format!("f\"{}\"", parts_str)
+                if *multiline {
+                    format!("f\"\"\"{}\"\"\"", parts_str)
+                } else {
+                    format!("f\"{}\"", parts_str)
+                }
             }
         }
     }
@@ -495,7 +792,7 @@ impl Formatter {
                     .iter()
                     .map(|(f, p_opt)| {
                         if let Some(p) = p_opt {
-                            format!("{}: {}", f, self.format_pattern(p))
+                            format!("{}={}", f, self.format_pattern(p))
                         } else {
                             f.clone()
                         }
@@ -517,20 +814,73 @@ impl Formatter {
                 };
                 format!("[{}{}]", patterns_str, rest_str)
             }
+            Pattern::Tuple(patterns) => {
+                self.format_tuple(patterns.iter().map(|p| self.format_pattern(p)).collect())
+            }
+            Pattern::StringPrefix { prefix, rest } => format!(
+                "{} + {}",
+                self.format_literal(prefix),
+                rest.as_deref().unwrap_or("_")
+            ),
+            Pattern::Or(alternatives) => alternatives
+                .iter()
+                .map(|p| self.format_pattern(p))
+                .collect::<Vec<_>>()
+                .join(" | "),
+            Pattern::Range {
+                start,
+                end,
+                inclusive,
+            } => {
+                let op = if *inclusive { "..=" } else { ".." };
+                format!(
+                    "{}{}{}",
+                    self.format_literal(start),
+                    op,
+                    self.format_literal(end)
+                )
+            }
+            Pattern::Binding { name, pattern } => {
+                let inner = self.format_pattern(pattern);
+                if matches!(pattern.as_ref().as_ref(), Pattern::Or(_)) {
+                    format!("{} @ ({})", name, inner)
+                } else {
+                    format!("{} @ {}", name, inner)
+                }
+            }
+        }
+    }
+
+    /// Parenthesized, comma-separated items; a one-element tuple keeps its trailing comma
+    fn format_tuple(&self, items: Vec<String>) -> String {
+        if items.len() == 1 {
+            format!("({},)", items[0])
+        } else {
+            format!("({})", items.join(", "))
         }
     }
 
     fn format_literal(&self, lit: &Node<Literal>) -> String {
         match lit.as_ref() {
             Literal::Number(n) => {
-                if !n.is_float_literal && n.value.fract() == 0.0 {
-                    format!("{}", n.value as i64)
+                if let Some(source) = &n.source {
+                    source.clone()
+                } else if let Some(integer) = n.integer {
+                    integer.to_string()
                 } else {
                     n.value.to_string()
                 }
             }
             Literal::Bool(b) => b.to_string(),
-            Literal::String(s) => format!("\"{}\"", s),
+            Literal::String(s) => match s.style {
+                StringStyle::Quoted => format!("\"{}\"", escape_string(&s.value, false, false)),
+                StringStyle::Multiline => {
+                    format!("\"\"\"{}\"\"\"", escape_string(&s.value, true, false))
+                }
+                StringStyle::Raw => format!("r\"{}\"", s.value),
+                StringStyle::RawMultiline => format!("r\"\"\"{}\"\"\"", s.value),
+            },
+            Literal::Bytes(bytes) => format!("b\"{}\"", escape_bytes(bytes)),
             Literal::None => "None".to_string(),
             Literal::Unit => "()".to_string(),
         }
@@ -551,33 +901,33 @@ impl Formatter {
                     format!("{}<{}>", base, args_str)
                 }
             }
+            Type::Function { params, ret } => {
+                let params_str = params
+                    .iter()
+                    .map(|p| self.format_type(p))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                match ret {
+                    Some(ret) => format!("fn({}) -> {}", params_str, self.format_type(ret)),
+                    None => format!("fn({})", params_str),
+                }
+            }
+            Type::Dyn(name) => format!("dyn {}", name),
+            Type::Tuple(elements) => {
+                self.format_tuple(elements.iter().map(|e| self.format_type(e)).collect())
+            }
         }
     }
 
     fn format_binary_op(&self, op: &BinaryOp) -> &str {
-        match op {
-            BinaryOp::Add => "+",
-            BinaryOp::Mul => "*",
-            BinaryOp::Sub => "-",
-            BinaryOp::Div => "/",
-            BinaryOp::Mod => "%",
-            BinaryOp::Eq => "==",
-            BinaryOp::Ne => "!=",
-            BinaryOp::Lt => "<",
-            BinaryOp::LtEq => "<=",
-            BinaryOp::Gt => ">",
-            BinaryOp::GtEq => ">=",
-            BinaryOp::Is => "is",
-            BinaryOp::IsNot => "is not",
-            BinaryOp::And => "and",
-            BinaryOp::Or => "or",
-        }
+        op.symbol()
     }
 
     fn format_unary_op(&self, op: &UnaryOp) -> &str {
         match op {
             UnaryOp::Not => "not ",
             UnaryOp::Neg => "-",
+            UnaryOp::BitNot => "~",
         }
     }
 
@@ -591,3 +941,53 @@ impl Default for Formatter {
         Self::new()
     }
 }
+
+/// The `name: ` prefix of a labelled loop
+fn format_label(label: Option<&str>) -> String {
+    label
+        .map(|label| format!("{}: ", label))
+        .unwrap_or_default()
+}
+
+/// Write `value` back as the body of a string literal. Inside triple quotes line breaks, tabs and
+/// quotes stay as they are, except for quotes that would close the string early; f-string text
+/// doubles its braces.
+fn escape_string(value: &str, multiline: bool, fstring: bool) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    let mut escaped = String::with_capacity(value.len());
+    for (i, &ch) in chars.iter().enumerate() {
+        match ch {
+            '\\' => escaped.push_str("\\\\"),
+            '\r' => escaped.push_str("\\r"),
+            '\n' if !multiline => escaped.push_str("\\n"),
+            '\t' if !multiline => escaped.push_str("\\t"),
+            '"' if !multiline => escaped.push_str("\\\""),
+            '"' if i + 1 == chars.len() || chars[i + 1..].starts_with(&['"', '"']) => {
+                escaped.push_str("\\\"");
+            }
+            '{' | '}' if fstring => {
+                escaped.push(ch);
+                escaped.push(ch);
+            }
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// Write the bytes of a byte string back as printable ASCII and escapes
+fn escape_bytes(bytes: &[u8]) -> String {
+    let mut escaped = String::with_capacity(bytes.len());
+    for &byte in bytes {
+        match byte {
+            b'\\' => escaped.push_str("\\\\"),
+            b'"' => escaped.push_str("\\\""),
+            b'\n' => escaped.push_str("\\n"),
+            b'\t' => escaped.push_str("\\t"),
+            b'\r' => escaped.push_str("\\r"),
+            b' '..=b'~' => escaped.push(char::from(byte)),
+            _ => escaped.push_str(&format!("\\x{:02x}", byte)),
+        }
+    }
+    escaped
+}
@@ -200,9 +200,12 @@ impl JitEngine {
 
         let mut functions = self.compiled_functions.lock().unwrap();
 
-        // Extract function definitions from program
+        // Extract function definitions from program; generic functions only exist as their
+        // instantiations
         for stmt in &program.statements {
-            if let Statement::Function(func) = stmt.as_ref() {
+            if let Statement::Function(func) = stmt.as_ref()
+                && !func.as_ref().is_generic()
+            {
                 let func_name = &func.as_ref().name;
                 let arg_count = func.as_ref().params.len();
 
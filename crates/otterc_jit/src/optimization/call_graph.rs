@@ -33,7 +33,10 @@ impl CallGraph {
 
     fn extract_callees_from_stmt(&self, stmt: &Statement, callees: &mut Vec<String>) {
         match stmt {
-            Statement::Expr(expr) => {
+            Statement::Expr(expr) if let Expr::Loop { body, .. } = expr.as_ref() => {
+                self.extract_callees_from_block(body.as_ref(), callees);
+            }
+            Statement::Expr(expr) | Statement::Defer(expr) => {
                 if let Expr::Call { func, .. } = expr.as_ref()
                     && let Expr::Identifier(name) = func.as_ref().as_ref()
                 {
@@ -54,8 +57,23 @@ impl CallGraph {
                     self.extract_callees_from_block(block.as_ref(), callees);
                 }
             }
-            Statement::For { body, .. } | Statement::While { body, .. } => {
+            Statement::For { body, .. }
+            | Statement::While { body, .. }
+            | Statement::With { body, .. } => {
+                self.extract_callees_from_block(body.as_ref(), callees);
+            }
+            Statement::Try {
+                body,
+                handlers,
+                finally_block,
+            } => {
                 self.extract_callees_from_block(body.as_ref(), callees);
+                for handler in handlers {
+                    self.extract_callees_from_block(handler.as_ref().body.as_ref(), callees);
+                }
+                if let Some(block) = finally_block {
+                    self.extract_callees_from_block(block.as_ref(), callees);
+                }
             }
             _ => {}
         }
@@ -3,7 +3,7 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 
 use super::call_graph::CallGraph;
 use otterc_ast::nodes::{
-    Block, Expr, FStringPart, Function, Literal, MatchArm, Node, Pattern, Program, Statement,
+    Block, Expr, FStringPart, Function, Literal, MatchArm, Node, Param, Pattern, Program, Statement,
 };
 
 /// Configuration for the inliner.
@@ -71,11 +71,13 @@ impl Inliner {
         let mut stats = InlineStats::default();
         let mut optimized = program.clone();
         let function_map = Self::index_functions(program);
+        let constants = Self::constant_names(program);
 
         let ctx = InlineContext {
             function_map: &function_map,
             hot_functions,
             call_graph,
+            constants: &constants,
         };
 
         for stmt in &mut optimized.statements {
@@ -162,7 +164,7 @@ impl Inliner {
         let (stmt, span) = stmt.into_parts();
         match stmt {
             Statement::Let {
-                name,
+                pattern,
                 ty,
                 expr,
                 public,
@@ -186,7 +188,7 @@ impl Inliner {
                     ));
                     out.push(Node::new(
                         Statement::Let {
-                            name,
+                            pattern,
                             ty: annotation.clone(),
                             expr: value,
                             public,
@@ -206,7 +208,7 @@ impl Inliner {
                     );
                     out.push(Node::new(
                         Statement::Let {
-                            name,
+                            pattern,
                             ty: annotation,
                             expr,
                             public,
@@ -215,7 +217,7 @@ impl Inliner {
                     ));
                 }
             }
-            Statement::Assignment { name, expr } => {
+            Statement::Assignment { target, op, expr } => {
                 let mut expr_clone = expr.clone();
                 if let Some(mut snippet) = self.try_inline_expr(
                     &mut expr_clone,
@@ -232,7 +234,14 @@ impl Inliner {
                         Expr::Literal(Node::new(Literal::Unit, span)),
                         span,
                     ));
-                    out.push(Node::new(Statement::Assignment { name, expr: value }, span));
+                    out.push(Node::new(
+                        Statement::Assignment {
+                            target,
+                            op,
+                            expr: value,
+                        },
+                        span,
+                    ));
                 } else {
                     let mut expr = expr;
                     self.inline_expr(
@@ -244,7 +253,7 @@ impl Inliner {
                         current_hot,
                         current_name,
                     );
-                    out.push(Node::new(Statement::Assignment { name, expr }, span));
+                    out.push(Node::new(Statement::Assignment { target, op, expr }, span));
                 }
             }
             Statement::Expr(mut expr) => {
@@ -312,7 +321,11 @@ impl Inliner {
                     span,
                 ));
             }
-            Statement::While { mut cond, mut body } => {
+            Statement::While {
+                label,
+                mut cond,
+                mut body,
+            } => {
                 self.inline_expr(
                     &mut cond,
                     ctx,
@@ -331,10 +344,11 @@ impl Inliner {
                     current_hot,
                     current_name,
                 );
-                out.push(Node::new(Statement::While { cond, body }, span));
+                out.push(Node::new(Statement::While { label, cond, body }, span));
             }
             Statement::For {
-                var,
+                label,
+                pattern,
                 mut iterable,
                 mut body,
             } => {
@@ -358,13 +372,38 @@ impl Inliner {
                 );
                 out.push(Node::new(
                     Statement::For {
-                        var,
+                        label,
+                        pattern,
                         iterable,
                         body,
                     },
                     span,
                 ));
             }
+            Statement::With {
+                expr,
+                binding,
+                mut body,
+            } => {
+                // The header stays a call so codegen can find the runtime handle's `exit`
+                self.inline_block(
+                    &mut body,
+                    ctx,
+                    stack,
+                    stats,
+                    depth,
+                    current_hot,
+                    current_name,
+                );
+                out.push(Node::new(
+                    Statement::With {
+                        expr,
+                        binding,
+                        body,
+                    },
+                    span,
+                ));
+            }
             Statement::Block(mut inner) => {
                 self.inline_block(
                     &mut inner,
@@ -377,7 +416,43 @@ impl Inliner {
                 );
                 out.push(Node::new(Statement::Block(inner), span));
             }
-            // Exception handling (try/except/finally/raise) removed - use Result<T, E> pattern matching instead
+            Statement::Try {
+                mut body,
+                mut handlers,
+                mut finally_block,
+            } => {
+                self.inline_block(
+                    &mut body,
+                    ctx,
+                    stack,
+                    stats,
+                    depth,
+                    current_hot,
+                    current_name,
+                );
+                for handler in &mut handlers {
+                    self.inline_block(
+                        &mut handler.as_mut().body,
+                        ctx,
+                        stack,
+                        stats,
+                        depth,
+                        current_hot,
+                        current_name,
+                    );
+                }
+                if let Some(ref mut blk) = finally_block {
+                    self.inline_block(blk, ctx, stack, stats, depth, current_hot, current_name);
+                }
+                out.push(Node::new(
+                    Statement::Try {
+                        body,
+                        handlers,
+                        finally_block,
+                    },
+                    span,
+                ));
+            }
             other => out.push(Node::new(other, span)),
         }
     }
@@ -397,9 +472,14 @@ impl Inliner {
         current_name: &str,
     ) {
         match expr.as_mut() {
-            Expr::Call { func, args } => {
+            Expr::Call {
+                func, args, kwargs, ..
+            } => {
                 self.inline_expr(func, ctx, stack, stats, depth, current_hot, current_name);
-                for arg in args {
+                for arg in args
+                    .iter_mut()
+                    .chain(kwargs.iter_mut().map(|(_, value)| value))
+                {
                     self.inline_expr(arg, ctx, stack, stats, depth, current_hot, current_name);
                 }
             }
@@ -407,9 +487,19 @@ impl Inliner {
                 self.inline_expr(left, ctx, stack, stats, depth, current_hot, current_name);
                 self.inline_expr(right, ctx, stack, stats, depth, current_hot, current_name);
             }
-            Expr::Unary { expr: inner, .. } => {
+            Expr::Unary { expr: inner, .. } | Expr::Cast { expr: inner, .. } => {
                 self.inline_expr(inner, ctx, stack, stats, depth, current_hot, current_name);
             }
+            Expr::Index { object, index } => {
+                self.inline_expr(object, ctx, stack, stats, depth, current_hot, current_name);
+                self.inline_expr(index, ctx, stack, stats, depth, current_hot, current_name);
+            }
+            Expr::Slice { object, start, end } => {
+                self.inline_expr(object, ctx, stack, stats, depth, current_hot, current_name);
+                for bound in [start, end].into_iter().flatten() {
+                    self.inline_expr(bound, ctx, stack, stats, depth, current_hot, current_name);
+                }
+            }
             Expr::If {
                 cond,
                 then_branch,
@@ -454,7 +544,7 @@ impl Inliner {
                     );
                 }
             }
-            Expr::Array(values) => {
+            Expr::Tuple(values) | Expr::Array(values) => {
                 for value in values {
                     self.inline_expr(value, ctx, stack, stats, depth, current_hot, current_name);
                 }
@@ -507,17 +597,19 @@ impl Inliner {
                     self.inline_expr(cond, ctx, stack, stats, depth, current_hot, current_name);
                 }
             }
-            Expr::FString { parts } => {
+            Expr::FString { parts, .. } => {
                 for part in parts {
                     if let FStringPart::Expr(expr) = part.as_mut() {
                         self.inline_expr(expr, ctx, stack, stats, depth, current_hot, current_name);
                     }
                 }
             }
-            // Lambda expressions removed - use anonymous fn syntax instead
-            Expr::Spawn(expr) | Expr::Await(expr) => {
+            Expr::Spawn(expr) | Expr::Await(expr) | Expr::Try(expr) | Expr::Spread(expr) => {
                 self.inline_expr(expr, ctx, stack, stats, depth, current_hot, current_name);
             }
+            Expr::Loop { body, .. } => {
+                self.inline_block(body, ctx, stack, stats, depth, current_hot, current_name);
+            }
             Expr::Struct { fields, .. } => {
                 for (_, value) in fields {
                     self.inline_expr(value, ctx, stack, stats, depth, current_hot, current_name);
@@ -542,7 +634,11 @@ impl Inliner {
         current_hot: bool,
         current_name: &str,
     ) -> Option<InlineSnippet> {
-        if let Expr::Call { func, args } = expr.as_mut()
+        // Keyword arguments are matched to parameters by the code generator, not here
+        if let Expr::Call {
+            func, args, kwargs, ..
+        } = expr.as_mut()
+            && kwargs.is_empty()
             && let Expr::Identifier(name) = func.as_ref().as_ref()
         {
             return self.try_inline_call(
@@ -583,7 +679,16 @@ impl Inliner {
             return None;
         };
 
-        if args.len() != callee.as_ref().params.len() {
+        // Generic functions are instantiated per call site by the code generator, which also
+        // collects the arguments of a variadic parameter
+        if args.len() != callee.as_ref().params.len()
+            || callee.as_ref().is_generic()
+            || callee
+                .as_ref()
+                .params
+                .iter()
+                .any(|param| param.as_ref().variadic)
+        {
             stats.skipped_complex += 1;
             return None;
         }
@@ -610,9 +715,14 @@ impl Inliner {
         }
 
         let inline_id = self.next_inline_id();
-        let mut builder = InlineBuilder::new(inline_id);
+        let mut builder = InlineBuilder::new(inline_id, ctx.constants);
         let snippet = builder.build_snippet(callee, args);
 
+        if builder.propagates_errors {
+            stats.skipped_complex += 1;
+            return None;
+        }
+
         if needs_result && snippet.result_expr.is_none() {
             stats.skipped_complex += 1;
             return None;
@@ -691,7 +801,17 @@ impl Inliner {
                         return true;
                     }
                 }
-                // Exception handling (try/except/finally/raise) removed
+                Statement::Let { expr, .. } | Statement::Expr(expr)
+                    if let Expr::Loop { body, .. } = expr.as_ref()
+                        && Self::has_internal_return(body) =>
+                {
+                    return true;
+                }
+                // Leaving a `try` or `with`, or a block with a `defer`, also runs cleanup code,
+                // which inlining cannot express
+                Statement::Try { .. } | Statement::With { .. } | Statement::Defer(_) => {
+                    return true;
+                }
                 _ => {}
             }
         }
@@ -712,6 +832,17 @@ impl Inliner {
         }
         map
     }
+
+    fn constant_names(program: &Program) -> HashSet<String> {
+        program
+            .statements
+            .iter()
+            .filter_map(|stmt| match stmt.as_ref() {
+                Statement::Const { name, .. } => Some(name.clone()),
+                _ => None,
+            })
+            .collect()
+    }
 }
 
 impl Default for Inliner {
@@ -731,6 +862,8 @@ struct InlineContext<'a> {
     hot_functions: &'a HashSet<String>,
     #[expect(dead_code, reason = "Work in progress")]
     call_graph: &'a CallGraph,
+    /// Module constants, which patterns match by value instead of binding
+    constants: &'a HashSet<String>,
 }
 
 struct BuiltSnippet {
@@ -738,14 +871,19 @@ struct BuiltSnippet {
     result_expr: Option<Node<Expr>>,
 }
 
-struct InlineBuilder {
+struct InlineBuilder<'a> {
     names: InlineNameGenerator,
+    constants: &'a HashSet<String>,
+    /// Set when the callee uses `?`, whose early return cannot be spliced into the caller
+    propagates_errors: bool,
 }
 
-impl InlineBuilder {
-    fn new(inline_id: usize) -> Self {
+impl<'a> InlineBuilder<'a> {
+    fn new(inline_id: usize, constants: &'a HashSet<String>) -> Self {
         Self {
             names: InlineNameGenerator::new(inline_id),
+            constants,
+            propagates_errors: false,
         }
     }
 
@@ -758,9 +896,14 @@ impl InlineBuilder {
                 param.as_ref().name.as_ref(),
                 format!("__inl{}_arg{}", inline_id, idx),
             );
+            // A destructured parameter binds only the names in its pattern
+            let pattern = match &param.as_ref().pattern {
+                Some(pattern) => self.rewrite_pattern(pattern),
+                None => Node::new(Pattern::Identifier(param_name), *param.as_ref().name.span()),
+            };
             statements.push(Node::new(
                 Statement::Let {
-                    name: Node::new(param_name, *param.as_ref().name.span()),
+                    pattern,
                     ty: param.as_ref().ty.clone(),
                     expr: arg,
                     public: false,
@@ -807,18 +950,19 @@ impl InlineBuilder {
     fn rewrite_statement(&mut self, stmt: &Node<Statement>) -> Node<Statement> {
         stmt.clone().map(|stmt| match stmt {
             Statement::Let {
-                name,
+                pattern,
                 ty,
                 expr,
                 public,
             } => Statement::Let {
-                name: name.map(|name| self.names.rename_local(&name)),
+                pattern: self.rewrite_pattern(&pattern),
                 ty: ty.clone(),
                 expr: self.rewrite_expr(&expr),
                 public,
             },
-            Statement::Assignment { name, expr } => Statement::Assignment {
-                name: name.map(|name| self.names.resolve_or_clone(&name)),
+            Statement::Assignment { target, op, expr } => Statement::Assignment {
+                target: self.rewrite_expr(&target),
+                op,
                 expr: self.rewrite_expr(&expr),
             },
             Statement::Expr(expr) => Statement::Expr(self.rewrite_expr(&expr)),
@@ -841,20 +985,32 @@ impl InlineBuilder {
                     .map(|block| self.rewrite_nested_block(block)),
             },
             Statement::For {
-                var,
+                label,
+                pattern,
                 iterable,
                 body,
             } => Statement::For {
-                var: var.map(|var| self.names.rename_local(&var)),
+                label,
+                pattern: self.rewrite_pattern(&pattern),
                 iterable: self.rewrite_expr(&iterable),
                 body: self.rewrite_nested_block(&body),
             },
-            Statement::While { cond, body } => Statement::While {
+            Statement::While { label, cond, body } => Statement::While {
+                label,
                 cond: self.rewrite_expr(&cond),
                 body: self.rewrite_nested_block(&body),
             },
+            Statement::Break { label, value } => Statement::Break {
+                label,
+                value: value.as_ref().map(|value| self.rewrite_expr(value)),
+            },
             Statement::Block(block) => Statement::Block(self.rewrite_nested_block(&block)),
-            // Exception handling (try/except/finally/raise) removed
+            Statement::Return(expr) => {
+                Statement::Return(expr.as_ref().map(|expr| self.rewrite_expr(expr)))
+            }
+            Statement::Raise(expr) => {
+                Statement::Raise(expr.as_ref().map(|expr| self.rewrite_expr(expr)))
+            }
             other => other.clone(),
         })
     }
@@ -879,9 +1035,38 @@ impl InlineBuilder {
                 op,
                 expr: Box::new(self.rewrite_expr(&expr)),
             },
-            Expr::Call { func, args } => Expr::Call {
+            Expr::Cast { expr, ty } => Expr::Cast {
+                expr: Box::new(self.rewrite_expr(&expr)),
+                ty,
+            },
+            Expr::Call {
+                func,
+                args,
+                kwargs,
+                type_args,
+            } => Expr::Call {
                 func: Box::new(self.rewrite_expr(&func)),
                 args: args.iter().map(|arg| self.rewrite_expr(arg)).collect(),
+                kwargs: kwargs
+                    .iter()
+                    .map(|(name, value)| (name.clone(), self.rewrite_expr(value)))
+                    .collect(),
+                type_args,
+            },
+            Expr::Member { object, field } => Expr::Member {
+                object: Box::new(self.rewrite_expr(&object)),
+                field,
+            },
+            Expr::Index { object, index } => Expr::Index {
+                object: Box::new(self.rewrite_expr(&object)),
+                index: Box::new(self.rewrite_expr(&index)),
+            },
+            Expr::Slice { object, start, end } => Expr::Slice {
+                object: Box::new(self.rewrite_expr(&object)),
+                start: start
+                    .as_ref()
+                    .map(|start| Box::new(self.rewrite_expr(start))),
+                end: end.as_ref().map(|end| Box::new(self.rewrite_expr(end))),
             },
             Expr::If {
                 cond,
@@ -907,6 +1092,12 @@ impl InlineBuilder {
                     })
                     .collect(),
             },
+            Expr::Tuple(values) => Expr::Tuple(
+                values
+                    .iter()
+                    .map(|value| self.rewrite_expr(value))
+                    .collect(),
+            ),
             Expr::Array(values) => Expr::Array(
                 values
                     .iter()
@@ -947,7 +1138,7 @@ impl InlineBuilder {
                     .as_ref()
                     .map(|cond| Box::new(self.rewrite_expr(cond))),
             },
-            Expr::FString { parts } => Expr::FString {
+            Expr::FString { parts, multiline } => Expr::FString {
                 parts: parts
                     .into_iter()
                     .map(|part| {
@@ -957,10 +1148,43 @@ impl InlineBuilder {
                         })
                     })
                     .collect(),
+                multiline,
+            },
+            Expr::Lambda {
+                params,
+                ret_ty,
+                body,
+            } => {
+                // Parameters shadow the enclosing names only inside the closure body
+                let outer_names = self.names.map.clone();
+                let params = params
+                    .iter()
+                    .map(|param| {
+                        param.clone().map(|param| Param {
+                            name: param.name.map(|name| self.names.rename_local(&name)),
+                            ..param
+                        })
+                    })
+                    .collect();
+                let body = self.rewrite_nested_block(&body);
+                self.names.map = outer_names;
+                Expr::Lambda {
+                    params,
+                    ret_ty,
+                    body,
+                }
+            }
+            Expr::Loop { label, body } => Expr::Loop {
+                label,
+                body: self.rewrite_nested_block(&body),
             },
-            // Lambda expressions removed - use anonymous fn syntax instead
             Expr::Spawn(expr) => Expr::Spawn(Box::new(self.rewrite_expr(&expr))),
             Expr::Await(expr) => Expr::Await(Box::new(self.rewrite_expr(&expr))),
+            Expr::Spread(expr) => Expr::Spread(Box::new(self.rewrite_expr(&expr))),
+            Expr::Try(expr) => {
+                self.propagates_errors = true;
+                Expr::Try(Box::new(self.rewrite_expr(&expr)))
+            }
             Expr::Struct { name, fields } => Expr::Struct {
                 name: name.clone(),
                 fields: fields
@@ -973,23 +1197,42 @@ impl InlineBuilder {
     }
 
     fn rewrite_pattern(&mut self, pattern: &Node<Pattern>) -> Node<Pattern> {
+        self.rewrite_pattern_bindings(pattern, true)
+    }
+
+    /// Rename the names a pattern binds: to fresh names, or with `fresh` unset to the names
+    /// already chosen for them, as the later alternatives of an or-pattern need
+    fn rewrite_pattern_bindings(&mut self, pattern: &Node<Pattern>, fresh: bool) -> Node<Pattern> {
+        let bind = |names: &mut InlineNameGenerator, name: &str| {
+            if fresh {
+                names.rename_local(name)
+            } else {
+                names.resolve_or_clone(name)
+            }
+        };
         pattern.clone().map(|pattern| match pattern {
-            Pattern::Identifier(name) => Pattern::Identifier(self.names.rename_local(&name)),
+            Pattern::Identifier(name) if self.constants.contains(&name) => {
+                Pattern::Identifier(name)
+            }
+            Pattern::Identifier(name) => Pattern::Identifier(bind(&mut self.names, &name)),
             Pattern::Struct { name, fields } => Pattern::Struct {
                 name: name.clone(),
                 fields: fields
                     .into_iter()
                     .map(|(field, pat)| {
-                        (field.clone(), pat.map(|inner| self.rewrite_pattern(&inner)))
+                        (
+                            field.clone(),
+                            pat.map(|inner| self.rewrite_pattern_bindings(&inner, fresh)),
+                        )
                     })
                     .collect(),
             },
             Pattern::Array { patterns, rest } => Pattern::Array {
                 patterns: patterns
                     .into_iter()
-                    .map(|pat| self.rewrite_pattern(&pat))
+                    .map(|pat| self.rewrite_pattern_bindings(&pat, fresh))
                     .collect(),
-                rest: rest.as_ref().map(|name| self.names.rename_local(name)),
+                rest: rest.as_ref().map(|name| bind(&mut self.names, name)),
             },
             Pattern::EnumVariant {
                 enum_name,
@@ -1000,8 +1243,30 @@ impl InlineBuilder {
                 variant: variant.clone(),
                 fields: fields
                     .into_iter()
-                    .map(|pat| self.rewrite_pattern(&pat))
+                    .map(|pat| self.rewrite_pattern_bindings(&pat, fresh))
+                    .collect(),
+            },
+            Pattern::Tuple(patterns) => Pattern::Tuple(
+                patterns
+                    .into_iter()
+                    .map(|pat| self.rewrite_pattern_bindings(&pat, fresh))
                     .collect(),
+            ),
+            // Every alternative binds the same names, so all of them share the first one's
+            Pattern::Or(alternatives) => Pattern::Or(
+                alternatives
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, pat)| self.rewrite_pattern_bindings(&pat, fresh && index == 0))
+                    .collect(),
+            ),
+            Pattern::Binding { name, pattern } => Pattern::Binding {
+                name: bind(&mut self.names, &name),
+                pattern: Box::new(self.rewrite_pattern_bindings(&pattern, fresh)),
+            },
+            Pattern::StringPrefix { prefix, rest } => Pattern::StringPrefix {
+                prefix,
+                rest: rest.as_ref().map(|name| bind(&mut self.names, name)),
             },
             _ => pattern.clone(),
         })
@@ -125,7 +125,12 @@ impl Reoptimizer {
             Statement::Let { expr, .. }
             | Statement::Assignment { expr, .. }
             | Statement::Expr(expr)
-            | Statement::Return(Some(expr)) => {
+            | Statement::Return(Some(expr))
+            | Statement::Raise(Some(expr))
+            | Statement::Defer(expr)
+            | Statement::Break {
+                value: Some(expr), ..
+            } => {
                 self.fold_constants_in_expr(expr.as_mut());
             }
             Statement::If {
@@ -143,16 +148,32 @@ impl Reoptimizer {
                     self.fold_constants_in_block(block.as_mut());
                 }
             }
-            Statement::While { cond, body } => {
+            Statement::While { cond, body, .. } => {
                 self.fold_constants_in_expr(cond.as_mut());
                 self.fold_constants_in_block(body.as_mut());
             }
+            Statement::With { expr, body, .. } => {
+                self.fold_constants_in_expr(expr.as_mut());
+                self.fold_constants_in_block(body.as_mut());
+            }
             Statement::For { iterable, body, .. } => {
                 self.fold_constants_in_expr(iterable.as_mut());
                 self.fold_constants_in_block(body.as_mut());
             }
             Statement::Block(inner) => self.fold_constants_in_block(inner.as_mut()),
-            // Exception handling (try/except/finally/raise) removed
+            Statement::Try {
+                body,
+                handlers,
+                finally_block,
+            } => {
+                self.fold_constants_in_block(body.as_mut());
+                for handler in handlers {
+                    self.fold_constants_in_block(handler.as_mut().body.as_mut());
+                }
+                if let Some(block) = finally_block {
+                    self.fold_constants_in_block(block.as_mut());
+                }
+            }
             _ => {}
         }
     }
@@ -205,14 +226,19 @@ impl Reoptimizer {
                 }
                 None
             }
-            Expr::Call { func, args } => {
+            Expr::Call {
+                func, args, kwargs, ..
+            } => {
                 self.fold_constants_in_expr(func.as_mut().as_mut());
-                for arg in args {
+                for arg in args
+                    .iter_mut()
+                    .chain(kwargs.iter_mut().map(|(_, value)| value))
+                {
                     self.fold_constants_in_expr(arg.as_mut());
                 }
                 None
             }
-            Expr::Array(values) => {
+            Expr::Tuple(values) | Expr::Array(values) => {
                 for value in values {
                     self.fold_constants_in_expr(value.as_mut());
                 }
@@ -263,7 +289,7 @@ impl Reoptimizer {
                 }
                 None
             }
-            Expr::FString { parts } => {
+            Expr::FString { parts, .. } => {
                 for part in parts {
                     if let FStringPart::Expr(expr) = part.as_mut() {
                         self.fold_constants_in_expr(expr.as_mut());
@@ -271,8 +297,15 @@ impl Reoptimizer {
                 }
                 None
             }
-            // Lambda expressions removed - use anonymous fn syntax instead
-            Expr::Spawn(expr) | Expr::Await(expr) => {
+            Expr::Lambda { body, .. } | Expr::Loop { body, .. } => {
+                self.fold_constants_in_block(body.as_mut());
+                None
+            }
+            Expr::Spawn(expr)
+            | Expr::Await(expr)
+            | Expr::Try(expr)
+            | Expr::Spread(expr)
+            | Expr::Cast { expr, .. } => {
                 self.fold_constants_in_expr(expr.as_mut().as_mut());
                 None
             }
@@ -290,10 +323,13 @@ impl Reoptimizer {
         let (literal, span) = literal.clone().into_parts();
         match (op, literal) {
             (UnaryOp::Not, Literal::Bool(value)) => Some(Node::new(Literal::Bool(!value), span)),
-            (UnaryOp::Neg, Literal::Number(num)) => Some(Node::new(
-                Literal::Number(NumberLiteral::new(-num.value, num.is_float_literal)),
-                span,
-            )),
+            (UnaryOp::Neg, Literal::Number(num)) => {
+                let negated = match num.integer {
+                    Some(value) => NumberLiteral::from_int(-value),
+                    None => NumberLiteral::new(-num.value, num.is_float_literal),
+                };
+                Some(Node::new(Literal::Number(negated), span))
+            }
             _ => None,
         }
     }
@@ -305,12 +341,21 @@ impl Reoptimizer {
     ) -> Option<Node<Literal>> {
         let span = left.span().merge(right.span());
         match op {
-            BinaryOp::Add => Self::eval_arithmetic(left.as_ref(), right.as_ref(), |a, b| a + b)
-                .map(|lit| Node::new(lit, span)),
-            BinaryOp::Sub => Self::eval_arithmetic(left.as_ref(), right.as_ref(), |a, b| a - b)
-                .map(|lit| Node::new(lit, span)),
-            BinaryOp::Mul => Self::eval_arithmetic(left.as_ref(), right.as_ref(), |a, b| a * b)
-                .map(|lit| Node::new(lit, span)),
+            BinaryOp::Add => {
+                Self::eval_integer_arithmetic(left.as_ref(), right.as_ref(), i64::checked_add)
+                    .or_else(|| Self::eval_arithmetic(left.as_ref(), right.as_ref(), |a, b| a + b))
+                    .map(|lit| Node::new(lit, span))
+            }
+            BinaryOp::Sub => {
+                Self::eval_integer_arithmetic(left.as_ref(), right.as_ref(), i64::checked_sub)
+                    .or_else(|| Self::eval_arithmetic(left.as_ref(), right.as_ref(), |a, b| a - b))
+                    .map(|lit| Node::new(lit, span))
+            }
+            BinaryOp::Mul => {
+                Self::eval_integer_arithmetic(left.as_ref(), right.as_ref(), i64::checked_mul)
+                    .or_else(|| Self::eval_arithmetic(left.as_ref(), right.as_ref(), |a, b| a * b))
+                    .map(|lit| Node::new(lit, span))
+            }
             BinaryOp::Div => {
                 if matches!(right.as_ref(), Literal::Number(n) if n.value == 0.0) {
                     None
@@ -353,6 +398,20 @@ impl Reoptimizer {
         }
     }
 
+    /// Fold integer operands exactly, as long as the result fits in an `int`
+    fn eval_integer_arithmetic(
+        left: &Literal,
+        right: &Literal,
+        op: fn(i64, i64) -> Option<i64>,
+    ) -> Option<Literal> {
+        let (Literal::Number(a), Literal::Number(b)) = (left, right) else {
+            return None;
+        };
+        let a = i64::try_from(a.integer?).ok()?;
+        let b = i64::try_from(b.integer?).ok()?;
+        op(a, b).map(|value| Literal::Number(NumberLiteral::from_int(value.into())))
+    }
+
     fn eval_arithmetic<F>(left: &Literal, right: &Literal, op: F) -> Option<Literal>
     where
         F: Fn(f64, f64) -> f64,
@@ -381,23 +440,9 @@ impl Reoptimizer {
                     && let Literal::Bool(value) = lit.as_ref()
                 {
                     if *value {
-                        StatementTransform::Many(
-                            then_block
-                                .into_inner()
-                                .statements
-                                .into_iter()
-                                .map(|stmt| stmt.into_inner())
-                                .collect(),
-                        )
+                        Self::splice_block(then_block)
                     } else if let Some(block) = else_block {
-                        StatementTransform::Many(
-                            block
-                                .into_inner()
-                                .statements
-                                .into_iter()
-                                .map(|stmt| stmt.into_inner())
-                                .collect(),
-                        )
+                        Self::splice_block(block)
                     } else {
                         StatementTransform::None
                     }
@@ -425,6 +470,27 @@ impl Reoptimizer {
         }
     }
 
+    /// Moves the statements of a branch into the enclosing block, unless a `defer` in it
+    /// has to keep running when the branch ends
+    fn splice_block(block: Node<Block>) -> StatementTransform {
+        if block
+            .as_ref()
+            .statements
+            .iter()
+            .any(|stmt| matches!(stmt.as_ref(), Statement::Defer(_)))
+        {
+            return StatementTransform::Single(Box::new(Statement::Block(block)));
+        }
+        StatementTransform::Many(
+            block
+                .into_inner()
+                .statements
+                .into_iter()
+                .map(|stmt| stmt.into_inner())
+                .collect(),
+        )
+    }
+
     fn remove_dead_statements(&self, block: &mut Block) {
         let mut pruned = Vec::with_capacity(block.statements.len());
         let mut terminated = false;
@@ -435,7 +501,10 @@ impl Reoptimizer {
             }
             terminated = matches!(
                 stmt.as_ref(),
-                Statement::Return(_) | Statement::Break | Statement::Continue
+                Statement::Return(_)
+                    | Statement::Raise(_)
+                    | Statement::Break { .. }
+                    | Statement::Continue { .. }
             );
             pruned.push(stmt);
         }
@@ -460,8 +529,26 @@ impl Reoptimizer {
                 }
                 Statement::While { body, .. }
                 | Statement::For { body, .. }
+                | Statement::With { body, .. }
                 | Statement::Block(body) => self.remove_dead_statements(body.as_mut()),
-                // Exception handling (try/except/finally/raise) removed
+                Statement::Try {
+                    body,
+                    handlers,
+                    finally_block,
+                } => {
+                    self.remove_dead_statements(body.as_mut());
+                    for handler in handlers {
+                        self.remove_dead_statements(handler.as_mut().body.as_mut());
+                    }
+                    if let Some(block) = finally_block {
+                        self.remove_dead_statements(block.as_mut());
+                    }
+                }
+                Statement::Let { expr, .. } | Statement::Expr(expr) => {
+                    if let Expr::Loop { body, .. } = expr.as_mut() {
+                        self.remove_dead_statements(body.as_mut());
+                    }
+                }
                 _ => {}
             }
         }
@@ -494,11 +581,32 @@ impl Reoptimizer {
                     }
                     flattened.push(stmt);
                 }
-                Statement::While { body, .. } | Statement::For { body, .. } => {
+                Statement::While { body, .. }
+                | Statement::For { body, .. }
+                | Statement::With { body, .. } => {
+                    self.prune_empty_blocks(body.as_mut());
+                    flattened.push(stmt);
+                }
+                Statement::Try {
+                    body,
+                    handlers,
+                    finally_block,
+                } => {
                     self.prune_empty_blocks(body.as_mut());
+                    for handler in handlers {
+                        self.prune_empty_blocks(handler.as_mut().body.as_mut());
+                    }
+                    if let Some(block) = finally_block {
+                        self.prune_empty_blocks(block.as_mut());
+                    }
+                    flattened.push(stmt);
+                }
+                Statement::Let { expr, .. } | Statement::Expr(expr) => {
+                    if let Expr::Loop { body, .. } = expr.as_mut() {
+                        self.prune_empty_blocks(body.as_mut());
+                    }
                     flattened.push(stmt);
                 }
-                // Exception handling (try/except/finally/raise) removed
                 _ => flattened.push(stmt),
             }
         }
@@ -8,7 +8,7 @@ impl ConstantPropagator {
     /// Analyze expression to extract constant values
     pub fn extract_constants(&self, expr: &Expr) -> Vec<Option<RuntimeConstant>> {
         match expr {
-            Expr::Call { args, .. } => args
+            Expr::Call { args, kwargs, .. } if kwargs.is_empty() => args
                 .iter()
                 .map(|arg| self.extract_constant_from_expr(arg.as_ref()))
                 .collect(),
@@ -27,7 +27,13 @@ impl ConstantPropagator {
         match lit {
             Literal::Bool(b) => Some(RuntimeConstant::Bool(*b)),
             Literal::Number(n) => {
-                if n.value.fract() == 0.0 {
+                if let Some(integer) = n.integer {
+                    if let Ok(value) = i32::try_from(integer) {
+                        Some(RuntimeConstant::I32(value))
+                    } else {
+                        Some(RuntimeConstant::I64(integer as i64))
+                    }
+                } else if n.value.fract() == 0.0 {
                     // Integer
                     if n.value >= i32::MIN as f64 && n.value <= i32::MAX as f64 {
                         Some(RuntimeConstant::I32(n.value as i32))
@@ -39,7 +45,7 @@ impl ConstantPropagator {
                     Some(RuntimeConstant::from_f64(n.value))
                 }
             }
-            Literal::String(s) => Some(RuntimeConstant::Str(s.clone())),
+            Literal::String(s) => Some(RuntimeConstant::Str(s.value.clone())),
             _ => None,
         }
     }
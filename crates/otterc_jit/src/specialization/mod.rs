@@ -31,9 +31,13 @@ impl From<FfiType> for RuntimeType {
         match ty {
             FfiType::Unit => RuntimeType::Unit,
             FfiType::Bool => RuntimeType::Bool,
-            FfiType::I32 => RuntimeType::I32,
-            FfiType::I64 => RuntimeType::I64,
-            FfiType::F64 => RuntimeType::F64,
+            // Narrow integers are specialized as the machine word they are
+            // passed in.
+            FfiType::I8 | FfiType::I16 | FfiType::I32 | FfiType::U8 | FfiType::U16 => {
+                RuntimeType::I32
+            }
+            FfiType::I64 | FfiType::U32 | FfiType::U64 => RuntimeType::I64,
+            FfiType::F32 | FfiType::F64 => RuntimeType::F64,
             FfiType::Str => RuntimeType::Str,
             FfiType::Opaque
             | FfiType::List
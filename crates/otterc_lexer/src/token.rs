@@ -42,7 +42,12 @@ pub enum TokenKind {
     // Literals
     Number(String),
     StringLiteral(String),
-    FString(String), // Raw f-string content like "π ≈ {result}"
+    MultilineString(String),    // `"""..."""`, may span lines
+    RawString(String),          // `r"..."`, backslashes kept as written
+    RawMultilineString(String), // `r"""..."""`
+    ByteString(Vec<u8>),        // `b"..."`
+    FString(String),            // Raw f-string content like "π ≈ {result}"
+    MultilineFString(String),   // `f"""..."""`
     Bool(bool),
 
     // Structural
@@ -58,9 +63,11 @@ pub enum TokenKind {
     RBracket,
     Comma,
     Dot,
+    At,
 
     // Operators
     Arrow,
+    FatArrow,
     Equals,
     EqEq,
     Neq,
@@ -76,12 +83,24 @@ pub enum TokenKind {
     Pipe,
     Amp,
     Bang,
+    Question,
+    Caret,
+    Tilde,
+    StarStar,
+    SlashSlash,
+    Shl,
 
     // Assignment operators
     PlusEq,
     MinusEq,
     StarEq,
     SlashEq,
+    AmpEq,
+    PipeEq,
+    CaretEq,
+    ShlEq,
+    StarStarEq,
+    SlashSlashEq,
 
     // Range operator
     DoubleDot,
@@ -150,6 +169,26 @@ impl Hash for TokenKind {
                 203u16.hash(state);
                 value.hash(state);
             }
+            TokenKind::MultilineString(value) => {
+                204u16.hash(state);
+                value.hash(state);
+            }
+            TokenKind::RawString(value) => {
+                205u16.hash(state);
+                value.hash(state);
+            }
+            TokenKind::RawMultilineString(value) => {
+                206u16.hash(state);
+                value.hash(state);
+            }
+            TokenKind::ByteString(bytes) => {
+                207u16.hash(state);
+                bytes.hash(state);
+            }
+            TokenKind::MultilineFString(content) => {
+                208u16.hash(state);
+                content.hash(state);
+            }
 
             // Structural tokens - use their ASCII values for consistency
             TokenKind::Colon => b':'.hash(state),
@@ -164,9 +203,11 @@ impl Hash for TokenKind {
             TokenKind::RBracket => b']'.hash(state),
             TokenKind::Comma => b','.hash(state),
             TokenKind::Dot => b'.'.hash(state),
+            TokenKind::At => b'@'.hash(state),
 
             // Operators
             TokenKind::Arrow => 400u16.hash(state),
+            TokenKind::FatArrow => 405u16.hash(state),
             TokenKind::Equals => b'='.hash(state),
             TokenKind::EqEq => 401u16.hash(state),
             TokenKind::Neq => 402u16.hash(state),
@@ -182,12 +223,24 @@ impl Hash for TokenKind {
             TokenKind::Pipe => b'|'.hash(state),
             TokenKind::Amp => b'&'.hash(state),
             TokenKind::Bang => b'!'.hash(state),
+            TokenKind::Question => b'?'.hash(state),
+            TokenKind::Caret => b'^'.hash(state),
+            TokenKind::Tilde => b'~'.hash(state),
+            TokenKind::StarStar => 406u16.hash(state),
+            TokenKind::SlashSlash => 407u16.hash(state),
+            TokenKind::Shl => 408u16.hash(state),
 
             // Assignment operators
             TokenKind::PlusEq => 500u16.hash(state),
             TokenKind::MinusEq => 501u16.hash(state),
             TokenKind::StarEq => 502u16.hash(state),
             TokenKind::SlashEq => 503u16.hash(state),
+            TokenKind::AmpEq => 504u16.hash(state),
+            TokenKind::PipeEq => 505u16.hash(state),
+            TokenKind::CaretEq => 506u16.hash(state),
+            TokenKind::ShlEq => 507u16.hash(state),
+            TokenKind::StarStarEq => 508u16.hash(state),
+            TokenKind::SlashSlashEq => 509u16.hash(state),
 
             // Range operator
             TokenKind::DoubleDot => 600u16.hash(state),
@@ -240,7 +293,12 @@ impl TokenKind {
             // Literals
             TokenKind::Number(_) => "number",
             TokenKind::StringLiteral(_) => "string",
+            TokenKind::MultilineString(_) => "multiline_string",
+            TokenKind::RawString(_) => "raw_string",
+            TokenKind::RawMultilineString(_) => "raw_multiline_string",
+            TokenKind::ByteString(_) => "byte_string",
             TokenKind::FString { .. } => "fstring",
+            TokenKind::MultilineFString(_) => "multiline_fstring",
             TokenKind::Bool(_) => "bool",
 
             // Structural
@@ -256,9 +314,11 @@ impl TokenKind {
             TokenKind::RBracket => "]",
             TokenKind::Comma => ",",
             TokenKind::Dot => ".",
+            TokenKind::At => "@",
 
             // Operators
             TokenKind::Arrow => "->",
+            TokenKind::FatArrow => "=>",
             TokenKind::Equals => "=",
             TokenKind::EqEq => "==",
             TokenKind::Neq => "!=",
@@ -274,12 +334,24 @@ impl TokenKind {
             TokenKind::Pipe => "|",
             TokenKind::Amp => "&",
             TokenKind::Bang => "!",
+            TokenKind::Question => "?",
+            TokenKind::Caret => "^",
+            TokenKind::Tilde => "~",
+            TokenKind::StarStar => "**",
+            TokenKind::SlashSlash => "//",
+            TokenKind::Shl => "<<",
 
             // Assignment operators
             TokenKind::PlusEq => "+=",
             TokenKind::MinusEq => "-=",
             TokenKind::StarEq => "*=",
             TokenKind::SlashEq => "/=",
+            TokenKind::AmpEq => "&=",
+            TokenKind::PipeEq => "|=",
+            TokenKind::CaretEq => "^=",
+            TokenKind::ShlEq => "<<=",
+            TokenKind::StarStarEq => "**=",
+            TokenKind::SlashSlashEq => "//=",
 
             // Range operator
             TokenKind::DoubleDot => "..",
@@ -296,7 +368,12 @@ impl fmt::Debug for TokenKind {
             TokenKind::UnicodeIdentifier(name) => write!(f, "UnicodeIdentifier({name})"),
             TokenKind::Number(number) => write!(f, "Number({number})"),
             TokenKind::StringLiteral(value) => write!(f, "StringLiteral(\"{value}\")"),
+            TokenKind::MultilineString(value) => write!(f, "MultilineString(\"{value}\")"),
+            TokenKind::RawString(value) => write!(f, "RawString(\"{value}\")"),
+            TokenKind::RawMultilineString(value) => write!(f, "RawMultilineString(\"{value}\")"),
+            TokenKind::ByteString(bytes) => write!(f, "ByteString({bytes:?})"),
             TokenKind::FString(content) => write!(f, "FString(\"{}\")", content),
+            TokenKind::MultilineFString(content) => write!(f, "MultilineFString(\"{content}\")"),
             TokenKind::Bool(value) => write!(f, "Bool({value})"),
             kind => f.write_str(kind.name()),
         }
@@ -370,7 +447,12 @@ impl Token {
             self.kind,
             TokenKind::Number(_)
                 | TokenKind::StringLiteral(_)
+                | TokenKind::MultilineString(_)
+                | TokenKind::RawString(_)
+                | TokenKind::RawMultilineString(_)
+                | TokenKind::ByteString(_)
                 | TokenKind::FString(_)
+                | TokenKind::MultilineFString(_)
                 | TokenKind::Bool(_)
                 | TokenKind::None
         )
@@ -401,13 +483,26 @@ impl Token {
                 | TokenKind::Is
                 | TokenKind::Not
                 | TokenKind::Arrow
+                | TokenKind::FatArrow
                 | TokenKind::Pipe
                 | TokenKind::Amp
                 | TokenKind::Bang
+                | TokenKind::Question
+                | TokenKind::Caret
+                | TokenKind::Tilde
+                | TokenKind::StarStar
+                | TokenKind::SlashSlash
+                | TokenKind::Shl
                 | TokenKind::PlusEq
                 | TokenKind::MinusEq
                 | TokenKind::StarEq
                 | TokenKind::SlashEq
+                | TokenKind::AmpEq
+                | TokenKind::PipeEq
+                | TokenKind::CaretEq
+                | TokenKind::ShlEq
+                | TokenKind::StarStarEq
+                | TokenKind::SlashSlashEq
                 | TokenKind::DoubleDot
         )
     }
@@ -424,6 +519,7 @@ impl Token {
                 | TokenKind::Colon
                 | TokenKind::Comma
                 | TokenKind::Dot
+                | TokenKind::At
         )
     }
 }
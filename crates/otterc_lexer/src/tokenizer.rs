@@ -25,6 +25,13 @@ pub enum LexerError {
         column: usize,
         span: Span,
     },
+    #[error("invalid number literal `{text}` (line {line}, column {column})")]
+    InvalidNumber {
+        text: String,
+        line: usize,
+        column: usize,
+        span: Span,
+    },
     #[error("unexpected character `{ch}` (line {line}, column {column})")]
     UnexpectedCharacter {
         ch: char,
@@ -68,6 +75,16 @@ impl LexerError {
             )
             .with_suggestion("Add a closing quote (\") to terminate the string")
             .with_help("String literals must be enclosed in double quotes."),
+            LexerError::InvalidNumber { span, .. } => Diagnostic::new(
+                DiagnosticSeverity::Error,
+                source_id,
+                *span,
+                self.to_string(),
+            )
+            .with_suggestion("Use only the digits of the literal's base after `0x`, `0o` or `0b`")
+            .with_help(
+                "Hex literals use 0-9 and a-f, octal literals 0-7, and binary literals 0 and 1.",
+            ),
             LexerError::UnexpectedCharacter { span, ch, .. } => {
                 let mut diag = Diagnostic::new(
                     DiagnosticSeverity::Error,
@@ -84,11 +101,6 @@ impl LexerError {
                         );
                     }
                     '~' => diag = diag.with_suggestion("Did you mean tilde (~) or negation (not)?"),
-                    '@' => {
-                        diag = diag.with_suggestion(
-                            "Did you mean the at symbol (@) or member access (.)?",
-                        );
-                    }
                     _ => {
                         if ch.is_ascii_punctuation() {
                             diag = diag.with_suggestion("Check for typos or invalid characters");
@@ -104,6 +116,18 @@ impl LexerError {
 
 pub type LexResult<T> = Result<T, Vec<LexerError>>;
 
+/// Escape sequences recognised in the body of a string literal
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Escapes {
+    Text,
+    /// `r"..."` keeps backslashes as written
+    Raw,
+    /// F-strings also escape braces
+    FString,
+    /// Byte strings also accept `\xNN`
+    Bytes,
+}
+
 // Optimized lexer state machine
 struct LexerState {
     tokens: Vec<Token>,
@@ -425,34 +449,79 @@ impl LexerState {
                     self.advance(1);
                 }
             },
-            b'*' => {
-                if self.peek_char(1) == Some(b'=') {
+            b'*' => match (self.peek_char(1), self.peek_char(2)) {
+                (Some(b'*'), Some(b'=')) => {
+                    self.emit_token(TokenKind::StarStarEq, self.offset, 3);
+                    self.advance(3);
+                }
+                (Some(b'*'), _) => {
+                    self.emit_token(TokenKind::StarStar, self.offset, 2);
+                    self.advance(2);
+                }
+                (Some(b'='), _) => {
                     self.emit_token(TokenKind::StarEq, self.offset, 2);
                     self.advance(2);
-                } else {
+                }
+                _ => {
                     self.emit_token(TokenKind::Star, self.offset, 1);
                     self.advance(1);
                 }
-            }
-            b'/' => {
-                if self.peek_char(1) == Some(b'=') {
+            },
+            b'/' => match (self.peek_char(1), self.peek_char(2)) {
+                (Some(b'/'), Some(b'=')) => {
+                    self.emit_token(TokenKind::SlashSlashEq, self.offset, 3);
+                    self.advance(3);
+                }
+                (Some(b'/'), _) => {
+                    self.emit_token(TokenKind::SlashSlash, self.offset, 2);
+                    self.advance(2);
+                }
+                (Some(b'='), _) => {
                     self.emit_token(TokenKind::SlashEq, self.offset, 2);
                     self.advance(2);
-                } else {
+                }
+                _ => {
                     self.emit_token(TokenKind::Slash, self.offset, 1);
                     self.advance(1);
                 }
-            }
+            },
             b'%' => {
                 self.emit_token(TokenKind::Percent, self.offset, 1);
                 self.advance(1);
             }
             b'|' => {
-                self.emit_token(TokenKind::Pipe, self.offset, 1);
-                self.advance(1);
+                if self.peek_char(1) == Some(b'=') {
+                    self.emit_token(TokenKind::PipeEq, self.offset, 2);
+                    self.advance(2);
+                } else {
+                    self.emit_token(TokenKind::Pipe, self.offset, 1);
+                    self.advance(1);
+                }
             }
             b'&' => {
-                self.emit_token(TokenKind::Amp, self.offset, 1);
+                if self.peek_char(1) == Some(b'=') {
+                    self.emit_token(TokenKind::AmpEq, self.offset, 2);
+                    self.advance(2);
+                } else {
+                    self.emit_token(TokenKind::Amp, self.offset, 1);
+                    self.advance(1);
+                }
+            }
+            b'^' => {
+                if self.peek_char(1) == Some(b'=') {
+                    self.emit_token(TokenKind::CaretEq, self.offset, 2);
+                    self.advance(2);
+                } else {
+                    self.emit_token(TokenKind::Caret, self.offset, 1);
+                    self.advance(1);
+                }
+            }
+            b'~' => {
+                self.emit_token(TokenKind::Tilde, self.offset, 1);
+                self.advance(1);
+            }
+            b'@' => {
+                self.emit_token(TokenKind::At, self.offset, 1);
                 self.advance(1);
             }
             b'!' => {
@@ -464,24 +533,44 @@ impl LexerState {
                     self.advance(1);
                 }
             }
-            b'=' => {
-                if self.peek_char(1) == Some(b'=') {
+            b'?' => {
+                self.emit_token(TokenKind::Question, self.offset, 1);
+                self.advance(1);
+            }
+            b'=' => match self.peek_char(1) {
+                Some(b'=') => {
                     self.emit_token(TokenKind::EqEq, self.offset, 2);
                     self.advance(2);
-                } else {
+                }
+                Some(b'>') => {
+                    self.emit_token(TokenKind::FatArrow, self.offset, 2);
+                    self.advance(2);
+                }
+                _ => {
                     self.emit_token(TokenKind::Equals, self.offset, 1);
                     self.advance(1);
                 }
-            }
-            b'<' => {
-                if self.peek_char(1) == Some(b'=') {
+            },
+            b'<' => match (self.peek_char(1), self.peek_char(2)) {
+                (Some(b'<'), Some(b'=')) => {
+                    self.emit_token(TokenKind::ShlEq, self.offset, 3);
+                    self.advance(3);
+                }
+                (Some(b'<'), _) => {
+                    self.emit_token(TokenKind::Shl, self.offset, 2);
+                    self.advance(2);
+                }
+                (Some(b'='), _) => {
                     self.emit_token(TokenKind::LtEq, self.offset, 2);
                     self.advance(2);
-                } else {
+                }
+                _ => {
                     self.emit_token(TokenKind::Lt, self.offset, 1);
                     self.advance(1);
                 }
-            }
+            },
+            // `>>` stays two `>` tokens so nested generics such as `List<List<int>>` close
+            // normally; the expression parser joins adjacent ones into a right shift.
             b'>' => {
                 if self.peek_char(1) == Some(b'=') {
                     self.emit_token(TokenKind::GtEq, self.offset, 2);
@@ -491,21 +580,10 @@ impl LexerState {
                     self.advance(1);
                 }
             }
-            b'"' => {
-                // Check for triple-quoted string (multi-line)
-                if self.peek_char(1) == Some(b'"') && self.peek_char(2) == Some(b'"') {
-                    self.tokenize_multiline_string();
-                } else {
-                    self.tokenize_string();
-                }
-            }
-            b'f' => {
-                // Check for f-string before treating as regular identifier
-                if self.peek_char(1) == Some(b'"') {
-                    self.tokenize_fstring();
-                } else {
-                    self.tokenize_identifier_or_keyword();
-                }
+            b'"' => self.tokenize_string(0),
+            // A prefix letter directly before a quote starts a raw, byte or f-string
+            b'r' | b'b' | b'f' if self.peek_char(1) == Some(b'"') => {
+                self.tokenize_string(1);
             }
             ch if ch.is_ascii_digit() => {
                 self.tokenize_number();
@@ -530,190 +608,203 @@ impl LexerState {
         }
     }
 
-    fn tokenize_string(&mut self) {
-        let start = self.offset;
-        self.advance(1); // Skip opening quote
+    /// Tokenize a string literal, skipping `prefix_len` prefix letters before the opening quote
+    fn tokenize_string(&mut self, prefix_len: usize) {
+        let prefix = if prefix_len == 0 {
+            None
+        } else {
+            self.current_char()
+        };
+        let quote = self.offset + prefix_len;
+        let multiline =
+            self.source.get(quote + 1) == Some(&b'"') && self.source.get(quote + 2) == Some(&b'"');
+        // Byte strings are single-line; `b"""` is the empty byte string followed by a quote
+        let multiline = multiline && prefix != Some(b'b');
+        let escapes = match prefix {
+            Some(b'r') => Escapes::Raw,
+            Some(b'b') => Escapes::Bytes,
+            Some(b'f') => Escapes::FString,
+            _ => Escapes::Text,
+        };
 
-        let mut result = String::new();
+        let start = self.offset;
+        self.advance(prefix_len);
+        let Some(body) = self.scan_string_body(start, multiline, escapes) else {
+            return;
+        };
+        let span = Span::new(start, self.offset);
+        let text = || String::from_utf8_lossy(&body).into_owned();
+        let kind = match (prefix, multiline) {
+            (Some(b'b'), _) => TokenKind::ByteString(body),
+            (Some(b'r'), false) => TokenKind::RawString(text()),
+            (Some(b'r'), true) => TokenKind::RawMultilineString(text()),
+            (Some(b'f'), false) => TokenKind::FString(text()),
+            (Some(b'f'), true) => TokenKind::MultilineFString(text()),
+            (_, false) => TokenKind::StringLiteral(text()),
+            (_, true) => TokenKind::MultilineString(text()),
+        };
+        self.tokens.push(Token::new(kind, span));
+    }
 
+    /// Scan from an opening quote (or `"""`) past the closing one, returning the bytes in
+    /// between with escapes resolved. Inside triple quotes newlines and leading spaces are text,
+    /// so the body does not take part in indentation. Reports an unterminated string starting at
+    /// `start` and returns `None` when the line (or, for triple quotes, the file) ends first.
+    fn scan_string_body(
+        &mut self,
+        start: usize,
+        multiline: bool,
+        escapes: Escapes,
+    ) -> Option<Vec<u8>> {
+        let quotes = if multiline { 3 } else { 1 };
+        self.advance(quotes);
+
+        let mut body = Vec::new();
         while let Some(ch) = self.current_char() {
             if self.current_newline_len().is_some() {
-                let span = self.create_span(start, self.offset - start);
-                self.emit_error(LexerError::UnterminatedString {
-                    line: self.line,
-                    column: self.column,
-                    span,
-                });
-                return;
+                if !multiline {
+                    break;
+                }
+                // `\r\n` inside the string is kept as `\n`
+                body.push(b'\n');
+                self.advance(1);
+                continue;
             }
 
             match ch {
-                b'"' => {
-                    let span = Span::new(start, self.offset + 1);
-                    self.tokens
-                        .push(Token::new(TokenKind::StringLiteral(result), span));
-                    self.advance(1);
-                    return;
+                b'"' if !multiline
+                    || (self.peek_char(1) == Some(b'"') && self.peek_char(2) == Some(b'"')) =>
+                {
+                    self.advance(quotes);
+                    return Some(body);
                 }
-                b'\\' => {
-                    // Escape sequence
+                b'\\' if escapes != Escapes::Raw => {
                     self.advance(1);
-                    if let Some(escaped) = self.current_char() {
-                        let escaped_char = match escaped {
-                            b'n' => '\n',
-                            b't' => '\t',
-                            b'r' => '\r',
-                            b'\\' => '\\',
-                            b'"' => '"',
-                            b'\'' => '\'',
-                            _ => escaped as char, // Unknown escape, keep as-is
-                        };
-                        result.push(escaped_char);
-                        self.advance(1);
-                    }
+                    self.scan_escape(&mut body, escapes);
                 }
                 _ => {
-                    result.push(ch as char);
+                    body.push(ch);
                     self.advance(1);
                 }
             }
         }
 
-        // Unterminated string at EOF
         let span = self.create_span(start, self.offset - start);
         self.emit_error(LexerError::UnterminatedString {
             line: self.line,
             column: self.column,
             span,
         });
+        None
     }
 
-    fn tokenize_multiline_string(&mut self) {
-        let start = self.offset;
-        self.advance(3); // Skip opening """
-
-        let mut result = String::new();
-
-        while let Some(ch) = self.current_char() {
-            if self.current_newline_len().is_some() {
-                // Actual newline in multi-line string
-                result.push('\n');
-                self.advance(1);
-                continue;
-            }
-
-            match ch {
-                b'"' => {
-                    // Check if this is the closing """
-                    if self.peek_char(1) == Some(b'"') && self.peek_char(2) == Some(b'"') {
-                        let span = Span::new(start, self.offset + 3);
-                        self.tokens
-                            .push(Token::new(TokenKind::StringLiteral(result), span));
-                        self.advance(3); // Skip closing """
-                        return;
-                    } else {
-                        // Just a regular " in the string
-                        result.push('"');
-                        self.advance(1);
-                    }
-                }
-                b'\\' => {
-                    // Escape sequence (same as regular strings)
-                    self.advance(1);
-                    if let Some(escaped) = self.current_char() {
-                        let escaped_char = match escaped {
-                            b'n' => '\n',
-                            b't' => '\t',
-                            b'r' => '\r',
-                            b'\\' => '\\',
-                            b'"' => '"',
-                            b'\'' => '\'',
-                            _ => escaped as char, // Unknown escape, keep as-is
-                        };
-                        result.push(escaped_char);
-                        self.advance(1);
+    /// Resolve the escape sequence following a backslash
+    fn scan_escape(&mut self, body: &mut Vec<u8>, escapes: Escapes) {
+        let Some(escaped) = self.current_char() else {
+            return;
+        };
+        self.advance(1);
+        match escaped {
+            b'n' => body.push(b'\n'),
+            b't' => body.push(b'\t'),
+            b'r' => body.push(b'\r'),
+            // An escaped brace is doubled so it stays text when the f-string is split into parts
+            b'{' | b'}' if escapes == Escapes::FString => body.extend([escaped, escaped]),
+            b'x' if escapes == Escapes::Bytes => {
+                let digits = [self.current_char(), self.peek_char(1)];
+                let value = match digits {
+                    [Some(high), Some(low)] => std::str::from_utf8(&[high, low])
+                        .ok()
+                        .and_then(|hex| u8::from_str_radix(hex, 16).ok()),
+                    _ => None,
+                };
+                match value {
+                    Some(value) => {
+                        body.push(value);
+                        self.advance(2);
                     }
-                }
-                _ => {
-                    result.push(ch as char);
-                    self.advance(1);
+                    None => body.push(b'x'),
                 }
             }
+            // `\\`, `\"`, `\'` and unknown escapes keep the escaped character
+            _ => body.push(escaped),
         }
-
-        // Unterminated multi-line string at EOF
-        let span = self.create_span(start, self.offset - start);
-        self.emit_error(LexerError::UnterminatedString {
-            line: self.line,
-            column: self.column,
-            span,
-        });
     }
 
-    fn tokenize_fstring(&mut self) {
+    fn tokenize_number(&mut self) {
         let start = self.offset;
-        self.advance(2); // Skip f"
+        let radix = match (self.current_char(), self.peek_char(1)) {
+            (Some(b'0'), Some(b'x' | b'X')) => 16,
+            (Some(b'0'), Some(b'o' | b'O')) => 8,
+            (Some(b'0'), Some(b'b' | b'B')) => 2,
+            _ => 10,
+        };
 
-        let mut result = String::new();
+        if radix != 10 {
+            self.advance(2); // Skip the base prefix
 
-        while let Some(ch) = self.current_char() {
-            if self.current_newline_len().is_some() {
+            // Letters are consumed too, so `0b102` or `0xG` is reported as one bad literal
+            let digits_start = self.offset;
+            let mut valid = true;
+            while let Some(ch) = self.current_char() {
+                if ch == b'_' {
+                    self.advance(1);
+                } else if ch.is_ascii_alphanumeric() {
+                    valid &= char::from(ch).is_digit(radix);
+                    self.advance(1);
+                } else {
+                    break;
+                }
+            }
+
+            let has_digits = self.source[digits_start..self.offset]
+                .iter()
+                .any(|&ch| ch != b'_');
+            if !valid || !has_digits {
+                let text = String::from_utf8_lossy(&self.source[start..self.offset]).into_owned();
                 let span = self.create_span(start, self.offset - start);
-                self.emit_error(LexerError::UnterminatedString {
+                self.emit_error(LexerError::InvalidNumber {
+                    text,
                     line: self.line,
                     column: self.column,
                     span,
                 });
                 return;
             }
+        } else {
+            self.skip_decimal_digits();
+
+            // Parse decimal part
+            if let Some(b'.') = self.current_char()
+                && let Some(next) = self.peek_char(1)
+                && next.is_ascii_digit()
+            {
+                self.advance(1); // Skip decimal point
+                self.skip_decimal_digits();
+            }
 
-            match ch {
-                b'"' => {
-                    let span = Span::new(start, self.offset + 1);
-                    self.tokens
-                        .push(Token::new(TokenKind::FString(result), span));
-                    self.advance(1);
-                    return;
-                }
-                b'\\' => {
-                    // Escape sequence
-                    self.advance(1);
-                    if let Some(escaped) = self.current_char() {
-                        let escaped_char = match escaped {
-                            b'n' => '\n',
-                            b't' => '\t',
-                            b'r' => '\r',
-                            b'\\' => '\\',
-                            b'"' => '"',
-                            b'\'' => '\'',
-                            b'{' => '{',
-                            b'}' => '}',
-                            _ => escaped as char, // Unknown escape, keep as-is
-                        };
-                        result.push(escaped_char);
-                        self.advance(1);
-                    }
-                }
-                _ => {
-                    result.push(ch as char);
-                    self.advance(1);
+            // Parse exponent, which needs at least one digit after the optional sign
+            if let Some(b'e' | b'E') = self.current_char() {
+                let sign_len = usize::from(matches!(self.peek_char(1), Some(b'+' | b'-')));
+                if self
+                    .peek_char(1 + sign_len)
+                    .is_some_and(|ch| ch.is_ascii_digit())
+                {
+                    self.advance(1 + sign_len);
+                    self.skip_decimal_digits();
                 }
             }
         }
 
-        // Unterminated fstring at EOF
-        let span = self.create_span(start, self.offset - start);
-        self.emit_error(LexerError::UnterminatedString {
-            line: self.line,
-            column: self.column,
-            span,
-        });
+        let value = unsafe { std::str::from_utf8_unchecked(&self.source[start..self.offset]) };
+        self.emit_token(
+            TokenKind::Number(value.to_string()),
+            start,
+            self.offset - start,
+        );
     }
 
-    fn tokenize_number(&mut self) {
-        let start = self.offset;
-
-        // Parse integer part
+    fn skip_decimal_digits(&mut self) {
         while let Some(ch) = self.current_char() {
             if ch.is_ascii_digit() || ch == b'_' {
                 self.advance(1);
@@ -721,29 +812,6 @@ impl LexerState {
                 break;
             }
         }
-
-        // Parse decimal part
-        if let Some(b'.') = self.current_char()
-            && let Some(next) = self.peek_char(1)
-            && next.is_ascii_digit()
-        {
-            self.advance(1); // Skip decimal point
-
-            while let Some(ch) = self.current_char() {
-                if ch.is_ascii_digit() || ch == b'_' {
-                    self.advance(1);
-                } else {
-                    break;
-                }
-            }
-        }
-
-        let value = unsafe { std::str::from_utf8_unchecked(&self.source[start..self.offset]) };
-        self.emit_token(
-            TokenKind::Number(value.to_string()),
-            start,
-            self.offset - start,
-        );
     }
 
     fn tokenize_identifier_or_keyword(&mut self) {
@@ -885,4 +953,97 @@ mod tests {
 
         assert_eq!(newline_span, 2);
     }
+
+    #[test]
+    fn lexes_bitwise_and_power_operators() {
+        let kinds = token_kinds("a ** b // c << d >> e & f | g ^ ~h\n");
+        let operators: Vec<_> = kinds
+            .into_iter()
+            .filter(|kind| !matches!(kind, TokenKind::Identifier(_)))
+            .collect();
+
+        assert_eq!(
+            operators,
+            vec![
+                TokenKind::StarStar,
+                TokenKind::SlashSlash,
+                TokenKind::Shl,
+                TokenKind::Gt,
+                TokenKind::Gt,
+                TokenKind::Amp,
+                TokenKind::Pipe,
+                TokenKind::Caret,
+                TokenKind::Tilde,
+                TokenKind::Newline,
+                TokenKind::Newline,
+                TokenKind::Eof,
+            ]
+        );
+
+        let kinds = token_kinds("x **= 2\nx //= 2\nx <<= 2\nx &= 1\nx |= 1\nx ^= 1\n");
+        assert!(kinds.contains(&TokenKind::StarStarEq));
+        assert!(kinds.contains(&TokenKind::SlashSlashEq));
+        assert!(kinds.contains(&TokenKind::ShlEq));
+        assert!(kinds.contains(&TokenKind::AmpEq));
+        assert!(kinds.contains(&TokenKind::PipeEq));
+        assert!(kinds.contains(&TokenKind::CaretEq));
+    }
+
+    #[test]
+    fn lexes_raw_and_byte_strings() {
+        let kinds = token_kinds("r\"\\d+\\.\" b\"ok\\n\\xff\" br\n");
+        assert_eq!(
+            &kinds[..3],
+            &[
+                TokenKind::RawString("\\d+\\.".to_string()),
+                TokenKind::ByteString(vec![b'o', b'k', b'\n', 0xff]),
+                TokenKind::Identifier("br".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn multiline_strings_ignore_indentation() {
+        let source = "fn main():\n    let q = \"\"\"\nSELECT *\n      FROM t\n\"\"\"\n    let r = r\"\"\"a\\\"b\"\"\"\n";
+        let kinds = token_kinds(source);
+
+        assert!(kinds.contains(&TokenKind::MultilineString(
+            "\nSELECT *\n      FROM t\n".to_string()
+        )));
+        assert!(kinds.contains(&TokenKind::RawMultilineString("a\\\"b".to_string())));
+        let indents = kinds
+            .iter()
+            .filter(|kind| matches!(kind, TokenKind::Indent))
+            .count();
+        assert_eq!(indents, 1);
+    }
+
+    #[test]
+    fn multiline_fstrings_keep_interpolation_and_escaped_braces() {
+        let kinds = token_kinds("f\"\"\"π {x}\n\\{y\\}\"\"\"\n");
+        assert_eq!(
+            kinds[0],
+            TokenKind::MultilineFString("π {x}\n{{y}}".to_string())
+        );
+    }
+
+    #[test]
+    fn lexes_number_bases_separators_and_exponents() {
+        let kinds = token_kinds("0xFF_FF 0o17 0b1010 1_000 1.5e3 2E-8 3e\n");
+        let numbers: Vec<_> = kinds
+            .into_iter()
+            .filter_map(|kind| match kind {
+                TokenKind::Number(text) => Some(text),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            numbers,
+            vec!["0xFF_FF", "0o17", "0b1010", "1_000", "1.5e3", "2E-8", "3"]
+        );
+
+        let errors = tokenize("let x = 0b102\nlet y = 0x\n").expect_err("bad digits");
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(&errors[0], LexerError::InvalidNumber { text, .. } if text == "0b102"));
+    }
 }
@@ -118,13 +118,21 @@ impl ModuleLoader {
                         exports.add_function(function.as_ref().name.clone());
                     }
                 }
-                Statement::Let { name, public, .. } => {
+                Statement::Let {
+                    pattern, public, ..
+                } => {
                     if *public {
-                        exports.add_constant(name.as_ref().clone());
+                        for name in pattern.as_ref().bindings() {
+                            exports.add_constant(name.to_string());
+                        }
                     }
                 }
+                Statement::Const { name, public, .. } if *public => {
+                    exports.add_constant(name.clone());
+                }
                 Statement::Struct { name, public, .. }
                 | Statement::Enum { name, public, .. }
+                | Statement::Trait { name, public, .. }
                 | Statement::TypeAlias { name, public, .. } => {
                     if *public {
                         exports.add_type(name.clone());
@@ -23,6 +23,7 @@ const VIRTUAL_STDLIB_MODULES: &[&str] = &[
     "test",
     "enums",
     "exceptions",
+    "string",
     "strings",
     "builtins",
 ];
@@ -3,8 +3,9 @@ use chumsky::Stream;
 use chumsky::prelude::*;
 
 use otterc_ast::nodes::{
-    BinaryOp, Block, EnumVariant, Expr, FStringPart, Function, Literal, MatchArm, Node,
-    NumberLiteral, Param, Pattern, Program, Statement, Type, UnaryOp, UseImport,
+    BinaryOp, Block, EnumVariant, ExceptHandler, Expr, FStringPart, Function, Literal, MatchArm,
+    Node, NumberLiteral, Param, Pattern, Program, Statement, StringLiteral, StringStyle, Type,
+    UnaryOp, UseImport,
 };
 
 use otterc_lexer::token::{Token, TokenKind};
@@ -109,26 +110,93 @@ fn identifier_or_keyword_parser() -> impl Parser<TokenKind, String, Error = Simp
 
 fn type_parser() -> impl Parser<TokenKind, Node<Type>, Error = Simple<TokenKind>> {
     recursive(|ty| {
-        identifier_parser()
-            .then(
-                ty.separated_by(just(TokenKind::Comma))
+        // Function types: fn(<param types>) -> <return type>
+        let function_type = just(TokenKind::Fn)
+            .ignore_then(
+                ty.clone()
+                    .separated_by(just(TokenKind::Comma))
                     .allow_trailing()
-                    .delimited_by(just(TokenKind::Lt), just(TokenKind::Gt))
-                    .or_not(),
+                    .delimited_by(just(TokenKind::LParen), just(TokenKind::RParen)),
             )
-            .map_with_span(|(base, args), span| {
+            .then(just(TokenKind::Arrow).ignore_then(ty.clone()).or_not())
+            .map_with_span(|(params, ret), span| {
                 Node::new(
-                    match args {
-                        Some(args) => Type::Generic { base, args },
-                        None => Type::Simple(base),
+                    Type::Function {
+                        params,
+                        ret: ret.map(Box::new),
                     },
                     span,
                 )
-            })
+            });
+
+        // Trait objects: dyn <trait name>
+        let dyn_type = just(TokenKind::Identifier("dyn".to_string()))
+            .ignore_then(identifier_parser())
+            .map_with_span(|name, span| Node::new(Type::Dyn(name), span));
+
+        // Tuple types: (<type>, <type>, ...); a single parenthesized type is just grouping
+        let tuple_type = ty
+            .clone()
+            .then(
+                just(TokenKind::Comma)
+                    .ignore_then(
+                        ty.clone()
+                            .separated_by(just(TokenKind::Comma))
+                            .allow_trailing(),
+                    )
+                    .or_not(),
+            )
+            .delimited_by(just(TokenKind::LParen), just(TokenKind::RParen))
+            .map_with_span(|(first, rest), span| match rest {
+                Some(rest) => Node::new(
+                    Type::Tuple(std::iter::once(first).chain(rest).collect()),
+                    span,
+                ),
+                None => first,
+            });
+
+        dyn_type
+            .or(identifier_parser()
+                .then(
+                    ty.separated_by(just(TokenKind::Comma))
+                        .allow_trailing()
+                        .delimited_by(just(TokenKind::Lt), just(TokenKind::Gt))
+                        .or_not(),
+                )
+                .map_with_span(|(base, args), span| {
+                    Node::new(
+                        match args {
+                            Some(args) => Type::Generic { base, args },
+                            None => Type::Simple(base),
+                        },
+                        span,
+                    )
+                }))
+            .or(function_type)
+            .or(tuple_type)
     })
 }
 
-fn parse_fstring(content: String, span: impl Into<Span>) -> Node<Expr> {
+/// Methods automatically get `self: Self` as first parameter if not present
+fn ensure_self_param(name: &str, mut params: Vec<Node<Param>>, start: usize) -> Vec<Node<Param>> {
+    if params.is_empty() || params[0].as_ref().name.as_ref() != "self" {
+        let self_type = Type::Simple("Self".to_string());
+        let self_span = Span::new(start + name.len() + 1, start + name.len() + 5);
+        let self_type_span = Span::new(self_span.start(), self_span.start());
+        let self_param = Node::new(
+            Param::new(
+                Node::new("self".to_string(), self_span),
+                Some(Node::new(self_type, self_type_span)),
+                None,
+            ),
+            self_span,
+        );
+        params.insert(0, self_param);
+    }
+    params
+}
+
+fn parse_fstring(content: String, multiline: bool, span: impl Into<Span>) -> Node<Expr> {
     use chumsky::Parser;
 
     // Parse f-string by splitting on braces and parsing expressions
@@ -243,39 +311,45 @@ fn parse_fstring(content: String, span: impl Into<Span>) -> Node<Expr> {
         .all(|part| matches!(part.as_ref(), FStringPart::Text(_)))
         && let Some(FStringPart::Text(text)) = parts.first().map(|p| p.as_ref())
     {
+        let style = if multiline {
+            StringStyle::Multiline
+        } else {
+            StringStyle::Quoted
+        };
         return Node::new(
-            Expr::Literal(Node::new(Literal::String(text.clone()), span)),
+            Expr::Literal(Node::new(
+                Literal::String(StringLiteral::with_style(text.clone(), style)),
+                span,
+            )),
             span,
         );
     }
 
-    Node::new(Expr::FString { parts }, span)
+    Node::new(Expr::FString { parts, multiline }, span)
 }
 
 fn literal_expr_parser() -> impl Parser<TokenKind, Node<Expr>, Error = Simple<TokenKind>> {
-    let string_lit = select! { TokenKind::StringLiteral(value) => Literal::String(value) }
-        .map_with_span(|lit, span: Range<usize>| {
-            let span: Span = span.into();
-            Node::new(Expr::Literal(Node::new(lit, span)), span)
-        })
-        .boxed();
+    let string_lit = select! {
+        TokenKind::StringLiteral(value) => Literal::String(StringLiteral::new(value)),
+        TokenKind::MultilineString(value) => {
+            Literal::String(StringLiteral::with_style(value, StringStyle::Multiline))
+        },
+        TokenKind::RawString(value) => {
+            Literal::String(StringLiteral::with_style(value, StringStyle::Raw))
+        },
+        TokenKind::RawMultilineString(value) => {
+            Literal::String(StringLiteral::with_style(value, StringStyle::RawMultiline))
+        },
+        TokenKind::ByteString(bytes) => Literal::Bytes(bytes),
+    }
+    .map_with_span(|lit, span: Range<usize>| {
+        let span: Span = span.into();
+        Node::new(Expr::Literal(Node::new(lit, span)), span)
+    })
+    .boxed();
     let number_lit = select! { TokenKind::Number(value) => {
-        // Remove underscores from the number
-        let clean_value = value.replace('_', "");
-        let is_float_literal = value.contains('.') || value.contains('e') || value.contains('E');
-        // Check if it contains a decimal point or is an integer
-        if clean_value.contains('.') {
-            NumberLiteral::new(
-                clean_value.parse().unwrap_or_default(),
-                true,
-            )
-        } else {
-            // Parse as integer
-            match clean_value.parse::<i64>() {
-                Ok(int_val) => NumberLiteral::new(int_val as f64, is_float_literal),
-                Err(_) => NumberLiteral::new(0.0, is_float_literal),
-            }
-        }
+        // The lexer only produces well-formed numbers
+        NumberLiteral::parse(&value).unwrap_or_else(|| NumberLiteral::new(0.0, false))
     }}
     .map_with_span(|num_lit, span: Range<usize>| {
         let span: Span = span.into();
@@ -301,8 +375,11 @@ fn literal_expr_parser() -> impl Parser<TokenKind, Node<Expr>, Error = Simple<To
             Node::new(Expr::Literal(Node::new(lit, span)), span)
         })
         .boxed();
-    let fstring_lit =
-        select! { |span| TokenKind::FString(content) => parse_fstring(content, span) }.boxed();
+    let fstring_lit = select! { |span|
+        TokenKind::FString(content) => parse_fstring(content, false, span),
+        TokenKind::MultilineFString(content) => parse_fstring(content, true, span),
+    }
+    .boxed();
     let unit_lit = just(TokenKind::LParen)
         .then(just(TokenKind::RParen))
         .map_with_span(|_, span: Range<usize>| {
@@ -320,11 +397,210 @@ fn literal_expr_parser() -> impl Parser<TokenKind, Node<Expr>, Error = Simple<To
     ))
 }
 
+/// Parses `place = value` and `place op= value`.
+///
+/// The left-hand side is parsed as an ordinary expression and must be a place
+/// (a variable, a member chain, or an index). Compound forms keep their operator on
+/// the statement so the place is evaluated once, as in `xs[f()] += 1`.
+fn assignment_parser<'a, P>(
+    expr: P,
+) -> BoxedParser<'a, TokenKind, Node<Statement>, Simple<TokenKind>>
+where
+    P: Parser<TokenKind, Node<Expr>, Error = Simple<TokenKind>> + Clone + 'a,
+{
+    let assign_op = choice((
+        just(TokenKind::Equals).to(None),
+        just(TokenKind::PlusEq).to(Some(BinaryOp::Add)),
+        just(TokenKind::MinusEq).to(Some(BinaryOp::Sub)),
+        just(TokenKind::StarEq).to(Some(BinaryOp::Mul)),
+        just(TokenKind::SlashEq).to(Some(BinaryOp::Div)),
+        just(TokenKind::SlashSlashEq).to(Some(BinaryOp::FloorDiv)),
+        just(TokenKind::StarStarEq).to(Some(BinaryOp::Pow)),
+        just(TokenKind::AmpEq).to(Some(BinaryOp::BitAnd)),
+        just(TokenKind::PipeEq).to(Some(BinaryOp::BitOr)),
+        just(TokenKind::CaretEq).to(Some(BinaryOp::BitXor)),
+        just(TokenKind::ShlEq).to(Some(BinaryOp::Shl)),
+        // `>>=` arrives as `>` followed by `>=`, see `shift_right_parser`
+        just(TokenKind::Gt)
+            .then(just(TokenKind::GtEq))
+            .try_map(|_, span: Range<usize>| {
+                if span.end - span.start == 3 {
+                    Ok(Some(BinaryOp::Shr))
+                } else {
+                    Err(Simple::custom(span, "expected `>>=`"))
+                }
+            }),
+    ));
+
+    expr.clone()
+        .try_map(|target, span| {
+            if target.as_ref().is_place() {
+                Ok(target)
+            } else {
+                Err(Simple::custom(span, "invalid assignment target"))
+            }
+        })
+        .then(assign_op)
+        .then(expr)
+        .map_with_span(|((target, op), expr), span| {
+            Node::new(Statement::Assignment { target, op, expr }, span)
+        })
+        .boxed()
+}
+
+/// Joins a left operand with the operator and right operand that follow it.
+fn binary_expr(left: Node<Expr>, (op, right): (BinaryOp, Node<Expr>)) -> Node<Expr> {
+    let span = left.span().merge(right.span());
+    Node::new(
+        Expr::Binary {
+            left: Box::new(left),
+            op,
+            right: Box::new(right),
+        },
+        span,
+    )
+}
+
+/// Matches `>>`, which the lexer leaves as two `>` tokens so that nested generic
+/// arguments close normally. The pair only forms a shift when nothing separates it.
+fn shift_right_parser() -> impl Parser<TokenKind, (), Error = Simple<TokenKind>> + Clone {
+    just(TokenKind::Gt)
+        .then(just(TokenKind::Gt))
+        .try_map(|_, span: Range<usize>| {
+            if span.end - span.start == 2 {
+                Ok(())
+            } else {
+                Err(Simple::custom(span, "expected `>>`"))
+            }
+        })
+}
+
+/// Postfix operators applied left-to-right after an atom.
+#[derive(Clone)]
+enum Postfix {
+    Member(String),
+    Call(Vec<Node<Type>>, Vec<Node<Expr>>, Vec<(String, Node<Expr>)>),
+    Index(Node<Expr>),
+    Slice(Option<Node<Expr>>, Option<Node<Expr>>),
+    Try,
+}
+
+/// A call argument as written; keyword arguments must follow the positional ones
+#[derive(Clone)]
+enum CallArg {
+    Positional(Node<Expr>),
+    Keyword(String, Node<Expr>),
+}
+
+type CallArgs = (Vec<Node<Expr>>, Vec<(String, Node<Expr>)>);
+
+fn split_call_args(args: Vec<CallArg>, span: Range<usize>) -> Result<CallArgs, Simple<TokenKind>> {
+    let mut positional = Vec::new();
+    let mut keywords = Vec::new();
+    for arg in args {
+        match arg {
+            CallArg::Positional(_) if !keywords.is_empty() => {
+                return Err(Simple::custom(
+                    span,
+                    "positional argument follows keyword argument",
+                ));
+            }
+            CallArg::Positional(value) => positional.push(value),
+            CallArg::Keyword(name, value) => keywords.push((name, value)),
+        }
+    }
+    Ok((positional, keywords))
+}
+
+/// Only the last parameter of a function can collect the remaining arguments
+fn check_variadic_params(
+    params: Vec<Node<Param>>,
+    span: Range<usize>,
+) -> Result<Vec<Node<Param>>, Simple<TokenKind>> {
+    let last = params.len().saturating_sub(1);
+    if params[..last].iter().any(|param| param.as_ref().variadic) {
+        return Err(Simple::custom(
+            span,
+            "variadic parameter must be the last parameter",
+        ));
+    }
+    Ok(params)
+}
+
+/// Separates the `as name` of a `with` header, which the expression parser reads as a cast
+fn split_with_binding(header: Node<Expr>) -> (Node<Expr>, Option<String>) {
+    if let Expr::Cast { expr, ty } = header.as_ref()
+        && let Type::Simple(name) = ty.as_ref()
+    {
+        return ((**expr).clone(), Some(name.clone()));
+    }
+    (header, None)
+}
+
+/// `break name` parses as a break carrying the value `name`. Inside a loop labelled `name`
+/// it leaves that loop instead; nested functions and lambdas are not searched.
+fn resolve_loop_label(block: &mut Block, label: &str) {
+    for statement in &mut block.statements {
+        match statement.as_mut() {
+            Statement::Break {
+                label: target,
+                value,
+            } => {
+                if target.is_none()
+                    && let Some(name) = value
+                    && matches!(name.as_ref(), Expr::Identifier(name) if name == label)
+                {
+                    *target = Some(label.to_string());
+                    *value = None;
+                }
+            }
+            Statement::If {
+                then_block,
+                elif_blocks,
+                else_block,
+                ..
+            } => {
+                resolve_loop_label(then_block.as_mut(), label);
+                for (_, block) in elif_blocks {
+                    resolve_loop_label(block.as_mut(), label);
+                }
+                if let Some(block) = else_block {
+                    resolve_loop_label(block.as_mut(), label);
+                }
+            }
+            Statement::For { body, .. }
+            | Statement::While { body, .. }
+            | Statement::With { body, .. }
+            | Statement::Block(body) => resolve_loop_label(body.as_mut(), label),
+            Statement::Try {
+                body,
+                handlers,
+                finally_block,
+            } => {
+                resolve_loop_label(body.as_mut(), label);
+                for handler in handlers {
+                    resolve_loop_label(handler.as_mut().body.as_mut(), label);
+                }
+                if let Some(block) = finally_block {
+                    resolve_loop_label(block.as_mut(), label);
+                }
+            }
+            Statement::Let { expr, .. } | Statement::Expr(expr) => match expr.as_mut() {
+                Expr::Loop { body, .. } => resolve_loop_label(body.as_mut(), label),
+                Expr::Match { arms, .. } => {
+                    for arm in arms {
+                        resolve_loop_label(arm.as_mut().body.as_mut(), label);
+                    }
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+}
+
 fn expr_parser() -> impl Parser<TokenKind, Node<Expr>, Error = Simple<TokenKind>> {
     recursive(|expr| {
-        // Lambda expressions removed - use anonymous fn syntax instead
-        // fn(<args>) expr or fn(<args>): <stmts>
-
         let struct_init_pythonic = identifier_parser()
             .then(
                 // Keyword argument: name=value
@@ -396,8 +672,25 @@ fn expr_parser() -> impl Parser<TokenKind, Node<Expr>, Error = Simple<TokenKind>
             literal_expr_parser(),
             struct_init_pythonic,
             identifier_parser().map_with_span(|name, span| Node::new(Expr::Identifier(name), span)),
+            // Parenthesized expression, or a tuple when a comma follows the first element
             expr.clone()
-                .delimited_by(just(TokenKind::LParen), just(TokenKind::RParen)),
+                .then(
+                    just(TokenKind::Comma)
+                        .ignore_then(
+                            expr.clone()
+                                .separated_by(just(TokenKind::Comma))
+                                .allow_trailing(),
+                        )
+                        .or_not(),
+                )
+                .delimited_by(just(TokenKind::LParen), just(TokenKind::RParen))
+                .map_with_span(|(first, rest), span| match rest {
+                    Some(rest) => Node::new(
+                        Expr::Tuple(std::iter::once(first).chain(rest).collect()),
+                        span,
+                    ),
+                    None => first,
+                }),
             list_comprehension,
             // Array literal [expr, expr, ...]
             expr.clone()
@@ -417,51 +710,93 @@ fn expr_parser() -> impl Parser<TokenKind, Node<Expr>, Error = Simple<TokenKind>
         ))
         .boxed();
 
-        let member_access = atom
-            .clone()
-            .then(
-                just(TokenKind::Dot)
-                    .ignore_then(identifier_or_keyword_parser())
-                    .map_with_span(Node::new)
-                    .repeated(),
-            )
-            .foldl(|object, field| {
-                let span = object.span().merge(field.span());
-                Node::new(
-                    Expr::Member {
-                        object: Box::new(object),
-                        field: field.into_inner(),
-                    },
-                    span,
-                )
-            })
-            .boxed();
+        let call_arg = choice((
+            identifier_parser()
+                .then_ignore(just(TokenKind::Equals))
+                .then(expr.clone())
+                .map(|(name, value)| CallArg::Keyword(name, value)),
+            just(TokenKind::Star)
+                .ignore_then(expr.clone())
+                .map_with_span(|value, span| {
+                    CallArg::Positional(Node::new(Expr::Spread(Box::new(value)), span))
+                }),
+            expr.clone().map(CallArg::Positional),
+        ));
 
         let call_suffix = just(TokenKind::LParen)
             .ignore_then(
-                expr.clone()
+                call_arg
                     .separated_by(just(TokenKind::Comma))
-                    .allow_trailing()
-                    .or_not()
-                    .map(|args| args.unwrap_or_default()),
+                    .allow_trailing(),
             )
             .then_ignore(just(TokenKind::RParen))
+            .try_map(split_call_args)
+            .boxed();
+
+        // Explicit instantiation of a generic function: `max<int>(a, b)`. The type list must
+        // be followed directly by the argument list, so `a < b` still parses as a comparison.
+        let type_args = type_parser()
+            .separated_by(just(TokenKind::Comma))
+            .at_least(1)
+            .allow_trailing()
+            .delimited_by(just(TokenKind::Lt), just(TokenKind::Gt))
+            .boxed();
+
+        // Index `[i]` or slice `[start:end]` (either bound may be omitted)
+        let index_suffix = just(TokenKind::LBracket)
+            .ignore_then(choice((
+                expr.clone()
+                    .or_not()
+                    .then_ignore(just(TokenKind::Colon))
+                    .then(expr.clone().or_not())
+                    .map(|(start, end)| Postfix::Slice(start, end)),
+                expr.clone().map(Postfix::Index),
+            )))
+            .then_ignore(just(TokenKind::RBracket))
             .boxed();
 
-        let call = member_access
+        let postfix_op = choice((
+            just(TokenKind::Dot)
+                .ignore_then(identifier_or_keyword_parser())
+                .map(Postfix::Member),
+            type_args
+                .then(call_suffix.clone())
+                .map(|(type_args, (args, kwargs))| Postfix::Call(type_args, args, kwargs)),
+            call_suffix.map(|(args, kwargs)| Postfix::Call(Vec::new(), args, kwargs)),
+            index_suffix,
+            just(TokenKind::Question).to(Postfix::Try),
+        ))
+        .map_with_span(|op, span: Range<usize>| (op, Span::from(span)))
+        .boxed();
+
+        let call = atom
             .clone()
-            .then(call_suffix.repeated())
-            .foldl(|func, args| {
-                let span = func
-                    .span()
-                    .merge(args.last().map(|_| func.span()).unwrap_or(func.span()));
-                Node::new(
-                    Expr::Call {
-                        func: Box::new(func),
+            .then(postfix_op.repeated())
+            .foldl(|object, (op, op_span)| {
+                let span = object.span().merge(&op_span);
+                let expr = match op {
+                    Postfix::Member(field) => Expr::Member {
+                        object: Box::new(object),
+                        field,
+                    },
+                    Postfix::Call(type_args, args, kwargs) => Expr::Call {
+                        func: Box::new(object),
                         args,
+                        kwargs,
+                        type_args,
                     },
-                    span,
-                )
+                    Postfix::Index(index) => Expr::Index {
+                        object: Box::new(object),
+                        index: Box::new(index),
+                    },
+                    Postfix::Slice(start, end) => Expr::Slice {
+                        object: Box::new(object),
+                        start: start.map(Box::new),
+                        end: end.map(Box::new),
+                    },
+                    Postfix::Try => Expr::Try(Box::new(object)),
+                };
+                Node::new(expr, span)
             })
             .boxed();
 
@@ -475,17 +810,15 @@ fn expr_parser() -> impl Parser<TokenKind, Node<Expr>, Error = Simple<TokenKind>
             .map_with_span(|expr, span| Node::new(Expr::Spawn(Box::new(expr)), span))
             .boxed();
 
-        let unary = choice((
+        let unary_op = choice((
             just(TokenKind::Minus).to(UnaryOp::Neg),
             just(TokenKind::Bang).to(UnaryOp::Not),
             just(TokenKind::Not).to(UnaryOp::Not),
+            just(TokenKind::Tilde).to(UnaryOp::BitNot),
         ))
-        .then(choice((
-            await_expr.clone(),
-            spawn_expr.clone(),
-            call.clone(),
-        )))
-        .map_with_span(|(op, expr), span| {
+        .boxed();
+
+        let unary_expr = |(op, expr): (UnaryOp, Node<Expr>), span: Range<usize>| {
             Node::new(
                 Expr::Unary {
                     op,
@@ -493,34 +826,68 @@ fn expr_parser() -> impl Parser<TokenKind, Node<Expr>, Error = Simple<TokenKind>
                 },
                 span,
             )
+        };
+
+        // `**` binds tighter than a unary operator on its left and is right-associative:
+        // `-2 ** 2` is `-(2 ** 2)` and `2 ** 3 ** 2` is `2 ** (3 ** 2)`.
+        let power = recursive(|power| {
+            let exponent = unary_op
+                .clone()
+                .then(power.clone())
+                .map_with_span(unary_expr)
+                .or(power);
+
+            choice((await_expr.clone(), spawn_expr.clone(), call.clone()))
+                .then(just(TokenKind::StarStar).ignore_then(exponent).or_not())
+                .map_with_span(|(base, exponent), span| match exponent {
+                    Some(exponent) => Node::new(
+                        Expr::Binary {
+                            left: Box::new(base),
+                            op: BinaryOp::Pow,
+                            right: Box::new(exponent),
+                        },
+                        span,
+                    ),
+                    None => base,
+                })
         })
-        .or(await_expr)
-        .or(spawn_expr)
-        .or(call.clone())
         .boxed();
 
-        let product = unary
+        let unary = unary_op
+            .then(power.clone())
+            .map_with_span(unary_expr)
+            .or(power)
+            .boxed();
+
+        // `as` binds tighter than the binary operators and looser than a unary one:
+        // `-x as u8` converts `-x`, and `a + b as f64` converts only `b`
+        let cast = unary
+            .then(just(TokenKind::As).ignore_then(type_parser()).repeated())
+            .foldl(|expr, ty| {
+                let span = expr.span().merge(ty.span());
+                Node::new(
+                    Expr::Cast {
+                        expr: Box::new(expr),
+                        ty,
+                    },
+                    span,
+                )
+            })
+            .boxed();
+
+        let product = cast
             .clone()
             .then(
                 choice((
                     just(TokenKind::Star).to(BinaryOp::Mul),
                     just(TokenKind::Slash).to(BinaryOp::Div),
+                    just(TokenKind::SlashSlash).to(BinaryOp::FloorDiv),
                     just(TokenKind::Percent).to(BinaryOp::Mod),
                 ))
-                .then(unary.clone())
+                .then(cast.clone())
                 .repeated(),
             )
-            .foldl(|left, (op, right)| {
-                let span = left.span().merge(right.span());
-                Node::new(
-                    Expr::Binary {
-                        left: Box::new(left),
-                        op,
-                        right: Box::new(right),
-                    },
-                    span,
-                )
-            })
+            .foldl(binary_expr)
             .boxed();
 
         let sum = product
@@ -533,22 +900,62 @@ fn expr_parser() -> impl Parser<TokenKind, Node<Expr>, Error = Simple<TokenKind>
                 .then(product)
                 .repeated(),
             )
-            .foldl(|left, (op, right)| {
-                let span = left.span().merge(right.span());
-                Node::new(
-                    Expr::Binary {
-                        left: Box::new(left),
-                        op,
-                        right: Box::new(right),
-                    },
-                    span,
-                )
-            })
+            .foldl(binary_expr)
+            .boxed();
+
+        let shift = sum
+            .clone()
+            .then(
+                choice((
+                    just(TokenKind::Shl).to(BinaryOp::Shl),
+                    shift_right_parser().to(BinaryOp::Shr),
+                ))
+                .then(sum)
+                .repeated(),
+            )
+            .foldl(binary_expr)
+            .boxed();
+
+        let bit_and = shift
+            .clone()
+            .then(
+                just(TokenKind::Amp)
+                    .to(BinaryOp::BitAnd)
+                    .then(shift)
+                    .repeated(),
+            )
+            .foldl(binary_expr)
+            .boxed();
+
+        let bit_xor = bit_and
+            .clone()
+            .then(
+                just(TokenKind::Caret)
+                    .to(BinaryOp::BitXor)
+                    .then(bit_and)
+                    .repeated(),
+            )
+            .foldl(binary_expr)
             .boxed();
 
-        let range = sum
+        let bit_or = bit_xor
             .clone()
-            .then(just(TokenKind::DoubleDot).ignore_then(sum.clone()).or_not())
+            .then(
+                just(TokenKind::Pipe)
+                    .to(BinaryOp::BitOr)
+                    .then(bit_xor)
+                    .repeated(),
+            )
+            .foldl(binary_expr)
+            .boxed();
+
+        let range = bit_or
+            .clone()
+            .then(
+                just(TokenKind::DoubleDot)
+                    .ignore_then(bit_or.clone())
+                    .or_not(),
+            )
             .map_with_span(|(start, end), span| {
                 if let Some(end) = end {
                     Node::new(
@@ -589,17 +996,7 @@ fn expr_parser() -> impl Parser<TokenKind, Node<Expr>, Error = Simple<TokenKind>
         let comparison = range
             .clone()
             .then(comparison_op.then(range.clone()).repeated())
-            .foldl(|left, (op, right)| {
-                let span = left.span().merge(right.span());
-                Node::new(
-                    Expr::Binary {
-                        left: Box::new(left),
-                        op,
-                        right: Box::new(right),
-                    },
-                    span,
-                )
-            })
+            .foldl(binary_expr)
             .boxed();
 
         let logical = comparison
@@ -612,25 +1009,17 @@ fn expr_parser() -> impl Parser<TokenKind, Node<Expr>, Error = Simple<TokenKind>
                 .then(comparison)
                 .repeated(),
             )
-            .foldl(|left, (op, right)| {
-                let span = left.span().merge(right.span());
-                Node::new(
-                    Expr::Binary {
-                        left: Box::new(left),
-                        op,
-                        right: Box::new(right),
-                    },
-                    span,
-                )
-            })
+            .foldl(binary_expr)
             .boxed();
 
         let newline = just(TokenKind::Newline).repeated().at_least(1);
 
-        // Define a local statement parser for match arms to avoid circular dependency
-        // This duplicates some logic from program_parser but is necessary because expr_parser
-        // cannot easily access the recursive statement parser from program_parser.
-        let match_stmt = recursive(|_stmt| {
+        // Define a local statement parser for match arms and closure bodies to avoid circular
+        // dependency. This duplicates some logic from program_parser but is necessary because
+        // expr_parser cannot easily access the recursive statement parser from program_parser.
+        // Statements of match arms and block closures, which nest `if`, `for` and `while`
+        // blocks of their own
+        let match_stmt = recursive(|stmt| {
             let print_stmt = just(TokenKind::Print)
                 .ignore_then(
                     expr.clone()
@@ -646,6 +1035,8 @@ fn expr_parser() -> impl Parser<TokenKind, Node<Expr>, Error = Simple<TokenKind>
                                     span,
                                 )),
                                 args: vec![arg],
+                                kwargs: Vec::new(),
+                                type_args: Vec::new(),
                             },
                             span,
                         )),
@@ -660,18 +1051,15 @@ fn expr_parser() -> impl Parser<TokenKind, Node<Expr>, Error = Simple<TokenKind>
                 .boxed();
 
             let let_stmt = just(TokenKind::Let)
-                .or_not()
-                .then(
-                    identifier_parser()
-                        .map_with_span(Node::new)
-                        .then(just(TokenKind::Colon).ignore_then(type_parser()).or_not()),
-                )
+                .ignore_then(binding_pattern_parser())
+                .or(simple_binding_pattern_parser())
+                .then(just(TokenKind::Colon).ignore_then(type_parser()).or_not())
                 .then_ignore(just(TokenKind::Equals))
                 .then(expr.clone())
-                .map_with_span(|((_let, (name, ty)), expr), span| {
+                .map_with_span(|((pattern, ty), expr), span| {
                     Node::new(
                         Statement::Let {
-                            name,
+                            pattern,
                             ty,
                             expr,
                             public: false, // Match arms are local scopes
@@ -680,71 +1068,117 @@ fn expr_parser() -> impl Parser<TokenKind, Node<Expr>, Error = Simple<TokenKind>
                     )
                 });
 
-            let assignment_stmt = identifier_parser()
-                .map_with_span(|name, span| (name, Span::new(span.start, span.end)))
-                .then(choice((
-                    just(TokenKind::PlusEq).to(BinaryOp::Add),
-                    just(TokenKind::MinusEq).to(BinaryOp::Sub),
-                    just(TokenKind::StarEq).to(BinaryOp::Mul),
-                    just(TokenKind::SlashEq).to(BinaryOp::Div),
-                )))
-                .then(expr.clone())
-                .map_with_span(|(((name, name_span), op), rhs), span| {
-                    let span: Span = span.into();
-                    let expr = Node::new(
-                        Expr::Binary {
-                            op,
-                            left: Box::new(Node::new(Expr::Identifier(name.clone()), name_span)),
-                            right: Box::new(rhs),
-                        },
-                        span,
-                    );
-                    Node::new(
-                        Statement::Assignment {
-                            name: Node::new(name, name_span),
-                            expr,
-                        },
-                        span,
-                    )
-                })
-                .boxed();
-
-            // Simple assignment (=)
-            let simple_assignment = identifier_parser()
-                .map_with_span(Node::new)
-                .then_ignore(just(TokenKind::Equals))
-                .then(expr.clone())
-                .map_with_span(|(name, expr), span| {
-                    Node::new(Statement::Assignment { name, expr }, span)
-                })
-                .boxed();
+            let assignment_stmt = assignment_parser(expr.clone());
 
             let pass_stmt = just(TokenKind::Pass)
                 .map_with_span(|_, span| Node::new(Statement::Pass, span))
                 .boxed();
 
             let break_stmt = just(TokenKind::Break)
-                .map_with_span(|_, span| Node::new(Statement::Break, span))
+                .ignore_then(expr.clone().or_not())
+                .map_with_span(|value, span| {
+                    Node::new(Statement::Break { label: None, value }, span)
+                })
                 .boxed();
 
             let continue_stmt = just(TokenKind::Continue)
-                .map_with_span(|_, span| Node::new(Statement::Continue, span))
+                .ignore_then(identifier_parser().or_not())
+                .map_with_span(|label, span| Node::new(Statement::Continue { label }, span))
                 .boxed();
 
-            choice((
-                print_stmt,
-                return_stmt,
-                let_stmt,
-                assignment_stmt,
-                simple_assignment,
-                pass_stmt,
-                break_stmt,
-                continue_stmt,
-                expr.clone()
-                    .map_with_span(|expr, span| Node::new(Statement::Expr(expr), span)),
-            ))
-            .then_ignore(newline.clone().or_not())
-            .boxed()
+            let nested_block = stmt
+                .repeated()
+                .at_least(1)
+                .delimited_by(just(TokenKind::Indent), just(TokenKind::Dedent))
+                .map_with_span(|block, span| Node::new(Block::new(block), span))
+                .boxed();
+
+            let if_stmt = just(TokenKind::If)
+                .ignore_then(expr.clone())
+                .then_ignore(just(TokenKind::Colon))
+                .then_ignore(newline.clone())
+                .then(nested_block.clone())
+                .then(
+                    just(TokenKind::Elif)
+                        .ignore_then(expr.clone())
+                        .then_ignore(just(TokenKind::Colon))
+                        .then_ignore(newline.clone())
+                        .then(nested_block.clone())
+                        .repeated(),
+                )
+                .then(
+                    just(TokenKind::Else)
+                        .ignore_then(just(TokenKind::Colon))
+                        .ignore_then(newline.clone())
+                        .ignore_then(nested_block.clone())
+                        .or_not(),
+                )
+                .map_with_span(|(((cond, then_block), elif_blocks), else_block), span| {
+                    Node::new(
+                        Statement::If {
+                            cond,
+                            then_block,
+                            elif_blocks,
+                            else_block,
+                        },
+                        span,
+                    )
+                })
+                .boxed();
+
+            let for_stmt = just(TokenKind::For)
+                .ignore_then(binding_pattern_parser())
+                .then_ignore(just(TokenKind::In))
+                .then(expr.clone())
+                .then_ignore(just(TokenKind::Colon))
+                .then_ignore(newline.clone())
+                .then(nested_block.clone())
+                .map_with_span(|((pattern, iterable), body), span| {
+                    Node::new(
+                        Statement::For {
+                            label: None,
+                            pattern,
+                            iterable,
+                            body,
+                        },
+                        span,
+                    )
+                })
+                .boxed();
+
+            let while_stmt = just(TokenKind::While)
+                .ignore_then(expr.clone())
+                .then_ignore(just(TokenKind::Colon))
+                .then_ignore(newline.clone())
+                .then(nested_block)
+                .map_with_span(|(cond, body), span| {
+                    Node::new(
+                        Statement::While {
+                            label: None,
+                            cond,
+                            body,
+                        },
+                        span,
+                    )
+                })
+                .boxed();
+
+            choice((
+                print_stmt,
+                return_stmt,
+                if_stmt,
+                for_stmt,
+                while_stmt,
+                let_stmt,
+                assignment_stmt,
+                pass_stmt,
+                break_stmt,
+                continue_stmt,
+                expr.clone()
+                    .map_with_span(|expr, span| Node::new(Statement::Expr(expr), span)),
+            ))
+            .then_ignore(newline.clone().or_not())
+            .boxed()
         });
 
         let match_case = just(TokenKind::Case)
@@ -773,6 +1207,56 @@ fn expr_parser() -> impl Parser<TokenKind, Node<Expr>, Error = Simple<TokenKind>
             .then_ignore(newline.clone().or_not())
             .boxed();
 
+        // Anonymous functions: fn(<params>) => <expr> or fn(<params>): <stmts>
+        let lambda_param = identifier_parser()
+            .map_with_span(Node::new)
+            .then(just(TokenKind::Colon).ignore_then(type_parser()).or_not())
+            .map_with_span(|(name, ty), span| Node::new(Param::new(name, ty, None), span))
+            .boxed();
+
+        let lambda_body = choice((
+            just(TokenKind::FatArrow)
+                .ignore_then(expr.clone())
+                .map(|body| {
+                    let span = *body.span();
+                    Node::new(
+                        Block::new(vec![Node::new(Statement::Return(Some(body)), span)]),
+                        span,
+                    )
+                }),
+            just(TokenKind::Colon)
+                .ignore_then(newline.clone())
+                .ignore_then(
+                    match_stmt
+                        .repeated()
+                        .at_least(1)
+                        .delimited_by(just(TokenKind::Indent), just(TokenKind::Dedent)),
+                )
+                .map_with_span(|statements, span| Node::new(Block::new(statements), span)),
+        ))
+        .boxed();
+
+        let lambda = just(TokenKind::Fn)
+            .ignore_then(
+                lambda_param
+                    .separated_by(just(TokenKind::Comma))
+                    .allow_trailing()
+                    .delimited_by(just(TokenKind::LParen), just(TokenKind::RParen)),
+            )
+            .then(just(TokenKind::Arrow).ignore_then(type_parser()).or_not())
+            .then(lambda_body)
+            .map_with_span(|((params, ret_ty), body), span| {
+                Node::new(
+                    Expr::Lambda {
+                        params,
+                        ret_ty,
+                        body,
+                    },
+                    span,
+                )
+            })
+            .boxed();
+
         just(TokenKind::Match)
             .ignore_then(logical.clone())
             .then(
@@ -791,6 +1275,7 @@ fn expr_parser() -> impl Parser<TokenKind, Node<Expr>, Error = Simple<TokenKind>
                     span,
                 )
             })
+            .or(lambda)
             .or(logical)
     })
 }
@@ -802,15 +1287,74 @@ fn pattern_parser() -> impl Parser<TokenKind, Node<Pattern>, Error = Simple<Toke
             .map_with_span(|_, span| Node::new(Pattern::Wildcard, span))
             .boxed();
 
-        let literal_pattern = literal_expr_parser()
-            .map_with_span(|expr, span| {
-                Node::new(
-                    match expr.into_inner() {
-                        Expr::Literal(lit) => Pattern::Literal(lit),
-                        _ => Pattern::Wildcard, // Fallback
+        let negative_number = just(TokenKind::Minus)
+            .ignore_then(select! { TokenKind::Number(value) => value })
+            .map_with_span(|value, span: Range<usize>| {
+                let number = NumberLiteral::parse(&value)
+                    .unwrap_or_else(|| NumberLiteral::new(0.0, false))
+                    .negated();
+                Some(Node::new(Literal::Number(number), span))
+            });
+
+        // `None` stands for an f-string, which falls back to a wildcard
+        let literal_value = literal_expr_parser()
+            .try_map(|expr, span: Range<usize>| match expr.into_inner() {
+                Expr::Literal(lit) if matches!(lit.as_ref(), Literal::Bytes(_)) => Err(
+                    Simple::custom(span, "byte strings cannot be used as patterns"),
+                ),
+                Expr::Literal(lit) => Ok(Some(lit)),
+                _ => Ok(None),
+            })
+            .or(negative_number)
+            .boxed();
+
+        let range_pattern = literal_value
+            .clone()
+            .then(
+                just(TokenKind::DoubleDot)
+                    .ignore_then(just(TokenKind::Equals).or_not())
+                    .map(|equals| equals.is_some()),
+            )
+            .then(literal_value.clone())
+            .try_map(|((start, inclusive), end), span| match (start, end) {
+                (Some(start), Some(end)) => Ok(Node::new(
+                    Pattern::Range {
+                        start,
+                        end,
+                        inclusive,
                     },
                     span,
-                )
+                )),
+                _ => Err(Simple::custom(
+                    span,
+                    "range pattern bounds must be literals",
+                )),
+            })
+            .boxed();
+
+        // `"GET " + path` matches strings starting with the literal and binds the rest
+        let string_prefix_pattern = literal_value
+            .clone()
+            .then_ignore(just(TokenKind::Plus))
+            .then(identifier_parser())
+            .try_map(|(prefix, rest), span| match prefix {
+                Some(prefix) if matches!(prefix.as_ref(), Literal::String(_)) => Ok(Node::new(
+                    Pattern::StringPrefix {
+                        prefix,
+                        rest: (rest != "_").then_some(rest),
+                    },
+                    span,
+                )),
+                _ => Err(Simple::custom(
+                    span,
+                    "prefix patterns start with a string literal",
+                )),
+            })
+            .boxed();
+
+        let literal_pattern = literal_value
+            .map_with_span(|lit, span| {
+                Node::new(lit.map_or(Pattern::Wildcard, Pattern::Literal), span)
             })
             .boxed();
 
@@ -872,6 +1416,22 @@ fn pattern_parser() -> impl Parser<TokenKind, Node<Pattern>, Error = Simple<Toke
             })
             .boxed();
 
+        // Mirrors struct construction: `Point(x=0, y)`
+        let struct_pattern_pythonic = identifier_parser()
+            .then(
+                identifier_parser()
+                    .then(
+                        just(TokenKind::Equals)
+                            .ignore_then(pattern.clone())
+                            .or_not(),
+                    )
+                    .separated_by(just(TokenKind::Comma))
+                    .allow_trailing()
+                    .delimited_by(just(TokenKind::LParen), just(TokenKind::RParen)),
+            )
+            .map_with_span(|(name, fields), span| Node::new(Pattern::Struct { name, fields }, span))
+            .boxed();
+
         let array_pattern = pattern
             .clone()
             .separated_by(just(TokenKind::Comma))
@@ -887,14 +1447,134 @@ fn pattern_parser() -> impl Parser<TokenKind, Node<Pattern>, Error = Simple<Toke
             })
             .boxed();
 
-        choice((
+        let tuple_pattern = tuple_pattern_parser(pattern).boxed();
+
+        let single = choice((
             wildcard,
+            range_pattern,
+            string_prefix_pattern,
             literal_pattern,
             enum_variant_pattern,
             struct_pattern,
+            struct_pattern_pythonic,
             array_pattern,
+            tuple_pattern,
             identifier_pattern,
         ))
+        .boxed();
+
+        // `@` binds tighter than `|`: `p @ (1 | 2)` names either value
+        let binding_pattern = identifier_parser()
+            .then_ignore(just(TokenKind::At))
+            .then(single.clone())
+            .map_with_span(|(name, pattern), span| {
+                Node::new(
+                    Pattern::Binding {
+                        name,
+                        pattern: Box::new(pattern),
+                    },
+                    span,
+                )
+            })
+            .or(single)
+            .boxed();
+
+        binding_pattern
+            .clone()
+            .then(
+                just(TokenKind::Pipe)
+                    .ignore_then(binding_pattern)
+                    .repeated(),
+            )
+            .map_with_span(|(first, rest), span| {
+                if rest.is_empty() {
+                    first
+                } else {
+                    Node::new(
+                        Pattern::Or(std::iter::once(first).chain(rest).collect()),
+                        span,
+                    )
+                }
+            })
+    })
+}
+
+/// `(p, q, ...)`; a single parenthesized pattern is just grouping
+fn tuple_pattern_parser<P>(
+    pattern: P,
+) -> impl Parser<TokenKind, Node<Pattern>, Error = Simple<TokenKind>> + Clone
+where
+    P: Parser<TokenKind, Node<Pattern>, Error = Simple<TokenKind>> + Clone,
+{
+    pattern
+        .clone()
+        .then(
+            just(TokenKind::Comma)
+                .ignore_then(
+                    pattern
+                        .separated_by(just(TokenKind::Comma))
+                        .allow_trailing(),
+                )
+                .or_not(),
+        )
+        .delimited_by(just(TokenKind::LParen), just(TokenKind::RParen))
+        .map_with_span(|(first, rest), span| match rest {
+            Some(rest) => Node::new(
+                Pattern::Tuple(std::iter::once(first).chain(rest).collect()),
+                span,
+            ),
+            None => first,
+        })
+}
+
+/// Patterns in `let` and `for` heads. Any pattern parses here and the type checker rejects
+/// the refutable ones. The outermost tuple may drop its parentheses (`let a, b = ...`).
+fn binding_pattern_parser() -> impl Parser<TokenKind, Node<Pattern>, Error = Simple<TokenKind>> {
+    unparenthesized_tuple(pattern_parser())
+}
+
+/// A name, `_` or a tuple of those, for the `let`-less bindings in match arms (`x = ...`),
+/// where a richer pattern would swallow field assignments such as `p.x = ...`
+fn simple_binding_pattern_parser()
+-> impl Parser<TokenKind, Node<Pattern>, Error = Simple<TokenKind>> {
+    let pattern = recursive(|pattern| {
+        let wildcard = just(TokenKind::Identifier("_".to_string()))
+            .map_with_span(|_, span| Node::new(Pattern::Wildcard, span));
+        let identifier = identifier_parser()
+            .map_with_span(|ident, span| Node::new(Pattern::Identifier(ident), span));
+
+        wildcard.or(identifier).or(tuple_pattern_parser(pattern))
+    });
+
+    unparenthesized_tuple(pattern)
+}
+
+/// `p, q, ...` as a tuple pattern, or just `p`
+fn unparenthesized_tuple<P>(
+    pattern: P,
+) -> impl Parser<TokenKind, Node<Pattern>, Error = Simple<TokenKind>>
+where
+    P: Parser<TokenKind, Node<Pattern>, Error = Simple<TokenKind>>,
+{
+    pattern
+        .separated_by(just(TokenKind::Comma))
+        .at_least(1)
+        .map_with_span(|mut patterns, span| {
+            if patterns.len() == 1 {
+                patterns.remove(0)
+            } else {
+                Node::new(Pattern::Tuple(patterns), span)
+            }
+        })
+}
+
+/// A parameter written as a pattern rather than a plain name: `(a, b)`, `Point(x=x, y=y)`
+fn param_pattern_parser() -> impl Parser<TokenKind, Node<Pattern>, Error = Simple<TokenKind>> {
+    pattern_parser().try_map(|pattern, span| match pattern.as_ref() {
+        Pattern::Identifier(_) | Pattern::Wildcard => {
+            Err(Simple::custom(span, "expected a destructuring pattern"))
+        }
+        _ => Ok(pattern),
     })
 }
 
@@ -914,6 +1594,8 @@ fn program_parser() -> impl Parser<TokenKind, Program, Error = Simple<TokenKind>
                     Expr::Call {
                         func: Box::new(Node::new(Expr::Identifier("print".to_string()), span)),
                         args: vec![arg],
+                        kwargs: Vec::new(),
+                        type_args: Vec::new(),
                     },
                     span,
                 )),
@@ -933,16 +1615,15 @@ fn program_parser() -> impl Parser<TokenKind, Program, Error = Simple<TokenKind>
         .clone()
         .then(just(TokenKind::Let))
         .then(
-            identifier_parser()
-                .map_with_span(Node::new)
+            binding_pattern_parser()
                 .then(just(TokenKind::Colon).ignore_then(type_parser()).or_not()),
         )
         .then_ignore(just(TokenKind::Equals))
         .then(expr.clone())
-        .map_with_span(|(((pub_kw, _let), (name, ty)), expr), span| {
+        .map_with_span(|(((pub_kw, _let), (pattern, ty)), expr), span| {
             Node::new(
                 Statement::Let {
-                    name,
+                    pattern,
                     ty,
                     expr,
                     public: pub_kw.is_some(),
@@ -951,41 +1632,7 @@ fn program_parser() -> impl Parser<TokenKind, Program, Error = Simple<TokenKind>
             )
         });
 
-    let simple_assignment_stmt = identifier_parser()
-        .map_with_span(Node::new)
-        .then_ignore(just(TokenKind::Equals))
-        .then(expr.clone())
-        .map_with_span(|(name, expr), span| Node::new(Statement::Assignment { name, expr }, span));
-
-    let compound_assignment_stmt = identifier_parser()
-        .map_with_span(|name, span| (name, Span::new(span.start, span.end)))
-        .then(choice((
-            just(TokenKind::PlusEq).to(BinaryOp::Add),
-            just(TokenKind::MinusEq).to(BinaryOp::Sub),
-            just(TokenKind::StarEq).to(BinaryOp::Mul),
-            just(TokenKind::SlashEq).to(BinaryOp::Div),
-        )))
-        .then(expr.clone())
-        .map_with_span(|(((name, name_span), op), rhs), span| {
-            let span: Span = span.into();
-            // Desugar: x += y becomes x = x + y
-            let expr = Node::new(
-                Expr::Binary {
-                    op,
-                    left: Box::new(Node::new(Expr::Identifier(name.clone()), name_span)),
-                    right: Box::new(rhs),
-                },
-                span,
-            );
-            Node::new(
-                Statement::Assignment {
-                    name: Node::new(name, name_span),
-                    expr,
-                },
-                span,
-            )
-        })
-        .boxed();
+    let assignment_stmt = assignment_parser(expr.clone());
 
     let path_segment = choice((
         just(TokenKind::Dot).to(".".to_string()),
@@ -1062,11 +1709,15 @@ fn program_parser() -> impl Parser<TokenKind, Program, Error = Simple<TokenKind>
         )
         .boxed();
 
+    // `break name` is read as a break carrying the value `name` until the enclosing loop
+    // labelled `name` claims it, see `resolve_loop_label`
     let break_stmt = just(TokenKind::Break)
-        .map_with_span(|_, span| Node::new(Statement::Break, span))
+        .ignore_then(expr.clone().or_not())
+        .map_with_span(|value, span| Node::new(Statement::Break { label: None, value }, span))
         .boxed();
     let continue_stmt = just(TokenKind::Continue)
-        .map_with_span(|_, span| Node::new(Statement::Continue, span))
+        .ignore_then(identifier_parser().or_not())
+        .map_with_span(|label, span| Node::new(Statement::Continue { label }, span))
         .boxed();
     let pass_stmt = just(TokenKind::Pass)
         .map_with_span(|_, span| Node::new(Statement::Pass, span))
@@ -1126,8 +1777,16 @@ fn program_parser() -> impl Parser<TokenKind, Program, Error = Simple<TokenKind>
             })
             .boxed();
 
-        let for_stmt = just(TokenKind::For)
-            .ignore_then(identifier_parser().map_with_span(Node::new))
+        // Optional `name:` in front of a loop
+        let loop_label = identifier_parser()
+            .then_ignore(just(TokenKind::Colon))
+            .or_not()
+            .boxed();
+
+        let for_stmt = loop_label
+            .clone()
+            .then_ignore(just(TokenKind::For))
+            .then(binding_pattern_parser())
             .then_ignore(just(TokenKind::In))
             .then(expr.clone())
             .then_ignore(just(TokenKind::Colon))
@@ -1139,10 +1798,14 @@ fn program_parser() -> impl Parser<TokenKind, Program, Error = Simple<TokenKind>
                     .delimited_by(just(TokenKind::Indent), just(TokenKind::Dedent))
                     .map_with_span(|block, span| Node::new(Block::new(block), span)),
             )
-            .map_with_span(|((var, iterable), body), span| {
+            .map_with_span(|(((label, pattern), iterable), mut body), span| {
+                if let Some(label) = &label {
+                    resolve_loop_label(body.as_mut(), label);
+                }
                 Node::new(
                     Statement::For {
-                        var,
+                        label,
+                        pattern,
                         iterable,
                         body,
                     },
@@ -1151,8 +1814,10 @@ fn program_parser() -> impl Parser<TokenKind, Program, Error = Simple<TokenKind>
             })
             .boxed();
 
-        let while_stmt = just(TokenKind::While)
-            .ignore_then(expr.clone())
+        let while_stmt = loop_label
+            .clone()
+            .then_ignore(just(TokenKind::While))
+            .then(expr.clone())
             .then_ignore(just(TokenKind::Colon))
             .then_ignore(newline.clone())
             .then(
@@ -1162,83 +1827,304 @@ fn program_parser() -> impl Parser<TokenKind, Program, Error = Simple<TokenKind>
                     .delimited_by(just(TokenKind::Indent), just(TokenKind::Dedent))
                     .map_with_span(|block, span| Node::new(Block::new(block), span)),
             )
-            .map_with_span(|(cond, body), span| Node::new(Statement::While { cond, body }, span))
+            .map_with_span(|((label, cond), mut body), span| {
+                if let Some(label) = &label {
+                    resolve_loop_label(body.as_mut(), label);
+                }
+                Node::new(Statement::While { label, cond, body }, span)
+            })
             .boxed();
 
-        // Exception handling (try/except/finally/raise) removed - use Result<T, E> pattern matching instead
-
-        choice((
-            print_stmt,
-            return_stmt,
-            let_stmt,
-            compound_assignment_stmt,
-            simple_assignment_stmt,
-            use_stmt,
-            pub_use_stmt,
-            if_stmt,
-            for_stmt,
-            while_stmt,
-            break_stmt,
-            continue_stmt,
-            pass_stmt,
-            expr.clone()
-                .map_with_span(|expr, span| Node::new(Statement::Expr(expr), span)),
-        ))
-        .then_ignore(newline.clone().or_not())
-        .boxed()
-    });
+        // `loop:` is a contextual keyword. Its body is an indented block, so the expression
+        // stands on its own line or initializes a `let`. An unlabelled `loop:` would itself
+        // read as a label, so the two forms are tried separately
+        let loop_keyword = just(TokenKind::Identifier("loop".to_string()));
+        let loop_expr = identifier_parser()
+            .then_ignore(just(TokenKind::Colon))
+            .then_ignore(loop_keyword.clone())
+            .map(Some)
+            .or(loop_keyword.to(None))
+            .then_ignore(just(TokenKind::Colon))
+            .then_ignore(newline.clone())
+            .then(
+                stmt.clone()
+                    .repeated()
+                    .at_least(1)
+                    .delimited_by(just(TokenKind::Indent), just(TokenKind::Dedent))
+                    .map_with_span(|block, span| Node::new(Block::new(block), span)),
+            )
+            .map_with_span(|(label, mut body), span| {
+                if let Some(label) = &label {
+                    resolve_loop_label(body.as_mut(), label);
+                }
+                Node::new(Expr::Loop { label, body }, span)
+            })
+            .boxed();
 
-    let block = statement
-        .clone()
-        .repeated()
-        .at_least(1)
-        .delimited_by(just(TokenKind::Indent), just(TokenKind::Dedent))
-        .map_with_span(|block, span| Node::new(Block::new(block), span))
-        .boxed();
+        let loop_stmt = loop_expr
+            .clone()
+            .map_with_span(|expr, span| Node::new(Statement::Expr(expr), span))
+            .boxed();
 
-    let function_param = identifier_parser()
-        .map_with_span(Node::new)
-        .then(choice((
-            just(TokenKind::Colon).ignore_then(type_parser()).map(Some),
-            empty().to(None),
-        )))
-        .then(choice((
-            just(TokenKind::Equals).ignore_then(expr.clone()).map(Some),
-            empty().to(None),
-        )))
-        .map_with_span(|((name, ty), default), span| Node::new(Param::new(name, ty, default), span))
-        .boxed();
+        let let_loop_stmt = pub_keyword
+            .clone()
+            .then_ignore(just(TokenKind::Let))
+            .then(
+                binding_pattern_parser()
+                    .then(just(TokenKind::Colon).ignore_then(type_parser()).or_not()),
+            )
+            .then_ignore(just(TokenKind::Equals))
+            .then(loop_expr)
+            .map_with_span(|((pub_kw, (pattern, ty)), expr), span| {
+                Node::new(
+                    Statement::Let {
+                        pattern,
+                        ty,
+                        expr,
+                        public: pub_kw.is_some(),
+                    },
+                    span,
+                )
+            })
+            .boxed();
 
-    let function_params = function_param
-        .separated_by(just(TokenKind::Comma))
-        .allow_trailing()
-        .delimited_by(just(TokenKind::LParen), just(TokenKind::RParen))
-        .or_not()
-        .map(|params| params.unwrap_or_default());
+        // Exception handling: `try`, `except`, `finally` and `raise` are contextual keywords so
+        // the `try(func)` builtin keeps working
+        let raise_stmt = just(TokenKind::Identifier("raise".to_string()))
+            .ignore_then(expr.clone().or_not())
+            .map_with_span(|value, span| Node::new(Statement::Raise(value), span))
+            .boxed();
+
+        let except_handler = just(TokenKind::Identifier("except".to_string()))
+            .ignore_then(
+                identifier_parser()
+                    .then(
+                        just(TokenKind::As)
+                            .ignore_then(identifier_parser())
+                            .or_not(),
+                    )
+                    .or_not(),
+            )
+            .then_ignore(just(TokenKind::Colon))
+            .then_ignore(newline.clone())
+            .then(
+                stmt.clone()
+                    .repeated()
+                    .at_least(1)
+                    .delimited_by(just(TokenKind::Indent), just(TokenKind::Dedent))
+                    .map_with_span(|block, span| Node::new(Block::new(block), span)),
+            )
+            .map_with_span(|(clause, body), span| {
+                let (exception_type, binding) = match clause {
+                    Some((ty, binding)) => (Some(ty), binding),
+                    None => (None, None),
+                };
+                Node::new(
+                    ExceptHandler {
+                        exception_type,
+                        binding,
+                        body,
+                    },
+                    span,
+                )
+            })
+            .boxed();
+
+        let finally_block = just(TokenKind::Identifier("finally".to_string()))
+            .ignore_then(just(TokenKind::Colon))
+            .ignore_then(newline.clone())
+            .ignore_then(
+                stmt.clone()
+                    .repeated()
+                    .at_least(1)
+                    .delimited_by(just(TokenKind::Indent), just(TokenKind::Dedent))
+                    .map_with_span(|block, span| Node::new(Block::new(block), span)),
+            )
+            .boxed();
+
+        let try_stmt = just(TokenKind::Identifier("try".to_string()))
+            .ignore_then(just(TokenKind::Colon))
+            .ignore_then(newline.clone())
+            .ignore_then(
+                stmt.clone()
+                    .repeated()
+                    .at_least(1)
+                    .delimited_by(just(TokenKind::Indent), just(TokenKind::Dedent))
+                    .map_with_span(|block, span| Node::new(Block::new(block), span)),
+            )
+            .then(except_handler.repeated())
+            .then(finally_block.or_not())
+            .try_map(|((body, handlers), finally_block), span| {
+                if handlers.is_empty() && finally_block.is_none() {
+                    return Err(Simple::custom(
+                        span,
+                        "`try` requires at least one `except` or a `finally` clause",
+                    ));
+                }
+                Ok(Node::new(
+                    Statement::Try {
+                        body,
+                        handlers,
+                        finally_block,
+                    },
+                    span,
+                ))
+            })
+            .boxed();
+
+        // Scoped cleanup: `with` and `defer` are contextual keywords as well. The header is parsed
+        // as an ordinary expression, so a trailing `as name` arrives as a cast and is split off here
+        let with_stmt = just(TokenKind::Identifier("with".to_string()))
+            .ignore_then(expr.clone())
+            .then_ignore(just(TokenKind::Colon))
+            .then_ignore(newline.clone())
+            .then(
+                stmt.clone()
+                    .repeated()
+                    .at_least(1)
+                    .delimited_by(just(TokenKind::Indent), just(TokenKind::Dedent))
+                    .map_with_span(|block, span| Node::new(Block::new(block), span)),
+            )
+            .map_with_span(|(header, body), span| {
+                let (expr, binding) = split_with_binding(header);
+                Node::new(
+                    Statement::With {
+                        expr,
+                        binding,
+                        body,
+                    },
+                    span,
+                )
+            })
+            .boxed();
+
+        // `defer(f)` stays a call to the runtime `defer` builtin
+        let defer_stmt = just(TokenKind::Identifier("defer".to_string()))
+            .then_ignore(none_of([TokenKind::LParen]).rewind())
+            .ignore_then(expr.clone())
+            .map_with_span(|value, span| Node::new(Statement::Defer(value), span))
+            .boxed();
+
+        choice((
+            print_stmt,
+            return_stmt,
+            let_loop_stmt,
+            loop_stmt,
+            let_stmt,
+            assignment_stmt,
+            use_stmt,
+            pub_use_stmt,
+            if_stmt,
+            for_stmt,
+            while_stmt,
+            try_stmt,
+            raise_stmt,
+            with_stmt,
+            defer_stmt,
+            break_stmt,
+            continue_stmt,
+            pass_stmt,
+            expr.clone()
+                .map_with_span(|expr, span| Node::new(Statement::Expr(expr), span)),
+        ))
+        .then_ignore(newline.clone().or_not())
+        .boxed()
+    });
+
+    let block = statement
+        .clone()
+        .repeated()
+        .at_least(1)
+        .delimited_by(just(TokenKind::Indent), just(TokenKind::Dedent))
+        .map_with_span(|block, span| Node::new(Block::new(block), span))
+        .boxed();
+
+    let function_param = just(TokenKind::Star)
+        .ignore_then(identifier_parser().map_with_span(Node::new))
+        .then(just(TokenKind::Colon).ignore_then(type_parser()).or_not())
+        .map_with_span(|(name, element), span| Node::new(Param::variadic(name, element), span))
+        .or(param_pattern_parser()
+            .then(just(TokenKind::Colon).ignore_then(type_parser()).or_not())
+            .then(just(TokenKind::Equals).ignore_then(expr.clone()).or_not())
+            .map_with_span(|((pattern, ty), default), span| {
+                Node::new(Param::destructured(pattern, ty, default), span)
+            }))
+        .or(identifier_parser()
+            .map_with_span(Node::new)
+            .then(choice((
+                just(TokenKind::Colon).ignore_then(type_parser()).map(Some),
+                empty().to(None),
+            )))
+            .then(choice((
+                just(TokenKind::Equals).ignore_then(expr.clone()).map(Some),
+                empty().to(None),
+            )))
+            .map_with_span(|((name, ty), default), span| {
+                Node::new(Param::new(name, ty, default), span)
+            }))
+        .boxed();
+
+    let function_params = function_param
+        .separated_by(just(TokenKind::Comma))
+        .allow_trailing()
+        .delimited_by(just(TokenKind::LParen), just(TokenKind::RParen))
+        .or_not()
+        .map(|params| params.unwrap_or_default())
+        .try_map(check_variadic_params);
 
     let function_ret_type = just(TokenKind::Arrow).ignore_then(type_parser()).or_not();
 
     let function_keyword = just(TokenKind::Fn);
 
+    // Generic parameters with optional trait bounds: <T, U: Show + Eq>
+    let generic_params = || {
+        identifier_parser()
+            .then(
+                just(TokenKind::Colon)
+                    .ignore_then(
+                        identifier_parser()
+                            .separated_by(just(TokenKind::Plus))
+                            .at_least(1),
+                    )
+                    .or_not(),
+            )
+            .separated_by(just(TokenKind::Comma))
+            .allow_trailing()
+            .delimited_by(just(TokenKind::Lt), just(TokenKind::Gt))
+            .or_not()
+            .map(|params| {
+                let mut generics = Vec::new();
+                let mut bounds = Vec::new();
+                for (name, traits) in params.unwrap_or_default() {
+                    if let Some(traits) = traits {
+                        bounds.push((name.clone(), traits));
+                    }
+                    generics.push(name);
+                }
+                (generics, bounds)
+            })
+    };
+
     let function = pub_keyword
         .clone()
         .then(function_keyword.clone())
         .then(identifier_parser())
+        .then(generic_params())
         .then(function_params)
         .then(function_ret_type)
         .then_ignore(just(TokenKind::Colon))
         .then_ignore(newline.clone())
         .then(block.clone())
-        .map_with_span(|(((((pub_kw, _fn), name), params), ret_ty), body), span| {
-            Node::new(
-                if pub_kw.is_some() {
+        .map_with_span(
+            |((((((pub_kw, _fn), name), (generics, bounds)), params), ret_ty), body), span| {
+                let function = if pub_kw.is_some() {
                     Function::new_public(name, params, ret_ty, body)
                 } else {
                     Function::new(name, params, ret_ty, body)
-                },
-                span,
-            )
-        })
+                };
+                Node::new(function.with_generics(generics, bounds), span)
+            },
+        )
         .map_with_span(|func, span| Node::new(Statement::Function(func), span))
         .then_ignore(newline.clone().or_not())
         .boxed();
@@ -1246,15 +2132,6 @@ fn program_parser() -> impl Parser<TokenKind, Program, Error = Simple<TokenKind>
     //     field: Type
     //     fn method(self, ...) -> ReturnType:
     //         ...
-    let struct_generics = || {
-        identifier_parser()
-            .separated_by(just(TokenKind::Comma))
-            .allow_trailing()
-            .delimited_by(just(TokenKind::Lt), just(TokenKind::Gt))
-            .or_not()
-            .map(|params| params.unwrap_or_default())
-    };
-
     let enum_variant_name = choice((
         identifier_parser(),
         just(TokenKind::None).to("None".to_string()),
@@ -1302,17 +2179,29 @@ fn program_parser() -> impl Parser<TokenKind, Program, Error = Simple<TokenKind>
 
     // Method definition (fn method(self, ...) -> ReturnType: ...)
     // Recreate parsers for method definition
-    let method_function_param = identifier_parser()
-        .map_with_span(Node::new)
-        .then(choice((
-            just(TokenKind::Colon).ignore_then(type_parser()).map(Some),
-            empty().to(None),
-        )))
-        .then(choice((
-            just(TokenKind::Equals).ignore_then(expr.clone()).map(Some),
-            empty().to(None),
-        )))
-        .map_with_span(|((name, ty), default), span| Node::new(Param::new(name, ty, default), span))
+    let method_function_param = just(TokenKind::Star)
+        .ignore_then(identifier_parser().map_with_span(Node::new))
+        .then(just(TokenKind::Colon).ignore_then(type_parser()).or_not())
+        .map_with_span(|(name, element), span| Node::new(Param::variadic(name, element), span))
+        .or(param_pattern_parser()
+            .then(just(TokenKind::Colon).ignore_then(type_parser()).or_not())
+            .then(just(TokenKind::Equals).ignore_then(expr.clone()).or_not())
+            .map_with_span(|((pattern, ty), default), span| {
+                Node::new(Param::destructured(pattern, ty, default), span)
+            }))
+        .or(identifier_parser()
+            .map_with_span(Node::new)
+            .then(choice((
+                just(TokenKind::Colon).ignore_then(type_parser()).map(Some),
+                empty().to(None),
+            )))
+            .then(choice((
+                just(TokenKind::Equals).ignore_then(expr.clone()).map(Some),
+                empty().to(None),
+            )))
+            .map_with_span(|((name, ty), default), span| {
+                Node::new(Param::new(name, ty, default), span)
+            }))
         .boxed();
 
     let method_function_params = method_function_param
@@ -1321,42 +2210,40 @@ fn program_parser() -> impl Parser<TokenKind, Program, Error = Simple<TokenKind>
         .delimited_by(just(TokenKind::LParen), just(TokenKind::RParen))
         .or_not()
         .map(|params| params.unwrap_or_default())
+        .try_map(check_variadic_params)
         .boxed();
 
-    let method_function_ret_type = just(TokenKind::Arrow).ignore_then(type_parser()).or_not();
+    let method_function_ret_type = just(TokenKind::Arrow)
+        .ignore_then(type_parser())
+        .or_not()
+        .boxed();
 
-    let struct_method_def = function_keyword
-        .clone()
+    // `static fn` declares an associated function, which is called on the type and has no
+    // `self` parameter
+    let method_def = just(TokenKind::Identifier("static".to_string()))
+        .or_not()
+        .then_ignore(function_keyword.clone())
         .then(identifier_parser())
-        .then(method_function_params)
-        .then(method_function_ret_type)
+        .then(method_function_params.clone())
+        .then(method_function_ret_type.clone())
         .then_ignore(just(TokenKind::Colon))
         .then_ignore(newline.clone())
         .then(block.clone())
-        .map_with_span(|((((_kw, name), params), ret_ty), body), span| {
-            // Methods automatically get 'self' as first parameter if not present
-            let mut method_params = params;
-            if method_params.is_empty() || method_params[0].as_ref().name.as_ref() != "self" {
-                // Add self parameter at the beginning
-                let self_type = Type::Simple("Self".to_string());
-                let self_span = Span::new(span.start + name.len() + 1, span.start + name.len() + 5);
-                let self_type_span = Span::new(self_span.start(), self_span.start());
-                let self_param = Node::new(
-                    Param::new(
-                        Node::new("self".to_string(), self_span),
-                        Some(Node::new(self_type, self_type_span)),
-                        None,
-                    ),
-                    self_span,
-                );
-                method_params.insert(0, self_param);
-            }
+        .map_with_span(|((((is_static, name), params), ret_ty), body), span| {
+            let method_params = match is_static {
+                Some(_) => params,
+                None => ensure_self_param(&name, params, span.start),
+            };
             Node::new(Function::new(name, method_params, ret_ty, body), span)
         })
-        .map(|method| (None::<(String, Node<Type>)>, Some(method)))
         .then_ignore(newline.clone().or_not())
         .boxed();
 
+    let struct_method_def = method_def
+        .clone()
+        .map(|method| (None::<(String, Node<Type>)>, Some(method)))
+        .boxed();
+
     let struct_body = choice((struct_field_def, struct_method_def))
         .repeated()
         .at_least(0)
@@ -1375,17 +2262,35 @@ fn program_parser() -> impl Parser<TokenKind, Program, Error = Simple<TokenKind>
             (fields, methods)
         });
 
-    let struct_def = pub_keyword
+    // `@derive(Eq, Hash)` lines in front of a struct or enum; the type checker validates the
+    // names and the code generator emits the methods
+    let derive_attributes = just(TokenKind::At)
+        .ignore_then(just(TokenKind::Identifier("derive".to_string())))
+        .ignore_then(
+            identifier_parser()
+                .map_with_span(Node::new)
+                .separated_by(just(TokenKind::Comma))
+                .allow_trailing()
+                .at_least(1)
+                .delimited_by(just(TokenKind::LParen), just(TokenKind::RParen)),
+        )
+        .then_ignore(newline.clone())
+        .repeated()
+        .flatten()
+        .boxed();
+
+    let struct_def = derive_attributes
         .clone()
+        .then(pub_keyword.clone())
         .then(just(TokenKind::Struct))
         .then(identifier_parser())
-        .then(struct_generics())
+        .then(generic_params())
         .then_ignore(just(TokenKind::Colon))
         .then_ignore(newline.clone())
         .then(struct_body.delimited_by(just(TokenKind::Indent), just(TokenKind::Dedent)))
         .then_ignore(newline.clone().or_not())
         .map_with_span(
-            |((((pub_kw, _), name), generics), (fields, methods)), span| {
+            |(((((derives, pub_kw), _), name), (generics, bounds)), (fields, methods)), span| {
                 Node::new(
                     Statement::Struct {
                         name,
@@ -1393,6 +2298,8 @@ fn program_parser() -> impl Parser<TokenKind, Program, Error = Simple<TokenKind>
                         methods,
                         public: pub_kw.is_some(),
                         generics,
+                        bounds,
+                        derives,
                     },
                     span,
                 )
@@ -1400,28 +2307,122 @@ fn program_parser() -> impl Parser<TokenKind, Program, Error = Simple<TokenKind>
         )
         .boxed();
 
-    let enum_def = pub_keyword
+    // Trait declaration:
+    // trait Shape:
+    //     fn area(self) -> float
+    //     fn describe(self) -> string:
+    //         return f"shape with area {self.area()}"
+    let trait_method_def = function_keyword
         .clone()
-        .then(just(TokenKind::Enum))
         .then(identifier_parser())
-        .then(struct_generics())
+        .then(method_function_params)
+        .then(method_function_ret_type)
+        .then(choice((
+            just(TokenKind::Colon)
+                .ignore_then(newline.clone())
+                .ignore_then(block.clone()),
+            newline
+                .clone()
+                .map_with_span(|_, span| Node::new(Block::new(Vec::new()), span)),
+        )))
+        .map_with_span(|((((_kw, name), params), ret_ty), body), span| {
+            let method_params = ensure_self_param(&name, params, span.start);
+            Node::new(Function::new(name, method_params, ret_ty, body), span)
+        })
+        .then_ignore(newline.clone().or_not())
+        .boxed();
+
+    let trait_def = pub_keyword
+        .clone()
+        .then_ignore(just(TokenKind::Identifier("trait".to_string())))
+        .then(identifier_parser())
         .then_ignore(just(TokenKind::Colon))
         .then_ignore(newline.clone())
-        .then(enum_body.delimited_by(just(TokenKind::Indent), just(TokenKind::Dedent)))
+        .then(
+            trait_method_def
+                .repeated()
+                .at_least(1)
+                .delimited_by(just(TokenKind::Indent), just(TokenKind::Dedent)),
+        )
         .then_ignore(newline.clone().or_not())
-        .map_with_span(|((((pub_kw, _), name), generics), variants), span| {
+        .map_with_span(|((pub_kw, name), methods), span| {
             Node::new(
-                Statement::Enum {
+                Statement::Trait {
                     name,
-                    variants,
+                    methods,
                     public: pub_kw.is_some(),
-                    generics,
                 },
                 span,
             )
         })
         .boxed();
 
+    // Trait implementation; the body may be omitted when every method has a default:
+    // impl Shape for Circle:
+    //     fn area(self) -> float:
+    //         return 3.14 * self.r * self.r
+    // Without `Trait for`, the block adds methods to a struct or enum: `impl Circle:`
+    let impl_def = just(TokenKind::Identifier("impl".to_string()))
+        .ignore_then(identifier_parser())
+        .then(
+            just(TokenKind::For)
+                .ignore_then(identifier_parser())
+                .or_not(),
+        )
+        .map(|(first, second)| match second {
+            Some(type_name) => (Some(first), type_name),
+            None => (None, first),
+        })
+        .then(
+            just(TokenKind::Colon)
+                .ignore_then(newline.clone())
+                .ignore_then(
+                    method_def
+                        .repeated()
+                        .at_least(1)
+                        .delimited_by(just(TokenKind::Indent), just(TokenKind::Dedent)),
+                )
+                .or_not(),
+        )
+        .then_ignore(newline.clone().or_not())
+        .map_with_span(|((trait_name, type_name), methods), span| {
+            Node::new(
+                Statement::Impl {
+                    trait_name,
+                    type_name,
+                    methods: methods.unwrap_or_default(),
+                },
+                span,
+            )
+        })
+        .boxed();
+
+    let enum_def = derive_attributes
+        .then(pub_keyword.clone())
+        .then(just(TokenKind::Enum))
+        .then(identifier_parser())
+        .then(generic_params())
+        .then_ignore(just(TokenKind::Colon))
+        .then_ignore(newline.clone())
+        .then(enum_body.delimited_by(just(TokenKind::Indent), just(TokenKind::Dedent)))
+        .then_ignore(newline.clone().or_not())
+        .map_with_span(
+            |(((((derives, pub_kw), _), name), (generics, bounds)), variants), span| {
+                Node::new(
+                    Statement::Enum {
+                        name,
+                        variants,
+                        public: pub_kw.is_some(),
+                        generics,
+                        bounds,
+                        derives,
+                    },
+                    span,
+                )
+            },
+        )
+        .boxed();
+
     // Type alias: type Name<T> = Type
     let type_alias_generics = identifier_parser()
         .separated_by(just(TokenKind::Comma))
@@ -1452,10 +2453,44 @@ fn program_parser() -> impl Parser<TokenKind, Program, Error = Simple<TokenKind>
         })
         .boxed();
 
+    let const_def = pub_keyword
+        .clone()
+        .then(just(TokenKind::Identifier("const".to_string())))
+        .then(identifier_parser())
+        .then_ignore(just(TokenKind::Colon))
+        .then(type_parser())
+        .then_ignore(just(TokenKind::Equals))
+        .then(expr.clone())
+        .then_ignore(newline.clone().or_not())
+        .map_with_span(|((((pub_kw, _), name), ty), expr), span| {
+            Node::new(
+                Statement::Const {
+                    name,
+                    ty,
+                    expr,
+                    public: pub_kw.is_some(),
+                },
+                span,
+            )
+        })
+        .boxed();
+
     newline
         .clone()
         .or_not()
-        .ignore_then(choice((struct_def, enum_def, type_alias_def, function, statement)).repeated())
+        .ignore_then(
+            choice((
+                struct_def,
+                enum_def,
+                trait_def,
+                impl_def,
+                type_alias_def,
+                const_def,
+                function,
+                statement,
+            ))
+            .repeated(),
+        )
         .then_ignore(newline.repeated().or_not())
         .then_ignore(just(TokenKind::Eof))
         .map(Program::new)
@@ -1515,4 +2550,872 @@ mod tests {
         let tokens = otterc_lexer::tokenize(source).expect("tokenize enum demo");
         parse(&tokens).expect("parse enum demo");
     }
+
+    #[test]
+    fn parses_index_slice_and_index_assignment() {
+        let source = "let x = xs[0] + m[\"k\"]\nlet s = name[1:]\nxs[i] += 2\n";
+        let tokens = otterc_lexer::tokenize(source).expect("tokenize indexing");
+        let program = parse(&tokens).expect("parse indexing");
+
+        assert_eq!(program.statements.len(), 3);
+        match program.statements[1].as_ref() {
+            Statement::Let { expr, .. } => match expr.as_ref() {
+                Expr::Slice { start, end, .. } => {
+                    assert!(start.is_some());
+                    assert!(end.is_none());
+                }
+                other => panic!("expected slice expression, got {:?}", other),
+            },
+            other => panic!("expected let statement, got {:?}", other),
+        }
+        match program.statements[2].as_ref() {
+            Statement::Assignment { target, op, .. } => {
+                assert!(matches!(target.as_ref(), Expr::Index { .. }));
+                assert_eq!(*op, Some(BinaryOp::Add));
+            }
+            other => panic!("expected index assignment, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_place_assignments() {
+        let source = "p.x = 1\na.b[i].c += 2\n";
+        let tokens = otterc_lexer::tokenize(source).expect("tokenize place assignments");
+        let program = parse(&tokens).expect("parse place assignments");
+
+        assert_eq!(program.statements.len(), 2);
+        match program.statements[1].as_ref() {
+            Statement::Assignment { target, op, expr } => {
+                match target.as_ref() {
+                    Expr::Member { object, field } => {
+                        assert_eq!(field, "c");
+                        assert!(matches!(object.as_ref().as_ref(), Expr::Index { .. }));
+                    }
+                    other => panic!("expected member target, got {:?}", other),
+                }
+                assert_eq!(*op, Some(BinaryOp::Add));
+                assert!(matches!(expr.as_ref(), Expr::Literal(_)));
+            }
+            other => panic!("expected assignment, got {:?}", other),
+        }
+
+        let tokens = otterc_lexer::tokenize("f() = 1\n").expect("tokenize call target");
+        assert!(parse(&tokens).is_err());
+    }
+
+    #[test]
+    fn parses_lambda_expressions() {
+        let source = "let f: fn(int) -> int = fn(x) => x + 1\nlet g = fn(a: int, b: int) -> int:\n    let s = a + b\n    return s\n";
+        let tokens = otterc_lexer::tokenize(source).expect("tokenize lambdas");
+        let program = parse(&tokens).expect("parse lambdas");
+
+        assert_eq!(program.statements.len(), 2);
+        match program.statements[0].as_ref() {
+            Statement::Let { ty, expr, .. } => {
+                assert!(matches!(
+                    ty.as_ref().map(|ty| ty.as_ref()),
+                    Some(Type::Function { params, ret: Some(_) }) if params.len() == 1
+                ));
+                match expr.as_ref() {
+                    Expr::Lambda { params, body, .. } => {
+                        assert_eq!(params.len(), 1);
+                        assert!(matches!(
+                            body.as_ref().statements[0].as_ref(),
+                            Statement::Return(Some(_))
+                        ));
+                    }
+                    other => panic!("expected lambda, got {:?}", other),
+                }
+            }
+            other => panic!("expected let statement, got {:?}", other),
+        }
+        match program.statements[1].as_ref() {
+            Statement::Let { expr, .. } => match expr.as_ref() {
+                Expr::Lambda {
+                    params,
+                    ret_ty,
+                    body,
+                } => {
+                    assert_eq!(params.len(), 2);
+                    assert!(ret_ty.is_some());
+                    assert_eq!(body.as_ref().statements.len(), 2);
+                }
+                other => panic!("expected lambda, got {:?}", other),
+            },
+            other => panic!("expected let statement, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_control_flow_in_closure_bodies() {
+        let source = "let f = fn(x: int):\n    if x > 0:\n        return \"a\"\n    elif x < 0:\n        for i in [1]:\n            return \"b\"\n    else:\n        while x == 0:\n            return \"c\"\n    return \"d\"\n";
+        let tokens = otterc_lexer::tokenize(source).expect("tokenize closure");
+        let program = parse(&tokens).expect("parse closure");
+
+        match program.statements[0].as_ref() {
+            Statement::Let { expr, .. } => match expr.as_ref() {
+                Expr::Lambda { body, .. } => {
+                    let statements = &body.as_ref().statements;
+                    assert_eq!(statements.len(), 2);
+                    match statements[0].as_ref() {
+                        Statement::If {
+                            elif_blocks,
+                            else_block: Some(else_block),
+                            ..
+                        } => {
+                            assert!(matches!(
+                                elif_blocks[0].1.as_ref().statements[0].as_ref(),
+                                Statement::For { .. }
+                            ));
+                            assert!(matches!(
+                                else_block.as_ref().statements[0].as_ref(),
+                                Statement::While { .. }
+                            ));
+                        }
+                        other => panic!("expected if statement, got {:?}", other),
+                    }
+                }
+                other => panic!("expected lambda, got {:?}", other),
+            },
+            other => panic!("expected let statement, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_traits_impls_and_bounds() {
+        let source = "trait Shape:\n    fn area(self) -> float\n    fn name(self) -> str:\n        return \"shape\"\nstruct Holder<T: Shape + Named, U>:\n    item: T\nimpl Shape for Holder:\n    fn area(self) -> float:\n        return 1.0\nimpl Named for Holder\nlet s: dyn Shape = make()\n";
+        let tokens = otterc_lexer::tokenize(source).expect("tokenize traits");
+        let program = parse(&tokens).expect("parse traits");
+
+        assert_eq!(program.statements.len(), 5);
+        match program.statements[0].as_ref() {
+            Statement::Trait { name, methods, .. } => {
+                assert_eq!(name, "Shape");
+                assert_eq!(methods.len(), 2);
+                assert!(methods[0].as_ref().body.as_ref().is_empty());
+                assert!(!methods[1].as_ref().body.as_ref().is_empty());
+            }
+            other => panic!("expected trait, got {:?}", other),
+        }
+        match program.statements[1].as_ref() {
+            Statement::Struct {
+                generics, bounds, ..
+            } => {
+                assert_eq!(generics, &vec!["T".to_string(), "U".to_string()]);
+                assert_eq!(
+                    bounds,
+                    &vec![(
+                        "T".to_string(),
+                        vec!["Shape".to_string(), "Named".to_string()]
+                    )]
+                );
+            }
+            other => panic!("expected struct, got {:?}", other),
+        }
+        match program.statements[2].as_ref() {
+            Statement::Impl {
+                trait_name,
+                type_name,
+                methods,
+            } => {
+                assert_eq!(trait_name.as_deref(), Some("Shape"));
+                assert_eq!(type_name, "Holder");
+                assert_eq!(methods.len(), 1);
+            }
+            other => panic!("expected impl, got {:?}", other),
+        }
+        assert!(matches!(
+            program.statements[3].as_ref(),
+            Statement::Impl { methods, .. } if methods.is_empty()
+        ));
+        assert!(matches!(
+            program.statements[4].as_ref(),
+            Statement::Let { ty: Some(ty), .. } if matches!(ty.as_ref(), Type::Dyn(name) if name == "Shape")
+        ));
+    }
+
+    #[test]
+    fn parses_associated_functions_and_inherent_impls() {
+        let source = "struct Point:\n    x: float\n    static fn origin() -> Self:\n        return Point(x=0.0)\n    fn norm(self) -> float:\n        return self.x\nimpl Shape:\n    static fn unit() -> Shape:\n        return Shape.Circle(1.0)\n    fn area(self) -> float:\n        return 1.0\nlet p = Point.origin()\n";
+        let tokens = otterc_lexer::tokenize(source).expect("tokenize associated functions");
+        let program = parse(&tokens).expect("parse associated functions");
+        match program.statements[0].as_ref() {
+            Statement::Struct { methods, .. } => {
+                assert!(!methods[0].as_ref().takes_self());
+                assert!(methods[0].as_ref().params.is_empty());
+                assert!(methods[1].as_ref().takes_self());
+            }
+            other => panic!("expected struct, got {:?}", other),
+        }
+        match program.statements[1].as_ref() {
+            Statement::Impl {
+                trait_name,
+                type_name,
+                methods,
+            } => {
+                assert_eq!(trait_name, &None);
+                assert_eq!(type_name, "Shape");
+                assert!(!methods[0].as_ref().takes_self());
+                assert!(methods[1].as_ref().takes_self());
+            }
+            other => panic!("expected impl, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_derive_attributes() {
+        let source = "@derive(Eq, Hash)\n@derive(Debug)\nstruct Point:\n    x: int\n    fn norm(self) -> int:\n        return self.x\n@derive(Ord, Clone)\nenum Shape:\n    Dot\n    Circle: (float)\n";
+        let tokens = otterc_lexer::tokenize(source).expect("tokenize derives");
+        let program = parse(&tokens).expect("parse derives");
+        let names = |derives: &[Node<String>]| {
+            derives
+                .iter()
+                .map(|derive| derive.as_ref().clone())
+                .collect::<Vec<_>>()
+        };
+        match program.statements[0].as_ref() {
+            Statement::Struct {
+                derives, methods, ..
+            } => {
+                assert_eq!(names(derives), ["Eq", "Hash", "Debug"]);
+                assert_eq!(
+                    &source[derives[2].span().start()..derives[2].span().end()],
+                    "Debug"
+                );
+                // Derived methods are not part of the tree
+                assert_eq!(methods.len(), 1);
+            }
+            other => panic!("expected struct, got {:?}", other),
+        }
+        match program.statements[1].as_ref() {
+            Statement::Enum { derives, .. } => assert_eq!(names(derives), ["Ord", "Clone"]),
+            other => panic!("expected enum, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_tuples_and_destructuring() {
+        let source = "fn divmod(a: int, b: int) -> (int, int):\n    return (a / b, a % b)\nlet q, r = (1, \"one\")\nlet single = (1,)\nlet (q, _) = divmod(7, 2)\nfor k, v in pairs:\n    pass\nlet x = match t:\n    case (0, y):\n        y\n    case _:\n        (x)\n";
+        let tokens = otterc_lexer::tokenize(source).expect("tokenize tuples");
+        let program = parse(&tokens).expect("parse tuples");
+
+        match program.statements[0].as_ref() {
+            Statement::Function(func) => {
+                let ret = func.as_ref().ret_ty.as_ref().expect("return type");
+                assert!(matches!(ret.as_ref(), Type::Tuple(elems) if elems.len() == 2));
+            }
+            other => panic!("expected function, got {:?}", other),
+        }
+        match program.statements[1].as_ref() {
+            Statement::Let { pattern, expr, .. } => {
+                assert!(matches!(pattern.as_ref(), Pattern::Tuple(elems) if elems.len() == 2));
+                assert!(matches!(expr.as_ref(), Expr::Tuple(elems) if elems.len() == 2));
+            }
+            other => panic!("expected let statement, got {:?}", other),
+        }
+        assert!(matches!(
+            program.statements[2].as_ref(),
+            Statement::Let { expr, .. } if matches!(expr.as_ref(), Expr::Tuple(elems) if elems.len() == 1)
+        ));
+        match program.statements[3].as_ref() {
+            Statement::Let { pattern, .. } => match pattern.as_ref() {
+                Pattern::Tuple(elems) => {
+                    assert!(matches!(elems[1].as_ref(), Pattern::Wildcard));
+                }
+                other => panic!("expected tuple pattern, got {:?}", other),
+            },
+            other => panic!("expected let statement, got {:?}", other),
+        }
+        assert!(matches!(
+            program.statements[4].as_ref(),
+            Statement::For { pattern, .. } if matches!(pattern.as_ref(), Pattern::Tuple(_))
+        ));
+        match program.statements[5].as_ref() {
+            Statement::Let { expr, .. } => match expr.as_ref() {
+                Expr::Match { arms, .. } => {
+                    assert!(matches!(
+                        arms[0].as_ref().pattern.as_ref(),
+                        Pattern::Tuple(elems) if matches!(elems[0].as_ref(), Pattern::Literal(_))
+                    ));
+                }
+                other => panic!("expected match expression, got {:?}", other),
+            },
+            other => panic!("expected let statement, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_try_operator() {
+        let source = "let n = parse(s)?.value + lookup(m, k)?\n";
+        let tokens = otterc_lexer::tokenize(source).expect("tokenize try operator");
+        let program = parse(&tokens).expect("parse try operator");
+
+        match program.statements[0].as_ref() {
+            Statement::Let { expr, .. } => match expr.as_ref() {
+                Expr::Binary { left, right, .. } => {
+                    match left.as_ref().as_ref() {
+                        Expr::Member { object, field } => {
+                            assert_eq!(field, "value");
+                            assert!(matches!(object.as_ref().as_ref(), Expr::Try(inner)
+                                if matches!(inner.as_ref().as_ref(), Expr::Call { .. })));
+                        }
+                        other => panic!("expected member access, got {:?}", other),
+                    }
+                    assert!(matches!(right.as_ref().as_ref(), Expr::Try(_)));
+                }
+                other => panic!("expected binary expression, got {:?}", other),
+            },
+            other => panic!("expected let statement, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_try_except_finally() {
+        let source = "fn main():\n    try:\n        risky()\n    except ParseError as e:\n        raise\n    except:\n        pass\n    finally:\n        raise \"done\"\n    let r = try(risky)\n";
+        let tokens = otterc_lexer::tokenize(source).expect("tokenize try statement");
+        let program = parse(&tokens).expect("parse try statement");
+
+        let body = match program.statements[0].as_ref() {
+            Statement::Function(func) => &func.as_ref().body.as_ref().statements,
+            other => panic!("expected function, got {:?}", other),
+        };
+        match body[0].as_ref() {
+            Statement::Try {
+                handlers,
+                finally_block,
+                ..
+            } => {
+                assert_eq!(handlers.len(), 2);
+                let typed = handlers[0].as_ref();
+                assert_eq!(typed.exception_type.as_deref(), Some("ParseError"));
+                assert_eq!(typed.binding.as_deref(), Some("e"));
+                assert!(matches!(
+                    typed.body.as_ref().statements[0].as_ref(),
+                    Statement::Raise(None)
+                ));
+                assert!(handlers[1].as_ref().exception_type.is_none());
+                let finally_block = finally_block.as_ref().expect("finally block");
+                assert!(matches!(
+                    finally_block.as_ref().statements[0].as_ref(),
+                    Statement::Raise(Some(_))
+                ));
+            }
+            other => panic!("expected try statement, got {:?}", other),
+        }
+        assert!(matches!(body[1].as_ref(), Statement::Let { .. }));
+    }
+
+    #[test]
+    fn parses_with_and_defer() {
+        let source = "fn main():\n    with net.dial(addr) as conn:\n        net.send(conn, msg)\n    with guard():\n        pass\n    defer println(\"bye\")\n    let n = x as i64\n";
+        let tokens = otterc_lexer::tokenize(source).expect("tokenize with statement");
+        let program = parse(&tokens).expect("parse with statement");
+
+        let body = match program.statements[0].as_ref() {
+            Statement::Function(func) => &func.as_ref().body.as_ref().statements,
+            other => panic!("expected function, got {:?}", other),
+        };
+        match body[0].as_ref() {
+            Statement::With {
+                expr,
+                binding,
+                body,
+            } => {
+                assert!(matches!(expr.as_ref(), Expr::Call { .. }));
+                assert_eq!(binding.as_deref(), Some("conn"));
+                assert_eq!(body.as_ref().statements.len(), 1);
+            }
+            other => panic!("expected with statement, got {:?}", other),
+        }
+        assert!(matches!(
+            body[1].as_ref(),
+            Statement::With { binding: None, .. }
+        ));
+        assert!(matches!(
+            body[2].as_ref(),
+            Statement::Defer(expr) if matches!(expr.as_ref(), Expr::Call { .. })
+        ));
+        assert!(matches!(
+            body[3].as_ref(),
+            Statement::Let { expr, .. } if matches!(expr.as_ref(), Expr::Cast { .. })
+        ));
+
+        let source = "fn main():\n    defer(cleanup)\n";
+        let tokens = otterc_lexer::tokenize(source).expect("tokenize defer call");
+        let program = parse(&tokens).expect("parse defer call");
+        let body = match program.statements[0].as_ref() {
+            Statement::Function(func) => &func.as_ref().body.as_ref().statements,
+            other => panic!("expected function, got {:?}", other),
+        };
+        assert!(matches!(
+            body[0].as_ref(),
+            Statement::Expr(expr) if matches!(expr.as_ref(), Expr::Call { .. })
+        ));
+    }
+
+    #[test]
+    fn parses_loop_labels_and_break_values() {
+        let source = "fn main():\n    outer: for row in rows:\n        while true:\n            if done:\n                break outer\n            continue outer\n    let n = loop:\n        break total\n    loop:\n        break\n";
+        let tokens = otterc_lexer::tokenize(source).expect("tokenize loop labels");
+        let program = parse(&tokens).expect("parse loop labels");
+
+        let body = match program.statements[0].as_ref() {
+            Statement::Function(func) => &func.as_ref().body.as_ref().statements,
+            other => panic!("expected function, got {:?}", other),
+        };
+        let inner = match body[0].as_ref() {
+            Statement::For { label, body, .. } => {
+                assert_eq!(label.as_deref(), Some("outer"));
+                match body.as_ref().statements[0].as_ref() {
+                    Statement::While {
+                        label: None, body, ..
+                    } => body.as_ref().statements.clone(),
+                    other => panic!("expected while loop, got {:?}", other),
+                }
+            }
+            other => panic!("expected for loop, got {:?}", other),
+        };
+        match inner[0].as_ref() {
+            Statement::If { then_block, .. } => assert!(matches!(
+                then_block.as_ref().statements[0].as_ref(),
+                Statement::Break { label: Some(label), value: None } if label == "outer"
+            )),
+            other => panic!("expected if statement, got {:?}", other),
+        }
+        assert!(matches!(
+            inner[1].as_ref(),
+            Statement::Continue { label: Some(label) } if label == "outer"
+        ));
+
+        // Without a loop of that name, `break total` carries the value `total`
+        match body[1].as_ref() {
+            Statement::Let { expr, .. } => match expr.as_ref() {
+                Expr::Loop { label: None, body } => assert!(matches!(
+                    body.as_ref().statements[0].as_ref(),
+                    Statement::Break { label: None, value: Some(value) }
+                        if matches!(value.as_ref(), Expr::Identifier(name) if name == "total")
+                )),
+                other => panic!("expected loop expression, got {:?}", other),
+            },
+            other => panic!("expected let statement, got {:?}", other),
+        }
+        assert!(matches!(
+            body[2].as_ref(),
+            Statement::Expr(expr) if matches!(expr.as_ref(), Expr::Loop { label: None, .. })
+        ));
+    }
+
+    #[test]
+    fn parses_or_range_and_binding_patterns() {
+        let source = "let r = match v:\n    case 1 | 2 | 3:\n        0\n    case -5..0:\n        1\n    case \"a\"..=\"z\":\n        2\n    case p @ Point(x=0, y):\n        3\n    case n @ (4 | 5):\n        4\n";
+        let tokens = otterc_lexer::tokenize(source).expect("tokenize patterns");
+        let program = parse(&tokens).expect("parse patterns");
+
+        let arms = match program.statements[0].as_ref() {
+            Statement::Let { expr, .. } => match expr.as_ref() {
+                Expr::Match { arms, .. } => arms,
+                other => panic!("expected match expression, got {:?}", other),
+            },
+            other => panic!("expected let statement, got {:?}", other),
+        };
+        let patterns: Vec<&Pattern> = arms
+            .iter()
+            .map(|arm| arm.as_ref().pattern.as_ref())
+            .collect();
+
+        assert!(matches!(patterns[0], Pattern::Or(alternatives) if alternatives.len() == 3));
+        match patterns[1] {
+            Pattern::Range {
+                start,
+                end,
+                inclusive: false,
+            } => {
+                assert!(matches!(start.as_ref(), Literal::Number(n) if n.int_value() == -5));
+                assert!(matches!(end.as_ref(), Literal::Number(n) if n.int_value() == 0));
+            }
+            other => panic!("expected range pattern, got {:?}", other),
+        }
+        assert!(matches!(
+            patterns[2],
+            Pattern::Range {
+                inclusive: true,
+                ..
+            }
+        ));
+        match patterns[3] {
+            Pattern::Binding { name, pattern } => {
+                assert_eq!(name, "p");
+                match pattern.as_ref().as_ref() {
+                    Pattern::Struct { name, fields } => {
+                        assert_eq!(name, "Point");
+                        assert!(matches!(&fields[0], (field, Some(_)) if field == "x"));
+                        assert!(matches!(&fields[1], (field, None) if field == "y"));
+                    }
+                    other => panic!("expected struct pattern, got {:?}", other),
+                }
+            }
+            other => panic!("expected binding pattern, got {:?}", other),
+        }
+        // Parentheses group alternatives under a single binding
+        assert!(matches!(
+            patterns[4],
+            Pattern::Binding { pattern, .. } if matches!(pattern.as_ref().as_ref(), Pattern::Or(_))
+        ));
+    }
+
+    #[test]
+    fn parses_string_prefix_patterns() {
+        let source = "let r = match v:\n    case \"GET \" + path:\n        path\n    case \"#\" + _ | \"//\" + _:\n        \"\"\n    case other:\n        other\n";
+        let tokens = otterc_lexer::tokenize(source).expect("tokenize patterns");
+        let program = parse(&tokens).expect("parse patterns");
+
+        let arms = match program.statements[0].as_ref() {
+            Statement::Let { expr, .. } => match expr.as_ref() {
+                Expr::Match { arms, .. } => arms,
+                other => panic!("expected match expression, got {:?}", other),
+            },
+            other => panic!("expected let statement, got {:?}", other),
+        };
+        match arms[0].as_ref().pattern.as_ref() {
+            Pattern::StringPrefix { prefix, rest } => {
+                assert!(matches!(prefix.as_ref(), Literal::String(text) if text.value == "GET "));
+                assert_eq!(rest.as_deref(), Some("path"));
+            }
+            other => panic!("expected prefix pattern, got {:?}", other),
+        }
+        match arms[1].as_ref().pattern.as_ref() {
+            Pattern::Or(alternatives) => assert!(alternatives.iter().all(|alternative| matches!(
+                alternative.as_ref(),
+                Pattern::StringPrefix { rest: None, .. }
+            ))),
+            other => panic!("expected or-pattern, got {:?}", other),
+        }
+
+        let tokens = otterc_lexer::tokenize("let r = match v:\n    case 1 + n:\n        n\n")
+            .expect("tokenize patterns");
+        assert!(parse(&tokens).is_err());
+    }
+
+    #[test]
+    fn parses_destructuring_let_for_and_parameters() {
+        let source = "fn norm((a, b): (int, int), Point(x=x, y), scale: int) -> int:\n    let Point{x: px, y: _} = p\n    for i, Pair(first=f) in pairs:\n        pass\n    return a\n";
+        let tokens = otterc_lexer::tokenize(source).expect("tokenize destructuring");
+        let program = parse(&tokens).expect("parse destructuring");
+
+        let function = match program.statements[0].as_ref() {
+            Statement::Function(function) => function.as_ref(),
+            other => panic!("expected function, got {:?}", other),
+        };
+        let params: Vec<&Param> = function.params.iter().map(|param| param.as_ref()).collect();
+        assert!(matches!(
+            params[0].pattern.as_ref().map(|pattern| pattern.as_ref()),
+            Some(Pattern::Tuple(elements)) if elements.len() == 2
+        ));
+        assert!(matches!(
+            params[1].pattern.as_ref().map(|pattern| pattern.as_ref()),
+            Some(Pattern::Struct { name, .. }) if name == "Point"
+        ));
+        assert!(params[1].ty.is_none());
+        assert!(params[2].pattern.is_none());
+        assert_eq!(params[2].name.as_ref(), "scale");
+
+        let body = &function.body.as_ref().statements;
+        assert!(matches!(
+            body[0].as_ref(),
+            Statement::Let { pattern, .. } if matches!(pattern.as_ref(), Pattern::Struct { .. })
+        ));
+        match body[1].as_ref() {
+            Statement::For { pattern, .. } => match pattern.as_ref() {
+                Pattern::Tuple(elements) => {
+                    assert!(
+                        matches!(elements[0].as_ref(), Pattern::Identifier(name) if name == "i")
+                    );
+                    assert!(matches!(elements[1].as_ref(), Pattern::Struct { .. }));
+                }
+                other => panic!("expected tuple pattern, got {:?}", other),
+            },
+            other => panic!("expected for loop, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn bitwise_and_power_operators_follow_precedence() {
+        fn render(expr: &Expr) -> String {
+            match expr {
+                Expr::Binary { left, op, right } => format!(
+                    "({:?} {} {})",
+                    op,
+                    render(left.as_ref().as_ref()),
+                    render(right.as_ref().as_ref())
+                ),
+                Expr::Unary { op, expr } => {
+                    format!("({:?} {})", op, render(expr.as_ref().as_ref()))
+                }
+                Expr::Identifier(name) => name.clone(),
+                Expr::Literal(lit) => match lit.as_ref() {
+                    Literal::Number(num) => num.value.to_string(),
+                    other => format!("{:?}", other),
+                },
+                other => format!("{:?}", other),
+            }
+        }
+
+        let source = "fn main():\n    let a = x | y ^ z & w << 1 + 2\n    let b = -2 ** 3 ** ~k // 4\n    let c = x >> 2 > y\n    a >>= 1\n    b **= 2\n    let xs: List<List<int>> = []\n";
+        let tokens = otterc_lexer::tokenize(source).expect("tokenize operators");
+        let program = parse(&tokens).expect("parse operators");
+
+        let body = match program.statements[0].as_ref() {
+            Statement::Function(func) => &func.as_ref().body.as_ref().statements,
+            other => panic!("expected function, got {:?}", other),
+        };
+        let rendered: Vec<String> = body
+            .iter()
+            .map(|stmt| match stmt.as_ref() {
+                Statement::Let { expr, .. } => render(expr.as_ref()),
+                Statement::Assignment {
+                    target,
+                    op: Some(op),
+                    expr,
+                } => format!(
+                    "({:?} {} {})",
+                    op,
+                    render(target.as_ref()),
+                    render(expr.as_ref())
+                ),
+                other => panic!("unexpected statement {:?}", other),
+            })
+            .collect();
+
+        assert_eq!(
+            rendered[0],
+            "(BitOr x (BitXor y (BitAnd z (Shl w (Add 1 2)))))"
+        );
+        assert_eq!(rendered[1], "(FloorDiv (Neg (Pow 2 (Pow 3 (BitNot k)))) 4)");
+        assert_eq!(rendered[2], "(Gt (Shr x 2) y)");
+        assert_eq!(rendered[3], "(Shr a 1)");
+        assert_eq!(rendered[4], "(Pow b 2)");
+        assert!(rendered[5].starts_with("Array"));
+
+        let spaced = otterc_lexer::tokenize("fn main():\n    let c = x > > 2\n")
+            .expect("tokenize spaced comparison");
+        assert!(parse(&spaced).is_err());
+    }
+
+    #[test]
+    fn parses_as_casts_between_unary_and_binary_operators() {
+        fn render(expr: &Expr) -> String {
+            match expr {
+                Expr::Binary { left, op, right } => format!(
+                    "({:?} {} {})",
+                    op,
+                    render(left.as_ref().as_ref()),
+                    render(right.as_ref().as_ref())
+                ),
+                Expr::Unary { op, expr } => {
+                    format!("({:?} {})", op, render(expr.as_ref().as_ref()))
+                }
+                Expr::Cast { expr, ty } => {
+                    format!("(as {} {:?})", render(expr.as_ref().as_ref()), ty.as_ref())
+                }
+                Expr::Identifier(name) => name.clone(),
+                other => format!("{:?}", other),
+            }
+        }
+
+        let source = "fn main():\n    let a = x + y as f64\n    let b = -x as u8 as i16\n    let c = n as u32 << k\n";
+        let tokens = otterc_lexer::tokenize(source).expect("tokenize casts");
+        let program = parse(&tokens).expect("parse casts");
+
+        let body = match program.statements[0].as_ref() {
+            Statement::Function(func) => &func.as_ref().body.as_ref().statements,
+            other => panic!("expected function, got {:?}", other),
+        };
+        let rendered: Vec<String> = body
+            .iter()
+            .map(|stmt| match stmt.as_ref() {
+                Statement::Let { expr, .. } => render(expr.as_ref()),
+                other => panic!("unexpected statement {:?}", other),
+            })
+            .collect();
+
+        assert_eq!(rendered[0], "(Add x (as y Simple(\"f64\")))");
+        assert_eq!(
+            rendered[1],
+            "(as (as (Neg x) Simple(\"u8\")) Simple(\"i16\"))"
+        );
+        assert_eq!(rendered[2], "(Shl (as n Simple(\"u32\")) k)");
+    }
+
+    #[test]
+    fn parses_const_declarations() {
+        let source = "pub const LIMIT: int = 4 * 1024\nconst NAME: str = \"otter\" + \"lang\"\n";
+        let tokens = otterc_lexer::tokenize(source).expect("tokenize const declarations");
+        let program = parse(&tokens).expect("parse const declarations");
+
+        assert_eq!(program.statements.len(), 2);
+        match program.statements[0].as_ref() {
+            Statement::Const {
+                name, ty, public, ..
+            } => {
+                assert_eq!(name, "LIMIT");
+                assert!(matches!(ty.as_ref(), Type::Simple(name) if name == "int"));
+                assert!(*public);
+            }
+            other => panic!("expected const declaration, got {:?}", other),
+        }
+        assert!(matches!(
+            program.statements[1].as_ref(),
+            Statement::Const { public: false, .. }
+        ));
+    }
+
+    #[test]
+    fn parses_generic_functions_and_instantiations() {
+        let source = "fn max<T: Ord>(a: T, b: T) -> T:\n    return a\nlet m = max<list<int>>(xs, ys)\nlet c = a < b\nlet d = (a < b) > (c)\n";
+        let tokens = otterc_lexer::tokenize(source).expect("tokenize generic functions");
+        let program = parse(&tokens).expect("parse generic functions");
+
+        match program.statements[0].as_ref() {
+            Statement::Function(func) => {
+                assert_eq!(func.as_ref().generics, vec!["T".to_string()]);
+                assert_eq!(
+                    func.as_ref().bounds,
+                    vec![("T".to_string(), vec!["Ord".to_string()])]
+                );
+            }
+            other => panic!("expected function, got {:?}", other),
+        }
+        match program.statements[1].as_ref() {
+            Statement::Let { expr, .. } => match expr.as_ref() {
+                Expr::Call {
+                    args, type_args, ..
+                } => {
+                    assert_eq!(args.len(), 2);
+                    assert_eq!(type_args.len(), 1);
+                    assert_eq!(type_args[0].as_ref().to_string(), "list<int>");
+                }
+                other => panic!("expected call, got {:?}", other),
+            },
+            other => panic!("expected let statement, got {:?}", other),
+        }
+        for statement in &program.statements[2..] {
+            assert!(matches!(
+                statement.as_ref(),
+                Statement::Let { expr, .. } if matches!(expr.as_ref(), Expr::Binary { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn parses_keyword_arguments() {
+        let source = "let a = log(msg, level=2, verbose=x == y)\nlet b = Point(x=1, y=2)\n";
+        let tokens = otterc_lexer::tokenize(source).expect("tokenize keyword arguments");
+        let program = parse(&tokens).expect("parse keyword arguments");
+
+        match program.statements[0].as_ref() {
+            Statement::Let { expr, .. } => match expr.as_ref() {
+                Expr::Call { args, kwargs, .. } => {
+                    assert_eq!(args.len(), 1);
+                    let names: Vec<&str> = kwargs.iter().map(|(name, _)| name.as_str()).collect();
+                    assert_eq!(names, vec!["level", "verbose"]);
+                    assert!(matches!(kwargs[1].1.as_ref(), Expr::Binary { .. }));
+                }
+                other => panic!("expected call, got {:?}", other),
+            },
+            other => panic!("expected let statement, got {:?}", other),
+        }
+        assert!(matches!(
+            program.statements[1].as_ref(),
+            Statement::Let { expr, .. } if matches!(expr.as_ref(), Expr::Struct { .. })
+        ));
+
+        let tokens = otterc_lexer::tokenize("log(level=2, msg)\n").expect("tokenize bad call");
+        assert!(parse(&tokens).is_err());
+    }
+
+    #[test]
+    fn parses_variadic_parameters_and_spread_arguments() {
+        let source = "fn log(fmt: string, *args: any):\n    pass\n\nlet a = log(\"x\", 1, *rest)\n";
+        let tokens = otterc_lexer::tokenize(source).expect("tokenize variadic function");
+        let program = parse(&tokens).expect("parse variadic function");
+
+        match program.statements[0].as_ref() {
+            Statement::Function(function) => {
+                let params = &function.as_ref().params;
+                assert!(!params[0].as_ref().variadic);
+                assert!(params[1].as_ref().variadic);
+                assert_eq!(
+                    params[1]
+                        .as_ref()
+                        .ty
+                        .as_ref()
+                        .map(|ty| ty.as_ref().to_string()),
+                    Some("list<any>".to_string())
+                );
+            }
+            other => panic!("expected function, got {:?}", other),
+        }
+        match program.statements[1].as_ref() {
+            Statement::Let { expr, .. } => match expr.as_ref() {
+                Expr::Call { args, .. } => {
+                    assert_eq!(args.len(), 3);
+                    assert!(matches!(args[2].as_ref(), Expr::Spread(_)));
+                }
+                other => panic!("expected call, got {:?}", other),
+            },
+            other => panic!("expected let statement, got {:?}", other),
+        }
+
+        let tokens = otterc_lexer::tokenize("fn f(*xs: int, y: int):\n    pass\n")
+            .expect("tokenize misplaced variadic parameter");
+        assert!(parse(&tokens).is_err());
+    }
+
+    #[test]
+    fn parses_string_literal_styles() {
+        let source = "let a = r\"\\w+\"\nlet b = \"\"\"x\ny\"\"\"\nlet c = b\"hi\"\nlet d = f\"\"\"\n{a}\n\"\"\"\n";
+        let tokens = otterc_lexer::tokenize(source).expect("tokenize string literals");
+        let program = parse(&tokens).expect("parse string literals");
+
+        let values: Vec<&Expr> = program
+            .statements
+            .iter()
+            .map(|statement| match statement.as_ref() {
+                Statement::Let { expr, .. } => expr.as_ref(),
+                other => panic!("expected let statement, got {:?}", other),
+            })
+            .collect();
+        match values[0] {
+            Expr::Literal(lit) => match lit.as_ref() {
+                Literal::String(s) => {
+                    assert_eq!(s.value, "\\w+");
+                    assert_eq!(s.style, StringStyle::Raw);
+                }
+                other => panic!("expected string, got {:?}", other),
+            },
+            other => panic!("expected literal, got {:?}", other),
+        }
+        match values[1] {
+            Expr::Literal(lit) => match lit.as_ref() {
+                Literal::String(s) => {
+                    assert_eq!(s.value, "x\ny");
+                    assert_eq!(s.style, StringStyle::Multiline);
+                }
+                other => panic!("expected string, got {:?}", other),
+            },
+            other => panic!("expected literal, got {:?}", other),
+        }
+        assert!(matches!(
+            values[2],
+            Expr::Literal(lit) if *lit.as_ref() == Literal::Bytes(b"hi".to_vec())
+        ));
+        match values[3] {
+            Expr::FString { parts, multiline } => {
+                assert!(*multiline);
+                assert_eq!(parts.len(), 3);
+                assert!(matches!(parts[1].as_ref(), FStringPart::Expr(_)));
+            }
+            other => panic!("expected f-string, got {:?}", other),
+        }
+    }
 }
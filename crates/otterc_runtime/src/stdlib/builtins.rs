@@ -132,6 +132,9 @@ pub static LISTS: Lazy<RwLock<std::collections::HashMap<HandleId, List>>> =
 
 struct Map {
     items: std::collections::HashMap<String, Value>,
+    /// Boxed struct or enum key of each entry stored under a slot made by
+    /// `otter_builtin_map_key_slot`
+    keys: std::collections::HashMap<String, u64>,
 }
 
 static MAPS: Lazy<RwLock<std::collections::HashMap<HandleId, Map>>> =
@@ -155,14 +158,15 @@ static STRING_ITERATORS: Lazy<RwLock<std::collections::HashMap<HandleId, StringI
 
 struct MapIterator {
     handle: HandleId,
-    keys: Vec<String>,
+    keys: Vec<Value>,
     index: usize,
 }
 
 static MAP_ITERATORS: Lazy<RwLock<std::collections::HashMap<HandleId, MapIterator>>> =
     Lazy::new(|| RwLock::new(std::collections::HashMap::new()));
 
-fn value_to_string(value: &Value) -> String {
+/// the text `str()` gives for a runtime value
+pub(crate) fn value_to_string(value: &Value) -> String {
     match value {
         Value::Unit => "None".to_string(),
         Value::Bool(b) => b.to_string(),
@@ -442,6 +446,22 @@ pub extern "C" fn otter_builtin_append_list_map(handle: u64, value_handle: u64)
     }
 }
 
+/// appends the elements of the list `source` to the list `handle`, as a call does when
+/// it spreads `*source` into a variadic parameter
+#[unsafe(no_mangle)]
+pub extern "C" fn otter_builtin_extend_list(handle: u64, source: u64) -> i32 {
+    let mut lists = LISTS.write();
+    let Some(items) = lists.get(&source).map(|list| list.items.clone()) else {
+        return 0;
+    };
+    if let Some(list) = lists.get_mut(&handle) {
+        list.items.extend(items);
+        1
+    } else {
+        0
+    }
+}
+
 // ============================================================================
 // delete(map, key) - Delete a key from a map
 // ============================================================================
@@ -461,6 +481,7 @@ pub unsafe extern "C" fn otter_builtin_delete_map(handle: u64, key: *const c_cha
 
     let mut maps = MAPS.write();
     if let Some(map) = maps.get_mut(&handle) {
+        map.keys.remove(&key_str);
         if map.items.remove(&key_str).is_some() {
             1
         } else {
@@ -540,6 +561,102 @@ pub extern "C" fn otter_builtin_enumerate_list(handle: u64) -> u64 {
     id
 }
 
+// ============================================================================
+// sort(list) - Sort a list in place
+// ============================================================================
+
+/// Natural order of runtime values: numbers by value, then bools, then strings
+fn compare_values(a: &Value, b: &Value) -> std::cmp::Ordering {
+    fn rank(value: &Value) -> u8 {
+        match value {
+            Value::Unit => 0,
+            Value::I64(_) | Value::F64(_) => 1,
+            Value::Bool(_) => 2,
+            Value::String(_) => 3,
+            Value::List(_) => 4,
+            Value::Map(_) => 5,
+        }
+    }
+    match (a, b) {
+        (Value::I64(a), Value::I64(b)) => a.cmp(b),
+        (Value::F64(a), Value::F64(b)) => a.total_cmp(b),
+        (Value::I64(a), Value::F64(b)) => (*a as f64).total_cmp(b),
+        (Value::F64(a), Value::I64(b)) => a.total_cmp(&(*b as f64)),
+        (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+        (Value::String(a), Value::String(b)) => a.cmp(b),
+        _ => rank(a).cmp(&rank(b)),
+    }
+}
+
+/// Stable merge sort that only asks whether one element is less than another, so a
+/// comparison that is not a total order cannot make it panic
+fn merge_sort_by(
+    mut items: Vec<Value>,
+    less: &mut impl FnMut(&Value, &Value) -> bool,
+) -> Vec<Value> {
+    if items.len() <= 1 {
+        return items;
+    }
+    let right = items.split_off(items.len() / 2);
+    let left = merge_sort_by(items, less);
+    let right = merge_sort_by(right, less);
+
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+    let mut left = left.into_iter().peekable();
+    let mut right = right.into_iter().peekable();
+    while let (Some(l), Some(r)) = (left.peek(), right.peek()) {
+        let next = if less(r, l) {
+            right.next()
+        } else {
+            left.next()
+        };
+        merged.extend(next);
+    }
+    merged.extend(left);
+    merged.extend(right);
+    merged
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn otter_builtin_sort_list(handle: u64) {
+    if let Some(list) = LISTS.write().get_mut(&handle) {
+        list.items.sort_by(compare_values);
+    }
+}
+
+/// Compiled comparison of two boxed elements, such as a struct's `lt` or `eq` method
+pub type BoxedCompareFn = extern "C" fn(u64, u64) -> bool;
+
+/// The boxed handle of a list element or map key as compiled code passes it
+fn boxed_handle(value: &Value) -> u64 {
+    match value {
+        Value::List(handle) | Value::Map(handle) => *handle,
+        Value::I64(value) => *value as u64,
+        _ => 0,
+    }
+}
+
+/// sorts the list `handle` with `less`, which compares two boxed elements
+///
+/// # Safety
+///
+/// `less` must be a function with that signature that accepts the list's elements
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn otter_builtin_sort_list_by(handle: u64, less: Option<BoxedCompareFn>) {
+    let Some(less) = less else {
+        return;
+    };
+    // The comparison runs compiled code that may use lists, so a copy is sorted without
+    // holding a lock and the list keeps its elements until the result is written back
+    let Some(items) = LISTS.read().get(&handle).map(|list| list.items.clone()) else {
+        return;
+    };
+    let sorted = merge_sort_by(items, &mut |a, b| less(boxed_handle(a), boxed_handle(b)));
+    if let Some(list) = LISTS.write().get_mut(&handle) {
+        list.items = sorted;
+    }
+}
+
 // ============================================================================
 // Helper functions for list/map creation
 // ============================================================================
@@ -557,6 +674,7 @@ pub extern "C" fn otter_builtin_map_new() -> u64 {
     let id = next_handle_id();
     let map = Map {
         items: std::collections::HashMap::new(),
+        keys: std::collections::HashMap::new(),
     };
     MAPS.write().insert(id, map);
     id
@@ -653,6 +771,196 @@ pub extern "C" fn otter_builtin_list_get_map(handle: u64, index: i64) -> u64 {
     }
 }
 
+// ============================================================================
+// Indexing and slicing - xs[i] = v, m[key], xs[a:b], s[i], s[a:b]
+// ============================================================================
+
+/// resolve Python-style slice bounds (negative values count from the end) into a clamped range
+pub(crate) fn slice_bounds(len: usize, start: i64, end: i64) -> (usize, usize) {
+    let clamp = |bound: i64| -> usize {
+        if bound < 0 {
+            (len as i64 + bound).max(0) as usize
+        } else {
+            (bound as usize).min(len)
+        }
+    };
+    let start = clamp(start);
+    let end = clamp(end);
+    (start, end.max(start))
+}
+
+fn set_list_value(handle: HandleId, index: i64, value: Value) -> i32 {
+    if index < 0 {
+        return 0;
+    }
+    let mut lists = LISTS.write();
+    match lists
+        .get_mut(&handle)
+        .and_then(|list| list.items.get_mut(index as usize))
+    {
+        Some(slot) => {
+            *slot = value;
+            1
+        }
+        None => 0,
+    }
+}
+
+/// replaces the string stored at `index` in a list
+///
+/// # Safety
+///
+/// this function dereferences a raw pointer
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn otter_builtin_list_set_string(
+    handle: u64,
+    index: i64,
+    val: *const c_char,
+) -> i32 {
+    if val.is_null() {
+        return 0;
+    }
+
+    let val_str = unsafe { CStr::from_ptr(val).to_str().unwrap_or("").to_string() };
+    set_list_value(handle, index, Value::String(val_str))
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn otter_builtin_list_set_int(handle: u64, index: i64, val: i64) -> i32 {
+    set_list_value(handle, index, Value::I64(val))
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn otter_builtin_list_set_float(handle: u64, index: i64, val: f64) -> i32 {
+    set_list_value(handle, index, Value::F64(val))
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn otter_builtin_list_set_bool(handle: u64, index: i64, val: bool) -> i32 {
+    set_list_value(handle, index, Value::Bool(val))
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn otter_builtin_list_set_list(handle: u64, index: i64, value_handle: u64) -> i32 {
+    set_list_value(handle, index, Value::List(value_handle))
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn otter_builtin_list_set_map(handle: u64, index: i64, value_handle: u64) -> i32 {
+    set_list_value(handle, index, Value::Map(value_handle))
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn otter_builtin_list_slice(handle: u64, start: i64, end: i64) -> u64 {
+    let items = {
+        let lists = LISTS.read();
+        lists
+            .get(&handle)
+            .map(|list| {
+                let (start, end) = slice_bounds(list.items.len(), start, end);
+                list.items[start..end].to_vec()
+            })
+            .unwrap_or_default()
+    };
+
+    let id = next_handle_id();
+    LISTS.write().insert(id, List { items });
+    id
+}
+
+/// check whether a map contains the key `key`
+///
+/// # Safety
+///
+/// this function dereferences a raw pointer
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn otter_builtin_map_has(handle: u64, key: *const c_char) -> bool {
+    if key.is_null() {
+        return false;
+    }
+
+    let key_str = unsafe { CStr::from_ptr(key).to_str().unwrap_or("") };
+    let maps = MAPS.read();
+    maps.get(&handle)
+        .is_some_and(|map| map.items.contains_key(key_str))
+}
+
+/// the slot of the map `handle` that stores the struct or enum `key`, whose `hash` method
+/// returned `hash`. Keys that hash the same are told apart with `eq`, and a key that is
+/// not in the map yet is given a free slot, which iteration maps back to `key`.
+///
+/// # Safety
+///
+/// `eq` must be a function with that signature that accepts `key` and the map's keys
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn otter_builtin_map_key_slot(
+    handle: u64,
+    hash: i64,
+    key: u64,
+    eq: Option<BoxedCompareFn>,
+) -> *mut c_char {
+    let first_slot = hash.to_string();
+    let slot_name = |index: usize| match index {
+        0 => first_slot.clone(),
+        n => format!("{}#{}", first_slot, n),
+    };
+    let is_candidate = |slot: &str| {
+        slot.strip_prefix(first_slot.as_str())
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('#'))
+    };
+    // `eq` runs compiled code that may use maps, so no lock is held while it compares
+    let candidates: Vec<(String, u64)> = MAPS
+        .read()
+        .get(&handle)
+        .map(|map| {
+            map.keys
+                .iter()
+                .filter(|(slot, _)| is_candidate(slot))
+                .map(|(slot, stored)| (slot.clone(), *stored))
+                .collect()
+        })
+        .unwrap_or_default();
+    let found = candidates
+        .into_iter()
+        .find(|(_, stored)| eq.is_some_and(|eq| eq(*stored, key)))
+        .map(|(slot, _)| slot);
+    let slot = found.unwrap_or_else(|| {
+        let mut maps = MAPS.write();
+        let Some(map) = maps.get_mut(&handle) else {
+            return slot_name(0);
+        };
+        // A slot whose entry was never set, as after a failed lookup, can be reused
+        let slot = (0..)
+            .map(slot_name)
+            .find(|slot| !map.items.contains_key(slot))
+            .unwrap_or_else(|| slot_name(0));
+        map.keys.insert(slot.clone(), key);
+        slot
+    });
+    CString::new(slot)
+        .map(CString::into_raw)
+        .unwrap_or(std::ptr::null_mut())
+}
+
+/// get the number of characters (not bytes) in a string
+///
+/// # Safety
+///
+/// this function dereferences a raw pointer
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn otter_builtin_str_char_len(s: *const c_char) -> i64 {
+    if s.is_null() {
+        return 0;
+    }
+
+    unsafe {
+        CStr::from_ptr(s)
+            .to_str()
+            .map(|str_ref| str_ref.chars().count() as i64)
+            .unwrap_or(0)
+    }
+}
+
 /// insert a string key-value pair into a map
 ///
 /// # Safety
@@ -1030,20 +1338,29 @@ pub extern "C" fn otter_builtin_iter_free_string(iter_handle: u64) {
 // Map Iterator
 // ============================================================================
 
+/// Keys of the map `handle`; struct and enum keys are boxed
+fn map_key_values(handle: HandleId) -> Vec<Value> {
+    let maps = MAPS.read();
+    if let Some(map) = maps.get(&handle) {
+        map.items
+            .keys()
+            .map(|slot| match map.keys.get(slot) {
+                Some(key) => Value::List(*key),
+                None => Value::String(slot.clone()),
+            })
+            .collect()
+    } else {
+        Vec::new()
+    }
+}
+
 #[unsafe(no_mangle)]
 pub extern "C" fn otter_builtin_iter_map(handle: u64) -> u64 {
     let id = next_handle_id();
-    
+
     // We snapshot the keys at iteration start
-    let keys = {
-        let maps = MAPS.read();
-        if let Some(map) = maps.get(&handle) {
-            map.items.keys().cloned().collect()
-        } else {
-            Vec::new()
-        }
-    };
-    
+    let keys = map_key_values(handle);
+
     let iter = MapIterator { handle, keys, index: 0 };
     MAP_ITERATORS.write().insert(id, iter);
     id
@@ -1066,7 +1383,7 @@ pub extern "C" fn otter_builtin_iter_next_map(iter_handle: u64) -> u64 {
         if iter.index < iter.keys.len() {
             let key = iter.keys[iter.index].clone();
             iter.index += 1;
-            encode_runtime_value(&Value::String(key))
+            encode_runtime_value(&key)
         } else {
             0
         }
@@ -1080,6 +1397,275 @@ pub extern "C" fn otter_builtin_iter_free_map(iter_handle: u64) {
     MAP_ITERATORS.write().remove(&iter_handle);
 }
 
+// ============================================================================
+// Lazy Iterators
+// `map`, `filter`, `zip` and `take` return these; elements are produced one at a time
+// as the consuming loop asks for them
+// ============================================================================
+
+/// Compiled code that appends the next element of the struct iterator at `state` to the
+/// list `out`, returning false once the iterator is exhausted
+pub type PullFn = extern "C" fn(u64, u64) -> bool;
+
+/// Compiled code that calls the closure `func` on an encoded element and appends the
+/// result to the list `out`
+pub type ApplyFn = extern "C" fn(u64, u64, u64);
+
+/// Compiled code that appends the tuple of two encoded elements to the list `out`
+pub type PairFn = extern "C" fn(u64, u64, u64);
+
+enum LazySource {
+    List {
+        handle: HandleId,
+        index: usize,
+    },
+    /// Characters of a string or keys of a map, taken when the iterator is made
+    Values(std::vec::IntoIter<Value>),
+    Pull {
+        pull: PullFn,
+        state: u64,
+    },
+    Map {
+        source: HandleId,
+        apply: ApplyFn,
+        func: u64,
+    },
+    Filter {
+        source: HandleId,
+        apply: ApplyFn,
+        predicate: u64,
+    },
+    Zip {
+        left: HandleId,
+        right: HandleId,
+        pair: PairFn,
+    },
+    Take {
+        source: HandleId,
+        remaining: i64,
+    },
+}
+
+struct LazyIterator {
+    source: LazySource,
+    /// Element produced by `has_next` that `next` has not returned yet
+    peeked: Option<Value>,
+}
+
+static LAZY_ITERATORS: Lazy<RwLock<std::collections::HashMap<HandleId, LazyIterator>>> =
+    Lazy::new(|| RwLock::new(std::collections::HashMap::new()));
+
+fn new_lazy_iterator(source: LazySource) -> u64 {
+    let id = next_handle_id();
+    let iter = LazyIterator {
+        source,
+        peeked: None,
+    };
+    LAZY_ITERATORS.write().insert(id, iter);
+    id
+}
+
+/// Runs compiled code that appends at most one value to a fresh list and takes it back.
+/// Nothing is appended when the code raises, which ends the iteration; the consuming loop
+/// then finds the exception pending.
+fn collect_one(run: impl FnOnce(u64)) -> Option<Value> {
+    let out = otter_builtin_list_new();
+    run(out);
+    let list = LISTS.write().remove(&out)?;
+    list.items.into_iter().next()
+}
+
+impl LazySource {
+    fn pull(&mut self) -> Option<Value> {
+        match self {
+            LazySource::List { handle, index } => {
+                let value = list_value(*handle, *index as i64)?;
+                *index += 1;
+                Some(value)
+            }
+            LazySource::Values(values) => values.next(),
+            LazySource::Pull { pull, state } => {
+                let mut produced = false;
+                let value = collect_one(|out| produced = pull(*state, out));
+                value.filter(|_| produced)
+            }
+            LazySource::Map {
+                source,
+                apply,
+                func,
+            } => {
+                let element = lazy_pull(*source)?;
+                collect_one(|out| apply(*func, encode_runtime_value(&element), out))
+            }
+            LazySource::Filter {
+                source,
+                apply,
+                predicate,
+            } => loop {
+                let element = lazy_pull(*source)?;
+                let keep =
+                    collect_one(|out| apply(*predicate, encode_runtime_value(&element), out))?;
+                if matches!(keep, Value::Bool(true)) {
+                    return Some(element);
+                }
+            },
+            LazySource::Zip { left, right, pair } => {
+                let left = lazy_pull(*left)?;
+                let right = lazy_pull(*right)?;
+                collect_one(|out| {
+                    pair(
+                        encode_runtime_value(&left),
+                        encode_runtime_value(&right),
+                        out,
+                    );
+                })
+            }
+            LazySource::Take { source, remaining } => {
+                if *remaining <= 0 {
+                    return None;
+                }
+                *remaining -= 1;
+                lazy_pull(*source)
+            }
+        }
+    }
+}
+
+/// Next element of the lazy iterator `handle`. The iterator leaves the registry while it
+/// runs compiled code, which may use other iterators, and is dropped once exhausted.
+fn lazy_pull(handle: HandleId) -> Option<Value> {
+    let mut iter = LAZY_ITERATORS.write().remove(&handle)?;
+    let value = iter.peeked.take().or_else(|| iter.source.pull());
+    if value.is_some() {
+        LAZY_ITERATORS.write().insert(handle, iter);
+    }
+    value
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn otter_builtin_lazy_from_list(handle: u64) -> u64 {
+    new_lazy_iterator(LazySource::List { handle, index: 0 })
+}
+
+/// # Safety
+///
+/// `ptr` must point to a valid null-terminated string
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn otter_builtin_lazy_from_string(ptr: *const c_char) -> u64 {
+    let s = unsafe { CStr::from_ptr(ptr).to_string_lossy().into_owned() };
+    let chars: Vec<Value> = s.chars().map(|c| Value::String(c.to_string())).collect();
+    new_lazy_iterator(LazySource::Values(chars.into_iter()))
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn otter_builtin_lazy_from_map(handle: u64) -> u64 {
+    new_lazy_iterator(LazySource::Values(map_key_values(handle).into_iter()))
+}
+
+/// # Safety
+///
+/// `pull` must be a function with that signature that accepts `state`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn otter_builtin_lazy_from_next(pull: Option<PullFn>, state: u64) -> u64 {
+    match pull {
+        Some(pull) => new_lazy_iterator(LazySource::Pull { pull, state }),
+        None => new_lazy_iterator(LazySource::Values(Vec::new().into_iter())),
+    }
+}
+
+/// # Safety
+///
+/// `apply` must be a function with that signature that accepts `func` and the elements of
+/// `source`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn otter_builtin_lazy_map(
+    source: u64,
+    apply: Option<ApplyFn>,
+    func: u64,
+) -> u64 {
+    match apply {
+        Some(apply) => new_lazy_iterator(LazySource::Map {
+            source,
+            apply,
+            func,
+        }),
+        None => new_lazy_iterator(LazySource::Values(Vec::new().into_iter())),
+    }
+}
+
+/// # Safety
+///
+/// `apply` must be a function with that signature that accepts `predicate` and the
+/// elements of `source`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn otter_builtin_lazy_filter(
+    source: u64,
+    apply: Option<ApplyFn>,
+    predicate: u64,
+) -> u64 {
+    match apply {
+        Some(apply) => new_lazy_iterator(LazySource::Filter {
+            source,
+            apply,
+            predicate,
+        }),
+        None => new_lazy_iterator(LazySource::Values(Vec::new().into_iter())),
+    }
+}
+
+/// # Safety
+///
+/// `pair` must be a function with that signature that accepts the elements of `left` and
+/// `right`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn otter_builtin_lazy_zip(
+    left: u64,
+    right: u64,
+    pair: Option<PairFn>,
+) -> u64 {
+    match pair {
+        Some(pair) => new_lazy_iterator(LazySource::Zip { left, right, pair }),
+        None => new_lazy_iterator(LazySource::Values(Vec::new().into_iter())),
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn otter_builtin_lazy_take(source: u64, count: i64) -> u64 {
+    new_lazy_iterator(LazySource::Take {
+        source,
+        remaining: count,
+    })
+}
+
+/// A loop iterates a lazy iterator directly, so the iterator handle is the cursor
+#[unsafe(no_mangle)]
+pub extern "C" fn otter_builtin_iter_lazy(handle: u64) -> u64 {
+    handle
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn otter_builtin_iter_has_next_lazy(iter_handle: u64) -> bool {
+    let Some(value) = lazy_pull(iter_handle) else {
+        return false;
+    };
+    if let Some(iter) = LAZY_ITERATORS.write().get_mut(&iter_handle) {
+        iter.peeked = Some(value);
+    }
+    true
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn otter_builtin_iter_next_lazy(iter_handle: u64) -> u64 {
+    lazy_pull(iter_handle)
+        .map(|value| encode_runtime_value(&value))
+        .unwrap_or(0)
+}
+
+/// Lazy iterators outlive the loop that consumes them: a loop left early leaves the rest
+/// for the next one, and exhausted iterators are already dropped
+#[unsafe(no_mangle)]
+pub extern "C" fn otter_builtin_iter_free_lazy(_iter_handle: u64) {}
+
 /// otter-lang's builtin panic function
 ///
 /// # Safety
@@ -1643,6 +2229,12 @@ fn register_builtin_symbols(registry: &SymbolRegistry) {
         signature: FfiSignature::new(vec![FfiType::List, FfiType::Map], FfiType::I32),
     });
 
+    registry.register(FfiFunction {
+        name: "extend<list>".into(),
+        symbol: "otter_builtin_extend_list".into(),
+        signature: FfiSignature::new(vec![FfiType::List, FfiType::List], FfiType::I32),
+    });
+
     // delete() function
     registry.register(FfiFunction {
         name: "delete<map>".into(),
@@ -1670,6 +2262,19 @@ fn register_builtin_symbols(registry: &SymbolRegistry) {
         signature: FfiSignature::new(vec![FfiType::List], FfiType::List),
     });
 
+    // sort() functions
+    registry.register(FfiFunction {
+        name: "sort<list>".into(),
+        symbol: "otter_builtin_sort_list".into(),
+        signature: FfiSignature::new(vec![FfiType::List], FfiType::Unit),
+    });
+
+    registry.register(FfiFunction {
+        name: "sort_by<list>".into(),
+        symbol: "otter_builtin_sort_list_by".into(),
+        signature: FfiSignature::new(vec![FfiType::List, FfiType::Opaque], FfiType::Unit),
+    });
+
     // Helper functions
     registry.register(FfiFunction {
         name: "list.new".into(),
@@ -1755,6 +2360,15 @@ fn register_builtin_symbols(registry: &SymbolRegistry) {
         signature: FfiSignature::new(vec![FfiType::Map, FfiType::Str], FfiType::Bool),
     });
 
+    registry.register(FfiFunction {
+        name: "map.key_slot".into(),
+        symbol: "otter_builtin_map_key_slot".into(),
+        signature: FfiSignature::new(
+            vec![FfiType::Map, FfiType::I64, FfiType::Opaque, FfiType::Opaque],
+            FfiType::Str,
+        ),
+    });
+
     registry.register(FfiFunction {
         name: "map.get_list".into(),
         symbol: "otter_builtin_map_get_list".into(),
@@ -1845,6 +2459,81 @@ fn register_builtin_symbols(registry: &SymbolRegistry) {
         signature: FfiSignature::new(vec![FfiType::Map, FfiType::Str, FfiType::Map], FfiType::I32),
     });
 
+    registry.register(FfiFunction {
+        name: "set<list,string>".into(),
+        symbol: "otter_builtin_list_set_string".into(),
+        signature: FfiSignature::new(
+            vec![FfiType::List, FfiType::I64, FfiType::Str],
+            FfiType::I32,
+        ),
+    });
+
+    registry.register(FfiFunction {
+        name: "set<list,int>".into(),
+        symbol: "otter_builtin_list_set_int".into(),
+        signature: FfiSignature::new(
+            vec![FfiType::List, FfiType::I64, FfiType::I64],
+            FfiType::I32,
+        ),
+    });
+
+    registry.register(FfiFunction {
+        name: "set<list,float>".into(),
+        symbol: "otter_builtin_list_set_float".into(),
+        signature: FfiSignature::new(
+            vec![FfiType::List, FfiType::I64, FfiType::F64],
+            FfiType::I32,
+        ),
+    });
+
+    registry.register(FfiFunction {
+        name: "set<list,bool>".into(),
+        symbol: "otter_builtin_list_set_bool".into(),
+        signature: FfiSignature::new(
+            vec![FfiType::List, FfiType::I64, FfiType::Bool],
+            FfiType::I32,
+        ),
+    });
+
+    registry.register(FfiFunction {
+        name: "set<list,list>".into(),
+        symbol: "otter_builtin_list_set_list".into(),
+        signature: FfiSignature::new(
+            vec![FfiType::List, FfiType::I64, FfiType::List],
+            FfiType::I32,
+        ),
+    });
+
+    registry.register(FfiFunction {
+        name: "set<list,map>".into(),
+        symbol: "otter_builtin_list_set_map".into(),
+        signature: FfiSignature::new(
+            vec![FfiType::List, FfiType::I64, FfiType::Map],
+            FfiType::I32,
+        ),
+    });
+
+    registry.register(FfiFunction {
+        name: "list.slice".into(),
+        symbol: "otter_builtin_list_slice".into(),
+        signature: FfiSignature::new(
+            vec![FfiType::List, FfiType::I64, FfiType::I64],
+            FfiType::List,
+        ),
+    });
+
+    registry.register(FfiFunction {
+        name: "map.has".into(),
+        symbol: "otter_builtin_map_has".into(),
+        signature: FfiSignature::new(vec![FfiType::Map, FfiType::Str], FfiType::Bool),
+    });
+
+    registry.register(FfiFunction {
+        name: "str.char_len".into(),
+        symbol: "otter_builtin_str_char_len".into(),
+        signature: FfiSignature::new(vec![FfiType::Str], FfiType::I64),
+    });
+
     // Error handling functions
     registry.register(FfiFunction {
         name: "panic".into(),
@@ -2001,6 +2690,7 @@ fn register_builtin_symbols(registry: &SymbolRegistry) {
         signature: FfiSignature {
             params: vec![FfiType::I64], // size
             result: FfiType::Opaque,    // ptr
+            variadic: None,
         },
     });
 
@@ -2010,6 +2700,7 @@ fn register_builtin_symbols(registry: &SymbolRegistry) {
         signature: FfiSignature {
             params: vec![FfiType::Opaque], // ptr
             result: FfiType::Unit,
+            variadic: None,
         },
     });
 
@@ -2019,6 +2710,7 @@ fn register_builtin_symbols(registry: &SymbolRegistry) {
         signature: FfiSignature {
             params: vec![FfiType::Opaque], // ptr
             result: FfiType::Unit,
+            variadic: None,
         },
     });
 
@@ -2052,6 +2744,12 @@ fn register_builtin_symbols(registry: &SymbolRegistry) {
         signature: FfiSignature::new(vec![FfiType::I64], FfiType::Bool),
     });
 
+    registry.register(FfiFunction {
+        name: "arena.create.exit".into(),
+        symbol: "otter_arena_destroy".into(),
+        signature: FfiSignature::new(vec![FfiType::I64], FfiType::Bool),
+    });
+
     registry.register(FfiFunction {
         name: "arena.reset".into(),
         symbol: "otter_arena_reset".into(),
@@ -2142,6 +2840,88 @@ fn register_builtin_symbols(registry: &SymbolRegistry) {
         signature: FfiSignature::new(vec![FfiType::Opaque], FfiType::Unit),
     });
 
+    // Lazy iterators
+    registry.register(FfiFunction {
+        name: "__otter_lazy_from_list".into(),
+        symbol: "otter_builtin_lazy_from_list".into(),
+        signature: FfiSignature::new(vec![FfiType::List], FfiType::Opaque),
+    });
+
+    registry.register(FfiFunction {
+        name: "__otter_lazy_from_string".into(),
+        symbol: "otter_builtin_lazy_from_string".into(),
+        signature: FfiSignature::new(vec![FfiType::Str], FfiType::Opaque),
+    });
+
+    registry.register(FfiFunction {
+        name: "__otter_lazy_from_map".into(),
+        symbol: "otter_builtin_lazy_from_map".into(),
+        signature: FfiSignature::new(vec![FfiType::Map], FfiType::Opaque),
+    });
+
+    registry.register(FfiFunction {
+        name: "__otter_lazy_from_next".into(),
+        symbol: "otter_builtin_lazy_from_next".into(),
+        signature: FfiSignature::new(vec![FfiType::Opaque, FfiType::Opaque], FfiType::Opaque),
+    });
+
+    registry.register(FfiFunction {
+        name: "__otter_lazy_map".into(),
+        symbol: "otter_builtin_lazy_map".into(),
+        signature: FfiSignature::new(
+            vec![FfiType::Opaque, FfiType::Opaque, FfiType::Opaque],
+            FfiType::Opaque,
+        ),
+    });
+
+    registry.register(FfiFunction {
+        name: "__otter_lazy_filter".into(),
+        symbol: "otter_builtin_lazy_filter".into(),
+        signature: FfiSignature::new(
+            vec![FfiType::Opaque, FfiType::Opaque, FfiType::Opaque],
+            FfiType::Opaque,
+        ),
+    });
+
+    registry.register(FfiFunction {
+        name: "__otter_lazy_zip".into(),
+        symbol: "otter_builtin_lazy_zip".into(),
+        signature: FfiSignature::new(
+            vec![FfiType::Opaque, FfiType::Opaque, FfiType::Opaque],
+            FfiType::Opaque,
+        ),
+    });
+
+    registry.register(FfiFunction {
+        name: "__otter_lazy_take".into(),
+        symbol: "otter_builtin_lazy_take".into(),
+        signature: FfiSignature::new(vec![FfiType::Opaque, FfiType::I64], FfiType::Opaque),
+    });
+
+    registry.register(FfiFunction {
+        name: "__otter_iter_lazy".into(),
+        symbol: "otter_builtin_iter_lazy".into(),
+        signature: FfiSignature::new(vec![FfiType::Opaque], FfiType::Opaque),
+    });
+
+    registry.register(FfiFunction {
+        name: "__otter_iter_has_next_lazy".into(),
+        symbol: "otter_builtin_iter_has_next_lazy".into(),
+        signature: FfiSignature::new(vec![FfiType::Opaque], FfiType::Bool),
+    });
+
+    registry.register(FfiFunction {
+        name: "__otter_iter_next_lazy".into(),
+        symbol: "otter_builtin_iter_next_lazy".into(),
+        signature: FfiSignature::new(vec![FfiType::Opaque], FfiType::Opaque),
+    });
+
+    registry.register(FfiFunction {
+        name: "__otter_iter_free_lazy".into(),
+        symbol: "otter_builtin_iter_free_lazy".into(),
+        signature: FfiSignature::new(vec![FfiType::Opaque], FfiType::Unit),
+    });
+
     // Runtime value decoding functions with full precision
     registry.register(FfiFunction {
         name: "__otter_decode_value_kind".into(),
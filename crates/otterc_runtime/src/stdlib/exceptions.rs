@@ -15,6 +15,8 @@ pub struct OtterException {
     pub message: *mut c_char,
     pub exception_type: *mut c_char,
     pub stack_trace: *mut c_char,
+    /// Heap copy of the raised value for exceptions raised from Otter code, 0 otherwise
+    pub payload: u64,
 }
 
 // Thread-local exception storage
@@ -52,16 +54,28 @@ fn set_current_exception(exception: OtterException) {
     });
 }
 
-fn store_exception(message: String, exception_type: String, stack_trace: Option<String>) {
+fn store_exception(
+    message: String,
+    exception_type: String,
+    stack_trace: Option<String>,
+    payload: u64,
+) {
     let stack_trace = stack_trace.unwrap_or_else(capture_stack_trace);
     let exception = OtterException {
         message: make_c_string(&message),
         exception_type: make_c_string(&exception_type),
         stack_trace: make_c_string(&stack_trace),
+        payload,
     };
     set_current_exception(exception);
 }
 
+/// Raise an exception of type `exception_type` from runtime code, for the caller to
+/// observe once the runtime function returns
+pub(crate) fn raise_exception(message: String, exception_type: &str) {
+    store_exception(message, exception_type.to_string(), None, 0);
+}
+
 /// Throw an exception with a message
 ///
 /// # Safety
@@ -75,7 +89,7 @@ pub unsafe extern "C" fn otter_throw_exception(message: *const c_char) {
     let msg = unsafe { CStr::from_ptr(message) }
         .to_string_lossy()
         .into_owned();
-    store_exception(msg, "Exception".to_string(), None);
+    store_exception(msg, "Exception".to_string(), None, 0);
 }
 
 /// Throw an exception with an explicit type label.
@@ -102,7 +116,25 @@ pub unsafe extern "C" fn otter_throw_typed_exception(
             .into_owned()
     };
 
-    store_exception(msg, exception_type, None);
+    store_exception(msg, exception_type, None, 0);
+}
+
+/// Throw an exception raised by Otter code, carrying the raised value as its payload.
+///
+/// # Safety
+/// All pointers must reference valid UTF-8 strings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn otter_throw_value_exception(
+    message: *const c_char,
+    exception_type: *const c_char,
+    payload: u64,
+) {
+    unsafe { otter_throw_typed_exception(message, exception_type) }
+    CURRENT_EXCEPTION.with(|exc| {
+        if let Some(ref mut exception) = *exc.borrow_mut() {
+            exception.payload = payload;
+        }
+    });
 }
 
 /// Check if there's a current exception
@@ -147,6 +179,74 @@ pub extern "C" fn otter_get_exception_stack_trace() -> *mut c_char {
     })
 }
 
+/// Get the payload of the current exception, or 0 when there is none
+#[unsafe(no_mangle)]
+pub extern "C" fn otter_get_exception_payload() -> u64 {
+    CURRENT_EXCEPTION.with(|exc| {
+        exc.borrow()
+            .as_ref()
+            .map_or(0, |exception| exception.payload)
+    })
+}
+
+fn copy_c_string(ptr: *const c_char) -> *mut c_char {
+    if ptr.is_null() {
+        return std::ptr::null_mut();
+    }
+    make_c_string(&unsafe { CStr::from_ptr(ptr) }.to_string_lossy())
+}
+
+/// Copy the current exception message so it outlives `otter_clear_exception`
+#[unsafe(no_mangle)]
+pub extern "C" fn otter_copy_exception_message() -> *mut c_char {
+    CURRENT_EXCEPTION.with(|exc| match *exc.borrow() {
+        Some(ref exception) => copy_c_string(exception.message),
+        None => std::ptr::null_mut(),
+    })
+}
+
+/// Copy the current exception type label so it outlives `otter_clear_exception`
+#[unsafe(no_mangle)]
+pub extern "C" fn otter_copy_exception_type() -> *mut c_char {
+    CURRENT_EXCEPTION.with(|exc| match *exc.borrow() {
+        Some(ref exception) => copy_c_string(exception.exception_type),
+        None => std::ptr::null_mut(),
+    })
+}
+
+/// Report an exception that propagated out of `main` and terminate the process
+#[unsafe(no_mangle)]
+#[expect(
+    clippy::print_stderr,
+    reason = "Uncaught exceptions are reported to the user on stderr"
+)]
+pub extern "C" fn otter_report_uncaught_exception() {
+    CURRENT_EXCEPTION.with(|exc| {
+        if let Some(ref exception) = *exc.borrow() {
+            let read = |ptr: *mut c_char| {
+                if ptr.is_null() {
+                    String::new()
+                } else {
+                    unsafe { CStr::from_ptr(ptr) }
+                        .to_string_lossy()
+                        .into_owned()
+                }
+            };
+            eprintln!(
+                "Uncaught {}: {}",
+                read(exception.exception_type),
+                read(exception.message)
+            );
+            eprintln!("{}", read(exception.stack_trace));
+        }
+    });
+    #[expect(
+        clippy::exit,
+        reason = "An uncaught exception ends the program with a failure status"
+    )]
+    std::process::exit(1);
+}
+
 /// Clear the current exception
 #[unsafe(no_mangle)]
 pub extern "C" fn otter_clear_exception() {
@@ -480,6 +580,15 @@ fn register_exception_runtime_symbols(registry: &SymbolRegistry) {
         signature: FfiSignature::new(vec![FfiType::Str, FfiType::Str], FfiType::Unit),
     });
 
+    registry.register(FfiFunction {
+        name: "__otter_throw_value".into(),
+        symbol: "otter_throw_value_exception".into(),
+        signature: FfiSignature::new(
+            vec![FfiType::Str, FfiType::Str, FfiType::Opaque],
+            FfiType::Unit,
+        ),
+    });
+
     registry.register(FfiFunction {
         name: "__otter_has_exception".into(),
         symbol: "otter_has_exception".into(),
@@ -504,6 +613,30 @@ fn register_exception_runtime_symbols(registry: &SymbolRegistry) {
         signature: FfiSignature::new(vec![], FfiType::Str),
     });
 
+    registry.register(FfiFunction {
+        name: "__otter_get_exception_payload".into(),
+        symbol: "otter_get_exception_payload".into(),
+        signature: FfiSignature::new(vec![], FfiType::Opaque),
+    });
+
+    registry.register(FfiFunction {
+        name: "__otter_copy_exception_message".into(),
+        symbol: "otter_copy_exception_message".into(),
+        signature: FfiSignature::new(vec![], FfiType::Str),
+    });
+
+    registry.register(FfiFunction {
+        name: "__otter_copy_exception_type".into(),
+        symbol: "otter_copy_exception_type".into(),
+        signature: FfiSignature::new(vec![], FfiType::Str),
+    });
+
+    registry.register(FfiFunction {
+        name: "__otter_uncaught_exception".into(),
+        symbol: "otter_report_uncaught_exception".into(),
+        signature: FfiSignature::new(vec![], FfiType::Unit),
+    });
+
     registry.register(FfiFunction {
         name: "__otter_clear_exception".into(),
         symbol: "otter_clear_exception".into(),
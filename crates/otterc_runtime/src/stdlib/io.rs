@@ -337,6 +337,12 @@ pub extern "C" fn otter_std_io_buffer_clear(handle: u64) {
     }
 }
 
+/// frees the buffer `handle`; also registered as the `exit` of `io.buffer`
+#[unsafe(no_mangle)]
+pub extern "C" fn otter_std_io_buffer_close(handle: u64) {
+    BUFFERS.write().remove(&handle);
+}
+
 #[unsafe(no_mangle)]
 pub extern "C" fn otter_std_io_buffer_data(handle: u64) -> *mut c_char {
     let buffers = BUFFERS.read();
@@ -632,6 +638,18 @@ fn register_std_io_symbols(registry: &SymbolRegistry) {
         signature: FfiSignature::new(vec![FfiType::Opaque], FfiType::Str),
     });
 
+    registry.register(FfiFunction {
+        name: "io.buffer.close".into(),
+        symbol: "otter_std_io_buffer_close".into(),
+        signature: FfiSignature::new(vec![FfiType::Opaque], FfiType::Unit),
+    });
+
+    registry.register(FfiFunction {
+        name: "io.buffer.exit".into(),
+        symbol: "otter_std_io_buffer_close".into(),
+        signature: FfiSignature::new(vec![FfiType::Opaque], FfiType::Unit),
+    });
+
     registry.register(FfiFunction {
         name: "fs.exists".into(),
         symbol: "otter_std_io_exists".into(),
@@ -1,5 +1,8 @@
 use otterc_symbol::registry::{FfiFunction, FfiSignature, FfiType, SymbolRegistry};
 
+use crate::stdlib::builtins::{LISTS, Value};
+use crate::stdlib::exceptions::raise_exception;
+
 #[unsafe(no_mangle)]
 pub extern "C" fn otter_std_math_abs(value: f64) -> f64 {
     libm::fabs(value)
@@ -71,14 +74,49 @@ pub extern "C" fn otter_std_math_clamp(value: f64, min: f64, max: f64) -> f64 {
     }
 }
 
+/// numbers held by the list `handle`, which a variadic math function collects its
+/// arguments into. Any other element raises a `TypeError` for `function` to return with.
+fn list_numbers(handle: u64, function: &str) -> Vec<f64> {
+    let lists = LISTS.read();
+    let Some(list) = lists.get(&handle) else {
+        return Vec::new();
+    };
+    let mut numbers = Vec::with_capacity(list.items.len());
+    for value in &list.items {
+        match value {
+            Value::F64(value) => numbers.push(*value),
+            Value::I64(value) => numbers.push(*value as f64),
+            other => {
+                let kind = match other {
+                    Value::Unit => "None",
+                    Value::Bool(_) => "bool",
+                    Value::String(_) => "str",
+                    Value::List(_) => "list",
+                    _ => "dict",
+                };
+                raise_exception(
+                    format!("math.{} expects numbers, got {}", function, kind),
+                    "TypeError",
+                );
+                return Vec::new();
+            }
+        }
+    }
+    numbers
+}
+
 #[unsafe(no_mangle)]
-pub extern "C" fn otter_std_math_min(a: f64, b: f64) -> f64 {
-    if a < b { a } else { b }
+pub extern "C" fn otter_std_math_min(first: f64, rest: u64) -> f64 {
+    list_numbers(rest, "min")
+        .into_iter()
+        .fold(first, |a, b| if a < b { a } else { b })
 }
 
 #[unsafe(no_mangle)]
-pub extern "C" fn otter_std_math_max(a: f64, b: f64) -> f64 {
-    if a > b { a } else { b }
+pub extern "C" fn otter_std_math_max(first: f64, rest: u64) -> f64 {
+    list_numbers(rest, "max")
+        .into_iter()
+        .fold(first, |a, b| if a > b { a } else { b })
 }
 
 #[unsafe(no_mangle)]
@@ -251,13 +289,13 @@ fn register_std_math_symbols(registry: &SymbolRegistry) {
     registry.register(FfiFunction {
         name: "math.min".into(),
         symbol: "otter_std_math_min".into(),
-        signature: FfiSignature::new(vec![FfiType::F64, FfiType::F64], FfiType::F64),
+        signature: FfiSignature::variadic(vec![FfiType::F64], FfiType::F64, FfiType::F64),
     });
 
     registry.register(FfiFunction {
         name: "math.max".into(),
         symbol: "otter_std_math_max".into(),
-        signature: FfiSignature::new(vec![FfiType::F64, FfiType::F64], FfiType::F64),
+        signature: FfiSignature::variadic(vec![FfiType::F64], FfiType::F64, FfiType::F64),
     });
 
     registry.register(FfiFunction {
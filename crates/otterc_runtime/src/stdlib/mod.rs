@@ -10,6 +10,7 @@ pub mod math;
 pub mod net;
 pub mod rand;
 pub mod runtime;
+pub mod string;
 pub mod sync;
 pub mod sys;
 pub mod task;
@@ -260,9 +260,13 @@ pub extern "C" fn otter_std_net_recv(conn: u64) -> *mut c_char {
     }
 }
 
+/// closes the connection or listener `handle`; also registered as the `exit` of `net.dial`
+/// and `net.listen` so `with` blocks release their socket
 #[unsafe(no_mangle)]
-pub extern "C" fn otter_std_net_close(conn: u64) {
-    CONNECTIONS.write().remove(&conn);
+pub extern "C" fn otter_std_net_close(handle: u64) {
+    if CONNECTIONS.write().remove(&handle).is_none() {
+        LISTENERS.write().remove(&handle);
+    }
 }
 
 /// runs an HTTP get request at the url `url`
@@ -366,6 +370,18 @@ fn register_std_net_symbols(registry: &SymbolRegistry) {
         signature: FfiSignature::new(vec![FfiType::Opaque], FfiType::Unit),
     });
 
+    registry.register(FfiFunction {
+        name: "net.dial.exit".into(),
+        symbol: "otter_std_net_close".into(),
+        signature: FfiSignature::new(vec![FfiType::Opaque], FfiType::Unit),
+    });
+
+    registry.register(FfiFunction {
+        name: "net.listen.exit".into(),
+        symbol: "otter_std_net_close".into(),
+        signature: FfiSignature::new(vec![FfiType::Opaque], FfiType::Unit),
+    });
+
     registry.register(FfiFunction {
         name: "net.http_get".into(),
         symbol: "otter_std_net_http_get".into(),
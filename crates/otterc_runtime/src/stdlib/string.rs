@@ -0,0 +1,733 @@
+//! The `otter:string` module. Every function takes the string it works on first, so
+//! `s.split(",")` calls `string.split(s, ",")`. Indexes and lengths count characters, not
+//! bytes, except for `byte_at` and `byte_len`.
+//!
+//! Every function that takes a string takes a raw pointer to a NUL-terminated string, which
+//! may be null.
+
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+
+use otterc_symbol::registry::{FfiFunction, FfiSignature, FfiType, SymbolRegistry};
+
+use crate::stdlib::builtins::{
+    LISTS, Value, otter_builtin_list_new, slice_bounds, value_to_string,
+};
+use crate::stdlib::exceptions::raise_exception;
+
+/// # Safety
+///
+/// `ptr` must be null or point to a NUL-terminated string
+unsafe fn read_c_string(ptr: *const c_char) -> Option<String> {
+    if ptr.is_null() {
+        return None;
+    }
+
+    unsafe { CStr::from_ptr(ptr).to_str().ok().map(|s| s.to_string()) }
+}
+
+fn into_c_string<S: Into<String>>(value: S) -> *mut c_char {
+    CString::new(value.into())
+        .ok()
+        .map(CString::into_raw)
+        .unwrap_or(std::ptr::null_mut())
+}
+
+/// new list holding `items` as strings
+fn into_string_list(items: impl IntoIterator<Item = String>) -> u64 {
+    let handle = otter_builtin_list_new();
+    if let Some(list) = LISTS.write().get_mut(&handle) {
+        list.items.extend(items.into_iter().map(Value::String));
+    }
+    handle
+}
+
+/// index in characters of the byte offset `byte` of `s`
+fn char_index(s: &str, byte: usize) -> i64 {
+    s[..byte].chars().count() as i64
+}
+
+/// the character at `index` of `s`, raising an `IndexError` when there is none
+fn char_at(s: &str, index: i64) -> Option<char> {
+    let found = usize::try_from(index)
+        .ok()
+        .and_then(|index| s.chars().nth(index));
+    if found.is_none() {
+        raise_exception(
+            format!(
+                "string index {} out of range for length {}",
+                index,
+                s.chars().count()
+            ),
+            "IndexError",
+        );
+    }
+    found
+}
+
+/// `s` padded with `fill` to `width` characters, at the start or the end
+fn pad(s: &str, width: i64, fill: &str, at_start: bool) -> String {
+    let missing = usize::try_from(width)
+        .unwrap_or(0)
+        .saturating_sub(s.chars().count());
+    let fill = fill.chars().next().unwrap_or(' ');
+    let padding: String = std::iter::repeat_n(fill, missing).collect();
+    if at_start {
+        padding + s
+    } else {
+        format!("{}{}", s, padding)
+    }
+}
+
+// ============================================================================
+// Searching
+// ============================================================================
+
+/// whether `needle` occurs in `s`
+///
+/// # Safety
+///
+/// this function dereferences a raw pointer
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn otter_std_string_contains(
+    s: *const c_char,
+    needle: *const c_char,
+) -> bool {
+    match unsafe { (read_c_string(s), read_c_string(needle)) } {
+        (Some(s), Some(needle)) => s.contains(&needle),
+        _ => false,
+    }
+}
+
+/// whether `s` begins with `prefix`
+///
+/// # Safety
+///
+/// this function dereferences a raw pointer
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn otter_std_string_starts_with(
+    s: *const c_char,
+    prefix: *const c_char,
+) -> bool {
+    match unsafe { (read_c_string(s), read_c_string(prefix)) } {
+        (Some(s), Some(prefix)) => s.starts_with(&prefix),
+        _ => false,
+    }
+}
+
+/// whether `s` ends with `suffix`
+///
+/// # Safety
+///
+/// this function dereferences a raw pointer
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn otter_std_string_ends_with(
+    s: *const c_char,
+    suffix: *const c_char,
+) -> bool {
+    match unsafe { (read_c_string(s), read_c_string(suffix)) } {
+        (Some(s), Some(suffix)) => s.ends_with(&suffix),
+        _ => false,
+    }
+}
+
+/// index of the first occurrence of `needle` in `s`, or -1
+///
+/// # Safety
+///
+/// this function dereferences a raw pointer
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn otter_std_string_find(s: *const c_char, needle: *const c_char) -> i64 {
+    match unsafe { (read_c_string(s), read_c_string(needle)) } {
+        (Some(s), Some(needle)) => s.find(&needle).map_or(-1, |byte| char_index(&s, byte)),
+        _ => -1,
+    }
+}
+
+/// index of the last occurrence of `needle` in `s`, or -1
+///
+/// # Safety
+///
+/// this function dereferences a raw pointer
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn otter_std_string_rfind(s: *const c_char, needle: *const c_char) -> i64 {
+    match unsafe { (read_c_string(s), read_c_string(needle)) } {
+        (Some(s), Some(needle)) => s.rfind(&needle).map_or(-1, |byte| char_index(&s, byte)),
+        _ => -1,
+    }
+}
+
+// ============================================================================
+// Splitting and Joining
+// ============================================================================
+
+/// the parts of `s` between occurrences of `separator`. An empty separator splits `s`
+/// into its characters.
+///
+/// # Safety
+///
+/// this function dereferences a raw pointer
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn otter_std_string_split(s: *const c_char, separator: *const c_char) -> u64 {
+    let s = unsafe { read_c_string(s) }.unwrap_or_default();
+    let separator = unsafe { read_c_string(separator) }.unwrap_or_default();
+    if separator.is_empty() {
+        into_string_list(s.chars().map(String::from))
+    } else {
+        into_string_list(s.split(separator.as_str()).map(String::from))
+    }
+}
+
+/// the elements of the list `parts` with `separator` between them. Elements that are not
+/// strings are converted the way `str()` converts them, so nested lists and maps join as
+/// their literal form.
+///
+/// # Safety
+///
+/// this function dereferences a raw pointer
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn otter_std_string_join(
+    separator: *const c_char,
+    parts: u64,
+) -> *mut c_char {
+    let separator = unsafe { read_c_string(separator) }.unwrap_or_default();
+    let joined = LISTS
+        .read()
+        .get(&parts)
+        .map(|list| {
+            list.items
+                .iter()
+                .map(value_to_string)
+                .collect::<Vec<_>>()
+                .join(&separator)
+        })
+        .unwrap_or_default();
+    into_c_string(joined)
+}
+
+/// the characters of `s`, each as a string
+///
+/// # Safety
+///
+/// this function dereferences a raw pointer
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn otter_std_string_chars(s: *const c_char) -> u64 {
+    let s = unsafe { read_c_string(s) }.unwrap_or_default();
+    into_string_list(s.chars().map(String::from))
+}
+
+// ============================================================================
+// Transforming
+// ============================================================================
+
+/// `s` with every occurrence of `from` replaced by `to`
+///
+/// # Safety
+///
+/// this function dereferences a raw pointer
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn otter_std_string_replace(
+    s: *const c_char,
+    from: *const c_char,
+    to: *const c_char,
+) -> *mut c_char {
+    match unsafe { (read_c_string(s), read_c_string(from), read_c_string(to)) } {
+        (Some(s), Some(from), Some(to)) if !from.is_empty() => into_c_string(s.replace(&from, &to)),
+        (Some(s), _, _) => into_c_string(s),
+        _ => std::ptr::null_mut(),
+    }
+}
+
+/// `s` without leading and trailing whitespace
+///
+/// # Safety
+///
+/// this function dereferences a raw pointer
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn otter_std_string_trim(s: *const c_char) -> *mut c_char {
+    unsafe { read_c_string(s) }.map_or(std::ptr::null_mut(), |s| into_c_string(s.trim()))
+}
+
+/// `s` without leading whitespace
+///
+/// # Safety
+///
+/// this function dereferences a raw pointer
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn otter_std_string_trim_start(s: *const c_char) -> *mut c_char {
+    unsafe { read_c_string(s) }.map_or(std::ptr::null_mut(), |s| into_c_string(s.trim_start()))
+}
+
+/// `s` without trailing whitespace
+///
+/// # Safety
+///
+/// this function dereferences a raw pointer
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn otter_std_string_trim_end(s: *const c_char) -> *mut c_char {
+    unsafe { read_c_string(s) }.map_or(std::ptr::null_mut(), |s| into_c_string(s.trim_end()))
+}
+
+/// `s` in upper case
+///
+/// # Safety
+///
+/// this function dereferences a raw pointer
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn otter_std_string_upper(s: *const c_char) -> *mut c_char {
+    unsafe { read_c_string(s) }.map_or(std::ptr::null_mut(), |s| into_c_string(s.to_uppercase()))
+}
+
+/// `s` in lower case
+///
+/// # Safety
+///
+/// this function dereferences a raw pointer
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn otter_std_string_lower(s: *const c_char) -> *mut c_char {
+    unsafe { read_c_string(s) }.map_or(std::ptr::null_mut(), |s| into_c_string(s.to_lowercase()))
+}
+
+/// `s` repeated `count` times; a negative count gives the empty string, and a result too
+/// large to allocate raises a `ValueError`
+///
+/// # Safety
+///
+/// this function dereferences a raw pointer
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn otter_std_string_repeat(s: *const c_char, count: i64) -> *mut c_char {
+    unsafe { read_c_string(s) }.map_or(std::ptr::null_mut(), |s| {
+        let count = usize::try_from(count).unwrap_or(0);
+        let mut repeated = String::new();
+        let fits = s
+            .len()
+            .checked_mul(count)
+            .is_some_and(|len| repeated.try_reserve_exact(len).is_ok());
+        if fits {
+            (0..count).for_each(|_| repeated.push_str(&s));
+        } else {
+            raise_exception(
+                format!(
+                    "string of length {} repeated {} times is too large",
+                    s.len(),
+                    count
+                ),
+                "ValueError",
+            );
+        }
+        into_c_string(repeated)
+    })
+}
+
+/// `s` with the first character of `fill` (a space when it is empty) prepended until it is
+/// `width` characters long
+///
+/// # Safety
+///
+/// this function dereferences a raw pointer
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn otter_std_string_pad_start(
+    s: *const c_char,
+    width: i64,
+    fill: *const c_char,
+) -> *mut c_char {
+    let fill = unsafe { read_c_string(fill) }.unwrap_or_default();
+    unsafe { read_c_string(s) }.map_or(std::ptr::null_mut(), |s| {
+        into_c_string(pad(&s, width, &fill, true))
+    })
+}
+
+/// `s` with the first character of `fill` (a space when it is empty) appended until it is
+/// `width` characters long
+///
+/// # Safety
+///
+/// this function dereferences a raw pointer
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn otter_std_string_pad_end(
+    s: *const c_char,
+    width: i64,
+    fill: *const c_char,
+) -> *mut c_char {
+    let fill = unsafe { read_c_string(fill) }.unwrap_or_default();
+    unsafe { read_c_string(s) }.map_or(std::ptr::null_mut(), |s| {
+        into_c_string(pad(&s, width, &fill, false))
+    })
+}
+
+// ============================================================================
+// Characters and Bytes
+// ============================================================================
+
+/// the character at `index` of `s` as a string; raises an `IndexError` when it is out of
+/// range
+///
+/// # Safety
+///
+/// this function dereferences a raw pointer
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn otter_std_string_char_at(s: *const c_char, index: i64) -> *mut c_char {
+    let s = unsafe { read_c_string(s) }.unwrap_or_default();
+    char_at(&s, index).map_or_else(|| into_c_string(""), |ch| into_c_string(ch.to_string()))
+}
+
+/// the characters of `s` from `start` up to `end`. Negative bounds count from the end and
+/// both are clamped to the string, so slicing never raises.
+///
+/// # Safety
+///
+/// this function dereferences a raw pointer
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn otter_std_string_slice(
+    s: *const c_char,
+    start: i64,
+    end: i64,
+) -> *mut c_char {
+    unsafe { read_c_string(s) }.map_or(std::ptr::null_mut(), |s| {
+        let (start, end) = slice_bounds(s.chars().count(), start, end);
+        into_c_string(s.chars().skip(start).take(end - start).collect::<String>())
+    })
+}
+
+/// the byte at `index` of the UTF-8 encoding of `s`; raises an `IndexError` when it is out
+/// of range
+///
+/// # Safety
+///
+/// this function dereferences a raw pointer
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn otter_std_string_byte_at(s: *const c_char, index: i64) -> i64 {
+    let s = unsafe { read_c_string(s) }.unwrap_or_default();
+    let byte = usize::try_from(index)
+        .ok()
+        .and_then(|index| s.as_bytes().get(index));
+    match byte {
+        Some(byte) => i64::from(*byte),
+        None => {
+            raise_exception(
+                format!("byte index {} out of range for length {}", index, s.len()),
+                "IndexError",
+            );
+            0
+        }
+    }
+}
+
+/// length of the UTF-8 encoding of `s` in bytes
+///
+/// # Safety
+///
+/// this function dereferences a raw pointer
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn otter_std_string_byte_len(s: *const c_char) -> i64 {
+    unsafe { read_c_string(s) }.map_or(0, |s| s.len() as i64)
+}
+
+// ============================================================================
+// Parsing
+// ============================================================================
+
+/// `s` parsed as a decimal integer, ignoring surrounding whitespace; raises a `ValueError`
+/// when it is not one
+///
+/// # Safety
+///
+/// this function dereferences a raw pointer
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn otter_std_string_to_int(s: *const c_char) -> i64 {
+    let s = unsafe { read_c_string(s) }.unwrap_or_default();
+    s.trim().parse::<i64>().unwrap_or_else(|_| {
+        raise_exception(format!("invalid integer: {:?}", s), "ValueError");
+        0
+    })
+}
+
+/// `s` parsed as a float, ignoring surrounding whitespace; raises a `ValueError` when it is
+/// not one
+///
+/// # Safety
+///
+/// this function dereferences a raw pointer
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn otter_std_string_to_float(s: *const c_char) -> f64 {
+    let s = unsafe { read_c_string(s) }.unwrap_or_default();
+    s.trim().parse::<f64>().unwrap_or_else(|_| {
+        raise_exception(format!("invalid float: {:?}", s), "ValueError");
+        0.0
+    })
+}
+
+fn register_std_string_symbols(registry: &SymbolRegistry) {
+    registry.register(FfiFunction {
+        name: "string.contains".into(),
+        symbol: "otter_std_string_contains".into(),
+        signature: FfiSignature::new(vec![FfiType::Str, FfiType::Str], FfiType::Bool),
+    });
+
+    registry.register(FfiFunction {
+        name: "string.starts_with".into(),
+        symbol: "otter_std_string_starts_with".into(),
+        signature: FfiSignature::new(vec![FfiType::Str, FfiType::Str], FfiType::Bool),
+    });
+
+    registry.register(FfiFunction {
+        name: "string.ends_with".into(),
+        symbol: "otter_std_string_ends_with".into(),
+        signature: FfiSignature::new(vec![FfiType::Str, FfiType::Str], FfiType::Bool),
+    });
+
+    registry.register(FfiFunction {
+        name: "string.find".into(),
+        symbol: "otter_std_string_find".into(),
+        signature: FfiSignature::new(vec![FfiType::Str, FfiType::Str], FfiType::I64),
+    });
+
+    registry.register(FfiFunction {
+        name: "string.rfind".into(),
+        symbol: "otter_std_string_rfind".into(),
+        signature: FfiSignature::new(vec![FfiType::Str, FfiType::Str], FfiType::I64),
+    });
+
+    registry.register(FfiFunction {
+        name: "string.split".into(),
+        symbol: "otter_std_string_split".into(),
+        signature: FfiSignature::new(vec![FfiType::Str, FfiType::Str], FfiType::List),
+    });
+
+    registry.register(FfiFunction {
+        name: "string.join".into(),
+        symbol: "otter_std_string_join".into(),
+        signature: FfiSignature::new(vec![FfiType::Str, FfiType::List], FfiType::Str),
+    });
+
+    registry.register(FfiFunction {
+        name: "string.chars".into(),
+        symbol: "otter_std_string_chars".into(),
+        signature: FfiSignature::new(vec![FfiType::Str], FfiType::List),
+    });
+
+    registry.register(FfiFunction {
+        name: "string.replace".into(),
+        symbol: "otter_std_string_replace".into(),
+        signature: FfiSignature::new(vec![FfiType::Str, FfiType::Str, FfiType::Str], FfiType::Str),
+    });
+
+    registry.register(FfiFunction {
+        name: "string.trim".into(),
+        symbol: "otter_std_string_trim".into(),
+        signature: FfiSignature::new(vec![FfiType::Str], FfiType::Str),
+    });
+
+    registry.register(FfiFunction {
+        name: "string.trim_start".into(),
+        symbol: "otter_std_string_trim_start".into(),
+        signature: FfiSignature::new(vec![FfiType::Str], FfiType::Str),
+    });
+
+    registry.register(FfiFunction {
+        name: "string.trim_end".into(),
+        symbol: "otter_std_string_trim_end".into(),
+        signature: FfiSignature::new(vec![FfiType::Str], FfiType::Str),
+    });
+
+    registry.register(FfiFunction {
+        name: "string.upper".into(),
+        symbol: "otter_std_string_upper".into(),
+        signature: FfiSignature::new(vec![FfiType::Str], FfiType::Str),
+    });
+
+    registry.register(FfiFunction {
+        name: "string.lower".into(),
+        symbol: "otter_std_string_lower".into(),
+        signature: FfiSignature::new(vec![FfiType::Str], FfiType::Str),
+    });
+
+    registry.register(FfiFunction {
+        name: "string.repeat".into(),
+        symbol: "otter_std_string_repeat".into(),
+        signature: FfiSignature::new(vec![FfiType::Str, FfiType::I64], FfiType::Str),
+    });
+
+    registry.register(FfiFunction {
+        name: "string.pad_start".into(),
+        symbol: "otter_std_string_pad_start".into(),
+        signature: FfiSignature::new(vec![FfiType::Str, FfiType::I64, FfiType::Str], FfiType::Str),
+    });
+
+    registry.register(FfiFunction {
+        name: "string.pad_end".into(),
+        symbol: "otter_std_string_pad_end".into(),
+        signature: FfiSignature::new(vec![FfiType::Str, FfiType::I64, FfiType::Str], FfiType::Str),
+    });
+
+    registry.register(FfiFunction {
+        name: "string.char_at".into(),
+        symbol: "otter_std_string_char_at".into(),
+        signature: FfiSignature::new(vec![FfiType::Str, FfiType::I64], FfiType::Str),
+    });
+
+    registry.register(FfiFunction {
+        name: "string.slice".into(),
+        symbol: "otter_std_string_slice".into(),
+        signature: FfiSignature::new(vec![FfiType::Str, FfiType::I64, FfiType::I64], FfiType::Str),
+    });
+
+    registry.register(FfiFunction {
+        name: "string.byte_at".into(),
+        symbol: "otter_std_string_byte_at".into(),
+        signature: FfiSignature::new(vec![FfiType::Str, FfiType::I64], FfiType::I64),
+    });
+
+    registry.register(FfiFunction {
+        name: "string.byte_len".into(),
+        symbol: "otter_std_string_byte_len".into(),
+        signature: FfiSignature::new(vec![FfiType::Str], FfiType::I64),
+    });
+
+    registry.register(FfiFunction {
+        name: "string.to_int".into(),
+        symbol: "otter_std_string_to_int".into(),
+        signature: FfiSignature::new(vec![FfiType::Str], FfiType::I64),
+    });
+
+    registry.register(FfiFunction {
+        name: "string.to_float".into(),
+        symbol: "otter_std_string_to_float".into(),
+        signature: FfiSignature::new(vec![FfiType::Str], FfiType::F64),
+    });
+}
+
+inventory::submit! {
+    otterc_ffi::SymbolProvider {
+        namespace: "string",
+        autoload: true,
+        register: register_std_string_symbols,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stdlib::exceptions::{
+        otter_clear_exception, otter_get_exception_message, otter_has_exception,
+    };
+
+    fn c(s: &str) -> CString {
+        CString::new(s).unwrap()
+    }
+
+    fn take(ptr: *mut c_char) -> String {
+        assert!(!ptr.is_null());
+        unsafe { CString::from_raw(ptr) }.into_string().unwrap()
+    }
+
+    fn strings(handle: u64) -> Vec<String> {
+        LISTS.read()[&handle]
+            .items
+            .iter()
+            .map(value_to_string)
+            .collect()
+    }
+
+    fn list(items: Vec<Value>) -> u64 {
+        let handle = otter_builtin_list_new();
+        LISTS.write().get_mut(&handle).unwrap().items.extend(items);
+        handle
+    }
+
+    #[test]
+    fn test_split() {
+        let split = |s: &str, sep: &str| {
+            strings(unsafe { otter_std_string_split(c(s).as_ptr(), c(sep).as_ptr()) })
+        };
+        assert_eq!(split("a,b,,c", ","), ["a", "b", "", "c"]);
+        assert_eq!(split("naïve→ok", "→"), ["naïve", "ok"]);
+        assert_eq!(split("🦦é", ""), ["🦦", "é"]);
+        assert_eq!(split("", ","), [""]);
+        assert!(split("", "").is_empty());
+    }
+
+    #[test]
+    fn test_join() {
+        let join =
+            |sep: &str, parts: u64| take(unsafe { otter_std_string_join(c(sep).as_ptr(), parts) });
+        let words = list(vec![
+            Value::String("über".into()),
+            Value::String("🦦".into()),
+        ]);
+        assert_eq!(join("·", words), "über·🦦");
+        assert_eq!(join(", ", list(Vec::new())), "");
+        assert_eq!(join("", words), "über🦦");
+
+        let nested = list(vec![Value::I64(1), Value::F64(2.5)]);
+        let mixed = list(vec![Value::Bool(true), Value::List(nested), Value::Unit]);
+        assert_eq!(join(" ", mixed), "true [1, 2.5] None");
+    }
+
+    #[test]
+    fn test_repeat() {
+        let repeat =
+            |s: &str, count: i64| take(unsafe { otter_std_string_repeat(c(s).as_ptr(), count) });
+        assert_eq!(repeat("ab", 3), "ababab");
+        assert_eq!(repeat("🦦", 2), "🦦🦦");
+        assert_eq!(repeat("ab", -1), "");
+        assert!(!otter_has_exception());
+
+        assert_eq!(repeat("ab", i64::MAX), "");
+        let message = unsafe { CStr::from_ptr(otter_get_exception_message()) };
+        assert!(message.to_str().unwrap().contains("too large"));
+        otter_clear_exception();
+    }
+
+    #[test]
+    fn test_replace() {
+        let replace = |s: &str, from: &str, to: &str| {
+            take(unsafe {
+                otter_std_string_replace(c(s).as_ptr(), c(from).as_ptr(), c(to).as_ptr())
+            })
+        };
+        assert_eq!(replace("a-b-c", "-", "+"), "a+b+c");
+        assert_eq!(replace("日本語", "本", "🦦"), "日🦦語");
+        assert_eq!(replace("abc", "", "x"), "abc");
+        assert_eq!(replace("", "a", "b"), "");
+        assert_eq!(replace("aaa", "a", ""), "");
+    }
+
+    #[test]
+    fn test_find() {
+        let find = |s: &str, needle: &str| unsafe {
+            otter_std_string_find(c(s).as_ptr(), c(needle).as_ptr())
+        };
+        let rfind = |s: &str, needle: &str| unsafe {
+            otter_std_string_rfind(c(s).as_ptr(), c(needle).as_ptr())
+        };
+        assert_eq!(find("hello", "l"), 2);
+        assert_eq!(rfind("hello", "l"), 3);
+        assert_eq!(find("héllo wörld", "w"), 6);
+        assert_eq!(rfind("🦦a🦦a", "a"), 3);
+        assert_eq!(find("hello", "z"), -1);
+        assert_eq!(find("hello", ""), 0);
+        assert_eq!(find("", "a"), -1);
+    }
+
+    #[test]
+    fn test_char_at_and_slice() {
+        let s = c("añ🦦b");
+        assert_eq!(
+            take(unsafe { otter_std_string_char_at(s.as_ptr(), 2) }),
+            "🦦"
+        );
+        assert_eq!(
+            take(unsafe { otter_std_string_slice(s.as_ptr(), 1, 3) }),
+            "ñ🦦"
+        );
+        assert_eq!(
+            take(unsafe { otter_std_string_slice(s.as_ptr(), -2, i64::MAX) }),
+            "🦦b"
+        );
+        assert_eq!(
+            take(unsafe { otter_std_string_slice(s.as_ptr(), 3, 1) }),
+            ""
+        );
+    }
+}
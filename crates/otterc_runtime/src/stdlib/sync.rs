@@ -1,16 +1,18 @@
 use std::cell::RefCell;
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::{Arc, Once};
 use std::thread;
 
 use once_cell::sync::Lazy;
-use parking_lot::{Mutex, RwLock};
+use parking_lot::{ArcMutexGuard, Mutex, RawMutex, RwLock};
 
 use otterc_symbol::registry::{FfiFunction, FfiSignature, FfiType, SymbolRegistry};
 
 thread_local! {
-    static THREAD_LOCKS: RefCell<HashSet<u64>> = RefCell::new(HashSet::new());
+    /// Guards returned by `sync.lock` on this thread, by guard handle. A guard cannot move
+    /// to another thread, so only the thread that locked a mutex can unlock it.
+    static THREAD_LOCKS: RefCell<HashMap<HandleId, LockGuard>> = RefCell::new(HashMap::new());
 }
 
 /// Handle IDs for opaque types
@@ -27,6 +29,11 @@ struct MutexHandle {
     inner: Arc<Mutex<()>>,
 }
 
+struct LockGuard {
+    mutex: HandleId,
+    _guard: ArcMutexGuard<RawMutex, ()>,
+}
+
 struct WaitGroup {
     _id: HandleId,
     count: Arc<AtomicUsize>,
@@ -67,21 +74,39 @@ pub extern "C" fn otter_sync_mutex() -> u64 {
     id
 }
 
+/// locks the mutex `handle` and returns a guard handle that keeps it locked until it is
+/// passed to `sync.unlock_guard`; returns 0 for an unknown mutex
 #[unsafe(no_mangle)]
-pub extern "C" fn otter_sync_lock(handle: u64) {
-    let mutexes = MUTEXES.read();
-    if let Some(mutex) = mutexes.get(&handle) {
-        let _guard = mutex.inner.lock();
-        THREAD_LOCKS.with(|locks| {
-            locks.borrow_mut().insert(handle);
-        });
-    }
+pub extern "C" fn otter_sync_lock(handle: u64) -> u64 {
+    // Clone the mutex out of the table so waiting for it does not block `sync.mutex`
+    let Some(inner) = MUTEXES.read().get(&handle).map(|mutex| mutex.inner.clone()) else {
+        return 0;
+    };
+    let guard = LockGuard {
+        mutex: handle,
+        _guard: inner.lock_arc(),
+    };
+    let id = next_handle_id();
+    THREAD_LOCKS.with(|locks| {
+        locks.borrow_mut().insert(id, guard);
+    });
+    id
+}
+
+/// unlocks the mutex held by the guard `guard`; also registered as the `exit` of `sync.lock`
+/// so `with` blocks release exactly the lock they took
+#[unsafe(no_mangle)]
+pub extern "C" fn otter_sync_unlock_guard(guard: u64) {
+    THREAD_LOCKS.with(|locks| {
+        locks.borrow_mut().remove(&guard);
+    });
 }
 
+/// unlocks the mutex `handle` if this thread holds it
 #[unsafe(no_mangle)]
 pub extern "C" fn otter_sync_unlock(handle: u64) {
     THREAD_LOCKS.with(|locks| {
-        locks.borrow_mut().remove(&handle);
+        locks.borrow_mut().retain(|_, guard| guard.mutex != handle);
     });
 }
 
@@ -218,7 +243,7 @@ fn register_std_sync_symbols(registry: &SymbolRegistry) {
     registry.register(FfiFunction {
         name: "sync.lock".into(),
         symbol: "otter_sync_lock".into(),
-        signature: FfiSignature::new(vec![FfiType::Opaque], FfiType::Unit),
+        signature: FfiSignature::new(vec![FfiType::Opaque], FfiType::Opaque),
     });
 
     registry.register(FfiFunction {
@@ -227,6 +252,18 @@ fn register_std_sync_symbols(registry: &SymbolRegistry) {
         signature: FfiSignature::new(vec![FfiType::Opaque], FfiType::Unit),
     });
 
+    registry.register(FfiFunction {
+        name: "sync.unlock_guard".into(),
+        symbol: "otter_sync_unlock_guard".into(),
+        signature: FfiSignature::new(vec![FfiType::Opaque], FfiType::Unit),
+    });
+
+    registry.register(FfiFunction {
+        name: "sync.lock.exit".into(),
+        symbol: "otter_sync_unlock_guard".into(),
+        signature: FfiSignature::new(vec![FfiType::Opaque], FfiType::Unit),
+    });
+
     registry.register(FfiFunction {
         name: "sync.waitgroup".into(),
         symbol: "otter_sync_waitgroup".into(),
@@ -295,3 +332,32 @@ inventory::submit! {
         register: register_std_sync_symbols,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_locked(handle: u64) -> bool {
+        MUTEXES.read()[&handle].inner.is_locked()
+    }
+
+    #[test]
+    fn test_lock_guards() {
+        let mutex = otter_sync_mutex();
+        let guard = otter_sync_lock(mutex);
+        assert_ne!(guard, 0);
+        assert!(is_locked(mutex));
+        otter_sync_unlock_guard(guard);
+        assert!(!is_locked(mutex));
+
+        // Releasing a guard twice does not unlock a later holder
+        let second = otter_sync_lock(mutex);
+        otter_sync_unlock_guard(guard);
+        assert!(is_locked(mutex));
+        otter_sync_unlock(mutex);
+        assert!(!is_locked(mutex));
+        otter_sync_unlock_guard(second);
+
+        assert_eq!(otter_sync_lock(0), 0);
+    }
+}
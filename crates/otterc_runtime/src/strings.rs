@@ -41,6 +41,23 @@ pub extern "C" fn otter_format_int(value: i64) -> *mut c_char {
     s
 }
 
+/// Format an unsigned 64-bit integer value to string
+#[unsafe(no_mangle)]
+pub extern "C" fn otter_format_uint(value: u64) -> *mut c_char {
+    let formatted = format!("{}", value);
+    let s = CString::new(formatted)
+        .map(CString::into_raw)
+        .unwrap_or_else(|_| std::ptr::null_mut());
+
+    if !s.is_null() {
+        unsafe {
+            let len = std::ffi::CStr::from_ptr(s).to_bytes_with_nul().len();
+            get_gc().register_object(s as usize, len, ObjectKind::CString);
+        }
+    }
+    s
+}
+
 /// Format a boolean value to string
 #[unsafe(no_mangle)]
 pub extern "C" fn otter_format_bool(value: bool) -> *mut c_char {
@@ -177,6 +194,42 @@ pub unsafe extern "C" fn otter_string_equal(s1: *const c_char, s2: *const c_char
     }
 }
 
+/// Orders two strings by their bytes: negative, zero or positive as `s1` sorts before, with
+/// or after `s2`. This is the order `sort` gives a list of strings.
+///
+/// # Safety
+///
+/// this function dereferences raw pointers
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn otter_string_compare(s1: *const c_char, s2: *const c_char) -> i64 {
+    let bytes = |s: *const c_char| {
+        if s.is_null() {
+            &[][..]
+        } else {
+            unsafe { CStr::from_ptr(s).to_bytes() }
+        }
+    };
+    bytes(s1).cmp(bytes(s2)) as i64
+}
+
+/// Hash of a string's bytes (64-bit FNV-1a), the same on every run
+///
+/// # Safety
+///
+/// this function dereferences raw pointers
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn otter_string_hash(s: *const c_char) -> i64 {
+    if s.is_null() {
+        return 0;
+    }
+
+    let bytes = unsafe { CStr::from_ptr(s).to_bytes() };
+    let hash = bytes.iter().fold(0xcbf2_9ce4_8422_2325_u64, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    hash as i64
+}
+
 fn register_string_functions(registry: &SymbolRegistry) {
     registry.register(FfiFunction {
         name: "std.strings.format_float".into(),
@@ -190,6 +243,12 @@ fn register_string_functions(registry: &SymbolRegistry) {
         signature: FfiSignature::new(vec![FfiType::I64], FfiType::Str),
     });
 
+    registry.register(FfiFunction {
+        name: "std.strings.format_uint".into(),
+        symbol: "otter_format_uint".into(),
+        signature: FfiSignature::new(vec![FfiType::U64], FfiType::Str),
+    });
+
     registry.register(FfiFunction {
         name: "std.strings.format_bool".into(),
         symbol: "otter_format_bool".into(),
@@ -225,6 +284,18 @@ fn register_string_functions(registry: &SymbolRegistry) {
         symbol: "otter_string_equal".into(),
         signature: FfiSignature::new(vec![FfiType::Str, FfiType::Str], FfiType::I32),
     });
+
+    registry.register(FfiFunction {
+        name: "std.strings.compare".into(),
+        symbol: "otter_string_compare".into(),
+        signature: FfiSignature::new(vec![FfiType::Str, FfiType::Str], FfiType::I64),
+    });
+
+    registry.register(FfiFunction {
+        name: "std.strings.hash".into(),
+        symbol: "otter_string_hash".into(),
+        signature: FfiSignature::new(vec![FfiType::Str], FfiType::I64),
+    });
 }
 
 inventory::submit! {
@@ -263,6 +334,17 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_format_uint() {
+        let result = otter_format_uint(u64::MAX);
+        assert!(!result.is_null());
+        unsafe {
+            let s = CStr::from_ptr(result).to_str().unwrap();
+            assert_eq!(s, "18446744073709551615");
+            otter_free_string(result);
+        }
+    }
+
     #[test]
     fn test_concat_strings() {
         let s1 = CString::new("Hello ").unwrap();
@@ -281,4 +363,25 @@ mod tests {
         let valid = CString::new("Hello 🦦").unwrap();
         assert_eq!(unsafe { otter_validate_utf8(valid.as_ptr()) }, 1);
     }
+
+    #[test]
+    fn test_string_hash() {
+        let a = CString::new("otter").unwrap();
+        let b = CString::new("otter").unwrap();
+        let c = CString::new("otters").unwrap();
+        let hash = |s: &CString| unsafe { otter_string_hash(s.as_ptr()) };
+        assert_eq!(hash(&a), hash(&b));
+        assert_ne!(hash(&a), hash(&c));
+    }
+
+    #[test]
+    fn test_string_compare() {
+        let a = CString::new("otter").unwrap();
+        let b = CString::new("otters").unwrap();
+        let compare =
+            |x: &CString, y: &CString| unsafe { otter_string_compare(x.as_ptr(), y.as_ptr()) };
+        assert!(compare(&a, &b) < 0);
+        assert!(compare(&b, &a) > 0);
+        assert_eq!(compare(&a, &a), 0);
+    }
 }
@@ -12,8 +12,15 @@ use parking_lot::RwLock;
 pub enum FfiType {
     Unit,
     Bool,
+    I8,
+    I16,
     I32,
     I64,
+    U8,
+    U16,
+    U32,
+    U64,
+    F32,
     F64,
     Str,
     Opaque,
@@ -28,8 +35,15 @@ impl fmt::Display for FfiType {
         match self {
             FfiType::Unit => write!(f, "unit"),
             FfiType::Bool => write!(f, "bool"),
+            FfiType::I8 => write!(f, "i8"),
+            FfiType::I16 => write!(f, "i16"),
             FfiType::I32 => write!(f, "i32"),
             FfiType::I64 => write!(f, "i64"),
+            FfiType::U8 => write!(f, "u8"),
+            FfiType::U16 => write!(f, "u16"),
+            FfiType::U32 => write!(f, "u32"),
+            FfiType::U64 => write!(f, "u64"),
+            FfiType::F32 => write!(f, "f32"),
             FfiType::F64 => write!(f, "f64"),
             FfiType::Str => write!(f, "str"),
             FfiType::Opaque => write!(f, "opaque"),
@@ -64,23 +78,39 @@ impl fmt::Display for FfiType {
 pub struct FfiSignature {
     pub params: Vec<FfiType>,
     pub result: FfiType,
+    /// Element type of a variadic tail. The last of `params` is then the list that the
+    /// remaining arguments of a call are collected into.
+    pub variadic: Option<FfiType>,
 }
 
 impl FfiSignature {
     pub fn new(params: Vec<FfiType>, result: FfiType) -> Self {
-        Self { params, result }
+        Self {
+            params,
+            result,
+            variadic: None,
+        }
+    }
+
+    /// Signature taking `params` followed by any number of `element` arguments, which the
+    /// function receives as one list handle
+    pub fn variadic(mut params: Vec<FfiType>, element: FfiType, result: FfiType) -> Self {
+        params.push(FfiType::List);
+        Self {
+            params,
+            result,
+            variadic: Some(element),
+        }
     }
 }
 
 impl fmt::Display for FfiSignature {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let params = self
-            .params
-            .iter()
-            .map(|ty| ty.to_string())
-            .collect::<Vec<_>>()
-            .join(", ");
-        write!(f, "({params}) -> {}", self.result)
+        let mut params: Vec<String> = self.params.iter().map(|ty| ty.to_string()).collect();
+        if let (Some(element), Some(tail)) = (&self.variadic, params.last_mut()) {
+            *tail = format!("*{element}");
+        }
+        write!(f, "({}) -> {}", params.join(", "), self.result)
     }
 }
 
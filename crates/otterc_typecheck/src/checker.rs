@@ -1,20 +1,69 @@
 use anyhow::{Result, bail};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
+use crate::consteval::{self, ConstValue};
+use crate::exhaustiveness;
 use crate::types::{
-    EnumDefinition, EnumLayout, StructDefinition, TypeContext, TypeError, TypeInfo,
+    EnumDefinition, EnumLayout, EnumVariantInfo, StructDefinition, TraitDefinition, TypeContext,
+    TypeError, TypeInfo,
 };
 use otterc_ast::nodes::{
-    BinaryOp, Block, Expr, FStringPart, Function, Literal, Node, Pattern, Program, Statement, Type,
-    UnaryOp, UseImport,
+    BinaryOp, Block, Expr, FStringPart, Function, Literal, MatchArm, Node, Param, Pattern, Program,
+    Statement, Type, UnaryOp, UseImport, derived_signature,
 };
 use otterc_config::LanguageFeatureFlags;
 use otterc_span::Span;
-use otterc_symbol::registry::{FfiType, SymbolRegistry};
+use otterc_symbol::registry::{FfiFunction, FfiSignature, FfiType, SymbolRegistry};
+
+/// Builtins returning a lazy `Iterator<T>` over their iterable argument, unless the program
+/// defines its own function or variable of that name
+pub const ITERATOR_ADAPTERS: [&str; 4] = ["map", "filter", "zip", "take"];
+
+/// How many `iter` methods are followed when resolving a struct's element type
+const MAX_ITER_DEPTH: usize = 8;
+
+/// How deeply instantiations of generic functions may nest before checking gives up
+const MAX_INSTANTIATION_DEPTH: usize = 32;
+
+/// Types of the exceptions runtime functions raise. `except ValueError as e` catches one of
+/// them by its type label and binds its message.
+pub const RUNTIME_EXCEPTION_TYPES: &[&str] = &["ValueError", "IndexError", "TypeError"];
+
+/// Name of one instantiation of a generic function, such as `max<i64>`. The code generator
+/// emits each instantiation as a separate function under this name.
+pub fn instantiation_name(function: &str, type_args: &[TypeInfo]) -> String {
+    let args = type_args
+        .iter()
+        .map(TypeInfo::display_name)
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("{}<{}>", function, args)
+}
+
+/// Arguments of a call in the order of the callee's parameters `names`. Parameters left to
+/// their default are `None`; a keyword argument that names no parameter, or one that is
+/// already given, is dropped. The code generator passes arguments in this order.
+pub fn arrange_arguments<'a>(
+    names: &[String],
+    args: &'a [Node<Expr>],
+    kwargs: &'a [(String, Node<Expr>)],
+) -> Vec<Option<&'a Node<Expr>>> {
+    let mut arranged: Vec<Option<&Node<Expr>>> = args.iter().map(Some).collect();
+    arranged.resize(names.len().max(args.len()), None);
+    for (name, value) in kwargs {
+        if let Some(index) = names.iter().position(|param| param == name)
+            && arranged[index].is_none()
+        {
+            arranged[index] = Some(value);
+        }
+    }
+    arranged
+}
 
 /// Type checker that validates and infers types in OtterLang programs
 pub struct TypeChecker {
     errors: Vec<TypeError>,
+    warnings: Vec<TypeError>,
     context: TypeContext,
     registry: Option<&'static SymbolRegistry>,
     expr_types: HashMap<usize, TypeInfo>,
@@ -23,9 +72,44 @@ pub struct TypeChecker {
     comprehension_var_types: HashMap<Span, TypeInfo>,
     method_comprehension_spans: HashMap<String, Vec<Span>>,
     method_expr_ids: HashMap<String, Vec<usize>>,
+    /// Copies of the methods checked under their qualified names. They live as long as the
+    /// checker, so the addresses recorded for their expressions are not reused, and those
+    /// addresses are left out of `into_type_maps`.
+    method_copies: Vec<Node<Function>>,
     features: LanguageFeatureFlags,
     /// Current function's return type (if inside a function)
     current_function_return_type: Option<TypeInfo>,
+    /// Number of `return`s checked so far in a closure without a return annotation, whose
+    /// first `return` gives the closure's return type
+    inferred_returns: Option<usize>,
+    /// Trait bounds on the generic parameters of the type whose methods are being checked
+    generic_bounds: Vec<(String, Vec<String>)>,
+    /// Whether an `except` clause is being checked, which makes a bare `raise` valid
+    handling_exception: bool,
+    /// Loops enclosing the statement being checked, innermost last
+    loops: Vec<LoopFrame>,
+    /// Values of the module's `const` declarations
+    constants: HashMap<String, ConstValue>,
+    /// Generic functions of the module, whose bodies are checked again for every instantiation
+    generic_functions: HashMap<String, Node<Function>>,
+    /// Generic functions whose bodies failed to type-check with their parameters left abstract
+    failed_generics: HashSet<String>,
+    /// Instantiations that have already been checked
+    instantiations: HashSet<String>,
+    instantiation_depth: usize,
+    /// Parameter names of the declared functions and methods, which keyword arguments refer to
+    param_names: HashMap<String, Vec<String>>,
+    /// Functions and methods whose last parameter collects the remaining positional arguments
+    variadic: HashSet<String>,
+}
+
+/// A loop that `break` and `continue` may refer to
+struct LoopFrame {
+    label: Option<String>,
+    /// `loop:` expressions are the only loops `break` may leave with a value
+    is_expr: bool,
+    /// Type of the first `break` out of a `loop:` expression, which the others must agree with
+    break_type: Option<TypeInfo>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -35,7 +119,11 @@ pub struct ModuleExports {
     pub variables: HashMap<String, TypeInfo>,
     pub structs: HashMap<String, StructDefinition>,
     pub enums: HashMap<String, EnumDefinition>,
+    pub traits: HashMap<String, TraitDefinition>,
+    pub trait_impls: HashMap<String, Vec<String>>,
     pub type_aliases: HashMap<String, TypeInfo>,
+    pub param_names: HashMap<String, Vec<String>>,
+    pub variadic: HashSet<String>,
 }
 
 impl ModuleExports {
@@ -51,6 +139,7 @@ impl ModuleExports {
             && self.variables.is_empty()
             && self.structs.is_empty()
             && self.enums.is_empty()
+            && self.traits.is_empty()
             && self.type_aliases.is_empty()
     }
 }
@@ -96,6 +185,19 @@ impl TypeChecker {
             || matches!(ty, TypeInfo::Generic { args, .. } if args.is_empty())
     }
 
+    /// Whether `ty` is or contains a type that is not known yet, which a literal of that
+    /// type should infer from its elements instead
+    fn mentions_unknown(ty: &TypeInfo) -> bool {
+        match ty.resolve_alias() {
+            TypeInfo::List(element) => Self::mentions_unknown(element),
+            TypeInfo::Dict { key, value } => {
+                Self::mentions_unknown(key) || Self::mentions_unknown(value)
+            }
+            TypeInfo::Tuple(elements) => elements.iter().any(Self::mentions_unknown),
+            ty => Self::is_unknown_like(ty),
+        }
+    }
+
     fn merge_unknown_like_types(left: &TypeInfo, right: &TypeInfo) -> TypeInfo {
         match (Self::is_unknown_like(left), Self::is_unknown_like(right)) {
             (false, false) | (false, true) => left.clone(),
@@ -153,6 +255,7 @@ impl TypeChecker {
 
         Self {
             errors: Vec::new(),
+            warnings: Vec::new(),
             context,
             registry: None,
             expr_types: HashMap::new(),
@@ -161,8 +264,20 @@ impl TypeChecker {
             comprehension_var_types: HashMap::new(),
             method_comprehension_spans: HashMap::new(),
             method_expr_ids: HashMap::new(),
+            method_copies: Vec::new(),
             features,
             current_function_return_type: None,
+            inferred_returns: None,
+            generic_bounds: Vec::new(),
+            handling_exception: false,
+            loops: Vec::new(),
+            constants: HashMap::new(),
+            generic_functions: HashMap::new(),
+            failed_generics: HashSet::new(),
+            instantiations: HashSet::new(),
+            instantiation_depth: 0,
+            param_names: HashMap::new(),
+            variadic: HashSet::new(),
         }
     }
 
@@ -238,6 +353,7 @@ impl TypeChecker {
         self.register_module_imports(&program.statements);
         // First pass: collect struct definitions, enums, and type aliases
         self.register_type_definitions(&program.statements);
+        self.register_constants(&program.statements);
 
         // Second pass: collect function signatures
         for statement in &program.statements {
@@ -246,6 +362,22 @@ impl TypeChecker {
                 self.context
                     .functions
                     .insert(function.as_ref().name.clone(), sig);
+                self.record_params(&function.as_ref().name, function.as_ref());
+                if function.as_ref().is_generic() {
+                    self.validate_bounds(&function.as_ref().bounds, *function.span());
+                    self.generic_functions
+                        .insert(function.as_ref().name.clone(), function.clone());
+                }
+            }
+        }
+
+        // Generic bodies are checked first, so that instantiations of a body that is already
+        // wrong are not reported a second time at every call site
+        for statement in &program.statements {
+            if let Statement::Function(function) = statement.as_ref()
+                && function.as_ref().is_generic()
+            {
+                self.check_generic_function(function)?;
             }
         }
 
@@ -254,17 +386,36 @@ impl TypeChecker {
             let span = statement.span();
             match statement.as_ref() {
                 Statement::Function(function) => {
-                    self.check_function(function)?;
+                    if !function.as_ref().is_generic() {
+                        self.check_function(function)?;
+                    }
                 }
                 Statement::Struct { name, methods, .. } => {
                     self.check_struct_methods(name, methods)?;
                 }
+                Statement::Impl {
+                    trait_name: Some(trait_name),
+                    type_name,
+                    methods,
+                } => {
+                    let methods = self.impl_methods(trait_name, type_name, methods);
+                    self.check_struct_methods(type_name, &methods)?;
+                }
+                Statement::Impl {
+                    trait_name: None,
+                    type_name,
+                    methods,
+                } => {
+                    self.check_struct_methods(type_name, methods)?;
+                }
                 Statement::Let { .. } | Statement::Expr(_) => {
                     // Top-level let and expressions are allowed
                     self.check_statement(statement)?;
                 }
                 Statement::Enum { .. }
+                | Statement::Trait { .. }
                 | Statement::TypeAlias { .. }
+                | Statement::Const { .. }
                 | Statement::Use { .. }
                 | Statement::PubUse { .. } => {}
                 _ => {
@@ -293,17 +444,60 @@ impl TypeChecker {
         struct_name: &str,
         methods: &[Node<Function>],
     ) -> Result<()> {
+        let bounds = self
+            .context
+            .get_struct(struct_name)
+            .map(|definition| definition.bounds.clone())
+            .unwrap_or_default();
+        let old_bounds = std::mem::replace(&mut self.generic_bounds, bounds);
         for method in methods {
             let mut method_clone = method.as_ref().clone();
             method_clone.name = format!("{}.{}", struct_name, method_clone.name);
-            self.rewrite_method_self_param(&mut method_clone, struct_name);
+            method_clone.resolve_self_type(struct_name);
             let node = Node::new(method_clone, *method.span());
             self.record_method_metadata(&node.as_ref().name, node.as_ref().body.as_ref());
-            self.check_function(&node)?;
+            let result = self.check_function(&node);
+            self.method_copies.push(node);
+            if let Err(err) = result {
+                self.generic_bounds = old_bounds;
+                return Err(err);
+            }
         }
+        self.generic_bounds = old_bounds;
         Ok(())
     }
 
+    /// Methods an `impl` block contributes to its type: the methods written in the block
+    /// plus a copy of every trait default it does not override, with `Self` resolved.
+    fn impl_methods(
+        &self,
+        trait_name: &str,
+        type_name: &str,
+        methods: &[Node<Function>],
+    ) -> Vec<Node<Function>> {
+        let mut resolved: Vec<Node<Function>> = methods
+            .iter()
+            .map(|method| {
+                let mut function = method.as_ref().clone();
+                function.resolve_self_type(type_name);
+                Node::new(function, *method.span())
+            })
+            .collect();
+        if let Some(definition) = self.context.get_trait(trait_name) {
+            for default in &definition.methods {
+                let overridden = methods
+                    .iter()
+                    .any(|method| method.as_ref().name == default.as_ref().name);
+                if !overridden && !default.as_ref().body.as_ref().is_empty() {
+                    let mut function = default.as_ref().clone();
+                    function.resolve_self_type(type_name);
+                    resolved.push(Node::new(function, *default.span()));
+                }
+            }
+        }
+        resolved
+    }
+
     fn record_method_metadata(&mut self, method_name: &str, body: &Block) {
         let mut spans = Vec::new();
         let mut expr_ids = Vec::new();
@@ -342,19 +536,31 @@ impl TypeChecker {
         match stmt {
             Statement::Expr(expr)
             | Statement::Let { expr, .. }
-            | Statement::Assignment { expr, .. } => {
+            | Statement::Return(Some(expr))
+            | Statement::Raise(Some(expr))
+            | Statement::Defer(expr)
+            | Statement::Break {
+                value: Some(expr), ..
+            } => {
+                self.collect_metadata_in_expr(expr, spans, expr_ids);
+            }
+            Statement::Assignment { target, expr, .. } => {
+                self.collect_metadata_in_expr(target, spans, expr_ids);
                 self.collect_metadata_in_expr(expr, spans, expr_ids);
             }
-            Statement::Return(Some(expr)) => self.collect_metadata_in_expr(expr, spans, expr_ids),
             Statement::Return(None)
-            | Statement::Break
-            | Statement::Continue
+            | Statement::Raise(None)
+            | Statement::Break { value: None, .. }
+            | Statement::Continue { .. }
             | Statement::Pass
             | Statement::Use { .. }
             | Statement::PubUse { .. }
             | Statement::Struct { .. }
             | Statement::Enum { .. }
+            | Statement::Trait { .. }
+            | Statement::Impl { .. }
             | Statement::TypeAlias { .. }
+            | Statement::Const { .. }
             | Statement::Function(_) => {}
             Statement::If {
                 cond,
@@ -372,14 +578,31 @@ impl TypeChecker {
                     self.collect_metadata_in_block(block.as_ref(), spans, expr_ids);
                 }
             }
+            Statement::With { expr, body, .. } => {
+                self.collect_metadata_in_expr(expr, spans, expr_ids);
+                self.collect_metadata_in_block(body.as_ref(), spans, expr_ids);
+            }
             Statement::For { iterable, body, .. } => {
                 self.collect_metadata_in_expr(iterable, spans, expr_ids);
                 self.collect_metadata_in_block(body.as_ref(), spans, expr_ids);
             }
-            Statement::While { cond, body } => {
+            Statement::While { cond, body, .. } => {
                 self.collect_metadata_in_expr(cond, spans, expr_ids);
                 self.collect_metadata_in_block(body.as_ref(), spans, expr_ids);
             }
+            Statement::Try {
+                body,
+                handlers,
+                finally_block,
+            } => {
+                self.collect_metadata_in_block(body.as_ref(), spans, expr_ids);
+                for handler in handlers {
+                    self.collect_metadata_in_block(handler.as_ref().body.as_ref(), spans, expr_ids);
+                }
+                if let Some(block) = finally_block {
+                    self.collect_metadata_in_block(block.as_ref(), spans, expr_ids);
+                }
+            }
             Statement::Block(block) => {
                 self.collect_metadata_in_block(block.as_ref(), spans, expr_ids);
             }
@@ -428,14 +651,32 @@ impl TypeChecker {
                 self.collect_metadata_in_expr(right, spans, expr_ids);
             }
             Expr::Unary { expr, .. }
+            | Expr::Cast { expr, .. }
             | Expr::Await(expr)
             | Expr::Spawn(expr)
+            | Expr::Try(expr)
+            | Expr::Spread(expr)
             | Expr::Member { object: expr, .. } => {
                 self.collect_metadata_in_expr(expr, spans, expr_ids);
             }
-            Expr::Call { func, args } => {
+            Expr::Lambda { body, .. } | Expr::Loop { body, .. } => {
+                self.collect_metadata_in_block(body.as_ref(), spans, expr_ids);
+            }
+            Expr::Index { object, index } => {
+                self.collect_metadata_in_expr(object, spans, expr_ids);
+                self.collect_metadata_in_expr(index, spans, expr_ids);
+            }
+            Expr::Slice { object, start, end } => {
+                self.collect_metadata_in_expr(object, spans, expr_ids);
+                for bound in [start, end].into_iter().flatten() {
+                    self.collect_metadata_in_expr(bound, spans, expr_ids);
+                }
+            }
+            Expr::Call {
+                func, args, kwargs, ..
+            } => {
                 self.collect_metadata_in_expr(func, spans, expr_ids);
-                for arg in args {
+                for arg in args.iter().chain(kwargs.iter().map(|(_, value)| value)) {
                     self.collect_metadata_in_expr(arg, spans, expr_ids);
                 }
             }
@@ -463,7 +704,7 @@ impl TypeChecker {
                 self.collect_metadata_in_expr(start, spans, expr_ids);
                 self.collect_metadata_in_expr(end, spans, expr_ids);
             }
-            Expr::Array(elements) => {
+            Expr::Tuple(elements) | Expr::Array(elements) => {
                 for elem in elements {
                     self.collect_metadata_in_expr(elem, spans, expr_ids);
                 }
@@ -474,7 +715,7 @@ impl TypeChecker {
                     self.collect_metadata_in_expr(value, spans, expr_ids);
                 }
             }
-            Expr::FString { parts } => {
+            Expr::FString { parts, .. } => {
                 for part in parts {
                     if let FStringPart::Expr(expr) = part.as_ref() {
                         self.collect_metadata_in_expr(expr, spans, expr_ids);
@@ -538,24 +779,19 @@ impl TypeChecker {
         }
     }
 
-    fn rewrite_method_self_param(&self, method_func: &mut Function, struct_name: &str) {
-        let Some(first_param) = method_func.params.first_mut() else {
-            return;
-        };
-
-        if let Some(ty) = first_param.as_ref().ty.as_ref() {
-            if matches!(ty.as_ref(), Type::Simple(name) if name == "Self") {
-                let span = *ty.span();
-                let replacement = Type::Simple(struct_name.to_string());
-                first_param.as_mut().ty = Some(Node::new(replacement, span));
-            }
-            return;
-        }
-
-        if first_param.as_ref().name.as_ref() == "self" {
-            let span = *first_param.as_ref().name.span();
-            let replacement = Type::Simple(struct_name.to_string());
-            first_param.as_mut().ty = Some(Node::new(replacement, span));
+    fn record_params(&mut self, name: &str, function: &Function) {
+        let names = function
+            .params
+            .iter()
+            .map(|param| param.as_ref().name.as_ref().clone())
+            .collect();
+        self.param_names.insert(name.to_string(), names);
+        if function
+            .params
+            .last()
+            .is_some_and(|param| param.as_ref().variadic)
+        {
+            self.variadic.insert(name.to_string());
         }
     }
 
@@ -704,7 +940,35 @@ impl TypeChecker {
         }
     }
 
+    /// Evaluate `const` declarations and make them visible as variables of their declared type
+    fn register_constants(&mut self, statements: &[Node<Statement>]) {
+        let context = &self.context;
+        let (constants, errors) =
+            consteval::evaluate_constants(statements, |ty| context.type_from_annotation(ty));
+        self.errors.extend(errors);
+
+        for statement in statements {
+            if let Statement::Const { name, ty, .. } = statement.as_ref() {
+                let ty = self.context.type_from_annotation(ty);
+                self.context.insert_variable(name.clone(), ty);
+            }
+        }
+        self.constants = constants;
+    }
+
     fn register_type_definitions(&mut self, statements: &[Node<Statement>]) {
+        // Traits come first so that bounds and impls can refer to them regardless of order
+        for statement in statements {
+            if let Statement::Trait {
+                name,
+                methods,
+                public,
+            } = statement.as_ref()
+            {
+                self.register_trait(name, methods, *public);
+            }
+        }
+
         for statement in statements {
             match statement.as_ref() {
                 Statement::Struct {
@@ -712,7 +976,9 @@ impl TypeChecker {
                     fields,
                     methods,
                     generics,
+                    bounds,
                     public,
+                    ..
                 } => {
                     let mut field_types = HashMap::new();
                     for (field_name, field_ty) in fields {
@@ -757,9 +1023,11 @@ impl TypeChecker {
                             );
                         }
                     }
+                    self.validate_bounds(bounds, *statement.span());
                     let definition = StructDefinition {
                         name: name.clone(),
                         generics: generics.clone(),
+                        bounds: bounds.clone(),
                         fields: field_types,
                         public: *public,
                     };
@@ -772,7 +1040,7 @@ impl TypeChecker {
 
                     for method in methods {
                         let mut method_clone = method.as_ref().clone();
-                        self.rewrite_method_self_param(&mut method_clone, name);
+                        method_clone.resolve_self_type(name);
                         let method_name = format!("{}.{}", name, method_clone.name);
                         let method_node = Node::new(method_clone, *method.span());
                         self.record_method_metadata(
@@ -780,6 +1048,7 @@ impl TypeChecker {
                             method_node.as_ref().body.as_ref(),
                         );
                         let sig = self.infer_function_signature(&method_node);
+                        self.record_params(&method_name, method_node.as_ref());
                         self.context.insert_function(method_name.clone(), sig);
                     }
 
@@ -801,11 +1070,15 @@ impl TypeChecker {
                     name,
                     variants,
                     generics,
+                    bounds,
                     public,
+                    ..
                 } => {
+                    self.validate_bounds(bounds, *statement.span());
                     let definition = EnumDefinition {
                         name: name.clone(),
                         generics: generics.clone(),
+                        bounds: bounds.clone(),
                         variants: variants.iter().map(|v| v.as_ref()).cloned().collect(),
                         public: *public,
                     };
@@ -814,6 +1087,463 @@ impl TypeChecker {
                 _ => {}
             }
         }
+
+        for statement in statements {
+            if let Statement::Impl {
+                trait_name,
+                type_name,
+                methods,
+            } = statement.as_ref()
+            {
+                match trait_name {
+                    Some(trait_name) => {
+                        self.register_trait_impl(trait_name, type_name, methods, *statement.span());
+                    }
+                    None => self.register_inherent_methods(type_name, methods, *statement.span()),
+                }
+            }
+        }
+
+        // Derived methods come last so that they can be checked against every method the
+        // type defines by hand
+        self.register_derives(statements);
+    }
+
+    /// Record the methods of an `impl Type:` block as `Type.method`, like the methods
+    /// written in a struct body. Enums get their methods this way.
+    fn register_inherent_methods(
+        &mut self,
+        type_name: &str,
+        methods: &[Node<Function>],
+        span: Span,
+    ) {
+        let variants: Vec<String> = if self.context.get_struct(type_name).is_some() {
+            Vec::new()
+        } else if let Some(definition) = self.context.get_enum(type_name) {
+            definition
+                .variants
+                .iter()
+                .map(|variant| variant.name.clone())
+                .collect()
+        } else {
+            self.errors.push(
+                TypeError::new(format!(
+                    "cannot add methods to '{}': it is not a struct or enum",
+                    type_name
+                ))
+                .with_hint(format!(
+                    "Declare `struct {}` or `enum {}` in this module before its `impl` block",
+                    type_name, type_name
+                ))
+                .with_span(span),
+            );
+            return;
+        };
+
+        for method in methods {
+            let name = &method.as_ref().name;
+            let method_name = format!("{}.{}", type_name, name);
+            if variants.contains(name) {
+                self.errors.push(
+                    TypeError::new(format!(
+                        "method '{}' has the same name as a variant of enum '{}'",
+                        name, type_name
+                    ))
+                    .with_hint(
+                        "Rename the method; `Enum.Variant` must name the variant".to_string(),
+                    )
+                    .with_span(*method.span()),
+                );
+                continue;
+            }
+            if self.context.get_function(&method_name).is_some() {
+                self.errors.push(
+                    TypeError::new(format!("duplicate method '{}' on '{}'", name, type_name))
+                        .with_hint("Each method name can only be defined once per type".to_string())
+                        .with_span(*method.span()),
+                );
+                continue;
+            }
+            let mut function = method.as_ref().clone();
+            function.resolve_self_type(type_name);
+            let node = Node::new(function, *method.span());
+            self.record_method_metadata(&method_name, node.as_ref().body.as_ref());
+            let sig = self.infer_function_signature(&node);
+            self.record_params(&method_name, node.as_ref());
+            self.context.insert_function(method_name, sig);
+        }
+    }
+
+    /// Record the methods named by the `@derive` attributes of each struct and enum as
+    /// `Type.method`. The code generator emits their bodies, so the checks that would find
+    /// errors in them happen here: every field must support the operation, and the type may
+    /// not define the method itself.
+    fn register_derives(&mut self, statements: &[Node<Statement>]) {
+        let mut derived = Vec::new();
+        for statement in statements {
+            let (kind, name, generics, derives, fields) = match statement.as_ref() {
+                Statement::Struct {
+                    name,
+                    fields,
+                    generics,
+                    derives,
+                    ..
+                } => {
+                    let fields: Vec<_> = fields
+                        .iter()
+                        .map(|(field, ty)| (format!("field `{}`", field), ty.clone()))
+                        .collect();
+                    ("struct", name, generics, derives, fields)
+                }
+                Statement::Enum {
+                    name,
+                    variants,
+                    generics,
+                    derives,
+                    ..
+                } => {
+                    let fields: Vec<_> = variants
+                        .iter()
+                        .flat_map(|variant| {
+                            let variant = variant.as_ref();
+                            variant.fields.iter().map(move |ty| {
+                                (format!("`{}.{}` payload", name, variant.name), ty.clone())
+                            })
+                        })
+                        .collect();
+                    ("enum", name, generics, derives, fields)
+                }
+                _ => continue,
+            };
+            let Some(first) = derives.first() else {
+                continue;
+            };
+            if !generics.is_empty() {
+                self.errors.push(
+                    TypeError::new(format!(
+                        "cannot derive methods for generic {} `{}`",
+                        kind, name
+                    ))
+                    .with_hint(format!("Write the methods in an `impl {}:` block", name))
+                    .with_span(*first.span()),
+                );
+                continue;
+            }
+
+            let mut seen = HashSet::new();
+            for derive in derives {
+                let Some(mut method) = derived_signature(derive) else {
+                    self.errors.push(
+                        TypeError::new(format!(
+                            "unknown derive `{}`; expected one of Eq, Ord, Hash, Debug, Clone",
+                            derive.as_ref()
+                        ))
+                        .with_span(*derive.span()),
+                    );
+                    continue;
+                };
+                let method_name = format!("{}.{}", name, method.name);
+                if !seen.insert(derive.as_ref().clone()) {
+                    self.errors.push(
+                        TypeError::new(format!("`{}` is derived more than once", derive.as_ref()))
+                            .with_span(*derive.span()),
+                    );
+                    continue;
+                }
+                if self.context.get_function(&method_name).is_some() {
+                    self.errors.push(
+                        TypeError::new(format!(
+                            "cannot derive `{}`: `{}` already defines `{}`",
+                            derive.as_ref(),
+                            name,
+                            method.name
+                        ))
+                        .with_hint(format!(
+                            "Remove `{}` from `@derive` or the `{}` method written by hand",
+                            derive.as_ref(),
+                            method.name
+                        ))
+                        .with_span(*derive.span()),
+                    );
+                    continue;
+                }
+                method.resolve_self_type(name);
+                let node = Node::new(method, *derive.span());
+                let sig = self.infer_function_signature(&node);
+                self.record_params(&method_name, node.as_ref());
+                self.context.insert_function(method_name, sig);
+                derived.push((
+                    derive.clone(),
+                    node.as_ref().name.clone(),
+                    name,
+                    fields.clone(),
+                ));
+            }
+        }
+
+        // Fields are checked once every derived method is known, since they may use each other
+        for (derive, method, type_name, fields) in derived {
+            for (field, annotation) in fields {
+                let ty = self.context.type_from_annotation(&annotation);
+                let Some(reason) = self.underivable_field(derive.as_ref(), &method, &ty) else {
+                    continue;
+                };
+                self.errors.push(
+                    TypeError::new(format!(
+                        "cannot derive `{}` for `{}`: {} has type `{}`, {}",
+                        derive.as_ref(),
+                        type_name,
+                        field,
+                        annotation,
+                        reason
+                    ))
+                    .with_span(*derive.span()),
+                );
+            }
+        }
+    }
+
+    /// Why a field of type `ty` cannot take part in the method `method` that `@derive(derive)`
+    /// generates, or `None` when it can
+    fn underivable_field(&self, derive: &str, method: &str, ty: &TypeInfo) -> Option<String> {
+        let ty = self.context.normalize_type(ty.resolve_alias().clone());
+        let supported = match &ty {
+            TypeInfo::Struct { name, .. } | TypeInfo::Enum { name, .. } => {
+                return (derive != "Clone"
+                    && self
+                        .context
+                        .get_function(&format!("{}.{}", name, method))
+                        .is_none())
+                .then(|| format!("which has no `{}` method", method));
+            }
+            TypeInfo::Error => true,
+            ty if Self::is_unknown_like(ty) => true,
+            ty => match derive {
+                "Eq" | "Hash" => ty.is_numeric() || matches!(ty, TypeInfo::Str | TypeInfo::Bool),
+                "Ord" => ty.is_numeric() || matches!(ty, TypeInfo::Str),
+                _ => true,
+            },
+        };
+        let reason = match derive {
+            "Eq" => "which cannot be compared with `==`",
+            "Ord" => "which cannot be compared with `<`",
+            _ => "which cannot be hashed",
+        };
+        (!supported).then(|| reason.to_string())
+    }
+
+    fn register_trait(&mut self, name: &str, methods: &[Node<Function>], public: bool) {
+        let mut signatures = HashMap::new();
+        let mut declared = Vec::new();
+        for method in methods {
+            let mut function = method.as_ref().clone();
+            // Trait signatures keep `Self`; this only types an unannotated receiver
+            function.resolve_self_type("Self");
+            let node = Node::new(function, *method.span());
+            let sig = self.infer_function_signature(&node);
+            signatures.insert(node.as_ref().name.clone(), sig);
+            declared.push(node);
+        }
+        self.context.define_trait(TraitDefinition {
+            name: name.to_string(),
+            methods: declared,
+            signatures,
+            public,
+        });
+    }
+
+    fn register_trait_impl(
+        &mut self,
+        trait_name: &str,
+        type_name: &str,
+        methods: &[Node<Function>],
+        span: Span,
+    ) {
+        let Some(definition) = self.context.get_trait(trait_name).cloned() else {
+            self.errors.push(
+                TypeError::new(format!("unknown trait '{}'", trait_name))
+                    .with_hint(
+                        "Declare the trait with `trait Name:` before implementing it".to_string(),
+                    )
+                    .with_span(span),
+            );
+            return;
+        };
+        let Some(struct_def) = self.context.get_struct(type_name).cloned() else {
+            self.errors.push(
+                TypeError::new(format!(
+                    "cannot implement trait '{}' for '{}': traits can only be implemented for structs",
+                    trait_name, type_name
+                ))
+                .with_span(span),
+            );
+            return;
+        };
+        let self_type = TypeInfo::Struct {
+            name: struct_def.name.clone(),
+            fields: struct_def.fields.clone(),
+        };
+        let self_subst = HashMap::from([("Self".to_string(), self_type)]);
+
+        for method in methods {
+            let method_name = &method.as_ref().name;
+            let Some(expected) = definition.signatures.get(method_name) else {
+                self.errors.push(
+                    TypeError::new(format!(
+                        "method '{}' is not a member of trait '{}'",
+                        method_name, trait_name
+                    ))
+                    .with_hint(format!(
+                        "Move `{}` into the struct body or add it to the trait",
+                        method_name
+                    ))
+                    .with_span(*method.span()),
+                );
+                continue;
+            };
+            let expected = expected.substitute(&self_subst);
+            let mut function = method.as_ref().clone();
+            function.resolve_self_type(type_name);
+            let actual = self.infer_function_signature(&Node::new(function, *method.span()));
+            if !Self::signature_matches(&actual, &expected) {
+                self.errors.push(
+                    TypeError::new(format!(
+                        "method '{}' has an incompatible signature for trait '{}'",
+                        method_name, trait_name
+                    ))
+                    .with_hint(format!(
+                        "expected {}, found {}",
+                        expected.display_name(),
+                        actual.display_name()
+                    ))
+                    .with_span(*method.span()),
+                );
+            }
+        }
+
+        for required in definition
+            .methods
+            .iter()
+            .filter(|method| definition.is_required(&method.as_ref().name))
+        {
+            let name = &required.as_ref().name;
+            if !methods.iter().any(|method| &method.as_ref().name == name) {
+                self.errors.push(
+                    TypeError::new(format!(
+                        "missing method '{}' in implementation of trait '{}' for '{}'",
+                        name, trait_name, type_name
+                    ))
+                    .with_hint(format!("Add `fn {}` to the impl block", name))
+                    .with_span(span),
+                );
+            }
+        }
+
+        for method in self.impl_methods(trait_name, type_name, methods) {
+            let method_name = format!("{}.{}", type_name, method.as_ref().name);
+            if self.context.get_function(&method_name).is_some() {
+                self.errors.push(
+                    TypeError::new(format!(
+                        "duplicate method '{}' on '{}'",
+                        method.as_ref().name,
+                        type_name
+                    ))
+                    .with_hint("A struct method and a trait method cannot share a name".to_string())
+                    .with_span(*method.span()),
+                );
+                continue;
+            }
+            self.record_method_metadata(&method_name, method.as_ref().body.as_ref());
+            let sig = self.infer_function_signature(&method);
+            self.record_params(&method_name, method.as_ref());
+            self.context.insert_function(method_name, sig);
+        }
+
+        self.context
+            .add_trait_impl(type_name.to_string(), trait_name.to_string());
+    }
+
+    fn signature_matches(actual: &TypeInfo, expected: &TypeInfo) -> bool {
+        match (actual, expected) {
+            (
+                TypeInfo::Function {
+                    params: actual_params,
+                    return_type: actual_ret,
+                    ..
+                },
+                TypeInfo::Function {
+                    params: expected_params,
+                    return_type: expected_ret,
+                    ..
+                },
+            ) => {
+                actual_params.len() == expected_params.len()
+                    && actual_params
+                        .iter()
+                        .zip(expected_params)
+                        .all(|(actual, expected)| actual.is_compatible_with(expected))
+                    && actual_ret.is_compatible_with(expected_ret)
+            }
+            _ => false,
+        }
+    }
+
+    /// Report bounds that name unknown traits
+    fn validate_bounds(&mut self, bounds: &[(String, Vec<String>)], span: Span) {
+        for (generic, traits) in bounds {
+            for trait_name in traits {
+                if self.context.get_trait(trait_name).is_none() {
+                    self.errors.push(
+                        TypeError::new(format!(
+                            "unknown trait '{}' in bound on '{}'",
+                            trait_name, generic
+                        ))
+                        .with_span(span),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Check the types inferred for generic parameters against their trait bounds
+    fn check_generic_bounds(
+        &mut self,
+        owner: &str,
+        bounds: &[(String, Vec<String>)],
+        inferred: &HashMap<String, TypeInfo>,
+        span: Span,
+    ) {
+        for (generic, traits) in bounds {
+            let Some(concrete) = inferred.get(generic) else {
+                continue;
+            };
+            for trait_name in traits {
+                if !self.satisfies_bound(concrete, trait_name) {
+                    let type_name = Self::trait_target_name(concrete);
+                    self.errors.push(
+                        TypeError::new(format!(
+                            "type '{}' does not implement trait '{}' required by '{}<{}: {}>'",
+                            type_name, trait_name, owner, generic, trait_name
+                        ))
+                        .with_hint(format!("Add `impl {} for {}`", trait_name, type_name))
+                        .with_span(span),
+                    );
+                }
+            }
+        }
+    }
+
+    fn satisfies_bound(&self, ty: &TypeInfo, trait_name: &str) -> bool {
+        match ty.resolve_alias() {
+            TypeInfo::Struct { name, .. } => self.context.implements_trait(name, trait_name),
+            TypeInfo::Dyn(name) => name == trait_name,
+            TypeInfo::Generic { base, args } if args.is_empty() => self
+                .generic_bounds
+                .iter()
+                .any(|(generic, traits)| generic == base && traits.iter().any(|t| t == trait_name)),
+            TypeInfo::Unknown | TypeInfo::Error => true,
+            _ => false,
+        }
     }
 
     /// Type check a function
@@ -827,6 +1557,7 @@ impl TypeChecker {
 
         let mut fn_context = TypeContext::with_features(self.features.clone());
         fn_context.variables = self.context.variables.clone();
+        fn_context.generic_args = self.context.generic_args.clone();
 
         // Add function parameters to context, overriding any globals/imports
         for param in &function.as_ref().params {
@@ -846,78 +1577,259 @@ impl TypeChecker {
         fn_context.structs = self.context.structs.clone();
         fn_context.type_aliases = self.context.type_aliases.clone();
         fn_context.enums = self.context.enums.clone();
+        fn_context.traits = self.context.traits.clone();
+        fn_context.trait_impls = self.context.trait_impls.clone();
 
         // Type check function body with return type tracking
         let old_context = std::mem::replace(&mut self.context, fn_context);
+        for param in &function.as_ref().params {
+            if let Some(pattern) = &param.as_ref().pattern {
+                let param_type = self
+                    .context
+                    .get_variable(param.as_ref().name.as_ref())
+                    .cloned()
+                    .unwrap_or(TypeInfo::Unknown);
+                self.bind_irrefutable_pattern(pattern, &param_type, "parameter");
+            }
+        }
         let old_return_type = self.current_function_return_type.replace(return_type);
-        let _ = self.check_block(&function.as_ref().body)?;
+        let old_inferred_returns = self.inferred_returns.take();
+        let outer_loops = std::mem::take(&mut self.loops);
+        let checked = self.check_block(&function.as_ref().body);
+        self.loops = outer_loops;
         self.context = old_context;
         self.current_function_return_type = old_return_type;
+        self.inferred_returns = old_inferred_returns;
+        checked?;
 
         Ok(())
     }
 
-    /// Check function with generic type parameters
-    /// This handles functions that have generic type parameters in their signature
-    #[expect(dead_code, reason = "Work in progress")]
-    fn check_function_with_generics(&mut self, function: &Node<Function>) -> Result<()> {
-        // Extract generic type parameters from function signature
-        let mut generic_params = Vec::new();
-
-        // Check parameter types for generic parameters
-        for param in &function.as_ref().params {
-            if let Some(ty) = &param.as_ref().ty {
-                self.extract_generic_params(ty, &mut generic_params);
-            }
-        }
-
-        if let Some(ret_ty) = &function.as_ref().ret_ty {
-            self.extract_generic_params(ret_ty, &mut generic_params);
-        }
-
-        // Push generic parameters to context
-        for param in &generic_params {
+    /// Check a generic function with its type parameters left abstract. Only the operations
+    /// every type supports, and the methods of the parameters' trait bounds, are accepted.
+    fn check_generic_function(&mut self, function: &Node<Function>) -> Result<()> {
+        let errors = self.errors.len();
+        let bounds = function.as_ref().bounds.clone();
+        let old_bounds = std::mem::replace(&mut self.generic_bounds, bounds);
+        for param in &function.as_ref().generics {
             self.context.push_generic(param.clone());
         }
-
-        // Type check function body
         let result = self.check_function(function);
-
-        // Pop generic parameters
-        for _ in &generic_params {
+        for _ in &function.as_ref().generics {
             self.context.pop_generic();
         }
-
+        self.generic_bounds = old_bounds;
+        if self.errors.len() > errors {
+            self.failed_generics.insert(function.as_ref().name.clone());
+        }
         result
     }
 
-    /// Extract generic type parameter names from a type
-    fn extract_generic_params(&self, ty: &Node<Type>, params: &mut Vec<String>) {
-        match ty.as_ref() {
-            Type::Simple(name) => {
-                // Check if this looks like a generic parameter (single uppercase letter or common generic names)
-                // In OtterLang, generic parameters are typically single uppercase letters (T, U, etc.)
-                if name.len() == 1
-                    && name.chars().next().unwrap().is_uppercase()
-                    && !params.contains(name)
-                {
-                    params.push(name.clone());
-                }
+    /// Signature of a generic function at one call site, with its type parameters replaced
+    /// by the explicit type arguments or by the types inferred from the arguments
+    fn instantiate_generic_call(
+        &mut self,
+        function: &Node<Function>,
+        type_args: &[Node<Type>],
+        args: &[Option<&Node<Expr>>],
+        rest: &[Node<Expr>],
+        span: Span,
+    ) -> Result<TypeInfo> {
+        let name = &function.as_ref().name;
+        let generics = &function.as_ref().generics;
+        let Some(signature @ TypeInfo::Function { .. }) = self.context.get_function(name).cloned()
+        else {
+            return Ok(TypeInfo::Error);
+        };
+
+        let mut inferred = HashMap::new();
+        if !type_args.is_empty() {
+            if type_args.len() != generics.len() {
+                self.errors.push(
+                    TypeError::new(format!(
+                        "`{}` expects {} type argument{}, got {}",
+                        name,
+                        generics.len(),
+                        if generics.len() == 1 { "" } else { "s" },
+                        type_args.len()
+                    ))
+                    .with_hint(format!(
+                        "`{}` is declared as `fn {}<{}>`",
+                        name,
+                        name,
+                        generics.join(", ")
+                    ))
+                    .with_span(span),
+                );
+                return Ok(TypeInfo::Error);
             }
-            Type::Generic { base, args } => {
-                // Check if base is a generic parameter
-                if base.len() == 1
-                    && base.chars().next().unwrap().is_uppercase()
-                    && !params.contains(base)
-                {
-                    params.push(base.clone());
+            for (generic, ty) in generics.iter().zip(type_args) {
+                inferred.insert(generic.clone(), self.context.type_from_annotation(ty));
+            }
+        } else {
+            let TypeInfo::Function { params, .. } = &signature else {
+                unreachable!()
+            };
+            // The arguments are checked against the instantiated signature afterwards, so
+            // the diagnostics of this first pass are dropped
+            let (errors, warnings) = (self.errors.len(), self.warnings.len());
+            let mut bindings: Vec<(&Node<Expr>, &TypeInfo)> = args
+                .iter()
+                .zip(params)
+                .filter_map(|(arg, param)| arg.map(|arg| (arg, param)))
+                .collect();
+            // Arguments collected by a variadic parameter are elements of its list, or lists
+            // themselves when spread
+            if let Some(list @ TypeInfo::List(element)) = params.last() {
+                for arg in rest {
+                    bindings.push(match arg.as_ref() {
+                        Expr::Spread(inner) => (inner.as_ref(), list),
+                        _ => (arg, element.as_ref()),
+                    });
                 }
-                // Recursively extract from type arguments
-                for arg in args {
-                    self.extract_generic_params(arg, params);
+            }
+            for (arg, param) in bindings {
+                let arg_type = self.infer_expr_type(arg)?;
+                if !matches!(arg_type, TypeInfo::Unknown | TypeInfo::Error) {
+                    self.infer_generics_from_type_info(param, &arg_type, generics, &mut inferred);
                 }
             }
+            self.errors.truncate(errors);
+            self.warnings.truncate(warnings);
+
+            if let Some(missing) = generics
+                .iter()
+                .find(|generic| !inferred.contains_key(*generic))
+            {
+                self.errors.push(
+                    TypeError::new(format!(
+                        "cannot infer type parameter `{}` of `{}`",
+                        missing, name
+                    ))
+                    .with_hint(format!(
+                        "Pass the type arguments explicitly: `{}<{}>(...)`",
+                        name,
+                        generics.join(", ")
+                    ))
+                    .with_span(span),
+                );
+                return Ok(TypeInfo::Error);
+            }
+        }
+
+        let errors = self.errors.len();
+        self.check_generic_bounds(name, &function.as_ref().bounds, &inferred, span);
+        if self.errors.len() > errors {
+            return Ok(TypeInfo::Error);
+        }
+
+        let concrete_args: Vec<TypeInfo> = generics
+            .iter()
+            .map(|generic| inferred[generic].clone())
+            .collect();
+        let mut free_params = HashSet::new();
+        for arg in &concrete_args {
+            self.collect_generic_usages(arg, &mut free_params);
+        }
+        // Calls made from another generic body are checked when that body is instantiated
+        if free_params.is_empty() {
+            self.check_instantiation(function, &inferred, &concrete_args, span)?;
+        }
+
+        Ok(signature.substitute(&inferred))
+    }
+
+    /// Runtime function for an explicitly typed call such as `task.channel<int>()`. The
+    /// registry names these overloads `name<types>`.
+    fn typed_runtime_overload(
+        &self,
+        func: &Node<Expr>,
+        type_args: &[Node<Type>],
+    ) -> Option<FfiFunction> {
+        let path = match func.as_ref() {
+            Expr::Identifier(name) => name.clone(),
+            Expr::Member { object, field } => self.build_member_path(object, field),
+            _ => return None,
+        };
+        let types = type_args
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(",");
+        self.registry?.resolve(&format!("{}<{}>", path, types))
+    }
+
+    /// Check the body of a generic function once for every distinct list of type arguments.
+    /// Failures are reported at the call site that introduced the instantiation.
+    fn check_instantiation(
+        &mut self,
+        function: &Node<Function>,
+        inferred: &HashMap<String, TypeInfo>,
+        type_args: &[TypeInfo],
+        span: Span,
+    ) -> Result<()> {
+        let name = &function.as_ref().name;
+        if self.failed_generics.contains(name) {
+            return Ok(());
+        }
+        let instance = instantiation_name(name, type_args);
+        if !self.instantiations.insert(instance.clone()) {
+            return Ok(());
+        }
+        if self.instantiation_depth >= MAX_INSTANTIATION_DEPTH {
+            self.errors.push(
+                TypeError::new(format!(
+                    "instantiation `{}` is nested more than {} levels deep",
+                    instance, MAX_INSTANTIATION_DEPTH
+                ))
+                .with_hint(
+                    "A generic function must not call itself with ever larger type arguments"
+                        .to_string(),
+                )
+                .with_span(span),
+            );
+            return Ok(());
+        }
+
+        // The types recorded for the body stay those of the generic check; the code generator
+        // substitutes the type arguments into them for each instantiation
+        let expr_types = self.expr_types.clone();
+        let expr_types_by_span = self.expr_types_by_span.clone();
+        let expr_spans = self.expr_spans.clone();
+        let comprehension_var_types = self.comprehension_var_types.clone();
+        let (errors, warnings) = (self.errors.len(), self.warnings.len());
+        let old_args = std::mem::replace(&mut self.context.generic_args, inferred.clone());
+        self.instantiation_depth += 1;
+
+        let result = self.check_function(function);
+
+        self.instantiation_depth -= 1;
+        self.context.generic_args = old_args;
+        self.expr_types = expr_types;
+        self.expr_types_by_span = expr_types_by_span;
+        self.expr_spans = expr_spans;
+        self.comprehension_var_types = comprehension_var_types;
+        self.warnings.truncate(warnings);
+
+        let substitutions = function
+            .as_ref()
+            .generics
+            .iter()
+            .zip(type_args)
+            .map(|(generic, ty)| format!("`{}` is {}", generic, ty.display_name()))
+            .collect::<Vec<_>>()
+            .join(", ");
+        for failure in self.errors.split_off(errors) {
+            self.errors.push(
+                TypeError::new(format!(
+                    "instantiation `{}` does not type-check: {}",
+                    instance, failure.message
+                ))
+                .with_hint(format!("In this call {}", substitutions))
+                .with_span(span),
+            );
         }
+        result
     }
 
     fn try_eval_enum_constructor(
@@ -934,6 +1846,14 @@ impl TypeChecker {
                 .iter()
                 .find(|variant| variant.name == *field)
             else {
+                // `Enum.name()` may call an associated function instead
+                if self
+                    .context
+                    .get_function(&format!("{}.{}", enum_name, field))
+                    .is_some()
+                {
+                    return Ok(None);
+                }
                 self.errors.push(
                     TypeError::new(format!("enum '{}' has no variant '{}'", enum_name, field))
                         .with_span(*object.span()),
@@ -983,6 +1903,8 @@ impl TypeChecker {
                 self.infer_enum_generics_from_type(field_ty, actual_ty, &definition, &mut inferred);
             }
 
+            self.check_generic_bounds(enum_name, &definition.bounds, &inferred, *func.span());
+
             let resolved_args = definition
                 .generics
                 .iter()
@@ -1007,6 +1929,14 @@ impl TypeChecker {
                         .iter()
                         .any(|arg| self.type_contains_enum_generic(arg.as_ref(), generics))
             }
+            Type::Function { params, ret } => params
+                .iter()
+                .chain(ret.as_deref())
+                .any(|ty| self.type_contains_enum_generic(ty.as_ref(), generics)),
+            Type::Tuple(elements) => elements
+                .iter()
+                .any(|ty| self.type_contains_enum_generic(ty.as_ref(), generics)),
+            Type::Dyn(_) => false,
         }
     }
 
@@ -1083,6 +2013,39 @@ impl TypeChecker {
                     }
                 }
             }
+            Type::Function { params, ret } => {
+                if let TypeInfo::Function {
+                    params: actual_params,
+                    return_type,
+                    ..
+                } = actual
+                {
+                    for (expected_param, actual_param) in params.iter().zip(actual_params.iter()) {
+                        self.infer_generics_from_type(
+                            expected_param,
+                            actual_param,
+                            generics,
+                            inferred,
+                        );
+                    }
+                    if let Some(ret) = ret {
+                        self.infer_generics_from_type(ret, return_type, generics, inferred);
+                    }
+                }
+            }
+            Type::Tuple(elements) => {
+                if let TypeInfo::Tuple(actual_elements) = actual {
+                    for (expected_elem, actual_elem) in elements.iter().zip(actual_elements) {
+                        self.infer_generics_from_type(
+                            expected_elem,
+                            actual_elem,
+                            generics,
+                            inferred,
+                        );
+                    }
+                }
+            }
+            Type::Dyn(_) => {}
         }
     }
 
@@ -1164,6 +2127,18 @@ impl TypeChecker {
                     self.infer_generics_from_type_info(value, actual_value, generics, inferred);
                 }
             }
+            TypeInfo::Tuple(elements) => {
+                if let TypeInfo::Tuple(actual_elements) = actual {
+                    for (expected_elem, actual_elem) in elements.iter().zip(actual_elements) {
+                        self.infer_generics_from_type_info(
+                            expected_elem,
+                            actual_elem,
+                            generics,
+                            inferred,
+                        );
+                    }
+                }
+            }
             _ => {}
         }
     }
@@ -1180,10 +2155,38 @@ impl TypeChecker {
 
     /// Bind variables from a pattern into the type checking context
     fn bind_pattern_variables(&mut self, pattern: &Node<Pattern>, ty: &TypeInfo) {
+        let mut bindings = Vec::new();
+        self.collect_pattern_bindings(pattern, ty, &mut bindings);
+        for (index, (name, _)) in bindings.iter().enumerate() {
+            if bindings[..index].iter().any(|(earlier, _)| earlier == name) {
+                self.errors.push(
+                    TypeError::new(format!(
+                        "variable `{}` is bound more than once in the same pattern",
+                        name
+                    ))
+                    .with_span(*pattern.span()),
+                );
+            }
+        }
+        for (name, ty) in bindings {
+            self.context.insert_variable(name, ty);
+        }
+    }
+
+    /// Names a pattern binds, with their types, in source order
+    fn collect_pattern_bindings(
+        &mut self,
+        pattern: &Node<Pattern>,
+        ty: &TypeInfo,
+        bindings: &mut Vec<(String, TypeInfo)>,
+    ) {
         match pattern.as_ref() {
+            Pattern::Identifier(name) if self.constants.contains_key(name) => {
+                // Constants are matched by value and bind nothing
+            }
             Pattern::Identifier(name) => {
                 // Simple identifier pattern binds the whole value
-                self.context.insert_variable(name.clone(), ty.clone());
+                bindings.push((name.clone(), ty.clone()));
             }
             Pattern::EnumVariant {
                 enum_name,
@@ -1202,7 +2205,7 @@ impl TypeChecker {
                 {
                     for (field_pattern, field_type) in fields.iter().zip(variant_info.fields.iter())
                     {
-                        self.bind_pattern_variables(field_pattern, field_type);
+                        self.collect_pattern_bindings(field_pattern, field_type, bindings);
                     }
                     bound = true;
                 }
@@ -1228,7 +2231,7 @@ impl TypeChecker {
                         for (field_pattern, field_type) in
                             fields.iter().zip(variant_info.fields.iter())
                         {
-                            self.bind_pattern_variables(field_pattern, field_type);
+                            self.collect_pattern_bindings(field_pattern, field_type, bindings);
                         }
                         bound = true;
                     }
@@ -1244,7 +2247,7 @@ impl TypeChecker {
                         for (field_pattern, field_type) in
                             fields.iter().zip(variant_info.fields.iter())
                         {
-                            self.bind_pattern_variables(field_pattern, field_type);
+                            self.collect_pattern_bindings(field_pattern, field_type, bindings);
                         }
                     }
                 }
@@ -1256,11 +2259,10 @@ impl TypeChecker {
                     for (field_name, nested_pattern) in fields {
                         if let Some(field_type) = struct_def.fields.get(field_name) {
                             if let Some(pattern) = nested_pattern {
-                                self.bind_pattern_variables(pattern, field_type);
+                                self.collect_pattern_bindings(pattern, field_type, bindings);
                             } else {
                                 // No nested pattern, bind the field name directly
-                                self.context
-                                    .insert_variable(field_name.clone(), field_type.clone());
+                                bindings.push((field_name.clone(), field_type.clone()));
                             }
                         }
                     }
@@ -1269,22 +2271,168 @@ impl TypeChecker {
             Pattern::Array { patterns, rest } => {
                 if let TypeInfo::List(elem_type) = ty {
                     for pattern in patterns {
-                        self.bind_pattern_variables(pattern, elem_type);
+                        self.collect_pattern_bindings(pattern, elem_type, bindings);
                     }
                     if let Some(rest_var) = rest {
                         // Rest pattern gets the list type
-                        self.context.insert_variable(rest_var.clone(), ty.clone());
+                        bindings.push((rest_var.clone(), ty.clone()));
                     }
                 }
             }
-            Pattern::Wildcard | Pattern::Literal(_) => {
+            Pattern::Tuple(patterns) => {
+                for (index, pattern) in patterns.iter().enumerate() {
+                    let elem_type = match ty {
+                        TypeInfo::Tuple(elements) => {
+                            elements.get(index).cloned().unwrap_or(TypeInfo::Error)
+                        }
+                        _ => TypeInfo::Unknown,
+                    };
+                    self.collect_pattern_bindings(pattern, &elem_type, bindings);
+                }
+            }
+            Pattern::Binding { name, pattern } => {
+                bindings.push((name.clone(), ty.clone()));
+                self.collect_pattern_bindings(pattern, ty, bindings);
+            }
+            Pattern::Or(alternatives) => {
+                let mut first: Option<Vec<(String, TypeInfo)>> = None;
+                for alternative in alternatives {
+                    let mut names = Vec::new();
+                    self.collect_pattern_bindings(alternative, ty, &mut names);
+                    match &first {
+                        Some(expected) => {
+                            self.check_alternative_bindings(expected, &names, *alternative.span());
+                        }
+                        None => first = Some(names),
+                    }
+                }
+                bindings.extend(first.unwrap_or_default());
+            }
+            Pattern::StringPrefix { rest, .. } => {
+                if let Some(rest) = rest {
+                    bindings.push((rest.clone(), TypeInfo::Str));
+                }
+            }
+            Pattern::Wildcard | Pattern::Literal(_) | Pattern::Range { .. } => {
                 // No variables to bind
             }
         }
     }
 
+    /// Report names that one alternative of an or-pattern binds but another does not, or
+    /// binds at a different type. `expected` comes from the first alternative.
+    fn check_alternative_bindings(
+        &mut self,
+        expected: &[(String, TypeInfo)],
+        found: &[(String, TypeInfo)],
+        span: Span,
+    ) {
+        let hint = "Every alternative of a `|` pattern must bind the same names".to_string();
+        for (name, expected_type) in expected {
+            match found.iter().find(|(other, _)| other == name) {
+                Some((_, found_type)) if !found_type.is_compatible_with(expected_type) => {
+                    self.errors.push(
+                        TypeError::new(format!(
+                            "variable `{}` is bound to {} here, but to {} in the first alternative",
+                            name,
+                            found_type.display_name(),
+                            expected_type.display_name()
+                        ))
+                        .with_span(span),
+                    );
+                }
+                Some(_) => {}
+                None => {
+                    self.errors.push(
+                        TypeError::new(format!(
+                            "variable `{}` is not bound in this alternative",
+                            name
+                        ))
+                        .with_span(span)
+                        .with_hint(hint.clone()),
+                    );
+                }
+            }
+        }
+        for (name, _) in found {
+            if !expected.iter().any(|(other, _)| other == name) {
+                self.errors.push(
+                    TypeError::new(format!(
+                        "variable `{}` is not bound in the first alternative",
+                        name
+                    ))
+                    .with_span(span)
+                    .with_hint(hint.clone()),
+                );
+            }
+        }
+    }
+
+    /// Name up to three missing patterns for a diagnostic and count the rest
+    fn describe_patterns(missing: &[String]) -> String {
+        const SHOWN: usize = 3;
+
+        let mut shown: Vec<String> = missing
+            .iter()
+            .take(SHOWN)
+            .map(|pattern| format!("`{}`", pattern))
+            .collect();
+        match missing.len() {
+            1 => format!("pattern {}", shown[0]),
+            count if count <= SHOWN => {
+                let last = shown.pop().unwrap_or_default();
+                format!("patterns {} and {}", shown.join(", "), last)
+            }
+            count => format!("patterns {} and {} more", shown.join(", "), count - SHOWN),
+        }
+    }
+
+    /// Report values no arm matches as an error and arms that can never run as warnings
+    fn check_match_coverage(&mut self, value_type: &TypeInfo, arms: &[Node<MatchArm>], span: Span) {
+        let report = exhaustiveness::check_match(&self.context, &self.constants, value_type, arms);
+
+        for index in report.unreachable {
+            self.warnings.push(
+                TypeError::new("unreachable match arm".to_string())
+                    .with_span(*arms[index].as_ref().pattern.span())
+                    .with_hint(
+                        "Earlier arms already match every value this pattern matches".to_string(),
+                    ),
+            );
+        }
+
+        if report.missing.is_empty() {
+            return;
+        }
+        self.errors.push(
+            TypeError::new(format!(
+                "non-exhaustive match: {} not covered",
+                Self::describe_patterns(&report.missing)
+            ))
+            .with_span(span)
+            .with_hint(
+                "Add a `case` for each missing pattern, or a `case _:` arm to handle the rest"
+                    .to_string(),
+            ),
+        );
+    }
+
     fn validate_pattern_against_type(&mut self, pattern: &Node<Pattern>, ty: &TypeInfo) {
         match pattern.as_ref() {
+            Pattern::Identifier(name) if self.constants.contains_key(name) => {
+                let const_type = self.constants[name].type_info();
+                if !const_type.is_compatible_with(ty) {
+                    self.errors.push(
+                        TypeError::new(format!(
+                            "constant pattern `{}` of type {} does not match expected type {}",
+                            name,
+                            const_type.display_name(),
+                            ty.display_name()
+                        ))
+                        .with_span(*pattern.span()),
+                    );
+                }
+            }
             Pattern::Wildcard | Pattern::Identifier(_) => {
                 // Wildcard and Identifier binds any type
             }
@@ -1292,6 +2440,12 @@ impl TypeChecker {
                 // Check literal type matches expected type
                 let lit_type = match lit.as_ref() {
                     Literal::String(_) => TypeInfo::Str,
+                    Literal::Bytes(_) => TypeInfo::List(Box::new(TypeInfo::U8)),
+                    // An integer pattern takes the type of the integer it is matched against
+                    Literal::Number(n) if !n.is_float_literal && ty.is_integer() => {
+                        self.check_integer_literal_range(n.int_value(), ty, *pattern.span());
+                        ty.clone()
+                    }
                     Literal::Number(n) => {
                         if n.value.fract() == 0.0
                             && n.value >= i32::MIN as f64
@@ -1473,6 +2627,172 @@ impl TypeChecker {
                     );
                 }
             }
+            Pattern::Or(alternatives) => {
+                for alternative in alternatives {
+                    self.validate_pattern_against_type(alternative, ty);
+                }
+            }
+            Pattern::Binding { pattern, .. } => self.validate_pattern_against_type(pattern, ty),
+            Pattern::Range {
+                start,
+                end,
+                inclusive,
+            } => self.validate_range_pattern(start, end, *inclusive, ty, *pattern.span()),
+            Pattern::StringPrefix { .. } => {
+                if !TypeInfo::Str.is_compatible_with(ty) {
+                    self.errors.push(
+                        TypeError::new(format!(
+                            "cannot match string prefix pattern against non-string type {}",
+                            ty.display_name()
+                        ))
+                        .with_span(*pattern.span()),
+                    );
+                }
+            }
+            Pattern::Tuple(patterns) => match ty {
+                TypeInfo::Tuple(elements) if elements.len() == patterns.len() => {
+                    for (elem_pattern, elem_type) in patterns.iter().zip(elements) {
+                        self.validate_pattern_against_type(elem_pattern, elem_type);
+                    }
+                }
+                TypeInfo::Tuple(elements) => {
+                    self.errors.push(
+                        TypeError::new(format!(
+                            "tuple pattern has {} element(s), but the value has type {}",
+                            patterns.len(),
+                            ty.display_name()
+                        ))
+                        .with_span(*pattern.span())
+                        .with_help(format!(
+                            "use exactly {} pattern(s) to destructure this tuple",
+                            elements.len()
+                        )),
+                    );
+                }
+                TypeInfo::Unknown | TypeInfo::Error => {}
+                _ => {
+                    self.errors.push(
+                        TypeError::new(format!(
+                            "cannot match tuple pattern against non-tuple type {}",
+                            ty.display_name()
+                        ))
+                        .with_span(*pattern.span()),
+                    );
+                }
+            },
+        }
+    }
+
+    /// Check that a range pattern's bounds fit the matched type and that the range is not empty
+    fn validate_range_pattern(
+        &mut self,
+        start: &Node<Literal>,
+        end: &Node<Literal>,
+        inclusive: bool,
+        ty: &TypeInfo,
+        span: Span,
+    ) {
+        let (bound_type, empty) = match (start.as_ref(), end.as_ref()) {
+            (Literal::Number(low), Literal::Number(high))
+                if !low.is_float_literal && !high.is_float_literal && ty.is_integer() =>
+            {
+                self.check_integer_literal_range(low.int_value(), ty, *start.span());
+                self.check_integer_literal_range(high.int_value(), ty, *end.span());
+                let (low, high) = (low.int_value(), high.int_value());
+                (ty.clone(), low > high || (low == high && !inclusive))
+            }
+            (Literal::Number(low), Literal::Number(high)) => {
+                let bound_type = if low.is_float_literal || high.is_float_literal {
+                    TypeInfo::F64
+                } else {
+                    TypeInfo::I32
+                };
+                let (low, high) = (low.value, high.value);
+                (bound_type, low > high || (low == high && !inclusive))
+            }
+            (Literal::String(low), Literal::String(high)) => {
+                let single_char = |text: &str| {
+                    let mut chars = text.chars();
+                    chars.next().filter(|_| chars.next().is_none())
+                };
+                let (Some(low), Some(high)) = (single_char(&low.value), single_char(&high.value))
+                else {
+                    self.errors.push(
+                        TypeError::new(
+                            "string range pattern bounds must be single characters".to_string(),
+                        )
+                        .with_span(span),
+                    );
+                    return;
+                };
+                (TypeInfo::Str, low > high || (low == high && !inclusive))
+            }
+            _ => {
+                self.errors.push(
+                    TypeError::new(
+                        "range patterns only match numbers and single-character strings"
+                            .to_string(),
+                    )
+                    .with_span(span),
+                );
+                return;
+            }
+        };
+
+        if !bound_type.is_compatible_with(ty) {
+            self.errors.push(
+                TypeError::new(format!(
+                    "range pattern type {} does not match expected type {}",
+                    bound_type.display_name(),
+                    ty.display_name()
+                ))
+                .with_span(span),
+            );
+        } else if empty {
+            self.errors.push(
+                TypeError::new("range pattern matches no values".to_string())
+                    .with_span(span)
+                    .with_hint(
+                        "The start of a range must be below its end, or equal to it with `..=`"
+                            .to_string(),
+                    ),
+            );
+        }
+    }
+
+    /// Bind the names of a `let`, `for` or parameter pattern, checking that the pattern fits
+    /// the value's shape and matches every value of its type. `place` names the construct
+    /// in diagnostics.
+    fn bind_irrefutable_pattern(&mut self, pattern: &Node<Pattern>, ty: &TypeInfo, place: &str) {
+        let errors = self.errors.len();
+        self.validate_pattern_against_type(pattern, ty);
+        // A pattern that does not fit the type already has an error of its own
+        if self.errors.len() == errors {
+            let missing = exhaustiveness::uncovered(&self.context, ty, pattern.as_ref());
+            if !missing.is_empty() {
+                self.errors.push(
+                    TypeError::new(format!(
+                        "refutable pattern in {}: {} not covered",
+                        place,
+                        Self::describe_patterns(&missing)
+                    ))
+                    .with_span(*pattern.span())
+                    .with_hint(
+                        "Patterns in `let`, `for` and parameters must match every value; use \
+                         `match` to handle the other cases"
+                            .to_string(),
+                    ),
+                );
+            }
+        }
+        self.bind_pattern_variables(pattern, ty);
+    }
+
+    /// How diagnostics refer to the target of a `let`
+    fn binding_description(pattern: &Node<Pattern>) -> String {
+        match pattern.as_ref().as_identifier() {
+            Some(name) => format!("variable `{}`", name),
+            None => "destructured value".to_string(),
         }
     }
 
@@ -1489,11 +2809,24 @@ impl TypeChecker {
     fn check_statement(&mut self, statement: &Node<Statement>) -> Result<TypeInfo> {
         let span = statement.span();
         match statement.as_ref() {
-            Statement::Let { name, ty, expr, .. } => {
-                let expr_type = self.infer_expr_type(expr)?;
+            Statement::Let {
+                pattern, ty, expr, ..
+            } => {
+                for name in pattern.as_ref().bindings() {
+                    if self.constants.contains_key(name) {
+                        self.errors.push(
+                            TypeError::new(format!("`{}` is already declared as a constant", name))
+                                .with_hint("Choose a different name for the variable".to_string())
+                                .with_span(*pattern.span()),
+                        );
+                    }
+                }
                 if let Some(annotation) = ty {
                     let annotated_type = self.context.type_from_annotation(annotation);
-                    if !expr_type.is_compatible_with(&annotated_type) {
+                    let expr_type = self.infer_expr_type_expecting(expr, &annotated_type)?;
+                    if !matches!(expr_type, TypeInfo::Error)
+                        && !expr_type.is_compatible_with(&annotated_type)
+                    {
                         self.errors.push(
                             TypeError::new(format!(
                                 "type mismatch: expected {}, got {}",
@@ -1501,8 +2834,8 @@ impl TypeChecker {
                                 expr_type.display_name()
                             ))
                             .with_hint(format!(
-                                "The variable `{}` is declared as `{}`, but the initializer has type `{}`",
-                                name,
+                                "The {} is declared as `{}`, but the initializer has type `{}`",
+                                Self::binding_description(pattern),
                                 annotated_type.display_name(),
                                 expr_type.display_name()
                             ))
@@ -1513,41 +2846,76 @@ impl TypeChecker {
                             .with_span(*span),
                         );
                     }
-                    self.context
-                        .insert_variable(name.as_ref().clone(), annotated_type);
+                    self.bind_irrefutable_pattern(pattern, &annotated_type, "`let` binding");
                 } else {
-                    self.context
-                        .insert_variable(name.as_ref().clone(), expr_type);
+                    let expr_type = self.infer_expr_type(expr)?;
+                    self.bind_irrefutable_pattern(pattern, &expr_type, "`let` binding");
                 }
                 Ok(TypeInfo::Unit)
             }
-            Statement::Assignment { name, expr } => {
-                let var_type = self
-                    .context
-                    .get_variable(name.as_ref())
-                    .ok_or_else(|| {
-                        TypeError::new(format!("undefined variable: {}", name))
-                            .with_hint(format!("did you mean to declare it with `let {}`?", name))
-                            .with_help(
-                                "Variables must be declared with `let` before they can be assigned"
+            Statement::Assignment { target, op, expr } => {
+                if let Expr::Identifier(name) = target.as_ref()
+                    && self.constants.contains_key(name)
+                {
+                    self.errors.push(
+                        TypeError::new(format!("cannot assign to constant `{}`", name))
+                            .with_hint(
+                                "Constants are fixed at compile time; use a `let` variable instead"
                                     .to_string(),
                             )
-                            .with_span(*span)
-                    })?
-                    .clone();
+                            .with_span(*span),
+                    );
+                    return Ok(TypeInfo::Unit);
+                }
+                let target_type = self.assignment_target_type(target)?;
+                // Codegen reads the place's type when loading it for a compound assignment
+                if !matches!(target_type, TypeInfo::Error) {
+                    self.record_expr_type(target, &target_type);
+                }
+                let expr_type = match op {
+                    Some(op) => {
+                        let operand = self.infer_literal_operand(expr, &target_type)?;
+                        self.infer_binary_type(*op, &target_type, &operand, *span)?
+                    }
+                    None => self.infer_expr_type_expecting(expr, &target_type)?,
+                };
+                if matches!(target_type, TypeInfo::Error)
+                    || matches!(expr_type, TypeInfo::Error)
+                    || expr_type.is_compatible_with(&target_type)
+                {
+                    return Ok(TypeInfo::Unit);
+                }
 
-                let expr_type = self.infer_expr_type(expr)?;
-                if !expr_type.is_compatible_with(&var_type) {
-                    self.errors.push(TypeError::new(format!(
+                let error = match target.as_ref() {
+                    Expr::Identifier(name) => TypeError::new(format!(
                         "cannot assign {} to {} (expected {})",
                         expr_type.display_name(),
                         name,
-                        var_type.display_name()
+                        target_type.display_name()
                     ))
-                    .with_hint(format!("The variable `{}` is declared as `{}`, but you're trying to assign a value of type `{}`", name, var_type.display_name(), expr_type.display_name()))
-                    .with_help("Make sure the types match or are compatible (e.g., i32 can be promoted to i64 or f64)".to_string())
-                    .with_span(*span));
-                }
+                    .with_hint(format!("The variable `{}` is declared as `{}`, but you're trying to assign a value of type `{}`", name, target_type.display_name(), expr_type.display_name())),
+                    Expr::Member { field, .. } => TypeError::new(format!(
+                        "cannot assign {} to field '{}' (expected {})",
+                        expr_type.display_name(),
+                        field,
+                        target_type.display_name()
+                    ))
+                    .with_hint(format!(
+                        "The field `{}` is declared as `{}`",
+                        field,
+                        target_type.display_name()
+                    )),
+                    _ => TypeError::new(format!(
+                        "cannot assign {} to an element of type {}",
+                        expr_type.display_name(),
+                        target_type.display_name()
+                    )),
+                };
+                self.errors.push(
+                    error
+                        .with_help("Make sure the types match or are compatible (e.g., i32 can be promoted to i64 or f64)".to_string())
+                        .with_span(*span),
+                );
                 Ok(TypeInfo::Unit)
             }
             Statement::If {
@@ -1577,51 +2945,33 @@ impl TypeChecker {
                 Ok(TypeInfo::Unit)
             }
             Statement::For {
-                var,
+                label,
+                pattern,
                 iterable,
                 body,
-                ..
             } => {
-                let iter_type = self.infer_expr_type(iterable)?;
-                let element_type = match &iter_type {
-                    TypeInfo::List(elem) => elem.as_ref().clone(),
-                    TypeInfo::Dict { .. } | TypeInfo::Str => TypeInfo::Str, // Map iteration yields keys (strings)
-                    TypeInfo::Range(start, end) => {
-                        if matches!(start.as_ref(), TypeInfo::I64)
-                            || matches!(end.as_ref(), TypeInfo::I64)
-                        {
-                            TypeInfo::I64
-                        } else {
-                            TypeInfo::I32
+                let element_type = self.infer_iteration_type(iterable)?;
+
+                let names = pattern.as_ref().bindings();
+                let previous = names
+                    .iter()
+                    .map(|name| self.context.remove_variable(name))
+                    .collect::<Vec<_>>();
+                self.bind_irrefutable_pattern(pattern, &element_type, "`for` loop");
+                self.check_loop_body(label.as_deref(), false, body)?;
+                for (name, previous) in names.into_iter().zip(previous) {
+                    match previous {
+                        Some(prev) => {
+                            self.context.insert_variable(name.to_string(), prev);
+                        }
+                        None => {
+                            self.context.remove_variable(name);
                         }
-                    }
-                    _ => {
-                        self.errors.push(
-                            TypeError::new(format!(
-                                "cannot iterate over type {}",
-                                iter_type.display_name()
-                            ))
-                            .with_span(*span),
-                        );
-                        TypeInfo::Unknown
-                    }
-                };
-
-                let previous = self.context.remove_variable(var.as_ref());
-                self.context
-                    .insert_variable(var.as_ref().clone(), element_type);
-                self.check_block(body)?;
-                match previous {
-                    Some(prev) => {
-                        self.context.insert_variable(var.as_ref().clone(), prev);
-                    }
-                    None => {
-                        self.context.remove_variable(var.as_ref());
                     }
                 }
                 Ok(TypeInfo::Unit)
             }
-            Statement::While { cond, body } => {
+            Statement::While { label, cond, body } => {
                 let cond_type = self.infer_expr_type(cond)?;
                 if !cond_type.is_compatible_with(&TypeInfo::Bool) {
                     self.errors.push(
@@ -1632,16 +2982,21 @@ impl TypeChecker {
                         .with_span(*span),
                     );
                 }
-                self.check_block(body)?;
+                self.check_loop_body(label.as_deref(), false, body)?;
                 Ok(TypeInfo::Unit)
             }
             Statement::Return(expr) => {
-                if let Some(expr) = expr {
-                    let expr_type = self.infer_expr_type(expr)?;
+                let returned_type = if let Some(expr) = expr {
+                    let expr_type = match self.current_function_return_type.clone() {
+                        Some(expected) => self.infer_expr_type_expecting(expr, &expected)?,
+                        None => self.infer_expr_type(expr)?,
+                    };
 
                     // Check return type matches function signature
                     if let Some(expected_return_type) = &self.current_function_return_type {
-                        if !expr_type.is_compatible_with(expected_return_type) {
+                        if !matches!(expr_type, TypeInfo::Error)
+                            && !expr_type.is_compatible_with(expected_return_type)
+                        {
                             self.errors.push(
                                 TypeError::new(format!(
                                     "return type mismatch: expected {}, got {}",
@@ -1658,6 +3013,7 @@ impl TypeChecker {
                                 .with_span(*span),
                         );
                     }
+                    expr_type
                 } else {
                     // Bare return - check if function expects unit
                     if let Some(expected_return_type) = &self.current_function_return_type {
@@ -1677,7 +3033,171 @@ impl TypeChecker {
                                 .with_span(*span),
                         );
                     }
+                    TypeInfo::Unit
+                };
+
+                // The first `return` of a closure without a return annotation sets the type
+                // the later ones are checked against
+                if let Some(returns) = &mut self.inferred_returns {
+                    if matches!(self.current_function_return_type, Some(TypeInfo::Unknown)) {
+                        self.current_function_return_type = Some(returned_type);
+                    }
+                    *returns += 1;
+                }
+                Ok(TypeInfo::Unit)
+            }
+            Statement::Raise(expr) => {
+                match expr {
+                    Some(expr) => {
+                        let raised = self.infer_expr_type(expr)?;
+                        let raised = self.context.normalize_type(raised);
+                        if !matches!(
+                            raised,
+                            TypeInfo::Str
+                                | TypeInfo::Struct { .. }
+                                | TypeInfo::Unknown
+                                | TypeInfo::Error
+                        ) {
+                            self.errors.push(
+                                TypeError::new(format!(
+                                    "`raise` expects a str message or a struct value, found {}",
+                                    raised.display_name()
+                                ))
+                                .with_span(*span),
+                            );
+                        }
+                    }
+                    None if !self.handling_exception => {
+                        self.errors.push(
+                            TypeError::new(
+                                "bare `raise` is only allowed inside an `except` clause"
+                                    .to_string(),
+                            )
+                            .with_hint(
+                                "pass the exception to raise, e.g. `raise \"message\"`".to_string(),
+                            )
+                            .with_span(*span),
+                        );
+                    }
+                    None => {}
+                }
+                Ok(TypeInfo::Unit)
+            }
+            Statement::Try {
+                body,
+                handlers,
+                finally_block,
+            } => {
+                self.check_block(body)?;
+                let mut caught_all = false;
+                let mut caught_types: Vec<&str> = Vec::new();
+                for handler in handlers {
+                    let clause = handler.as_ref();
+                    let binding_type = match clause.exception_type.as_deref() {
+                        None => None,
+                        Some("Exception") => Some(TypeInfo::Str),
+                        Some(name) => match self.context.get_struct(name) {
+                            Some(definition) => Some(TypeInfo::Struct {
+                                name: definition.name.clone(),
+                                fields: definition.fields.clone(),
+                            }),
+                            None if RUNTIME_EXCEPTION_TYPES.contains(&name) => Some(TypeInfo::Str),
+                            None => {
+                                self.errors.push(
+                                    TypeError::new(format!("unknown exception type `{}`", name))
+                                        .with_hint(format!(
+                                            "catch `Exception`, a struct type that is raised or one of the runtime's {}",
+                                            RUNTIME_EXCEPTION_TYPES.join(", ")
+                                        ))
+                                        .with_span(*handler.span()),
+                                );
+                                Some(TypeInfo::Unknown)
+                            }
+                        },
+                    };
+
+                    let shadowed = clause
+                        .exception_type
+                        .as_deref()
+                        .is_some_and(|name| name != "Exception" && caught_types.contains(&name));
+                    if caught_all || shadowed {
+                        self.warnings.push(
+                            TypeError::new("unreachable except clause".to_string())
+                                .with_hint(
+                                    "an earlier clause already catches these exceptions"
+                                        .to_string(),
+                                )
+                                .with_span(*handler.span()),
+                        );
+                    }
+                    match clause.exception_type.as_deref() {
+                        None | Some("Exception") => caught_all = true,
+                        Some(name) => caught_types.push(name),
+                    }
+
+                    let previous = clause
+                        .binding
+                        .as_ref()
+                        .map(|name| self.context.remove_variable(name));
+                    if let (Some(name), Some(ty)) = (&clause.binding, binding_type) {
+                        self.context.insert_variable(name.clone(), ty);
+                    }
+                    let was_handling = std::mem::replace(&mut self.handling_exception, true);
+                    let result = self.check_block(&clause.body);
+                    self.handling_exception = was_handling;
+                    if let Some(name) = &clause.binding {
+                        match previous.flatten() {
+                            Some(prev) => self.context.insert_variable(name.clone(), prev),
+                            None => {
+                                self.context.remove_variable(name);
+                            }
+                        }
+                    }
+                    result?;
+                }
+                if let Some(block) = finally_block {
+                    self.check_block(block)?;
+                }
+                Ok(TypeInfo::Unit)
+            }
+            Statement::With {
+                expr,
+                binding,
+                body,
+            } => {
+                let resource = self.infer_expr_type(expr)?;
+                let resource = self.context.normalize_type(resource);
+                if let Some(hint) = self.missing_with_cleanup(expr, &resource) {
+                    self.errors.push(
+                        TypeError::new(format!(
+                            "`with` needs a value with an `exit` or `close` method, found {}",
+                            resource.display_name()
+                        ))
+                        .with_hint(hint)
+                        .with_span(*expr.span()),
+                    );
+                }
+
+                let previous = binding
+                    .as_ref()
+                    .map(|name| self.context.remove_variable(name));
+                if let Some(name) = binding {
+                    self.context.insert_variable(name.clone(), resource);
+                }
+                let result = self.check_block(body);
+                if let Some(name) = binding {
+                    match previous.flatten() {
+                        Some(prev) => self.context.insert_variable(name.clone(), prev),
+                        None => {
+                            self.context.remove_variable(name);
+                        }
+                    }
                 }
+                result?;
+                Ok(TypeInfo::Unit)
+            }
+            Statement::Defer(expr) => {
+                self.infer_expr_type(expr)?;
                 Ok(TypeInfo::Unit)
             }
             Statement::Function(_) => {
@@ -1689,8 +3209,12 @@ impl TypeChecker {
                 // Expression statements are allowed (e.g., function calls)
                 Ok(expr_type)
             }
-            Statement::Break | Statement::Continue => {
-                // These are handled by loop context
+            Statement::Break { label, value } => {
+                self.check_break(label.as_deref(), value.as_ref(), *span)?;
+                Ok(TypeInfo::Unit)
+            }
+            Statement::Continue { label } => {
+                self.resolve_loop(label.as_deref(), "continue", *span);
                 Ok(TypeInfo::Unit)
             }
             Statement::Pass => {
@@ -1710,10 +3234,18 @@ impl TypeChecker {
                 // Enums are handled during the module pass
                 Ok(TypeInfo::Unit)
             }
+            Statement::Trait { .. } | Statement::Impl { .. } => {
+                // Traits and their implementations are handled at the module level
+                Ok(TypeInfo::Unit)
+            }
             Statement::TypeAlias { .. } => {
                 // Type aliases are handled at the module level
                 Ok(TypeInfo::Unit)
             }
+            Statement::Const { .. } => {
+                // Constants are evaluated at the module level
+                Ok(TypeInfo::Unit)
+            }
             Statement::Block(block) => self.check_block(block),
         }
     }
@@ -1728,16 +3260,25 @@ impl TypeChecker {
                         if num.is_float_literal {
                             TypeInfo::F64
                         } else {
+                            self.check_integer_literal_range(
+                                num.int_value(),
+                                &TypeInfo::I64,
+                                *span,
+                            );
                             TypeInfo::I64
                         }
                     }
                     Literal::String(_) => TypeInfo::Str,
+                    Literal::Bytes(_) => TypeInfo::List(Box::new(TypeInfo::U8)),
                     Literal::Bool(_) => TypeInfo::Bool,
                     Literal::None | Literal::Unit => TypeInfo::Unit,
                 }),
                 Expr::Identifier(name) => {
                     if let Some(var_type) = self.context.get_variable(name) {
                         Ok(var_type.clone())
+                    } else if let Some(func_type) = self.context.get_function(name) {
+                        // Named functions can be used as values
+                        Ok(func_type.clone())
                     } else {
                         if self.registry.is_some_and(|r| r.has_module(name)) {
                             self.errors.push(
@@ -1772,134 +3313,23 @@ impl TypeChecker {
                     }
                 }
                 Expr::Binary { op, left, right } => {
-                    let left_type = self.infer_expr_type(left)?;
-                    let right_type = self.infer_expr_type(right)?;
-
-                    match op {
-                        BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div => {
-                            // Numeric operations
-                            match (&left_type, &right_type) {
-                                // String concatenation (must come before numeric patterns)
-                                (TypeInfo::Str, TypeInfo::Str)
-                                | (TypeInfo::Str, TypeInfo::I64 | TypeInfo::I32)
-                                | (TypeInfo::I64 | TypeInfo::I32, TypeInfo::Str)
-                                    if matches!(op, BinaryOp::Add) =>
-                                {
-                                    Ok(TypeInfo::Str)
-                                }
-                                // Numeric operations
-                                (TypeInfo::F64, _) | (_, TypeInfo::F64) => Ok(TypeInfo::F64),
-                                (TypeInfo::I64, _) | (_, TypeInfo::I64) => Ok(TypeInfo::I64),
-                                (TypeInfo::I32, TypeInfo::I32) => Ok(TypeInfo::I32),
-                                _ => {
-                                    if Self::is_unknown_like(&left_type)
-                                        || Self::is_unknown_like(&right_type)
-                                    {
-                                        Ok(Self::merge_unknown_like_types(&left_type, &right_type))
-                                    } else {
-                                        self.errors.push(
-                                            TypeError::new(format!(
-                                                "cannot apply {op:?} to {} and {}",
-                                                left_type.display_name(),
-                                                right_type.display_name()
-                                            ))
-                                            .with_span(*span),
-                                        );
-                                        Ok(TypeInfo::Error)
-                                    }
-                                }
-                            }
-                        }
-                        BinaryOp::Eq
-                        | BinaryOp::Ne
-                        | BinaryOp::Lt
-                        | BinaryOp::LtEq
-                        | BinaryOp::Gt
-                        | BinaryOp::GtEq => {
-                            // Comparison operations return bool
-                            if left_type.is_compatible_with(&right_type) {
-                                Ok(TypeInfo::Bool)
-                            } else {
-                                self.errors.push(
-                                    TypeError::new(format!(
-                                        "cannot compare {} and {}",
-                                        left_type.display_name(),
-                                        right_type.display_name()
-                                    ))
-                                    .with_span(*span),
-                                );
-                                Ok(TypeInfo::Error)
-                            }
-                        }
-                        BinaryOp::Is | BinaryOp::IsNot => {
-                            // Identity comparison allows matching types or comparisons with None
-                            let allow = left_type.is_compatible_with(&right_type)
-                                || right_type.is_compatible_with(&left_type)
-                                || matches!(left_type, TypeInfo::Unit)
-                                || matches!(right_type, TypeInfo::Unit)
-                                || matches!(left_type, TypeInfo::Unknown)
-                                || matches!(right_type, TypeInfo::Unknown);
-
-                            if allow {
-                                Ok(TypeInfo::Bool)
-                            } else {
-                                self.errors.push(
-                                    TypeError::new(format!(
-                                        "cannot use `is` between {} and {}",
-                                        left_type.display_name(),
-                                        right_type.display_name()
-                                    ))
-                                    .with_span(*span),
-                                );
-                                Ok(TypeInfo::Error)
-                            }
-                        }
-                        BinaryOp::And | BinaryOp::Or => {
-                            // Logical operations require bool operands
-                            if left_type.is_compatible_with(&TypeInfo::Bool)
-                                && right_type.is_compatible_with(&TypeInfo::Bool)
-                            {
-                                Ok(TypeInfo::Bool)
-                            } else {
-                                self.errors.push(
-                                    TypeError::new(format!(
-                                        "logical operations require bool operands, got {} and {}",
-                                        left_type.display_name(),
-                                        right_type.display_name()
-                                    ))
-                                    .with_span(*span),
-                                );
-                                Ok(TypeInfo::Error)
-                            }
-                        }
-                        BinaryOp::Mod => {
-                            // Modulo requires integer operands
-                            match (&left_type, &right_type) {
-                                (TypeInfo::I32, TypeInfo::I32) => Ok(TypeInfo::I32),
-                                (TypeInfo::I64, TypeInfo::I64) => Ok(TypeInfo::I64),
-                                _ => {
-                                    if Self::is_unknown_like(&left_type)
-                                        || Self::is_unknown_like(&right_type)
-                                    {
-                                        Ok(TypeInfo::Unknown)
-                                    } else {
-                                        self.errors.push(
-                                            TypeError::new(format!(
-                                                "modulo requires integer operands, got {} and {}",
-                                                left_type.display_name(),
-                                                right_type.display_name()
-                                            ))
-                                            .with_span(*span),
-                                        );
-                                        Ok(TypeInfo::Error)
-                                    }
-                                }
-                            }
-                        }
-                    }
+                    let (left_type, right_type) = self.infer_operand_types(left, right)?;
+                    self.infer_binary_type(*op, &left_type, &right_type, *span)
+                }
+                // Checked as one literal, so `-9223372036854775808` fits in an `int`
+                Expr::Unary {
+                    op: UnaryOp::Neg,
+                    expr: operand,
+                } if let Some(value) = Self::integer_literal_value(expr.as_ref()) => {
+                    self.check_integer_literal_range(value, &TypeInfo::I64, *span);
+                    self.record_expr_type(operand, &TypeInfo::I64);
+                    Ok(TypeInfo::I64)
                 }
                 Expr::Unary { op, expr } => {
                     let expr_type = self.infer_expr_type(expr)?;
+                    if let Some(result) = self.infer_unary_operator_call(*op, &expr_type, *span) {
+                        return Ok(result);
+                    }
                     match op {
                         UnaryOp::Not => {
                             if expr_type.is_compatible_with(&TypeInfo::Bool) {
@@ -1915,6 +3345,24 @@ impl TypeChecker {
                                 Ok(TypeInfo::Error)
                             }
                         }
+                        UnaryOp::Neg
+                            if expr_type
+                                .integer_layout()
+                                .is_some_and(|(_, signed)| !signed) =>
+                        {
+                            self.errors.push(
+                                TypeError::new(format!(
+                                    "cannot negate a value of unsigned type {}",
+                                    expr_type.display_name()
+                                ))
+                                .with_hint(
+                                    "convert it to a signed type first, as in `x as i64`"
+                                        .to_string(),
+                                )
+                                .with_span(*span),
+                            );
+                            Ok(TypeInfo::Error)
+                        }
                         UnaryOp::Neg => {
                             if expr_type.is_compatible_with(&TypeInfo::I32)
                                 || expr_type.is_compatible_with(&TypeInfo::I64)
@@ -1932,16 +3380,169 @@ impl TypeChecker {
                                 Ok(TypeInfo::Error)
                             }
                         }
+                        UnaryOp::BitNot => match expr_type {
+                            ref ty if ty.is_integer() => Ok(expr_type),
+                            _ if Self::is_unknown_like(&expr_type) => Ok(TypeInfo::Unknown),
+                            _ => {
+                                self.errors.push(
+                                    TypeError::new(format!(
+                                        "bitwise not requires an integer operand, got {}",
+                                        expr_type.display_name()
+                                    ))
+                                    .with_span(*span),
+                                );
+                                Ok(TypeInfo::Error)
+                            }
+                        },
+                    }
+                }
+                Expr::Cast { expr: operand, ty } => {
+                    let target = self.context.type_from_annotation(ty);
+                    let source = self.infer_expr_type(operand)?;
+                    let source_ok = match source.resolve_alias() {
+                        TypeInfo::Bool => true,
+                        other => other.is_numeric() || Self::is_unknown_like(other),
+                    };
+                    if matches!(source, TypeInfo::Error) {
+                        Ok(TypeInfo::Error)
+                    } else if target.is_numeric() && source_ok {
+                        Ok(target)
+                    } else {
+                        self.errors.push(
+                            TypeError::new(format!(
+                                "cannot cast {} to {}",
+                                source.display_name(),
+                                target.display_name()
+                            ))
+                            .with_hint(
+                                "`as` converts numbers and bools to a numeric type".to_string(),
+                            )
+                            .with_span(*span),
+                        );
+                        Ok(TypeInfo::Error)
                     }
                 }
-                Expr::Call { func, args } => {
+                Expr::Call {
+                    func,
+                    args,
+                    kwargs,
+                    type_args,
+                } => {
                     if let Some(enum_type) = self.try_eval_enum_constructor(func.as_ref(), args)? {
+                        if !kwargs.is_empty() {
+                            if let Expr::Member { object, field } = func.as_ref().as_ref() {
+                                let variant = self.build_member_path(object, field);
+                                self.reject_keyword_arguments(&variant, *span);
+                            }
+                            return Ok(TypeInfo::Error);
+                        }
                         return Ok(enum_type);
                     }
+                    if matches!(func.as_ref().as_ref(), Expr::Identifier(name) if name == "sort")
+                        && self.context.get_variable("sort").is_none()
+                        && self.context.get_function("sort").is_none()
+                    {
+                        if !kwargs.is_empty() {
+                            self.reject_keyword_arguments("sort", *span);
+                            return Ok(TypeInfo::Error);
+                        }
+                        return self.check_sort_call(args, *span);
+                    }
+                    if let Expr::Identifier(name) = func.as_ref().as_ref()
+                        && ITERATOR_ADAPTERS.contains(&name.as_str())
+                        && self.context.get_variable(name).is_none()
+                        && self.context.get_function(name).is_none()
+                    {
+                        if !kwargs.is_empty() {
+                            self.reject_keyword_arguments(name, *span);
+                            return Ok(TypeInfo::Error);
+                        }
+                        return self.check_adapter_call(name, args, *span);
+                    }
+                    if matches!(func.as_ref().as_ref(), Expr::Identifier(name) if name == "hash")
+                        && self.context.get_variable("hash").is_none()
+                        && self.context.get_function("hash").is_none()
+                    {
+                        if !kwargs.is_empty() {
+                            self.reject_keyword_arguments("hash", *span);
+                            return Ok(TypeInfo::Error);
+                        }
+                        return self.check_hash_call(args, *span);
+                    }
+                    if matches!(func.as_ref().as_ref(), Expr::Identifier(name) if name == "str")
+                        && self.context.get_variable("str").is_none()
+                        && !self.param_names.contains_key("str")
+                        && let [arg] = args.as_slice()
+                    {
+                        if !kwargs.is_empty() {
+                            self.reject_keyword_arguments("str", *span);
+                            return Ok(TypeInfo::Error);
+                        }
+                        let ty = self.infer_expr_type(arg)?;
+                        self.check_displayable(&ty, *arg.span());
+                        return Ok(TypeInfo::Str);
+                    }
+                    if matches!(func.as_ref().as_ref(), Expr::Identifier(name) if name == "defer")
+                        && self.context.get_variable("defer").is_none()
+                        && self.context.get_function("defer").is_none()
+                        && self.rejects_deferred_value(args, *span)?
+                    {
+                        return Ok(TypeInfo::Error);
+                    }
                     let span = func.span();
+                    let generic = match func.as_ref().as_ref() {
+                        Expr::Identifier(name) if self.context.get_variable(name).is_none() => {
+                            self.generic_functions.get(name).cloned()
+                        }
+                        _ => None,
+                    };
+                    let overload = if generic.is_none() && !type_args.is_empty() {
+                        self.typed_runtime_overload(func, type_args)
+                    } else {
+                        None
+                    };
+                    if generic.is_none() && overload.is_none() && !type_args.is_empty() {
+                        self.errors.push(
+                            TypeError::new("type arguments given to a non-generic function".to_string())
+                                .with_hint("Only functions declared with type parameters, such as `fn max<T>(a: T, b: T) -> T`, take type arguments".to_string())
+                                .with_span(*span),
+                        );
+                        return Ok(TypeInfo::Error);
+                    }
+                    // `h.f(x)` calls a closure stored in a field when the struct has no method `f`
+                    let field_closure = match func.as_ref().as_ref() {
+                        Expr::Member { object, field } if self.type_receiver(object).is_none() => {
+                            self.function_field(object, field)
+                        }
+                        _ => None,
+                    };
                     let func_type = match func.as_ref().as_ref() {
+                        _ if let Some(symbol) = &overload => TypeInfo::Function {
+                            params: ffi_params_to_typeinfo(&symbol.signature),
+                            param_defaults: vec![false; symbol.signature.params.len()],
+                            return_type: Box::new(ffi_type_to_typeinfo(&symbol.signature.result)),
+                        },
+                        Expr::Identifier(name) if let Some(generic) = &generic => {
+                            let mut names = self.param_names.get(name).cloned().unwrap_or_default();
+                            let (fixed_args, rest) = if self.variadic.contains(name) {
+                                names.pop();
+                                args.split_at(args.len().min(names.len()))
+                            } else {
+                                (&args[..], &[][..])
+                            };
+                            let arranged = arrange_arguments(&names, fixed_args, kwargs);
+                            let instantiated = self.instantiate_generic_call(
+                                generic, type_args, &arranged, rest, *span,
+                            )?;
+                            self.record_expr_type(func, &instantiated);
+                            instantiated
+                        }
                         Expr::Identifier(name) => {
-                            if let Some(func) = self.context.get_function(name).cloned() {
+                            if let Some(closure @ TypeInfo::Function { .. }) =
+                                self.context.get_variable(name).cloned()
+                            {
+                                closure
+                            } else if let Some(func) = self.context.get_function(name).cloned() {
                                 func
                             } else {
                                 self.errors.push(
@@ -1951,18 +3552,17 @@ impl TypeChecker {
                                 TypeInfo::Error
                             }
                         }
+                        Expr::Member { object, field } if self.type_receiver(object).is_some() => {
+                            self.resolve_member_function(object, field, span)
+                        }
+                        _ if let Some(closure) = &field_closure => closure.clone(),
                         Expr::Member { object, field } => {
                             let full_name = self.build_member_path(object, field);
 
                             // First check registry for exact FFI signatures
                             if let Some(registry) = self.registry {
                                 if let Some(symbol) = registry.resolve(&full_name) {
-                                    let params: Vec<TypeInfo> = symbol
-                                        .signature
-                                        .params
-                                        .iter()
-                                        .map(ffi_type_to_typeinfo)
-                                        .collect();
+                                    let params = ffi_params_to_typeinfo(&symbol.signature);
                                     let return_type = if full_name == "sys.getenv" {
                                         if let Some(option_enum) = self
                                             .context
@@ -1972,6 +3572,8 @@ impl TypeChecker {
                                         } else {
                                             ffi_type_to_typeinfo(&symbol.signature.result)
                                         }
+                                    } else if full_name.starts_with("string.") {
+                                        string_result_type(&symbol.signature.result)
                                     } else {
                                         ffi_type_to_typeinfo(&symbol.signature.result)
                                     };
@@ -1997,16 +3599,49 @@ impl TypeChecker {
                                     })
                             }
                         }
-                        _ => {
-                            self.errors.push(
-                                TypeError::new(
-                                    "function calls must use identifier or module.function syntax"
-                                        .to_string(),
-                                )
-                                .with_span(*span),
-                            );
-                            return Ok(TypeInfo::Error);
+                        _ => match self.infer_expr_type(func)? {
+                            closure @ (TypeInfo::Function { .. } | TypeInfo::Error) => closure,
+                            _ => {
+                                self.errors.push(
+                                    TypeError::new(
+                                        "function calls must use identifier or module.function syntax"
+                                            .to_string(),
+                                    )
+                                    .with_span(*span),
+                                );
+                                return Ok(TypeInfo::Error);
+                            }
+                        },
+                    };
+
+                    // Keyword arguments name the parameters of declared functions and methods
+                    let (callee, mut param_names) = match func.as_ref().as_ref() {
+                        Expr::Identifier(name) => {
+                            let names = match self.context.get_variable(name) {
+                                Some(TypeInfo::Function { .. }) => None,
+                                _ if overload.is_some() => None,
+                                _ => self.param_names.get(name).cloned(),
+                            };
+                            (name.clone(), names)
+                        }
+                        Expr::Member { object, field } => {
+                            let path = self.build_member_path(object, field);
+                            let names = self.param_names.get(&path).cloned();
+                            (path, names)
                         }
+                        _ => ("closure".to_string(), None),
+                    };
+                    let mut variadic = match func.as_ref().as_ref() {
+                        Expr::Identifier(name)
+                            if matches!(
+                                self.context.get_variable(name),
+                                Some(TypeInfo::Function { .. })
+                            ) =>
+                        {
+                            false
+                        }
+                        Expr::Identifier(_) | Expr::Member { .. } => self.is_variadic(&callee),
+                        _ => false,
                     };
 
                     match func_type {
@@ -2021,8 +3656,13 @@ impl TypeChecker {
                             let has_signature = !params.is_empty() || !param_defaults.is_empty();
 
                             if let Expr::Member { object, .. } = func.as_ref().as_ref()
+                                && field_closure.is_none()
+                                && self.type_receiver(object).is_none()
                                 && let Ok(object_type) = self.infer_expr_type(object)
-                                && matches!(object_type, TypeInfo::Struct { .. })
+                                && matches!(
+                                    object_type,
+                                    TypeInfo::Struct { .. } | TypeInfo::Enum { .. }
+                                )
                                 && !params.is_empty()
                             {
                                 let self_param = &params[0];
@@ -2053,74 +3693,86 @@ impl TypeChecker {
                                         Expr::Member { field, .. } => format!("{}.{}", name, field),
                                         _ => name.clone(),
                                     };
+                                    param_names = self
+                                        .param_names
+                                        .get(&method_name)
+                                        .map(|names| names[1..].to_vec());
+                                    variadic = self.variadic.contains(&method_name);
                                     self.apply_method_specialization(&method_name, &inferred);
+                                } else if let TypeInfo::Enum { name, .. } = &object_type
+                                    && let Expr::Member { field, .. } = func.as_ref().as_ref()
+                                {
+                                    let method_name = format!("{}.{}", name, field);
+                                    param_names = self
+                                        .param_names
+                                        .get(&method_name)
+                                        .map(|names| names[1..].to_vec());
+                                    variadic = self.variadic.contains(&method_name);
                                 }
                             }
 
                             if has_signature {
-                                let total_params = params_slice.len();
-                                let required_params =
-                                    defaults_slice.iter().filter(|flag| !**flag).count();
-
-                                if args.len() > total_params {
-                                    self.errors.push(
-                                        TypeError::new(format!(
-                                            "function expects at most {} arguments, got {}",
-                                            total_params,
-                                            args.len()
-                                        ))
-                                        .with_hint(
-                                            "Remove extra arguments or verify the function signature"
-                                                .to_string(),
-                                        )
-                                        .with_span(*span),
-                                    );
-                                    return Ok(TypeInfo::Error);
-                                }
-
-                                if args.len() < required_params {
-                                    self.errors.push(
-                                        TypeError::new(format!(
-                                            "function expects at least {} arguments, got {}",
-                                            required_params,
-                                            args.len()
-                                        ))
-                                        .with_hint(
-                                            "Provide values for all parameters without defaults"
-                                                .to_string(),
-                                        )
-                                        .with_span(*span),
-                                    );
-                                    return Ok(TypeInfo::Error);
-                                }
+                                let mut names = param_names.unwrap_or_default();
+                                // A variadic parameter takes the positional arguments past the
+                                // fixed parameters as a list
+                                let rest_param = if variadic && !params_slice.is_empty() {
+                                    let list_type = params_slice.last().cloned();
+                                    params_slice = &params_slice[..params_slice.len() - 1];
+                                    defaults_slice = &defaults_slice[..defaults_slice.len() - 1];
+                                    list_type.map(|ty| (names.pop(), ty))
+                                } else {
+                                    None
+                                };
+                                let (args, rest) = match rest_param {
+                                    Some(_) => args.split_at(args.len().min(params_slice.len())),
+                                    None => (&args[..], &[][..]),
+                                };
+                                let rest_name =
+                                    rest_param.as_ref().and_then(|(name, _)| name.as_deref());
+                                if !self.check_spread_arguments(
+                                    &callee,
+                                    rest_param.is_some(),
+                                    rest_name,
+                                    args,
+                                    kwargs,
+                                ) || !self.check_call_arguments(
+                                    &callee,
+                                    &names,
+                                    defaults_slice,
+                                    args,
+                                    kwargs,
+                                    *span,
+                                ) {
+                                    return Ok(TypeInfo::Error);
+                                }
+                                if let Some((_, list_type)) = &rest_param {
+                                    self.check_rest_arguments(rest, args.len(), list_type, *span)?;
+                                }
 
+                                let arranged = arrange_arguments(&names, args, kwargs);
                                 for (i, (arg, param_type)) in
-                                    args.iter().zip(params_slice.iter()).enumerate()
+                                    arranged.iter().zip(params_slice.iter()).enumerate()
                                 {
-                                    let arg_type = self.infer_expr_type(arg)?;
-                                    if !matches!(arg_type, TypeInfo::Error)
-                                        && !arg_type.is_compatible_with(param_type)
-                                    {
-                                        self.errors.push(
-                                            TypeError::new(format!(
-                                                "argument {} type mismatch: expected {}, got {}",
-                                                i + 1,
-                                                param_type.display_name(),
-                                                arg_type.display_name()
-                                            ))
-                                            .with_span(*span)
-                                            .with_hint(format!(
-                                                "Argument {} should be of type `{}`",
-                                                i + 1,
-                                                param_type.display_name()
-                                            ))
-                                            .with_help("Check the function signature and ensure argument types match".to_string()),
-                                        );
-                                    }
+                                    let Some(arg) = arg else {
+                                        continue;
+                                    };
+                                    let argument = if i < args.len() {
+                                        format!("{}", i + 1)
+                                    } else {
+                                        format!("`{}`", names[i])
+                                    };
+                                    self.check_argument_type(arg, param_type, &argument, *span)?;
                                 }
                             } else {
+                                if !kwargs.is_empty() {
+                                    self.reject_keyword_arguments(&callee, *span);
+                                }
+                                if !self.check_spread_arguments(&callee, false, None, args, &[]) {
+                                    return Ok(TypeInfo::Error);
+                                }
                                 // For unknown FFI functions, just ensure arguments are type-checked
-                                for arg in args {
+                                for arg in args.iter().chain(kwargs.iter().map(|(_, value)| value))
+                                {
                                     let _ = self.infer_expr_type(arg)?;
                                 }
                             }
@@ -2264,12 +3916,7 @@ impl TypeChecker {
                         }
                         if let Some(symbol) = registry.resolve(&full_name) {
                             return Ok(TypeInfo::Function {
-                                params: symbol
-                                    .signature
-                                    .params
-                                    .iter()
-                                    .map(ffi_type_to_typeinfo)
-                                    .collect(),
+                                params: ffi_params_to_typeinfo(&symbol.signature),
                                 param_defaults: vec![false; symbol.signature.params.len()],
                                 return_type: Box::new(ffi_type_to_typeinfo(
                                     &symbol.signature.result,
@@ -2292,12 +3939,7 @@ impl TypeChecker {
                                 }
                                 if let Some(symbol) = registry.resolve(&full_name) {
                                     return Ok(TypeInfo::Function {
-                                        params: symbol
-                                            .signature
-                                            .params
-                                            .iter()
-                                            .map(ffi_type_to_typeinfo)
-                                            .collect(),
+                                        params: ffi_params_to_typeinfo(&symbol.signature),
                                         param_defaults: vec![false; symbol.signature.params.len()],
                                         return_type: Box::new(ffi_type_to_typeinfo(
                                             &symbol.signature.result,
@@ -2353,18 +3995,66 @@ impl TypeChecker {
                         }
                     }
                 }
-                Expr::FString { parts } => {
+                Expr::Index { object, index } => {
+                    let object_type = self.infer_expr_type(object)?;
+                    let index_type = self.infer_expr_type(index)?;
+                    Ok(self.index_element_type(&object_type, &index_type, span))
+                }
+                Expr::Slice { object, start, end } => {
+                    let object_type = self.infer_expr_type(object)?;
+                    for bound in [start, end].into_iter().flatten() {
+                        let bound_type = self.infer_expr_type(bound)?;
+                        if !bound_type.is_integer() && !Self::is_unknown_like(&bound_type) {
+                            self.errors.push(
+                                TypeError::new(format!(
+                                    "slice bounds must be integers, got {}",
+                                    bound_type.display_name()
+                                ))
+                                .with_span(*bound.span()),
+                            );
+                        }
+                    }
+                    match object_type.resolve_alias() {
+                        TypeInfo::List(_) | TypeInfo::Str => {
+                            Ok(object_type.resolve_alias().clone())
+                        }
+                        TypeInfo::Error => Ok(TypeInfo::Error),
+                        ty if Self::is_unknown_like(ty) => Ok(TypeInfo::Unknown),
+                        _ => {
+                            self.errors.push(
+                                TypeError::new(format!(
+                                    "cannot slice type {}",
+                                    object_type.display_name()
+                                ))
+                                .with_hint("Only lists and strings support slicing".to_string())
+                                .with_span(*span),
+                            );
+                            Ok(TypeInfo::Error)
+                        }
+                    }
+                }
+                Expr::FString { parts, .. } => {
                     // F-strings always evaluate to strings
                     // Type check all embedded expressions
                     for part in parts {
                         if let FStringPart::Expr(expr) = part.as_ref() {
-                            let _ = self.infer_expr_type(expr)?;
-                            // We don't care about the type, just that it's valid
+                            let ty = self.infer_expr_type(expr)?;
+                            self.check_displayable(&ty, *expr.span());
                         }
                     }
                     Ok(TypeInfo::Str)
                 }
-                // Lambda expressions removed - use anonymous fn syntax instead
+                Expr::Lambda {
+                    params,
+                    ret_ty,
+                    body,
+                } => self.infer_lambda_type(params, ret_ty.as_ref(), body, None),
+                Expr::Tuple(elements) => Ok(TypeInfo::Tuple(
+                    elements
+                        .iter()
+                        .map(|e| self.infer_expr_type(e))
+                        .collect::<Result<Vec<_>>>()?,
+                )),
                 Expr::Array(elements) => {
                     if elements.is_empty() {
                         // Empty array - can't infer element type
@@ -2460,21 +4150,12 @@ impl TypeChecker {
                             }
                         }
 
-                        let key_type = if common_key_type.is_compatible_with(&TypeInfo::Str) {
+                        let key_type = if common_key_type.is_compatible_with(&TypeInfo::Str)
+                            || !self.check_dict_key(&common_key_type, *span)
+                        {
                             TypeInfo::Str
                         } else {
-                            self.errors.push(
-                                TypeError::new(format!(
-                                    "dictionary keys must be str, got {}",
-                                    common_key_type.display_name()
-                                ))
-                                .with_span(*span)
-                                .with_hint(
-                                    "Only string keys are supported for dictionaries currently"
-                                        .to_string(),
-                                ),
-                            );
-                            TypeInfo::Str
+                            common_key_type
                         };
 
                         Ok(TypeInfo::Dict {
@@ -2489,19 +4170,7 @@ impl TypeChecker {
                     iterable,
                     condition,
                 } => {
-                    let iterable_type = self.infer_expr_type(iterable)?;
-                    let element_iter_type = if let TypeInfo::List(elem) = &iterable_type {
-                        elem.as_ref().clone()
-                    } else {
-                        self.errors.push(
-                            TypeError::new(format!(
-                                "list comprehension expects list iterable, got {}",
-                                iterable_type.display_name()
-                            ))
-                            .with_span(*span),
-                        );
-                        TypeInfo::Unknown
-                    };
+                    let element_iter_type = self.infer_iteration_type(iterable)?;
 
                     let previous = self.context.remove_variable(var);
                     self.context
@@ -2543,19 +4212,7 @@ impl TypeChecker {
                     iterable,
                     condition,
                 } => {
-                    let iterable_type = self.infer_expr_type(iterable)?;
-                    let element_iter_type = if let TypeInfo::List(elem) = &iterable_type {
-                        elem.as_ref().clone()
-                    } else {
-                        self.errors.push(
-                            TypeError::new(format!(
-                                "dict comprehension expects list iterable, got {}",
-                                iterable_type.display_name()
-                            ))
-                            .with_span(*span),
-                        );
-                        TypeInfo::Unknown
-                    };
+                    let element_iter_type = self.infer_iteration_type(iterable)?;
 
                     let previous = self.context.remove_variable(var);
                     self.context
@@ -2578,7 +4235,11 @@ impl TypeChecker {
                         .insert(*span, element_iter_type.clone());
 
                     let key_type = self.infer_expr_type(key)?;
-                    if !key_type.is_compatible_with(&TypeInfo::Str) {
+                    let key_type = if key_type.is_compatible_with(&TypeInfo::Str) {
+                        TypeInfo::Str
+                    } else if self.check_struct_dict_key(&key_type, *span) {
+                        key_type
+                    } else {
                         self.errors.push(
                             TypeError::new(format!(
                                 "dict comprehension key must be string, got {}",
@@ -2586,7 +4247,8 @@ impl TypeChecker {
                             ))
                             .with_span(*span),
                         );
-                    }
+                        TypeInfo::Str
+                    };
 
                     let value_type = self.infer_expr_type(value)?;
 
@@ -2600,10 +4262,14 @@ impl TypeChecker {
                     }
 
                     Ok(TypeInfo::Dict {
-                        key: Box::new(TypeInfo::Str),
+                        key: Box::new(key_type),
                         value: Box::new(value_type),
                     })
                 }
+                Expr::Loop { label, body } => {
+                    let break_type = self.check_loop_body(label.as_deref(), true, body)?;
+                    Ok(break_type.unwrap_or(TypeInfo::Unit))
+                }
                 Expr::Match { value, arms } => {
                     let mut value_type = self.infer_expr_type(value)?;
                     // Normalize generic types to enum types if applicable
@@ -2621,6 +4287,7 @@ impl TypeChecker {
                     }
 
                     // Type check each arm
+                    let errors_before = self.errors.len();
                     let mut arm_types = Vec::new();
                     for arm in arms {
                         // Bind pattern variables before checking guard and body
@@ -2669,6 +4336,11 @@ impl TypeChecker {
                         self.context.variables = old_vars;
                     }
 
+                    // Coverage is only meaningful once every pattern fits the value type
+                    if self.features.match_exhaustiveness && self.errors.len() == errors_before {
+                        self.check_match_coverage(&value_type, arms, *span);
+                    }
+
                     // All arms must return compatible types
                     let common_type = arm_types.remove(0);
                     for (i, arm_type) in arm_types.iter().enumerate() {
@@ -2691,6 +4363,18 @@ impl TypeChecker {
                     Ok(common_type)
                 }
                 Expr::Struct { name, fields } => {
+                    if self.context.get_struct(name).is_none() {
+                        let variable = self.context.get_variable(name);
+                        if variable.is_none() && self.param_names.contains_key(name) {
+                            return self.check_keyword_call(name, fields, *span);
+                        }
+                        if matches!(variable, Some(TypeInfo::Function { .. }))
+                            || self.context.get_function(name).is_some()
+                        {
+                            self.reject_keyword_arguments(name, *span);
+                            return Ok(TypeInfo::Error);
+                        }
+                    }
                     // Get struct definition (clone to avoid borrow checker issues)
                     let struct_def = match self.context.get_struct(name) {
                         Some(def) => def.clone(),
@@ -2782,6 +4466,8 @@ impl TypeChecker {
                         }
                     }
 
+                    self.check_generic_bounds(name, &struct_def.bounds, &inferred_generics, *span);
+
                     // Construct the concrete struct type with substituted fields
                     let concrete_fields = struct_def
                         .fields
@@ -2832,6 +4518,21 @@ impl TypeChecker {
                         args: vec![inner_type],
                     })
                 }
+                Expr::Try(inner) => self.infer_try_type(inner, *span),
+                Expr::Spread(inner) => {
+                    self.infer_expr_type(inner)?;
+                    self.errors.push(
+                        TypeError::new(
+                            "`*` spreading is only valid as a call argument".to_string(),
+                        )
+                        .with_hint(
+                            "Spread a list into the variadic parameter of a call, as in `f(*xs)`"
+                                .to_string(),
+                        )
+                        .with_span(*span),
+                    );
+                    Ok(TypeInfo::Error)
+                }
             }
         })()?;
 
@@ -2839,82 +4540,850 @@ impl TypeChecker {
         Ok(ty)
     }
 
-    /// Get collected errors
-    pub fn errors(&self) -> &[TypeError] {
-        &self.errors
+    /// Infer an expression's type where the surrounding context expects `expected`.
+    /// Closures take their unannotated parameter types from an expected function type.
+    fn infer_expr_type_expecting(
+        &mut self,
+        expr: &Node<Expr>,
+        expected: &TypeInfo,
+    ) -> Result<TypeInfo> {
+        if let Some(ty) = self.infer_integer_literal_type(expr, expected) {
+            return Ok(ty);
+        }
+        if matches!(expected.resolve_alias(), TypeInfo::F32)
+            && Self::is_number_literal(expr.as_ref())
+        {
+            self.record_expr_type(expr, &TypeInfo::F32);
+            if let Expr::Unary { expr: operand, .. } = expr.as_ref() {
+                self.record_expr_type(operand, &TypeInfo::F32);
+            }
+            return Ok(TypeInfo::F32);
+        }
+        if let Some(ty) = self.infer_collection_literal_type(expr, expected)? {
+            return Ok(ty);
+        }
+        let Expr::Lambda {
+            params,
+            ret_ty,
+            body,
+        } = expr.as_ref()
+        else {
+            let ty = self.infer_expr_type(expr)?;
+            if let TypeInfo::Dyn(trait_name) = expected.resolve_alias() {
+                return Ok(self.coerce_to_dyn(ty, trait_name, *expr.span()));
+            }
+            return Ok(ty);
+        };
+        let ty = self.infer_lambda_type(params, ret_ty.as_ref(), body, Some(expected))?;
+        self.record_expr_type(expr, &ty);
+        Ok(ty)
     }
 
-    pub fn expr_type_map(&self) -> &HashMap<usize, TypeInfo> {
-        &self.expr_types
+    /// Type of a list, dict or tuple literal where a collection of the same shape is expected.
+    /// Each element is checked against its expected type, so the literals in
+    /// `let xs: List<u8> = [1, 2]` are `u8` and the elements of a `List<dyn Shape>` become
+    /// trait objects.
+    fn infer_collection_literal_type(
+        &mut self,
+        expr: &Node<Expr>,
+        expected: &TypeInfo,
+    ) -> Result<Option<TypeInfo>> {
+        if Self::mentions_unknown(expected) {
+            return Ok(None);
+        }
+        let ty = match (expr.as_ref(), expected.resolve_alias()) {
+            (Expr::Array(elements), TypeInfo::List(element)) if !elements.is_empty() => {
+                for (index, item) in elements.iter().enumerate() {
+                    let item_type = self.infer_expr_type_expecting(item, element)?;
+                    self.check_literal_element(&item_type, element, "array element", index, item);
+                }
+                TypeInfo::List(element.clone())
+            }
+            (Expr::Dict(entries), TypeInfo::Dict { key, value }) if !entries.is_empty() => {
+                for (index, (key_expr, value_expr)) in entries.iter().enumerate() {
+                    let key_type = self.infer_expr_type_expecting(key_expr, key)?;
+                    self.check_literal_element(&key_type, key, "dictionary key", index, key_expr);
+                    let value_type = self.infer_expr_type_expecting(value_expr, value)?;
+                    self.check_literal_element(
+                        &value_type,
+                        value,
+                        "dictionary value",
+                        index,
+                        value_expr,
+                    );
+                }
+                expected.resolve_alias().clone()
+            }
+            (Expr::Tuple(items), TypeInfo::Tuple(types)) if items.len() == types.len() => {
+                TypeInfo::Tuple(
+                    items
+                        .iter()
+                        .zip(types)
+                        .map(|(item, ty)| self.infer_expr_type_expecting(item, ty))
+                        .collect::<Result<Vec<_>>>()?,
+                )
+            }
+            _ => return Ok(None),
+        };
+        self.record_expr_type(expr, &ty);
+        Ok(Some(ty))
     }
 
-    pub fn into_type_maps(
-        self,
-    ) -> (
-        HashMap<usize, TypeInfo>,
-        HashMap<Span, TypeInfo>,
-        HashMap<Span, TypeInfo>,
+    /// Reports the element `index` of a collection literal whose type `actual` does not fit
+    /// the `expected` element type
+    fn check_literal_element(
+        &mut self,
+        actual: &TypeInfo,
+        expected: &TypeInfo,
+        what: &str,
+        index: usize,
+        item: &Node<Expr>,
     ) {
-        (
-            self.expr_types,
-            self.expr_types_by_span,
-            self.comprehension_var_types,
-        )
+        if matches!(actual, TypeInfo::Error) || actual.is_compatible_with(expected) {
+            return;
+        }
+        self.errors.push(
+            TypeError::new(format!(
+                "{} {} has incompatible type: expected {}, got {}",
+                what,
+                index,
+                expected.display_name(),
+                actual.display_name()
+            ))
+            .with_span(*item.span()),
+        );
     }
 
-    pub fn enum_layouts(&self) -> HashMap<String, EnumLayout> {
-        self.context.enum_layouts()
+    /// Type of an integer literal, or a negated one, where an integer type is expected. The literal
+    /// takes that type, and a value outside its range is reported.
+    fn infer_integer_literal_type(
+        &mut self,
+        expr: &Node<Expr>,
+        expected: &TypeInfo,
+    ) -> Option<TypeInfo> {
+        let value = Self::integer_literal_value(expr.as_ref())?;
+        let ty = expected.resolve_alias();
+        ty.integer_bounds()?;
+        self.check_integer_literal_range(value, ty, *expr.span());
+        self.record_expr_type(expr, ty);
+        if let Expr::Unary { expr: operand, .. } = expr.as_ref() {
+            self.record_expr_type(operand, ty);
+        }
+        Some(ty.clone())
     }
 
-    pub fn collect_public_exports(&self, module_name: &str, program: &Program) -> ModuleExports {
-        let mut exports = ModuleExports::new(module_name.to_string());
-        for statement in &program.statements {
-            match statement.as_ref() {
-                Statement::Function(function) if function.as_ref().public => {
-                    if let Some(sig) = self.context.functions.get(&function.as_ref().name).cloned()
+    /// Types of the operands of a binary operator. A number literal takes the type of an
+    /// integer or `f32` operand on the other side, so `byte + 1` stays a `u8`.
+    fn infer_operand_types(
+        &mut self,
+        left: &Node<Expr>,
+        right: &Node<Expr>,
+    ) -> Result<(TypeInfo, TypeInfo)> {
+        if Self::is_number_literal(left.as_ref()) && !Self::is_number_literal(right.as_ref()) {
+            let right_type = self.infer_expr_type(right)?;
+            let left_type = self.infer_literal_operand(left, &right_type)?;
+            Ok((left_type, right_type))
+        } else {
+            let left_type = self.infer_expr_type(left)?;
+            let right_type = self.infer_literal_operand(right, &left_type)?;
+            Ok((left_type, right_type))
+        }
+    }
+
+    fn infer_literal_operand(
+        &mut self,
+        operand: &Node<Expr>,
+        other: &TypeInfo,
+    ) -> Result<TypeInfo> {
+        let adopts = (other.is_integer()
+            && Self::integer_literal_value(operand.as_ref()).is_some())
+            || (matches!(other, TypeInfo::F32) && Self::is_number_literal(operand.as_ref()));
+        if adopts {
+            self.infer_expr_type_expecting(operand, other)
+        } else {
+            self.infer_expr_type(operand)
+        }
+    }
+
+    /// Type of `left op right` given the operand types, shared by binary expressions and
+    /// compound assignments such as `x += y`
+    fn infer_binary_type(
+        &mut self,
+        op: BinaryOp,
+        left_type: &TypeInfo,
+        right_type: &TypeInfo,
+        span: Span,
+    ) -> Result<TypeInfo> {
+        if let Some(result) = self.infer_operator_call(op, left_type, right_type, span) {
+            return Ok(result);
+        }
+
+        match op {
+            BinaryOp::Add
+            | BinaryOp::Sub
+            | BinaryOp::Mul
+            | BinaryOp::Div
+            | BinaryOp::FloorDiv
+            | BinaryOp::Pow => {
+                // Numeric operations
+                match (left_type, right_type) {
+                    // String concatenation (must come before numeric patterns)
+                    (TypeInfo::Str, TypeInfo::Str)
+                    | (TypeInfo::Str, TypeInfo::I64 | TypeInfo::I32)
+                    | (TypeInfo::I64 | TypeInfo::I32, TypeInfo::Str)
+                        if matches!(op, BinaryOp::Add) =>
                     {
-                        exports
-                            .functions
-                            .insert(function.as_ref().name.clone(), sig);
+                        Ok(TypeInfo::Str)
                     }
-                }
-                Statement::Struct { name, public, .. } if *public => {
-                    if let Some(def) = self.context.get_struct(name).cloned() {
-                        exports.structs.insert(name.clone(), def);
+                    // Numeric operations
+                    (left, right) if let Some(common) = left.common_numeric_type(right) => {
+                        Ok(common)
                     }
-                }
-                Statement::Enum { name, public, .. } if *public => {
-                    if let Some(def) = self.context.get_enum(name).cloned() {
-                        exports.enums.insert(name.clone(), def);
+                    (left, right) if left.is_numeric() && right.is_numeric() => {
+                        Ok(self.report_mixed_signedness(op, left, right, span))
+                    }
+                    (TypeInfo::F64, _) | (_, TypeInfo::F64) => Ok(TypeInfo::F64),
+                    (TypeInfo::I64, _) | (_, TypeInfo::I64) => Ok(TypeInfo::I64),
+                    _ => {
+                        if Self::is_unknown_like(left_type) || Self::is_unknown_like(right_type) {
+                            Ok(Self::merge_unknown_like_types(left_type, right_type))
+                        } else {
+                            self.errors.push(
+                                TypeError::new(format!(
+                                    "cannot apply {op:?} to {} and {}",
+                                    left_type.display_name(),
+                                    right_type.display_name()
+                                ))
+                                .with_span(span),
+                            );
+                            Ok(TypeInfo::Error)
+                        }
                     }
                 }
-                Statement::TypeAlias { name, public, .. } if *public => {
-                    if let Some(alias) = self.context.resolve_type_alias(name).cloned() {
-                        exports.type_aliases.insert(name.clone(), alias);
+            }
+            BinaryOp::Eq
+            | BinaryOp::Ne
+            | BinaryOp::Lt
+            | BinaryOp::LtEq
+            | BinaryOp::Gt
+            | BinaryOp::GtEq => {
+                // Comparison operations return bool; numbers compare after widening
+                if left_type.is_compatible_with(right_type)
+                    || left_type.common_numeric_type(right_type).is_some()
+                {
+                    Ok(TypeInfo::Bool)
+                } else {
+                    self.errors.push(
+                        TypeError::new(format!(
+                            "cannot compare {} and {}",
+                            left_type.display_name(),
+                            right_type.display_name()
+                        ))
+                        .with_span(span),
+                    );
+                    Ok(TypeInfo::Error)
+                }
+            }
+            BinaryOp::Is | BinaryOp::IsNot => {
+                // Identity comparison allows matching types or comparisons with None
+                let allow = left_type.is_compatible_with(right_type)
+                    || right_type.is_compatible_with(left_type)
+                    || matches!(left_type, TypeInfo::Unit)
+                    || matches!(right_type, TypeInfo::Unit)
+                    || matches!(left_type, TypeInfo::Unknown)
+                    || matches!(right_type, TypeInfo::Unknown);
+
+                if allow {
+                    Ok(TypeInfo::Bool)
+                } else {
+                    self.errors.push(
+                        TypeError::new(format!(
+                            "cannot use `is` between {} and {}",
+                            left_type.display_name(),
+                            right_type.display_name()
+                        ))
+                        .with_span(span),
+                    );
+                    Ok(TypeInfo::Error)
+                }
+            }
+            BinaryOp::And | BinaryOp::Or => {
+                // Logical operations require bool operands
+                if left_type.is_compatible_with(&TypeInfo::Bool)
+                    && right_type.is_compatible_with(&TypeInfo::Bool)
+                {
+                    Ok(TypeInfo::Bool)
+                } else {
+                    self.errors.push(
+                        TypeError::new(format!(
+                            "logical operations require bool operands, got {} and {}",
+                            left_type.display_name(),
+                            right_type.display_name()
+                        ))
+                        .with_span(span),
+                    );
+                    Ok(TypeInfo::Error)
+                }
+            }
+            BinaryOp::Mod => {
+                // Modulo requires integer operands
+                match (left_type, right_type) {
+                    (left, right) if left.is_integer() && right.is_integer() => {
+                        match left.common_numeric_type(right) {
+                            Some(common) => Ok(common),
+                            None => Ok(self.report_mixed_signedness(op, left, right, span)),
+                        }
+                    }
+                    _ => {
+                        if Self::is_unknown_like(left_type) || Self::is_unknown_like(right_type) {
+                            Ok(TypeInfo::Unknown)
+                        } else {
+                            self.errors.push(
+                                TypeError::new(format!(
+                                    "modulo requires integer operands, got {} and {}",
+                                    left_type.display_name(),
+                                    right_type.display_name()
+                                ))
+                                .with_span(span),
+                            );
+                            Ok(TypeInfo::Error)
+                        }
                     }
                 }
-                Statement::Let { name, public, .. } if *public => {
-                    if let Some(var_type) = self.context.get_variable(name.as_ref()).cloned() {
-                        exports.variables.insert(name.as_ref().clone(), var_type);
+            }
+            BinaryOp::BitAnd
+            | BinaryOp::BitOr
+            | BinaryOp::BitXor
+            | BinaryOp::Shl
+            | BinaryOp::Shr => {
+                // Bitwise operations require integer operands; a shift keeps the
+                // type of the value being shifted
+                let is_shift = matches!(op, BinaryOp::Shl | BinaryOp::Shr);
+                match (left_type, right_type) {
+                    (left, right) if is_shift && left.is_integer() && right.is_integer() => {
+                        Ok(left.clone())
+                    }
+                    (left, right) if left.is_integer() && right.is_integer() => {
+                        match left.common_numeric_type(right) {
+                            Some(common) => Ok(common),
+                            None => Ok(self.report_mixed_signedness(op, left, right, span)),
+                        }
+                    }
+                    _ => {
+                        if Self::is_unknown_like(left_type) || Self::is_unknown_like(right_type) {
+                            Ok(TypeInfo::Unknown)
+                        } else {
+                            let mut error = TypeError::new(format!(
+                                "bitwise operations require integer operands, got {} and {}",
+                                left_type.display_name(),
+                                right_type.display_name()
+                            ))
+                            .with_span(span);
+                            if matches!(left_type, TypeInfo::Bool)
+                                && matches!(right_type, TypeInfo::Bool)
+                            {
+                                error = error.with_hint(
+                                    "use `and`, `or` and `not` to combine bool values".to_string(),
+                                );
+                            }
+                            self.errors.push(error);
+                            Ok(TypeInfo::Error)
+                        }
                     }
                 }
-                _ => {}
             }
         }
-        exports
     }
 
-    pub fn import_module_exports(&mut self, alias: &str, exports: &ModuleExports) {
-        if exports.is_empty() {
-            return;
-        }
-
-        self.context
-            .insert_variable(alias.to_string(), TypeInfo::Module(exports.module.clone()));
+    /// Report an operator applied to integers where neither type holds every value of the
+    /// other, such as `u32` and `i32`
+    fn report_mixed_signedness(
+        &mut self,
+        op: BinaryOp,
+        left: &TypeInfo,
+        right: &TypeInfo,
+        span: Span,
+    ) -> TypeInfo {
+        self.errors.push(
+            TypeError::new(format!(
+                "cannot apply {op:?} to {} and {}",
+                left.display_name(),
+                right.display_name()
+            ))
+            .with_hint(format!(
+                "mixing signed and unsigned integers needs a conversion, as in `x as {}`",
+                left.display_name()
+            ))
+            .with_span(span),
+        );
+        TypeInfo::Error
+    }
 
-        for (name, ty) in &exports.functions {
-            let qualified = format!("{}.{}", exports.module, name);
-            self.context.insert_function(qualified, ty.clone());
+    /// Whether the expression is a number literal or a negated one
+    fn is_number_literal(expr: &Expr) -> bool {
+        match expr {
+            Expr::Literal(lit) => matches!(lit.as_ref(), Literal::Number(_)),
+            Expr::Unary {
+                op: UnaryOp::Neg,
+                expr,
+            } => {
+                Self::is_number_literal(expr.as_ref().as_ref())
+                    && matches!(expr.as_ref().as_ref(), Expr::Literal(_))
+            }
+            _ => false,
+        }
+    }
+
+    /// Value of an integer literal, or of a negated one such as `-128`
+    fn integer_literal_value(expr: &Expr) -> Option<i128> {
+        match expr {
+            Expr::Literal(lit) => match lit.as_ref() {
+                Literal::Number(number) if !number.is_float_literal => Some(number.int_value()),
+                _ => None,
+            },
+            Expr::Unary {
+                op: UnaryOp::Neg,
+                expr,
+            } if matches!(expr.as_ref().as_ref(), Expr::Literal(_)) => {
+                Self::integer_literal_value(expr.as_ref().as_ref()).map(|value| -value)
+            }
+            _ => None,
+        }
+    }
+
+    fn check_integer_literal_range(&mut self, value: i128, ty: &TypeInfo, span: Span) {
+        let Some((min, max)) = ty.integer_bounds() else {
+            return;
+        };
+        if value < min || value > max {
+            self.errors.push(
+                TypeError::new(format!(
+                    "integer literal is out of range for `{}`",
+                    ty.display_name()
+                ))
+                .with_hint(format!(
+                    "`{}` holds values from {} to {}",
+                    ty.display_name(),
+                    min,
+                    max
+                ))
+                .with_span(span),
+            );
+        }
+    }
+
+    /// Coerce a value into a `dyn Trait` object, reporting types that do not implement the trait
+    fn coerce_to_dyn(&mut self, ty: TypeInfo, trait_name: &str, span: Span) -> TypeInfo {
+        match ty.resolve_alias() {
+            TypeInfo::Dyn(name) if name == trait_name => ty,
+            TypeInfo::Unknown | TypeInfo::Error => ty,
+            TypeInfo::Struct { name, .. } if self.context.implements_trait(name, trait_name) => {
+                TypeInfo::Dyn(trait_name.to_string())
+            }
+            other => {
+                let type_name = Self::trait_target_name(other);
+                self.errors.push(
+                    TypeError::new(format!(
+                        "type '{}' does not implement trait '{}'",
+                        type_name, trait_name
+                    ))
+                    .with_hint(format!("Add `impl {} for {}`", trait_name, type_name))
+                    .with_span(span),
+                );
+                TypeInfo::Error
+            }
+        }
+    }
+
+    fn trait_target_name(ty: &TypeInfo) -> String {
+        match ty {
+            TypeInfo::Struct { name, .. } => name.clone(),
+            other => other.display_name(),
+        }
+    }
+
+    /// Type of `expr?`, which is the `Ok`/`Some` payload of its operand. The `Err`/`None` case
+    /// returns early, so the enclosing function must return the same kind of value; an error
+    /// type that differs from the function's is adapted by the error's `into_error` method.
+    fn infer_try_type(&mut self, inner: &Node<Expr>, span: Span) -> Result<TypeInfo> {
+        let operand = self.infer_expr_type(inner)?;
+        let operand = self.context.normalize_type(operand);
+        let variant_field = |variants: &HashMap<String, EnumVariantInfo>, variant: &str| {
+            variants
+                .get(variant)
+                .and_then(|info| info.fields.first().cloned())
+        };
+        let (kind, payload, source_error) = match &operand {
+            TypeInfo::Unknown | TypeInfo::Error => return Ok(operand),
+            TypeInfo::Enum { name, variants, .. } if name == "Result" => (
+                "Result",
+                variant_field(variants, "Ok"),
+                variant_field(variants, "Err"),
+            ),
+            TypeInfo::Enum { name, variants, .. } if name == "Option" => {
+                ("Option", variant_field(variants, "Some"), None)
+            }
+            other => {
+                self.errors.push(
+                    TypeError::new(format!(
+                        "the `?` operator can only be applied to Result or Option, found {}",
+                        other.display_name()
+                    ))
+                    .with_span(span),
+                );
+                return Ok(TypeInfo::Unknown);
+            }
+        };
+        let payload = payload.unwrap_or(TypeInfo::Unit);
+
+        let Some(return_type) = self.current_function_return_type.clone() else {
+            self.errors.push(
+                TypeError::new("the `?` operator can only be used inside a function".to_string())
+                    .with_span(span),
+            );
+            return Ok(payload);
+        };
+        match &return_type {
+            TypeInfo::Unknown | TypeInfo::Error => {}
+            TypeInfo::Enum { name, variants, .. } if name == kind => {
+                if let Some(source) = source_error
+                    && let Some(target) = variant_field(variants, "Err")
+                    && !source.is_compatible_with(&target)
+                    && !self.converts_error(&source, &target)
+                {
+                    let mut error = TypeError::new(format!(
+                        "`?` cannot convert error type {} into {}",
+                        source.display_name(),
+                        target.display_name()
+                    ));
+                    if let TypeInfo::Struct { name, .. } | TypeInfo::Generic { base: name, .. } =
+                        &source
+                    {
+                        error = error.with_hint(format!(
+                            "add `fn into_error(self) -> {}` to {}",
+                            target.display_name(),
+                            name
+                        ));
+                    }
+                    self.errors.push(error.with_span(span));
+                }
+            }
+            other => {
+                let hint = match other {
+                    TypeInfo::Unit => format!("declare the function as returning a {}", kind),
+                    _ => format!("the enclosing function returns {}", other.display_name()),
+                };
+                self.errors.push(
+                    TypeError::new(format!(
+                        "the `?` operator can only be used in a function that returns {}",
+                        kind
+                    ))
+                    .with_hint(hint)
+                    .with_span(span),
+                );
+            }
+        }
+
+        Ok(payload)
+    }
+
+    /// Whether `source` has an `into_error` method producing `target`.
+    fn converts_error(&self, source: &TypeInfo, target: &TypeInfo) -> bool {
+        let (TypeInfo::Struct {
+            name: type_name, ..
+        }
+        | TypeInfo::Enum {
+            name: type_name, ..
+        }
+        | TypeInfo::Generic {
+            base: type_name, ..
+        }) = source
+        else {
+            return false;
+        };
+        matches!(
+            self.context.get_function(&format!("{}.into_error", type_name)),
+            Some(TypeInfo::Function { return_type, .. })
+                if self.context.normalize_type(return_type.as_ref().clone()).is_compatible_with(target)
+        )
+    }
+
+    /// Element type bound by a `for` loop or comprehension iterating over `iterable`
+    fn infer_iteration_type(&mut self, iterable: &Node<Expr>) -> Result<TypeInfo> {
+        let iter_type = self.infer_expr_type(iterable)?;
+        if let Some(element) = self.element_type_of(&iter_type, 0) {
+            return Ok(element);
+        }
+        let mut error = TypeError::new(format!(
+            "cannot iterate over type {}",
+            iter_type.display_name()
+        ));
+        if let TypeInfo::Struct { name, .. } = &iter_type {
+            error = error.with_hint(format!(
+                "add `fn next(self) -> Option<T>` or an `iter` method to {}",
+                name
+            ));
+        }
+        self.errors.push(error.with_span(*iterable.span()));
+        Ok(TypeInfo::Unknown)
+    }
+
+    /// Element type produced by iterating over a value of `iter_type`, or `None` when it is
+    /// not iterable. Structs are iterators when they define `next(self) -> Option<T>`, and
+    /// iterable when their `iter` method returns something iterable.
+    fn element_type_of(&self, iter_type: &TypeInfo, depth: usize) -> Option<TypeInfo> {
+        match self.context.normalize_type(iter_type.clone()) {
+            TypeInfo::List(elem) => Some(elem.as_ref().clone()),
+            // Map iteration yields the keys
+            TypeInfo::Dict { key, .. }
+                if matches!(
+                    key.as_ref(),
+                    TypeInfo::Struct { .. } | TypeInfo::Enum { .. }
+                ) =>
+            {
+                Some(key.as_ref().clone())
+            }
+            TypeInfo::Dict { .. } | TypeInfo::Str => Some(TypeInfo::Str),
+            TypeInfo::Range(start, end) => {
+                if matches!(start.as_ref(), TypeInfo::I64) || matches!(end.as_ref(), TypeInfo::I64)
+                {
+                    Some(TypeInfo::I64)
+                } else {
+                    Some(TypeInfo::I32)
+                }
+            }
+            TypeInfo::Generic { base, mut args } if base == "Iterator" && args.len() == 1 => {
+                args.pop()
+            }
+            TypeInfo::Unknown | TypeInfo::Error => Some(TypeInfo::Unknown),
+            TypeInfo::Alias { underlying, .. } => self.element_type_of(&underlying, depth),
+            TypeInfo::Struct { name, .. } if depth < MAX_ITER_DEPTH => {
+                let method_return =
+                    |method: &str| match self.context.get_function(&format!("{}.{}", name, method))
+                    {
+                        Some(TypeInfo::Function { return_type, .. }) => {
+                            Some(self.context.normalize_type(return_type.as_ref().clone()))
+                        }
+                        _ => None,
+                    };
+                if let Some(next) = method_return("next") {
+                    match next {
+                        TypeInfo::Enum { name, variants, .. } if name == "Option" => Some(
+                            variants
+                                .get("Some")
+                                .and_then(|variant| variant.fields.first().cloned())
+                                .unwrap_or(TypeInfo::Unit),
+                        ),
+                        _ => None,
+                    }
+                } else {
+                    let iter = method_return("iter")?;
+                    self.element_type_of(&iter, depth + 1)
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Type check a closure body in a scope that sees the enclosing variables, returning
+    /// the closure's function type.
+    fn infer_lambda_type(
+        &mut self,
+        params: &[Node<Param>],
+        ret_ty: Option<&Node<Type>>,
+        body: &Node<Block>,
+        expected: Option<&TypeInfo>,
+    ) -> Result<TypeInfo> {
+        let (expected_params, expected_return) = match expected.map(TypeInfo::resolve_alias) {
+            Some(TypeInfo::Function {
+                params: expected_params,
+                return_type,
+                ..
+            }) if expected_params.len() == params.len() => {
+                (expected_params.clone(), Some(return_type.as_ref().clone()))
+            }
+            _ => (Vec::new(), None),
+        };
+
+        let param_types: Vec<TypeInfo> = params
+            .iter()
+            .enumerate()
+            .map(|(i, param)| match &param.as_ref().ty {
+                Some(ty) => self.context.type_from_annotation(ty),
+                None => expected_params.get(i).cloned().unwrap_or(TypeInfo::Unknown),
+            })
+            .collect();
+        let annotated_return = ret_ty.map(|ty| self.context.type_from_annotation(ty));
+
+        let outer_variables = self.context.variables.clone();
+        for (param, ty) in params.iter().zip(&param_types) {
+            self.context
+                .insert_variable(param.as_ref().name.as_ref().clone(), ty.clone());
+        }
+        let old_return_type = self.current_function_return_type.replace(
+            annotated_return
+                .clone()
+                .or(expected_return)
+                .unwrap_or(TypeInfo::Unknown),
+        );
+        let old_inferred_returns = std::mem::replace(
+            &mut self.inferred_returns,
+            annotated_return.is_none().then_some(0),
+        );
+        let was_handling = std::mem::replace(&mut self.handling_exception, false);
+        let outer_loops = std::mem::take(&mut self.loops);
+        let checked = self.check_block(body);
+        self.loops = outer_loops;
+        self.handling_exception = was_handling;
+        let body_return_type =
+            std::mem::replace(&mut self.current_function_return_type, old_return_type);
+        let inferred_returns = std::mem::replace(&mut self.inferred_returns, old_inferred_returns);
+        self.context.variables = outer_variables;
+        checked?;
+
+        // Without an annotation the `return`s in the body, nested blocks included, give the
+        // return type, and a body without one returns unit
+        let return_type = match annotated_return {
+            Some(annotated) => annotated,
+            None if inferred_returns == Some(0) => TypeInfo::Unit,
+            None => body_return_type.unwrap_or(TypeInfo::Unknown),
+        };
+
+        Ok(TypeInfo::Function {
+            params: param_types,
+            param_defaults: vec![false; params.len()],
+            return_type: Box::new(return_type),
+        })
+    }
+
+    /// Get collected errors
+    pub fn errors(&self) -> &[TypeError] {
+        &self.errors
+    }
+
+    /// Get collected warnings; these never fail type checking
+    pub fn warnings(&self) -> &[TypeError] {
+        &self.warnings
+    }
+
+    pub fn expr_type_map(&self) -> &HashMap<usize, TypeInfo> {
+        &self.expr_types
+    }
+
+    pub fn into_type_maps(
+        mut self,
+    ) -> (
+        HashMap<usize, TypeInfo>,
+        HashMap<Span, TypeInfo>,
+        HashMap<Span, TypeInfo>,
+    ) {
+        // The method copies are dropped with the checker, so their expressions can only be
+        // looked up by span
+        for id in self.method_expr_ids.values().flatten() {
+            self.expr_types.remove(id);
+        }
+        (
+            self.expr_types,
+            self.expr_types_by_span,
+            self.comprehension_var_types,
+        )
+    }
+
+    pub fn enum_layouts(&self) -> HashMap<String, EnumLayout> {
+        self.context.enum_layouts()
+    }
+
+    pub fn collect_public_exports(&self, module_name: &str, program: &Program) -> ModuleExports {
+        let mut exports = ModuleExports::new(module_name.to_string());
+        for statement in &program.statements {
+            match statement.as_ref() {
+                Statement::Function(function) if function.as_ref().public => {
+                    if let Some(sig) = self.context.functions.get(&function.as_ref().name).cloned()
+                    {
+                        exports
+                            .functions
+                            .insert(function.as_ref().name.clone(), sig);
+                    }
+                    if let Some(names) = self.param_names.get(&function.as_ref().name) {
+                        exports
+                            .param_names
+                            .insert(function.as_ref().name.clone(), names.clone());
+                    }
+                    if self.variadic.contains(&function.as_ref().name) {
+                        exports.variadic.insert(function.as_ref().name.clone());
+                    }
+                }
+                Statement::Struct { name, public, .. } if *public => {
+                    if let Some(def) = self.context.get_struct(name).cloned() {
+                        exports.structs.insert(name.clone(), def);
+                    }
+                }
+                Statement::Enum { name, public, .. } if *public => {
+                    if let Some(def) = self.context.get_enum(name).cloned() {
+                        exports.enums.insert(name.clone(), def);
+                    }
+                }
+                Statement::Trait { name, public, .. } if *public => {
+                    if let Some(def) = self.context.get_trait(name).cloned() {
+                        exports.traits.insert(name.clone(), def);
+                    }
+                }
+                Statement::Impl {
+                    trait_name: Some(trait_name),
+                    type_name,
+                    ..
+                } => {
+                    exports
+                        .trait_impls
+                        .entry(type_name.clone())
+                        .or_default()
+                        .push(trait_name.clone());
+                }
+                Statement::TypeAlias { name, public, .. } if *public => {
+                    if let Some(alias) = self.context.resolve_type_alias(name).cloned() {
+                        exports.type_aliases.insert(name.clone(), alias);
+                    }
+                }
+                Statement::Const { name, public, .. } if *public => {
+                    if let Some(var_type) = self.context.get_variable(name).cloned() {
+                        exports.variables.insert(name.clone(), var_type);
+                    }
+                }
+                Statement::Let {
+                    pattern, public, ..
+                } if *public => {
+                    for name in pattern.as_ref().bindings() {
+                        if let Some(var_type) = self.context.get_variable(name).cloned() {
+                            exports.variables.insert(name.to_string(), var_type);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        exports
+    }
+
+    pub fn import_module_exports(&mut self, alias: &str, exports: &ModuleExports) {
+        if exports.is_empty() {
+            return;
+        }
+
+        self.context
+            .insert_variable(alias.to_string(), TypeInfo::Module(exports.module.clone()));
+
+        for (name, ty) in &exports.functions {
+            let qualified = format!("{}.{}", exports.module, name);
+            self.context.insert_function(qualified, ty.clone());
+        }
+
+        for (name, names) in &exports.param_names {
+            let qualified = format!("{}.{}", exports.module, name);
+            self.param_names.insert(qualified, names.clone());
+        }
+
+        for name in &exports.variadic {
+            self.variadic.insert(format!("{}.{}", exports.module, name));
         }
 
         for (name, ty) in &exports.variables {
@@ -2930,11 +5399,1216 @@ impl TypeChecker {
             self.context.define_enum(def.clone());
         }
 
+        for def in exports.traits.values() {
+            self.context.define_trait(def.clone());
+        }
+
+        for (type_name, traits) in &exports.trait_impls {
+            for trait_name in traits {
+                self.context
+                    .add_trait_impl(type_name.clone(), trait_name.clone());
+            }
+        }
+
         for (name, ty) in &exports.type_aliases {
             self.context.type_aliases.insert(name.clone(), ty.clone());
         }
     }
 
+    /// Resolve the type stored at an assignment target, reporting targets that are
+    /// not assignable (non-places, unknown fields, string characters).
+    fn assignment_target_type(&mut self, target: &Node<Expr>) -> Result<TypeInfo> {
+        let span = target.span();
+        if !target.as_ref().is_place() {
+            self.errors.push(
+                TypeError::new("invalid assignment target".to_string())
+                    .with_help(
+                        "Only variables, struct fields and indexed elements can be assigned"
+                            .to_string(),
+                    )
+                    .with_span(*span),
+            );
+            return Ok(TypeInfo::Error);
+        }
+
+        match target.as_ref() {
+            Expr::Identifier(name) => {
+                let var_type = self
+                    .context
+                    .get_variable(name)
+                    .ok_or_else(|| {
+                        TypeError::new(format!("undefined variable: {}", name))
+                            .with_hint(format!("did you mean to declare it with `let {}`?", name))
+                            .with_help(
+                                "Variables must be declared with `let` before they can be assigned"
+                                    .to_string(),
+                            )
+                            .with_span(*span)
+                    })?
+                    .clone();
+                Ok(var_type)
+            }
+            Expr::Member { object, field } => {
+                let object_type = self.infer_expr_type(object)?;
+                match object_type.resolve_alias() {
+                    TypeInfo::Struct { name, fields } => match fields.get(field) {
+                        Some(field_type) => Ok(field_type.clone()),
+                        None => {
+                            self.errors.push(
+                                TypeError::new(format!(
+                                    "struct '{}' has no field '{}'",
+                                    name, field
+                                ))
+                                .with_hint(
+                                    "Check the struct definition for available fields".to_string(),
+                                )
+                                .with_span(*span),
+                            );
+                            Ok(TypeInfo::Error)
+                        }
+                    },
+                    TypeInfo::Error => Ok(TypeInfo::Error),
+                    ty if Self::is_unknown_like(ty) => Ok(TypeInfo::Unknown),
+                    _ => {
+                        self.errors.push(
+                            TypeError::new(format!(
+                                "cannot assign to member '{}' of type {}",
+                                field,
+                                object_type.display_name()
+                            ))
+                            .with_hint("Only struct fields can be assigned".to_string())
+                            .with_span(*span),
+                        );
+                        Ok(TypeInfo::Error)
+                    }
+                }
+            }
+            Expr::Index { object, index } => {
+                let object_type = self.infer_expr_type(object)?;
+                let index_type = self.infer_expr_type(index)?;
+                if matches!(object_type.resolve_alias(), TypeInfo::Str) {
+                    self.errors.push(
+                        TypeError::new("cannot assign to an index of a string".to_string())
+                            .with_hint("Strings are immutable".to_string())
+                            .with_help(
+                                "Build a new string with slicing and concatenation instead"
+                                    .to_string(),
+                            )
+                            .with_span(*span),
+                    );
+                    return Ok(TypeInfo::Error);
+                }
+                Ok(self.index_element_type(&object_type, &index_type, span))
+            }
+            _ => Ok(TypeInfo::Error),
+        }
+    }
+
+    /// Type of `left op right` when one of the operands is a struct, or an enum with the
+    /// operator method, which calls that method (see `BinaryOp::method_name`). `None` when the
+    /// built-in rules apply.
+    fn infer_operator_call(
+        &mut self,
+        op: BinaryOp,
+        left: &TypeInfo,
+        right: &TypeInfo,
+        span: Span,
+    ) -> Option<TypeInfo> {
+        let method = op.method_name()?;
+        let left = self.context.normalize_type(left.resolve_alias().clone());
+        let right = self.context.normalize_type(right.resolve_alias().clone());
+        let has_operator = |ty: &TypeInfo| match ty {
+            TypeInfo::Struct { .. } => true,
+            TypeInfo::Enum { name, .. } => self
+                .context
+                .get_function(&format!("{}.{}", name, method))
+                .is_some(),
+            _ => false,
+        };
+        if !has_operator(&left) && !has_operator(&right) {
+            return None;
+        }
+        let (receiver, other) = if op.swaps_operands() {
+            (&right, &left)
+        } else {
+            (&left, &right)
+        };
+        let (TypeInfo::Struct { name, .. } | TypeInfo::Enum { name, .. }) = receiver else {
+            if Self::is_unknown_like(receiver) {
+                return Some(TypeInfo::Unknown);
+            }
+            let mut error = TypeError::new(format!(
+                "cannot apply `{}` to {} and {}",
+                op.symbol(),
+                left.display_name(),
+                right.display_name()
+            ))
+            .with_span(span);
+            if !op.swaps_operands() {
+                error = error.with_hint(format!(
+                    "`{}` calls the `{}` method of its left operand; put the struct first",
+                    op.symbol(),
+                    method
+                ));
+            }
+            self.errors.push(error);
+            return Some(TypeInfo::Error);
+        };
+
+        let returns_bool = matches!(method, "eq" | "lt");
+        let qualified = format!("{}.{}", name, method);
+        let Some(TypeInfo::Function {
+            params,
+            return_type,
+            ..
+        }) = self.context.get_function(&qualified).cloned()
+        else {
+            let result = if returns_bool {
+                "bool".to_string()
+            } else {
+                name.clone()
+            };
+            let other_name = match other {
+                TypeInfo::Struct { name, .. } | TypeInfo::Enum { name, .. } => name.clone(),
+                other => other.display_name(),
+            };
+            let kind = if matches!(receiver, TypeInfo::Enum { .. }) {
+                "enum"
+            } else {
+                "struct"
+            };
+            self.errors.push(
+                TypeError::new(format!(
+                    "`{}` is not defined for {}: {} `{}` has no `{}` method",
+                    op.symbol(),
+                    name,
+                    kind,
+                    name,
+                    method
+                ))
+                .with_hint(format!(
+                    "Define `fn {}(self, other: {}) -> {}` on `{}`",
+                    method, other_name, result, name
+                ))
+                .with_span(span),
+            );
+            return Some(TypeInfo::Error);
+        };
+
+        let resolve_self = |ty: &TypeInfo| match ty {
+            TypeInfo::Generic { base, args } if base == "Self" && args.is_empty() => {
+                receiver.clone()
+            }
+            other => other.clone(),
+        };
+        if params.len() != 2 {
+            self.errors.push(
+                TypeError::new(format!(
+                    "operator method `{}` must take `self` and one other parameter",
+                    qualified
+                ))
+                .with_hint(format!(
+                    "`{}` passes its other operand to `{}`",
+                    op.symbol(),
+                    method
+                ))
+                .with_span(span),
+            );
+            return Some(TypeInfo::Error);
+        }
+        let param = resolve_self(&params[1]);
+        if !matches!(other, TypeInfo::Error) && !other.is_compatible_with(&param) {
+            self.errors.push(
+                TypeError::new(format!(
+                    "cannot apply `{}` to {} and {}",
+                    op.symbol(),
+                    left.display_name(),
+                    right.display_name()
+                ))
+                .with_hint(format!(
+                    "`{}` takes {}, got {}",
+                    qualified,
+                    param.display_name(),
+                    other.display_name()
+                ))
+                .with_span(span),
+            );
+            return Some(TypeInfo::Error);
+        }
+
+        let result = resolve_self(&return_type);
+        if returns_bool {
+            if !result.is_compatible_with(&TypeInfo::Bool) {
+                self.errors.push(
+                    TypeError::new(format!(
+                        "`{}` must return bool to implement `{}`, but returns {}",
+                        qualified,
+                        op.symbol(),
+                        result.display_name()
+                    ))
+                    .with_span(span),
+                );
+                return Some(TypeInfo::Error);
+            }
+            return Some(TypeInfo::Bool);
+        }
+        Some(result)
+    }
+
+    /// Type of `-operand` or `~operand` on a struct, which calls its `neg` or `bitnot` method
+    fn infer_unary_operator_call(
+        &mut self,
+        op: UnaryOp,
+        operand: &TypeInfo,
+        span: Span,
+    ) -> Option<TypeInfo> {
+        let method = op.method_name()?;
+        let operand = self.context.normalize_type(operand.resolve_alias().clone());
+        let TypeInfo::Struct { name, .. } = &operand else {
+            return None;
+        };
+        let qualified = format!("{}.{}", name, method);
+        match self.context.get_function(&qualified).cloned() {
+            Some(TypeInfo::Function {
+                params,
+                return_type,
+                ..
+            }) if params.len() == 1 => Some(match return_type.as_ref() {
+                TypeInfo::Generic { base, args } if base == "Self" && args.is_empty() => operand,
+                other => other.clone(),
+            }),
+            Some(_) => {
+                self.errors.push(
+                    TypeError::new(format!(
+                        "operator method `{}` must take only `self`",
+                        qualified
+                    ))
+                    .with_span(span),
+                );
+                Some(TypeInfo::Error)
+            }
+            None => {
+                self.errors.push(
+                    TypeError::new(format!(
+                        "struct `{}` has no `{}` method, so it cannot be used with unary `{}`",
+                        name,
+                        method,
+                        if op == UnaryOp::Neg { "-" } else { "~" }
+                    ))
+                    .with_hint(format!(
+                        "Define `fn {}(self) -> {}` on `{}`",
+                        method, name, name
+                    ))
+                    .with_span(span),
+                );
+                Some(TypeInfo::Error)
+            }
+        }
+    }
+
+    /// Check the arguments of a call against the callee's parameters `names`, reporting
+    /// surplus, unknown, repeated and missing arguments. Returns whether the call is valid.
+    fn check_call_arguments(
+        &mut self,
+        callee: &str,
+        names: &[String],
+        defaults: &[bool],
+        args: &[Node<Expr>],
+        kwargs: &[(String, Node<Expr>)],
+        span: Span,
+    ) -> bool {
+        let total_params = defaults.len();
+        if args.len() > total_params {
+            self.errors.push(
+                TypeError::new(format!(
+                    "function expects at most {} arguments, got {}",
+                    total_params,
+                    args.len()
+                ))
+                .with_hint("Remove extra arguments or verify the function signature".to_string())
+                .with_span(span),
+            );
+            return false;
+        }
+
+        if kwargs.is_empty() {
+            let required_params = defaults.iter().filter(|flag| !**flag).count();
+            if args.len() < required_params {
+                self.errors.push(
+                    TypeError::new(format!(
+                        "function expects at least {} arguments, got {}",
+                        required_params,
+                        args.len()
+                    ))
+                    .with_hint("Provide values for all parameters without defaults".to_string())
+                    .with_span(span),
+                );
+                return false;
+            }
+            return true;
+        }
+
+        if names.len() != total_params {
+            self.reject_keyword_arguments(callee, span);
+            return false;
+        }
+
+        let errors = self.errors.len();
+        let mut given = vec![false; total_params];
+        given[..args.len()].fill(true);
+        for (name, value) in kwargs {
+            let Some(index) = names.iter().position(|param| param == name) else {
+                self.errors.push(
+                    TypeError::new(format!("`{}` has no parameter named `{}`", callee, name))
+                        .with_hint(format!(
+                            "The parameters of `{}` are: {}",
+                            callee,
+                            names.join(", ")
+                        ))
+                        .with_span(*value.span()),
+                );
+                continue;
+            };
+            if given[index] {
+                let hint = if index < args.len() {
+                    format!(
+                        "`{}` is already given by positional argument {}",
+                        name,
+                        index + 1
+                    )
+                } else {
+                    format!("Remove one of the `{}=` arguments", name)
+                };
+                self.errors.push(
+                    TypeError::new(format!(
+                        "argument `{}` given more than once in call to `{}`",
+                        name, callee
+                    ))
+                    .with_hint(hint)
+                    .with_span(*value.span()),
+                );
+            }
+            given[index] = true;
+        }
+
+        for ((name, given), has_default) in names.iter().zip(&given).zip(defaults) {
+            if !given && !has_default {
+                self.errors.push(
+                    TypeError::new(format!(
+                        "missing argument `{}` in call to `{}`",
+                        name, callee
+                    ))
+                    .with_hint(format!("Pass `{}` positionally or as `{}=...`", name, name))
+                    .with_span(span),
+                );
+            }
+        }
+        self.errors.len() == errors
+    }
+
+    fn check_argument_type(
+        &mut self,
+        arg: &Node<Expr>,
+        param_type: &TypeInfo,
+        argument: &str,
+        span: Span,
+    ) -> Result<()> {
+        let arg_type = self.infer_expr_type_expecting(arg, param_type)?;
+        if !matches!(arg_type, TypeInfo::Error) && !arg_type.is_compatible_with(param_type) {
+            self.errors.push(
+                TypeError::new(format!(
+                    "argument {} type mismatch: expected {}, got {}",
+                    argument,
+                    param_type.display_name(),
+                    arg_type.display_name()
+                ))
+                .with_span(span)
+                .with_hint(format!(
+                    "Argument {} should be of type `{}`",
+                    argument,
+                    param_type.display_name()
+                ))
+                .with_help(
+                    "Check the function signature and ensure argument types match".to_string(),
+                ),
+            );
+        }
+        Ok(())
+    }
+
+    /// `f(x=1)` reads like a struct literal; it calls `f` when `f` is a declared function
+    fn check_keyword_call(
+        &mut self,
+        name: &str,
+        kwargs: &[(String, Node<Expr>)],
+        span: Span,
+    ) -> Result<TypeInfo> {
+        let mut names = self.param_names.get(name).cloned().unwrap_or_default();
+        // A variadic parameter cannot be named, so it collects nothing here
+        let rest_name = if self.variadic.contains(name) {
+            names.pop()
+        } else {
+            None
+        };
+        let signature = match self.generic_functions.get(name).cloned() {
+            Some(generic) => {
+                let arranged = arrange_arguments(&names, &[], kwargs);
+                self.instantiate_generic_call(&generic, &[], &arranged, &[], span)?
+            }
+            None => self
+                .context
+                .get_function(name)
+                .cloned()
+                .unwrap_or(TypeInfo::Error),
+        };
+        let TypeInfo::Function {
+            params,
+            param_defaults,
+            return_type,
+        } = signature
+        else {
+            return Ok(TypeInfo::Error);
+        };
+        let defaults = &param_defaults[..names.len().min(param_defaults.len())];
+        if !self.check_spread_arguments(
+            name,
+            rest_name.is_some(),
+            rest_name.as_deref(),
+            &[],
+            kwargs,
+        ) || !self.check_call_arguments(name, &names, defaults, &[], kwargs, span)
+        {
+            return Ok(TypeInfo::Error);
+        }
+        for (index, (arg, param_type)) in arrange_arguments(&names, &[], kwargs)
+            .into_iter()
+            .zip(&params)
+            .enumerate()
+        {
+            if let Some(arg) = arg {
+                let argument = format!("`{}`", names[index]);
+                self.check_argument_type(arg, param_type, &argument, span)?;
+            }
+        }
+        Ok(*return_type)
+    }
+
+    fn reject_keyword_arguments(&mut self, callee: &str, span: Span) {
+        self.errors.push(
+            TypeError::new(format!("`{}` does not take keyword arguments", callee))
+                .with_hint(
+                    "Only the parameters of functions and methods declared in Otter can be named; pass the arguments positionally"
+                        .to_string(),
+                )
+                .with_span(span),
+        );
+    }
+
+    /// Whether the function or method `path` takes a variadic parameter, either declared in
+    /// Otter or registered by the runtime with a variadic tail
+    fn is_variadic(&self, path: &str) -> bool {
+        self.variadic.contains(path)
+            || self
+                .registry
+                .and_then(|registry| registry.resolve(path))
+                .is_some_and(|symbol| symbol.signature.variadic.is_some())
+    }
+
+    /// Report `*xs` arguments outside the variadic tail of a call and keyword arguments that
+    /// name the variadic parameter. `rest_name` names the variadic parameter of a callee
+    /// declared in Otter. Returns whether there were none.
+    fn check_spread_arguments(
+        &mut self,
+        callee: &str,
+        variadic: bool,
+        rest_name: Option<&str>,
+        args: &[Node<Expr>],
+        kwargs: &[(String, Node<Expr>)],
+    ) -> bool {
+        let errors = self.errors.len();
+        for arg in args {
+            if !matches!(arg.as_ref(), Expr::Spread(_)) {
+                continue;
+            }
+            let error = match (variadic, rest_name) {
+                (true, Some(rest_name)) => TypeError::new(format!(
+                    "a spread argument can only fill the variadic parameter `{}` of `{}`",
+                    rest_name, callee
+                ))
+                .with_hint(format!(
+                    "Pass the parameters before `*{}` individually",
+                    rest_name
+                )),
+                (true, None) => TypeError::new(format!(
+                    "a spread argument can only fill the variadic arguments of `{}`",
+                    callee
+                ))
+                .with_hint("Pass the leading parameters individually".to_string()),
+                (false, _) => TypeError::new(format!(
+                    "`{}` has no variadic parameter to spread arguments into",
+                    callee
+                ))
+                .with_hint(
+                    "Only a parameter declared as `*name: T` collects a spread list".to_string(),
+                ),
+            };
+            self.errors.push(error.with_span(*arg.span()));
+        }
+        for (name, value) in kwargs {
+            if rest_name == Some(name.as_str()) {
+                self.errors.push(
+                    TypeError::new(format!(
+                        "the variadic parameter `{}` of `{}` cannot be given by keyword",
+                        name, callee
+                    ))
+                    .with_hint("Pass its values positionally after the other arguments".to_string())
+                    .with_span(*value.span()),
+                );
+            }
+        }
+        self.errors.len() == errors
+    }
+
+    /// Check the positional arguments that a variadic parameter of type `list_type`
+    /// collects; `offset` is the number of arguments before them
+    fn check_rest_arguments(
+        &mut self,
+        rest: &[Node<Expr>],
+        offset: usize,
+        list_type: &TypeInfo,
+        span: Span,
+    ) -> Result<()> {
+        let element = match list_type {
+            TypeInfo::List(element) => element.as_ref().clone(),
+            _ => TypeInfo::Unknown,
+        };
+        for (index, arg) in rest.iter().enumerate() {
+            let argument = format!("{}", offset + index + 1);
+            let Expr::Spread(inner) = arg.as_ref() else {
+                self.check_argument_type(arg, &element, &argument, span)?;
+                continue;
+            };
+            let spread_type = self.infer_expr_type(inner)?;
+            match spread_type {
+                TypeInfo::List(_) => {
+                    if !spread_type.is_compatible_with(list_type) {
+                        self.errors.push(
+                            TypeError::new(format!(
+                                "argument {} type mismatch: expected {}, got {}",
+                                argument,
+                                list_type.display_name(),
+                                spread_type.display_name()
+                            ))
+                            .with_hint(format!(
+                                "The elements of a spread list must be of type `{}`",
+                                element.display_name()
+                            ))
+                            .with_span(*arg.span()),
+                        );
+                    }
+                }
+                TypeInfo::Unknown | TypeInfo::Error => {}
+                other => self.errors.push(
+                    TypeError::new(format!(
+                        "cannot spread {}: only lists can be spread",
+                        other.display_name()
+                    ))
+                    .with_hint("Pass the value without `*`".to_string())
+                    .with_span(*arg.span()),
+                ),
+            }
+        }
+        Ok(())
+    }
+
+    /// `sort(xs)` sorts a list in place. The elements must be numbers, strings, bools, or
+    /// structs and enums with an `lt` method.
+    fn check_sort_call(&mut self, args: &[Node<Expr>], span: Span) -> Result<TypeInfo> {
+        if args.len() != 1 {
+            self.errors.push(
+                TypeError::new(format!("`sort` expects 1 argument, got {}", args.len()))
+                    .with_hint("call it as `sort(list)`".to_string())
+                    .with_span(span),
+            );
+            return Ok(TypeInfo::Error);
+        }
+        let list_type = self.infer_expr_type(&args[0])?;
+        let element = match self
+            .context
+            .normalize_type(list_type.resolve_alias().clone())
+        {
+            TypeInfo::List(element) => self.context.normalize_type(element.as_ref().clone()),
+            TypeInfo::Error => return Ok(TypeInfo::Error),
+            ty if Self::is_unknown_like(&ty) => return Ok(TypeInfo::Unit),
+            other => {
+                self.errors.push(
+                    TypeError::new(format!(
+                        "`sort` expects a list, got {}",
+                        other.display_name()
+                    ))
+                    .with_span(*args[0].span()),
+                );
+                return Ok(TypeInfo::Error);
+            }
+        };
+        match &element {
+            TypeInfo::I32
+            | TypeInfo::I64
+            | TypeInfo::F64
+            | TypeInfo::Str
+            | TypeInfo::Bool
+            | TypeInfo::Error => {}
+            ty if Self::is_unknown_like(ty) => {}
+            TypeInfo::Struct { name, .. } | TypeInfo::Enum { name, .. } => {
+                let kind = if matches!(element, TypeInfo::Enum { .. }) {
+                    "enum"
+                } else {
+                    "struct"
+                };
+                let lt = self.context.get_function(&format!("{}.lt", name));
+                if !matches!(
+                    lt,
+                    Some(TypeInfo::Function { params, return_type, .. })
+                        if params.len() == 2 && return_type.is_compatible_with(&TypeInfo::Bool)
+                ) {
+                    self.errors.push(
+                        TypeError::new(format!(
+                            "cannot sort list<{}>: {} `{}` has no `lt` method",
+                            name, kind, name
+                        ))
+                        .with_hint(format!(
+                            "Add `@derive(Ord)` to `{}` or define `fn lt(self, other: {}) -> bool` to order its values",
+                            name, name
+                        ))
+                        .with_span(*args[0].span()),
+                    );
+                }
+            }
+            other => {
+                self.errors.push(
+                    TypeError::new(format!(
+                        "cannot sort list<{}>: its elements have no ordering",
+                        other.display_name()
+                    ))
+                    .with_hint(
+                        "Lists of numbers, strings, bools, and structs and enums with an `lt` method, can be sorted"
+                            .to_string(),
+                    )
+                    .with_span(*args[0].span()),
+                );
+            }
+        }
+        Ok(TypeInfo::Unit)
+    }
+
+    /// Reports a struct without a `str` method in a value formatted by `str(x)` or an
+    /// f-string, including one inside a list or tuple
+    fn check_displayable(&mut self, ty: &TypeInfo, span: Span) {
+        match self.context.normalize_type(ty.resolve_alias().clone()) {
+            TypeInfo::Struct { name, .. }
+                if self
+                    .context
+                    .get_function(&format!("{}.str", name))
+                    .is_none() =>
+            {
+                self.errors.push(
+                    TypeError::new(format!(
+                        "struct `{}` cannot be formatted without a `str` method",
+                        name
+                    ))
+                    .with_hint(format!(
+                        "Add `@derive(Debug)` to `{}` or define `fn str(self) -> str`",
+                        name
+                    ))
+                    .with_span(span),
+                );
+            }
+            TypeInfo::List(element) => self.check_displayable(&element, span),
+            TypeInfo::Tuple(elements) => {
+                for element in &elements {
+                    self.check_displayable(element, span);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Reports `defer(x)` where `x` is not a function: the parentheses make it a call to the
+    /// runtime `defer` builtin, so a `defer (conn.close())` meant as a `defer` statement would
+    /// otherwise lose its cleanup
+    fn rejects_deferred_value(&mut self, args: &[Node<Expr>], span: Span) -> Result<bool> {
+        let [arg] = args else {
+            return Ok(false);
+        };
+        let arg_type = self.infer_expr_type(arg)?;
+        if matches!(arg_type, TypeInfo::Function { .. } | TypeInfo::Error) {
+            return Ok(false);
+        }
+        self.errors.push(
+            TypeError::new(
+                "`defer(...)` calls the runtime `defer` builtin, which takes a function"
+                    .to_string(),
+            )
+            .with_hint(
+                "Write `defer expr` without parentheses to run an expression when the block is left"
+                    .to_string(),
+            )
+            .with_span(span),
+        );
+        Ok(true)
+    }
+
+    /// `hash(x)` gives an int for a number, string or bool, or calls the `hash` method of a
+    /// struct or enum
+    fn check_hash_call(&mut self, args: &[Node<Expr>], span: Span) -> Result<TypeInfo> {
+        if args.len() != 1 {
+            self.errors.push(
+                TypeError::new(format!("`hash` expects 1 argument, got {}", args.len()))
+                    .with_hint("call it as `hash(value)`".to_string())
+                    .with_span(span),
+            );
+            return Ok(TypeInfo::Error);
+        }
+        let value_type = self.infer_expr_type(&args[0])?;
+        let (kind, name) = match self
+            .context
+            .normalize_type(value_type.resolve_alias().clone())
+        {
+            TypeInfo::Struct { name, .. } => ("struct", name),
+            TypeInfo::Enum { name, .. } => ("enum", name),
+            ty if ty.is_numeric()
+                || matches!(ty, TypeInfo::Str | TypeInfo::Bool | TypeInfo::Error)
+                || Self::is_unknown_like(&ty) =>
+            {
+                return Ok(TypeInfo::I64);
+            }
+            other => {
+                self.errors.push(
+                    TypeError::new(format!("cannot hash {}", other.display_name()))
+                        .with_hint(
+                            "Numbers, strings, bools, and structs and enums with a `hash` method, can be hashed"
+                                .to_string(),
+                        )
+                        .with_span(*args[0].span()),
+                );
+                return Ok(TypeInfo::Error);
+            }
+        };
+        match self.context.get_function(&format!("{}.hash", name)) {
+            Some(TypeInfo::Function {
+                params,
+                return_type,
+                ..
+            }) if params.len() == 1 && return_type.is_compatible_with(&TypeInfo::I64) => {
+                Ok(TypeInfo::I64)
+            }
+            _ => {
+                self.errors.push(
+                    TypeError::new(format!(
+                        "cannot hash {} `{}`: it has no `hash` method",
+                        kind, name
+                    ))
+                    .with_hint(format!(
+                        "Add `@derive(Hash)` to `{}` or define `fn hash(self) -> int`",
+                        name
+                    ))
+                    .with_span(*args[0].span()),
+                );
+                Ok(TypeInfo::Error)
+            }
+        }
+    }
+
+    /// `map(f, iterable)`, `filter(predicate, iterable)`, `zip(left, right)` and
+    /// `take(iterable, count)`, which return an `Iterator` of the elements they produce
+    fn check_adapter_call(
+        &mut self,
+        name: &str,
+        args: &[Node<Expr>],
+        span: Span,
+    ) -> Result<TypeInfo> {
+        if args.len() != 2 {
+            let usage = match name {
+                "map" => "map(f, iterable)",
+                "filter" => "filter(predicate, iterable)",
+                "zip" => "zip(left, right)",
+                _ => "take(iterable, count)",
+            };
+            self.errors.push(
+                TypeError::new(format!(
+                    "`{}` expects 2 arguments, got {}",
+                    name,
+                    args.len()
+                ))
+                .with_hint(format!("call it as `{}`", usage))
+                .with_span(span),
+            );
+            return Ok(TypeInfo::Error);
+        }
+
+        let element = match name {
+            "map" | "filter" => {
+                let element = self.infer_iteration_type(&args[1])?;
+                let expected = TypeInfo::Function {
+                    params: vec![element.clone()],
+                    param_defaults: vec![false],
+                    return_type: Box::new(TypeInfo::Unknown),
+                };
+                let func_type = self.infer_expr_type_expecting(&args[0], &expected)?;
+                let return_type = match func_type.resolve_alias() {
+                    TypeInfo::Function {
+                        params,
+                        return_type,
+                        ..
+                    } if params.len() == 1 => {
+                        if !element.is_compatible_with(&params[0]) {
+                            self.errors.push(
+                                TypeError::new(format!(
+                                    "`{}` callback takes {}, but the iterable yields {}",
+                                    name,
+                                    params[0].display_name(),
+                                    element.display_name()
+                                ))
+                                .with_span(*args[0].span()),
+                            );
+                        }
+                        return_type.as_ref().clone()
+                    }
+                    ty if Self::is_unknown_like(ty) || matches!(ty, TypeInfo::Error) => {
+                        TypeInfo::Unknown
+                    }
+                    other => {
+                        self.errors.push(
+                            TypeError::new(format!(
+                                "`{}` expects a function of one argument, got {}",
+                                name,
+                                other.display_name()
+                            ))
+                            .with_span(*args[0].span()),
+                        );
+                        TypeInfo::Unknown
+                    }
+                };
+                if name == "map" {
+                    return_type
+                } else {
+                    if !return_type.is_compatible_with(&TypeInfo::Bool) {
+                        self.errors.push(
+                            TypeError::new(format!(
+                                "`filter` predicate must return bool, got {}",
+                                return_type.display_name()
+                            ))
+                            .with_span(*args[0].span()),
+                        );
+                    }
+                    element
+                }
+            }
+            "zip" => {
+                let left = self.infer_iteration_type(&args[0])?;
+                let right = self.infer_iteration_type(&args[1])?;
+                TypeInfo::Tuple(vec![left, right])
+            }
+            _ => {
+                let element = self.infer_iteration_type(&args[0])?;
+                let count = self.infer_expr_type(&args[1])?;
+                if !count.is_compatible_with(&TypeInfo::I64) {
+                    self.errors.push(
+                        TypeError::new(format!(
+                            "`take` count must be int, got {}",
+                            count.display_name()
+                        ))
+                        .with_span(*args[1].span()),
+                    );
+                }
+                element
+            }
+        };
+        Ok(TypeInfo::Generic {
+            base: "Iterator".to_string(),
+            args: vec![element],
+        })
+    }
+
+    /// Whether `key_type` is a struct or enum, reporting one that lacks the `eq` method or the
+    /// `hash` method returning int that dict keys need
+    fn check_struct_dict_key(&mut self, key_type: &TypeInfo, span: Span) -> bool {
+        let (kind, name) = match self
+            .context
+            .normalize_type(key_type.resolve_alias().clone())
+        {
+            TypeInfo::Struct { name, .. } => ("struct", name),
+            TypeInfo::Enum { name, .. } => ("enum", name),
+            _ => return false,
+        };
+        let missing: Vec<&str> = [("eq", TypeInfo::Bool), ("hash", TypeInfo::I64)]
+            .into_iter()
+            .filter(|(method, result)| {
+                !matches!(
+                    self.context.get_function(&format!("{}.{}", name, method)),
+                    Some(TypeInfo::Function { return_type, .. })
+                        if return_type.is_compatible_with(result)
+                )
+            })
+            .map(|(method, _)| method)
+            .collect();
+        let missing = match missing.as_slice() {
+            [] => return true,
+            ["eq"] => "an `eq` method",
+            ["hash"] => "a `hash` method",
+            _ => "`eq` and `hash` methods",
+        };
+        self.errors.push(
+            TypeError::new(format!(
+                "{} `{}` cannot be used as a dict key without {}",
+                kind, name, missing
+            ))
+            .with_hint(format!(
+                "Add `@derive(Eq, Hash)` to `{}`, or define `fn eq(self, other: {}) -> bool` and `fn hash(self) -> int`; keys that are equal must hash the same",
+                name, name
+            ))
+            .with_span(span),
+        );
+        true
+    }
+
+    /// Whether values of `key_type` can key a dict: strings, and structs and enums with `eq`
+    /// and `hash` methods. Other types are reported, since the runtime would turn them into
+    /// strings and `m[1]` would alias `m["1"]`.
+    fn check_dict_key(&mut self, key_type: &TypeInfo, span: Span) -> bool {
+        if key_type.is_compatible_with(&TypeInfo::Str)
+            || matches!(key_type, TypeInfo::Error)
+            || self.check_struct_dict_key(key_type, span)
+        {
+            return true;
+        }
+        self.errors.push(
+            TypeError::new(format!(
+                "dictionary keys must be str, got {}",
+                key_type.display_name()
+            ))
+            .with_span(span)
+            .with_hint(
+                "Only strings, and structs and enums with `eq` and `hash` methods, can be dictionary keys"
+                    .to_string(),
+            ),
+        );
+        false
+    }
+
+    /// Element type produced by `object[index]`, reporting mismatched index types.
+    fn index_element_type(
+        &mut self,
+        object_type: &TypeInfo,
+        index_type: &TypeInfo,
+        span: &Span,
+    ) -> TypeInfo {
+        let (expected_index, element) = match object_type.resolve_alias() {
+            TypeInfo::List(elem) => (TypeInfo::I64, elem.as_ref().clone()),
+            TypeInfo::Str => (TypeInfo::I64, TypeInfo::Str),
+            TypeInfo::Dict { key, value } => {
+                // An empty `{}` has no key type yet, so the index decides it
+                let key_type = if Self::is_unknown_like(key) {
+                    index_type
+                } else {
+                    key.as_ref()
+                };
+                if !self.check_dict_key(key_type, *span) {
+                    return TypeInfo::Error;
+                }
+                (key.as_ref().clone(), value.as_ref().clone())
+            }
+            TypeInfo::Error => return TypeInfo::Error,
+            ty if Self::is_unknown_like(ty) => return TypeInfo::Unknown,
+            _ => {
+                self.errors.push(
+                    TypeError::new(format!(
+                        "cannot index into type {}",
+                        object_type.display_name()
+                    ))
+                    .with_hint("Only lists, dicts and strings support indexing".to_string())
+                    .with_span(*span),
+                );
+                return TypeInfo::Error;
+            }
+        };
+
+        let index_ok = match &expected_index {
+            TypeInfo::I64 => index_type.is_integer(),
+            expected => index_type.is_compatible_with(expected),
+        };
+        if !index_ok && !Self::is_unknown_like(index_type) && !matches!(index_type, TypeInfo::Error)
+        {
+            self.errors.push(
+                TypeError::new(format!(
+                    "cannot index {} with {}",
+                    object_type.display_name(),
+                    index_type.display_name()
+                ))
+                .with_hint(format!(
+                    "Expected an index of type `{}`",
+                    expected_index.display_name()
+                ))
+                .with_span(*span),
+            );
+        }
+        element
+    }
+
+    /// Checks the body of a loop with `label` pushed as the target of `break` and `continue`.
+    /// Returns the type the body's `break`s leave a `loop:` expression with.
+    fn check_loop_body(
+        &mut self,
+        label: Option<&str>,
+        is_expr: bool,
+        body: &Node<Block>,
+    ) -> Result<Option<TypeInfo>> {
+        self.loops.push(LoopFrame {
+            label: label.map(str::to_string),
+            is_expr,
+            break_type: None,
+        });
+        let checked = self.check_block(body);
+        let frame = self.loops.pop();
+        checked?;
+        Ok(frame.and_then(|frame| frame.break_type))
+    }
+
+    /// Index in `self.loops` of the loop a `break` or `continue` leaves, reporting a
+    /// statement outside any loop or a label no enclosing loop declares
+    fn resolve_loop(&mut self, label: Option<&str>, keyword: &str, span: Span) -> Option<usize> {
+        let index = match label {
+            Some(label) => self
+                .loops
+                .iter()
+                .rposition(|frame| frame.label.as_deref() == Some(label)),
+            None => self.loops.len().checked_sub(1),
+        };
+        if index.is_none() {
+            let error = match label {
+                Some(label) => TypeError::new(format!("no enclosing loop is labelled `{}`", label))
+                    .with_hint(format!(
+                        "Label the loop to leave with `{}: for ...`, `{}: while ...` or `{}: loop:`",
+                        label, label, label
+                    )),
+                None => TypeError::new(format!("`{}` outside of a loop", keyword)),
+            };
+            self.errors.push(error.with_span(span));
+        }
+        index
+    }
+
+    fn check_break(
+        &mut self,
+        label: Option<&str>,
+        value: Option<&Node<Expr>>,
+        span: Span,
+    ) -> Result<()> {
+        let Some(index) = self.resolve_loop(label, "break", span) else {
+            if let Some(value) = value {
+                self.infer_expr_type(value)?;
+            }
+            return Ok(());
+        };
+        if !self.loops[index].is_expr {
+            if let Some(value) = value {
+                self.infer_expr_type(value)?;
+                self.errors.push(
+                    TypeError::new(
+                        "`break` with a value is only allowed inside `loop`".to_string(),
+                    )
+                    .with_span(span)
+                    .with_hint(
+                        "`for` and `while` loops have no result; use `loop:` to compute one"
+                            .to_string(),
+                    ),
+                );
+            }
+            return Ok(());
+        }
+
+        // A bare `break` leaves a `loop:` expression with unit
+        let expected = self.loops[index].break_type.clone();
+        let value_type = match (value, &expected) {
+            (Some(value), Some(expected)) => self.infer_expr_type_expecting(value, expected)?,
+            (Some(value), None) => self.infer_expr_type(value)?,
+            (None, _) => TypeInfo::Unit,
+        };
+        match expected {
+            None => self.loops[index].break_type = Some(value_type),
+            Some(expected)
+                if !value_type.is_compatible_with(&expected)
+                    && !matches!(value_type, TypeInfo::Error)
+                    && !matches!(expected, TypeInfo::Error) =>
+            {
+                self.errors.push(
+                    TypeError::new(format!(
+                        "`break` leaves the loop with {}, but an earlier `break` gave {}",
+                        value_type.display_name(),
+                        expected.display_name()
+                    ))
+                    .with_span(span),
+                );
+            }
+            Some(_) => {}
+        }
+        Ok(())
+    }
+
+    /// Why a `with` statement cannot release `resource`, or `None` when it can: a struct
+    /// needs an `exit` or `close` method, and a runtime handle has to be created in the header
+    /// by a function with a companion `<name>.exit`, because a variable or a helper function
+    /// does not tell which runtime function made the handle
+    fn missing_with_cleanup(&self, header: &Node<Expr>, resource: &TypeInfo) -> Option<String> {
+        match resource {
+            TypeInfo::Struct { name, .. } => {
+                let releasable = ["exit", "close"].iter().any(|method| {
+                    self.context
+                        .get_function(&format!("{}.{}", name, method))
+                        .is_some()
+                });
+                (!releasable).then(|| format!("add `fn close(self)` to `{}`", name))
+            }
+            TypeInfo::Error | TypeInfo::Unknown => None,
+            _ => {
+                let constructor = match header.as_ref() {
+                    Expr::Call { func, .. } => match func.as_ref().as_ref() {
+                        Expr::Identifier(name) => Some(name.clone()),
+                        Expr::Member { object, field } => {
+                            Some(self.build_member_path(object, field))
+                        }
+                        _ => None,
+                    },
+                    _ => None,
+                };
+                match (header.as_ref(), constructor) {
+                    (_, Some(constructor)) => {
+                        let registry = self.registry?;
+                        if registry.contains(&format!("{}.exit", constructor)) {
+                            return None;
+                        }
+                        Some(if registry.contains(&constructor) {
+                            format!("`{}` creates nothing that `with` can release", constructor)
+                        } else {
+                            format!(
+                                "`with` cannot see which runtime function `{}` used; create the handle in the header, e.g. `with net.dial(addr) as conn:`, or return a struct with a `close` method",
+                                constructor
+                            )
+                        })
+                    }
+                    (Expr::Identifier(name), None) => Some(format!(
+                        "`with` cannot see which runtime function created `{}`; create it in the header instead, e.g. `with net.dial(addr) as {}:`",
+                        name, name
+                    )),
+                    _ => Some(
+                        "runtime handles are released when created in the `with` header, e.g. `with net.dial(addr) as conn:`"
+                            .to_string(),
+                    ),
+                }
+            }
+        }
+    }
+
     fn build_member_path(&self, object: &Node<Expr>, field: &str) -> String {
         match object.as_ref() {
             Expr::Identifier(name) => {
@@ -2959,25 +6633,183 @@ impl TypeChecker {
         }
     }
 
+    /// The struct or enum named by `object` when it is used as the receiver of an
+    /// associated function call like `Point.origin()`, rather than a value
+    fn type_receiver<'e>(&self, object: &'e Node<Expr>) -> Option<&'e str> {
+        let Expr::Identifier(name) = object.as_ref() else {
+            return None;
+        };
+        if self.context.get_variable(name).is_some() {
+            return None;
+        }
+        (self.context.get_struct(name).is_some() || self.context.get_enum(name).is_some())
+            .then_some(name.as_str())
+    }
+
+    /// Whether the method `Type.method` was declared without a `self` receiver
+    fn is_associated_function(&self, method_name: &str) -> bool {
+        self.param_names
+            .get(method_name)
+            .is_none_or(|names| names.first().is_none_or(|name| name != "self"))
+    }
+
+    fn resolve_associated_function(
+        &mut self,
+        type_name: &str,
+        field: &str,
+        span: &Span,
+    ) -> TypeInfo {
+        let method_name = format!("{}.{}", type_name, field);
+        let Some(signature) = self.context.get_function(&method_name).cloned() else {
+            self.errors.push(
+                TypeError::new(format!(
+                    "'{}' has no associated function '{}'",
+                    type_name, field
+                ))
+                .with_hint(format!(
+                    "Declare it in `{}` as `static fn {}(...)`",
+                    type_name, field
+                ))
+                .with_span(*span),
+            );
+            return TypeInfo::Error;
+        };
+        if !self.is_associated_function(&method_name) {
+            self.errors.push(
+                TypeError::new(format!(
+                    "method '{}' of '{}' takes `self` and cannot be called on the type",
+                    field, type_name
+                ))
+                .with_hint(format!(
+                    "Call it on a value, `value.{}(...)`, or declare it `static fn`",
+                    field
+                ))
+                .with_span(*span),
+            );
+            return TypeInfo::Error;
+        }
+        signature
+    }
+
+    /// The closure type of `object.field` when `object` is a struct with a function-typed
+    /// field of that name and no method that shadows it
+    fn function_field(&mut self, object: &Node<Expr>, field: &str) -> Option<TypeInfo> {
+        let Ok(TypeInfo::Struct { name, fields }) = self.infer_expr_type(object) else {
+            return None;
+        };
+        if self
+            .context
+            .get_function(&format!("{}.{}", name, field))
+            .is_some()
+        {
+            return None;
+        }
+        fields
+            .get(field)
+            .filter(|ty| matches!(ty, TypeInfo::Function { .. }))
+            .cloned()
+    }
+
     fn resolve_member_function(
         &mut self,
         object: &Node<Expr>,
         field: &str,
         span: &Span,
     ) -> TypeInfo {
+        if let Some(type_name) = self.type_receiver(object) {
+            return self.resolve_associated_function(type_name, field, span);
+        }
         match self.infer_expr_type(object) {
-            Ok(TypeInfo::Struct { name, .. }) => {
+            Ok(TypeInfo::Struct { name, .. }) | Ok(TypeInfo::Enum { name, .. }) => {
                 let method_name = format!("{}.{}", name, field);
-                self.context
-                    .get_function(&method_name)
-                    .cloned()
-                    .unwrap_or_else(|| {
+                let kind = if self.context.get_struct(&name).is_some() {
+                    "struct"
+                } else {
+                    "enum"
+                };
+                match self.context.get_function(&method_name).cloned() {
+                    Some(_) if self.is_associated_function(&method_name) => {
                         self.errors.push(
-                            TypeError::new(format!("struct '{}' has no method '{}'", name, field))
-                                .with_span(*span),
+                            TypeError::new(format!(
+                                "'{}' is an associated function of '{}' and takes no `self`",
+                                field, name
+                            ))
+                            .with_hint(format!("Call it on the type: `{}.{}(...)`", name, field))
+                            .with_span(*span),
                         );
                         TypeInfo::Error
-                    })
+                    }
+                    Some(signature) => signature,
+                    None => {
+                        self.errors.push(
+                            TypeError::new(format!(
+                                "{} '{}' has no method '{}'",
+                                kind, name, field
+                            ))
+                            .with_span(*span),
+                        );
+                        TypeInfo::Error
+                    }
+                }
+            }
+            Ok(TypeInfo::Str) if let Some(signature) = self.string_method_signature(field) => {
+                signature
+            }
+            Ok(TypeInfo::Str) if self.registry.is_some() => {
+                self.errors.push(
+                    TypeError::new(format!("string has no method '{}'", field))
+                        .with_hint(
+                            "String methods are the functions of the `string` module".to_string(),
+                        )
+                        .with_span(*span),
+                );
+                TypeInfo::Error
+            }
+            Ok(receiver @ TypeInfo::Dyn(_)) => {
+                let TypeInfo::Dyn(trait_name) = &receiver else {
+                    return TypeInfo::Error;
+                };
+                match self.trait_method_signature(trait_name, field, &receiver) {
+                    Some(sig) => sig,
+                    None => {
+                        self.errors.push(
+                            TypeError::new(format!(
+                                "trait '{}' has no method '{}'",
+                                trait_name, field
+                            ))
+                            .with_span(*span),
+                        );
+                        TypeInfo::Error
+                    }
+                }
+            }
+            Ok(receiver @ TypeInfo::Generic { .. }) if receiver.is_generic_param() => {
+                let TypeInfo::Generic { base, .. } = &receiver else {
+                    return TypeInfo::Error;
+                };
+                let traits = self
+                    .generic_bounds
+                    .iter()
+                    .find(|(generic, _)| generic == base)
+                    .map(|(_, traits)| traits.clone())
+                    .unwrap_or_default();
+                if let Some(sig) = traits.iter().find_map(|trait_name| {
+                    self.trait_method_signature(trait_name, field, &receiver)
+                }) {
+                    return sig;
+                }
+                self.errors.push(
+                    TypeError::new(format!(
+                        "no method '{}' found for generic parameter '{}'",
+                        field, base
+                    ))
+                    .with_hint(format!(
+                        "Add a trait bound that provides `{}`, e.g. `<{}: SomeTrait>`",
+                        field, base
+                    ))
+                    .with_span(*span),
+                );
+                TypeInfo::Error
             }
             _ => TypeInfo::Function {
                 params: vec![],
@@ -2986,6 +6818,42 @@ impl TypeChecker {
             },
         }
     }
+
+    /// Signature of the `string` runtime function that `s.method(...)` calls, without the
+    /// string itself
+    fn string_method_signature(&self, method: &str) -> Option<TypeInfo> {
+        let symbol = self.registry?.resolve(&format!("string.{}", method))?;
+        let params = ffi_params_to_typeinfo(&symbol.signature);
+        let (_, params) = params.split_first()?;
+        Some(TypeInfo::Function {
+            params: params.to_vec(),
+            param_defaults: vec![false; params.len()],
+            return_type: Box::new(string_result_type(&symbol.signature.result)),
+        })
+    }
+
+    /// Signature of a trait method as seen through `receiver`, without the receiver itself
+    fn trait_method_signature(
+        &self,
+        trait_name: &str,
+        method: &str,
+        receiver: &TypeInfo,
+    ) -> Option<TypeInfo> {
+        let sig = self.context.get_trait(trait_name)?.signatures.get(method)?;
+        let subst = HashMap::from([("Self".to_string(), receiver.clone())]);
+        match sig.substitute(&subst) {
+            TypeInfo::Function {
+                params,
+                param_defaults,
+                return_type,
+            } => Some(TypeInfo::Function {
+                params: params.into_iter().skip(1).collect(),
+                param_defaults: param_defaults.into_iter().skip(1).collect(),
+                return_type,
+            }),
+            _ => None,
+        }
+    }
 }
 
 impl Default for TypeChecker {
@@ -2994,13 +6862,37 @@ impl Default for TypeChecker {
     }
 }
 
+/// Parameter types of a runtime function; a variadic tail is a list of its element type
+fn ffi_params_to_typeinfo(signature: &FfiSignature) -> Vec<TypeInfo> {
+    let mut params: Vec<TypeInfo> = signature.params.iter().map(ffi_type_to_typeinfo).collect();
+    if let (Some(element), Some(tail)) = (&signature.variadic, params.last_mut()) {
+        *tail = TypeInfo::List(Box::new(ffi_type_to_typeinfo(element)));
+    }
+    params
+}
+
+/// Result type of a function of the `string` runtime module, whose lists hold strings
+fn string_result_type(result: &FfiType) -> TypeInfo {
+    match result {
+        FfiType::List => TypeInfo::List(Box::new(TypeInfo::Str)),
+        other => ffi_type_to_typeinfo(other),
+    }
+}
+
 fn ffi_type_to_typeinfo(ft: &FfiType) -> TypeInfo {
     match ft {
         FfiType::Unit => TypeInfo::Unit,
         FfiType::Bool => TypeInfo::Bool,
+        FfiType::I8 => TypeInfo::I8,
+        FfiType::I16 => TypeInfo::I16,
         FfiType::I32 => TypeInfo::I32,
         // Opaque handles are i64 at type level
         FfiType::I64 | FfiType::Opaque => TypeInfo::I64,
+        FfiType::U8 => TypeInfo::U8,
+        FfiType::U16 => TypeInfo::U16,
+        FfiType::U32 => TypeInfo::U32,
+        FfiType::U64 => TypeInfo::U64,
+        FfiType::F32 => TypeInfo::F32,
         FfiType::F64 => TypeInfo::F64,
         FfiType::Str => TypeInfo::Str,
         FfiType::List => TypeInfo::List(Box::new(TypeInfo::Unknown)),
@@ -3019,6 +6911,39 @@ mod tests {
     use otterc_span::Span;
     use std::f64;
 
+    /// Checks `source` as a whole program and returns the messages of the reported errors
+    fn check_source(source: &str) -> Vec<String> {
+        check_source_with(source, None)
+            .into_iter()
+            .map(|error| error.message)
+            .collect()
+    }
+
+    /// Errors of `source`, checked against the runtime functions of `registry` if given
+    fn check_source_with(
+        source: &str,
+        registry: Option<&'static SymbolRegistry>,
+    ) -> Vec<TypeError> {
+        let tokens = otterc_lexer::tokenize(source).expect("tokenize source");
+        let program = otterc_parser::parse(&tokens).expect("parse source");
+        let mut checker = TypeChecker::new();
+        if let Some(registry) = registry {
+            checker = checker.with_registry(registry);
+        }
+        let _ = checker.check_program(&program);
+        checker.errors().to_vec()
+    }
+
+    /// A registry holding `functions`, with `module` imported
+    fn registry_with(module: &str, functions: Vec<FfiFunction>) -> &'static SymbolRegistry {
+        let registry: &'static SymbolRegistry = Box::leak(Box::new(SymbolRegistry::new()));
+        for function in functions {
+            registry.register(function);
+        }
+        registry.mark_module_active(module);
+        registry
+    }
+
     #[test]
     fn test_type_inference_literal() {
         let mut checker = TypeChecker::new();
@@ -3071,4 +6996,481 @@ mod tests {
         let ty = checker.infer_expr_type(&expr).unwrap();
         assert_eq!(ty, TypeInfo::F64);
     }
+
+    #[test]
+    fn typed_integer_literals_are_range_checked() {
+        let literal = |value: i128| {
+            Node::new(
+                Expr::Literal(Node::new(
+                    Literal::Number(NumberLiteral::from_int(value)),
+                    Span::new(0, 0),
+                )),
+                Span::new(0, 0),
+            )
+        };
+
+        let mut checker = TypeChecker::new();
+        let ty = checker
+            .infer_expr_type_expecting(&literal(2_147_483_647), &TypeInfo::I32)
+            .unwrap();
+        assert_eq!(ty, TypeInfo::I32);
+        assert!(checker.errors().is_empty());
+
+        checker
+            .infer_expr_type_expecting(&literal(2_147_483_648), &TypeInfo::I32)
+            .unwrap();
+        assert_eq!(checker.errors().len(), 1);
+
+        let ty = checker.infer_expr_type(&literal(1 << 63)).unwrap();
+        assert_eq!(ty, TypeInfo::I64);
+        assert_eq!(checker.errors().len(), 2);
+    }
+
+    #[test]
+    fn mixed_numeric_operands_widen_without_loss() {
+        assert_eq!(
+            TypeInfo::I8.common_numeric_type(&TypeInfo::I32),
+            Some(TypeInfo::I32)
+        );
+        assert_eq!(
+            TypeInfo::U8.common_numeric_type(&TypeInfo::I16),
+            Some(TypeInfo::I16)
+        );
+        assert_eq!(
+            TypeInfo::U16.common_numeric_type(&TypeInfo::F32),
+            Some(TypeInfo::F32)
+        );
+        assert_eq!(
+            TypeInfo::I32.common_numeric_type(&TypeInfo::F32),
+            Some(TypeInfo::F64)
+        );
+        assert_eq!(TypeInfo::U32.common_numeric_type(&TypeInfo::I32), None);
+        assert_eq!(TypeInfo::I64.common_numeric_type(&TypeInfo::U64), None);
+        assert_eq!(
+            TypeInfo::U64.integer_bounds(),
+            Some((0, i128::from(u64::MAX)))
+        );
+    }
+
+    #[test]
+    fn byte_strings_are_lists_of_u8() {
+        let errors = check_source("fn main():\n    let bytes: list<u8> = b\"hi\"\n");
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+
+        let errors = check_source("fn main():\n    let first: str = b\"hi\"[0]\n");
+        assert_eq!(errors, ["type mismatch: expected str, got u8"]);
+    }
+
+    #[test]
+    fn compound_assignment_checks_the_operator_result() {
+        let errors = check_source(
+            "fn main():\n    let xs = [1, 2]\n    xs[0] += 2\n    let s = \"a\"\n    s += \"b\"\n",
+        );
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+
+        let errors = check_source("fn main():\n    let x = 1\n    x += 1.5\n");
+        assert_eq!(errors, ["cannot assign f64 to x (expected i64)"]);
+    }
+
+    #[test]
+    fn closures_take_parameter_types_from_the_expected_function_type() {
+        let errors = check_source(
+            "fn apply(f: fn(int) -> int, x: int) -> int:\n    return f(x)\n\nfn main():\n    let inc: fn(int) -> int = fn(x) => x + 1\n    let n: int = apply(fn(x) => x * 2, inc(1))\n    let bad: fn(int) -> int = fn(x):\n        let s: str = x\n        return 0\n",
+        );
+        assert_eq!(errors, ["type mismatch: expected str, got i64"]);
+    }
+
+    #[test]
+    fn closure_return_types_come_from_every_return() {
+        let errors = check_source(
+            "fn main():\n    let sign = fn(x: int):\n        if x > 0:\n            return \"positive\"\n        else:\n            for i in [1]:\n                return \"other\"\n        return \"zero\"\n    let s: str = sign(1)\n    let log = fn(x: int):\n        let y = x\n    let u: int = log(1)\n",
+        );
+        assert_eq!(errors, ["type mismatch: expected i64, got None"]);
+
+        let errors = check_source(
+            "fn main():\n    let f = fn(x: int):\n        if x > 0:\n            return \"a\"\n        else:\n            return 1\n    let g = fn(x: int):\n        if x > 0:\n            return\n        return x\n",
+        );
+        assert_eq!(
+            errors,
+            [
+                "return type mismatch: expected str, got i64",
+                "return type mismatch: expected None, got i64",
+            ]
+        );
+    }
+
+    #[test]
+    fn dict_keys_must_be_strings_or_keyable_structs() {
+        let errors = check_source(
+            "fn main():\n    let m = {}\n    m[1] = 2\n    let n = {\"a\": 1}\n    n[\"b\"] = 2\n",
+        );
+        assert_eq!(errors, ["dictionary keys must be str, got i64"]);
+
+        let errors = check_source("fn main():\n    let m = {1: \"one\"}\n");
+        assert_eq!(errors, ["dictionary keys must be str, got i64"]);
+    }
+
+    #[test]
+    fn comparisons_and_sort_need_an_lt_method_taking_one_operand() {
+        let point = "struct Point:\n    x: int\n\n";
+        let errors = check_source(&format!(
+            "{}fn main():\n    let smaller = Point(x=1) < Point(x=2)\n    sort([Point(x=2), Point(x=1)])\n",
+            point
+        ));
+        assert_eq!(
+            errors,
+            [
+                "`<` is not defined for Point: struct `Point` has no `lt` method",
+                "cannot sort list<Point>: struct `Point` has no `lt` method",
+            ]
+        );
+
+        let errors = check_source(&format!(
+            "{}    fn lt(self) -> bool:\n        return self.x < 0\n\nfn main():\n    let smaller = Point(x=1) < Point(x=2)\n    sort([Point(x=2), Point(x=1)])\n",
+            point
+        ));
+        assert_eq!(
+            errors,
+            [
+                "operator method `Point.lt` must take `self` and one other parameter",
+                "cannot sort list<Point>: struct `Point` has no `lt` method",
+            ]
+        );
+    }
+
+    #[test]
+    fn iterating_a_dict_with_struct_keys_yields_the_keys() {
+        let errors = check_source(
+            "struct Point:\n    x: int\n\n    fn eq(self, other: Point) -> bool:\n        return self.x == other.x\n\n    fn hash(self) -> int:\n        return self.x\n\nfn main():\n    let names = {Point(x=1): \"one\"}\n    for point in names:\n        let x: int = point.x\n        let s: str = point\n",
+        );
+        assert_eq!(
+            errors,
+            ["type mismatch: expected str, got Point { x: i64 }"]
+        );
+    }
+
+    #[test]
+    fn derived_methods_back_operators_keys_and_printing() {
+        let errors = check_source(
+            "@derive(Eq, Ord, Hash, Debug, Clone)\nenum Suit:\n    Hearts\n    Spades\n\n@derive(Eq, Ord, Hash, Debug, Clone)\nstruct Card:\n    rank: int\n    suit: Suit\n\nfn main():\n    let card = Card(rank=1, suit=Suit.Hearts)\n    let same: bool = card == card.clone() and Suit.Hearts < Suit.Spades\n    sort([card, card])\n    sort([Suit.Spades, Suit.Hearts])\n    let counts = {card: 1, card.clone(): 2}\n    let suits = {Suit.Hearts: \"h\"}\n    let h: int = hash(card) + card.hash()\n    let text: str = f\"{card} {Suit.Spades}\" + str(card)\n",
+        );
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+    }
+
+    #[test]
+    fn derives_must_not_redefine_methods() {
+        let errors = check_source(
+            "@derive(Eq, Display, Eq)\nstruct Point:\n    x: int\n\n    fn eq(self, other: Point) -> bool:\n        return true\n",
+        );
+        assert_eq!(
+            errors,
+            [
+                "cannot derive `Eq`: `Point` already defines `eq`",
+                "unknown derive `Display`; expected one of Eq, Ord, Hash, Debug, Clone",
+                "`Eq` is derived more than once",
+            ]
+        );
+
+        // Methods from `impl` blocks count as well, wherever the block is
+        let errors = check_source(
+            "@derive(Eq, Hash)\nstruct Point:\n    x: int\n\n@derive(Debug)\nenum Suit:\n    Hearts\n\nimpl Suit:\n    fn str(self) -> str:\n        return \"h\"\n\nimpl Point:\n    fn hash(self) -> int:\n        return self.x\n",
+        );
+        assert_eq!(
+            errors,
+            [
+                "cannot derive `Hash`: `Point` already defines `hash`",
+                "cannot derive `Debug`: `Suit` already defines `str`",
+            ]
+        );
+    }
+
+    #[test]
+    fn derived_fields_must_support_the_operation() {
+        let errors = check_source(
+            "struct Plain:\n    x: int\n\n@derive(Eq, Ord, Hash, Debug, Clone)\nstruct Tagged:\n    plain: Plain\n    tags: list<str>\n    on: bool\n\n@derive(Ord)\nenum Shape:\n    Dot\n    Poly: (list<int>)\n\n@derive(Eq)\nstruct Box<T>:\n    item: T\n",
+        );
+        assert_eq!(
+            errors,
+            [
+                "cannot derive methods for generic struct `Box`",
+                "cannot derive `Eq` for `Tagged`: field `plain` has type `Plain`, which has no `eq` method",
+                "cannot derive `Eq` for `Tagged`: field `tags` has type `list<str>`, which cannot be compared with `==`",
+                "cannot derive `Ord` for `Tagged`: field `plain` has type `Plain`, which has no `lt` method",
+                "cannot derive `Ord` for `Tagged`: field `tags` has type `list<str>`, which cannot be compared with `<`",
+                "cannot derive `Ord` for `Tagged`: field `on` has type `bool`, which cannot be compared with `<`",
+                "cannot derive `Hash` for `Tagged`: field `plain` has type `Plain`, which has no `hash` method",
+                "cannot derive `Hash` for `Tagged`: field `tags` has type `list<str>`, which cannot be hashed",
+                "cannot derive `Debug` for `Tagged`: field `plain` has type `Plain`, which has no `str` method",
+                "cannot derive `Ord` for `Shape`: `Shape.Poly` payload has type `list<int>`, which cannot be compared with `<`",
+            ]
+        );
+    }
+
+    #[test]
+    fn variadic_parameters_collect_and_spread_arguments() {
+        let log = "fn log(prefix: str, *values: int) -> int:\n    return len(values)\n\n";
+        let errors = check_source(&format!(
+            "{}fn main():\n    let xs = [1, 2]\n    log(\"a\")\n    log(\"a\", 1, 2)\n    log(\"a\", *xs)\n",
+            log
+        ));
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+
+        let errors = check_source(&format!(
+            "{}fn main():\n    log(\"a\", 1, \"b\")\n    log(\"a\", *[\"b\"])\n    log(*[\"a\"])\n",
+            log
+        ));
+        assert_eq!(
+            errors,
+            [
+                "argument 3 type mismatch: expected i64, got str",
+                "argument 2 type mismatch: expected list<i64>, got list<str>",
+                "a spread argument can only fill the variadic parameter `values` of `log`",
+            ]
+        );
+    }
+
+    #[test]
+    fn runtime_functions_can_declare_a_variadic_tail() {
+        let registry = registry_with(
+            "fmt",
+            vec![FfiFunction {
+                name: "fmt.join".into(),
+                symbol: "otter_fmt_join".into(),
+                signature: FfiSignature::variadic(vec![FfiType::Str], FfiType::Str, FfiType::Str),
+            }],
+        );
+        let check = |body: &str| {
+            check_source_with(&format!("use fmt\n\nfn main():\n{}", body), Some(registry))
+                .into_iter()
+                .map(|error| error.message)
+                .collect::<Vec<_>>()
+        };
+
+        let errors = check(
+            "    let parts = [\"b\", \"c\"]\n    let a: str = fmt.join(\",\")\n    let b: str = fmt.join(\",\", \"a\", \"b\")\n    let c: str = fmt.join(\",\", *parts)\n",
+        );
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+
+        let errors = check("    fmt.join(\",\", \"a\", 2)\n    fmt.join(\",\", *[1])\n");
+        assert_eq!(
+            errors,
+            [
+                "argument 3 type mismatch: expected str, got i64",
+                "argument 2 type mismatch: expected list<str>, got list<i64>",
+            ]
+        );
+    }
+
+    #[test]
+    fn struct_iterators_yield_the_payload_of_next() {
+        let countdown = "enum Option<T>:\n    Some: (T)\n    None\n\nstruct Countdown:\n    remaining: int\n\n    fn next(self) -> Option<int>:\n        return Option.None\n\n";
+        let errors = check_source(&format!(
+            "{}fn main():\n    for n in Countdown(remaining=3):\n        let s: str = n\n",
+            countdown
+        ));
+        assert_eq!(errors, ["type mismatch: expected str, got i64"]);
+
+        let errors = check_source(&format!(
+            "{}fn main():\n    let doubled = [n * 2 for n in Countdown(remaining=3)]\n    let first: int = doubled[0]\n",
+            countdown
+        ));
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+
+        let errors = check_source(
+            "struct Point:\n    x: int\n\nfn main():\n    for p in Point(x=1):\n        pass\n",
+        );
+        assert_eq!(errors, ["cannot iterate over type Point { x: i64 }"]);
+    }
+
+    #[test]
+    fn with_rejects_handles_it_cannot_trace_to_their_constructor() {
+        let registry = registry_with(
+            "net",
+            ["net.dial", "net.dial.exit"]
+                .into_iter()
+                .map(|name| FfiFunction {
+                    name: name.into(),
+                    symbol: "otter_std_net_dial".into(),
+                    signature: FfiSignature::new(vec![FfiType::Str], FfiType::I64),
+                })
+                .collect(),
+        );
+        let check = |body: &str| {
+            let source = format!(
+                "use net\n\nfn open(addr: str) -> int:\n    return net.dial(addr)\n\nfn main():\n{}",
+                body
+            );
+            check_source_with(&source, Some(registry))
+                .into_iter()
+                .map(|error| (error.message, error.hint.unwrap_or_default()))
+                .collect::<Vec<_>>()
+        };
+
+        let errors = check("    with net.dial(\"a\") as conn:\n        pass\n");
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+
+        let errors = check("    let conn = net.dial(\"a\")\n    with conn:\n        pass\n");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].0,
+            "`with` needs a value with an `exit` or `close` method, found i64"
+        );
+        assert!(errors[0].1.contains("created `conn`"), "{}", errors[0].1);
+
+        let errors = check("    with open(\"a\") as conn:\n        pass\n");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].1.contains("`open` used"), "{}", errors[0].1);
+    }
+
+    #[test]
+    fn defer_call_rejects_values_that_are_not_functions() {
+        let errors = check_source(
+            "struct Conn:\n    open: bool\n\n    fn close(self):\n        pass\n\nfn main():\n    let conn = Conn(open=true)\n    defer (conn.close())\n    defer conn.close()\n    defer (1)\n",
+        );
+        let message = "`defer(...)` calls the runtime `defer` builtin, which takes a function";
+        assert_eq!(errors, [message, message]);
+    }
+
+    #[test]
+    fn keyword_arguments_report_unknown_repeated_and_missing_parameters() {
+        let errors = check_source(
+            "fn f(a: int, b: int = 2) -> int:\n    return a + b\n\nfn main():\n    f(1, b=3)\n    f(b=3, a=1)\n    f(1, a=2)\n    f(b=3)\n    f(1, c=3)\n",
+        );
+        assert_eq!(
+            errors,
+            [
+                "argument `a` given more than once in call to `f`",
+                "missing argument `a` in call to `f`",
+                "`f` has no parameter named `c`",
+            ]
+        );
+    }
+
+    #[test]
+    fn generic_calls_report_failed_instantiations_and_uninferable_parameters() {
+        let errors = check_source(
+            "fn twice<T>(x: T) -> T:\n    return x + x\n\nfn main():\n    twice(2)\n    twice(true)\n",
+        );
+        assert_eq!(
+            errors,
+            ["instantiation `twice<bool>` does not type-check: cannot apply Add to bool and bool"]
+        );
+
+        let errors = check_source(
+            "fn empty<T>() -> List<T>:\n    return []\n\nfn main():\n    let xs = empty()\n    let ys = empty<int>()\n",
+        );
+        assert_eq!(errors, ["cannot infer type parameter `T` of `empty`"]);
+    }
+
+    #[test]
+    fn loop_labels_and_break_values_are_checked() {
+        let errors = check_source(
+            "fn main():\n    outer: while true:\n        for x in [1]:\n            continue outer\n        continue inner\n",
+        );
+        assert_eq!(errors, ["no enclosing loop is labelled `inner`"]);
+
+        let errors = check_source(
+            "fn main():\n    for x in [1]:\n        break x\n    while true:\n        break 2\n",
+        );
+        assert_eq!(
+            errors,
+            [
+                "`break` with a value is only allowed inside `loop`",
+                "`break` with a value is only allowed inside `loop`",
+            ]
+        );
+
+        let errors = check_source(
+            "fn main():\n    let v = loop:\n        if true:\n            break 1\n        break \"a\"\n",
+        );
+        assert_eq!(
+            errors,
+            ["`break` leaves the loop with str, but an earlier `break` gave i64"]
+        );
+    }
+
+    #[test]
+    fn except_clauses_accept_runtime_exception_types() {
+        let errors = check_source(
+            "fn main():\n    try:\n        pass\n    except ValueError as message:\n        let n: int = message\n    except IndexError:\n        pass\n    except KeyError:\n        pass\n",
+        );
+        assert_eq!(
+            errors,
+            [
+                "type mismatch: expected i64, got str",
+                "unknown exception type `KeyError`"
+            ]
+        );
+    }
+
+    #[test]
+    fn formatting_requires_str_methods_inside_lists_and_tuples() {
+        let errors = check_source(
+            "struct P:\n    x: int\n\nstruct Q:\n    x: int\n\n    fn str(self) -> str:\n        return \"q\"\n\nfn main():\n    let a = str([P(x=1)])\n    let b = f\"{(1, [P(x=2)])}\"\n    let c = str(P(x=3))\n    let d = str([Q(x=1)])\n    let e = f\"{(Q(x=2), [Q(x=3)])}\"\n",
+        );
+        let message = "struct `P` cannot be formatted without a `str` method";
+        assert_eq!(errors, [message, message, message]);
+    }
+
+    #[test]
+    fn collection_literals_take_the_expected_element_types() {
+        let errors = check_source(
+            "fn main():\n    let xs: List<u8> = [1, 2]\n    let us: List<u64> = [18446744073709551615]\n    let d: Dict<str, u8> = {\"a\": 200}\n    let t: (u8, List<i8>) = (255, [-128])\n    let any: list = [1, 2]\n",
+        );
+        assert!(errors.is_empty(), "{:?}", errors);
+
+        let errors = check_source(
+            "fn main():\n    let xs: List<u8> = [1, 256]\n    let ys: List<int> = [1, \"a\"]\n    let t: (u8, str) = (-1, \"a\")\n",
+        );
+        assert_eq!(
+            errors,
+            [
+                "integer literal is out of range for `u8`",
+                "array element 1 has incompatible type: expected i64, got str",
+                "integer literal is out of range for `u8`",
+            ]
+        );
+    }
+
+    #[test]
+    fn function_typed_fields_are_called_like_methods() {
+        let errors = check_source(
+            "struct Handler:\n    f: fn(int) -> int\n    name: str\n\nfn main():\n    let h = Handler(f=fn(x: int) => x + 1, name=\"h\")\n    let n: int = h.f(41)\n    h.f(\"a\")\n    h.name(1)\n",
+        );
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+        assert!(errors[0].contains("expected i64, got str"), "{:?}", errors);
+        assert_eq!(errors[1], "struct 'Handler' has no method 'name'");
+    }
+
+    #[test]
+    fn string_prefix_patterns_bind_the_rest_as_a_string() {
+        let errors = check_source(
+            "fn main():\n    let s = \"GET /\"\n    let path: str = match s:\n        case \"GET \" + rest:\n            rest\n        case _:\n            \"\"\n    let n = match 3:\n        case \"a\" + _:\n            1\n        case _:\n            0\n",
+        );
+        assert_eq!(
+            errors,
+            ["cannot match string prefix pattern against non-string type i64"]
+        );
+    }
+
+    #[test]
+    fn iterator_adapters_yield_typed_elements() {
+        let errors = check_source(
+            "fn evens(limit: int) -> Iterator<int>:\n    return filter(fn(n: int) => n % 2 == 0, 0..limit)\n\nfn main():\n    let words: List<str> = [w + \"!\" for w in map(fn(n: int) => str(n), evens(6))]\n    for (w, n) in zip([\"a\"], take(evens(4), 1)):\n        let s: str = w\n        let m: int = n\n",
+        );
+        assert!(errors.is_empty(), "{:?}", errors);
+
+        let errors = check_source(
+            "fn main():\n    let a = filter(fn(n: int) => n + 1, [1])\n    let b = map(fn(s: str) => s, [1])\n    let c = take([1], \"2\")\n    let d = zip([1])\n",
+        );
+        assert_eq!(
+            errors,
+            [
+                "`filter` predicate must return bool, got i64",
+                "`map` callback takes str, but the iterable yields i64",
+                "`take` count must be int, got str",
+                "`zip` expects 2 arguments, got 1",
+            ]
+        );
+    }
 }
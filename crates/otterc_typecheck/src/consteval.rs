@@ -0,0 +1,468 @@
+//! Compile-time evaluation of `const` declarations
+//!
+//! A constant's initializer may use literals, arithmetic, bitwise, comparison and logical
+//! operators, string concatenation and other constants. Constants can be declared in any
+//! order as long as their references do not form a cycle.
+
+use std::collections::{HashMap, HashSet};
+
+use otterc_ast::nodes::{
+    BinaryOp, Expr, Literal, Node, NumberLiteral, Statement, StringLiteral, Type, UnaryOp,
+};
+use otterc_span::Span;
+
+use crate::types::{TypeError, TypeInfo};
+
+/// Value of a constant, known before code generation
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConstValue {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Str(String),
+}
+
+impl ConstValue {
+    pub fn type_info(&self) -> TypeInfo {
+        match self {
+            ConstValue::Int(_) => TypeInfo::I64,
+            ConstValue::Float(_) => TypeInfo::F64,
+            ConstValue::Bool(_) => TypeInfo::Bool,
+            ConstValue::Str(_) => TypeInfo::Str,
+        }
+    }
+
+    /// The literal that a use of the constant stands for
+    pub fn to_literal(&self) -> Literal {
+        match self {
+            ConstValue::Int(value) => Literal::Number(NumberLiteral::from_int((*value).into())),
+            ConstValue::Float(value) => Literal::Number(NumberLiteral::new(*value, true)),
+            ConstValue::Bool(value) => Literal::Bool(*value),
+            ConstValue::Str(value) => Literal::String(StringLiteral::new(value.clone())),
+        }
+    }
+}
+
+/// Evaluate every `const` declaration in `statements`.
+///
+/// `declared_type` resolves the annotation of each constant. Values are checked against it,
+/// with ints widened to floats; a declared type of `Unknown` accepts any value unchanged.
+///
+/// Returns the values of the constants that could be evaluated, and an error for each
+/// declaration that could not. Uses of a failed constant are not reported again.
+pub fn evaluate_constants(
+    statements: &[Node<Statement>],
+    declared_type: impl Fn(&Node<Type>) -> TypeInfo,
+) -> (HashMap<String, ConstValue>, Vec<TypeError>) {
+    let mut evaluator = Evaluator {
+        declared_type: &declared_type,
+        declarations: HashMap::new(),
+        values: HashMap::new(),
+        in_progress: Vec::new(),
+        failed: HashSet::new(),
+        errors: Vec::new(),
+    };
+    let mut order = Vec::new();
+
+    for statement in statements {
+        if let Statement::Const { name, ty, expr, .. } = statement.as_ref() {
+            if evaluator.declarations.contains_key(name.as_str()) {
+                evaluator.errors.push(
+                    TypeError::new(format!("constant `{}` is declared more than once", name))
+                        .with_span(*statement.span()),
+                );
+                continue;
+            }
+            evaluator
+                .declarations
+                .insert(name.as_str(), Declaration { ty, expr });
+            order.push(name.as_str());
+        }
+    }
+
+    for name in order {
+        evaluator.constant(name, *evaluator.declarations[name].expr.span());
+    }
+
+    (evaluator.values, evaluator.errors)
+}
+
+struct Declaration<'a> {
+    ty: &'a Node<Type>,
+    expr: &'a Node<Expr>,
+}
+
+struct Evaluator<'a> {
+    declared_type: &'a dyn Fn(&Node<Type>) -> TypeInfo,
+    declarations: HashMap<&'a str, Declaration<'a>>,
+    values: HashMap<String, ConstValue>,
+    /// Constants whose initializer is being evaluated, used to detect cycles
+    in_progress: Vec<&'a str>,
+    /// Constants that already failed to evaluate
+    failed: HashSet<&'a str>,
+    errors: Vec<TypeError>,
+}
+
+impl<'a> Evaluator<'a> {
+    /// Value of the constant `name`, evaluating its initializer on first use
+    fn constant(&mut self, name: &'a str, use_span: Span) -> Option<ConstValue> {
+        if let Some(value) = self.values.get(name) {
+            return Some(value.clone());
+        }
+        if self.failed.contains(name) {
+            return None;
+        }
+        if self.in_progress.contains(&name) {
+            return self.error(
+                use_span,
+                format!("constant `{}` depends on itself", name),
+                Some("Break the cycle by spelling out one of the values"),
+            );
+        }
+
+        let (ty, expr) = {
+            let declaration = self.declarations.get(name)?;
+            (declaration.ty, declaration.expr)
+        };
+        self.in_progress.push(name);
+        let value = self
+            .eval(expr)
+            .and_then(|value| self.convert(name, ty, value, *expr.span()));
+        self.in_progress.pop();
+
+        match value {
+            Some(value) => {
+                self.values.insert(name.to_string(), value.clone());
+                Some(value)
+            }
+            None => {
+                self.failed.insert(name);
+                None
+            }
+        }
+    }
+
+    /// Check `value` against the declared type of the constant, widening ints to floats
+    fn convert(
+        &mut self,
+        name: &str,
+        ty: &Node<Type>,
+        value: ConstValue,
+        span: Span,
+    ) -> Option<ConstValue> {
+        let declared = (self.declared_type)(ty);
+        match (declared.resolve_alias(), value) {
+            (TypeInfo::Unknown, value)
+            | (TypeInfo::I64, value @ ConstValue::Int(_))
+            | (TypeInfo::F32 | TypeInfo::F64, value @ ConstValue::Float(_))
+            | (TypeInfo::Bool, value @ ConstValue::Bool(_))
+            | (TypeInfo::Str, value @ ConstValue::Str(_)) => Some(value),
+            (declared, ConstValue::Int(value))
+                if let Some((min, max)) = declared.integer_bounds() =>
+            {
+                if (min..=max).contains(&i128::from(value)) {
+                    Some(ConstValue::Int(value))
+                } else {
+                    self.error(
+                        span,
+                        format!(
+                            "value {} of constant `{}` does not fit in {}",
+                            value,
+                            name,
+                            declared.display_name()
+                        ),
+                        None,
+                    )
+                }
+            }
+            (TypeInfo::F32 | TypeInfo::F64, ConstValue::Int(value)) => {
+                Some(ConstValue::Float(value as f64))
+            }
+            (declared, value)
+                if declared.is_numeric() || matches!(declared, TypeInfo::Bool | TypeInfo::Str) =>
+            {
+                self.error(
+                    span,
+                    format!(
+                        "constant `{}` is declared as {} but its value is {}",
+                        name,
+                        declared.display_name(),
+                        value.type_info().display_name()
+                    ),
+                    None,
+                )
+            }
+            (other, _) => self.error(
+                *ty.span(),
+                format!(
+                    "constants must be int, float, bool or str, found {}",
+                    other.display_name()
+                ),
+                Some("Use `let` for values of other types"),
+            ),
+        }
+    }
+
+    fn eval(&mut self, expr: &'a Node<Expr>) -> Option<ConstValue> {
+        let span = *expr.span();
+        match expr.as_ref() {
+            Expr::Literal(literal) => match literal.as_ref() {
+                Literal::Number(number) if number.is_float_literal => {
+                    Some(ConstValue::Float(number.value))
+                }
+                Literal::Number(number) => match i64::try_from(number.int_value()) {
+                    Ok(value) => Some(ConstValue::Int(value)),
+                    Err(_) => self.error(
+                        span,
+                        "integer literal is out of range for `int`".to_string(),
+                        None,
+                    ),
+                },
+                Literal::String(value) => Some(ConstValue::Str(value.value.clone())),
+                Literal::Bool(value) => Some(ConstValue::Bool(*value)),
+                Literal::None | Literal::Unit => self.error(
+                    span,
+                    "constants cannot hold `None` or `()`".to_string(),
+                    None,
+                ),
+                Literal::Bytes(_) => self.error(
+                    span,
+                    "constants cannot hold byte strings".to_string(),
+                    Some("Use `let` for byte strings"),
+                ),
+            },
+            Expr::Identifier(name) => {
+                if self.declarations.contains_key(name.as_str()) {
+                    self.constant(name, span)
+                } else {
+                    self.error(
+                        span,
+                        format!("`{}` is not a constant", name),
+                        Some("Constant initializers can only refer to other constants"),
+                    )
+                }
+            }
+            Expr::Unary { op, expr } => {
+                let value = self.eval(expr)?;
+                self.unary(*op, value, span)
+            }
+            Expr::Binary { op, left, right } => {
+                let left = self.eval(left)?;
+                let right = self.eval(right)?;
+                self.binary(*op, left, right, span)
+            }
+            _ => self.error(
+                span,
+                "constant initializers can only use literals, operators and other constants"
+                    .to_string(),
+                Some("Use `let` for values computed at runtime"),
+            ),
+        }
+    }
+
+    fn unary(&mut self, op: UnaryOp, value: ConstValue, span: Span) -> Option<ConstValue> {
+        match (op, value) {
+            (UnaryOp::Neg, ConstValue::Int(value)) => match value.checked_neg() {
+                Some(result) => Some(ConstValue::Int(result)),
+                None => self.overflow(span),
+            },
+            (UnaryOp::Neg, ConstValue::Float(value)) => Some(ConstValue::Float(-value)),
+            (UnaryOp::Not, ConstValue::Bool(value)) => Some(ConstValue::Bool(!value)),
+            (UnaryOp::BitNot, ConstValue::Int(value)) => Some(ConstValue::Int(!value)),
+            (op, value) => self.error(
+                span,
+                format!(
+                    "cannot apply {:?} to {} in a constant expression",
+                    op,
+                    value.type_info().display_name()
+                ),
+                None,
+            ),
+        }
+    }
+
+    fn binary(
+        &mut self,
+        op: BinaryOp,
+        left: ConstValue,
+        right: ConstValue,
+        span: Span,
+    ) -> Option<ConstValue> {
+        match (left, right) {
+            (ConstValue::Int(left), ConstValue::Int(right)) => {
+                self.int_binary(op, left, right, span)
+            }
+            (ConstValue::Int(left), ConstValue::Float(right)) => {
+                self.float_binary(op, left as f64, right, span)
+            }
+            (ConstValue::Float(left), ConstValue::Int(right)) => {
+                self.float_binary(op, left, right as f64, span)
+            }
+            (ConstValue::Float(left), ConstValue::Float(right)) => {
+                self.float_binary(op, left, right, span)
+            }
+            (ConstValue::Str(left), ConstValue::Int(right)) if op == BinaryOp::Add => {
+                Some(ConstValue::Str(format!("{}{}", left, right)))
+            }
+            (ConstValue::Int(left), ConstValue::Str(right)) if op == BinaryOp::Add => {
+                Some(ConstValue::Str(format!("{}{}", left, right)))
+            }
+            (ConstValue::Str(left), ConstValue::Str(right)) => match op {
+                BinaryOp::Add => Some(ConstValue::Str(left + &right)),
+                _ => match compare(op, &left, &right) {
+                    Some(result) => Some(ConstValue::Bool(result)),
+                    None => self.unsupported(op, &TypeInfo::Str, &TypeInfo::Str, span),
+                },
+            },
+            (ConstValue::Bool(left), ConstValue::Bool(right)) => match op {
+                BinaryOp::And => Some(ConstValue::Bool(left && right)),
+                BinaryOp::Or => Some(ConstValue::Bool(left || right)),
+                BinaryOp::Eq => Some(ConstValue::Bool(left == right)),
+                BinaryOp::Ne => Some(ConstValue::Bool(left != right)),
+                _ => self.unsupported(op, &TypeInfo::Bool, &TypeInfo::Bool, span),
+            },
+            (left, right) => self.unsupported(op, &left.type_info(), &right.type_info(), span),
+        }
+    }
+
+    fn int_binary(
+        &mut self,
+        op: BinaryOp,
+        left: i64,
+        right: i64,
+        span: Span,
+    ) -> Option<ConstValue> {
+        if matches!(op, BinaryOp::Div | BinaryOp::FloorDiv | BinaryOp::Mod) && right == 0 {
+            return self.error(
+                span,
+                "division by zero in constant expression".to_string(),
+                None,
+            );
+        }
+
+        let result = match op {
+            BinaryOp::Add => left.checked_add(right),
+            BinaryOp::Sub => left.checked_sub(right),
+            BinaryOp::Mul => left.checked_mul(right),
+            BinaryOp::Div => left.checked_div(right),
+            BinaryOp::Mod => left.checked_rem(right),
+            BinaryOp::FloorDiv => left.checked_div(right).map(|quotient| {
+                let remainder = left % right;
+                if remainder != 0 && (remainder < 0) != (right < 0) {
+                    quotient - 1
+                } else {
+                    quotient
+                }
+            }),
+            BinaryOp::Pow => match u32::try_from(right) {
+                Ok(exponent) => left.checked_pow(exponent),
+                // Negative exponents truncate toward zero, as they do at runtime
+                Err(_) if right > 0 => None,
+                Err(_) => Some(match left {
+                    1 => 1,
+                    -1 if right % 2 == 0 => 1,
+                    -1 => -1,
+                    _ => 0,
+                }),
+            },
+            BinaryOp::BitAnd => Some(left & right),
+            BinaryOp::BitOr => Some(left | right),
+            BinaryOp::BitXor => Some(left ^ right),
+            // Shift amounts wrap at the bit width, as they do at runtime
+            BinaryOp::Shl => Some(left.wrapping_shl(right as u32)),
+            BinaryOp::Shr => Some(left.wrapping_shr(right as u32)),
+            _ => {
+                return match compare(op, &left, &right) {
+                    Some(result) => Some(ConstValue::Bool(result)),
+                    None => self.unsupported(op, &TypeInfo::I64, &TypeInfo::I64, span),
+                };
+            }
+        };
+
+        match result {
+            Some(result) => Some(ConstValue::Int(result)),
+            None => self.overflow(span),
+        }
+    }
+
+    fn float_binary(
+        &mut self,
+        op: BinaryOp,
+        left: f64,
+        right: f64,
+        span: Span,
+    ) -> Option<ConstValue> {
+        if matches!(op, BinaryOp::Div | BinaryOp::FloorDiv) && right == 0.0 {
+            return self.error(
+                span,
+                "division by zero in constant expression".to_string(),
+                None,
+            );
+        }
+
+        let result = match op {
+            BinaryOp::Add => left + right,
+            BinaryOp::Sub => left - right,
+            BinaryOp::Mul => left * right,
+            BinaryOp::Div => left / right,
+            BinaryOp::FloorDiv => (left / right).floor(),
+            BinaryOp::Pow => left.powf(right),
+            _ => {
+                return match compare(op, &left, &right) {
+                    Some(result) => Some(ConstValue::Bool(result)),
+                    None => self.unsupported(op, &TypeInfo::F64, &TypeInfo::F64, span),
+                };
+            }
+        };
+        Some(ConstValue::Float(result))
+    }
+
+    fn unsupported(
+        &mut self,
+        op: BinaryOp,
+        left: &TypeInfo,
+        right: &TypeInfo,
+        span: Span,
+    ) -> Option<ConstValue> {
+        self.error(
+            span,
+            format!(
+                "cannot apply {:?} to {} and {} in a constant expression",
+                op,
+                left.display_name(),
+                right.display_name()
+            ),
+            None,
+        )
+    }
+
+    fn overflow(&mut self, span: Span) -> Option<ConstValue> {
+        self.error(
+            span,
+            "integer overflow in constant expression".to_string(),
+            None,
+        )
+    }
+
+    fn error(&mut self, span: Span, message: String, hint: Option<&str>) -> Option<ConstValue> {
+        let mut error = TypeError::new(message).with_span(span);
+        if let Some(hint) = hint {
+            error = error.with_hint(hint.to_string());
+        }
+        self.errors.push(error);
+        None
+    }
+}
+
+/// Result of a comparison operator, or `None` when `op` does not compare
+fn compare<T: PartialOrd>(op: BinaryOp, left: &T, right: &T) -> Option<bool> {
+    match op {
+        BinaryOp::Eq => Some(left == right),
+        BinaryOp::Ne => Some(left != right),
+        BinaryOp::Lt => Some(left < right),
+        BinaryOp::Gt => Some(left > right),
+        BinaryOp::LtEq => Some(left <= right),
+        BinaryOp::GtEq => Some(left >= right),
+        _ => None,
+    }
+}
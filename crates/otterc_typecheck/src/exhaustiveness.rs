@@ -0,0 +1,969 @@
+//! Match exhaustiveness and reachability checking
+//!
+//! Implements the usefulness algorithm from Maranget's "Warnings for pattern matching".
+//! A pattern row is *useful* with respect to a matrix of earlier rows when some value
+//! matches the row but none of the rows above it. A match is exhaustive when a wildcard
+//! is not useful after all of its arms, and an arm is unreachable when its own pattern
+//! is not useful after the arms before it.
+
+use std::collections::HashMap;
+
+use otterc_ast::nodes::{Literal, MatchArm, Node, Pattern};
+
+use crate::consteval::ConstValue;
+use crate::types::{TypeContext, TypeInfo};
+
+/// Upper bound on the number of missing patterns collected for one match
+const MAX_WITNESSES: usize = 32;
+
+/// Result of checking a single `match` expression
+#[derive(Debug, Default)]
+pub(crate) struct MatchReport {
+    /// Example patterns for values that no arm matches
+    pub missing: Vec<String>,
+    /// Indices of arms that can never be selected
+    pub unreachable: Vec<usize>,
+}
+
+/// Check `arms` against values of type `scrutinee`.
+///
+/// Guarded arms may fail at runtime, so they never count towards covering a value, but they
+/// are still reported when earlier arms already cover everything they match. Identifiers
+/// naming one of `constants` match that constant's value rather than binding.
+pub(crate) fn check_match(
+    context: &TypeContext,
+    constants: &HashMap<String, ConstValue>,
+    scrutinee: &TypeInfo,
+    arms: &[Node<MatchArm>],
+) -> MatchReport {
+    let checker = Usefulness { context };
+    let types = [scrutinee.clone()];
+    let mut report = MatchReport::default();
+    let mut rows: Vec<Vec<Pat>> = Vec::new();
+
+    for (index, arm) in arms.iter().enumerate() {
+        let row = vec![Pat::lower(arm.as_ref().pattern.as_ref(), constants)];
+        if !checker.is_useful(&rows, &row, &types) {
+            report.unreachable.push(index);
+        }
+        if arm.as_ref().guard.is_none() {
+            rows.push(row);
+        }
+    }
+
+    report.missing = checker
+        .missing(&rows, &types)
+        .into_iter()
+        .filter_map(|witness| witness.first().map(Pat::to_string))
+        .collect();
+    report
+}
+
+/// Example values of type `ty` that `pattern` does not match, empty when it is irrefutable.
+///
+/// Names in a binding pattern always bind, even when they match a constant's name.
+pub(crate) fn uncovered(context: &TypeContext, ty: &TypeInfo, pattern: &Pattern) -> Vec<String> {
+    let checker = Usefulness { context };
+    let rows = vec![vec![Pat::lower(pattern, &HashMap::new())]];
+    checker
+        .missing(&rows, std::slice::from_ref(ty))
+        .into_iter()
+        .filter_map(|witness| witness.first().map(Pat::to_string))
+        .collect()
+}
+
+/// Pattern reduced to the shapes the algorithm distinguishes; bindings become wildcards
+#[derive(Debug, Clone)]
+enum Pat {
+    Wild,
+    Ctor(Ctor, Vec<Pat>),
+    Struct {
+        name: String,
+        fields: Vec<(String, Pat)>,
+    },
+    /// List pattern with fixed leading elements; `rest` when longer lists also match
+    List {
+        prefix: Vec<Pat>,
+        rest: bool,
+    },
+    /// Alternatives; rows are split into one row per alternative before they are compared
+    Or(Vec<Pat>),
+}
+
+/// A value constructor that splits a column of patterns
+#[derive(Debug, Clone, PartialEq)]
+enum Ctor {
+    Variant {
+        enum_name: String,
+        variant: String,
+    },
+    Bool(bool),
+    Unit,
+    Int(i64),
+    /// Integers from the first bound to the second, both included
+    IntRange(i64, i64),
+    /// A range of floats or characters, as written; only an identical range covers it
+    Range(String),
+    Float(String),
+    Str(String),
+    /// Strings starting with this text
+    StrPrefix(String),
+    Bytes(Vec<u8>),
+    Tuple(usize),
+    Struct,
+    /// Lists of exactly this length
+    ListExact(usize),
+    /// Lists of at least this length
+    ListAtLeast(usize),
+}
+
+impl Ctor {
+    fn literal(literal: &Literal) -> Self {
+        match literal {
+            Literal::Bool(value) => Ctor::Bool(*value),
+            Literal::None | Literal::Unit => Ctor::Unit,
+            Literal::Number(number) if !number.is_float_literal => {
+                Ctor::Int(number.int_value() as i64)
+            }
+            Literal::Number(number) => Ctor::Float(format!("{:?}", number.value)),
+            Literal::String(text) => Ctor::Str(text.value.clone()),
+            Literal::Bytes(bytes) => Ctor::Bytes(bytes.clone()),
+        }
+    }
+
+    fn range(start: &Literal, end: &Literal, inclusive: bool) -> Self {
+        match (start, end) {
+            (Literal::Number(low), Literal::Number(high))
+                if !low.is_float_literal && !high.is_float_literal =>
+            {
+                let high = high.int_value() as i64;
+                let high = if inclusive {
+                    high
+                } else {
+                    high.saturating_sub(1)
+                };
+                Ctor::IntRange(low.int_value() as i64, high)
+            }
+            _ => {
+                let op = if inclusive { "..=" } else { ".." };
+                Ctor::Range(format!(
+                    "{}{}{}",
+                    Pat::Ctor(Ctor::literal(start), Vec::new()),
+                    op,
+                    Pat::Ctor(Ctor::literal(end), Vec::new())
+                ))
+            }
+        }
+    }
+
+    /// Whether the integer `value` is one of the values this constructor builds
+    fn contains_int(&self, value: i64) -> bool {
+        match self {
+            Ctor::Int(own) => *own == value,
+            Ctor::IntRange(low, high) => (*low..=*high).contains(&value),
+            _ => false,
+        }
+    }
+}
+
+/// The set of values a column ranges over
+enum Domain {
+    Enum {
+        name: String,
+        variants: Vec<(String, Vec<TypeInfo>)>,
+    },
+    Bool,
+    Unit,
+    Tuple(Vec<TypeInfo>),
+    Struct {
+        name: String,
+        fields: Vec<(String, TypeInfo)>,
+    },
+    List(TypeInfo),
+    /// Numbers, strings and unresolved types: too many values to enumerate
+    Open,
+}
+
+impl Pat {
+    fn lower(pattern: &Pattern, constants: &HashMap<String, ConstValue>) -> Self {
+        match pattern {
+            Pattern::Identifier(name) => match constants.get(name) {
+                Some(value) => Pat::Ctor(Ctor::literal(&value.to_literal()), Vec::new()),
+                None => Pat::Wild,
+            },
+            Pattern::Wildcard => Pat::Wild,
+            Pattern::Literal(literal) => Pat::Ctor(Ctor::literal(literal.as_ref()), Vec::new()),
+            Pattern::EnumVariant {
+                enum_name,
+                variant,
+                fields,
+            } => Pat::Ctor(
+                Ctor::Variant {
+                    enum_name: enum_name.clone(),
+                    variant: variant.clone(),
+                },
+                fields
+                    .iter()
+                    .map(|field| Pat::lower(field.as_ref(), constants))
+                    .collect(),
+            ),
+            Pattern::Struct { name, fields } => Pat::Struct {
+                name: name.clone(),
+                fields: fields
+                    .iter()
+                    .map(|(field, pattern)| {
+                        let pattern = pattern
+                            .as_ref()
+                            .map_or(Pat::Wild, |pattern| Pat::lower(pattern.as_ref(), constants));
+                        (field.clone(), pattern)
+                    })
+                    .collect(),
+            },
+            Pattern::Array { patterns, rest } => Pat::List {
+                prefix: patterns
+                    .iter()
+                    .map(|pattern| Pat::lower(pattern.as_ref(), constants))
+                    .collect(),
+                rest: rest.is_some(),
+            },
+            Pattern::Tuple(elements) => Pat::Ctor(
+                Ctor::Tuple(elements.len()),
+                elements
+                    .iter()
+                    .map(|element| Pat::lower(element.as_ref(), constants))
+                    .collect(),
+            ),
+            Pattern::Or(alternatives) => Pat::Or(
+                alternatives
+                    .iter()
+                    .map(|alternative| Pat::lower(alternative.as_ref(), constants))
+                    .collect(),
+            ),
+            Pattern::Range {
+                start,
+                end,
+                inclusive,
+            } => Pat::Ctor(
+                Ctor::range(start.as_ref(), end.as_ref(), *inclusive),
+                Vec::new(),
+            ),
+            Pattern::Binding { pattern, .. } => Pat::lower(pattern.as_ref().as_ref(), constants),
+            Pattern::StringPrefix { prefix, .. } => match prefix.as_ref() {
+                Literal::String(text) => Pat::Ctor(Ctor::StrPrefix(text.value.clone()), Vec::new()),
+                other => Pat::Ctor(Ctor::literal(other), Vec::new()),
+            },
+        }
+    }
+
+    fn is_wild(&self) -> bool {
+        matches!(self, Pat::Wild)
+    }
+
+    /// Whether this pattern matches every value built by `ctor`
+    fn covers(&self, ctor: &Ctor) -> bool {
+        match (self, ctor) {
+            (Pat::Wild, _) | (Pat::Struct { .. }, Ctor::Struct) => true,
+            (Pat::Ctor(Ctor::Variant { variant: own, .. }, _), Ctor::Variant { variant, .. }) => {
+                own == variant
+            }
+            (Pat::Ctor(own @ (Ctor::Int(_) | Ctor::IntRange(..)), _), Ctor::Int(value)) => {
+                own.contains_int(*value)
+            }
+            (
+                Pat::Ctor(own @ (Ctor::Int(_) | Ctor::IntRange(..)), _),
+                Ctor::IntRange(low, high),
+            ) => own.contains_int(*low) && own.contains_int(*high),
+            (Pat::Ctor(Ctor::StrPrefix(own), _), Ctor::Str(text) | Ctor::StrPrefix(text)) => {
+                text.starts_with(own.as_str())
+            }
+            (Pat::Ctor(own, _), ctor) => own == ctor,
+            (Pat::List { prefix, rest }, Ctor::ListExact(len)) => {
+                if *rest {
+                    prefix.len() <= *len
+                } else {
+                    prefix.len() == *len
+                }
+            }
+            (Pat::List { prefix, rest }, Ctor::ListAtLeast(len)) => *rest && prefix.len() <= *len,
+            _ => false,
+        }
+    }
+}
+
+impl std::fmt::Display for Pat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Pat::Ctor(Ctor::Variant { enum_name, variant }, fields) => {
+                write!(f, "{}.{}", enum_name, variant)?;
+                if !fields.is_empty() {
+                    write!(f, "({})", join(fields))?;
+                }
+                Ok(())
+            }
+            Pat::Ctor(Ctor::Tuple(_), fields) if fields.len() == 1 => {
+                write!(f, "({},)", fields[0])
+            }
+            Pat::Ctor(Ctor::Tuple(_), fields) => write!(f, "({})", join(fields)),
+            Pat::Ctor(Ctor::Bool(value), _) => write!(f, "{}", value),
+            Pat::Ctor(Ctor::Unit, _) => write!(f, "()"),
+            Pat::Ctor(Ctor::Int(value), _) => write!(f, "{}", value),
+            Pat::Ctor(Ctor::IntRange(low, high), _) => write!(f, "{}..={}", low, high),
+            Pat::Ctor(Ctor::Range(range), _) => write!(f, "{}", range),
+            Pat::Ctor(Ctor::Float(value), _) => write!(f, "{}", value),
+            Pat::Ctor(Ctor::Str(value), _) => write!(f, "{:?}", value),
+            Pat::Ctor(Ctor::StrPrefix(value), _) => write!(f, "{:?} + _", value),
+            Pat::Ctor(Ctor::Bytes(value), _) => write!(f, "b\"{}\"", value.escape_ascii()),
+            Pat::Wild | Pat::Ctor(_, _) => write!(f, "_"),
+            Pat::Struct { name, fields } => {
+                let shown: Vec<String> = fields
+                    .iter()
+                    .filter(|(_, pattern)| !pattern.is_wild())
+                    .map(|(field, pattern)| format!("{}: {}", field, pattern))
+                    .collect();
+                if shown.is_empty() {
+                    write!(f, "_")
+                } else {
+                    write!(f, "{}{{{}}}", name, shown.join(", "))
+                }
+            }
+            Pat::List { prefix, rest } => {
+                write!(f, "[{}]", join(prefix))?;
+                if *rest {
+                    write!(f, "..rest")?;
+                }
+                Ok(())
+            }
+            Pat::Or(alternatives) => {
+                let shown: Vec<String> = alternatives.iter().map(Pat::to_string).collect();
+                write!(f, "{}", shown.join(" | "))
+            }
+        }
+    }
+}
+
+fn join(patterns: &[Pat]) -> String {
+    patterns
+        .iter()
+        .map(Pat::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl Domain {
+    /// Every constructor of the domain, or `None` when it cannot be enumerated.
+    ///
+    /// Lists are split by length: exact lengths up to the longest pattern, then one
+    /// constructor for everything longer.
+    fn constructors(&self, heads: &[&Pat]) -> Option<Vec<Ctor>> {
+        match self {
+            Domain::Enum { name, variants } => Some(
+                variants
+                    .iter()
+                    .map(|(variant, _)| Ctor::Variant {
+                        enum_name: name.clone(),
+                        variant: variant.clone(),
+                    })
+                    .collect(),
+            ),
+            Domain::Bool => Some(vec![Ctor::Bool(true), Ctor::Bool(false)]),
+            Domain::Unit => Some(vec![Ctor::Unit]),
+            Domain::Tuple(elements) => Some(vec![Ctor::Tuple(elements.len())]),
+            Domain::Struct { .. } => Some(vec![Ctor::Struct]),
+            Domain::List(_) => {
+                let mut boundary = 0;
+                for head in heads {
+                    if let Pat::List { prefix, rest } = head {
+                        let needed = if *rest {
+                            prefix.len()
+                        } else {
+                            prefix.len() + 1
+                        };
+                        boundary = boundary.max(needed);
+                    }
+                }
+                let mut ctors: Vec<Ctor> = (0..boundary).map(Ctor::ListExact).collect();
+                ctors.push(Ctor::ListAtLeast(boundary));
+                Some(ctors)
+            }
+            Domain::Open => None,
+        }
+    }
+
+    fn field_types(&self, ctor: &Ctor, heads: &[&Pat]) -> Vec<TypeInfo> {
+        match (self, ctor) {
+            (Domain::Enum { variants, .. }, Ctor::Variant { variant, .. }) => variants
+                .iter()
+                .find(|(name, _)| name == variant)
+                .map(|(_, fields)| fields.clone())
+                .unwrap_or_default(),
+            (Domain::Tuple(elements), Ctor::Tuple(len)) if elements.len() == *len => {
+                elements.clone()
+            }
+            (Domain::Struct { fields, .. }, Ctor::Struct) => {
+                fields.iter().map(|(_, ty)| ty.clone()).collect()
+            }
+            (Domain::List(element), Ctor::ListExact(len) | Ctor::ListAtLeast(len)) => {
+                vec![element.clone(); *len]
+            }
+            _ => {
+                // The column type is unresolved; take the arity from the patterns themselves
+                let arity = heads
+                    .iter()
+                    .filter_map(|head| match head {
+                        Pat::Ctor(_, fields) if head.covers(ctor) => Some(fields.len()),
+                        _ => None,
+                    })
+                    .max()
+                    .unwrap_or(0);
+                vec![TypeInfo::Unknown; arity]
+            }
+        }
+    }
+
+    /// Rebuild a witness pattern for `ctor` from the witnesses of its fields
+    fn rebuild(&self, ctor: &Ctor, fields: Vec<Pat>) -> Pat {
+        match (self, ctor) {
+            (
+                Domain::Struct {
+                    name,
+                    fields: names,
+                },
+                Ctor::Struct,
+            ) => Pat::Struct {
+                name: name.clone(),
+                fields: names
+                    .iter()
+                    .map(|(field, _)| field.clone())
+                    .zip(fields)
+                    .collect(),
+            },
+            (_, Ctor::ListExact(_)) => Pat::List {
+                prefix: fields,
+                rest: false,
+            },
+            (_, Ctor::ListAtLeast(_)) => Pat::List {
+                prefix: fields,
+                rest: true,
+            },
+            _ => Pat::Ctor(ctor.clone(), fields),
+        }
+    }
+
+    /// An example value of an open domain that none of `present` matches, preferring
+    /// the smallest non-negative integer
+    fn fresh_value(&self, present: &[Ctor]) -> Pat {
+        if present
+            .iter()
+            .all(|ctor| matches!(ctor, Ctor::Int(_) | Ctor::IntRange(..)))
+        {
+            // The smallest unused integer is 0 or sits just past one of the present values
+            let mut candidates = vec![0];
+            for ctor in present {
+                match ctor {
+                    Ctor::Int(value) => candidates.extend(value.checked_add(1)),
+                    Ctor::IntRange(low, high) => {
+                        candidates.extend(high.checked_add(1));
+                        candidates.extend(low.checked_sub(1));
+                    }
+                    _ => {}
+                }
+            }
+            candidates.sort_by_key(|value| (*value < 0, value.unsigned_abs()));
+            let unused = candidates
+                .into_iter()
+                .find(|candidate| !present.iter().any(|ctor| ctor.contains_int(*candidate)));
+            if let Some(value) = unused {
+                return Pat::Ctor(Ctor::Int(value), Vec::new());
+            }
+        }
+        Pat::Wild
+    }
+}
+
+struct Usefulness<'a> {
+    context: &'a TypeContext,
+}
+
+impl Usefulness<'_> {
+    /// Work out the domain of a column from its type, falling back to the shape of its patterns
+    fn domain(&self, ty: &TypeInfo, heads: &[&Pat]) -> Domain {
+        match self.context.normalize_type(ty.resolve_alias().clone()) {
+            TypeInfo::Enum { name, variants, .. } => {
+                let order: Vec<String> = match self.context.get_enum(&name) {
+                    Some(definition) => definition
+                        .variants
+                        .iter()
+                        .map(|variant| variant.name.clone())
+                        .collect(),
+                    None => {
+                        let mut names: Vec<String> = variants.keys().cloned().collect();
+                        names.sort();
+                        names
+                    }
+                };
+                let variants = order
+                    .into_iter()
+                    .map(|variant| {
+                        let fields = variants
+                            .get(&variant)
+                            .map(|info| info.fields.clone())
+                            .unwrap_or_default();
+                        (variant, fields)
+                    })
+                    .collect();
+                Domain::Enum { name, variants }
+            }
+            TypeInfo::Bool => Domain::Bool,
+            TypeInfo::Unit => Domain::Unit,
+            TypeInfo::Tuple(elements) => Domain::Tuple(elements),
+            TypeInfo::Struct { name, fields } => {
+                let mut fields: Vec<(String, TypeInfo)> = fields.into_iter().collect();
+                fields.sort_by(|a, b| a.0.cmp(&b.0));
+                Domain::Struct { name, fields }
+            }
+            TypeInfo::List(element) => Domain::List(*element),
+            _ => self.domain_from_patterns(heads),
+        }
+    }
+
+    fn domain_from_patterns(&self, heads: &[&Pat]) -> Domain {
+        for head in heads {
+            match head {
+                Pat::Ctor(Ctor::Variant { enum_name, .. }, _) => {
+                    if let Some(enum_type) = self.context.build_enum_type(enum_name, Vec::new()) {
+                        return self.domain(&enum_type, heads);
+                    }
+                }
+                Pat::Ctor(Ctor::Bool(_), _) => return Domain::Bool,
+                Pat::Ctor(Ctor::Unit, _) => return Domain::Unit,
+                Pat::Ctor(Ctor::Tuple(len), _) => {
+                    return Domain::Tuple(vec![TypeInfo::Unknown; *len]);
+                }
+                Pat::Struct { name, .. } => {
+                    if let Some(definition) = self.context.get_struct(name) {
+                        let ty = TypeInfo::Struct {
+                            name: definition.name.clone(),
+                            fields: definition.fields.clone(),
+                        };
+                        return self.domain(&ty, heads);
+                    }
+                }
+                Pat::List { .. } => return Domain::List(TypeInfo::Unknown),
+                _ => {}
+            }
+        }
+        Domain::Open
+    }
+
+    /// Rows whose first pattern matches values built by `ctor`, with that pattern expanded
+    /// into its fields
+    fn specialize(
+        &self,
+        row: &[Pat],
+        ctor: &Ctor,
+        domain: &Domain,
+        arity: usize,
+    ) -> Option<Vec<Pat>> {
+        let (head, tail) = row.split_first()?;
+        if !head.covers(ctor) {
+            return None;
+        }
+        let mut fields = match head {
+            Pat::Wild | Pat::Or(_) => Vec::new(),
+            Pat::Ctor(_, fields) => fields.clone(),
+            Pat::Struct { fields, .. } => match domain {
+                Domain::Struct { fields: names, .. } => names
+                    .iter()
+                    .map(|(name, _)| {
+                        fields
+                            .iter()
+                            .find(|(field, _)| field == name)
+                            .map_or(Pat::Wild, |(_, pattern)| pattern.clone())
+                    })
+                    .collect(),
+                _ => Vec::new(),
+            },
+            Pat::List { prefix, .. } => prefix.clone(),
+        };
+        fields.resize(arity, Pat::Wild);
+        fields.extend_from_slice(tail);
+        Some(fields)
+    }
+
+    fn specialize_all(
+        &self,
+        rows: &[Vec<Pat>],
+        ctor: &Ctor,
+        domain: &Domain,
+        arity: usize,
+    ) -> Vec<Vec<Pat>> {
+        rows.iter()
+            .filter_map(|row| self.specialize(row, ctor, domain, arity))
+            .collect()
+    }
+
+    /// Rows with an or-pattern in the first column split into one row per alternative
+    fn expand_or(rows: &[Vec<Pat>]) -> Vec<Vec<Pat>> {
+        let mut expanded = Vec::new();
+        for row in rows {
+            match row.split_first() {
+                Some((Pat::Or(alternatives), tail)) => {
+                    let split: Vec<Vec<Pat>> = alternatives
+                        .iter()
+                        .map(|alternative| {
+                            let mut row = vec![alternative.clone()];
+                            row.extend_from_slice(tail);
+                            row
+                        })
+                        .collect();
+                    expanded.extend(Self::expand_or(&split));
+                }
+                _ => expanded.push(row.clone()),
+            }
+        }
+        expanded
+    }
+
+    /// Rows whose first pattern is a wildcard, without that column
+    fn default_rows(rows: &[Vec<Pat>]) -> Vec<Vec<Pat>> {
+        rows.iter()
+            .filter(|row| row.first().is_some_and(Pat::is_wild))
+            .map(|row| row[1..].to_vec())
+            .collect()
+    }
+
+    /// Constructors of `all` (or the literals seen, for open domains) matched by some head
+    fn present(all: Option<&[Ctor]>, heads: &[&Pat]) -> Vec<Ctor> {
+        match all {
+            Some(all) => all
+                .iter()
+                .filter(|ctor| {
+                    heads
+                        .iter()
+                        .any(|head| !head.is_wild() && head.covers(ctor))
+                })
+                .cloned()
+                .collect(),
+            None => {
+                let mut present: Vec<Ctor> = Vec::new();
+                for head in heads {
+                    if let Pat::Ctor(ctor, _) = head
+                        && !present.contains(ctor)
+                    {
+                        present.push(ctor.clone());
+                    }
+                }
+                present
+            }
+        }
+    }
+
+    /// Whether some value matches `row` but none of `rows`
+    fn is_useful(&self, rows: &[Vec<Pat>], row: &[Pat], types: &[TypeInfo]) -> bool {
+        let (Some((head, tail)), Some((ty, tail_types))) = (row.split_first(), types.split_first())
+        else {
+            return rows.is_empty();
+        };
+        // An or-pattern is useful when any of its alternatives is
+        if let Pat::Or(alternatives) = head {
+            return alternatives.iter().any(|alternative| {
+                let mut row = vec![alternative.clone()];
+                row.extend_from_slice(tail);
+                self.is_useful(rows, &row, types)
+            });
+        }
+        let rows = &Self::expand_or(rows);
+
+        let mut heads: Vec<&Pat> = rows.iter().filter_map(|row| row.first()).collect();
+        heads.push(head);
+        let domain = self.domain(ty, &heads);
+        let all = domain.constructors(&heads);
+
+        let candidates = if head.is_wild() {
+            let row_heads = &heads[..heads.len() - 1];
+            match &all {
+                Some(all) if Self::present(Some(all), row_heads).len() == all.len() => all.clone(),
+                _ => return self.is_useful(&Self::default_rows(rows), tail, tail_types),
+            }
+        } else {
+            match &all {
+                Some(all) => all
+                    .iter()
+                    .filter(|ctor| head.covers(ctor))
+                    .cloned()
+                    .collect(),
+                None => match head {
+                    Pat::Ctor(ctor, _) => vec![ctor.clone()],
+                    // A struct or list pattern whose type could not be resolved
+                    _ => return true,
+                },
+            }
+        };
+
+        candidates.iter().any(|ctor| {
+            let mut field_types = domain.field_types(ctor, &heads);
+            let arity = field_types.len();
+            field_types.extend_from_slice(tail_types);
+            let rows = self.specialize_all(rows, ctor, &domain, arity);
+            self.specialize(row, ctor, &domain, arity)
+                .is_some_and(|row| self.is_useful(&rows, &row, &field_types))
+        })
+    }
+
+    /// Example rows of values that none of `rows` match
+    fn missing(&self, rows: &[Vec<Pat>], types: &[TypeInfo]) -> Vec<Vec<Pat>> {
+        let Some((ty, tail_types)) = types.split_first() else {
+            return if rows.is_empty() {
+                vec![Vec::new()]
+            } else {
+                Vec::new()
+            };
+        };
+        let rows = &Self::expand_or(rows);
+
+        let heads: Vec<&Pat> = rows.iter().filter_map(|row| row.first()).collect();
+        let domain = self.domain(ty, &heads);
+        let all = domain.constructors(&heads);
+        let present = Self::present(all.as_deref(), &heads);
+
+        if let Some(all) = &all
+            && present.len() == all.len()
+        {
+            let mut witnesses = Vec::new();
+            for ctor in all {
+                let mut field_types = domain.field_types(ctor, &heads);
+                let arity = field_types.len();
+                field_types.extend_from_slice(tail_types);
+                let specialized = self.specialize_all(rows, ctor, &domain, arity);
+                for mut witness in self.missing(&specialized, &field_types) {
+                    let rest = witness.split_off(arity);
+                    let mut rebuilt = vec![domain.rebuild(ctor, witness)];
+                    rebuilt.extend(rest);
+                    witnesses.push(rebuilt);
+                }
+                if witnesses.len() >= MAX_WITNESSES {
+                    break;
+                }
+            }
+            witnesses.truncate(MAX_WITNESSES);
+            return witnesses;
+        }
+
+        let tails = self.missing(&Self::default_rows(rows), tail_types);
+        if tails.is_empty() {
+            return Vec::new();
+        }
+
+        let firsts: Vec<Pat> = match &all {
+            _ if present.is_empty() => vec![Pat::Wild],
+            Some(all) => all
+                .iter()
+                .filter(|ctor| !present.contains(ctor))
+                .map(|ctor| {
+                    let arity = domain.field_types(ctor, &heads).len();
+                    domain.rebuild(ctor, vec![Pat::Wild; arity])
+                })
+                .collect(),
+            None => vec![domain.fresh_value(&present)],
+        };
+
+        let mut witnesses = Vec::new();
+        'outer: for first in &firsts {
+            for tail in &tails {
+                let mut witness = vec![first.clone()];
+                witness.extend(tail.iter().cloned());
+                witnesses.push(witness);
+                if witnesses.len() >= MAX_WITNESSES {
+                    break 'outer;
+                }
+            }
+        }
+        witnesses
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::EnumDefinition;
+    use otterc_ast::nodes::{Block, EnumVariant, NumberLiteral, StringLiteral, Type};
+    use otterc_span::Span;
+
+    fn node<T>(value: T) -> Node<T> {
+        Node::new(value, Span::new(0, 0))
+    }
+
+    fn arm(pattern: Pattern) -> Node<MatchArm> {
+        node(MatchArm {
+            pattern: node(pattern),
+            guard: None,
+            body: node(Block::new(Vec::new())),
+        })
+    }
+
+    fn variant(name: &str, fields: Vec<Pattern>) -> Pattern {
+        Pattern::EnumVariant {
+            enum_name: "Shape".to_string(),
+            variant: name.to_string(),
+            fields: fields.into_iter().map(node).collect(),
+        }
+    }
+
+    fn int(value: i64) -> Pattern {
+        Pattern::Literal(node(Literal::Number(NumberLiteral::new(
+            value as f64,
+            false,
+        ))))
+    }
+
+    fn shape_context() -> TypeContext {
+        let mut context = TypeContext::new();
+        let int_type = || node(Type::Simple("int".to_string()));
+        context.define_enum(EnumDefinition {
+            name: "Shape".to_string(),
+            generics: Vec::new(),
+            bounds: Vec::new(),
+            variants: vec![
+                EnumVariant::new("Circle", vec![int_type()]),
+                EnumVariant::new("Square", vec![int_type()]),
+                EnumVariant::new("Empty", Vec::new()),
+            ],
+            public: false,
+        });
+        context
+    }
+
+    #[test]
+    fn reports_missing_variants_and_unreachable_arms() {
+        let context = shape_context();
+        let shape = context.build_enum_type("Shape", Vec::new()).unwrap();
+
+        let arms = vec![
+            arm(variant("Circle", vec![int(0)])),
+            arm(variant(
+                "Circle",
+                vec![Pattern::Identifier("r".to_string())],
+            )),
+            arm(variant("Circle", vec![int(1)])),
+            arm(variant("Empty", Vec::new())),
+        ];
+        let report = check_match(&context, &HashMap::new(), &shape, &arms);
+        assert_eq!(report.missing, vec!["Shape.Square(_)".to_string()]);
+        assert_eq!(report.unreachable, vec![2]);
+
+        let arms = vec![arm(variant("Square", vec![int(0)])), arm(Pattern::Wildcard)];
+        let report = check_match(&context, &HashMap::new(), &shape, &arms);
+        assert!(report.missing.is_empty());
+        assert!(report.unreachable.is_empty());
+    }
+
+    #[test]
+    fn expands_or_patterns_and_integer_ranges() {
+        let context = shape_context();
+        let shape = context.build_enum_type("Shape", Vec::new()).unwrap();
+        let or = |patterns: Vec<Pattern>| Pattern::Or(patterns.into_iter().map(node).collect());
+
+        let arms = vec![
+            arm(or(vec![
+                variant("Circle", vec![Pattern::Wildcard]),
+                variant("Empty", Vec::new()),
+            ])),
+            arm(variant("Circle", vec![int(3)])),
+        ];
+        let report = check_match(&context, &HashMap::new(), &shape, &arms);
+        assert_eq!(report.missing, vec!["Shape.Square(_)".to_string()]);
+        assert_eq!(report.unreachable, vec![1]);
+
+        let bound = |value: i64| node(Literal::Number(NumberLiteral::new(value as f64, false)));
+        let range = |start: i64, end: i64, inclusive: bool| Pattern::Range {
+            start: bound(start),
+            end: bound(end),
+            inclusive,
+        };
+        let arms = vec![
+            arm(range(0, 10, false)),
+            arm(range(2, 5, true)),
+            arm(or(vec![int(10), int(11)])),
+        ];
+        let report = check_match(&context, &HashMap::new(), &TypeInfo::I64, &arms);
+        assert_eq!(report.missing, vec!["12".to_string()]);
+        assert_eq!(report.unreachable, vec![1]);
+    }
+
+    #[test]
+    fn finds_values_a_binding_pattern_refutes() {
+        let context = shape_context();
+        let shape = context.build_enum_type("Shape", Vec::new()).unwrap();
+        let tuple = TypeInfo::Tuple(vec![TypeInfo::I64, shape.clone()]);
+        let pair =
+            |first: Pattern, second: Pattern| Pattern::Tuple(vec![node(first), node(second)]);
+
+        let irrefutable = pair(
+            Pattern::Identifier("n".to_string()),
+            Pattern::Or(vec![
+                node(variant(
+                    "Circle",
+                    vec![Pattern::Identifier("r".to_string())],
+                )),
+                node(variant(
+                    "Square",
+                    vec![Pattern::Identifier("r".to_string())],
+                )),
+                node(variant("Empty", Vec::new())),
+            ]),
+        );
+        assert!(uncovered(&context, &tuple, &irrefutable).is_empty());
+
+        let refutable = pair(int(0), variant("Empty", Vec::new()));
+        assert_eq!(uncovered(&context, &tuple, &refutable), vec!["(1, _)"]);
+        assert_eq!(
+            uncovered(&context, &shape, &Pattern::Identifier("x".to_string())),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn splits_lists_by_length() {
+        let context = TypeContext::new();
+        let list = TypeInfo::List(Box::new(TypeInfo::Bool));
+        let array = |patterns: Vec<Pattern>, rest: Option<&str>| Pattern::Array {
+            patterns: patterns.into_iter().map(node).collect(),
+            rest: rest.map(str::to_string),
+        };
+
+        let arms = vec![
+            arm(array(Vec::new(), None)),
+            arm(array(vec![Pattern::Wildcard], Some("rest"))),
+        ];
+        assert!(
+            check_match(&context, &HashMap::new(), &list, &arms)
+                .missing
+                .is_empty()
+        );
+
+        let arms = vec![arm(array(vec![Pattern::Wildcard, Pattern::Wildcard], None))];
+        let report = check_match(&context, &HashMap::new(), &list, &arms);
+        assert_eq!(report.missing, vec!["[]", "[_]", "[_, _, _]..rest"]);
+    }
+
+    #[test]
+    fn string_prefixes_cover_longer_strings() {
+        let context = TypeContext::new();
+        let text = |value: &str| node(Literal::String(StringLiteral::new(value)));
+        let prefix = |value: &str, rest: Option<&str>| Pattern::StringPrefix {
+            prefix: text(value),
+            rest: rest.map(str::to_string),
+        };
+
+        let arms = vec![
+            arm(Pattern::Literal(text("GET"))),
+            arm(prefix("GET ", Some("path"))),
+            arm(Pattern::Literal(text("GET /index"))),
+            arm(prefix("GET /", None)),
+            arm(prefix("GE", None)),
+        ];
+        let report = check_match(&context, &HashMap::new(), &TypeInfo::Str, &arms);
+        assert_eq!(report.unreachable, vec![2, 3]);
+        assert_eq!(report.missing, vec!["_"]);
+    }
+}
@@ -3,11 +3,18 @@
 //! Provides type inference, validation, and error reporting
 
 pub mod checker;
+pub mod consteval;
 pub mod diagnostics;
+mod exhaustiveness;
 pub mod types;
 pub mod workspace;
 
-pub use checker::{ModuleExports, TypeChecker};
+pub use checker::{
+    ITERATOR_ADAPTERS, ModuleExports, RUNTIME_EXCEPTION_TYPES, TypeChecker, arrange_arguments,
+    instantiation_name,
+};
+pub use consteval::{ConstValue, evaluate_constants};
 pub use diagnostics::from_type_errors as diagnostics_from_type_errors;
+pub use diagnostics::from_type_warnings as diagnostics_from_type_warnings;
 pub use types::{EnumLayout, TypeContext, TypeError, TypeInfo};
 pub use workspace::{ModuleDependency, ModuleRecord, TypecheckWorkspace};
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use otterc_ast::nodes::{EnumVariant, Node, Type};
+use otterc_ast::nodes::{EnumVariant, Function, Node, Type};
 use otterc_span::Span;
 
 use otterc_config::LanguageFeatureFlags;
@@ -12,10 +12,24 @@ pub enum TypeInfo {
     Unit,
     /// Boolean type
     Bool,
+    /// 8-bit integer
+    I8,
+    /// 16-bit integer
+    I16,
     /// 32-bit integer
     I32,
     /// 64-bit integer
     I64,
+    /// 8-bit unsigned integer
+    U8,
+    /// 16-bit unsigned integer
+    U16,
+    /// 32-bit unsigned integer
+    U32,
+    /// 64-bit unsigned integer
+    U64,
+    /// 32-bit floating point
+    F32,
     /// 64-bit floating point
     F64,
     /// String type
@@ -60,6 +74,10 @@ pub enum TypeInfo {
     Error,
     /// Module type (for FFI modules like `rand`, `chrono`, etc.)
     Module(String),
+    /// Trait object (`dyn Trait`) dispatched through a vtable
+    Dyn(String),
+    /// Fixed-size heterogeneous tuple
+    Tuple(Vec<TypeInfo>),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -101,6 +119,12 @@ impl TypeInfo {
                 return_type: Box::new(return_type.substitute(substitutions)),
             },
             TypeInfo::List(element) => TypeInfo::List(Box::new(element.substitute(substitutions))),
+            TypeInfo::Tuple(elements) => TypeInfo::Tuple(
+                elements
+                    .iter()
+                    .map(|element| element.substitute(substitutions))
+                    .collect(),
+            ),
             TypeInfo::Dict { key, value } => TypeInfo::Dict {
                 key: Box::new(key.substitute(substitutions)),
                 value: Box::new(value.substitute(substitutions)),
@@ -159,15 +183,11 @@ impl TypeInfo {
             // Same types are compatible
             (TypeInfo::Unit, TypeInfo::Unit)
             | (TypeInfo::Bool, TypeInfo::Bool)
-            | (TypeInfo::I32, TypeInfo::I32)
-            | (TypeInfo::I64, TypeInfo::I64)
-            | (TypeInfo::F64, TypeInfo::F64)
             | (TypeInfo::Str, TypeInfo::Str)
-            // Numeric promotions
-            | (TypeInfo::I32, TypeInfo::I64) | (TypeInfo::I32, TypeInfo::F64)
-            | (TypeInfo::I64, TypeInfo::F64)
             // Unknown types are compatible with anything (during inference)
             | (TypeInfo::Unknown, _) | (_, TypeInfo::Unknown) => true,
+            // Numeric types are compatible with themselves and the types they widen to
+            (from, to) if from.is_numeric() && to.is_numeric() => from == to || from.widens_to(to),
             // Error types are compatible with strings (for convenience) and themselves
             (TypeInfo::Error, TypeInfo::Error)
             | (TypeInfo::Str, TypeInfo::Error) // Allow raising strings as errors
@@ -177,7 +197,9 @@ impl TypeInfo {
             // Struct types must match exactly
             (TypeInfo::Struct { name: n1, .. }, TypeInfo::Struct { name: n2, .. })
             // Module types are compatible with themselves
-            | (TypeInfo::Module(n1), TypeInfo::Module(n2)) => n1 == n2,
+            | (TypeInfo::Module(n1), TypeInfo::Module(n2))
+            // Trait objects of the same trait are interchangeable
+            | (TypeInfo::Dyn(n1), TypeInfo::Dyn(n2)) => n1 == n2,
 
             // Generic types must match structure
             (
@@ -204,6 +226,13 @@ impl TypeInfo {
                         .all(|(t1, t2)| t1.is_compatible_with(t2))
             }
             (TypeInfo::List(elem1), TypeInfo::List(elem2)) => elem1.is_compatible_with(elem2),
+            (TypeInfo::Tuple(elems1), TypeInfo::Tuple(elems2)) => {
+                elems1.len() == elems2.len()
+                    && elems1
+                        .iter()
+                        .zip(elems2.iter())
+                        .all(|(t1, t2)| t1.is_compatible_with(t2))
+            }
             (TypeInfo::Dict { key: k1, value: v1 }, TypeInfo::Dict { key: k2, value: v2 }) => {
                 k1.is_compatible_with(k2) && v1.is_compatible_with(v2)
             }
@@ -297,8 +326,15 @@ impl TypeInfo {
         match self {
             TypeInfo::Unit => "None".to_string(),
             TypeInfo::Bool => "bool".to_string(),
+            TypeInfo::I8 => "i8".to_string(),
+            TypeInfo::I16 => "i16".to_string(),
             TypeInfo::I32 => "i32".to_string(),
             TypeInfo::I64 => "i64".to_string(),
+            TypeInfo::U8 => "u8".to_string(),
+            TypeInfo::U16 => "u16".to_string(),
+            TypeInfo::U32 => "u32".to_string(),
+            TypeInfo::U64 => "u64".to_string(),
+            TypeInfo::F32 => "f32".to_string(),
             TypeInfo::F64 => "f64".to_string(),
             TypeInfo::Str => "str".to_string(),
             TypeInfo::Function {
@@ -314,6 +350,18 @@ impl TypeInfo {
                 format!("fn({}) -> {}", params_str, return_type.display_name())
             }
             TypeInfo::List(element) => format!("list<{}>", element.display_name()),
+            TypeInfo::Tuple(elements) => {
+                let elements_str = elements
+                    .iter()
+                    .map(|t| t.display_name())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                if elements.len() == 1 {
+                    format!("({},)", elements_str)
+                } else {
+                    format!("({})", elements_str)
+                }
+            }
             TypeInfo::Dict { key, value } => {
                 format!("dict<{}, {}>", key.display_name(), value.display_name())
             }
@@ -360,12 +408,91 @@ impl TypeInfo {
             TypeInfo::Unknown => "?".to_string(),
             TypeInfo::Error => "<error>".to_string(),
             TypeInfo::Module(name) => format!("module<{}>", name),
+            TypeInfo::Dyn(name) => format!("dyn {}", name),
         }
     }
 
-    /// Check if the type is an integer type (i32 or i64)
+    /// Check if the type is an integer type (`i8` through `u64`)
     pub fn is_integer(&self) -> bool {
-        matches!(self, TypeInfo::I32 | TypeInfo::I64)
+        self.integer_layout().is_some()
+    }
+
+    /// Check if the type is a floating point type (`f32` or `f64`)
+    pub fn is_float(&self) -> bool {
+        matches!(self, TypeInfo::F32 | TypeInfo::F64)
+    }
+
+    /// Check if the type is an integer or floating point type
+    pub fn is_numeric(&self) -> bool {
+        self.is_integer() || self.is_float()
+    }
+
+    /// Bit width of an integer type and whether it is signed
+    pub fn integer_layout(&self) -> Option<(u32, bool)> {
+        match self {
+            TypeInfo::I8 => Some((8, true)),
+            TypeInfo::I16 => Some((16, true)),
+            TypeInfo::I32 => Some((32, true)),
+            TypeInfo::I64 => Some((64, true)),
+            TypeInfo::U8 => Some((8, false)),
+            TypeInfo::U16 => Some((16, false)),
+            TypeInfo::U32 => Some((32, false)),
+            TypeInfo::U64 => Some((64, false)),
+            _ => None,
+        }
+    }
+
+    /// Smallest and largest value of an integer type
+    pub fn integer_bounds(&self) -> Option<(i128, i128)> {
+        let (bits, signed) = self.resolve_alias().integer_layout()?;
+        if signed {
+            Some((-(1 << (bits - 1)), (1 << (bits - 1)) - 1))
+        } else {
+            Some((0, (1 << bits) - 1))
+        }
+    }
+
+    /// Whether a value of this numeric type converts to `target` implicitly. An integer
+    /// widens to a larger integer of the same signedness, an unsigned one also to a larger
+    /// signed integer, and every number widens to `f64`. Anything else needs `as`.
+    pub fn widens_to(&self, target: &TypeInfo) -> bool {
+        match (self.integer_layout(), target.integer_layout()) {
+            (Some((from_bits, from_signed)), Some((to_bits, to_signed))) => {
+                from_bits < to_bits && (to_signed || !from_signed)
+            }
+            (Some((bits, _)), None) => match target {
+                TypeInfo::F32 => bits <= 16,
+                TypeInfo::F64 => true,
+                _ => false,
+            },
+            (None, None) => matches!((self, target), (TypeInfo::F32, TypeInfo::F64)),
+            (None, Some(_)) => false,
+        }
+    }
+
+    /// Type that both operands of an arithmetic operator are converted to, or `None` when
+    /// neither widens to the other, as for `u32` and `i32`
+    pub fn common_numeric_type(&self, other: &TypeInfo) -> Option<TypeInfo> {
+        if !self.is_numeric() || !other.is_numeric() {
+            return None;
+        }
+        if self == other || other.widens_to(self) {
+            Some(self.clone())
+        } else if self.widens_to(other) {
+            Some(other.clone())
+        } else if self.is_float() || other.is_float() {
+            Some(TypeInfo::F64)
+        } else {
+            None
+        }
+    }
+
+    /// Follow alias chains down to the underlying type
+    pub fn resolve_alias(&self) -> &TypeInfo {
+        match self {
+            TypeInfo::Alias { underlying, .. } => underlying.resolve_alias(),
+            other => other,
+        }
     }
 }
 
@@ -375,8 +502,15 @@ impl From<&Type> for TypeInfo {
             Type::Simple(name) => match name.as_str() {
                 "unit" | "None" | "none" => TypeInfo::Unit,
                 "bool" => TypeInfo::Bool,
+                "i8" => TypeInfo::I8,
+                "i16" => TypeInfo::I16,
                 "i32" => TypeInfo::I32,
                 "i64" | "int" => TypeInfo::I64,
+                "u8" => TypeInfo::U8,
+                "u16" => TypeInfo::U16,
+                "u32" => TypeInfo::U32,
+                "u64" => TypeInfo::U64,
+                "f32" => TypeInfo::F32,
                 "f64" | "float" => TypeInfo::F64,
                 "str" | "string" => TypeInfo::Str,
                 "list" | "List" => TypeInfo::List(Box::new(TypeInfo::Unknown)),
@@ -385,6 +519,8 @@ impl From<&Type> for TypeInfo {
                     value: Box::new(TypeInfo::Unknown),
                 },
                 "Error" => TypeInfo::Error,
+                // `any` opts out of checking, like a value whose type is not known
+                "any" => TypeInfo::Unknown,
                 _ => TypeInfo::Generic {
                     base: name.clone(),
                     args: Vec::new(),
@@ -414,6 +550,13 @@ impl From<&Type> for TypeInfo {
                     args: args.iter().map(|t| t.into()).collect(),
                 },
             },
+            Type::Function { params, ret } => TypeInfo::Function {
+                params: params.iter().map(TypeInfo::from).collect(),
+                param_defaults: vec![false; params.len()],
+                return_type: Box::new(ret.as_deref().map_or(TypeInfo::Unit, TypeInfo::from)),
+            },
+            Type::Dyn(name) => TypeInfo::Dyn(name.clone()),
+            Type::Tuple(elements) => TypeInfo::Tuple(elements.iter().map(TypeInfo::from).collect()),
         }
     }
 }
@@ -429,8 +572,15 @@ impl From<&str> for TypeInfo {
         match name {
             "unit" => TypeInfo::Unit,
             "bool" => TypeInfo::Bool,
+            "i8" => TypeInfo::I8,
+            "i16" => TypeInfo::I16,
             "i32" => TypeInfo::I32,
             "i64" => TypeInfo::I64,
+            "u8" => TypeInfo::U8,
+            "u16" => TypeInfo::U16,
+            "u32" => TypeInfo::U32,
+            "u64" => TypeInfo::U64,
+            "f32" => TypeInfo::F32,
             "f64" => TypeInfo::F64,
             "str" => TypeInfo::Str,
             "list" | "List" => TypeInfo::List(Box::new(TypeInfo::Unknown)),
@@ -438,6 +588,7 @@ impl From<&str> for TypeInfo {
                 key: Box::new(TypeInfo::Unknown),
                 value: Box::new(TypeInfo::Unknown),
             },
+            "any" => TypeInfo::Unknown,
             _ => TypeInfo::Generic {
                 base: name.to_string(),
                 args: Vec::new(),
@@ -517,12 +668,19 @@ pub struct TypeContext {
     pub functions: HashMap<String, TypeInfo>,
     /// Generic type parameters in scope
     pub generic_params: Vec<String>,
+    /// Concrete types of the type parameters while an instantiation of a generic function
+    /// is being checked
+    pub generic_args: HashMap<String, TypeInfo>,
     /// Struct definitions: name -> definition
     pub structs: HashMap<String, StructDefinition>,
     /// Type aliases: name -> actual type
     pub type_aliases: HashMap<String, TypeInfo>,
     /// Enum definitions available in the current module
     pub enums: HashMap<String, EnumDefinition>,
+    /// Trait definitions: name -> definition
+    pub traits: HashMap<String, TraitDefinition>,
+    /// Trait implementations: type name -> implemented traits
+    pub trait_impls: HashMap<String, Vec<String>>,
     /// Active language feature flags
     pub features: LanguageFeatureFlags,
 }
@@ -537,9 +695,12 @@ impl TypeContext {
             variables: HashMap::new(),
             functions: HashMap::new(),
             generic_params: Vec::new(),
+            generic_args: HashMap::new(),
             structs: HashMap::new(),
             type_aliases: HashMap::new(),
             enums: HashMap::new(),
+            traits: HashMap::new(),
+            trait_impls: HashMap::new(),
             features,
         }
     }
@@ -614,6 +775,27 @@ impl TypeContext {
         self.enums.get(name)
     }
 
+    pub fn define_trait(&mut self, definition: TraitDefinition) {
+        self.traits.insert(definition.name.clone(), definition);
+    }
+
+    pub fn get_trait(&self, name: &str) -> Option<&TraitDefinition> {
+        self.traits.get(name)
+    }
+
+    pub fn add_trait_impl(&mut self, type_name: String, trait_name: String) {
+        let traits = self.trait_impls.entry(type_name).or_default();
+        if !traits.contains(&trait_name) {
+            traits.push(trait_name);
+        }
+    }
+
+    pub fn implements_trait(&self, type_name: &str, trait_name: &str) -> bool {
+        self.trait_impls
+            .get(type_name)
+            .is_some_and(|traits| traits.iter().any(|name| name == trait_name))
+    }
+
     pub fn enum_variant(&self, enum_name: &str, variant: &str) -> Option<&EnumVariant> {
         self.enums
             .get(enum_name)
@@ -708,9 +890,15 @@ impl TypeContext {
         })
     }
 
+    /// Resolve struct and enum names, including those nested in other types, so an
+    /// annotation such as `List<Point>` names the struct rather than a generic parameter
     pub fn normalize_type(&self, ty: TypeInfo) -> TypeInfo {
         match ty {
             TypeInfo::Generic { base, args } => {
+                let args: Vec<TypeInfo> = args
+                    .into_iter()
+                    .map(|arg| self.normalize_type(arg))
+                    .collect();
                 if args.is_empty()
                     && let Some(struct_def) = self.structs.get(&base)
                 {
@@ -725,6 +913,29 @@ impl TypeContext {
                     TypeInfo::Generic { base, args }
                 }
             }
+            TypeInfo::List(element) => TypeInfo::List(Box::new(self.normalize_type(*element))),
+            TypeInfo::Dict { key, value } => TypeInfo::Dict {
+                key: Box::new(self.normalize_type(*key)),
+                value: Box::new(self.normalize_type(*value)),
+            },
+            TypeInfo::Tuple(elements) => TypeInfo::Tuple(
+                elements
+                    .into_iter()
+                    .map(|element| self.normalize_type(element))
+                    .collect(),
+            ),
+            TypeInfo::Function {
+                params,
+                param_defaults,
+                return_type,
+            } => TypeInfo::Function {
+                params: params
+                    .into_iter()
+                    .map(|param| self.normalize_type(param))
+                    .collect(),
+                param_defaults,
+                return_type: Box::new(self.normalize_type(*return_type)),
+            },
             other => other,
         }
     }
@@ -737,7 +948,12 @@ impl TypeContext {
         {
             info = aliased_type.clone();
         }
-        self.normalize_type(info)
+        let info = self.normalize_type(info);
+        if self.generic_args.is_empty() {
+            info
+        } else {
+            info.substitute(&self.generic_args)
+        }
     }
 
     pub fn set_language_features(&mut self, features: LanguageFeatureFlags) {
@@ -755,6 +971,7 @@ impl Default for TypeContext {
 pub struct StructDefinition {
     pub name: String,
     pub generics: Vec<String>,
+    pub bounds: Vec<(String, Vec<String>)>,
     pub fields: HashMap<String, TypeInfo>,
     pub public: bool,
 }
@@ -763,10 +980,29 @@ pub struct StructDefinition {
 pub struct EnumDefinition {
     pub name: String,
     pub generics: Vec<String>,
+    pub bounds: Vec<(String, Vec<String>)>,
     pub variants: Vec<EnumVariant>,
     pub public: bool,
 }
 
+#[derive(Debug, Clone)]
+pub struct TraitDefinition {
+    pub name: String,
+    /// Method declarations in source order; an empty body marks a required method
+    pub methods: Vec<Node<Function>>,
+    /// Method signatures with the receiver typed as `Self`
+    pub signatures: HashMap<String, TypeInfo>,
+    pub public: bool,
+}
+
+impl TraitDefinition {
+    pub fn is_required(&self, method: &str) -> bool {
+        self.methods.iter().any(|function| {
+            function.as_ref().name == method && function.as_ref().body.as_ref().is_empty()
+        })
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct EnumLayout {
     pub name: String,
@@ -234,6 +234,8 @@ mod tests {
                     span(),
                 )),
                 args: vec![literal_int(41)],
+                kwargs: Vec::new(),
+                type_args: Vec::new(),
             },
             span(),
         );
@@ -499,6 +499,13 @@ pub fn compile_pipeline(
     let type_check_result =
         profiler.record_phase("Type Checking", || type_checker.check_program(&program));
 
+    let warnings = otterc_typecheck::diagnostics_from_type_warnings(
+        type_checker.warnings(),
+        &source_id,
+        source,
+    );
+    emit_diagnostics(&warnings, source);
+
     if let Err(err) = type_check_result {
         let diagnostics = otterc_typecheck::diagnostics_from_type_errors(
             type_checker.errors(),
@@ -1336,8 +1343,15 @@ fn type_spec_to_ffi_helper(
             }
         }
         TypeSpec::Bool => Ok(FfiType::Bool),
+        TypeSpec::I8 => Ok(FfiType::I8),
+        TypeSpec::I16 => Ok(FfiType::I16),
         TypeSpec::I32 => Ok(FfiType::I32),
         TypeSpec::I64 => Ok(FfiType::I64),
+        TypeSpec::U8 => Ok(FfiType::U8),
+        TypeSpec::U16 => Ok(FfiType::U16),
+        TypeSpec::U32 => Ok(FfiType::U32),
+        TypeSpec::U64 => Ok(FfiType::U64),
+        TypeSpec::F32 => Ok(FfiType::F32),
         TypeSpec::F64 => Ok(FfiType::F64),
         TypeSpec::Str => Ok(FfiType::Str),
         TypeSpec::Opaque | TypeSpec::Option(_) => Ok(FfiType::Opaque),
@@ -41,7 +41,8 @@ const BUILTIN_FUNCTION_COMPLETIONS: &[(&str, &str)] = &[
 
 const KEYWORD_COMPLETIONS: &[&str] = &[
     "fn", "let", "pub", "struct", "enum", "match", "case", "if", "elif", "else", "for", "while",
-    "try", "except", "finally", "raise", "await", "spawn", "use", "from", "as", "type",
+    "loop", "break", "continue", "try", "except", "finally", "raise", "with", "defer", "await",
+    "spawn", "use", "from", "as", "type",
 ];
 
 struct SnippetCompletion {
@@ -119,10 +120,12 @@ impl CallableInfo {
 #[derive(Debug, Clone)]
 enum SymbolKind {
     Variable,
+    Constant,
     Parameter,
     Function,
     Struct,
     Enum,
+    Trait,
     TypeAlias,
     Method,
 }
@@ -153,6 +156,18 @@ impl SymbolTable {
         );
     }
 
+    fn add_constant(&mut self, name: String, span: Span, ty: String) {
+        self.symbols.insert(
+            name.clone(),
+            SymbolInfo {
+                span,
+                kind: SymbolKind::Constant,
+                ty: Some(ty),
+                callable: None,
+            },
+        );
+    }
+
     fn add_parameter(&mut self, name: String, span: Span, ty: Option<String>) {
         self.symbols.insert(
             name.clone(),
@@ -225,6 +240,18 @@ impl SymbolTable {
         );
     }
 
+    fn add_trait(&mut self, name: String, span: Span) {
+        self.symbols.insert(
+            name.clone(),
+            SymbolInfo {
+                span,
+                kind: SymbolKind::Trait,
+                ty: None,
+                callable: None,
+            },
+        );
+    }
+
     fn add_type_alias(&mut self, name: String, span: Span) {
         self.symbols.insert(
             name.clone(),
@@ -518,8 +545,10 @@ impl LanguageServer for Backend {
                     SymbolKind::Variable | SymbolKind::Parameter => {
                         tower_lsp::lsp_types::SymbolKind::VARIABLE
                     }
+                    SymbolKind::Constant => tower_lsp::lsp_types::SymbolKind::CONSTANT,
                     SymbolKind::Struct => tower_lsp::lsp_types::SymbolKind::STRUCT,
                     SymbolKind::Enum => tower_lsp::lsp_types::SymbolKind::ENUM,
+                    SymbolKind::Trait => tower_lsp::lsp_types::SymbolKind::INTERFACE,
                     SymbolKind::TypeAlias => tower_lsp::lsp_types::SymbolKind::TYPE_PARAMETER,
                     SymbolKind::Method => tower_lsp::lsp_types::SymbolKind::METHOD,
                 };
@@ -562,8 +591,10 @@ impl LanguageServer for Backend {
                             SymbolKind::Variable | SymbolKind::Parameter => {
                                 tower_lsp::lsp_types::SymbolKind::VARIABLE
                             }
+                            SymbolKind::Constant => tower_lsp::lsp_types::SymbolKind::CONSTANT,
                             SymbolKind::Struct => tower_lsp::lsp_types::SymbolKind::STRUCT,
                             SymbolKind::Enum => tower_lsp::lsp_types::SymbolKind::ENUM,
+                            SymbolKind::Trait => tower_lsp::lsp_types::SymbolKind::INTERFACE,
                             SymbolKind::TypeAlias => {
                                 tower_lsp::lsp_types::SymbolKind::TYPE_PARAMETER
                             }
@@ -658,9 +689,11 @@ impl LanguageServer for Backend {
             let kind_str = match symbol_info.kind {
                 SymbolKind::Function => "function",
                 SymbolKind::Variable => "variable",
+                SymbolKind::Constant => "constant",
                 SymbolKind::Parameter => "parameter",
                 SymbolKind::Struct => "struct",
                 SymbolKind::Enum => "enum",
+                SymbolKind::Trait => "trait",
                 SymbolKind::TypeAlias => "type",
                 SymbolKind::Method => "method",
             };
@@ -729,8 +762,10 @@ impl LanguageServer for Backend {
                     SymbolKind::Function | SymbolKind::Variable | SymbolKind::Parameter => {
                         CompletionItemKind::VARIABLE
                     }
+                    SymbolKind::Constant => CompletionItemKind::CONSTANT,
                     SymbolKind::Struct => CompletionItemKind::STRUCT,
                     SymbolKind::Enum => CompletionItemKind::ENUM,
+                    SymbolKind::Trait => CompletionItemKind::INTERFACE,
                     SymbolKind::TypeAlias => CompletionItemKind::TYPE_PARAMETER,
                     SymbolKind::Method => CompletionItemKind::METHOD,
                 };
@@ -849,11 +884,11 @@ impl LanguageServer for Backend {
                 let pos = span_to_position(info.span.start(), &text);
                 let token_type = match info.kind {
                     SymbolKind::Function | SymbolKind::Method => 0, // FUNCTION
-                    SymbolKind::Variable => 1,                      // VARIABLE
+                    SymbolKind::Variable | SymbolKind::Constant => 1, // VARIABLE
                     SymbolKind::Parameter => 2,                     // PARAMETER
                     SymbolKind::Struct => 4,                        // CLASS
                     SymbolKind::Enum => 5,                          // ENUM
-                    SymbolKind::TypeAlias => 3,                     // TYPE
+                    SymbolKind::Trait | SymbolKind::TypeAlias => 3, // TYPE
                 };
 
                 let delta_line = pos.line as u32 - prev_line;
@@ -978,12 +1013,20 @@ fn build_symbol_table_from_statements(
     for stmt in statements {
         let span = stmt.span();
         match stmt.as_ref() {
-            Statement::Let { name, ty, expr, .. } => {
-                let ty_str = ty
-                    .as_ref()
-                    .map(|ty| format_type(ty.as_ref()))
-                    .or_else(|| infer_type_from_expr(expr.as_ref()));
-                table.add_variable(name.as_ref().clone(), *span, ty_str);
+            Statement::Let {
+                pattern, ty, expr, ..
+            } => {
+                if let Some(name) = pattern.as_ref().as_identifier() {
+                    let ty_str = ty
+                        .as_ref()
+                        .map(|ty| format_type(ty.as_ref()))
+                        .or_else(|| infer_type_from_expr(expr.as_ref()));
+                    table.add_variable(name.to_string(), *span, ty_str);
+                } else {
+                    for name in pattern.as_ref().bindings() {
+                        table.add_variable(name.to_string(), *span, None);
+                    }
+                }
             }
 
             Statement::Function(func) => {
@@ -1025,11 +1068,35 @@ fn build_symbol_table_from_statements(
                     table.add_enum(name.clone(), span);
                 }
             }
+            Statement::Trait { name, methods, .. } => {
+                if let Some(span) = find_name_span(name, tokens, text) {
+                    table.add_trait(name.clone(), span);
+                }
+                for method in methods {
+                    if let Some(span) = find_name_span(&method.as_ref().name, tokens, text) {
+                        let sig = format_function_signature(method.as_ref());
+                        let callable = Some(CallableInfo::from_function(method.as_ref()));
+                        table.add_method(method.as_ref().name.clone(), span, Some(sig), callable);
+                    }
+                }
+            }
+            Statement::Impl { methods, .. } => {
+                for method in methods {
+                    if let Some(span) = find_name_span(&method.as_ref().name, tokens, text) {
+                        let sig = format_function_signature(method.as_ref());
+                        let callable = Some(CallableInfo::from_function(method.as_ref()));
+                        table.add_method(method.as_ref().name.clone(), span, Some(sig), callable);
+                    }
+                }
+            }
             Statement::TypeAlias { name, .. } => {
                 if let Some(span) = find_name_span(name, tokens, text) {
                     table.add_type_alias(name.clone(), span);
                 }
             }
+            Statement::Const { name, ty, .. } => {
+                table.add_constant(name.clone(), *span, format_type(ty.as_ref()));
+            }
             Statement::If {
                 then_block,
                 elif_blocks,
@@ -1059,16 +1126,51 @@ fn build_symbol_table_from_statements(
                     );
                 }
             }
-            Statement::For { var, body, .. } => {
-                table.add_variable(var.as_ref().clone(), *span, None);
+            Statement::For { pattern, body, .. } => {
+                for name in pattern.as_ref().bindings() {
+                    table.add_variable(name.to_string(), *span, None);
+                }
                 build_symbol_table_from_statements(&body.as_ref().statements, table, tokens, text);
             }
             Statement::While { body, .. } => {
                 build_symbol_table_from_statements(&body.as_ref().statements, table, tokens, text);
             }
+            Statement::With { binding, body, .. } => {
+                if let Some(name) = binding {
+                    table.add_variable(name.clone(), *span, None);
+                }
+                build_symbol_table_from_statements(&body.as_ref().statements, table, tokens, text);
+            }
             Statement::Block(block) => {
                 build_symbol_table_from_statements(&block.as_ref().statements, table, tokens, text);
             }
+            Statement::Try {
+                body,
+                handlers,
+                finally_block,
+            } => {
+                build_symbol_table_from_statements(&body.as_ref().statements, table, tokens, text);
+                for handler in handlers {
+                    let handler = handler.as_ref();
+                    if let Some(name) = &handler.binding {
+                        table.add_variable(name.clone(), *span, None);
+                    }
+                    build_symbol_table_from_statements(
+                        &handler.body.as_ref().statements,
+                        table,
+                        tokens,
+                        text,
+                    );
+                }
+                if let Some(block) = finally_block {
+                    build_symbol_table_from_statements(
+                        &block.as_ref().statements,
+                        table,
+                        tokens,
+                        text,
+                    );
+                }
+            }
             _ => {}
         }
     }
@@ -1092,6 +1194,8 @@ fn collect_references_from_statements(
                             *span,
                         )),
                         args: vec![],
+                        kwargs: vec![],
+                        type_args: vec![],
                     },
                     table,
                     tokens,
@@ -1104,7 +1208,15 @@ fn collect_references_from_statements(
                     text,
                 );
             }
-            Statement::Let { expr, .. } | Statement::Expr(expr) | Statement::Return(Some(expr)) => {
+            Statement::Let { expr, .. }
+            | Statement::Const { expr, .. }
+            | Statement::Expr(expr)
+            | Statement::Return(Some(expr))
+            | Statement::Raise(Some(expr))
+            | Statement::Defer(expr)
+            | Statement::Break {
+                value: Some(expr), ..
+            } => {
                 collect_references_from_expr(expr.as_ref(), table, tokens, text);
             }
             Statement::If {
@@ -1143,10 +1255,37 @@ fn collect_references_from_statements(
                 collect_references_from_expr(iterable.as_ref(), table, tokens, text);
                 collect_references_from_statements(&body.as_ref().statements, table, tokens, text);
             }
-            Statement::While { cond, body } => {
+            Statement::While { cond, body, .. } => {
                 collect_references_from_expr(cond.as_ref(), table, tokens, text);
                 collect_references_from_statements(&body.as_ref().statements, table, tokens, text);
             }
+            Statement::With { expr, body, .. } => {
+                collect_references_from_expr(expr.as_ref(), table, tokens, text);
+                collect_references_from_statements(&body.as_ref().statements, table, tokens, text);
+            }
+            Statement::Try {
+                body,
+                handlers,
+                finally_block,
+            } => {
+                collect_references_from_statements(&body.as_ref().statements, table, tokens, text);
+                for handler in handlers {
+                    collect_references_from_statements(
+                        &handler.as_ref().body.as_ref().statements,
+                        table,
+                        tokens,
+                        text,
+                    );
+                }
+                if let Some(block) = finally_block {
+                    collect_references_from_statements(
+                        &block.as_ref().statements,
+                        table,
+                        tokens,
+                        text,
+                    );
+                }
+            }
             _ => {}
         }
     }
@@ -1165,20 +1304,35 @@ fn collect_references_from_expr(
                 table.add_reference(name.clone(), span);
             }
         }
-        Expr::Call { func, args } => {
+        Expr::Call {
+            func, args, kwargs, ..
+        } => {
             collect_references_from_expr(func.as_ref().as_ref(), table, tokens, text);
-            for arg in args {
+            for arg in args.iter().chain(kwargs.iter().map(|(_, value)| value)) {
                 collect_references_from_expr(arg.as_ref(), table, tokens, text);
             }
         }
         Expr::Member { object, .. } => {
             collect_references_from_expr(object.as_ref().as_ref(), table, tokens, text);
         }
+        Expr::Index { object, index } => {
+            collect_references_from_expr(object.as_ref().as_ref(), table, tokens, text);
+            collect_references_from_expr(index.as_ref().as_ref(), table, tokens, text);
+        }
+        Expr::Slice { object, start, end } => {
+            collect_references_from_expr(object.as_ref().as_ref(), table, tokens, text);
+            for bound in [start, end].into_iter().flatten() {
+                collect_references_from_expr(bound.as_ref().as_ref(), table, tokens, text);
+            }
+        }
         Expr::Binary { left, right, .. } => {
             collect_references_from_expr(left.as_ref().as_ref(), table, tokens, text);
             collect_references_from_expr(right.as_ref().as_ref(), table, tokens, text);
         }
-        Expr::Unary { expr, .. } => {
+        Expr::Unary { expr, .. }
+        | Expr::Cast { expr, .. }
+        | Expr::Try(expr)
+        | Expr::Spread(expr) => {
             collect_references_from_expr(expr.as_ref().as_ref(), table, tokens, text);
         }
         Expr::If {
@@ -1192,7 +1346,7 @@ fn collect_references_from_expr(
                 collect_references_from_expr(else_expr.as_ref().as_ref(), table, tokens, text);
             }
         }
-        Expr::Array(elements) => {
+        Expr::Tuple(elements) | Expr::Array(elements) => {
             for elem in elements {
                 collect_references_from_expr(elem.as_ref(), table, tokens, text);
             }
@@ -1203,6 +1357,9 @@ fn collect_references_from_expr(
                 collect_references_from_expr(value.as_ref(), table, tokens, text);
             }
         }
+        Expr::Lambda { body, .. } | Expr::Loop { body, .. } => {
+            collect_references_from_statements(&body.as_ref().statements, table, tokens, text);
+        }
         _ => {}
     }
 }
@@ -1268,6 +1425,20 @@ fn format_type(ty: &Type) -> String {
             let args_str: Vec<String> = args.iter().map(|t| format_type(t.as_ref())).collect();
             format!("{}<{}>", base, args_str.join(", "))
         }
+        Type::Function { params, ret } => {
+            let params_str: Vec<String> = params.iter().map(|t| format_type(t.as_ref())).collect();
+            let ret_str = ret
+                .as_ref()
+                .map(|t| format!(" -> {}", format_type(t.as_ref().as_ref())))
+                .unwrap_or_default();
+            format!("fn({}){}", params_str.join(", "), ret_str)
+        }
+        Type::Dyn(name) => format!("dyn {}", name),
+        Type::Tuple(elements) => {
+            let elements_str: Vec<String> =
+                elements.iter().map(|t| format_type(t.as_ref())).collect();
+            format!("({})", elements_str.join(", "))
+        }
     }
 }
 
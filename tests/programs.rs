@@ -0,0 +1,48 @@
+//! Compiles and runs every Otter program in `tests/`. The programs check their own results
+//! with the `test` module, whose assertions exit with a failure status.
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+#[test]
+fn test_programs_run_successfully() {
+    let tests_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests");
+    // `otter run` writes its binary relative to the working directory
+    let work_dir = std::env::temp_dir().join(format!("otter-programs-{}", std::process::id()));
+    fs::create_dir_all(&work_dir).expect("create the working directory");
+
+    let mut programs: Vec<_> = fs::read_dir(&tests_dir)
+        .expect("read the tests directory")
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "ot"))
+        .collect();
+    programs.sort();
+
+    let mut failures = Vec::new();
+    for program in &programs {
+        let output = Command::new(env!("CARGO_BIN_EXE_otter"))
+            .args(["run", "--no-cache"])
+            .arg(program)
+            .current_dir(&work_dir)
+            .output()
+            .expect("start otter");
+        if !output.status.success() {
+            failures.push(format!(
+                "{}:\n{}{}",
+                program.display(),
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+    }
+    let _ = fs::remove_dir_all(&work_dir);
+
+    assert!(
+        failures.is_empty(),
+        "{} of {} programs failed\n\n{}",
+        failures.len(),
+        programs.len(),
+        failures.join("\n")
+    );
+}